@@ -0,0 +1,129 @@
+#!/usr/bin/env python3
+"""Serve local directories as read-only S3 buckets for the workbench.
+
+Each top-level directory under ROOT is a bucket, laid out like the public
+ones:
+
+    ROOT/unidata-nexrad-level2/2013/05/20/KTLX/KTLX20130520_200356_V06
+    ROOT/unidata-nexrad-level2-chunks/KTLX/42/20130520-200356-001-S
+    ROOT/unidata-nexrad-level3/TLX_N0Q_2013_05_20_20_03_56
+
+Objects are served at /<bucket>/<key>, and /<bucket>/?list-type=2 answers
+with a ListObjectsV2 listing (prefix, max-keys and continuation-token are
+supported). Point the workbench's Data Source settings at
+http://localhost:8081/<bucket>.
+
+    python3 scripts/fixture_server.py ROOT [--port 8081]
+"""
+
+import argparse
+import os
+from datetime import datetime, timezone
+from email.utils import formatdate
+from http.server import BaseHTTPRequestHandler, ThreadingHTTPServer
+from urllib.parse import parse_qs, unquote, urlsplit
+from xml.sax.saxutils import escape
+
+PAGE_SIZE = 1000
+
+
+def bucket_keys(bucket_dir):
+    """Every object key in a bucket directory, in S3 (lexicographic) order."""
+    keys = []
+    for dirpath, _, filenames in os.walk(bucket_dir):
+        for name in filenames:
+            path = os.path.join(dirpath, name)
+            keys.append(os.path.relpath(path, bucket_dir).replace(os.sep, "/"))
+    return sorted(keys)
+
+
+def list_objects_xml(bucket, bucket_dir, query):
+    prefix = query.get("prefix", [""])[0]
+    max_keys = min(int(query.get("max-keys", [PAGE_SIZE])[0]), PAGE_SIZE)
+    after = query.get("continuation-token", [""])[0]
+
+    keys = [k for k in bucket_keys(bucket_dir) if k.startswith(prefix) and k > after]
+    page, truncated = keys[:max_keys], len(keys) > max_keys
+
+    contents = []
+    for key in page:
+        stat = os.stat(os.path.join(bucket_dir, key))
+        modified = datetime.fromtimestamp(stat.st_mtime, timezone.utc)
+        contents.append(
+            "<Contents><Key>{}</Key><LastModified>{}</LastModified>"
+            "<Size>{}</Size></Contents>".format(
+                escape(key),
+                modified.strftime("%Y-%m-%dT%H:%M:%S.000Z"),
+                stat.st_size,
+            )
+        )
+    next_token = (
+        "<NextContinuationToken>{}</NextContinuationToken>".format(escape(page[-1]))
+        if truncated
+        else ""
+    )
+    return (
+        '<?xml version="1.0" encoding="UTF-8"?>'
+        '<ListBucketResult xmlns="http://s3.amazonaws.com/doc/2006-03-01/">'
+        "<Name>{}</Name><Prefix>{}</Prefix><KeyCount>{}</KeyCount>"
+        "<MaxKeys>{}</MaxKeys><IsTruncated>{}</IsTruncated>{}{}"
+        "</ListBucketResult>".format(
+            escape(bucket),
+            escape(prefix),
+            len(page),
+            max_keys,
+            "true" if truncated else "false",
+            "".join(contents),
+            next_token,
+        )
+    ).encode()
+
+
+class BucketHandler(BaseHTTPRequestHandler):
+    root = "."
+
+    def send(self, status, body, content_type, headers=()):
+        self.send_response(status)
+        self.send_header("Content-Type", content_type)
+        self.send_header("Content-Length", str(len(body)))
+        self.send_header("Access-Control-Allow-Origin", "*")
+        self.send_header("Access-Control-Expose-Headers", "Last-Modified")
+        for name, value in headers:
+            self.send_header(name, value)
+        self.end_headers()
+        self.wfile.write(body)
+
+    def do_GET(self):
+        url = urlsplit(self.path)
+        bucket, _, key = unquote(url.path).lstrip("/").partition("/")
+        bucket_dir = os.path.realpath(os.path.join(self.root, bucket))
+        if not bucket or not os.path.isdir(bucket_dir):
+            return self.send(404, b"no such bucket", "text/plain")
+
+        if not key:
+            query = parse_qs(url.query)
+            return self.send(200, list_objects_xml(bucket, bucket_dir, query), "application/xml")
+
+        path = os.path.realpath(os.path.join(bucket_dir, key))
+        if not path.startswith(bucket_dir + os.sep) or not os.path.isfile(path):
+            return self.send(404, b"no such key", "text/plain")
+        with open(path, "rb") as f:
+            body = f.read()
+        modified = formatdate(os.stat(path).st_mtime, usegmt=True)
+        self.send(200, body, "application/octet-stream", [("Last-Modified", modified)])
+
+
+def main():
+    parser = argparse.ArgumentParser(description=__doc__.splitlines()[0])
+    parser.add_argument("root", help="directory holding one subdirectory per bucket")
+    parser.add_argument("--port", type=int, default=8081)
+    args = parser.parse_args()
+
+    BucketHandler.root = os.path.realpath(args.root)
+    server = ThreadingHTTPServer(("127.0.0.1", args.port), BucketHandler)
+    print("Serving buckets in {} at http://127.0.0.1:{}/".format(BucketHandler.root, args.port))
+    server.serve_forever()
+
+
+if __name__ == "__main__":
+    main()
@@ -50,7 +50,7 @@ impl AlertsManager {
         }
 
         // Drop expired alerts proactively so the UI never shows stale items.
-        let now = js_sys::Date::now() / 1000.0;
+        let now = crate::platform::now_secs();
         if !state.alerts.alerts.is_empty() {
             state.alerts.alerts.retain(|a| !a.is_expired(now));
         }
@@ -59,7 +59,7 @@ impl AlertsManager {
         let manual_refresh = std::mem::take(&mut state.alerts.refresh_requested);
 
         // Due to poll?
-        let now_ms = js_sys::Date::now();
+        let now_ms = crate::platform::now_ms();
         let elapsed = now_ms - state.alerts.last_poll_ms;
         let due = state.alerts.last_poll_ms <= 0.0
             || (state.alerts.last_error.is_some() && elapsed >= RETRY_INTERVAL_MS)
@@ -73,7 +73,7 @@ impl AlertsManager {
     fn start_fetch(&mut self, ctx: &egui::Context, state: &mut AppState) {
         self.fetch_in_flight = true;
         state.alerts.fetch_in_flight = true;
-        state.alerts.last_poll_ms = js_sys::Date::now();
+        state.alerts.last_poll_ms = crate::platform::now_ms();
         let etag = state.alerts.last_etag.clone();
         api::spawn_fetch(ctx.clone(), self.channel.clone(), etag);
     }
@@ -85,12 +85,12 @@ impl AlertsManager {
                 state.alerts.alerts = alerts;
                 state.alerts.last_etag = etag;
                 state.alerts.last_error = None;
-                state.alerts.last_success_ms = js_sys::Date::now();
+                state.alerts.last_success_ms = crate::platform::now_ms();
                 log::info!("NWS alerts refreshed: {} active", state.alerts.alerts.len());
             }
             AlertsEvent::NotModified => {
                 state.alerts.last_error = None;
-                state.alerts.last_success_ms = js_sys::Date::now();
+                state.alerts.last_success_ms = crate::platform::now_ms();
                 log::debug!("NWS alerts: 304 Not Modified");
             }
             AlertsEvent::Error(msg) => {
@@ -0,0 +1,4757 @@
+//! The eframe application: `WorkbenchApp` and the two entry points that
+//! start it.
+//!
+//! `WorkbenchApp` sets up the coordination managers (acquisition, render,
+//! streaming, persistence) and runs the main update loop that polls channels,
+//! processes commands, and renders the UI.
+
+use crate::{alerts, data, export, geo, nexrad, platform, state, ui};
+use data::DataFacade;
+use eframe::egui;
+use state::AppState;
+
+// ---------------------------------------------------------------------------
+// Tuning constants
+// ---------------------------------------------------------------------------
+
+/// Maximum age (in seconds) for a scan to be considered relevant to the current
+/// playback position. Scans older than this are not displayed when scrubbing.
+/// 15 minutes covers a full VCP cycle with margin.
+const MAX_SCAN_AGE_SECS: f64 = 15.0 * 60.0;
+
+/// How far ahead (in real-time seconds) to prefetch the next sweep when
+/// playback is active. Multiplied by the playback speed to get the lookahead
+/// in timeline seconds. 0.5 s keeps the pipeline one decode ahead without
+/// wasting bandwidth.
+const PREFETCH_LOOKAHEAD_SECS: f64 = 0.5;
+
+/// Fallback scan duration (in seconds) used when the true end timestamp of
+/// a scan boundary is unknown. 300 s (5 minutes) is a conservative upper
+/// bound for a single volume scan.
+const FALLBACK_SCAN_DURATION_SECS: i64 = 300;
+
+/// Maximum time difference (in seconds) between a cached scan's start_time
+/// and an archive file's timestamp for them to be considered the same scan.
+/// 60 s allows for minor clock drift and timestamp rounding.
+const SCAN_CACHE_MATCH_TOLERANCE_SECS: i64 = 60;
+
+/// Open the desktop window and run the app until it is closed.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn run_native() -> eframe::Result {
+    platform::init_logger();
+
+    let options = eframe::NativeOptions {
+        viewport: egui::ViewportBuilder::default()
+            .with_title("NEXRAD Workbench")
+            .with_app_id("nexrad-workbench")
+            .with_inner_size([1440.0, 900.0])
+            .with_min_inner_size([800.0, 500.0]),
+        ..Default::default()
+    };
+    eframe::run_native(
+        "NEXRAD Workbench",
+        options,
+        Box::new(|cc| Ok(Box::new(WorkbenchApp::new(cc)))),
+    )
+}
+
+/// Mount the app on the page's `app_canvas`.
+#[cfg(target_arch = "wasm32")]
+pub fn run_web() {
+    // Web Workers have no window — skip app initialization
+    if web_sys::window().is_none() {
+        return;
+    }
+
+    use eframe::wasm_bindgen::JsCast as _;
+
+    // Redirect `log` messages to `console.log`:
+    platform::init_logger();
+
+    let web_options = eframe::WebOptions::default();
+
+    wasm_bindgen_futures::spawn_local(async {
+        let document = web_sys::window()
+            .expect("No window")
+            .document()
+            .expect("No document");
+
+        let canvas = document
+            .get_element_by_id("app_canvas")
+            .expect("Failed to find app_canvas")
+            .dyn_into::<web_sys::HtmlCanvasElement>()
+            .expect("app_canvas was not a HtmlCanvasElement");
+
+        let start_result = eframe::WebRunner::new()
+            .start(
+                canvas,
+                web_options,
+                Box::new(|cc| Ok(Box::new(WorkbenchApp::new(cc)))),
+            )
+            .await;
+
+        // Remove the loading text once the app has loaded:
+        if let Some(loading_text) = document.get_element_by_id("loading_text") {
+            match start_result {
+                Ok(_) => {
+                    loading_text.remove();
+                }
+                Err(e) => {
+                    loading_text.set_inner_html(
+                        "<p>The app has crashed. See the developer console for details.</p>",
+                    );
+                    panic!("Failed to start eframe: {e:?}");
+                }
+            }
+        }
+    });
+}
+
+/// All GPU renderers and their shared GL context, grouped for clarity.
+pub struct GpuResources {
+    /// GPU renderer for radar data (None if GL not available).
+    pub gpu: Option<std::sync::Arc<std::sync::Mutex<nexrad::RadarGpuRenderer>>>,
+    /// GL context for uploading data to GPU textures.
+    pub gl: Option<std::sync::Arc<glow::Context>>,
+    /// Globe sphere renderer (3D mode).
+    pub globe: Option<std::sync::Arc<std::sync::Mutex<geo::GlobeRenderer>>>,
+    /// Geographic line renderer for 3D globe.
+    pub geo_line: Option<std::sync::Arc<std::sync::Mutex<geo::GeoLineRenderer>>>,
+    /// Globe-mode radar renderer (projects radar data onto sphere).
+    pub globe_radar: Option<std::sync::Arc<std::sync::Mutex<nexrad::GlobeRadarRenderer>>>,
+    /// Volumetric ray-march renderer for 3D mode.
+    pub volume_ray: Option<std::sync::Arc<std::sync::Mutex<nexrad::VolumeRayRenderer>>>,
+    /// One radar renderer per extra split-view panel (empty if GL not available).
+    pub panels: Vec<std::sync::Arc<std::sync::Mutex<nexrad::RadarGpuRenderer>>>,
+    /// One radar renderer per pinned site slot (empty if GL not available).
+    pub pinned: Vec<std::sync::Arc<std::sync::Mutex<nexrad::RadarGpuRenderer>>>,
+}
+
+use nexrad::dealias::DEALIASED_VELOCITY;
+use nexrad::download_queue::{QueueAction, QueueItem};
+use nexrad::RenderRequest;
+use state::playback_manager::{
+    sweep_cache_key, CachedSweepData, InterpolationAction, PlaybackManager, PrevSweepAction,
+};
+use state::MAX_RECENT_NETWORK_REQUESTS;
+
+/// Main application state and logic.
+pub struct WorkbenchApp {
+    /// Application state containing all sub-states
+    state: AppState,
+
+    /// Geographic layer data for map overlays
+    geo_layers: geo::GeoLayerSet,
+
+    /// All GPU renderers and their GL context.
+    gpu: GpuResources,
+
+    /// Render coordinator: owns the decode worker, scan key, elevations, and render dedup.
+    render: nexrad::RenderCoordinator,
+
+    /// Download pipeline: channels, queue, archive index, current scan.
+    acquisition: nexrad::AcquisitionCoordinator,
+
+    /// Live streaming and backfill lifecycle manager.
+    streaming: nexrad::StreamingManager,
+
+    /// URL state, preferences, and site change detection.
+    persistence: nexrad::PersistenceManager,
+
+    /// Transient state for the site selection modal.
+    site_modal_state: ui::SiteModalState,
+
+    /// Transient state for the event create/edit modal.
+    event_modal_state: ui::EventModalState,
+    /// Cached image for the cross-section window.
+    cross_section_window_state: ui::CrossSectionWindowState,
+
+    /// Service worker network monitor (None if SW not available).
+    network_monitor: Option<nexrad::NetworkMonitor>,
+
+    /// Sweep cache and previous-sweep resolution for sweep animation.
+    playback_manager: PlaybackManager,
+
+    /// Cache key of the dealiased velocity sweep requested for rotation
+    /// detection, so it is asked for once.
+    requested_rotation_sweep: Option<String>,
+
+    /// NWS alerts polling lifecycle.
+    alerts_manager: alerts::AlertsManager,
+
+    /// PNG/GIF export capture loop.
+    export_manager: export::ExportManager,
+
+    /// Cache of the inputs that drive `advance_playback`'s scrub-detection
+    /// pass so we can skip the O(scans) timeline search on idle frames
+    /// where the playback position, elevation selection, and scan count
+    /// have not changed.
+    scrub_cache: ScrubCache,
+
+    /// Last `AppMode` pushed to the favicon. `None` until the first frame so
+    /// the initial mode is always sent. See `sync_favicon_to_mode`.
+    #[cfg(target_arch = "wasm32")]
+    last_favicon_mode: Option<state::AppMode>,
+}
+
+#[derive(Default)]
+struct ScrubCache {
+    last_playback_ts: Option<f64>,
+    last_elevation_selection: Option<state::ElevationSelection>,
+    last_scan_count: usize,
+    last_displayed_scan_ts: Option<i64>,
+}
+
+// Embed shapefile data at compile time
+static STATES_SHP: &[u8] =
+    include_bytes!("../assets/vectors/cb_2023_us_state_20m/cb_2023_us_state_20m.shp");
+static STATES_DBF: &[u8] =
+    include_bytes!("../assets/vectors/cb_2023_us_state_20m/cb_2023_us_state_20m.dbf");
+static COUNTIES_SHP: &[u8] =
+    include_bytes!("../assets/vectors/cb_2023_us_county_20m/cb_2023_us_county_20m.shp");
+static COUNTIES_DBF: &[u8] =
+    include_bytes!("../assets/vectors/cb_2023_us_county_20m/cb_2023_us_county_20m.dbf");
+
+impl WorkbenchApp {
+    /// Creates a new WorkbenchApp instance.
+    pub fn new(cc: &eframe::CreationContext<'_>) -> Self {
+        // Initialize Phosphor icon font
+        let mut fonts = egui::FontDefinitions::default();
+        egui_phosphor::add_to_fonts(&mut fonts, egui_phosphor::Variant::Regular);
+        cc.egui_ctx.set_fonts(fonts);
+
+        let mut geo_layers = geo::GeoLayerSet::new();
+
+        // Load embedded geographic data
+        if let Err(e) = geo_layers.load_layer_from_shapefile(
+            geo::GeoLayerType::States,
+            STATES_SHP,
+            Some(STATES_DBF),
+        ) {
+            log::error!("Failed to load states layer: {}", e);
+        }
+
+        if let Err(e) = geo_layers.load_layer_from_shapefile(
+            geo::GeoLayerType::Counties,
+            COUNTIES_SHP,
+            Some(COUNTIES_DBF),
+        ) {
+            log::error!("Failed to load counties layer: {}", e);
+        }
+
+        // Load built-in cities layer
+        geo_layers.set_layer(geo::cities::build_cities_layer());
+
+        log::debug!(
+            "Loaded geo layers: {} states, {} counties, {} cities",
+            geo_layers
+                .states
+                .as_ref()
+                .map(|l| l.features.len())
+                .unwrap_or(0),
+            geo_layers
+                .counties
+                .as_ref()
+                .map(|l| l.features.len())
+                .unwrap_or(0),
+            geo_layers
+                .cities
+                .as_ref()
+                .map(|l| l.features.len())
+                .unwrap_or(0),
+        );
+
+        let mut state = AppState::new();
+        nexrad::data_source::configure(&state.data_source);
+        nexrad::palette::configure(&state.palettes);
+
+        // Apply URL parameters (site, time, lat/lon)
+        let url_params = state::url_state::parse_from_url();
+        if let Some(ref site) = url_params.site {
+            state.viz_state.site_id = site.to_uppercase();
+            if let Some(site_info) = data::sites::get_site(site) {
+                state.viz_state.center_lat = site_info.lat;
+                state.viz_state.center_lon = site_info.lon;
+                state
+                    .viz_state
+                    .camera
+                    .center_on(site_info.lat, site_info.lon);
+            }
+            state.push_command(state::AppCommand::RefreshTimeline {
+                auto_position: false,
+            });
+        }
+        if let Some(lat) = url_params.lat {
+            state.viz_state.center_lat = lat;
+        }
+        if let Some(lon) = url_params.lon {
+            state.viz_state.center_lon = lon;
+        }
+        // Sync camera with potentially overridden lat/lon
+        state
+            .viz_state
+            .camera
+            .center_on(state.viz_state.center_lat, state.viz_state.center_lon);
+        // Apply view state (zoom levels) before centering so the zoom is correct
+        if let Some(mz) = url_params.view.mz {
+            state.viz_state.zoom = mz;
+        }
+        if let Some(tz) = url_params.view.tz {
+            state.playback_state.timeline_zoom = tz;
+        }
+        // Restore 3D view mode and camera parameters from URL
+        {
+            let v = &url_params.view;
+            if let Some(vm) = v.vm {
+                state.viz_state.view_mode = match vm {
+                    0 => state::ViewMode::Flat2D,
+                    _ => state::ViewMode::Globe3D,
+                };
+            }
+            if let Some(cm) = v.cm {
+                state.viz_state.camera.mode = match cm {
+                    1 => state::CameraMode::SiteOrbit,
+                    2 => state::CameraMode::FreeLook,
+                    _ => state::CameraMode::PlanetOrbit,
+                };
+            }
+            if let Some(cd) = v.cd {
+                state.viz_state.camera.distance = cd;
+            }
+            if let Some(clat) = v.clat {
+                state.viz_state.camera.center_lat = clat;
+            }
+            if let Some(clon) = v.clon {
+                state.viz_state.camera.center_lon = clon;
+            }
+            if let Some(ct) = v.ct {
+                state.viz_state.camera.tilt = ct;
+            }
+            if let Some(cr) = v.cr {
+                state.viz_state.camera.rotation = cr;
+            }
+            if let Some(ob) = v.ob {
+                state.viz_state.camera.orbit_bearing = ob;
+            }
+            if let Some(oe) = v.oe {
+                state.viz_state.camera.orbit_elevation = oe;
+            }
+            if let Some(fp) = v.fp {
+                state.viz_state.camera.free_pos = glam::Vec3::new(fp[0], fp[1], fp[2]);
+            }
+            if let Some(fy) = v.fy {
+                state.viz_state.camera.free_yaw = fy;
+            }
+            if let Some(fpt) = v.fpt {
+                state.viz_state.camera.free_pitch = fpt;
+            }
+            if let Some(fs) = v.fs {
+                state.viz_state.camera.free_speed = fs;
+            }
+            if let Some(v3d) = v.v3d {
+                state.viz_state.volume_3d_enabled = v3d;
+            }
+            if let Some(vdc) = v.vdc {
+                state.viz_state.volume_density_cutoff = vdc;
+            }
+        }
+        // If the URL indicates real-time mode was active, re-enter live on boot.
+        // Queued behind the initial RefreshTimeline so the timeline populates first.
+        if url_params.view.rt == Some(true) {
+            state.push_command(state::AppCommand::StartLive);
+        }
+        if let Some(ref product_code) = url_params.product {
+            if let Some(product) = state::RadarProduct::from_short_code(product_code) {
+                state.viz_state.product = product;
+            }
+        }
+        if let Some(time) = url_params.time {
+            state.playback_state.set_playback_position(time);
+            // Center view on the restored position. timeline_width_px may
+            // still be the default 1000px since we haven't rendered yet, but
+            // it will be accurate on subsequent centers.
+            state.playback_state.center_view_on(time);
+        }
+
+        // First-launch detection: if no site specified in the URL, check for a
+        // saved preferred site. If one exists, apply it silently. Otherwise open
+        // the first-visit modal so the user can choose a site.
+        if url_params.site.is_none() {
+            if let Some(ref preferred) = state.preferred_site {
+                if let Some(site) = crate::data::get_site(preferred) {
+                    state.viz_state.site_id = site.id.to_string();
+                    state.viz_state.center_lat = site.lat;
+                    state.viz_state.center_lon = site.lon;
+                    state.viz_state.camera.center_on(site.lat, site.lon);
+                    // Not a first visit — modal starts in SiteList mode if reopened
+                }
+            } else {
+                state.site_modal_open = true;
+            }
+        }
+
+        let initial_site_id = state.viz_state.site_id.clone();
+        let data_facade = DataFacade::new();
+        let acquisition = nexrad::AcquisitionCoordinator::new(data_facade.clone());
+        let realtime_channel = nexrad::RealtimeChannel::with_stats(acquisition.download_stats());
+
+        // Open the record cache database
+        {
+            let facade = data_facade.clone();
+            platform::spawn_local(async move {
+                if let Err(e) = facade.open().await {
+                    log::error!("Failed to open record cache: {}", e);
+                }
+            });
+        }
+
+        let initial_prefs = state::UserPreferences::from_app_state(&state);
+        let has_preferred_site = state.preferred_site.is_some();
+
+        // Create decode worker pool (offloads heavy NEXRAD work to parallel
+        // Web Workers so the download → decompress → store pipeline can fan
+        // out across cores instead of serializing through a single worker).
+        let pool_size = nexrad::default_pool_size();
+        let decode_worker = match nexrad::WorkerPool::new(cc.egui_ctx.clone(), pool_size) {
+            Ok(pool) => Some(pool),
+            Err(e) => {
+                log::warn!("Failed to create decode worker pool: {}", e);
+                state.worker_init_error =
+                    Some(format!("Decode worker failed to initialize: {}", e));
+                None
+            }
+        };
+
+        // Create GPU renderer for radar visualization
+        let gpu_renderer_gl = cc.gl.clone();
+        let gpu_renderer = cc
+            .gl
+            .as_ref()
+            .and_then(|gl| match nexrad::RadarGpuRenderer::new(gl) {
+                Ok(renderer) => Some(std::sync::Arc::new(std::sync::Mutex::new(renderer))),
+                Err(e) => {
+                    log::error!("Failed to create GPU radar renderer: {}", e);
+                    None
+                }
+            });
+
+        // Create globe and geo-line renderers for 3D mode
+        let globe_renderer = cc.gl.as_ref().map(|gl| {
+            let r = geo::GlobeRenderer::new(gl);
+            std::sync::Arc::new(std::sync::Mutex::new(r))
+        });
+        let geo_line_renderer = cc.gl.as_ref().map(|gl| {
+            let mut r = geo::GeoLineRenderer::new(gl);
+            // Upload all static geo layers now
+            let layers_vec: Vec<&geo::GeoLayer> = [
+                geo_layers.states.as_ref(),
+                geo_layers.counties.as_ref(),
+                geo_layers.highways.as_ref(),
+                geo_layers.lakes.as_ref(),
+            ]
+            .into_iter()
+            .flatten()
+            .collect();
+            let owned: Vec<geo::GeoLayer> = layers_vec.into_iter().cloned().collect();
+            r.upload_layers(gl, &owned);
+            std::sync::Arc::new(std::sync::Mutex::new(r))
+        });
+        let globe_radar_renderer = cc.gl.as_ref().map(|gl| {
+            let r = nexrad::GlobeRadarRenderer::new(gl);
+            std::sync::Arc::new(std::sync::Mutex::new(r))
+        });
+        let volume_ray_renderer = cc.gl.as_ref().map(|gl| {
+            let r = nexrad::VolumeRayRenderer::new(gl);
+            std::sync::Arc::new(std::sync::Mutex::new(r))
+        });
+        let panel_renderers = cc
+            .gl
+            .as_ref()
+            .map(|gl| {
+                (0..state::EXTRA_PANEL_COUNT)
+                    .filter_map(|_| match nexrad::RadarGpuRenderer::new(gl) {
+                        Ok(renderer) => Some(std::sync::Arc::new(std::sync::Mutex::new(renderer))),
+                        Err(e) => {
+                            log::error!("Failed to create split-view panel renderer: {}", e);
+                            None
+                        }
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        let pinned_renderers = cc
+            .gl
+            .as_ref()
+            .map(|gl| {
+                (0..state::MAX_PINNED_SITES)
+                    .filter_map(|_| match nexrad::RadarGpuRenderer::new(gl) {
+                        Ok(renderer) => Some(std::sync::Arc::new(std::sync::Mutex::new(renderer))),
+                        Err(e) => {
+                            log::error!("Failed to create pinned site renderer: {}", e);
+                            None
+                        }
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let mut app = Self {
+            state,
+            geo_layers,
+            gpu: GpuResources {
+                gpu: gpu_renderer,
+                gl: gpu_renderer_gl,
+                globe: globe_renderer,
+                geo_line: geo_line_renderer,
+                globe_radar: globe_radar_renderer,
+                volume_ray: volume_ray_renderer,
+                panels: panel_renderers,
+                pinned: pinned_renderers,
+            },
+            render: nexrad::RenderCoordinator::new(decode_worker),
+            acquisition,
+            streaming: nexrad::StreamingManager::new(realtime_channel),
+            persistence: nexrad::PersistenceManager::new(initial_site_id, initial_prefs),
+            site_modal_state: {
+                let mut sms = ui::SiteModalState::default();
+                if has_preferred_site {
+                    sms.is_first_visit = false;
+                }
+                sms
+            },
+            event_modal_state: ui::EventModalState::default(),
+            cross_section_window_state: ui::CrossSectionWindowState::default(),
+            network_monitor: nexrad::NetworkMonitor::new(),
+            playback_manager: PlaybackManager::new(),
+            requested_rotation_sweep: None,
+            alerts_manager: alerts::AlertsManager::new(),
+            export_manager: export::ExportManager::default(),
+            scrub_cache: ScrubCache::default(),
+            #[cfg(target_arch = "wasm32")]
+            last_favicon_mode: None,
+        };
+
+        // Check cross-origin isolation status on startup
+        app.state.cross_origin_isolated = nexrad::is_cross_origin_isolated();
+        if cfg!(target_arch = "wasm32") && !app.state.cross_origin_isolated {
+            log::warn!("Not cross-origin isolated: SharedArrayBuffer unavailable");
+        }
+
+        app
+    }
+
+    /// Process selection download: download scans in the selected time range serially.
+    ///
+    /// `download_type` is `None` when pumping the existing queue (no new command),
+    /// `Some(true)` for a position-download, or `Some(false)` for a range-selection download.
+    fn process_selection_download(&mut self, ctx: &egui::Context, download_type: Option<bool>) {
+        let site_id = self.state.viz_state.site_id.clone();
+
+        // If we have items in the queue, try to advance the state machine.
+        // The queue allows up to `max_parallel` concurrent downloads; we both
+        // reap completed slots and fill empty slots on every poll.
+        if self.acquisition.download_queue.has_work() {
+            // 1. Sweep all Active items and mark any whose download has finished.
+            let finished_starts: Vec<i64> = self
+                .acquisition
+                .download_queue
+                .active_items()
+                .filter_map(|item| {
+                    if !self
+                        .acquisition
+                        .download_channel
+                        .is_download_pending(&site_id, item.scan_start)
+                    {
+                        Some(item.scan_start)
+                    } else {
+                        None
+                    }
+                })
+                .collect();
+            for start in finished_starts {
+                self.acquisition.download_queue.mark_active_done(start);
+            }
+
+            // 2. Refresh the timeline-ghost list from the queue state.
+            self.state.download_progress.active_scans = self
+                .acquisition
+                .download_queue
+                .active_items()
+                .map(|item| (item.scan_start, item.scan_end))
+                .collect();
+
+            // 3. Fill as many concurrency slots as possible.
+            let is_paused = self.state.acquisition.is_paused();
+            let mut completed_this_pump = false;
+            loop {
+                match self.acquisition.download_queue.advance(is_paused) {
+                    QueueAction::StartDownload {
+                        idx: _,
+                        date,
+                        file_name,
+                        scan_start,
+                        scan_end,
+                        remaining,
+                    } => {
+                        self.state.status_message =
+                            format!("Downloading {} ({} remaining)", file_name, remaining);
+                        self.state.download_progress.phase =
+                            crate::state::DownloadPhase::Downloading;
+                        self.state.download_progress.batch_completed += 1;
+                        self.state
+                            .download_progress
+                            .active_scans
+                            .push((scan_start, scan_end));
+
+                        // Mark next acquisition operation as active and pin it
+                        // to this download's scan_start so correlation survives
+                        // concurrent completions.
+                        if let Some(op_id) = self.state.acquisition.next_queued_id() {
+                            self.state.acquisition.mark_active(op_id);
+                            self.acquisition
+                                .download_queue
+                                .set_operation_id(scan_start, op_id);
+                        }
+
+                        self.acquisition.download_channel.download_file(
+                            ctx.clone(),
+                            site_id.clone(),
+                            date,
+                            file_name,
+                            scan_start,
+                            self.acquisition.facade().clone(),
+                        );
+                    }
+                    QueueAction::Complete => {
+                        completed_this_pump = true;
+                        break;
+                    }
+                    QueueAction::Saturated | QueueAction::Paused => {
+                        break;
+                    }
+                }
+            }
+
+            if completed_this_pump {
+                self.state.download_selection_in_progress = false;
+                self.state.download_progress.pending_scans.clear();
+                self.state.download_progress.active_scans.clear();
+                self.state.download_progress.phase = crate::state::DownloadPhase::Done;
+                // Full clear only if no in-flight scans remain.
+                if self.state.download_progress.in_flight_scans.is_empty() {
+                    self.state.download_progress.clear();
+                }
+                self.state.status_message = "Selection download complete".to_string();
+                log::debug!("Selection download complete");
+            }
+            return;
+        }
+
+        // No queue — check if a new download command was issued or a pending
+        // download is being resumed after a listing arrived.
+        let is_position_download = match download_type {
+            Some(is_pos) => {
+                // Fresh user action (not a pending resume) — reset pending state
+                if self
+                    .acquisition
+                    .pending_download
+                    .as_ref()
+                    .is_none_or(|p| p.is_position != is_pos)
+                {
+                    self.acquisition.pending_download = None;
+                }
+                is_pos
+            }
+            None => return, // Just pumping queue, nothing to do
+        };
+
+        // Get the download range: either from selection or from current position.
+        // For position download, we use a temporary wide window to determine which
+        // date listings to fetch, then narrow to the exact scan below.
+        let (sel_start, sel_end) = if is_position_download {
+            let pos = self.state.playback_state.playback_position();
+            (pos, pos)
+        } else {
+            match self.state.playback_state.selection_range() {
+                Some(range) => range,
+                None => {
+                    log::warn!("Download selection requested but no valid selection");
+                    return;
+                }
+            }
+        };
+
+        let sel_start_i64 = sel_start as i64;
+        let sel_end_i64 = sel_end as i64;
+
+        // Determine the date range for listing lookups
+        let start_date = match chrono::DateTime::from_timestamp(sel_start_i64, 0) {
+            Some(dt) => dt.date_naive(),
+            None => return,
+        };
+        let end_date = match chrono::DateTime::from_timestamp(sel_end_i64, 0) {
+            Some(dt) => dt.date_naive(),
+            None => return,
+        };
+
+        log::debug!(
+            "Building download queue for selection: {} to {} ({} to {})",
+            sel_start_i64,
+            sel_end_i64,
+            start_date,
+            end_date
+        );
+
+        // Collect all files whose scan boundaries intersect the selection
+        let mut files_to_download: Vec<QueueItem> = Vec::new();
+        let mut current_date = start_date;
+
+        while current_date <= end_date {
+            if let Some(listing) = self.acquisition.archive_index.get(&site_id, &current_date) {
+                if is_position_download {
+                    // Single-position: find the exact scan containing the playback position
+                    if let Some((file, boundary)) = listing.find_scan_containing(sel_start_i64) {
+                        let is_cached = self.state.radar_timeline.scans.iter().any(|s| {
+                            (s.start_time as i64 - file.timestamp).abs()
+                                < SCAN_CACHE_MATCH_TOLERANCE_SECS
+                        });
+                        if !is_cached {
+                            files_to_download.push(QueueItem::new(
+                                current_date,
+                                file.name.clone(),
+                                boundary.start,
+                                boundary.end,
+                            ));
+                        }
+                    } else {
+                        // No scan covers this time in the cached listing.
+                        // Check if we already re-fetched this date's listing.
+                        let already_refetched = self
+                            .acquisition
+                            .pending_download
+                            .as_ref()
+                            .is_some_and(|p| p.refetched_dates.contains(&current_date));
+
+                        if !already_refetched {
+                            // The listing may be stale (e.g. archives created
+                            // after it was cached), so invalidate and re-fetch
+                            // once. Store intent so we resume when it arrives.
+                            log::debug!(
+                                "No scan at {} in cached listing for {}/{}; re-fetching",
+                                sel_start_i64,
+                                site_id,
+                                current_date
+                            );
+                            let pending =
+                                self.acquisition.pending_download.get_or_insert_with(|| {
+                                    nexrad::acquisition_coordinator::PendingDownload {
+                                        is_position: true,
+                                        refetched_dates: std::collections::HashSet::new(),
+                                    }
+                                });
+                            pending.refetched_dates.insert(current_date);
+                            self.acquisition
+                                .archive_index
+                                .remove(&site_id, &current_date);
+                            if !self
+                                .acquisition
+                                .download_channel
+                                .is_listing_pending(&site_id, &current_date)
+                            {
+                                self.acquisition.download_channel.fetch_listing(
+                                    ctx.clone(),
+                                    site_id.clone(),
+                                    current_date,
+                                );
+                            }
+                            self.state.status_message =
+                                format!("Re-fetching archive listing for {}...", current_date);
+                            return;
+                        }
+
+                        // Already re-fetched — no scan here, skip.
+                        log::debug!(
+                            "No scan at {} in listing for {}/{} after re-fetch; skipping",
+                            sel_start_i64,
+                            site_id,
+                            current_date
+                        );
+                    }
+                } else {
+                    // Range selection: find all scans that intersect [sel_start, sel_end]
+                    for (file, boundary) in listing.scans_intersecting(sel_start_i64, sel_end_i64) {
+                        let is_cached = self.state.radar_timeline.scans.iter().any(|s| {
+                            (s.start_time as i64 - file.timestamp).abs()
+                                < SCAN_CACHE_MATCH_TOLERANCE_SECS
+                        });
+                        if !is_cached {
+                            files_to_download.push(QueueItem::new(
+                                current_date,
+                                file.name.clone(),
+                                boundary.start,
+                                boundary.end,
+                            ));
+                        }
+                    }
+                }
+            } else {
+                // Need to fetch the listing first. Store intent so we resume
+                // when the listing arrives (via handle_listing_outcome).
+                if !self
+                    .acquisition
+                    .download_channel
+                    .is_listing_pending(&site_id, &current_date)
+                {
+                    log::debug!("Fetching listing for {}/{}", site_id, current_date);
+                    self.acquisition.download_channel.fetch_listing(
+                        ctx.clone(),
+                        site_id.clone(),
+                        current_date,
+                    );
+                }
+                self.acquisition.pending_download.get_or_insert_with(|| {
+                    nexrad::acquisition_coordinator::PendingDownload {
+                        is_position: is_position_download,
+                        refetched_dates: std::collections::HashSet::new(),
+                    }
+                });
+                self.state.status_message =
+                    format!("Fetching archive listing for {}...", current_date);
+                return;
+            }
+
+            current_date += chrono::Duration::days(1);
+        }
+
+        // Queue building complete — clear pending state
+        self.acquisition.pending_download = None;
+
+        if files_to_download.is_empty() {
+            self.state.status_message = "No new scans to download in selection".to_string();
+            log::debug!("No new scans to download in selection");
+            return;
+        }
+
+        // Sort by start timestamp
+        files_to_download.sort_by_key(|item| item.scan_start);
+
+        log::debug!(
+            "Queued {} files for download in selection",
+            files_to_download.len()
+        );
+
+        // Start downloading
+        self.state.download_selection_in_progress = true;
+
+        // Cancel any existing acquisition operations (selection change = cancel all + rebuild)
+        self.state.acquisition.cancel_all();
+        self.acquisition.download_queue.set_queue(files_to_download);
+
+        // Create acquisition operations for each file in the queue
+        for item in self.acquisition.download_queue.items() {
+            self.state
+                .acquisition
+                .create_operation(state::OperationKind::ArchiveDownload {
+                    site_id: site_id.clone(),
+                    file_name: item.file_name.clone(),
+                    scan_start: item.scan_start,
+                    scan_end: item.scan_end,
+                });
+        }
+
+        // Populate download progress for timeline ghosts and pipeline display
+        {
+            let progress = &mut self.state.download_progress;
+            progress.pending_scans = self
+                .acquisition
+                .download_queue
+                .items()
+                .iter()
+                .map(|item| (item.scan_start, item.scan_end))
+                .collect();
+            progress.batch_total = self.acquisition.download_queue.len() as u32;
+            progress.batch_completed = 0;
+            progress.phase = crate::state::DownloadPhase::Downloading;
+            progress.active_scans.clear();
+        }
+
+        // Kick off as many downloads as the concurrency limit allows.
+        let is_paused = self.state.acquisition.is_paused();
+        while let QueueAction::StartDownload {
+            idx: _,
+            date,
+            file_name,
+            scan_start,
+            scan_end,
+            remaining,
+        } = self.acquisition.download_queue.advance(is_paused)
+        {
+            self.state.status_message =
+                format!("Downloading {} ({} remaining)", file_name, remaining);
+            self.state
+                .download_progress
+                .active_scans
+                .push((scan_start, scan_end));
+
+            if let Some(op_id) = self.state.acquisition.next_queued_id() {
+                self.state.acquisition.mark_active(op_id);
+                self.acquisition
+                    .download_queue
+                    .set_operation_id(scan_start, op_id);
+            }
+
+            self.acquisition.download_channel.download_file(
+                ctx.clone(),
+                site_id.clone(),
+                date,
+                file_name,
+                scan_start,
+                self.acquisition.facade().clone(),
+            );
+        }
+    }
+
+    /// Start live mode streaming for the current site.
+    fn start_live_mode(&mut self, ctx: &egui::Context) {
+        let site_id = self.state.viz_state.site_id.clone();
+        log::info!("Starting live mode for site: {}", site_id);
+
+        // Get current time
+        let now = crate::platform::now_secs();
+
+        // Initialize live mode state
+        self.state.live_mode_state.start(now);
+        self.state.playback_state.set_playback_position(now);
+        self.state.playback_state.time_model.enable_realtime_lock();
+        self.state.playback_state.playing = true;
+
+        // Ensure the timeline is zoomed in far enough to show individual sweeps
+        // and chunks. Live mode enforces micro-mode as the widest allowed zoom.
+        const LIVE_DEFAULT_ZOOM: f64 = 2.0;
+        if self.state.playback_state.timeline_zoom < LIVE_DEFAULT_ZOOM {
+            self.state.playback_state.timeline_zoom = LIVE_DEFAULT_ZOOM;
+            self.state.playback_state.center_view_on(now);
+        }
+
+        self.state.status_message = "Connecting to live stream...".to_string();
+
+        self.streaming
+            .start_live(ctx.clone(), site_id, self.acquisition.facade().clone());
+    }
+
+    /// Find the best elevation number for the current elevation selection.
+    fn best_elevation_number(&self) -> u8 {
+        match &self.state.viz_state.elevation_selection {
+            crate::state::ElevationSelection::Fixed {
+                elevation_number, ..
+            } => *elevation_number,
+            crate::state::ElevationSelection::Latest => {
+                let playback_ts = self.state.playback_state.playback_position();
+                if let Some(scan) = self
+                    .state
+                    .radar_timeline
+                    .find_recent_scan(playback_ts, MAX_SCAN_AGE_SECS)
+                {
+                    return self.most_recent_sweep_elevation(scan, playback_ts);
+                }
+                self.render
+                    .available_elevations()
+                    .first()
+                    .copied()
+                    .unwrap_or(1)
+            }
+            crate::state::ElevationSelection::LowestAvailable => {
+                let playback_ts = self.state.playback_state.playback_position();
+                self.state
+                    .radar_timeline
+                    .find_recent_scan(playback_ts, MAX_SCAN_AGE_SECS)
+                    .and_then(|scan| self.best_elevation_at_playback(scan, playback_ts))
+                    .or_else(|| self.render.available_elevations().first().copied())
+                    .unwrap_or(1)
+            }
+        }
+    }
+
+    /// Find the best elevation number for a scan given the playback position.
+    /// Returns None when no sweep in the scan matches the user's fixed selection
+    /// (so callers can clear display instead of issuing a doomed render).
+    fn best_elevation_at_playback(
+        &self,
+        scan: &crate::state::radar_data::Scan,
+        playback_ts: f64,
+    ) -> Option<u8> {
+        state::playback_manager::best_elevation_at_playback(
+            &self.state.viz_state.elevation_selection,
+            scan,
+            playback_ts,
+            self.render.available_elevations(),
+        )
+    }
+
+    /// Find the most recent sweep (any elevation) at or before the playback position.
+    fn most_recent_sweep_elevation(
+        &self,
+        scan: &crate::state::radar_data::Scan,
+        playback_ts: f64,
+    ) -> u8 {
+        let fallback = self
+            .render
+            .available_elevations()
+            .first()
+            .copied()
+            .unwrap_or(1);
+        state::playback_manager::most_recent_sweep_elevation(scan, playback_ts, fallback)
+    }
+
+    /// Build the elevation list from a scan's VCP data.
+    fn build_elevation_list(
+        scan: &crate::state::radar_data::Scan,
+    ) -> Vec<crate::state::ElevationListEntry> {
+        state::playback_manager::build_elevation_list(scan)
+    }
+
+    /// Update the canvas overlay text with sweep timing and elevation info.
+    fn update_overlay_from_sweep(&mut self, start: f64, end: f64, elevation_deg: f32) {
+        self.state
+            .viz_state
+            .update_overlay(start, end, elevation_deg, self.state.use_local_time);
+    }
+
+    /// Send a render request to the worker for the current scan/elevation/product.
+    fn request_worker_render(&mut self) {
+        if self.state.viz_state.product.is_qpe_product() {
+            self.request_worker_render_qpe();
+            return;
+        }
+        if self
+            .state
+            .viz_state
+            .elevation_selection
+            .is_lowest_available()
+            && !self.state.live_mode_state.is_active()
+            && !self.state.viz_state.product.is_volume_product()
+            && !self.state.viz_state.product.is_level3()
+        {
+            self.request_worker_render_lowest_available();
+            return;
+        }
+
+        let mut elevation_number = self
+            .state
+            .viz_state
+            .displayed_sweep_elevation_number
+            .unwrap_or_else(|| self.best_elevation_number());
+
+        // During real-time streaming, constrain to what's actually available.
+        if self.state.live_mode_state.is_active()
+            && !self.render.available_elevations().is_empty()
+            && !self
+                .render
+                .available_elevations()
+                .contains(&elevation_number)
+        {
+            elevation_number = self.render.best_available_elevation(elevation_number);
+        }
+
+        let product = self.state.viz_state.product.to_worker_string().to_string();
+
+        // Volume products are stored once per scan at its lowest elevation,
+        // whichever tilt is selected.
+        if self.state.viz_state.product.is_volume_product() {
+            elevation_number = self
+                .state
+                .viz_state
+                .displayed_scan_timestamp
+                .and_then(|ts| self.state.radar_timeline.find_scan_at_timestamp(ts as f64))
+                .and_then(|scan| scan.sweeps.iter().map(|s| s.elevation_number).min())
+                .unwrap_or(1);
+        }
+
+        // Preemptive availability gate (archive/scrub path only — live-mode
+        // scans may have more elevations than radar_timeline yet knows about).
+        // If the displayed scan exists in radar_timeline but has no sweep at
+        // this elevation — or has a sweep that doesn't carry the selected
+        // product (e.g. reflectivity-only split cuts when viewing velocity) —
+        // clear the canvas rather than issuing a request the worker will
+        // reject with "No pre-computed sweep".
+        if !self.state.live_mode_state.is_active() {
+            let missing = self
+                .state
+                .viz_state
+                .displayed_scan_timestamp
+                .and_then(|ts| self.state.radar_timeline.find_scan_at_timestamp(ts as f64))
+                .is_some_and(|scan| scan.lacks_sweep(elevation_number, &product));
+            if missing {
+                self.state.viz_state.volume_product_missing =
+                    self.state.viz_state.product.is_volume_product();
+                self.clear_display_no_sweep();
+                return;
+            }
+        }
+
+        let is_auto = self.state.viz_state.elevation_selection.is_auto();
+
+        if self
+            .render
+            .request_render(elevation_number, &product, is_auto)
+            && !self.state.session_stats.pipeline.processing
+        {
+            self.state.session_stats.pipeline.processing = true;
+        }
+    }
+
+    /// Request a rainfall accumulation over the cached scans in the QPE window.
+    fn request_worker_render_qpe(&mut self) {
+        let site_id = &self.state.viz_state.site_id;
+        let qpe_scans: Vec<nexrad::QpeScan> = match self.state.qpe_window() {
+            Some((start, end)) => {
+                let scans: Vec<_> = self
+                    .state
+                    .radar_timeline
+                    .scans_in_range(start, end)
+                    .collect();
+                let starts: Vec<f64> = scans.iter().map(|s| s.start_time).collect();
+                let durations = nexrad::qpe::scan_durations(&starts, start, end);
+                scans
+                    .iter()
+                    .zip(durations)
+                    .filter(|(_, secs)| *secs > 0.0)
+                    .map(|(scan, secs)| nexrad::QpeScan {
+                        scan_key: data::ScanKey::from_secs(site_id, scan.key_timestamp as i64)
+                            .to_storage_key(),
+                        // Accumulations use the lowest tilt; metadata-only
+                        // scans have no sweep list yet, and that's tilt 1.
+                        elevation_number: scan
+                            .sweeps
+                            .iter()
+                            .min_by(|a, b| a.elevation.total_cmp(&b.elevation))
+                            .map_or(1, |s| s.elevation_number),
+                        hours: secs / 3600.0,
+                    })
+                    .collect()
+            }
+            None => Vec::new(),
+        };
+        if qpe_scans.is_empty() {
+            self.render.clear_last_qpe_render();
+            self.clear_display_no_sweep();
+            return;
+        }
+
+        let request = nexrad::QpeRenderRequest {
+            product: self.state.viz_state.product.to_worker_string().to_string(),
+            scans: qpe_scans,
+            settings: self.state.viz_state.qpe_settings,
+        };
+        if self.render.request_qpe_render(request) && !self.state.session_stats.pipeline.processing
+        {
+            self.state.session_stats.pipeline.processing = true;
+        }
+    }
+
+    /// Request a lowest-available composite of the cached sweeps ending in
+    /// the trailing window before the playback position.
+    fn request_worker_render_lowest_available(&mut self) {
+        let viz = &self.state.viz_state;
+        let product = viz.product.to_worker_string().to_string();
+        let end = self.state.playback_state.playback_position();
+        let start = end - viz.lowest_available_mins as f64 * 60.0;
+        // Sweeps listing no products are assumed to carry it.
+        let carries_product = |s: &state::radar_data::Sweep| {
+            s.available_products.is_empty() || s.available_products.contains(&product)
+        };
+        let carries_product = &carries_product;
+        let mut candidates: Vec<(f32, f64, nexrad::LowestAvailableSweep)> = self
+            .state
+            .radar_timeline
+            .scans_in_range(start, end)
+            .flat_map(|scan| {
+                let scan_key = data::ScanKey::from_secs(&viz.site_id, scan.key_timestamp as i64)
+                    .to_storage_key();
+                scan.sweeps
+                    .iter()
+                    .filter(move |s| (start..=end).contains(&s.end_time) && carries_product(s))
+                    .map(move |s| {
+                        (
+                            s.elevation,
+                            s.end_time,
+                            nexrad::LowestAvailableSweep {
+                                scan_key: scan_key.clone(),
+                                elevation_number: s.elevation_number,
+                            },
+                        )
+                    })
+            })
+            .collect();
+        if candidates.is_empty() {
+            self.render.clear_last_lowest_available();
+            self.clear_display_no_sweep();
+            return;
+        }
+        nexrad::lowest_available::composite_order(&mut candidates, |c| (c.0, c.1));
+
+        let request = nexrad::LowestAvailableRequest {
+            product,
+            sweeps: candidates.into_iter().map(|(_, _, sweep)| sweep).collect(),
+        };
+        if self.render.request_lowest_available_render(request)
+            && !self.state.session_stats.pipeline.processing
+        {
+            self.state.session_stats.pipeline.processing = true;
+        }
+    }
+
+    /// Request renders of the current scan for the extra split-view panels.
+    ///
+    /// Panels without a fixed tilt follow the main panel's; volume products
+    /// use the scan's lowest tilt. A panel whose sweep isn't stored (or that
+    /// shows an accumulation, which only the main panel computes) is cleared
+    /// instead of asking the worker.
+    fn request_panel_renders(&mut self) {
+        if self.render.scan_key().is_none() || !self.render.has_worker() {
+            return;
+        }
+        let live = self.state.live_mode_state.is_active();
+        let main_elevation = self
+            .state
+            .viz_state
+            .displayed_sweep_elevation_number
+            .unwrap_or_else(|| self.best_elevation_number());
+        let scan = self
+            .state
+            .viz_state
+            .displayed_scan_timestamp
+            .and_then(|ts| self.state.radar_timeline.find_scan_at_timestamp(ts as f64));
+
+        let targets: Vec<Option<(u8, &'static str)>> = self
+            .state
+            .viz_state
+            .visible_extra_panels()
+            .iter()
+            .map(|view| {
+                if view.product.is_qpe_product() {
+                    return None;
+                }
+                let product = view.product.to_worker_string();
+                let mut elevation_number = if view.product.is_volume_product() {
+                    scan.and_then(|s| s.sweeps.iter().map(|s| s.elevation_number).min())
+                        .unwrap_or(1)
+                } else {
+                    view.elevation_number.unwrap_or(main_elevation)
+                };
+                if live && !self.render.available_elevations().is_empty() {
+                    elevation_number = self.render.best_available_elevation(elevation_number);
+                }
+                let missing =
+                    !live && scan.is_some_and(|s| s.lacks_sweep(elevation_number, product));
+                (!missing).then_some((elevation_number, product))
+            })
+            .collect();
+
+        for (panel, target) in targets.into_iter().enumerate() {
+            match target {
+                Some((elevation_number, product)) => {
+                    self.render
+                        .request_panel_render(panel, elevation_number, product);
+                }
+                None => self.clear_panel(panel),
+            }
+        }
+    }
+
+    /// Request volume render (all elevations for ray marching).
+    fn request_worker_render_volume(&mut self) {
+        let product = self.state.viz_state.product.to_worker_string().to_string();
+        self.render.request_volume_render(&product);
+    }
+
+    /// Request a vertical cross-section along the drawn line while its window
+    /// is open. Re-sent whenever the scan, product, line or storm motion
+    /// changes.
+    fn request_cross_section(&mut self) {
+        let viz = &self.state.viz_state;
+        let section = &self.state.cross_section;
+        if !section.window_open || viz.product.is_volume_product() || viz.product.is_qpe_product() {
+            return;
+        }
+        let Some((start_km, end_km)) = section.line_km(viz.center_lat, viz.center_lon) else {
+            return;
+        };
+        let storm_motion_ms = viz
+            .storm_relative_motion_for(viz.product)
+            .map_or([0.0; 2], |m| [m.east_kmh / 3.6, m.north_kmh / 3.6]);
+        let Some(request) = self.render.cross_section_request(
+            viz.product.to_worker_string(),
+            start_km,
+            end_km,
+            storm_motion_ms,
+        ) else {
+            return;
+        };
+        if self.render.request_cross_section(request) {
+            self.state.cross_section.pending = true;
+        }
+    }
+
+    /// Request each visible pinned site's scan at the playback position,
+    /// at the tilt nearest the main view's, into its GPU slot. Slots with
+    /// nothing to draw are cleared.
+    fn request_pinned_renders(&mut self) {
+        if !self.render.has_worker() || self.state.viz_state.view_mode != state::ViewMode::Flat2D {
+            return;
+        }
+        let viz = &self.state.viz_state;
+        let product = if viz.product.is_volume_product() || viz.product.is_qpe_product() {
+            None
+        } else {
+            Some(viz.product.to_worker_string())
+        };
+        let elevation_deg =
+            viz.rendered_sweep_elevation_deg
+                .unwrap_or(match viz.elevation_selection {
+                    state::ElevationSelection::Fixed { angle, .. } => angle,
+                    state::ElevationSelection::Latest
+                    | state::ElevationSelection::LowestAvailable => 0.5,
+                });
+        let position = self.state.playback_state.playback_position();
+
+        for slot in 0..state::MAX_PINNED_SITES {
+            let target = self
+                .state
+                .pinned_sites
+                .sites
+                .get(slot)
+                .filter(|site| site.visible)
+                .zip(product)
+                .and_then(|(site, product)| {
+                    let scan = site.scan_at(position)?;
+                    let elevation_number = scan.sweep_near(elevation_deg, product)?;
+                    Some((scan.key.clone(), elevation_number, product))
+                });
+            if let Some(site) = self.state.pinned_sites.sites.get_mut(slot) {
+                site.shown_scan = target.as_ref().map(|(key, ..)| key.clone());
+            }
+            match target {
+                Some((scan_key, elevation_number, product)) => {
+                    self.render
+                        .request_pinned_render(slot, &scan_key, elevation_number, product);
+                }
+                None => {
+                    if let Some(renderer) = self.gpu.pinned.get(slot) {
+                        if let Ok(mut r) = renderer.lock() {
+                            r.clear_data();
+                        }
+                    }
+                    self.render.clear_pinned_render(slot);
+                }
+            }
+        }
+    }
+
+    /// Request the regional mosaic while it is shown. The time is taken to
+    /// the minute so playback doesn't composite every frame; a new request
+    /// waits for the one in flight.
+    fn request_mosaic(&mut self) {
+        let mosaic = &self.state.mosaic;
+        if !mosaic.enabled || mosaic.site_ids.is_empty() || mosaic.compositing {
+            return;
+        }
+        let product = &self.state.viz_state.product;
+        let product = if product.is_volume_product() || product.is_qpe_product() {
+            "reflectivity"
+        } else {
+            product.to_worker_string()
+        };
+        let position = self.state.playback_state.playback_position();
+        let request = nexrad::MosaicRequest {
+            site_ids: mosaic.site_ids.clone(),
+            time_secs: (position / 60.0).floor() as i64 * 60,
+            max_age_secs: mosaic.max_age_secs(),
+            product: product.to_string(),
+            mode: mosaic.mode,
+        };
+        if self.render.request_mosaic(request) {
+            self.state.mosaic.compositing = true;
+        }
+    }
+
+    /// Start a CF-Radial, GeoTIFF or CSV export asked for in the export
+    /// panel. Image formats are left to the export manager.
+    fn start_data_export(&mut self) {
+        let format = self.state.export.format;
+        if format.is_image() || !std::mem::take(&mut self.state.export.start_requested) {
+            return;
+        }
+        let result = match format {
+            state::ExportFormat::CfRadial => self.request_cf_radial(),
+            _ => self.export_grid(format).map(Some),
+        };
+        match result {
+            Ok(Some(message)) => self.state.export.last_result = Some(Ok(message)),
+            Ok(None) => self.state.export.last_result = None,
+            Err(message) => self.state.export.last_result = Some(Err(message)),
+        }
+    }
+
+    /// Ask a worker to write the current scan as CF-Radial. The download
+    /// starts when the result arrives.
+    fn request_cf_radial(&mut self) -> Result<Option<String>, String> {
+        if self.state.export.data_pending {
+            return Err("A CF-Radial export is already running".into());
+        }
+        let scan_key = self.render.scan_key().ok_or("No scan loaded")?.to_string();
+        let elevation_numbers = self.render.available_elevations().to_vec();
+        if elevation_numbers.is_empty() {
+            return Err("No cached elevations to export".into());
+        }
+        let site_id = &self.state.viz_state.site_id;
+        let site = data::get_site(site_id).ok_or_else(|| format!("Unknown site {}", site_id))?;
+        let scan_secs = data::keys::ScanKey::from_storage_key(&scan_key)
+            .map_or(0.0, |k| k.scan_start.as_secs() as f64);
+        let request = nexrad::CfRadialRequest {
+            file_stem: export::file_stem(site.id, "CFRAD", scan_secs),
+            scan_key,
+            elevation_numbers,
+            site_id: site.id.to_string(),
+            site_name: site.name.to_string(),
+            latitude: site.lat,
+            longitude: site.lon,
+            altitude_m: site.altitude_km() * 1000.0,
+        };
+        if !self.render.export_cf_radial(request) {
+            return Err("Data worker unavailable".into());
+        }
+        self.state.export.data_pending = true;
+        Ok(None)
+    }
+
+    /// Grid the displayed sweep around the site and download it.
+    fn export_grid(&mut self, format: state::ExportFormat) -> Result<String, String> {
+        let viz = &self.state.viz_state;
+        let sweep = self
+            .gpu
+            .gpu
+            .as_ref()
+            .and_then(|r| r.lock().expect("renderer mutex poisoned").polar_sweep())
+            .ok_or("Nothing rendered to export")?;
+        let product = viz.product;
+        let value_name = match product.unit() {
+            "" => product.short_code().to_string(),
+            unit => format!("{} ({})", product.short_code(), unit),
+        };
+        let secs = viz
+            .rendered_sweep_end_secs
+            .unwrap_or_else(|| self.state.playback_state.playback_position());
+        export::save_grid(
+            format,
+            &sweep,
+            viz.center_lat,
+            viz.center_lon,
+            &value_name,
+            &export::file_stem(&viz.site_id, product.short_code(), secs),
+        )
+    }
+
+    /// Stop live mode streaming.
+    #[allow(dead_code)] // Called from UI when user stops live mode
+    fn stop_live_mode(&mut self, reason: state::LiveExitReason) {
+        log::info!("Stopping live mode: {:?}", reason);
+
+        self.state.live_mode_state.stop(reason);
+        self.state.playback_state.time_model.disable_realtime_lock();
+        self.streaming.stop_realtime();
+
+        // Halt playback unless the user is actively scrubbing/jogging — those
+        // paths set the new position themselves. Without this, we leave
+        // playing=true at Realtime speed and position=wall-clock, so the
+        // cursor keeps pace with "now" and mimics still being locked.
+        if !matches!(
+            reason,
+            state::LiveExitReason::UserSeeked | state::LiveExitReason::UserJogged
+        ) {
+            self.state.playback_state.playing = false;
+        }
+
+        self.state.status_message = self
+            .state
+            .live_mode_state
+            .last_exit_reason
+            .map(|r| r.message().to_string())
+            .unwrap_or_default();
+    }
+
+    /// Handle a realtime streaming result.
+    fn handle_realtime_result(&mut self, result: nexrad::RealtimeResult, _ctx: &egui::Context) {
+        // Get current time
+        let now = crate::platform::now_secs();
+
+        match result {
+            nexrad::RealtimeResult::Started { site_id } => {
+                log::debug!("Realtime streaming started for site: {}", site_id);
+                self.state.live_mode_state.handle_streaming_started(now);
+                self.state.status_message = format!("Live: connected to {}", site_id);
+            }
+            nexrad::RealtimeResult::ChunkReceived {
+                chunks_in_volume,
+                time_until_next,
+                is_volume_end,
+                fetch_latency_ms,
+                projected_volume_end_secs,
+                chunk_projections,
+                arrival_stat,
+            } => {
+                self.state
+                    .session_stats
+                    .record_fetch_latency(fetch_latency_ms);
+                log::debug!(
+                    "Realtime status: chunks_in_volume={} is_end={} latency={:.0}ms next_in={:?} proj_end={:?}",
+                    chunks_in_volume,
+                    is_volume_end,
+                    fetch_latency_ms,
+                    time_until_next,
+                    projected_volume_end_secs,
+                );
+                self.state.live_mode_state.handle_realtime_chunk(
+                    chunks_in_volume,
+                    time_until_next,
+                    is_volume_end,
+                    now,
+                    projected_volume_end_secs,
+                    chunk_projections,
+                );
+
+                if let Some(stat) = arrival_stat {
+                    self.state.live_mode_state.record_chunk_arrival(stat);
+                }
+
+                // Record chunk latency for the acquisition drawer
+                self.state.acquisition.record_chunk_latency(
+                    chunks_in_volume,
+                    fetch_latency_ms,
+                    None, // radial timestamps populated after ingest
+                    None,
+                );
+            }
+            nexrad::RealtimeResult::ChunkData {
+                data,
+                chunk_index,
+                is_start,
+                is_end,
+                timestamp,
+                skip_overlap_delete,
+            } => {
+                log::debug!(
+                    "Realtime chunk received: index={} is_start={} is_end={} size={} bytes ts={}",
+                    chunk_index,
+                    is_start,
+                    is_end,
+                    data.len(),
+                    timestamp,
+                );
+
+                // Track realtime chunk as an acquisition operation
+                let rt_site_id = self.state.viz_state.site_id.clone();
+                let op_id =
+                    self.state
+                        .acquisition
+                        .create_operation(state::OperationKind::RealtimeChunk {
+                            site_id: rt_site_id,
+                            chunk_index,
+                            is_start,
+                            is_end,
+                            scan_timestamp: timestamp,
+                        });
+                self.state.acquisition.mark_active(op_id);
+                self.state
+                    .acquisition
+                    .mark_completed(op_id, data.len() as u64);
+
+                if is_start {
+                    self.state.status_message = "Live: receiving new volume...".to_string();
+                    log::debug!("Realtime: new volume started, forwarding start chunk to worker");
+                }
+
+                // Forward chunk to worker for incremental ingest
+                let site_id = self.state.viz_state.site_id.clone();
+                let file_name = format!("live_{}_{}.nexrad", site_id, timestamp);
+                if is_start {
+                    self.state.session_stats.pipeline.processing = true;
+                }
+
+                // Look up whether this is the last chunk in its sweep from
+                // the projection metadata. sequence = chunk_index + 1 (1-based).
+                let sequence = (chunk_index + 1) as usize;
+                let is_last_in_sweep = self
+                    .state
+                    .live_mode_state
+                    .chunk_projections
+                    .as_ref()
+                    .and_then(|projs| projs.iter().find(|c| c.sequence == sequence))
+                    .map(|c| c.chunk_index_in_sweep + 1 == c.chunks_in_sweep)
+                    .unwrap_or(false);
+
+                log::debug!(
+                    "Realtime: forwarding chunk {} to worker for ingest (site={}, ts={}, last_in_sweep={})",
+                    chunk_index,
+                    site_id,
+                    timestamp,
+                    is_last_in_sweep,
+                );
+                self.render.ingest_chunk(
+                    data,
+                    site_id,
+                    timestamp,
+                    chunk_index,
+                    is_start,
+                    is_end,
+                    file_name,
+                    skip_overlap_delete,
+                    is_last_in_sweep,
+                );
+            }
+            nexrad::RealtimeResult::Error(msg) => {
+                log::error!("Realtime streaming error: {}", msg);
+                self.stop_live_mode(state::LiveExitReason::ConnectionError);
+                // Preserve error message (stop_live_mode clears it)
+                self.state.live_mode_state.error_message = Some(msg.clone());
+                self.state.status_message = format!("Live error: {}", msg);
+
+                // Track error as a failed acquisition operation
+                let err_site_id = self.state.viz_state.site_id.clone();
+                let op_id =
+                    self.state
+                        .acquisition
+                        .create_operation(state::OperationKind::RealtimeChunk {
+                            site_id: err_site_id,
+                            chunk_index: 0,
+                            is_start: false,
+                            is_end: false,
+                            scan_timestamp: 0,
+                        });
+                self.state.acquisition.mark_failed(op_id, msg);
+            }
+        }
+    }
+
+    /// Per-frame bookkeeping: record stats, apply theme, recompute staleness,
+    /// update storm cells, and detect site changes.
+    fn apply_frame_setup(&mut self, ctx: &egui::Context) {
+        // Record frame time for FPS meter
+        let dt = ctx.input(|i| i.stable_dt);
+        self.state.session_stats.record_frame_time(dt);
+
+        // Resolve theme and apply egui visuals
+        self.state.is_dark = self.state.theme_mode.is_dark();
+        if self.state.is_dark {
+            let mut visuals = egui::Visuals::dark();
+            visuals.panel_fill = egui::Color32::BLACK;
+            visuals.window_fill = egui::Color32::BLACK;
+            visuals.extreme_bg_color = egui::Color32::BLACK;
+            ctx.set_visuals(visuals);
+        } else {
+            ctx.set_visuals(egui::Visuals::light());
+        }
+
+        // Recompute data staleness every frame against wall-clock time.
+        // This ensures archive data correctly shows its true age (days/years)
+        // rather than a misleading "few minutes" relative to playback position.
+        {
+            let now = crate::platform::now_secs();
+            if let Some(sweep_end) = self.state.viz_state.rendered_sweep_end_secs {
+                let staleness = now - sweep_end;
+                self.state.viz_state.data_staleness_secs = if staleness >= 0.0 {
+                    Some(staleness)
+                } else {
+                    None
+                };
+            }
+            if let Some(sweep_start) = self.state.viz_state.rendered_sweep_start_secs {
+                let staleness = now - sweep_start;
+                self.state.viz_state.data_staleness_start_secs = if staleness >= 0.0 {
+                    Some(staleness)
+                } else {
+                    None
+                };
+            }
+        }
+
+        // Ensure continuous repainting for time-dependent UI elements (the "now"
+        // marker on the timeline and the data age desaturation) even when the user
+        // is idle and playback is stopped.  Repaint once per second which is
+        // sufficient for these indicators while being easy on the CPU.
+        ctx.request_repaint_after(std::time::Duration::from_secs(1));
+
+        self.render
+            .set_dealias_velocity(self.state.render_processing.dealias_velocity);
+
+        // Storm-relative velocity is applied in the shader; push the active
+        // motion (km/h → m/s) every frame so source/heading edits show at once.
+        if let Some(ref renderer) = self.gpu.gpu {
+            if let Ok(mut r) = renderer.lock() {
+                let motion = self.state.viz_state.storm_relative_motion();
+                r.set_storm_motion(motion.map(|m| [m.east_kmh / 3.6, m.north_kmh / 3.6]));
+            }
+        }
+        for (renderer, view) in self
+            .gpu
+            .panels
+            .iter()
+            .zip(self.state.viz_state.extra_panels)
+        {
+            if let Ok(mut r) = renderer.lock() {
+                let motion = self.state.viz_state.storm_relative_motion_for(view.product);
+                r.set_storm_motion(motion.map(|m| [m.east_kmh / 3.6, m.north_kmh / 3.6]));
+            }
+        }
+
+        // Run storm cell detection on demand when toggled on with existing data
+        if self.state.viz_state.storm_cells_visible
+            && self.state.viz_state.detected_storm_cells.is_empty()
+        {
+            if let Some(ref renderer) = self.gpu.gpu {
+                if let Ok(r) = renderer.lock() {
+                    if r.has_data() {
+                        let cells = r.detect_storm_cells(
+                            self.state.viz_state.center_lat,
+                            self.state.viz_state.center_lon,
+                            self.state.viz_state.storm_cell_threshold_dbz,
+                        );
+                        let elevation = self.state.viz_state.displayed_sweep_elevation_number;
+                        let sweep_start = self.state.viz_state.rendered_sweep_start_secs;
+                        self.state.viz_state.apply_storm_cell_detection(
+                            cells,
+                            elevation,
+                            sweep_start,
+                        );
+                    }
+                }
+            }
+        }
+        // Clear cached cells when toggle is off
+        if !self.state.viz_state.storm_cells_visible
+            && !self.state.viz_state.detected_storm_cells.is_empty()
+        {
+            self.state.viz_state.detected_storm_cells.clear();
+        }
+
+        // Same for rotation detection, which only applies to velocity data.
+        if self.state.viz_state.rotation_visible
+            && self.state.viz_state.detected_rotations.is_none()
+            && self.state.viz_state.product.is_velocity()
+        {
+            self.state.viz_state.detected_rotations = self.detect_rotations();
+        }
+        if !self.state.viz_state.rotation_visible {
+            self.state.viz_state.detected_rotations = None;
+        }
+
+        // Detect site changes and clear volume ring
+        if self
+            .persistence
+            .detect_site_change(&self.state.viz_state.site_id)
+        {
+            if let Some(ref renderer) = self.gpu.gpu {
+                if let Ok(mut r) = renderer.lock() {
+                    r.clear_data();
+                }
+            }
+            self.clear_panels();
+            self.playback_manager.clear_cache();
+            self.render.clear_for_site_change();
+            self.state.viz_state.displayed_scan_timestamp = None;
+            self.state.viz_state.displayed_sweep_elevation_number = None;
+            self.state.viz_state.storm_tracker.clear();
+            self.state.viz_state.tracked_mean_motion = None;
+            self.state.viz_state.detected_rotations = None;
+            self.state.shadow_scan_boundaries.clear();
+            // The new active site can't stay pinned under itself.
+            let site_id = self.state.viz_state.site_id.clone();
+            self.unpin_site(&site_id);
+        }
+    }
+
+    /// Detect rotation on the displayed velocity sweep. The scan's dealiased
+    /// velocity is preferred so folds can't pose as couplets: it is requested
+    /// from the worker when only the raw sweep is on screen, and until it
+    /// arrives the raw sweep is searched with fold rejection. `None` while
+    /// nothing is rendered.
+    fn detect_rotations(&mut self) -> Option<Vec<state::RotationInfo>> {
+        let viz = &self.state.viz_state;
+        let (radar_lat, radar_lon) = (viz.center_lat, viz.center_lon);
+        let elevation_deg = viz.rendered_sweep_elevation_deg.unwrap_or(0.5);
+
+        let dealiased = (viz.product != state::RadarProduct::DealiasedVelocity
+            && !self.state.live_mode_state.is_active())
+        .then(|| {
+            let scan_key = self.render.scan_key()?;
+            let elevation_number = viz.displayed_sweep_elevation_number?;
+            Some((
+                scan_key.to_string(),
+                elevation_number,
+                sweep_cache_key(scan_key, elevation_number, DEALIASED_VELOCITY),
+            ))
+        })
+        .flatten();
+        if let Some((scan_key, elevation_number, key)) = dealiased {
+            if let Some(cached) = self.playback_manager.get_cached_sweep(&key) {
+                let input = nexrad::detection::DetectionInput {
+                    azimuths: &cached.azimuths,
+                    gate_values: &cached.gate_values,
+                    azimuth_count: cached.azimuth_count as usize,
+                    gate_count: cached.gate_count as usize,
+                    first_gate_km: cached.first_gate_range_km,
+                    gate_interval_km: cached.gate_interval_km,
+                    data_scale: cached.scale,
+                    data_offset: cached.offset,
+                    radar_lat,
+                    radar_lon,
+                };
+                return Some(nexrad::detection::detect_rotation(
+                    &input,
+                    elevation_deg,
+                    None,
+                    &Default::default(),
+                ));
+            }
+            // Only ask for sweeps known to carry it: a missing sweep would
+            // come back as an error that blanks the display.
+            let stored = viz
+                .displayed_scan_timestamp
+                .and_then(|ts| self.state.radar_timeline.find_scan_at_timestamp(ts as f64))
+                .and_then(|scan| {
+                    scan.sweeps
+                        .iter()
+                        .find(|s| s.elevation_number == elevation_number)
+                })
+                .is_some_and(|s| s.available_products.iter().any(|p| p == DEALIASED_VELOCITY));
+            if stored && self.requested_rotation_sweep.as_deref() != Some(key.as_str()) {
+                self.render.render_direct(
+                    scan_key,
+                    elevation_number,
+                    DEALIASED_VELOCITY.to_string(),
+                );
+                self.requested_rotation_sweep = Some(key);
+            }
+        }
+
+        let renderer = self.gpu.gpu.as_ref()?;
+        let r = renderer.lock().ok()?;
+        r.has_data()
+            .then(|| r.detect_rotation(radar_lat, radar_lon, elevation_deg))
+    }
+
+    /// Drain the command queue and execute each command.
+    /// Returns flags for (download_selection, download_at_position, pump_queue).
+    fn dispatch_commands(&mut self, ctx: &egui::Context) -> (bool, bool, bool) {
+        let commands = self.state.drain_commands();
+        let mut do_download_selection = false;
+        let mut do_download_at_position = false;
+        let mut do_pump_queue = false;
+        for cmd in commands {
+            match cmd {
+                state::AppCommand::ClearCache => {
+                    if !self.acquisition.cache_load_channel.is_loading() {
+                        self.acquisition
+                            .cache_load_channel
+                            .clear_cache(ctx.clone(), self.acquisition.facade().clone());
+                    } else {
+                        // Re-enqueue if channel is busy
+                        self.state.push_command(state::AppCommand::ClearCache);
+                    }
+                }
+                state::AppCommand::WipeAll => {
+                    let facade = self.acquisition.facade().clone();
+                    #[cfg(not(target_arch = "wasm32"))]
+                    let ctx = ctx.clone();
+                    platform::spawn_local(async move {
+                        if let Err(e) = facade.clear_all().await {
+                            log::error!("Failed to clear record cache: {}", e);
+                        }
+                        platform::storage_clear();
+                        #[cfg(target_arch = "wasm32")]
+                        if let Some(window) = web_sys::window() {
+                            let _ = window.location().reload();
+                        }
+                        // No page to reload; start clean on the next launch.
+                        #[cfg(not(target_arch = "wasm32"))]
+                        ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+                    });
+                }
+                state::AppCommand::RefreshTimeline { auto_position } => {
+                    if auto_position {
+                        self.state.auto_position_on_timeline_load = true;
+                    }
+                    if !self.acquisition.cache_load_channel.is_loading() {
+                        self.acquisition.cache_load_channel.load_site_timeline(
+                            ctx.clone(),
+                            self.acquisition.facade().clone(),
+                            self.state.viz_state.site_id.clone(),
+                        );
+                    } else {
+                        self.state.push_command(state::AppCommand::RefreshTimeline {
+                            auto_position: false,
+                        });
+                    }
+                }
+                state::AppCommand::CheckEviction => {
+                    let facade = self.acquisition.facade().clone();
+                    let quota = self.state.storage_settings.quota_bytes;
+                    let target = self.state.storage_settings.eviction_target_bytes;
+                    let ctx_clone = ctx.clone();
+                    platform::spawn_local(async move {
+                        match facade.check_and_evict(quota, target).await {
+                            Ok((evicted, count, quota_warning)) => {
+                                if evicted {
+                                    log::debug!("Eviction complete: removed {} scans", count);
+                                }
+                                if let Some(warning) = quota_warning {
+                                    log::warn!("Quota warning: {}", warning);
+                                }
+                            }
+                            Err(e) => {
+                                log::error!("Eviction check failed: {}", e);
+                            }
+                        }
+                        ctx_clone.request_repaint();
+                    });
+                }
+                state::AppCommand::StartLive
+                | state::AppCommand::DownloadSelection
+                | state::AppCommand::DownloadAtPosition
+                    if self.state.radar_type() == data::RadarType::Tdwr =>
+                {
+                    self.state.set_status(
+                        "TDWR sites have no Level II data \u{2014} fetch Level III products instead",
+                    );
+                }
+                state::AppCommand::StartLive => {
+                    self.start_live_mode(ctx);
+                }
+                state::AppCommand::ApplyDataSource => {
+                    if self.state.live_mode_state.is_active() {
+                        self.stop_live_mode(state::LiveExitReason::UserStopped);
+                    }
+                    self.state.data_source.save();
+                    nexrad::data_source::configure(&self.state.data_source);
+                    // Listings from the previous source may not match the new one.
+                    self.acquisition.archive_index.clear();
+                    self.state.status_message = if self.state.data_source.is_custom() {
+                        "Using custom data source".to_string()
+                    } else {
+                        "Using AWS data source".to_string()
+                    };
+                }
+                state::AppCommand::DownloadSelection => {
+                    do_download_selection = true;
+                }
+                state::AppCommand::DownloadAtPosition => {
+                    do_download_at_position = true;
+                }
+                state::AppCommand::PauseQueue => {
+                    self.state.acquisition.pause();
+                }
+                state::AppCommand::ResumeQueue => {
+                    self.state.acquisition.resume();
+                    do_pump_queue = true;
+                }
+                state::AppCommand::RetryFailed(op_id) => {
+                    self.state.acquisition.retry_failed(op_id);
+                    do_pump_queue = true;
+                }
+                state::AppCommand::SkipFailed(op_id) => {
+                    self.state.acquisition.skip_failed(op_id);
+                    do_pump_queue = true;
+                }
+                state::AppCommand::CancelOperation(op_id) => {
+                    self.state.acquisition.cancel_operation(op_id);
+                }
+                state::AppCommand::ReorderOperation(op_id, delta) => {
+                    self.state.acquisition.reorder_operation(op_id, delta);
+                }
+                state::AppCommand::ImportFiles { directory } => {
+                    let channel = &self.acquisition.local_import_channel;
+                    if let Err(e) = channel.open_picker(ctx.clone(), directory) {
+                        self.state.set_status(format!("Import failed: {}", e));
+                    }
+                }
+                state::AppCommand::ImportPalettes => {
+                    let channel = &self.acquisition.palette_import_channel;
+                    if let Err(e) = channel.open_picker(ctx.clone(), false) {
+                        self.state
+                            .set_status(format!("Palette import failed: {}", e));
+                    }
+                }
+                state::AppCommand::ApplyPalettes => self.apply_palettes(),
+                state::AppCommand::FetchLevel3 => self.fetch_level3(ctx),
+                state::AppCommand::FetchMosaicScans => self.fetch_mosaic_scans(ctx),
+                state::AppCommand::PinSite(site_id) => self.pin_site(ctx, site_id),
+                state::AppCommand::UnpinSite(site_id) => self.unpin_site(&site_id),
+                state::AppCommand::FetchPinnedScans => self.fetch_pinned_scans(ctx),
+                state::AppCommand::RetryWorker => match self.render.create_worker(ctx.clone()) {
+                    Ok(()) => {
+                        self.state.worker_init_error = None;
+                        self.state.set_status("Decode worker initialized");
+                    }
+                    Err(msg) => {
+                        self.state.worker_init_error = Some(msg);
+                    }
+                },
+                state::AppCommand::RefreshAlerts => {
+                    self.state.alerts.refresh_requested = true;
+                }
+                state::AppCommand::OpenAlert(id) => {
+                    self.state.alerts.selected_alert_id = Some(id);
+                }
+                state::AppCommand::CloseAlert => {
+                    self.state.alerts.selected_alert_id = None;
+                    self.state.alerts.list_modal_open = false;
+                }
+            }
+        }
+
+        (
+            do_download_selection,
+            do_download_at_position,
+            do_pump_queue,
+        )
+    }
+
+    /// Process results from cache loads, web workers, downloads, and archive listings.
+    fn handle_worker_results(&mut self, ctx: &egui::Context) {
+        if let Some(result) = self.acquisition.cache_load_channel.try_recv() {
+            self.handle_cache_load_outcome(result);
+        }
+
+        for outcome in self.render.try_recv() {
+            match outcome {
+                nexrad::WorkerOutcome::Ingested(result) if result.context.imported => {
+                    self.handle_imported_outcome(result);
+                }
+                nexrad::WorkerOutcome::Ingested(result) if result.context.mosaic => {
+                    self.handle_mosaic_ingested_outcome(result);
+                }
+                nexrad::WorkerOutcome::Ingested(result) if result.context.pinned => {
+                    self.handle_pinned_ingested_outcome(result);
+                }
+                nexrad::WorkerOutcome::Ingested(result) => {
+                    self.handle_ingested_outcome(result);
+                }
+                nexrad::WorkerOutcome::ChunkIngested(result) => {
+                    self.handle_chunk_ingested_outcome(result);
+                }
+                nexrad::WorkerOutcome::Decoded(result) => {
+                    self.handle_decoded_outcome(result);
+                }
+                nexrad::WorkerOutcome::LiveDecoded(result) => {
+                    self.handle_live_decoded_outcome(result);
+                }
+                nexrad::WorkerOutcome::VolumeDecoded(volume_data) => {
+                    self.handle_volume_decoded_outcome(volume_data);
+                }
+                nexrad::WorkerOutcome::QpeDecoded(result) => {
+                    self.handle_qpe_decoded_outcome(result);
+                }
+                nexrad::WorkerOutcome::LowestAvailableDecoded(result) => {
+                    self.handle_lowest_available_decoded_outcome(result);
+                }
+                nexrad::WorkerOutcome::CrossSectionDecoded(data) => {
+                    // Drop sections superseded while the worker was busy.
+                    if self.render.is_latest_cross_section(&data.request) {
+                        self.state.cross_section.set_result(data);
+                    }
+                }
+                nexrad::WorkerOutcome::CrossSectionFailed { request, message } => {
+                    if self.render.is_latest_cross_section(&request) {
+                        self.state.cross_section.pending = false;
+                        self.state.cross_section.error = Some(message);
+                    }
+                }
+                nexrad::WorkerOutcome::MosaicDecoded(data) => {
+                    if self.render.is_latest_mosaic(&data.request) {
+                        self.state.mosaic.set_result(ctx, data);
+                    }
+                }
+                nexrad::WorkerOutcome::MosaicFailed { request, message } => {
+                    if self.render.is_latest_mosaic(&request) {
+                        self.state.mosaic.compositing = false;
+                        self.state.mosaic.error = Some(message);
+                    }
+                }
+                nexrad::WorkerOutcome::MosaicIngestFailed { file_name, message } => {
+                    log::warn!("mosaic: {} failed: {}", file_name, message);
+                    self.state.mosaic.record_failure(file_name, message);
+                    self.state.set_status(self.state.mosaic.summary());
+                }
+                nexrad::WorkerOutcome::PinnedIngestFailed { file_name, message } => {
+                    log::warn!("pinned: {} failed: {}", file_name, message);
+                    if let Some(site) = self.state.pinned_sites.site_for_file(&file_name) {
+                        site.pending = site.pending.saturating_sub(1);
+                        site.error = Some(message);
+                    }
+                }
+                nexrad::WorkerOutcome::CfRadialEncoded(data) => {
+                    self.state.export.data_pending = false;
+                    self.state.export.last_result = Some(export::save_cf_radial(&data));
+                }
+                nexrad::WorkerOutcome::CfRadialFailed { request, message } => {
+                    log::warn!(
+                        "CF-Radial export of {} failed: {}",
+                        request.scan_key,
+                        message
+                    );
+                    self.state.export.data_pending = false;
+                    self.state.export.last_result = Some(Err(message));
+                }
+                nexrad::WorkerOutcome::ImportFailed { file_name, message } => {
+                    log::warn!("import: {} failed: {}", file_name, message);
+                    self.state.import.record_failure(file_name, message);
+                    self.finish_import_if_done();
+                }
+                nexrad::WorkerOutcome::Level3Ingested(result) => {
+                    self.handle_level3_ingested_outcome(result);
+                }
+                nexrad::WorkerOutcome::Level3Failed { file, message } => {
+                    log::warn!("level3: {} failed: {}", file.key, message);
+                    self.state.level3.record_failure(file.key, message);
+                    self.state.set_status(self.state.level3.summary());
+                }
+                nexrad::WorkerOutcome::WorkerError {
+                    id,
+                    message,
+                    failed_scan_timestamp_secs,
+                    failed_render,
+                } => {
+                    self.handle_worker_error_outcome(
+                        id,
+                        message,
+                        failed_scan_timestamp_secs,
+                        failed_render,
+                    );
+                }
+            }
+        }
+
+        if let Some(result) = self.acquisition.download_channel.try_recv() {
+            self.handle_download_outcome(result);
+        }
+
+        if let Some(result) = self.acquisition.download_channel.try_recv_listing() {
+            self.handle_listing_outcome(result);
+        }
+    }
+
+    fn handle_cache_load_outcome(&mut self, result: nexrad::CacheLoadResult) {
+        match result {
+            nexrad::CacheLoadResult::Success {
+                site_id,
+                metadata,
+                total_cache_size,
+            } => {
+                log::debug!(
+                    "Timeline loaded from cache: {} scan(s) for site {}",
+                    metadata.len(),
+                    site_id
+                );
+
+                // Update cache size in session stats
+                self.state.session_stats.cache_size_bytes = total_cache_size;
+
+                // Build timeline from metadata
+                self.state.radar_timeline = state::RadarTimeline::from_metadata(metadata);
+
+                // Get time ranges (may be non-contiguous)
+                let ranges = self.state.radar_timeline.time_ranges();
+                if !ranges.is_empty() {
+                    // Set overall bounds from first to last
+                    let start = ranges.first().unwrap().start;
+                    let end = ranges.last().unwrap().end;
+                    self.state.playback_state.data_start_timestamp = Some(start as i64);
+                    self.state.playback_state.data_end_timestamp = Some(end as i64);
+
+                    // Position playback at the end of the most recent range
+                    let most_recent_end = ranges.last().unwrap().end;
+
+                    // Only auto-position on initial load or site change,
+                    // not when refreshing after a download.
+                    if self.state.auto_position_on_timeline_load {
+                        self.state.auto_position_on_timeline_load = false;
+                        let ts = self.state.playback_state.playback_position();
+                        let in_any_range = ranges.iter().any(|r| r.contains(ts));
+                        if !in_any_range {
+                            self.state
+                                .playback_state
+                                .set_playback_position(most_recent_end);
+                            self.state.playback_state.center_view_on(most_recent_end);
+                        }
+                    }
+
+                    log::debug!("Timeline has {} contiguous range(s)", ranges.len());
+                }
+            }
+            nexrad::CacheLoadResult::Error(msg) => {
+                log::error!("Cache load failed: {}", msg);
+            }
+        }
+    }
+
+    fn handle_ingested_outcome(&mut self, result: nexrad::IngestResult) {
+        // Processing stays active through decode — don't mark done yet.
+        // Transition to decoding phase. Don't remove the ghost
+        // yet — it stays visible until the timeline refreshes
+        // and a real scan block replaces it (the ghost renderer's
+        // overlap check handles the visual transition).
+        self.state.download_progress.phase = crate::state::DownloadPhase::Decoding;
+        log::debug!(
+            "Ingest complete: {} ({} records, {} elevations, {} sweeps, {:.0}ms, fetch: {:.0}ms)",
+            result.scan_key,
+            result.records_stored,
+            result.elevation_numbers.len(),
+            result.sweeps.len(),
+            result.total_ms,
+            result.context.fetch_latency_ms,
+        );
+
+        self.state
+            .session_stats
+            .record_fetch_latency(result.context.fetch_latency_ms);
+        self.state
+            .session_stats
+            .record_processing_time(result.total_ms);
+
+        // Store detailed ingest timing for the detail modal.
+        self.state.session_stats.last_ingest_detail = Some(crate::state::IngestTimingDetail {
+            split_ms: result.split_ms,
+            decompress_ms: result.decompress_ms,
+            decode_ms: result.decode_ms,
+            extract_ms: result.extract_ms,
+            store_ms: result.store_ms,
+            index_ms: result.index_ms,
+        });
+
+        // Track the scan for render requests
+        self.render
+            .set_scan(result.scan_key.clone(), result.elevation_numbers);
+        self.state.viz_state.displayed_scan_timestamp = Some(result.context.timestamp_secs);
+        self.state.viz_state.displayed_sweep_elevation_number = None;
+        // Refresh timeline to include the new scan
+        self.state.push_command(state::AppCommand::RefreshTimeline {
+            auto_position: false,
+        });
+
+        // Request eviction check
+        self.state.push_command(state::AppCommand::CheckEviction);
+
+        // Force a fresh render
+        self.render.force_fresh_render();
+
+        // Trigger render for the ingested scan
+        self.request_worker_render();
+        if self.state.viz_state.volume_3d_enabled {
+            self.request_worker_render_volume();
+        }
+    }
+
+    /// Start fetching the picked Level III product for the current site
+    /// over the selection, else the contiguous loaded data at the playhead,
+    /// else the hour before it.
+    fn fetch_level3(&mut self, ctx: &egui::Context) {
+        let position = self.state.playback_state.playback_position();
+        let (start, end) = self
+            .state
+            .playback_state
+            .selection_range()
+            .or_else(|| {
+                self.state
+                    .radar_timeline
+                    .time_ranges()
+                    .into_iter()
+                    .find(|r| r.start <= position && position <= r.end)
+                    .map(|r| (r.start, r.end))
+            })
+            .unwrap_or((position - 3600.0, position));
+        let product = self.state.level3.product;
+        self.state.level3.start(product);
+        self.acquisition.level3_channel.fetch(
+            ctx.clone(),
+            self.state.viz_state.site_id.clone(),
+            product,
+            start.floor() as i64,
+            end.ceil() as i64,
+            self.acquisition.download_channel.stats(),
+        );
+        self.state.set_status(self.state.level3.summary());
+    }
+
+    /// Send downloaded Level III files to the workers and count listing or
+    /// download failures.
+    fn handle_level3_events(&mut self) {
+        while let Some(event) = self.acquisition.level3_channel.try_recv() {
+            match event {
+                nexrad::level3::Level3Event::Listed { count } => {
+                    self.state.level3.record_listed(count);
+                }
+                nexrad::level3::Level3Event::Downloaded { file, data } => {
+                    self.render.ingest_level3(data, file);
+                }
+                nexrad::level3::Level3Event::Failed { name, message } => {
+                    log::warn!("level3: {} failed: {}", name, message);
+                    self.state.level3.record_failure(name, message);
+                }
+            }
+            self.state.set_status(self.state.level3.summary());
+        }
+    }
+
+    /// A Level III product is in IndexedDB: count it, refresh the timeline
+    /// (it may have created a scan entry) and redraw if it is on screen.
+    fn handle_level3_ingested_outcome(&mut self, result: nexrad::Level3IngestResult) {
+        log::info!(
+            "level3: {} stored as {} under {} ({:.0}ms)",
+            result.file.key,
+            result.product,
+            result.scan_key,
+            result.total_ms,
+        );
+        self.state.level3.record_success();
+        self.state.set_status(self.state.level3.summary());
+        self.state.push_command(state::AppCommand::RefreshTimeline {
+            auto_position: false,
+        });
+        self.state.push_command(state::AppCommand::CheckEviction);
+        if result.product == self.state.viz_state.product.to_worker_string() {
+            self.render.force_fresh_render();
+            self.request_worker_render();
+        }
+    }
+
+    /// Start fetching the mosaic sites' volumes over the selection, else
+    /// at the playback position. The current site is fetched too; volumes
+    /// already cached are skipped.
+    fn fetch_mosaic_scans(&mut self, ctx: &egui::Context) {
+        let position = self.state.playback_state.playback_position();
+        let (start, end) = self
+            .state
+            .playback_state
+            .selection_range()
+            .unwrap_or((position, position));
+        self.start_mosaic_fetch(ctx, start, end);
+    }
+
+    fn start_mosaic_fetch(&mut self, ctx: &egui::Context, start: f64, end: f64) {
+        let mosaic = &mut self.state.mosaic;
+        if mosaic.site_ids.is_empty() {
+            return;
+        }
+        mosaic.start_fetch(mosaic.site_ids.len());
+        self.acquisition.mosaic_channel.fetch(
+            ctx.clone(),
+            mosaic.site_ids.clone(),
+            start.floor() as i64,
+            end.ceil() as i64,
+            mosaic.max_age_secs(),
+            self.acquisition.facade().clone(),
+            self.acquisition.download_channel.stats(),
+        );
+        self.state.set_status(self.state.mosaic.summary());
+    }
+
+    /// In live mode, fetch the neighbours' latest volumes every few minutes
+    /// while the mosaic is shown.
+    fn refresh_live_mosaic(&mut self, ctx: &egui::Context) {
+        let mosaic = &self.state.mosaic;
+        if !mosaic.enabled || !self.state.live_mode_state.is_active() || mosaic.is_fetching() {
+            return;
+        }
+        let now = crate::platform::now_secs();
+        if mosaic.live_fetch_due(now) {
+            self.state.mosaic.last_live_fetch = Some(now);
+            self.start_mosaic_fetch(ctx, now, now);
+        }
+    }
+
+    /// Send downloaded neighbour volumes to the workers and count listing
+    /// or download failures.
+    fn handle_mosaic_events(&mut self) {
+        while let Some(event) = self.acquisition.mosaic_channel.try_recv() {
+            match event {
+                nexrad::neighbour_fetch::NeighbourEvent::Listed { site_id, result } => {
+                    if let Err(message) = &result {
+                        log::warn!("mosaic: listing {} failed: {}", site_id, message);
+                    }
+                    let result = result.map(|volumes| volumes.len());
+                    self.state.mosaic.record_listed(site_id, result);
+                }
+                nexrad::neighbour_fetch::NeighbourEvent::Downloaded {
+                    site_id,
+                    file_name,
+                    timestamp,
+                    fetch_latency_ms,
+                    data,
+                } => {
+                    self.render.ingest_mosaic(
+                        data,
+                        site_id,
+                        timestamp,
+                        file_name,
+                        fetch_latency_ms,
+                    );
+                }
+                nexrad::neighbour_fetch::NeighbourEvent::Cached { .. } => {
+                    self.state.mosaic.record_stored();
+                    self.render.clear_last_mosaic();
+                }
+                nexrad::neighbour_fetch::NeighbourEvent::Failed {
+                    file_name, message, ..
+                } => {
+                    log::warn!("mosaic: {} failed: {}", file_name, message);
+                    self.state.mosaic.record_failure(file_name, message);
+                }
+                nexrad::neighbour_fetch::NeighbourEvent::Scans { .. } => {}
+            }
+            self.state.set_status(self.state.mosaic.summary());
+        }
+    }
+
+    /// A neighbour volume is in IndexedDB: count it, refresh the timeline
+    /// (it may be the current site's) and composite again.
+    fn handle_mosaic_ingested_outcome(&mut self, result: nexrad::IngestResult) {
+        log::info!(
+            "mosaic: stored {} ({} sweeps, {:.0}ms)",
+            result.scan_key,
+            result.sweeps.len(),
+            result.total_ms,
+        );
+        self.state.mosaic.record_stored();
+        self.state.set_status(self.state.mosaic.summary());
+        self.render.clear_last_mosaic();
+        self.state.push_command(state::AppCommand::RefreshTimeline {
+            auto_position: false,
+        });
+        self.state.push_command(state::AppCommand::CheckEviction);
+    }
+
+    /// Pin a secondary site, list its cached scans and fetch its volume at
+    /// the playback position.
+    fn pin_site(&mut self, ctx: &egui::Context, site_id: String) {
+        let site_id = site_id.to_uppercase();
+        if !self
+            .state
+            .pinned_sites
+            .pin(&site_id, &self.state.viz_state.site_id)
+        {
+            return;
+        }
+        let position = self.state.playback_state.playback_position();
+        self.start_pinned_fetch(ctx, vec![site_id], position, position);
+    }
+
+    /// Unpin a secondary site. Later sites move down a slot, so every slot
+    /// is cleared and rendered again.
+    fn unpin_site(&mut self, site_id: &str) {
+        if self.state.pinned_sites.unpin(site_id).is_some() {
+            self.clear_pinned_slots();
+        }
+    }
+
+    /// Clear every pinned site's GPU slot and forget its last request.
+    fn clear_pinned_slots(&mut self) {
+        for slot in 0..state::MAX_PINNED_SITES {
+            if let Some(renderer) = self.gpu.pinned.get(slot) {
+                if let Ok(mut r) = renderer.lock() {
+                    r.clear_data();
+                }
+            }
+            self.render.clear_pinned_render(slot);
+        }
+    }
+
+    /// Fetch every pinned site's volumes over the selection, else at the
+    /// playback position.
+    fn fetch_pinned_scans(&mut self, ctx: &egui::Context) {
+        let position = self.state.playback_state.playback_position();
+        let (start, end) = self
+            .state
+            .playback_state
+            .selection_range()
+            .unwrap_or((position, position));
+        let site_ids = self
+            .state
+            .pinned_sites
+            .sites
+            .iter()
+            .map(|s| s.site_id.clone())
+            .collect();
+        self.start_pinned_fetch(ctx, site_ids, start, end);
+    }
+
+    fn start_pinned_fetch(
+        &mut self,
+        ctx: &egui::Context,
+        site_ids: Vec<String>,
+        start: f64,
+        end: f64,
+    ) {
+        for site_id in &site_ids {
+            if let Some(site) = self.state.pinned_sites.site_mut(site_id) {
+                site.listing = true;
+                site.error = None;
+            }
+        }
+        self.acquisition.pinned_channel.fetch(
+            ctx.clone(),
+            site_ids,
+            start.floor() as i64,
+            end.ceil() as i64,
+            state::MAX_PINNED_SCAN_AGE_SECS,
+            self.acquisition.facade().clone(),
+            self.acquisition.download_channel.stats(),
+        );
+    }
+
+    /// In live mode, fetch pinned sites' latest volumes every few minutes.
+    fn refresh_live_pinned_sites(&mut self, ctx: &egui::Context) {
+        let pinned = &self.state.pinned_sites;
+        if !self.state.live_mode_state.is_active() || pinned.is_fetching() {
+            return;
+        }
+        let now = crate::platform::now_secs();
+        if pinned.live_fetch_due(now) {
+            let site_ids = pinned.sites.iter().map(|s| s.site_id.clone()).collect();
+            self.state.pinned_sites.last_live_fetch = Some(now);
+            self.start_pinned_fetch(ctx, site_ids, now, now);
+        }
+    }
+
+    /// List the cached scans of pinned sites whose list is out of date.
+    fn reload_pinned_scans(&mut self, ctx: &egui::Context) {
+        for site in &mut self.state.pinned_sites.sites {
+            if site.stale && !site.loading {
+                site.stale = false;
+                site.loading = true;
+                self.acquisition.pinned_channel.list_cached(
+                    ctx.clone(),
+                    site.site_id.clone(),
+                    self.acquisition.facade().clone(),
+                );
+            }
+        }
+    }
+
+    /// Track pinned sites' volume fetches as acquisition operations, send
+    /// downloads to the workers, and take in cached scan lists.
+    fn handle_pinned_events(&mut self) {
+        use nexrad::neighbour_fetch::NeighbourEvent;
+
+        while let Some(event) = self.acquisition.pinned_channel.try_recv() {
+            match event {
+                NeighbourEvent::Listed { site_id, result } => {
+                    let Some(site) = self.state.pinned_sites.site_mut(&site_id) else {
+                        continue;
+                    };
+                    site.listing = false;
+                    let volumes = match result {
+                        Ok(volumes) => volumes,
+                        Err(message) => {
+                            log::warn!("pinned: listing {} failed: {}", site_id, message);
+                            site.error = Some(message);
+                            continue;
+                        }
+                    };
+                    site.pending += volumes.len();
+                    for (file_name, scan_start) in volumes {
+                        let id = self.state.acquisition.create_operation(
+                            state::OperationKind::ArchiveDownload {
+                                site_id: site_id.clone(),
+                                file_name: file_name.clone(),
+                                scan_start,
+                                scan_end: scan_start + FALLBACK_SCAN_DURATION_SECS,
+                            },
+                        );
+                        // Fetched beside the queue, so never left for its pump.
+                        self.state.acquisition.mark_active(id);
+                        self.state.pinned_sites.operations.insert(file_name, id);
+                    }
+                }
+                NeighbourEvent::Downloaded {
+                    site_id,
+                    file_name,
+                    timestamp,
+                    fetch_latency_ms,
+                    data,
+                } => {
+                    if let Some(id) = self.state.pinned_sites.operations.remove(&file_name) {
+                        self.state.acquisition.mark_completed(id, data.len() as u64);
+                    }
+                    self.render.ingest_pinned(
+                        data,
+                        site_id,
+                        timestamp,
+                        file_name,
+                        fetch_latency_ms,
+                    );
+                }
+                NeighbourEvent::Cached { site_id, file_name } => {
+                    if let Some(id) = self.state.pinned_sites.operations.remove(&file_name) {
+                        self.state.acquisition.mark_completed(id, 0);
+                    }
+                    if let Some(site) = self.state.pinned_sites.site_mut(&site_id) {
+                        site.pending = site.pending.saturating_sub(1);
+                    }
+                }
+                NeighbourEvent::Failed {
+                    site_id,
+                    file_name,
+                    message,
+                } => {
+                    log::warn!("pinned: {} failed: {}", file_name, message);
+                    if let Some(id) = self.state.pinned_sites.operations.remove(&file_name) {
+                        self.state
+                            .acquisition
+                            .mark_failed_unpaused(id, message.clone());
+                    }
+                    if let Some(site) = self.state.pinned_sites.site_mut(&site_id) {
+                        site.pending = site.pending.saturating_sub(1);
+                        site.error = Some(message);
+                    }
+                }
+                NeighbourEvent::Scans { site_id, result } => {
+                    let Some(site) = self.state.pinned_sites.site_mut(&site_id) else {
+                        continue;
+                    };
+                    site.loading = false;
+                    match result {
+                        Ok(entries) => site.set_scans(&entries),
+                        Err(message) => site.error = Some(message),
+                    }
+                }
+            }
+        }
+    }
+
+    /// A pinned site's volume is in IndexedDB: list its scans again so it
+    /// can be drawn.
+    fn handle_pinned_ingested_outcome(&mut self, result: nexrad::IngestResult) {
+        log::info!(
+            "pinned: stored {} ({} sweeps, {:.0}ms)",
+            result.scan_key,
+            result.sweeps.len(),
+            result.total_ms,
+        );
+        if let Some(site) = self
+            .state
+            .pinned_sites
+            .site_for_file(&result.context.file_name)
+        {
+            site.pending = site.pending.saturating_sub(1);
+            site.stale = true;
+        }
+        self.state.push_command(state::AppCommand::CheckEviction);
+    }
+
+    /// Forward dropped files to the import channel, then send any picked or
+    /// dropped batch to the workers.
+    fn handle_local_imports(&mut self, ctx: &egui::Context) {
+        let (palettes, dropped): (Vec<nexrad::LocalFile>, Vec<nexrad::LocalFile>) =
+            ctx.input(|i| {
+                i.raw
+                    .dropped_files
+                    .iter()
+                    .flat_map(nexrad::read_dropped)
+                    .partition(|f| f.name.to_ascii_lowercase().ends_with(".pal"))
+            });
+        if !palettes.is_empty() {
+            self.acquisition.palette_import_channel.submit(palettes);
+        }
+        if let Some(files) = self.acquisition.palette_import_channel.try_recv() {
+            self.import_palettes(files);
+        }
+
+        let channel = &self.acquisition.local_import_channel;
+        if !dropped.is_empty() {
+            channel.submit(dropped);
+        }
+        let Some(files) = channel.try_recv() else {
+            return;
+        };
+
+        let volumes = nexrad::assemble_volumes(files);
+        if volumes.is_empty() {
+            self.state.set_status("No files to import");
+            return;
+        }
+        self.state.import.start(volumes.len());
+        for volume in volumes {
+            log::info!(
+                "import: {} ({})",
+                volume.file_name,
+                state::format_bytes(volume.data.len() as u64)
+            );
+            self.render.import(volume.data, volume.file_name);
+        }
+        self.state.set_status(self.state.import.summary());
+    }
+
+    /// Store picked or dropped `.pal` files and switch to them. Files without
+    /// a recognised `Product:` line are taken as palettes for the displayed
+    /// product.
+    fn import_palettes(&mut self, files: Vec<nexrad::LocalFile>) {
+        let fallback = nexrad::palette::moment_of(self.state.viz_state.product.to_worker_string());
+        let mut imported = Vec::new();
+        for file in files {
+            let source = String::from_utf8_lossy(&file.data);
+            match self.state.palettes.import(&file.name, &source, fallback) {
+                Ok((name, moment)) => {
+                    log::info!("palette: imported {} for {}", name, moment);
+                    imported.push(name);
+                }
+                Err(e) => {
+                    log::warn!("palette: {}: {}", file.name, e);
+                    self.state
+                        .set_status(format!("Palette {} not imported: {}", file.name, e));
+                }
+            }
+        }
+        if !imported.is_empty() {
+            self.apply_palettes();
+            self.state
+                .set_status(format!("Imported palette {}", imported.join(", ")));
+        }
+    }
+
+    /// Install the palette choices and rebuild every color table built from
+    /// them. Mosaics pick them up on their next composite.
+    fn apply_palettes(&mut self) {
+        nexrad::palette::configure(&self.state.palettes);
+        if let Some(ref gl) = self.gpu.gl {
+            let renderers = self
+                .gpu
+                .gpu
+                .iter()
+                .chain(&self.gpu.panels)
+                .chain(&self.gpu.pinned);
+            for renderer in renderers {
+                if let Ok(mut r) = renderer.lock() {
+                    r.refresh_color_table(gl);
+                }
+            }
+        }
+        // The cross-section image is colored on the CPU; rebuild it.
+        self.state.cross_section.revision += 1;
+    }
+
+    /// An imported archive is in IndexedDB: count it and refresh the
+    /// timeline so it appears like any downloaded scan.
+    fn handle_imported_outcome(&mut self, result: nexrad::IngestResult) {
+        let Some(key) = data::ScanKey::from_storage_key(&result.scan_key) else {
+            log::warn!("import: unexpected scan key {}", result.scan_key);
+            return;
+        };
+        log::info!(
+            "import: {} stored as {} ({} sweeps, {:.0}ms)",
+            result.context.file_name,
+            result.scan_key,
+            result.sweeps.len(),
+            result.total_ms,
+        );
+        let end = result
+            .sweeps
+            .iter()
+            .map(|s| s.end)
+            .fold(key.scan_start.as_secs() as f64, f64::max);
+        self.state.import.record_success(&key.site.0, end);
+        self.state.push_command(state::AppCommand::RefreshTimeline {
+            auto_position: false,
+        });
+        self.state.push_command(state::AppCommand::CheckEviction);
+        self.finish_import_if_done();
+    }
+
+    /// Report import progress; once the batch is done, switch to the site
+    /// of the imported scans and move the playhead to the latest one.
+    fn finish_import_if_done(&mut self) {
+        self.state.set_status(self.state.import.summary());
+        if self.state.import.is_busy() {
+            return;
+        }
+        let (Some(site_id), Some(end)) = (
+            self.state.import.site_id.clone(),
+            self.state.import.latest_end,
+        ) else {
+            return;
+        };
+        if site_id != self.state.viz_state.site_id {
+            if let Some(site) = data::get_site(&site_id) {
+                self.state.viz_state.site_id = site.id.to_string();
+                self.state.viz_state.center_lat = site.lat;
+                self.state.viz_state.center_lon = site.lon;
+                self.state.viz_state.pan_offset = egui::Vec2::ZERO;
+                self.state.viz_state.camera.center_on(site.lat, site.lon);
+                self.state.push_command(state::AppCommand::RefreshTimeline {
+                    auto_position: false,
+                });
+            } else {
+                log::warn!("import: unknown site {}, staying on current site", site_id);
+                return;
+            }
+        }
+        self.state.playback_state.set_playback_position(end);
+        self.state.playback_state.center_view_on(end);
+    }
+
+    fn handle_chunk_ingested_outcome(&mut self, result: nexrad::ChunkIngestResult) {
+        let is_live = self.state.live_mode_state.is_active();
+        let source = "Realtime";
+
+        // Build enriched log with projection-derived chunk positioning.
+        let chunk_vol_index = result.context.chunk_index + 1; // 1-based for display
+        let elev_nums: Vec<u8> = result
+            .chunk_elev_spans
+            .iter()
+            .map(|&(e, _, _, _)| e)
+            .collect();
+        let total_azimuths: u32 = result
+            .chunk_elev_spans
+            .iter()
+            .map(|&(_, _, _, count)| count)
+            .sum();
+
+        // Azimuth angle range from the chunk's azimuth data
+        let az_range_str =
+            if let Some(&(_, first_az, last_az)) = result.chunk_elev_az_ranges.first() {
+                format!("{:.1}°–{:.1}°", first_az, last_az)
+            } else {
+                "n/a".to_string()
+            };
+
+        // Look up chunk-in-sweep and remaining from projection metadata.
+        // chunk_index is 0-based where 0 = Start chunk (sequence 1), so
+        // chunk_vol_index (= chunk_index + 1) already equals the 1-based sequence.
+        let sequence = chunk_vol_index as usize;
+        let (chunk_in_sweep_str, remaining_str) = self
+            .state
+            .live_mode_state
+            .chunk_projections
+            .as_ref()
+            .and_then(|projs| {
+                projs.iter().find(|c| c.sequence == sequence).map(|c| {
+                    let in_sweep = format!("{}/{}", c.chunk_index_in_sweep + 1, c.chunks_in_sweep);
+                    // Count remaining chunks in this sweep after this one
+                    let remaining_in_sweep =
+                        c.chunks_in_sweep.saturating_sub(c.chunk_index_in_sweep + 1);
+                    (in_sweep, format!("{}", remaining_in_sweep))
+                })
+            })
+            .unwrap_or_else(|| ("?/?".to_string(), "?".to_string()));
+
+        log::debug!(
+            "{}: chunk ingested scan={} vol_chunk={} sweep_chunk={} remaining_in_sweep={} \
+             elevs={:?} azimuths={} az_range={} \
+             elevs_completed={:?} sweeps_stored={} is_end={} vcp={:?} {:.1}ms",
+            source,
+            result.scan_key,
+            chunk_vol_index,
+            chunk_in_sweep_str,
+            remaining_str,
+            elev_nums,
+            total_azimuths,
+            az_range_str,
+            result.elevations_completed,
+            result.sweeps_stored,
+            result.is_end,
+            result.vcp.as_ref().map(|v| v.number),
+            result.total_ms,
+        );
+
+        // Update scan key and available elevations
+        self.render.set_scan_key(result.scan_key.clone());
+        let had_elevations = !self.render.available_elevations().is_empty();
+        self.render.add_elevations(&result.elevations_completed);
+
+        // Update displayed timestamp
+        self.state.viz_state.displayed_scan_timestamp = Some(result.context.timestamp_secs);
+
+        // Only update live_mode_state when actually in live mode
+        if is_live {
+            self.state.live_mode_state.current_scan_key = Some(result.scan_key.clone());
+
+            if !result.chunk_elev_spans.is_empty() {
+                self.state
+                    .live_mode_state
+                    .record_chunk_elev_spans(&result.chunk_elev_spans);
+            }
+
+            // Set the volume start time from the authoritative
+            // timestamp parsed directly from the NEXRAD message
+            // header (the first radial of the volume scan).
+            if let Some(header_time) = result.volume_header_time_secs {
+                self.state.live_mode_state.current_volume_start = Some(header_time);
+                // Retry the forecast snapshot in case the VCP pattern was
+                // already recorded before the volume-start timestamp arrived.
+                // `record_vcp` below also calls this, so the usual flow picks
+                // up regardless of which side arrives first.
+                self.state.live_mode_state.try_capture_forecast();
+            }
+            if !result.elevations_completed.is_empty() {
+                let vol_start_ts = self
+                    .state
+                    .live_mode_state
+                    .current_volume_start
+                    .unwrap_or(result.context.timestamp_secs as f64);
+                self.state
+                    .live_mode_state
+                    .record_elevations(&result.elevations_completed, vol_start_ts);
+            }
+            if let Some(ref vcp) = result.vcp {
+                self.state.live_mode_state.record_vcp(vcp);
+            }
+
+            self.state.live_mode_state.record_in_progress_elevation(
+                result.current_elevation,
+                result.current_elevation_radials,
+            );
+
+            // Record per-chunk azimuth ranges for the current elevation
+            if let Some(cur_elev) = result.current_elevation {
+                for &(elev, first_az, last_az) in &result.chunk_elev_az_ranges {
+                    if elev == cur_elev {
+                        let radial_count = result
+                            .chunk_elev_spans
+                            .iter()
+                            .find(|&&(e, _, _, _)| e == elev)
+                            .map(|&(_, _, _, c)| c)
+                            .unwrap_or(0);
+                        self.state.live_mode_state.current_elev_chunks.push((
+                            first_az,
+                            last_az,
+                            radial_count,
+                        ));
+                    }
+                }
+            }
+
+            if !result.sweeps.is_empty() {
+                self.state
+                    .live_mode_state
+                    .update_sweep_metas(result.sweeps.clone());
+            }
+
+            self.state
+                .live_mode_state
+                .record_last_radial(result.last_radial_azimuth, result.last_radial_time_secs);
+
+            // ── Log: sweep storage ────────────────────────────────────
+            if !result.elevations_completed.is_empty() {
+                for &completed_elev in &result.elevations_completed {
+                    if let Some(meta) = result
+                        .sweeps
+                        .iter()
+                        .find(|s| s.elevation_number == completed_elev)
+                    {
+                        log::debug!(
+                            "{}: sweep stored elev={} angle={:.1}° start_az={:.1}° \
+                             time={:.1}–{:.1}s dur={:.2}s products={} vol_chunk={}",
+                            source,
+                            completed_elev,
+                            meta.elevation,
+                            meta.start_azimuth,
+                            meta.start,
+                            meta.end,
+                            meta.end - meta.start,
+                            result.sweeps_stored,
+                            chunk_vol_index,
+                        );
+                    } else {
+                        log::debug!(
+                            "{}: sweep stored elev={} (no SweepMeta) products={} vol_chunk={}",
+                            source,
+                            completed_elev,
+                            result.sweeps_stored,
+                            chunk_vol_index,
+                        );
+                    }
+                }
+            }
+
+            // ── Log + dispatch: live partial-sweep render ─────────────
+            // Always render whatever elevation is currently being
+            // accumulated — the user expects to see live progress
+            // regardless of which elevation was previously displayed.
+            // Volume products only exist once the volume completes, and
+            // accumulations are built from stored scans.
+            if !result.is_end
+                && !self.state.viz_state.product.is_volume_product()
+                && !self.state.viz_state.product.is_qpe_product()
+            {
+                if let Some(target_elev) = result.current_elevation {
+                    let product = self.state.viz_state.product.to_worker_string().to_string();
+
+                    // Summarize what the accumulator holds for this elevation
+                    let accum_radials = result.current_elevation_radials.unwrap_or(0);
+                    let accum_chunks: usize = self
+                        .state
+                        .live_mode_state
+                        .chunk_elev_spans
+                        .iter()
+                        .filter(|&&(e, _, _, _)| e == target_elev)
+                        .count();
+                    let accum_az_range = self
+                        .state
+                        .live_mode_state
+                        .current_elev_chunks
+                        .iter()
+                        .fold((f32::MAX, f32::MIN), |(lo, hi), &(first_az, last_az, _)| {
+                            (lo.min(first_az), hi.max(last_az))
+                        });
+                    let az_str = if accum_az_range.0 < f32::MAX {
+                        format!("{:.1}°–{:.1}°", accum_az_range.0, accum_az_range.1)
+                    } else {
+                        "n/a".to_string()
+                    };
+
+                    log::debug!(
+                        "{}: render_live dispatched elev={} product={} accum_radials={} \
+                         accum_chunks={} accum_az={} vol_chunk={}",
+                        source,
+                        target_elev,
+                        product,
+                        accum_radials,
+                        accum_chunks,
+                        az_str,
+                        chunk_vol_index,
+                    );
+
+                    self.render.render_live(target_elev, product);
+                }
+            }
+        }
+
+        // Refresh timeline when new elevations are written to cache
+        if !result.elevations_completed.is_empty() {
+            log::debug!(
+                "{}: {} new elevation(s) cached, refreshing timeline (total available: {:?})",
+                source,
+                result.elevations_completed.len(),
+                self.render.available_elevations(),
+            );
+            self.state.push_command(state::AppCommand::RefreshTimeline {
+                auto_position: !is_live,
+            });
+
+            if is_live {
+                self.state.status_message = format!(
+                    "Live: {} elevation(s) cached",
+                    self.render.available_elevations().len()
+                );
+            }
+        }
+
+        if result.is_end {
+            if is_live {
+                if let (Some(ref renderer), Some(ref gl)) = (&self.gpu.gpu, &self.gpu.gl) {
+                    if let Ok(mut r) = renderer.lock() {
+                        r.promote_current_to_previous(gl);
+                    }
+                }
+                let now = crate::platform::now_secs();
+                self.state.live_mode_state.handle_volume_complete(now);
+                self.state.status_message = format!(
+                    "Live: volume complete ({} elevations)",
+                    self.render.available_elevations().len()
+                );
+            } else {
+                let now = crate::platform::now_secs();
+                self.state.playback_state.set_playback_position(now);
+                self.state.playback_state.center_view_on(now);
+            }
+
+            log::debug!(
+                "{}: volume complete — {} elevations, triggering render",
+                source,
+                self.render.available_elevations().len()
+            );
+            self.state.push_command(state::AppCommand::RefreshTimeline {
+                auto_position: !is_live,
+            });
+            self.state.push_command(state::AppCommand::CheckEviction);
+            self.state.session_stats.pipeline.mark_processing_done();
+
+            self.state.viz_state.displayed_sweep_elevation_number = None;
+            self.render.force_fresh_render();
+            if !is_live {
+                self.request_worker_render();
+                if self.state.viz_state.volume_3d_enabled {
+                    self.request_worker_render_volume();
+                }
+            }
+        } else if !had_elevations && !self.render.available_elevations().is_empty() {
+            log::debug!(
+                "{}: first elevation available, triggering initial render",
+                source
+            );
+            self.render.force_fresh_render();
+            if !is_live {
+                self.request_worker_render();
+                if self.state.viz_state.volume_3d_enabled {
+                    self.request_worker_render_volume();
+                }
+            }
+        }
+    }
+
+    fn handle_decoded_outcome(&mut self, result: nexrad::DecodeResult) {
+        // Pinned sites' sweeps only go to their slots; they are another
+        // site's data, so none of the main view's bookkeeping applies.
+        let site_prefix = format!("{}|", self.state.viz_state.site_id);
+        let for_pinned = !self
+            .render
+            .pinned_awaiting(
+                &result.context.scan_key,
+                result.context.elevation_number,
+                &result.product,
+            )
+            .is_empty();
+        if for_pinned && !result.context.scan_key.starts_with(&site_prefix) {
+            self.upload_pinned_result(&result);
+            return;
+        }
+
+        // Processing complete → transition to rendering.
+        self.state.session_stats.pipeline.mark_processing_done();
+        self.state.session_stats.pipeline.rendering = true;
+        self.state.viz_state.volume_product_missing = false;
+
+        log::debug!(
+            "Decode complete: {}x{} (az x gates), {} radials, product={}, {:.0}ms",
+            result.azimuth_count,
+            result.gate_count,
+            result.radial_count,
+            result.product,
+            result.total_ms,
+        );
+
+        self.state.session_stats.record_render_time(result.total_ms);
+
+        // Cache decoded data for stateless sweep animation
+        let result_sweep_id = sweep_cache_key(
+            &result.context.scan_key,
+            result.context.elevation_number,
+            &result.product,
+        );
+        self.playback_manager.cache_sweep(
+            result_sweep_id.clone(),
+            CachedSweepData {
+                gate_values: result.gate_values.clone(),
+                azimuths: result.azimuths.clone(),
+                azimuth_count: result.azimuth_count,
+                gate_count: result.gate_count,
+                first_gate_range_km: result.first_gate_range_km,
+                gate_interval_km: result.gate_interval_km,
+                max_range_km: result.max_range_km,
+                offset: result.offset,
+                scale: result.scale,
+                azimuth_spacing_deg: result.azimuth_spacing_deg,
+                radial_times: result.radial_times.clone(),
+                sweep_start_secs: result.sweep_start_secs,
+                sweep_end_secs: result.sweep_end_secs,
+                product: result.product.clone(),
+            },
+        );
+
+        // Upload decoded data to GPU renderer — but only if this
+        // result is for the currently displayed scan. Background
+        // prev-sweep decodes are cached but not uploaded here;
+        // sync_prev_sweep_texture picks them up next frame.
+        // Only upload to the primary GPU texture if this result
+        // matches what advance_playback intended: same scan key AND
+        // same elevation number. Without the elevation check, SAILS
+        // VCPs (duplicate 0.5° at elev 1 and 2) cause oscillation
+        // where prefetch/sync requests fight the main render path.
+        // The product check keeps split-view panel decodes of the same
+        // sweep out of the main texture.
+        let is_current_scan = self
+            .render
+            .scan_key()
+            .is_some_and(|k| k == result.context.scan_key)
+            && self
+                .state
+                .viz_state
+                .displayed_sweep_elevation_number
+                .is_some_and(|e| e == result.context.elevation_number)
+            && result.product == self.state.viz_state.product.to_worker_string();
+        if self.state.effective_sweep_animation() && !is_current_scan {
+            log::debug!("[sweep-anim] cached bg decode: {}", result_sweep_id);
+            // Clear pending tracker so sync_prev_sweep_texture can load from cache
+            if self.playback_manager.pending_prev_sweep_key() == Some(&result_sweep_id) {
+                self.playback_manager.set_pending_prev_sweep_key(None);
+            }
+        }
+        let t_gpu = web_time::Instant::now();
+        // In live mode, LiveDecoded drives the GPU — skip Decoded
+        // uploads so completed-elevation IDB renders don't overwrite
+        // the current partial sweep.
+        let skip_gpu_upload = self.state.live_mode_state.is_active();
+        // An observed upload replaces any interpolated frame on screen.
+        self.playback_manager
+            .sweep_decoded(&result_sweep_id, is_current_scan && !skip_gpu_upload);
+        if is_current_scan && !skip_gpu_upload {
+            if let (Some(ref renderer), Some(ref gl)) = (&self.gpu.gpu, &self.gpu.gl) {
+                if let Ok(mut r) = renderer.lock() {
+                    r.update_data(
+                        gl,
+                        &result.azimuths,
+                        &result.gate_values,
+                        result.azimuth_count,
+                        result.gate_count,
+                        result.first_gate_range_km,
+                        result.gate_interval_km,
+                        result.max_range_km,
+                        result.offset,
+                        result.scale,
+                        result.azimuth_spacing_deg,
+                        &result.radial_times,
+                    );
+                    r.set_current_sweep_id(Some(result_sweep_id.clone()));
+                    r.set_current_nyquist_velocity(result.aliased_nyquist());
+                    r.update_color_table(gl, &result.product);
+
+                    // Run storm cell detection if enabled
+                    if self.state.viz_state.storm_cells_visible {
+                        let cells = r.detect_storm_cells(
+                            self.state.viz_state.center_lat,
+                            self.state.viz_state.center_lon,
+                            self.state.viz_state.storm_cell_threshold_dbz,
+                        );
+                        self.state.viz_state.apply_storm_cell_detection(
+                            cells,
+                            Some(result.context.elevation_number),
+                            Some(result.sweep_start_secs),
+                        );
+                    }
+                }
+            }
+            // Rotation re-runs next frame, on this sweep's dealiased
+            // counterpart when there is one.
+            self.state.viz_state.detected_rotations = None;
+        }
+        if self.requested_rotation_sweep.as_deref() == Some(result_sweep_id.as_str()) {
+            self.requested_rotation_sweep = None;
+            self.state.viz_state.detected_rotations = None;
+        }
+        self.upload_panel_result(&result);
+        let gpu_upload_ms = t_gpu.elapsed().as_secs_f64() * 1000.0;
+
+        // Store detailed render timing for the detail modal.
+        self.state.session_stats.last_render_detail = Some(crate::state::RenderTimingDetail {
+            fetch_ms: result.fetch_ms,
+            deser_ms: result.deser_ms,
+            marshal_ms: result.marshal_ms,
+            gpu_upload_ms,
+        });
+
+        // GPU upload complete.
+        self.state.session_stats.pipeline.mark_render_done();
+
+        // Remove this scan from in-flight ghost tracking.
+        if let Some(displayed_ts) = self.state.viz_state.displayed_scan_timestamp {
+            self.state
+                .download_progress
+                .in_flight_scans
+                .retain(|&(start, _)| start != displayed_ts);
+        }
+        // If no more in-flight or pending, fully clear progress.
+        if self.state.download_progress.in_flight_scans.is_empty()
+            && self.state.download_progress.pending_scans.is_empty()
+            && !self.state.download_selection_in_progress
+        {
+            self.state.download_progress.clear();
+        }
+
+        // Refine canvas overlay with precise decoded data
+        if result.sweep_start_secs > 0.0 {
+            self.update_overlay_from_sweep(
+                result.sweep_start_secs,
+                result.sweep_end_secs,
+                result.mean_elevation,
+            );
+        }
+    }
+
+    /// Upload a decoded sweep to every pinned site slot that requested it.
+    fn upload_pinned_result(&self, result: &nexrad::DecodeResult) {
+        let Some(ref gl) = self.gpu.gl else {
+            return;
+        };
+        let slots = self.render.pinned_awaiting(
+            &result.context.scan_key,
+            result.context.elevation_number,
+            &result.product,
+        );
+        for renderer in slots.iter().filter_map(|&i| self.gpu.pinned.get(i)) {
+            if let Ok(mut r) = renderer.lock() {
+                r.update_data(
+                    gl,
+                    &result.azimuths,
+                    &result.gate_values,
+                    result.azimuth_count,
+                    result.gate_count,
+                    result.first_gate_range_km,
+                    result.gate_interval_km,
+                    result.max_range_km,
+                    result.offset,
+                    result.scale,
+                    result.azimuth_spacing_deg,
+                    &result.radial_times,
+                );
+                r.update_color_table(gl, &result.product);
+            }
+        }
+    }
+
+    /// Upload a decoded sweep to every split-view panel that requested it.
+    fn upload_panel_result(&self, result: &nexrad::DecodeResult) {
+        let Some(ref gl) = self.gpu.gl else {
+            return;
+        };
+        let panels = self.render.panels_awaiting(
+            &result.context.scan_key,
+            result.context.elevation_number,
+            &result.product,
+        );
+        for renderer in panels.iter().filter_map(|&i| self.gpu.panels.get(i)) {
+            if let Ok(mut r) = renderer.lock() {
+                r.update_data(
+                    gl,
+                    &result.azimuths,
+                    &result.gate_values,
+                    result.azimuth_count,
+                    result.gate_count,
+                    result.first_gate_range_km,
+                    result.gate_interval_km,
+                    result.max_range_km,
+                    result.offset,
+                    result.scale,
+                    result.azimuth_spacing_deg,
+                    &result.radial_times,
+                );
+                r.update_color_table(gl, &result.product);
+            }
+        }
+    }
+
+    fn handle_qpe_decoded_outcome(&mut self, result: nexrad::DecodeResult) {
+        self.state.session_stats.pipeline.mark_processing_done();
+        self.state.session_stats.pipeline.rendering = true;
+
+        log::debug!(
+            "Accumulation complete: {}x{} (az x gates), product={}, {:.0}ms",
+            result.azimuth_count,
+            result.gate_count,
+            result.product,
+            result.total_ms,
+        );
+        self.state.session_stats.record_render_time(result.total_ms);
+
+        // The user may have switched products while the worker was busy.
+        let t_gpu = web_time::Instant::now();
+        if result.product == self.state.viz_state.product.to_worker_string() {
+            if let (Some(ref renderer), Some(ref gl)) = (&self.gpu.gpu, &self.gpu.gl) {
+                if let Ok(mut r) = renderer.lock() {
+                    r.update_data(
+                        gl,
+                        &result.azimuths,
+                        &result.gate_values,
+                        result.azimuth_count,
+                        result.gate_count,
+                        result.first_gate_range_km,
+                        result.gate_interval_km,
+                        result.max_range_km,
+                        result.offset,
+                        result.scale,
+                        result.azimuth_spacing_deg,
+                        &result.radial_times,
+                    );
+                    r.set_current_sweep_id(Some(result.product.clone()));
+                    r.set_current_nyquist_velocity(None);
+                    r.update_color_table(gl, &result.product);
+                }
+            }
+            self.state.viz_state.detected_rotations = None;
+        }
+        let gpu_upload_ms = t_gpu.elapsed().as_secs_f64() * 1000.0;
+
+        self.state.session_stats.last_render_detail = Some(crate::state::RenderTimingDetail {
+            fetch_ms: result.fetch_ms,
+            deser_ms: result.deser_ms,
+            marshal_ms: result.marshal_ms,
+            gpu_upload_ms,
+        });
+        self.state.session_stats.pipeline.mark_render_done();
+    }
+
+    fn handle_lowest_available_decoded_outcome(&mut self, result: nexrad::DecodeResult) {
+        self.state.session_stats.pipeline.mark_processing_done();
+        self.state.session_stats.pipeline.rendering = true;
+
+        log::debug!(
+            "Lowest-available composite: {}x{} (az x gates), product={}, {:.0}ms",
+            result.azimuth_count,
+            result.gate_count,
+            result.product,
+            result.total_ms,
+        );
+        self.state.session_stats.record_render_time(result.total_ms);
+
+        // The user may have switched products or modes while the worker was busy.
+        let t_gpu = web_time::Instant::now();
+        let current = result.product == self.state.viz_state.product.to_worker_string()
+            && self
+                .state
+                .viz_state
+                .elevation_selection
+                .is_lowest_available();
+        if current {
+            if let (Some(ref renderer), Some(ref gl)) = (&self.gpu.gpu, &self.gpu.gl) {
+                if let Ok(mut r) = renderer.lock() {
+                    r.update_data(
+                        gl,
+                        &result.azimuths,
+                        &result.gate_values,
+                        result.azimuth_count,
+                        result.gate_count,
+                        result.first_gate_range_km,
+                        result.gate_interval_km,
+                        result.max_range_km,
+                        result.offset,
+                        result.scale,
+                        result.azimuth_spacing_deg,
+                        &result.radial_times,
+                    );
+                    r.set_gate_times(&result.gate_times);
+                    r.set_current_sweep_id(Some(format!("lowest|{}", result.product)));
+                    r.set_current_nyquist_velocity(result.aliased_nyquist());
+                    r.update_color_table(gl, &result.product);
+                }
+            }
+            self.update_overlay_from_sweep(
+                result.sweep_start_secs,
+                result.sweep_end_secs,
+                result.mean_elevation,
+            );
+            self.state.viz_state.detected_rotations = None;
+        }
+        let gpu_upload_ms = t_gpu.elapsed().as_secs_f64() * 1000.0;
+
+        self.state.session_stats.last_render_detail = Some(crate::state::RenderTimingDetail {
+            fetch_ms: result.fetch_ms,
+            deser_ms: result.deser_ms,
+            marshal_ms: result.marshal_ms,
+            gpu_upload_ms,
+        });
+        self.state.session_stats.pipeline.mark_render_done();
+    }
+
+    fn handle_live_decoded_outcome(&mut self, result: nexrad::DecodeResult) {
+        log::debug!(
+            "Live decode: {}x{}, {} radials, {}, {:.0}ms",
+            result.azimuth_count,
+            result.gate_count,
+            result.radial_count,
+            result.product,
+            result.total_ms,
+        );
+
+        if let (Some(ref renderer), Some(ref gl)) = (&self.gpu.gpu, &self.gpu.gl) {
+            if let Ok(mut r) = renderer.lock() {
+                // Build a live sweep ID so we can detect elevation transitions
+                let live_elev = result.context.elevation_number;
+                let live_sweep_id = format!("live|{}", live_elev);
+
+                // If the current texture has data from a different sweep
+                // (complete or different live elevation), promote it to previous
+                // so it becomes the background for compositing partial data.
+                let should_promote = r.current_sweep_id().is_some_and(|id| id != live_sweep_id);
+                if should_promote {
+                    // Capture the current sweep's metadata before promoting it to
+                    // "previous" — this drives the overlay info panel and donut labels.
+                    let prev_elev_deg =
+                        self.state.viz_state.rendered_sweep_end_secs.and_then(|_| {
+                            self.state
+                                .viz_state
+                                .elevation
+                                .trim_end_matches('\u{00B0}')
+                                .parse::<f32>()
+                                .ok()
+                        });
+                    let prev_elev_num = self.state.viz_state.displayed_sweep_elevation_number;
+                    if let (Some(start), Some(end), Some(elev)) = (
+                        self.state.viz_state.rendered_sweep_start_secs,
+                        self.state.viz_state.rendered_sweep_end_secs,
+                        prev_elev_deg,
+                    ) {
+                        self.state.viz_state.prev_sweep_overlay = Some((elev, start, end));
+                        self.state.viz_state.prev_sweep_elevation_number = prev_elev_num;
+                    }
+
+                    r.promote_current_to_previous(gl);
+                }
+
+                r.update_data(
+                    gl,
+                    &result.azimuths,
+                    &result.gate_values,
+                    result.azimuth_count,
+                    result.gate_count,
+                    result.first_gate_range_km,
+                    result.gate_interval_km,
+                    result.max_range_km,
+                    result.offset,
+                    result.scale,
+                    result.azimuth_spacing_deg,
+                    &result.radial_times,
+                );
+                r.set_current_sweep_id(Some(live_sweep_id));
+                r.set_current_nyquist_velocity(result.aliased_nyquist());
+                r.update_color_table(gl, &result.product);
+
+                // Re-run storm cell detection on the freshly-uploaded live
+                // sweep so the overlay tracks the incoming chunks rather
+                // than freezing until the user toggles the feature.
+                if self.state.viz_state.storm_cells_visible {
+                    let cells = r.detect_storm_cells(
+                        self.state.viz_state.center_lat,
+                        self.state.viz_state.center_lon,
+                        self.state.viz_state.storm_cell_threshold_dbz,
+                    );
+                    self.state.viz_state.apply_storm_cell_detection(
+                        cells,
+                        Some(result.context.elevation_number),
+                        Some(result.sweep_start_secs),
+                    );
+                }
+            }
+            self.state.viz_state.detected_rotations = None;
+        }
+
+        // Update overlay staleness so the age counter reflects
+        // the most recently received live data.
+        if result.sweep_end_secs > 0.0 {
+            self.update_overlay_from_sweep(
+                result.sweep_start_secs,
+                result.sweep_end_secs,
+                result.mean_elevation,
+            );
+        }
+
+        // Store the chronological azimuth range for sweep compositing.
+        // Must use chronological first/last (from radial timestamps), NOT
+        // sorted min/max. Once a sweep wraps past 0°, the sorted range
+        // spans ~360° and the shader thinks the entire circle has current
+        // data, hiding the previous sweep.
+        if !result.azimuths.is_empty() {
+            // Chronological first = sweep start azimuth (set once per sweep).
+            // Chronological last = most recent radial's azimuth from the live state.
+            if self.state.live_mode_state.sweep_start_azimuth.is_none() {
+                // First live decode for this sweep: use the earliest radial
+                // by collection time as the sweep start.
+                let first_az = if !result.radial_times.is_empty() {
+                    let min_time_idx = result
+                        .radial_times
+                        .iter()
+                        .enumerate()
+                        .min_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap())
+                        .map(|(i, _)| i)
+                        .unwrap_or(0);
+                    result.azimuths[min_time_idx]
+                } else {
+                    result.azimuths[0]
+                };
+                self.state.live_mode_state.sweep_start_azimuth = Some(first_az);
+            }
+
+            // The trailing edge of received data: latest radial by collection time.
+            let last_az = if !result.radial_times.is_empty() {
+                let max_time_idx = result
+                    .radial_times
+                    .iter()
+                    .enumerate()
+                    .max_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap())
+                    .map(|(i, _)| i)
+                    .unwrap_or(result.azimuths.len() - 1);
+                result.azimuths[max_time_idx]
+            } else {
+                *result.azimuths.last().unwrap()
+            };
+
+            let first_az = self
+                .state
+                .live_mode_state
+                .sweep_start_azimuth
+                .unwrap_or(0.0);
+            log::debug!(
+                "Live azimuth range: chrono_first={:.1} chrono_last={:.1} count={}",
+                first_az,
+                last_az,
+                result.azimuths.len(),
+            );
+            self.state.live_mode_state.live_data_azimuth_range = Some((first_az, last_az));
+        }
+    }
+
+    fn handle_volume_decoded_outcome(&mut self, volume_data: nexrad::VolumeData) {
+        log::debug!(
+            "Volume decode complete: {} sweeps, {:.1}KB, product={}, {:.0}ms",
+            volume_data.sweeps.len(),
+            volume_data.buffer.len() as f64 / 1024.0,
+            volume_data.product,
+            volume_data.total_ms,
+        );
+
+        // Upload to volume ray renderer
+        if let (Some(ref renderer), Some(ref gl)) = (&self.gpu.volume_ray, &self.gpu.gl) {
+            if let Ok(mut r) = renderer.lock() {
+                r.update_volume(
+                    gl,
+                    &volume_data.buffer,
+                    volume_data.word_size,
+                    &volume_data.sweeps,
+                    self.state.viz_state.center_lat,
+                    self.state.viz_state.center_lon,
+                );
+            }
+        }
+
+        // Update LUT for the volume product
+        if let (Some(ref renderer), Some(ref gl)) = (&self.gpu.gpu, &self.gpu.gl) {
+            if let Ok(mut r) = renderer.lock() {
+                r.update_color_table(gl, &volume_data.product);
+            }
+        }
+    }
+
+    fn handle_worker_error_outcome(
+        &mut self,
+        id: u64,
+        message: String,
+        failed_scan_timestamp_secs: Option<i64>,
+        failed_render: Option<nexrad::RenderContext>,
+    ) {
+        log::warn!("Worker error (request {}): {}", id, message);
+
+        // A pinned site's failed render only blanks its slot; the dedup
+        // entry is kept so the request isn't retried every frame.
+        let pinned = failed_render
+            .as_ref()
+            .map(|ctx| {
+                self.render
+                    .pinned_awaiting(&ctx.scan_key, ctx.elevation_number, &ctx.product)
+            })
+            .unwrap_or_default();
+        if !pinned.is_empty() {
+            for renderer in pinned.iter().filter_map(|&i| self.gpu.pinned.get(i)) {
+                if let Ok(mut r) = renderer.lock() {
+                    r.clear_data();
+                }
+            }
+            return;
+        }
+
+        self.state.status_message = format!("Worker error: {}", message);
+
+        // When the worker reports that the requested (elevation, product) has
+        // no pre-computed sweep, clear the stale canvas so the user sees what
+        // the timeline already knows — nothing matches their current filter.
+        // Narrowed to this specific message so transient errors (worker
+        // disconnect, IDB failure) keep the last-good view instead of blanking.
+        // A split-view panel's request only blanks that panel; its dedup entry
+        // is kept so the same doomed request isn't retried every frame.
+        if message.starts_with("No pre-computed sweep") {
+            let panels = failed_render
+                .as_ref()
+                .map(|ctx| {
+                    self.render
+                        .panels_awaiting(&ctx.scan_key, ctx.elevation_number, &ctx.product)
+                })
+                .unwrap_or_default();
+            for renderer in panels.iter().filter_map(|&i| self.gpu.panels.get(i)) {
+                if let Ok(mut r) = renderer.lock() {
+                    r.clear_data();
+                }
+            }
+            let for_main = failed_render.as_ref().is_none_or(|ctx| {
+                self.render
+                    .is_last_render(&ctx.scan_key, ctx.elevation_number, &ctx.product)
+            });
+            if panels.is_empty() || for_main {
+                // Index entries written before sweeps recorded their
+                // products can't be gated up front, so a scan cached before
+                // volume products existed lands here.
+                if failed_render
+                    .as_ref()
+                    .is_some_and(|ctx| nexrad::derived::is_volume_product(&ctx.product))
+                {
+                    self.state.viz_state.volume_product_missing = true;
+                }
+                self.clear_display_no_sweep();
+            }
+        }
+
+        // Clean up the "processing" timeline ghost for the failed scan.
+        // Prefer the scan attributed to the failing worker request so the
+        // right ghost is removed even after the user scrolled away and
+        // displayed_scan_timestamp now points elsewhere.
+        let cleanup_ts =
+            failed_scan_timestamp_secs.or(self.state.viz_state.displayed_scan_timestamp);
+        if let Some(ts) = cleanup_ts {
+            self.state
+                .download_progress
+                .in_flight_scans
+                .retain(|&(start, _)| start != ts);
+        }
+        self.state.session_stats.pipeline.processing = false;
+        self.state.session_stats.pipeline.rendering = false;
+        if self.state.download_progress.in_flight_scans.is_empty()
+            && self.state.download_progress.pending_scans.is_empty()
+            && !self.state.download_selection_in_progress
+        {
+            self.state.download_progress.clear();
+        }
+    }
+
+    fn handle_download_outcome(&mut self, result: nexrad::DownloadResult) {
+        // Extract scan and timing info from result
+        let (scan_opt, is_cache_hit) = match &result {
+            nexrad::DownloadResult::Success {
+                scan,
+                fetch_latency_ms,
+                decode_latency_ms,
+            } => {
+                self.state
+                    .session_stats
+                    .record_fetch_latency(*fetch_latency_ms);
+                self.state
+                    .session_stats
+                    .record_processing_time(*decode_latency_ms);
+                (Some(scan), false)
+            }
+            nexrad::DownloadResult::CacheHit(scan) => (Some(scan), true),
+            _ => (None, false),
+        };
+
+        if let Some(scan) = scan_opt {
+            let fetch_latency = match &result {
+                nexrad::DownloadResult::Success {
+                    fetch_latency_ms, ..
+                } => *fetch_latency_ms,
+                _ => 0.0,
+            };
+
+            // Move this scan's boundary to in-flight tracking (ghost stays
+            // visible until processing completes in the Decoded handler).
+            let scan_ts = scan.key.scan_start.as_secs();
+            let scan_end = self
+                .acquisition
+                .download_queue
+                .find_by_scan_start(scan_ts)
+                .map(|item| item.scan_end)
+                .unwrap_or(scan_ts + FALLBACK_SCAN_DURATION_SECS);
+            self.state
+                .download_progress
+                .in_flight_scans
+                .push((scan_ts, scan_end));
+
+            // Track which scan is being processed so error cleanup
+            // can remove the correct ghost.
+            self.state.viz_state.displayed_scan_timestamp = Some(scan_ts);
+
+            if is_cache_hit {
+                self.state.status_message = format!("Loaded from cache: {}", scan.file_name);
+
+                // Cache hit: skip ingest, go straight to decode.
+                // Ghost stays until timeline refresh shows the real scan.
+                self.state.download_progress.phase = crate::state::DownloadPhase::Decoding;
+
+                // Cache hit: records already in IDB. Send render request directly.
+                self.render.set_scan_key(scan.key.to_storage_key());
+                self.state.viz_state.displayed_sweep_elevation_number = None;
+
+                // Populate elevation numbers from timeline metadata if available
+                if let Some(tl_scan) = self
+                    .state
+                    .radar_timeline
+                    .find_recent_scan(scan_ts as f64, 1.0)
+                {
+                    let mut elev_nums: Vec<u8> =
+                        tl_scan.sweeps.iter().map(|s| s.elevation_number).collect();
+                    elev_nums.sort_unstable();
+                    elev_nums.dedup();
+                    if !elev_nums.is_empty() {
+                        self.render.set_elevations(elev_nums);
+                    }
+                }
+
+                self.render.force_fresh_render();
+                self.request_worker_render();
+                if self.state.viz_state.volume_3d_enabled {
+                    self.request_worker_render_volume();
+                }
+            } else {
+                self.state.status_message =
+                    format!("Downloaded: {} ({} bytes)", scan.file_name, scan.data.len());
+
+                // Transition to ingesting phase
+                self.state.download_progress.phase = crate::state::DownloadPhase::Ingesting;
+
+                // Fresh download: send raw bytes to worker for ingest.
+                // Worker splits records, probes elevations, stores in IDB,
+                // then returns metadata. We render on the Ingested callback.
+                self.state.session_stats.pipeline.processing = true;
+                self.render.ingest(
+                    scan.data.clone(),
+                    scan.key.site.0.clone(),
+                    scan.key.scan_start.as_secs(),
+                    scan.file_name.clone(),
+                    fetch_latency,
+                );
+            }
+
+            self.acquisition.current_scan = Some(scan.clone());
+
+            // Refresh timeline to show the new/loaded scan
+            self.state.push_command(state::AppCommand::RefreshTimeline {
+                auto_position: false,
+            });
+        }
+
+        // Mark acquisition operation completed on success
+        if let Some(scan) = scan_opt {
+            let scan_ts = scan.key.scan_start.as_secs();
+            if let Some(op_id) = self.acquisition.download_queue.take_operation_id(scan_ts) {
+                self.state
+                    .acquisition
+                    .mark_completed(op_id, scan.data.len() as u64);
+            }
+        }
+
+        if let nexrad::DownloadResult::Error {
+            message,
+            scan_start,
+        } = &result
+        {
+            self.state.status_message = format!("Download failed: {}", message);
+            log::error!("Download failed: {}", message);
+
+            // Mark this scan's acquisition operation as failed
+            if let Some(op_id) = self
+                .acquisition
+                .download_queue
+                .take_operation_id(*scan_start)
+            {
+                self.state.acquisition.mark_failed(op_id, message.clone());
+            }
+
+            // Transition the failed queue item out of Active so the concurrency
+            // slot frees up for the next pump.
+            self.acquisition
+                .download_queue
+                .mark_active_done(*scan_start);
+            self.state
+                .download_progress
+                .active_scans
+                .retain(|&(s, _)| s != *scan_start);
+
+            // Clear download progress on error if no more work remains
+            if !self.acquisition.download_queue.has_work() {
+                self.acquisition.download_queue.clear();
+                self.state.download_progress.clear();
+            }
+        }
+    }
+
+    fn handle_listing_outcome(&mut self, result: nexrad::ListingResult) {
+        match result {
+            nexrad::ListingResult::Success {
+                site_id,
+                date,
+                listing,
+            } => {
+                log::debug!(
+                    "Archive listing received: {} files for {}/{}",
+                    listing.files.len(),
+                    site_id,
+                    date
+                );
+                self.acquisition
+                    .archive_index
+                    .insert(&site_id, date, listing);
+
+                // Rebuild shadow scan boundaries for the timeline
+                if site_id == self.state.viz_state.site_id {
+                    self.state.shadow_scan_boundaries = self
+                        .acquisition
+                        .archive_index
+                        .all_boundaries_for_site(&site_id);
+                }
+
+                // Resume pending download now that the listing is available
+                if let Some(pending) = &self.acquisition.pending_download {
+                    if pending.is_position {
+                        self.state
+                            .push_command(state::AppCommand::DownloadAtPosition);
+                    } else {
+                        self.state
+                            .push_command(state::AppCommand::DownloadSelection);
+                    }
+                }
+            }
+            nexrad::ListingResult::Error(msg) => {
+                log::error!("Listing request failed: {}", msg);
+                // Abandon pending download on listing failure
+                if self.acquisition.pending_download.is_some() {
+                    self.acquisition.pending_download = None;
+                    self.state.status_message =
+                        format!("Download cancelled: listing fetch failed ({})", msg);
+                }
+            }
+        }
+    }
+
+    /// Kick off or continue selection/position downloads.
+    fn pump_download_queue(
+        &mut self,
+        ctx: &egui::Context,
+        do_download_selection: bool,
+        do_download_at_position: bool,
+        do_pump_queue: bool,
+    ) {
+        {
+            let download_type = if do_download_at_position {
+                Some(true)
+            } else if do_download_selection {
+                Some(false)
+            } else {
+                None // Just pumping existing queue, or nothing to do
+            };
+            let queue_has_work = self.acquisition.download_queue.has_work();
+            if do_download_selection || do_download_at_position || do_pump_queue || queue_has_work {
+                self.process_selection_download(ctx, download_type);
+            }
+        }
+    }
+
+    /// Drain the realtime channel and manage live-mode lifecycle.
+    fn handle_streaming_results(&mut self, ctx: &egui::Context) {
+        for event in self.streaming.poll() {
+            match event {
+                nexrad::StreamingEvent::Realtime(result) => {
+                    self.handle_realtime_result(result, ctx);
+                }
+            }
+        }
+
+        // Stop realtime channel if live mode was stopped by UI
+        if !self.state.live_mode_state.is_active() && self.streaming.is_realtime_active() {
+            log::debug!("Stopping realtime channel (live mode ended)");
+            self.streaming.stop_realtime();
+        }
+
+        // Update live mode countdown from realtime channel
+        if self.state.live_mode_state.is_active() {
+            if let Some(duration) = self.streaming.time_until_next() {
+                let now = crate::platform::now_secs();
+                self.state.live_mode_state.next_chunk_expected_at =
+                    Some(now + duration.as_secs_f64());
+            }
+        }
+    }
+
+    /// Auto-load scans when scrubbing the timeline and prefetch upcoming sweeps.
+    fn advance_playback(&mut self) {
+        // Live mode drives rendering via ChunkIngested/LiveDecoded — skip playback-driven renders.
+        if self.state.live_mode_state.is_active() {
+            return;
+        }
+        // Rebuild macro frame list when dirty (elevation selection, bounds, or scan count changed)
+        if self.state.playback_state.playback_mode() == crate::state::PlaybackMode::Macro {
+            let mp = &self.state.playback_state.macro_playback;
+            let elev_sel = self.state.viz_state.elevation_selection.clone();
+            let bounds = self.state.playback_state.time_model.playback_bounds;
+            let scan_count = self.state.radar_timeline.scans.len();
+
+            let dirty = mp.cached_elevation_selection != elev_sel
+                || mp.cached_bounds != bounds
+                || mp.cached_scan_count != scan_count;
+
+            if dirty {
+                let frames = match &elev_sel {
+                    crate::state::ElevationSelection::Fixed {
+                        elevation_number, ..
+                    } => self
+                        .state
+                        .radar_timeline
+                        .matching_sweep_end_times_by_number(*elevation_number, bounds),
+                    crate::state::ElevationSelection::Latest
+                    | crate::state::ElevationSelection::LowestAvailable => {
+                        self.state.radar_timeline.all_sweep_end_times(bounds)
+                    }
+                };
+                self.state.playback_state.macro_playback.sweep_frames = frames;
+                self.state
+                    .playback_state
+                    .macro_playback
+                    .cached_elevation_selection = elev_sel;
+                self.state.playback_state.macro_playback.cached_bounds = bounds;
+                self.state.playback_state.macro_playback.cached_scan_count = scan_count;
+                self.state.playback_state.sync_macro_frame_index();
+            }
+
+            // Detect manual seek: if playback position changed externally
+            // (user clicked timeline, jog, etc.) re-sync frame index.
+            let pos = self.state.playback_state.playback_position();
+            let cached_pos = self
+                .state
+                .playback_state
+                .macro_playback
+                .cached_playback_position;
+            if (pos - cached_pos).abs() > 0.5 {
+                self.state.playback_state.sync_macro_frame_index();
+                self.state.playback_state.macro_playback.frame_accumulator = 0.0;
+            }
+            self.state
+                .playback_state
+                .macro_playback
+                .cached_playback_position = pos;
+        }
+
+        // Auto-load scan when scrubbing: find the most recent scan within 15 minutes.
+        // In the worker architecture, this sends a render request directly —
+        // the worker reads records from IDB, decodes the target elevation, and renders.
+        //
+        // In FixedTilt mode, we also detect intra-scan sweep changes: a scan may
+        // contain multiple sweeps at the target elevation (e.g. VCP 215 has 0.5°
+        // at both elevation_number 1 and 3). As playback advances past a new
+        // sweep's start_time, we re-render with that sweep's elevation_number.
+        // Uses module-level MAX_SCAN_AGE_SECS constant.
+        {
+            let playback_ts = self.state.playback_state.playback_position();
+
+            // Skip the timeline walk when nothing that feeds the scrub
+            // decision has moved since last frame. The O(scans) search
+            // below used to run every frame even while paused; this lets
+            // the idle case cost only a few comparisons.
+            let scan_count = self.state.radar_timeline.scans.len();
+            let elev_sel = &self.state.viz_state.elevation_selection;
+            let displayed_ts = self.state.viz_state.displayed_scan_timestamp;
+            let scrub_cache_hit = self.scrub_cache.last_playback_ts == Some(playback_ts)
+                && self.scrub_cache.last_scan_count == scan_count
+                && self.scrub_cache.last_displayed_scan_ts == displayed_ts
+                && self
+                    .scrub_cache
+                    .last_elevation_selection
+                    .as_ref()
+                    .is_some_and(|cached| cached == elev_sel);
+
+            if !scrub_cache_hit {
+                self.scrub_cache.last_playback_ts = Some(playback_ts);
+                self.scrub_cache.last_scan_count = scan_count;
+                self.scrub_cache.last_displayed_scan_ts = displayed_ts;
+                self.scrub_cache.last_elevation_selection = Some(elev_sel.clone());
+            }
+
+            if !scrub_cache_hit {
+                // Extract scrub decision data from the immutable borrow of radar_timeline
+                let scrub_action = self
+                    .state
+                    .radar_timeline
+                    .find_recent_scan(playback_ts, MAX_SCAN_AGE_SECS)
+                    .map(|scan| {
+                        let scan_ts = scan.key_timestamp as i64;
+                        let target_elev_num: Option<u8> =
+                            match &self.state.viz_state.elevation_selection {
+                                crate::state::ElevationSelection::Fixed { .. }
+                                | crate::state::ElevationSelection::LowestAvailable => {
+                                    self.best_elevation_at_playback(scan, playback_ts)
+                                }
+                                crate::state::ElevationSelection::Latest => {
+                                    Some(self.most_recent_sweep_elevation(scan, playback_ts))
+                                }
+                            };
+
+                        let needs_new_scan = match self.state.viz_state.displayed_scan_timestamp {
+                            Some(displayed) => displayed != scan_ts,
+                            None => true,
+                        };
+                        let needs_new_sweep = !needs_new_scan
+                            && self.state.viz_state.displayed_sweep_elevation_number
+                                != target_elev_num;
+
+                        // Capture overlay data from the matching sweep (if any)
+                        let sweep_overlay = target_elev_num.and_then(|num| {
+                            scan.sweeps
+                                .iter()
+                                .find(|s| s.elevation_number == num)
+                                .map(|s| (s.start_time, s.end_time, s.elevation))
+                        });
+
+                        // Extract all elevation numbers for volume rendering
+                        let mut elev_nums: Vec<u8> =
+                            scan.sweeps.iter().map(|s| s.elevation_number).collect();
+                        elev_nums.sort_unstable();
+                        elev_nums.dedup();
+
+                        // Build elevation list for new scans
+                        let new_elev_list = if needs_new_scan {
+                            Some(Self::build_elevation_list(scan))
+                        } else {
+                            None
+                        };
+
+                        (
+                            scan_ts,
+                            target_elev_num,
+                            needs_new_scan,
+                            needs_new_sweep,
+                            sweep_overlay,
+                            elev_nums,
+                            new_elev_list,
+                        )
+                    });
+
+                if let Some((
+                    scan_ts,
+                    target_elev_num,
+                    needs_new_scan,
+                    needs_new_sweep,
+                    sweep_overlay,
+                    elev_nums,
+                    new_elev_list,
+                )) = scrub_action
+                {
+                    if (needs_new_scan || needs_new_sweep) && self.render.has_worker() {
+                        let scan_key =
+                            data::ScanKey::from_secs(&self.state.viz_state.site_id, scan_ts);
+                        self.render.set_scan_key(scan_key.to_storage_key());
+                        self.state.viz_state.displayed_scan_timestamp = Some(scan_ts);
+                        if !elev_nums.is_empty() {
+                            self.render.set_elevations(elev_nums);
+                        }
+                        if let Some(entries) = new_elev_list {
+                            self.state.viz_state.cached_vcp_elevations = entries.clone();
+                            self.state
+                                .viz_state
+                                .elevation_selection
+                                .resolve_for_vcp(&entries);
+                        }
+
+                        match target_elev_num {
+                            Some(num) => {
+                                if let Some((start, end, elev)) = sweep_overlay {
+                                    self.update_overlay_from_sweep(start, end, elev);
+                                }
+                                self.state.viz_state.displayed_sweep_elevation_number = Some(num);
+                                self.render.force_fresh_render();
+                                self.request_worker_render();
+                                if self.state.viz_state.volume_3d_enabled {
+                                    self.request_worker_render_volume();
+                                }
+                            }
+                            None => {
+                                // Scan exists but the selected fixed elevation has no sweep.
+                                // Clear the stale sweep so the canvas matches the timeline.
+                                self.clear_display_no_sweep();
+                            }
+                        }
+                        // The side-effects above change displayed_scan_timestamp,
+                        // so refresh the cache snapshot now to keep it in sync.
+                        self.scrub_cache.last_displayed_scan_ts =
+                            self.state.viz_state.displayed_scan_timestamp;
+                    }
+                } else if self.state.viz_state.displayed_scan_timestamp.is_some() {
+                    self.clear_display_no_scan();
+                }
+            }
+        }
+
+        // Pre-render next sweep: when playing and near the end of the current sweep,
+        // preemptively send a render request for the upcoming sweep so the result
+        // is ready when the boundary is crossed, reducing perceived stutter.
+        // Skip in macro mode — frame jumps are instant and the frame list handles sequencing —
+        // and for volume products and accumulations, which have a single frame per scan.
+        if self.state.playback_state.playing
+            && self.render.has_worker()
+            && self.state.playback_state.playback_mode() == crate::state::PlaybackMode::Micro
+            && !self.state.viz_state.product.is_volume_product()
+            && !self.state.viz_state.product.is_qpe_product()
+            && !self
+                .state
+                .viz_state
+                .elevation_selection
+                .is_lowest_available()
+        {
+            let playback_ts = self.state.playback_state.playback_position();
+            let speed = self
+                .state
+                .playback_state
+                .speed
+                .timeline_seconds_per_real_second();
+            let prefetch_lookahead = PREFETCH_LOOKAHEAD_SECS * speed;
+
+            if let Some(scan) = self
+                .state
+                .radar_timeline
+                .find_scan_at_timestamp(playback_ts)
+            {
+                if let Some((sweep_idx, sweep)) = scan.find_sweep_at_timestamp(playback_ts) {
+                    let time_to_end = sweep.end_time - playback_ts;
+                    if time_to_end > 0.0 && time_to_end < prefetch_lookahead {
+                        let next_elev_num = if sweep_idx + 1 < scan.sweeps.len() {
+                            Some(scan.sweeps[sweep_idx + 1].elevation_number)
+                        } else {
+                            let future_ts = playback_ts + prefetch_lookahead;
+                            self.state
+                                .radar_timeline
+                                .find_scan_at_timestamp(future_ts)
+                                .and_then(|next_scan| {
+                                    next_scan.sweeps.first().map(|s| s.elevation_number)
+                                })
+                        };
+
+                        if let Some(next_en) = next_elev_num {
+                            if self.state.viz_state.displayed_sweep_elevation_number
+                                != Some(next_en)
+                            {
+                                if let Some(scan_key) =
+                                    self.render.scan_key().map(|s| s.to_string())
+                                {
+                                    let product =
+                                        self.state.viz_state.product.to_worker_string().to_string();
+                                    let prefetch_request = RenderRequest {
+                                        scan_key: scan_key.clone(),
+                                        elevation_number: next_en,
+                                        product: product.clone(),
+                                        is_auto: self.state.viz_state.elevation_selection.is_auto(),
+                                    };
+                                    log::debug!(
+                                        "Prefetching next sweep: elev_num={} ({:.1}s ahead)",
+                                        next_en,
+                                        time_to_end,
+                                    );
+                                    self.render.set_last_render(prefetch_request);
+                                    self.render.render_direct(scan_key, next_en, product);
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Stateless sweep animation: ensure the previous-sweep GPU texture matches
+    /// the sweep that *should* be the under-layer based on the current playback
+    /// position, not based on what happened to be rendered before.
+    ///
+    /// The "previous sweep" is the one displayed just before the current one:
+    /// within the same scan that's the preceding sweep in time order. Only look
+    /// at the previous scan if the current sweep is the very first in its scan.
+    fn sync_prev_sweep_texture(&mut self) {
+        // In live mode, the previous sweep texture is managed by
+        // promote_current_to_previous in the LiveDecoded handler —
+        // don't let the timeline-based sync overwrite or clear it.
+        if self.state.live_mode_state.is_active() {
+            return;
+        }
+
+        if !self.state.effective_sweep_animation() {
+            self.state.viz_state.prev_sweep_overlay = None;
+            self.state.viz_state.prev_sweep_scan_timestamp = None;
+            self.state.viz_state.prev_sweep_elevation_number = None;
+            self.state.viz_state.last_sweep_line_cache = None;
+            return;
+        }
+
+        let playback_ts = self.state.playback_state.playback_position();
+        let displayed_elev = match self.state.viz_state.displayed_sweep_elevation_number {
+            Some(e) => e,
+            None => return,
+        };
+
+        let is_auto = self.state.viz_state.elevation_selection.is_auto();
+
+        // Determine which sweep should be the previous-sweep under-layer.
+        let prev_info = PlaybackManager::find_prev_sweep(
+            &self.state.radar_timeline,
+            playback_ts,
+            displayed_elev,
+            is_auto,
+            MAX_SCAN_AGE_SECS,
+        );
+
+        let (prev_scan_key_ts, prev_elev_num, prev_elev_deg, prev_start, prev_end) = match prev_info
+        {
+            Some(info) => info,
+            None => {
+                self.state.viz_state.prev_sweep_overlay = None;
+                self.state.viz_state.prev_sweep_scan_timestamp = None;
+                self.state.viz_state.prev_sweep_elevation_number = None;
+                // Clear GPU previous sweep so shader composites against black
+                if let Some(ref renderer) = self.gpu.gpu {
+                    if let Ok(mut r) = renderer.lock() {
+                        r.clear_previous_data();
+                    }
+                }
+                return;
+            }
+        };
+
+        // Store previous sweep metadata for canvas overlay and timeline highlight
+        self.state.viz_state.prev_sweep_overlay = Some((prev_elev_deg, prev_start, prev_end));
+        self.state.viz_state.prev_sweep_scan_timestamp = Some(prev_scan_key_ts);
+        self.state.viz_state.prev_sweep_elevation_number = Some(prev_elev_num);
+
+        let prev_scan_key =
+            data::ScanKey::from_secs(&self.state.viz_state.site_id, prev_scan_key_ts)
+                .to_storage_key();
+
+        // Get current GPU prev sweep ID for comparison
+        let current_gpu_prev_id = self.gpu.gpu.as_ref().and_then(|renderer| {
+            renderer
+                .lock()
+                .ok()
+                .and_then(|r| r.prev_sweep_id().map(String::from))
+        });
+
+        let product = self.state.viz_state.product.to_worker_string().to_string();
+        let action = self.playback_manager.resolve_prev_sweep(
+            &prev_scan_key,
+            prev_elev_num,
+            current_gpu_prev_id.as_deref(),
+            &product,
+        );
+
+        match action {
+            PrevSweepAction::AlreadyLoaded => {}
+            PrevSweepAction::UploadFromCache(cache_key) => {
+                // Clear stale previous sweep immediately
+                if let Some(ref renderer) = self.gpu.gpu {
+                    if let Ok(mut r) = renderer.lock() {
+                        r.clear_previous_data();
+                    }
+                }
+                if let Some(cached) = self.playback_manager.get_cached_sweep(&cache_key) {
+                    if let (Some(ref renderer), Some(ref gl)) = (&self.gpu.gpu, &self.gpu.gl) {
+                        if let Ok(mut r) = renderer.lock() {
+                            r.update_previous_data(
+                                gl,
+                                &cached.azimuths,
+                                &cached.gate_values,
+                                cached.azimuth_count,
+                                cached.gate_count,
+                                cached.first_gate_range_km,
+                                cached.gate_interval_km,
+                                cached.max_range_km,
+                                cached.offset,
+                                cached.scale,
+                                cached.azimuth_spacing_deg,
+                                Some(cache_key),
+                                &cached.radial_times,
+                            );
+                        }
+                    }
+                }
+            }
+            PrevSweepAction::FetchFromWorker {
+                scan_key,
+                elevation_number,
+                product,
+            } => {
+                // Clear stale previous sweep immediately
+                if let Some(ref renderer) = self.gpu.gpu {
+                    if let Ok(mut r) = renderer.lock() {
+                        r.clear_previous_data();
+                    }
+                }
+                self.render
+                    .render_direct(scan_key, elevation_number, product);
+            }
+            PrevSweepAction::Clear => {
+                if let Some(ref renderer) = self.gpu.gpu {
+                    if let Ok(mut r) = renderer.lock() {
+                        r.clear_previous_data();
+                    }
+                }
+            }
+        }
+    }
+
+    /// Motion-compensated macro playback: while the frame accumulator moves
+    /// towards the next frame, replace the observed sweep on screen with
+    /// frames interpolated towards the next sweep of the same tilt. The
+    /// observed sweep is put back when playback stops or the mode no longer
+    /// applies, and the canvas is told which frames are interpolated.
+    fn sync_interpolated_frame(&mut self) {
+        let transition =
+            if self.state.effective_frame_interpolation() && self.state.playback_state.playing {
+                self.state.playback_state.macro_frame_transition()
+            } else {
+                None
+            };
+        let product = self.state.viz_state.product.to_worker_string();
+        let displayed_elev = self.state.viz_state.displayed_sweep_elevation_number;
+        let gpu_sweep_id = self.gpu.gpu.as_ref().and_then(|renderer| {
+            renderer
+                .lock()
+                .ok()
+                .and_then(|r| r.current_sweep_id().map(String::from))
+        });
+
+        if transition.is_some() {
+            // Wait for the frame's own sweep to reach the GPU; until then
+            // the screen still shows the previous frame.
+            let observed_key = self
+                .render
+                .scan_key()
+                .zip(displayed_elev)
+                .map(|(scan_key, elev_num)| sweep_cache_key(scan_key, elev_num, product));
+            if observed_key.is_none() || observed_key != gpu_sweep_id {
+                return;
+            }
+        }
+
+        // The next frame's sweep: same elevation number in the scan whose
+        // sweep ends at the next frame time.
+        let target = transition.zip(displayed_elev).and_then(
+            |((from_secs, to_secs, fraction), elev_num)| {
+                let to_scan = self
+                    .state
+                    .radar_timeline
+                    .find_scan_with_sweep_ending(to_secs, elev_num)?;
+                let to_scan_key = data::ScanKey::from_secs(
+                    &self.state.viz_state.site_id,
+                    to_scan.key_timestamp as i64,
+                )
+                .to_storage_key();
+                Some((to_scan_key, elev_num, from_secs, to_secs, fraction))
+            },
+        );
+        let Some((to_scan_key, elev_num, from_secs, to_secs, fraction)) = target else {
+            let action = self
+                .playback_manager
+                .stop_interpolation()
+                .map_or(InterpolationAction::Unchanged, InterpolationAction::Restore);
+            self.apply_interpolation_action(action);
+            self.state.viz_state.interpolated_frame = None;
+            return;
+        };
+        let from_key = gpu_sweep_id.unwrap_or_default();
+        let action = self.playback_manager.resolve_interpolated_frame(
+            &from_key,
+            &to_scan_key,
+            elev_num,
+            product,
+            fraction,
+            nexrad::color_table::is_categorical(product),
+        );
+        self.apply_interpolation_action(action);
+        self.state.viz_state.interpolated_frame = self
+            .playback_manager
+            .interpolated_fraction()
+            .map(|fraction| state::InterpolatedFrame {
+                from_secs,
+                to_secs,
+                fraction,
+            });
+    }
+
+    /// Carry out an interpolation action on the primary GPU texture.
+    fn apply_interpolation_action(&mut self, action: InterpolationAction) {
+        let (from_key, gate_values) = match action {
+            InterpolationAction::Unchanged => return,
+            InterpolationAction::FetchFromWorker {
+                scan_key,
+                elevation_number,
+                product,
+            } => {
+                self.render
+                    .render_direct(scan_key, elevation_number, product);
+                return;
+            }
+            InterpolationAction::Upload {
+                from_key,
+                gate_values,
+            } => (from_key, Some(gate_values)),
+            InterpolationAction::Restore(from_key) => (from_key, None),
+        };
+        let Some(cached) = self.playback_manager.get_cached_sweep(&from_key) else {
+            // Evicted before it could be put back: decode it again.
+            if gate_values.is_none() {
+                self.render.force_fresh_render();
+                self.request_worker_render();
+            }
+            return;
+        };
+        if let (Some(ref renderer), Some(ref gl)) = (&self.gpu.gpu, &self.gpu.gl) {
+            if let Ok(mut r) = renderer.lock() {
+                r.update_data(
+                    gl,
+                    &cached.azimuths,
+                    gate_values.as_deref().unwrap_or(&cached.gate_values[..]),
+                    cached.azimuth_count,
+                    cached.gate_count,
+                    cached.first_gate_range_km,
+                    cached.gate_interval_km,
+                    cached.max_range_km,
+                    cached.offset,
+                    cached.scale,
+                    cached.azimuth_spacing_deg,
+                    &cached.radial_times,
+                );
+            }
+        }
+    }
+
+    /// Clear the on-canvas sweep and the overlay fields when the entire scan
+    /// is gone (e.g. scrubbed off the timeline). Resets the scan key too.
+    fn clear_display_no_scan(&mut self) {
+        if let Some(ref renderer) = self.gpu.gpu {
+            if let Ok(mut r) = renderer.lock() {
+                r.clear_data();
+            }
+        }
+        self.clear_panels();
+        self.state.viz_state.displayed_scan_timestamp = None;
+        self.state.viz_state.displayed_sweep_elevation_number = None;
+        self.render.clear_scan_key();
+        self.state.viz_state.data_staleness_secs = None;
+        self.state.viz_state.rendered_sweep_end_secs = None;
+        self.state.viz_state.detected_rotations = None;
+        self.state.viz_state.timestamp = "--:--:-- UTC".to_string();
+        self.state.viz_state.elevation = "-- deg".to_string();
+        // clear_data() drops both GPU textures; match the prev-sweep metadata
+        // so the timeline highlight and canvas overlay don't point at state
+        // that no longer has backing pixels.
+        self.state.viz_state.prev_sweep_scan_timestamp = None;
+        self.state.viz_state.prev_sweep_elevation_number = None;
+        self.state.viz_state.prev_sweep_overlay = None;
+        self.scrub_cache.last_displayed_scan_ts = None;
+    }
+
+    /// Clear the on-canvas sweep when the selected (elevation, product) isn't
+    /// available for the current scan, but the scan itself is still valid.
+    /// Leaves the scan key intact so other elevations/products can still render.
+    fn clear_display_no_sweep(&mut self) {
+        if let Some(ref renderer) = self.gpu.gpu {
+            if let Ok(mut r) = renderer.lock() {
+                r.clear_data();
+            }
+        }
+        self.state.viz_state.displayed_sweep_elevation_number = None;
+        self.state.viz_state.data_staleness_secs = None;
+        self.state.viz_state.rendered_sweep_end_secs = None;
+        self.state.viz_state.detected_rotations = None;
+        self.state.viz_state.timestamp = "--:--:-- UTC".to_string();
+        self.state.viz_state.elevation = "-- deg".to_string();
+        // clear_data() drops both GPU textures. sync_prev_sweep_texture
+        // early-returns while displayed_sweep_elevation_number is None, so
+        // clear prev metadata here to prevent a stale timeline/overlay hint.
+        self.state.viz_state.prev_sweep_scan_timestamp = None;
+        self.state.viz_state.prev_sweep_elevation_number = None;
+        self.state.viz_state.prev_sweep_overlay = None;
+        self.render.clear_last_render();
+    }
+
+    /// Clear one extra split-view panel and forget its last request.
+    fn clear_panel(&mut self, panel: usize) {
+        if let Some(renderer) = self.gpu.panels.get(panel) {
+            if let Ok(mut r) = renderer.lock() {
+                r.clear_data();
+            }
+        }
+        self.render.clear_panel_render(panel);
+    }
+
+    /// Clear every extra split-view panel.
+    fn clear_panels(&mut self) {
+        for panel in 0..self.gpu.panels.len() {
+            self.clear_panel(panel);
+        }
+    }
+
+    /// Re-render when the user changes elevation, product, or view mode.
+    fn request_render_if_needed(&mut self) {
+        // Split-view panels and the cross-section read completed sweeps from
+        // IDB, in live mode too.
+        self.request_panel_renders();
+        self.request_cross_section();
+        self.request_mosaic();
+        self.request_pinned_renders();
+
+        // Live mode re-renders on the next ChunkIngested (~12s) — no IDB-based render needed.
+        if self.state.live_mode_state.is_active() {
+            return;
+        }
+        // Detect elevation/product changes and trigger worker re-render.
+        // If the user changes these settings and we have a current scan, we need
+        // a new render from the worker.
+        if self.render.scan_key().is_some() && self.render.has_worker() {
+            if self.state.viz_state.volume_3d_enabled
+                && self.state.viz_state.view_mode == state::ViewMode::Globe3D
+            {
+                self.request_worker_render_volume();
+            }
+            self.request_worker_render();
+        }
+    }
+
+    /// Sync network statistics from the download channel and service worker.
+    fn update_network_stats(&mut self) {
+        // Update session stats from live network statistics
+        let network_stats = self.acquisition.download_channel.stats();
+        self.state
+            .session_stats
+            .update_from_network_stats(&network_stats);
+
+        // Drain service worker network metrics into app state
+        if let Some(ref monitor) = self.network_monitor {
+            self.state.network_aggregate = monitor.aggregate();
+            let mut pending = monitor.take_pending();
+            if !pending.is_empty() {
+                // Correlate each new request exactly once, then append to
+                // the app-level ring. The previous implementation
+                // re-cloned and re-correlated the entire ring every frame
+                // regardless of whether anything had changed.
+                for req in pending.iter_mut() {
+                    req.operation_id = self.state.acquisition.correlate_network_request(&req.url);
+                }
+                let ring = &mut self.state.recent_network_requests;
+                ring.reserve(pending.len());
+                for req in pending {
+                    if ring.len() >= MAX_RECENT_NETWORK_REQUESTS {
+                        ring.pop_front();
+                    }
+                    ring.push_back(req);
+                }
+            }
+        }
+    }
+
+    /// Push current app state to the URL bar and save user preferences (throttled).
+    fn persist_url_state(&mut self) {
+        self.persistence.persist_if_due(&self.state);
+    }
+
+    /// Push the current `AppMode`'s color to the browser favicon via the
+    /// `setFaviconColor` JS hook in `index.html`. No-op when the mode hasn't
+    /// changed since the last push.
+    #[cfg(target_arch = "wasm32")]
+    fn sync_favicon_to_mode(&mut self) {
+        use wasm_bindgen::prelude::*;
+        #[wasm_bindgen]
+        extern "C" {
+            #[wasm_bindgen(js_namespace = window, js_name = setFaviconColor, catch)]
+            fn js_set_favicon_color(hex: &str) -> Result<(), JsValue>;
+        }
+
+        let mode = self.state.app_mode;
+        if self.last_favicon_mode == Some(mode) {
+            return;
+        }
+        let c = mode.color();
+        let hex = format!("#{:02x}{:02x}{:02x}", c.r(), c.g(), c.b());
+        let _ = js_set_favicon_color(&hex);
+        self.last_favicon_mode = Some(mode);
+    }
+
+    /// The desktop window icon stays fixed.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn sync_favicon_to_mode(&mut self) {}
+}
+
+impl eframe::App for WorkbenchApp {
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        platform::poll_tasks(ctx);
+        self.apply_frame_setup(ctx);
+        ui::run_console_commands(ctx, &mut self.state);
+        let (dl_sel, dl_pos, pump) = self.dispatch_commands(ctx);
+        self.handle_local_imports(ctx);
+        self.handle_level3_events();
+        self.handle_mosaic_events();
+        self.refresh_live_mosaic(ctx);
+        self.handle_pinned_events();
+        self.refresh_live_pinned_sites(ctx);
+        self.reload_pinned_scans(ctx);
+        self.handle_worker_results(ctx);
+        self.pump_download_queue(ctx, dl_sel, dl_pos, pump);
+        self.handle_streaming_results(ctx);
+        self.state
+            .national_mosaic
+            .poll_tick(ctx, self.state.layer_state.geo.national_mosaic);
+        self.advance_playback();
+        self.sync_prev_sweep_texture();
+        self.sync_interpolated_frame();
+        self.request_render_if_needed();
+        self.update_network_stats();
+        self.persist_url_state();
+
+        // Poll the NWS alerts feed if due; drain any completed fetches.
+        self.alerts_manager.tick(ctx, &mut self.state);
+
+        // Start data exports; step and capture any running image export.
+        self.start_data_export();
+        self.export_manager.tick(ctx, &mut self.state);
+
+        // Compute the live radar model snapshot for this frame so all UI
+        // consumers see consistent state from the same `now` timestamp.
+        self.state.refresh_live_model();
+
+        // Resolve mobile/desktop layout for this frame before panels render.
+        self.state.refresh_mobile_mode(ctx);
+
+        // Recolor the favicon if the AppMode changed this frame.
+        self.sync_favicon_to_mode();
+
+        // Render UI panels in the correct order for egui layout.
+        // Side and top/bottom panels must be rendered before CentralPanel.
+        // On mobile, the tabbed chrome replaces both the desktop top bar and
+        // bottom panel; the left/right side panels early-return internally.
+        if self.state.is_mobile {
+            // Consume any deferred geolocation request raised by the mobile
+            // action bar. Handled here because the site-modal state lives
+            // outside AppState.
+            if self.state.mobile_geolocate_requested {
+                self.state.mobile_geolocate_requested = false;
+                ui::trigger_geolocation(ctx, &mut self.state, &mut self.site_modal_state);
+            }
+
+            ui::render_mobile_top_bar(ctx, &mut self.state);
+            ui::render_mobile_chrome(ctx, &mut self.state);
+            // The desktop bottom_panel is still called so its per-frame
+            // side effects (pulse animation update) run. It early-returns
+            // before rendering when `is_mobile` is true.
+            ui::render_bottom_panel(ctx, &mut self.state);
+        } else {
+            ui::render_top_bar(ctx, &mut self.state);
+            ui::render_bottom_panel(ctx, &mut self.state);
+            ui::render_left_panel(ctx, &mut self.state);
+            ui::render_right_panel(ctx, &mut self.state);
+        }
+
+        // Render canvas with GPU-based radar rendering
+        ui::render_canvas_with_geo(ctx, &mut self.state, Some(&self.geo_layers), &self.gpu);
+
+        // Process keyboard shortcuts
+        ui::handle_shortcuts(ctx, &mut self.state);
+
+        // Render overlays (on top of everything)
+        ui::render_site_modal(ctx, &mut self.state, &mut self.site_modal_state);
+        ui::render_mobile_settings_modal(ctx, &mut self.state);
+        ui::render_shortcuts_help(ctx, &mut self.state);
+        ui::render_wipe_modal(ctx, &mut self.state);
+        ui::render_stats_modal(ctx, &mut self.state);
+        ui::render_vcp_forecast_modal(ctx, &mut self.state);
+        ui::render_network_log(ctx, &mut self.state);
+        ui::render_event_modal(ctx, &mut self.state, &mut self.event_modal_state);
+        ui::render_cross_section_window(ctx, &mut self.state, &mut self.cross_section_window_state);
+        ui::render_alerts_modals(ctx, &mut self.state);
+        ui::render_console_window(ctx, &mut self.state);
+    }
+}
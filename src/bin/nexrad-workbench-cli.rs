@@ -0,0 +1,10 @@
+//! Headless entry point: batch ingest, detection and rendering without a
+//! window. See `nexrad_workbench::cli`.
+
+#[cfg(not(target_arch = "wasm32"))]
+fn main() -> std::process::ExitCode {
+    nexrad_workbench::cli::main()
+}
+
+#[cfg(target_arch = "wasm32")]
+fn main() {}
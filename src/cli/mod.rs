@@ -0,0 +1,461 @@
+//! Headless batch processing: `nexrad-workbench-cli`.
+//!
+//! Runs the desktop build's data pipeline without a window. Archive II
+//! volumes are read from disk (or listed and downloaded from the archive
+//! bucket for a site and time range), ingested through the same
+//! `worker_api::ingest` the decode threads use, and written as sweep blobs
+//! to a [`DirectoryRecordStore`]. Each scan then gets storm cell detection
+//! with cross-scan tracking, and optionally a PNG per product on a north-up
+//! lat/lon grid and a JSON summary (see `scan`).
+//!
+//! Scans are processed in time order so track IDs and motion come out the
+//! same as when stepping through the event in the app.
+
+mod scan;
+
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use crate::data::directory::DirectoryRecordStore;
+use crate::data::RecordStore;
+use crate::export::grid::GRID_CELL_DEG;
+use crate::nexrad::data_source;
+use crate::nexrad::detection::StormTracker;
+use crate::nexrad::palette;
+use crate::nexrad::worker_api::{self, IngestParams};
+use crate::nexrad::{assemble_volumes, read_paths, LocalFile};
+use crate::platform;
+use crate::state::{DataSourceSettings, PaletteSettings, RadarProduct};
+
+const USAGE: &str = "\
+Usage:
+  nexrad-workbench-cli [OPTIONS] <FILE|DIR>...
+  nexrad-workbench-cli [OPTIONS] --site <ID> --start <TIME> --end <TIME>
+
+Ingests Archive II volumes (plain, gzip or bzip2; real-time chunk files are
+joined per volume), detects storm cells and writes the results.
+
+Options:
+  -o, --out <DIR>          Output directory [default: .]
+      --store <DIR>        Sweep blob store [default: <out>/store]
+      --site <ID>          Download volumes for this site from the archive
+      --start <TIME>       Start of the range, UTC (2024-05-06T20:00[:00][Z])
+      --end <TIME>         End of the range, UTC
+      --archive-url <URL>  S3-compatible archive bucket [default: AWS]
+  -p, --product <CODES>    Products to render, comma separated [default: REF]
+  -e, --elevation <N>      Elevation number [default: lowest with the product]
+      --png                Write a PNG per product
+      --json               Write a JSON summary per scan (default without --png)
+      --cell-deg <DEG>     PNG grid spacing in degrees [default: 0.01]
+      --palette <FILE>     Color PNGs with a .pal color table, or a bundled
+                           palette by name; repeat for other products
+      --threshold <DBZ>    Storm cell threshold [default: 35]
+      --dealias            Also store dealiased velocity (product code DVEL)
+  -h, --help               Print this help
+
+Product codes are those used in the app's URLs: REF, VEL, DVEL, SW, ZDR,
+CC, KDP, CFP, HCA, CREF, ET18, ET30, ET50, VIL. A palette applies to the
+product on its Product line; bundled palettes are \"NWS Reflectivity\",
+\"Viridis Reflectivity\", \"Classic Velocity (kt)\", \"Blue-Orange Velocity (kt)\"
+and \"Cividis CC\".
+";
+
+/// Where volumes come from.
+#[derive(Debug, PartialEq)]
+enum Input {
+    /// Files, or directories of files, on disk.
+    Files(Vec<PathBuf>),
+    /// Every archive volume for `site` with a start time in `start..=end`
+    /// (Unix seconds).
+    Archive { site: String, start: i64, end: i64 },
+}
+
+/// Parsed command line.
+#[derive(Debug, PartialEq)]
+struct Args {
+    input: Input,
+    out_dir: PathBuf,
+    store_dir: Option<PathBuf>,
+    archive_url: Option<String>,
+    products: Vec<RadarProduct>,
+    elevation: Option<u8>,
+    png: bool,
+    json: bool,
+    cell_deg: f64,
+    /// `.pal` files or bundled palette names.
+    palettes: Vec<String>,
+    threshold_dbz: f32,
+    dealias_velocity: bool,
+}
+
+impl Args {
+    fn store_dir(&self) -> PathBuf {
+        self.store_dir
+            .clone()
+            .unwrap_or_else(|| self.out_dir.join("store"))
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum Command {
+    Help,
+    Run(Args),
+}
+
+/// Entry point for the `nexrad-workbench-cli` binary.
+pub fn main() -> ExitCode {
+    platform::init_logger();
+    let args = match parse_args(std::env::args().skip(1)) {
+        Ok(Command::Run(args)) => args,
+        Ok(Command::Help) => {
+            print!("{}", USAGE);
+            return ExitCode::SUCCESS;
+        }
+        Err(e) => {
+            eprintln!("error: {}\n\n{}", e, USAGE);
+            return ExitCode::from(2);
+        }
+    };
+    match platform::block_on(run(&args)) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn parse_args(args: impl IntoIterator<Item = String>) -> Result<Command, String> {
+    let mut paths = Vec::new();
+    let mut site = None;
+    let mut start = None;
+    let mut end = None;
+    let mut parsed = Args {
+        input: Input::Files(Vec::new()),
+        out_dir: PathBuf::from("."),
+        store_dir: None,
+        archive_url: None,
+        products: vec![RadarProduct::Reflectivity],
+        elevation: None,
+        png: false,
+        json: false,
+        cell_deg: GRID_CELL_DEG,
+        palettes: Vec::new(),
+        threshold_dbz: 35.0,
+        dealias_velocity: false,
+    };
+
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        if !arg.starts_with('-') {
+            paths.push(PathBuf::from(arg));
+            continue;
+        }
+        let (flag, inline) = match arg.split_once('=') {
+            Some((flag, value)) => (flag.to_string(), Some(value.to_string())),
+            None => (arg, None),
+        };
+        let mut value = || {
+            inline
+                .clone()
+                .or_else(|| args.next())
+                .ok_or_else(|| format!("{} needs a value", flag))
+        };
+        match flag.as_str() {
+            "-h" | "--help" => return Ok(Command::Help),
+            "-o" | "--out" => parsed.out_dir = PathBuf::from(value()?),
+            "--store" => parsed.store_dir = Some(PathBuf::from(value()?)),
+            "--site" => site = Some(value()?.to_uppercase()),
+            "--start" => start = Some(parse_utc(&value()?)?),
+            "--end" => end = Some(parse_utc(&value()?)?),
+            "--archive-url" => parsed.archive_url = Some(value()?),
+            "-p" | "--product" => parsed.products = parse_products(&value()?)?,
+            "-e" | "--elevation" => {
+                let v = value()?;
+                let n = v.parse().map_err(|_| format!("bad elevation: {}", v))?;
+                parsed.elevation = Some(n);
+            }
+            "--png" => parsed.png = true,
+            "--json" => parsed.json = true,
+            "--cell-deg" => {
+                let v = value()?;
+                parsed.cell_deg = v
+                    .parse()
+                    .ok()
+                    .filter(|d: &f64| *d > 0.0)
+                    .ok_or_else(|| format!("bad grid spacing: {}", v))?;
+            }
+            "--palette" => parsed.palettes.push(value()?),
+            "--threshold" => {
+                let v = value()?;
+                parsed.threshold_dbz = v.parse().map_err(|_| format!("bad threshold: {}", v))?;
+            }
+            "--dealias" => parsed.dealias_velocity = true,
+            _ => return Err(format!("unknown option: {}", flag)),
+        }
+    }
+
+    parsed.input = match (site, start, end) {
+        (None, None, None) if paths.is_empty() => return Err("no input files".to_string()),
+        (None, None, None) => Input::Files(paths),
+        (Some(site), Some(start), Some(end)) if paths.is_empty() => {
+            if end < start {
+                return Err("--end is before --start".to_string());
+            }
+            Input::Archive { site, start, end }
+        }
+        (Some(_), Some(_), Some(_)) => {
+            return Err("give either input files or --site/--start/--end".to_string())
+        }
+        _ => return Err("--site, --start and --end go together".to_string()),
+    };
+    if !parsed.png {
+        parsed.json = true;
+    }
+    Ok(Command::Run(parsed))
+}
+
+/// A UTC date and time: `YYYY-MM-DDTHH:MM`, optionally with seconds and a
+/// trailing `Z`; a space may stand in for the `T`.
+fn parse_utc(s: &str) -> Result<i64, String> {
+    let trimmed = s.trim_end_matches('Z').replace(' ', "T");
+    ["%Y-%m-%dT%H:%M:%S", "%Y-%m-%dT%H:%M"]
+        .iter()
+        .find_map(|format| chrono::NaiveDateTime::parse_from_str(&trimmed, format).ok())
+        .map(|t| t.and_utc().timestamp())
+        .ok_or_else(|| format!("bad time: {} (expected e.g. 2024-05-06T20:00Z)", s))
+}
+
+/// Comma-separated product codes. Products the command line can't produce
+/// (storm-relative velocity needs a motion, accumulations a timeline, and
+/// Level III comes from a different archive) are rejected.
+fn parse_products(s: &str) -> Result<Vec<RadarProduct>, String> {
+    s.split(',')
+        .map(|code| {
+            let code = code.trim().to_uppercase();
+            match RadarProduct::from_short_code(&code) {
+                Some(p)
+                    if !(p == RadarProduct::StormRelativeVelocity
+                        || p.is_qpe_product()
+                        || p.is_level3()) =>
+                {
+                    Ok(p)
+                }
+                Some(_) => Err(format!("{} is not available from the command line", code)),
+                None => Err(format!("unknown product: {}", code)),
+            }
+        })
+        .collect()
+}
+
+async fn run(args: &Args) -> Result<(), String> {
+    configure_palettes(&args.palettes)?;
+    std::fs::create_dir_all(&args.out_dir)
+        .map_err(|e| format!("{}: {}", args.out_dir.display(), e))?;
+    let store = DirectoryRecordStore::new(args.store_dir());
+    store
+        .open()
+        .await
+        .map_err(|e| format!("Failed to open sweep store: {}", e))?;
+
+    let files = match &args.input {
+        Input::Files(paths) => read_paths(paths),
+        Input::Archive { site, start, end } => {
+            if let Some(archive_url) = &args.archive_url {
+                data_source::configure(&DataSourceSettings {
+                    archive_url: archive_url.clone(),
+                    ..Default::default()
+                });
+            }
+            fetch_archive(site, *start, *end).await?
+        }
+    };
+    let volumes = assemble_volumes(files);
+    if volumes.is_empty() {
+        return Err("no volumes to process".to_string());
+    }
+
+    // A bad file shouldn't sink a whole event, so failures are logged and
+    // the rest of the batch carries on.
+    let mut scans = Vec::new();
+    for volume in volumes {
+        let params = IngestParams {
+            site_id: None,
+            timestamp_secs: None,
+            file_name: volume.file_name.clone(),
+            dealias_velocity: args.dealias_velocity,
+        };
+        match worker_api::ingest(&store, volume.data, params).await {
+            Ok(response) => {
+                log::info!(
+                    "Ingested {}: {} sweeps in {:.0}ms",
+                    volume.file_name,
+                    response.sweeps.len(),
+                    response.total_ms
+                );
+                scans.push((volume.file_name, response));
+            }
+            Err(e) => log::error!("{}", e),
+        }
+    }
+    if scans.is_empty() {
+        return Err("no volume could be ingested".to_string());
+    }
+    scans.sort_by(|a, b| a.1.scan_key.cmp(&b.1.scan_key));
+
+    let mut tracker = StormTracker::default();
+    for (file_name, ingested) in &scans {
+        let written = scan::process(&store, &mut tracker, args, file_name, ingested).await?;
+        for path in written {
+            println!("{}", path.display());
+        }
+    }
+    Ok(())
+}
+
+/// Install `--palette` choices, each a `.pal` file or a bundled palette's
+/// name. The palette's `Product:` line picks the product it colors.
+fn configure_palettes(specs: &[String]) -> Result<(), String> {
+    let mut settings = PaletteSettings::default();
+    for spec in specs {
+        let bundled = palette::BUNDLED
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(spec));
+        let (name, source) = match bundled {
+            Some((name, source)) => (name.to_string(), source.to_string()),
+            None => {
+                let path = Path::new(spec);
+                let source =
+                    std::fs::read_to_string(path).map_err(|e| format!("{}: {}", spec, e))?;
+                let name = path
+                    .file_name()
+                    .map_or(spec.clone(), |n| n.to_string_lossy().into_owned());
+                (name, source)
+            }
+        };
+        settings
+            .import(&name, &source, None)
+            .map_err(|e| format!("{}: {}", spec, e))?;
+    }
+    palette::configure(&settings);
+    Ok(())
+}
+
+/// Download every volume for `site` starting within `start..=end`.
+async fn fetch_archive(site: &str, start: i64, end: i64) -> Result<Vec<LocalFile>, String> {
+    let first = chrono::DateTime::from_timestamp(start, 0).ok_or("start out of range")?;
+    let last = chrono::DateTime::from_timestamp(end, 0).ok_or("end out of range")?;
+
+    let mut files = Vec::new();
+    for date in first.date_naive().iter_days() {
+        if date > last.date_naive() {
+            break;
+        }
+        let listing = data_source::list_archive_files(site, &date)
+            .await
+            .map_err(|e| format!("Failed to list {} on {}: {}", site, date, e))?;
+        for identifier in listing {
+            let in_range = identifier
+                .date_time()
+                .is_some_and(|t| (start..=end).contains(&t.timestamp()));
+            if !in_range || identifier.name().ends_with("_MDM") {
+                continue;
+            }
+            let name = identifier.name().to_string();
+            log::info!("Downloading {}", name);
+            let file = data_source::download_archive_file(identifier)
+                .await
+                .map_err(|e| format!("Failed to download {}: {}", name, e))?;
+            files.push(LocalFile {
+                name,
+                data: file.data().to_vec(),
+            });
+        }
+    }
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Command, String> {
+        parse_args(args.iter().map(|a| a.to_string()))
+    }
+
+    fn run_args(args: &[&str]) -> Args {
+        match parse(args) {
+            Ok(Command::Run(args)) => args,
+            other => panic!("expected a run, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn files_default_to_json_summaries_of_reflectivity() {
+        let args = run_args(&["KTLX20240506_200102_V06", "chunks/"]);
+        assert_eq!(
+            args.input,
+            Input::Files(vec!["KTLX20240506_200102_V06".into(), "chunks/".into()])
+        );
+        assert_eq!(args.products, vec![RadarProduct::Reflectivity]);
+        assert!(args.json && !args.png);
+        assert_eq!(args.store_dir(), PathBuf::from("./store"));
+    }
+
+    #[test]
+    fn parses_site_range_and_options() {
+        let args = run_args(&[
+            "--site=ktlx",
+            "--start",
+            "2024-05-06T20:00Z",
+            "--end",
+            "2024-05-06 21:30:15",
+            "-p",
+            "ref,vel",
+            "-e",
+            "3",
+            "--png",
+            "--palette",
+            "Cividis CC",
+            "-o",
+            "out",
+        ]);
+        assert_eq!(
+            args.input,
+            Input::Archive {
+                site: "KTLX".to_string(),
+                start: 1_715_025_600,
+                end: 1_715_031_015,
+            }
+        );
+        assert_eq!(
+            args.products,
+            vec![RadarProduct::Reflectivity, RadarProduct::Velocity]
+        );
+        assert_eq!(args.elevation, Some(3));
+        assert_eq!(args.palettes, vec!["Cividis CC".to_string()]);
+        assert!(args.png && !args.json);
+        assert_eq!(args.store_dir(), PathBuf::from("out/store"));
+    }
+
+    #[test]
+    fn rejects_bad_command_lines() {
+        assert!(parse(&[]).is_err());
+        assert!(parse(&["--site", "KTLX", "--start", "2024-05-06T20:00"]).is_err());
+        assert!(parse(&[
+            "file",
+            "--site",
+            "KTLX",
+            "--start",
+            "2024-05-06T20:00",
+            "--end",
+            "2024-05-06T21:00"
+        ])
+        .is_err());
+        assert!(parse(&["file", "-p", "SRM"]).is_err());
+        assert!(parse(&["file", "-p", "N0Q"]).is_err());
+        assert!(parse(&["file", "--bogus"]).is_err());
+        assert!(parse(&["file", "--out"]).is_err());
+        assert_eq!(parse(&["file", "--help"]), Ok(Command::Help));
+    }
+}
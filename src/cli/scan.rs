@@ -0,0 +1,356 @@
+//! Per-scan outputs: storm cells, product renders and the JSON summary.
+//!
+//! Renders are resampled onto a north-up lat/lon grid with
+//! `export::grid` (the GeoTIFF export's grid) and coloured through
+//! `color_table`'s product LUTs, one pixel per cell, transparent where
+//! there is no data. The grid edges go into the summary so the images can
+//! be placed on a map.
+
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use super::Args;
+use crate::data::directory::DirectoryRecordStore;
+use crate::data::keys::{GateValues, PrecomputedSweep, ScanKey, SweepDataKey, SweepMeta};
+use crate::data::sites::NexradSite;
+use crate::export::encode::{encode_png, RgbaImage};
+use crate::export::grid::{grid_sweep, LatLonGrid};
+use crate::nexrad::color_table::{build_product_lut, value_range_for};
+use crate::nexrad::derived::is_volume_product;
+use crate::nexrad::detection::{detect_cells, DetectionInput, DetectionParams, StormTracker};
+use crate::nexrad::polar::PolarSweep;
+use crate::nexrad::worker_api::{load_sweep, IngestResponse};
+use crate::state::StormCellInfo;
+
+/// Product string storm cells are detected on.
+const DETECTION_PRODUCT: &str = "reflectivity";
+
+/// Everything the command line reports about one scan.
+#[derive(Serialize)]
+struct ScanSummary {
+    site: String,
+    /// Volume start, RFC 3339.
+    scan_start: String,
+    file_name: String,
+    vcp: Option<u16>,
+    ingest_ms: f64,
+    sweeps: Vec<SweepSummary>,
+    /// Elevation number cells were detected on, if any.
+    detection_elevation: Option<u8>,
+    cells: Vec<CellSummary>,
+    renders: Vec<RenderSummary>,
+}
+
+#[derive(Serialize)]
+struct SweepSummary {
+    elevation_number: u8,
+    elevation_deg: f32,
+    start_secs: f64,
+    end_secs: f64,
+    products: Vec<String>,
+}
+
+#[derive(Serialize)]
+struct CellSummary {
+    /// Persistent across the scans of one run.
+    track_id: Option<u32>,
+    lat: f64,
+    lon: f64,
+    max_dbz: f32,
+    mean_dbz: f32,
+    area_km2: f32,
+    bearing_deg: f32,
+    range_km: f32,
+    /// Motion toward `heading_deg`, once the track spans enough scans.
+    speed_kmh: Option<f32>,
+    heading_deg: Option<f32>,
+}
+
+#[derive(Serialize)]
+struct RenderSummary {
+    /// Product short code.
+    product: &'static str,
+    elevation_number: u8,
+    elevation_deg: f32,
+    file: String,
+    width: usize,
+    height: usize,
+    /// Outer edges of the image in degrees.
+    west: f64,
+    east: f64,
+    north: f64,
+    south: f64,
+}
+
+impl From<&SweepMeta> for SweepSummary {
+    fn from(sweep: &SweepMeta) -> Self {
+        Self {
+            elevation_number: sweep.elevation_number,
+            elevation_deg: sweep.elevation,
+            start_secs: sweep.start,
+            end_secs: sweep.end,
+            products: sweep.available_products.clone(),
+        }
+    }
+}
+
+impl From<&StormCellInfo> for CellSummary {
+    fn from(cell: &StormCellInfo) -> Self {
+        let motion = cell.track.as_ref().and_then(|t| t.motion);
+        Self {
+            track_id: cell.track.as_ref().map(|t| t.id),
+            lat: cell.lat,
+            lon: cell.lon,
+            max_dbz: cell.max_dbz,
+            mean_dbz: cell.mean_dbz,
+            area_km2: cell.area_km2,
+            bearing_deg: cell.bearing_from_radar_deg,
+            range_km: cell.range_from_radar_km,
+            speed_kmh: motion.map(|m| m.speed_kmh),
+            heading_deg: motion.map(|m| m.heading_deg),
+        }
+    }
+}
+
+/// Detect, render and summarize one ingested scan. Returns the files
+/// written.
+pub(super) async fn process(
+    store: &DirectoryRecordStore,
+    tracker: &mut StormTracker,
+    args: &Args,
+    file_name: &str,
+    ingested: &IngestResponse,
+) -> Result<Vec<PathBuf>, String> {
+    let scan_key = ScanKey::from_storage_key(&ingested.scan_key)
+        .ok_or_else(|| format!("Invalid scan key: {}", ingested.scan_key))?;
+    let site_id = scan_key.site.0.clone();
+    let scan_secs = scan_key.scan_start.as_secs() as f64;
+    let site = crate::data::get_site(&site_id);
+    if site.is_none() {
+        log::warn!(
+            "{}: unknown site {}, skipping detection and renders",
+            file_name,
+            site_id
+        );
+    }
+
+    let mut summary = ScanSummary {
+        site: site_id.clone(),
+        scan_start: chrono::DateTime::from_timestamp(scan_secs as i64, 0)
+            .map(|t| t.to_rfc3339())
+            .unwrap_or_default(),
+        file_name: file_name.to_string(),
+        vcp: ingested.vcp.as_ref().map(|v| v.number),
+        ingest_ms: ingested.total_ms,
+        sweeps: ingested.sweeps.iter().map(SweepSummary::from).collect(),
+        detection_elevation: None,
+        cells: Vec::new(),
+        renders: Vec::new(),
+    };
+    let mut written = Vec::new();
+    let Some(site) = site else {
+        if args.json {
+            written.push(write_summary(&args.out_dir, &summary, scan_secs)?);
+        }
+        return Ok(written);
+    };
+
+    let detection_elevation = pick_elevation(&ingested.sweeps, DETECTION_PRODUCT, args.elevation);
+    if let Some(elevation) = detection_elevation {
+        let key = SweepDataKey::new(scan_key.clone(), elevation, DETECTION_PRODUCT);
+        if let Some(sweep) = load_sweep(store, key).await {
+            let cells = detect(&sweep, site, args.threshold_dbz);
+            let cells = tracker.update(
+                (site_id.clone(), elevation, DETECTION_PRODUCT),
+                sweep.sweep_start_secs,
+                cells,
+            );
+            summary.detection_elevation = Some(elevation);
+            summary.cells = cells.iter().map(CellSummary::from).collect();
+        }
+    }
+
+    if args.png {
+        for &product in &args.products {
+            let product_str = product.to_worker_string();
+            let Some(elevation) = pick_elevation(&ingested.sweeps, product_str, args.elevation)
+            else {
+                log::warn!("{}: no {} sweep", file_name, product.short_code());
+                continue;
+            };
+            let key = SweepDataKey::new(scan_key.clone(), elevation, product_str);
+            let Some(sweep) = load_sweep(store, key).await else {
+                log::warn!(
+                    "{}: no {} sweep at elevation {}",
+                    file_name,
+                    product.short_code(),
+                    elevation
+                );
+                continue;
+            };
+
+            let grid = grid_sweep(
+                &PolarSweep::from_precomputed(&sweep),
+                site.lat,
+                site.lon,
+                args.cell_deg,
+            );
+            let image = colorize(&grid, product_str);
+            let name = format!(
+                "{}.png",
+                crate::export::file_stem(&site_id, product.short_code(), scan_secs)
+            );
+            let path = args.out_dir.join(&name);
+            write_file(&path, &encode_png(&image)?)?;
+            written.push(path);
+
+            summary.renders.push(RenderSummary {
+                product: product.short_code(),
+                elevation_number: elevation,
+                elevation_deg: sweep.mean_elevation,
+                file: name,
+                width: grid.columns,
+                height: grid.rows,
+                west: grid.west,
+                east: grid.west + grid.columns as f64 * grid.cell_deg,
+                north: grid.north,
+                south: grid.north - grid.rows as f64 * grid.cell_deg,
+            });
+        }
+    }
+
+    if args.json {
+        written.push(write_summary(&args.out_dir, &summary, scan_secs)?);
+    }
+    Ok(written)
+}
+
+/// Elevation number to read `product` from: `requested` if given, else the
+/// lowest sweep that stored it. Volume products are always filed under the
+/// volume's lowest elevation.
+fn pick_elevation(sweeps: &[SweepMeta], product: &str, requested: Option<u8>) -> Option<u8> {
+    let lowest = |with_product: bool| {
+        sweeps
+            .iter()
+            .filter(|s| {
+                !with_product
+                    || s.available_products.is_empty()
+                    || s.available_products.iter().any(|p| p == product)
+            })
+            .map(|s| s.elevation_number)
+            .min()
+    };
+    if is_volume_product(product) {
+        return lowest(false);
+    }
+    requested.or_else(|| lowest(true))
+}
+
+/// Storm cells in a reflectivity sweep at the default detector settings.
+fn detect(sweep: &PrecomputedSweep, site: &NexradSite, threshold_dbz: f32) -> Vec<StormCellInfo> {
+    let gate_values: Vec<f32> = match &sweep.gate_values {
+        GateValues::U8(v) => v.iter().map(|&raw| raw as f32).collect(),
+        GateValues::U16(v) => v.iter().map(|&raw| raw as f32).collect(),
+    };
+    let input = DetectionInput {
+        azimuths: &sweep.azimuths,
+        gate_values: &gate_values,
+        azimuth_count: sweep.azimuth_count as usize,
+        gate_count: sweep.gate_count as usize,
+        first_gate_km: sweep.first_gate_range_km,
+        gate_interval_km: sweep.gate_interval_km,
+        data_scale: sweep.scale,
+        data_offset: sweep.offset,
+        radar_lat: site.lat,
+        radar_lon: site.lon,
+    };
+    let params = DetectionParams {
+        threshold_dbz,
+        ..Default::default()
+    };
+    detect_cells(&input, &params)
+}
+
+/// Colour `grid` through `product`'s LUT. Values pick the entry they fall
+/// in, as the GPU's nearest sampling does for categorical products.
+fn colorize(grid: &LatLonGrid, product: &str) -> RgbaImage {
+    let lut = build_product_lut(product);
+    let entries = lut.len() / 4;
+    let (min_val, max_val) = value_range_for(product);
+    let mut pixels = Vec::with_capacity(grid.values.len() * 4);
+    for &value in &grid.values {
+        if value.is_nan() {
+            pixels.extend_from_slice(&[0, 0, 0, 0]);
+            continue;
+        }
+        let t = ((value - min_val) / (max_val - min_val)).clamp(0.0, 1.0);
+        let entry = ((t * entries as f32) as usize).min(entries - 1);
+        pixels.extend_from_slice(&lut[entry * 4..entry * 4 + 4]);
+    }
+    RgbaImage::new(grid.columns, grid.rows, pixels)
+}
+
+fn write_summary(out_dir: &Path, summary: &ScanSummary, scan_secs: f64) -> Result<PathBuf, String> {
+    let json = serde_json::to_vec_pretty(summary)
+        .map_err(|e| format!("Failed to serialize summary: {}", e))?;
+    let path = out_dir.join(format!(
+        "{}.json",
+        crate::export::file_stem(&summary.site, "SCAN", scan_secs)
+    ));
+    write_file(&path, &json)?;
+    Ok(path)
+}
+
+fn write_file(path: &Path, bytes: &[u8]) -> Result<(), String> {
+    std::fs::write(path, bytes).map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sweep(elevation_number: u8, products: &[&str]) -> SweepMeta {
+        SweepMeta {
+            start: 0.0,
+            end: 0.0,
+            elevation: 0.5,
+            elevation_number,
+            start_azimuth: 0.0,
+            available_products: products.iter().map(|p| p.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn picks_lowest_elevation_with_product() {
+        // Split cut: surveillance at 1, Doppler at 2.
+        let sweeps = vec![
+            sweep(3, &["reflectivity", "velocity"]),
+            sweep(2, &["velocity"]),
+            sweep(1, &["reflectivity", "composite_reflectivity"]),
+        ];
+        assert_eq!(pick_elevation(&sweeps, "reflectivity", None), Some(1));
+        assert_eq!(pick_elevation(&sweeps, "velocity", None), Some(2));
+        assert_eq!(pick_elevation(&sweeps, "velocity", Some(3)), Some(3));
+        assert_eq!(pick_elevation(&sweeps, "vil", Some(3)), Some(1));
+        assert_eq!(pick_elevation(&sweeps, "spectrum_width", None), None);
+    }
+
+    #[test]
+    fn colorizes_through_the_product_lut() {
+        let grid = LatLonGrid {
+            west: -98.0,
+            north: 36.0,
+            cell_deg: 0.01,
+            columns: 3,
+            rows: 1,
+            values: vec![f32::NAN, -32.0, 95.0],
+        };
+        let lut = build_product_lut("reflectivity");
+        let image = colorize(&grid, "reflectivity");
+        assert_eq!((image.width, image.height), (3, 1));
+        assert_eq!(&image.pixels[0..4], &[0, 0, 0, 0]);
+        assert_eq!(&image.pixels[4..8], &lut[0..4]);
+        assert_eq!(&image.pixels[8..12], &lut[lut.len() - 4..]);
+    }
+}
@@ -0,0 +1,228 @@
+//! On-disk sweep store for native builds.
+//!
+//! ## Layout
+//!
+//! ```text
+//! <root>/
+//! ├── sweeps/      - One file per sweep blob, "SITE_SCAN_MS_ELEV_NUM_PRODUCT.bin"
+//! └── scan_index/  - One JSON file per scan, "SITE_SCAN_START_MS.json"
+//! ```
+//!
+//! File names are storage keys with `|` swapped for `_`, which some
+//! filesystems reject. Writes go to a temporary file that is then renamed,
+//! so a crash mid-write never leaves a truncated blob or entry behind. The
+//! desktop build's UI and decode threads each hold their own handle on the
+//! same directory, so temporary names are unique per write and listings
+//! tolerate files that vanish underneath them.
+
+use crate::data::keys::*;
+use crate::data::store::{DataError, RecordStore};
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+const SWEEPS_DIR: &str = "sweeps";
+const SCAN_INDEX_DIR: &str = "scan_index";
+
+/// Sweep store in a directory on disk.
+#[derive(Clone)]
+pub struct DirectoryRecordStore {
+    root: Rc<PathBuf>,
+}
+
+impl DirectoryRecordStore {
+    /// A store rooted at `root`. Nothing is touched until [`RecordStore::open`].
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self {
+            root: Rc::new(root.into()),
+        }
+    }
+
+    fn sweep_path(&self, key: &str) -> PathBuf {
+        self.root
+            .join(SWEEPS_DIR)
+            .join(format!("{}.bin", file_stem(key)))
+    }
+
+    fn index_path(&self, key: &str) -> PathBuf {
+        self.root
+            .join(SCAN_INDEX_DIR)
+            .join(format!("{}.json", file_stem(key)))
+    }
+}
+
+fn file_stem(key: &str) -> String {
+    key.replace('|', "_")
+}
+
+fn io_error(path: &Path, e: std::io::Error) -> DataError {
+    DataError::Io(format!("{}: {}", path.display(), e))
+}
+
+/// Read a file, `None` if it doesn't exist.
+fn read_optional(path: &Path) -> Result<Option<Vec<u8>>, DataError> {
+    match std::fs::read(path) {
+        Ok(bytes) => Ok(Some(bytes)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(io_error(path, e)),
+    }
+}
+
+/// Replace a file's contents via a temporary sibling and a rename.
+fn write_atomic(path: &Path, bytes: &[u8]) -> Result<(), DataError> {
+    static NEXT_TMP: AtomicU64 = AtomicU64::new(0);
+    let n = NEXT_TMP.fetch_add(1, Ordering::Relaxed);
+    let tmp = path.with_extension(format!("{}-{}.tmp", std::process::id(), n));
+    std::fs::write(&tmp, bytes).map_err(|e| io_error(&tmp, e))?;
+    std::fs::rename(&tmp, path).map_err(|e| io_error(path, e))
+}
+
+fn remove_optional(path: &Path) -> Result<(), DataError> {
+    match std::fs::remove_file(path) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(io_error(path, e)),
+        _ => Ok(()),
+    }
+}
+
+fn parse_entry(bytes: &[u8]) -> Result<ScanIndexEntry, DataError> {
+    serde_json::from_slice(bytes).map_err(|e| DataError::DeserializationError(e.to_string()))
+}
+
+impl RecordStore for DirectoryRecordStore {
+    async fn open(&self) -> Result<(), DataError> {
+        for dir in [SWEEPS_DIR, SCAN_INDEX_DIR] {
+            let path = self.root.join(dir);
+            std::fs::create_dir_all(&path).map_err(|e| io_error(&path, e))?;
+        }
+        Ok(())
+    }
+
+    async fn put_sweeps_batch(&self, items: &[(String, Vec<u8>)]) -> Result<(), DataError> {
+        for (key, data) in items {
+            write_atomic(&self.sweep_path(key), data)?;
+        }
+        Ok(())
+    }
+
+    async fn get_sweep(&self, key: &str) -> Result<Option<Vec<u8>>, DataError> {
+        read_optional(&self.sweep_path(key))
+    }
+
+    async fn get_scan_index_entry(
+        &self,
+        storage_key: &str,
+    ) -> Result<Option<ScanIndexEntry>, DataError> {
+        read_optional(&self.index_path(storage_key))?
+            .map(|bytes| parse_entry(&bytes))
+            .transpose()
+    }
+
+    async fn put_scan_index_entry(&self, entry: &ScanIndexEntry) -> Result<(), DataError> {
+        let json = serde_json::to_vec(entry)
+            .map_err(|e| DataError::DeserializationError(e.to_string()))?;
+        write_atomic(&self.index_path(&entry.storage_key()), &json)
+    }
+
+    async fn all_scan_index_entries(&self) -> Result<Vec<ScanIndexEntry>, DataError> {
+        let dir = self.root.join(SCAN_INDEX_DIR);
+        let listing = std::fs::read_dir(&dir).map_err(|e| io_error(&dir, e))?;
+        let mut entries = Vec::new();
+        for item in listing {
+            let path = item.map_err(|e| io_error(&dir, e))?.path();
+            if path.extension().is_none_or(|ext| ext != "json") {
+                continue;
+            }
+            // Deleted by another handle since the listing was taken.
+            let Some(bytes) = read_optional(&path)? else {
+                continue;
+            };
+            // Like the IndexedDB store, skip entries that no longer parse
+            // rather than failing the whole listing.
+            match parse_entry(&bytes) {
+                Ok(entry) => entries.push(entry),
+                Err(e) => log::warn!("Skipping {}: {}", path.display(), e),
+            }
+        }
+        Ok(entries)
+    }
+
+    async fn delete_records(
+        &self,
+        sweep_keys: &[String],
+        index_key: &str,
+    ) -> Result<(), DataError> {
+        for key in sweep_keys {
+            remove_optional(&self.sweep_path(key))?;
+        }
+        // The index entry goes last so a failure part-way leaves the scan
+        // listed and a retry can finish the job.
+        remove_optional(&self.index_path(index_key))
+    }
+
+    async fn clear_all(&self) -> Result<(), DataError> {
+        for dir in [SWEEPS_DIR, SCAN_INDEX_DIR] {
+            let path = self.root.join(dir);
+            match std::fs::remove_dir_all(&path) {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                    return Err(io_error(&path, e));
+                }
+                _ => {}
+            }
+        }
+        self.open().await?;
+        log::info!("Cleared sweep cache in {}", self.root.display());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::store::block_on;
+
+    fn temp_root(name: &str) -> PathBuf {
+        let root =
+            std::env::temp_dir().join(format!("nexrad-workbench-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        root
+    }
+
+    #[test]
+    fn persists_across_handles_and_deletes_scans() {
+        let root = temp_root("directory-store");
+        let scan = ScanKey::from_secs("KTLX", 1_369_080_000);
+        let sweep_key = SweepDataKey::new(scan.clone(), 1, "velocity").to_storage_key();
+
+        let store = DirectoryRecordStore::new(&root);
+        block_on(store.open()).unwrap();
+        block_on(store.put_sweeps_batch(&[(sweep_key.clone(), vec![1, 2, 3])])).unwrap();
+        let mut entry = ScanIndexEntry::new(scan.clone());
+        entry.total_size_bytes = 3;
+        entry.sweeps = Some(vec![SweepMeta {
+            start: 1_369_080_000.0,
+            end: 1_369_080_020.0,
+            elevation: 0.5,
+            elevation_number: 1,
+            start_azimuth: 0.0,
+            available_products: vec!["velocity".to_string()],
+        }]);
+        block_on(store.put_scan_index_entry(&entry)).unwrap();
+
+        // A fresh handle on the same directory sees the same cache.
+        let reopened = DirectoryRecordStore::new(&root);
+        block_on(reopened.open()).unwrap();
+        assert_eq!(
+            block_on(reopened.get_sweep(&sweep_key)).unwrap(),
+            Some(vec![1, 2, 3])
+        );
+        assert_eq!(block_on(reopened.total_cache_size()).unwrap(), 3);
+
+        assert_eq!(block_on(reopened.delete_scan(&scan)).unwrap(), 3);
+        assert!(block_on(reopened.get_sweep(&sweep_key)).unwrap().is_none());
+        assert!(block_on(reopened.scan_availability(&scan))
+            .unwrap()
+            .is_none());
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+}
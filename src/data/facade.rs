@@ -1,17 +1,25 @@
-//! Data facade providing a unified interface for IndexedDB storage.
+//! Data facade providing a unified interface over the sweep cache.
 //!
-//! Wraps `IndexedDbRecordStore` with cache eviction logic.
+//! Wraps a [`RecordStore`] backend (IndexedDB in the browser, a directory
+//! on the desktop) with cache eviction logic.
 
-use crate::data::indexeddb::{DataError, IndexedDbRecordStore};
 use crate::data::keys::*;
+use crate::data::store::{DataError, RecordStore};
+
+/// The cache backend of this build.
+#[cfg(target_arch = "wasm32")]
+pub type DefaultRecordStore = crate::data::indexeddb::IndexedDbRecordStore;
+/// The cache backend of this build.
+#[cfg(not(target_arch = "wasm32"))]
+pub type DefaultRecordStore = crate::data::directory::DirectoryRecordStore;
 
 /// Result type for cache operations.
 pub type CacheResult<T> = Result<T, DataError>;
 
-/// Data facade for accessing radar data in IndexedDB.
+/// Data facade for accessing cached radar data.
 #[derive(Clone)]
-pub struct DataFacade {
-    store: IndexedDbRecordStore,
+pub struct DataFacade<S: RecordStore = DefaultRecordStore> {
+    store: S,
 }
 
 impl Default for DataFacade {
@@ -21,10 +29,22 @@ impl Default for DataFacade {
 }
 
 impl DataFacade {
+    #[cfg(target_arch = "wasm32")]
     pub fn new() -> Self {
-        Self {
-            store: IndexedDbRecordStore::new(),
-        }
+        Self::with_store(DefaultRecordStore::new())
+    }
+
+    /// A facade over the cache in the user's XDG cache directory.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn new() -> Self {
+        Self::with_store(DefaultRecordStore::new(crate::platform::cache_dir()))
+    }
+}
+
+impl<S: RecordStore> DataFacade<S> {
+    /// A facade over another storage backend.
+    pub fn with_store(store: S) -> Self {
+        Self { store }
     }
 
     /// Opens the cache database.
@@ -60,9 +80,10 @@ impl DataFacade {
     /// Checks if eviction is needed and performs it.
     /// Returns `(evicted, scans_evicted, quota_warning)`.
     ///
-    /// Checks both the app-level quota and the browser-level storage quota.
-    /// If browser quota is critically low (less than 10% remaining), triggers
-    /// proactive eviction and returns a warning message.
+    /// Checks both the app-level quota and the backend's storage quota (the
+    /// browser's, for IndexedDB). If that quota is critically low (less than
+    /// 10% remaining), triggers proactive eviction and returns a warning
+    /// message.
     pub async fn check_and_evict(
         &self,
         quota_bytes: u64,
@@ -85,10 +106,8 @@ impl DataFacade {
             did_evict = true;
         }
 
-        // Browser-level quota check via navigator.storage.estimate()
-        let quota_warning = if let Some(estimate) =
-            IndexedDbRecordStore::estimate_storage_quota().await
-        {
+        // Backend-level quota check, e.g. navigator.storage.estimate()
+        let quota_warning = if let Some(estimate) = self.store.storage_quota().await {
             let remaining = estimate.remaining();
             let threshold = estimate.quota / 10; // 10% of browser quota
 
@@ -1,5 +1,8 @@
 //! IndexedDB storage for pre-computed radar sweep data.
 //!
+//! The browser's [`RecordStore`] backend. The cache semantics built on top
+//! (eviction, index merges) live in [`crate::data::store`].
+//!
 //! ## Object Stores
 //!
 //! 1. `sweeps` - Pre-computed sweep blobs (ArrayBuffer)
@@ -9,6 +12,7 @@
 //!    - Key: "SITE|SCAN_START_MS"
 
 use crate::data::keys::*;
+use crate::data::store::{DataError, RecordStore, StorageQuotaEstimate};
 use js_sys::{Array, ArrayBuffer, Uint8Array};
 use serde::de::DeserializeOwned;
 use std::cell::RefCell;
@@ -18,60 +22,6 @@ use wasm_bindgen::prelude::*;
 use wasm_bindgen::JsCast;
 use web_sys::{IdbDatabase, IdbObjectStore, IdbRequest, IdbTransaction, IdbTransactionMode};
 
-/// Structured error type for IndexedDB operations.
-#[derive(Debug)]
-#[allow(dead_code)]
-pub enum DataError {
-    /// The database has not been opened yet.
-    NotOpen,
-    /// An IDB transaction failed.
-    TransactionFailed(String),
-    /// An IDB request failed.
-    RequestFailed(String),
-    /// Browser storage quota exceeded.
-    QuotaExceeded { available_mb: f64, required_mb: f64 },
-    /// The requested key was not found.
-    NotFound,
-    /// Deserialization of stored data failed.
-    DeserializationError(String),
-}
-
-impl std::fmt::Display for DataError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            DataError::NotOpen => write!(f, "Database not open"),
-            DataError::TransactionFailed(msg) => write!(f, "Transaction failed: {}", msg),
-            DataError::RequestFailed(msg) => write!(f, "Request failed: {}", msg),
-            DataError::QuotaExceeded {
-                available_mb,
-                required_mb,
-            } => write!(
-                f,
-                "Insufficient storage quota: {:.1} MB available, {:.1} MB required",
-                available_mb, required_mb
-            ),
-            DataError::NotFound => write!(f, "Not found"),
-            DataError::DeserializationError(msg) => write!(f, "Deserialization error: {}", msg),
-        }
-    }
-}
-
-/// Browser storage quota estimate from `navigator.storage.estimate()`.
-#[derive(Debug, Clone, Copy)]
-pub struct StorageQuotaEstimate {
-    /// Total quota granted by the browser (bytes).
-    pub quota: u64,
-    /// Current usage across all storage mechanisms (bytes).
-    pub usage: u64,
-}
-
-impl StorageQuotaEstimate {
-    /// Remaining bytes available.
-    pub fn remaining(&self) -> u64 {
-        self.quota.saturating_sub(self.usage)
-    }
-}
-
 /// Current database schema version.
 const DATABASE_VERSION: u32 = 3;
 
@@ -262,9 +212,27 @@ impl IndexedDbRecordStore {
         Ok(deserialize_js_array(&array))
     }
 
-    // ========================================================================
-    // Sweep operations
-    // ========================================================================
+    /// Gets a pre-computed sweep blob by key, returning the raw JS ArrayBuffer.
+    /// Avoids the 5MB+ copy from JS to Rust that `get_sweep` performs.
+    pub async fn get_sweep_as_js(&self, key: &str) -> Result<Option<ArrayBuffer>, DataError> {
+        self.ensure_open().await?;
+        let result = self.read_one(STORE_SWEEPS, key).await?;
+
+        if result.is_undefined() || result.is_null() {
+            return Ok(None);
+        }
+
+        let buffer: ArrayBuffer = result
+            .dyn_into()
+            .map_err(|_| DataError::DeserializationError("Expected ArrayBuffer".to_string()))?;
+        Ok(Some(buffer))
+    }
+}
+
+impl RecordStore for IndexedDbRecordStore {
+    async fn open(&self) -> Result<(), DataError> {
+        IndexedDbRecordStore::open(self).await
+    }
 
     /// Stores multiple pre-computed sweep blobs in a single IDB transaction.
     ///
@@ -275,7 +243,7 @@ impl IndexedDbRecordStore {
     ///
     /// Checks browser storage quota before writing. If remaining quota is
     /// insufficient for the batch, returns an error instead of silently failing.
-    pub async fn put_sweeps_batch(&self, items: &[(String, Vec<u8>)]) -> Result<(), DataError> {
+    async fn put_sweeps_batch(&self, items: &[(String, Vec<u8>)]) -> Result<(), DataError> {
         if items.is_empty() {
             return Ok(());
         }
@@ -310,28 +278,21 @@ impl IndexedDbRecordStore {
         .await
     }
 
-    /// Gets a pre-computed sweep blob by key, returning the raw JS ArrayBuffer.
-    /// Avoids the 5MB+ copy from JS to Rust that `get_sweep` performs.
-    pub async fn get_sweep_as_js(&self, key: &str) -> Result<Option<ArrayBuffer>, DataError> {
-        self.ensure_open().await?;
-        let result = self.read_one(STORE_SWEEPS, key).await?;
-
-        if result.is_undefined() || result.is_null() {
-            return Ok(None);
-        }
-
-        let buffer: ArrayBuffer = result
-            .dyn_into()
-            .map_err(|_| DataError::DeserializationError("Expected ArrayBuffer".to_string()))?;
-        Ok(Some(buffer))
+    async fn get_sweep(&self, key: &str) -> Result<Option<Vec<u8>>, DataError> {
+        let buffer = self.get_sweep_as_js(key).await?;
+        Ok(buffer.map(|b| Uint8Array::new(&b).to_vec()))
     }
 
-    // ========================================================================
-    // Scan index operations
-    // ========================================================================
+    async fn get_scan_index_entry(
+        &self,
+        storage_key: &str,
+    ) -> Result<Option<ScanIndexEntry>, DataError> {
+        self.ensure_open().await?;
+        let result = self.read_one(STORE_SCAN_INDEX, storage_key).await?;
+        Ok(deserialize_js_value(&result))
+    }
 
-    /// Writes or updates a scan index entry.
-    pub async fn put_scan_index_entry(&self, entry: &ScanIndexEntry) -> Result<(), DataError> {
+    async fn put_scan_index_entry(&self, entry: &ScanIndexEntry) -> Result<(), DataError> {
         self.ensure_open().await?;
         let storage_key = entry.storage_key();
 
@@ -352,301 +313,38 @@ impl IndexedDbRecordStore {
         .await
     }
 
-    /// Gets scan availability information.
-    pub async fn scan_availability(
-        &self,
-        scan: &ScanKey,
-    ) -> Result<Option<ScanIndexEntry>, DataError> {
+    async fn all_scan_index_entries(&self) -> Result<Vec<ScanIndexEntry>, DataError> {
         self.ensure_open().await?;
-        let storage_key = scan.to_storage_key();
-        let result = self.read_one(STORE_SCAN_INDEX, &storage_key).await?;
-        Ok(deserialize_js_value(&result))
+        self.read_all(STORE_SCAN_INDEX).await
     }
 
-    /// Lists all scans for a site within a time window.
-    pub async fn list_scans(
+    /// Deletes the sweep blobs and scan index entry in one transaction.
+    async fn delete_records(
         &self,
-        site: &SiteId,
-        start: UnixMillis,
-        end: UnixMillis,
-    ) -> Result<Vec<ScanIndexEntry>, DataError> {
-        self.ensure_open().await?;
-        let entries: Vec<ScanIndexEntry> = self.read_all(STORE_SCAN_INDEX).await?;
-
-        let mut scans: Vec<ScanIndexEntry> = entries
-            .into_iter()
-            .filter(|entry| {
-                entry.scan.site.0 == site.0
-                    && entry.scan.scan_start >= start
-                    && entry.scan.scan_start <= end
-            })
-            .collect();
-
-        scans.sort_by_key(|s| s.scan.scan_start.0);
-        Ok(scans)
-    }
-
-    /// Gets total cache size across all scans.
-    pub async fn total_cache_size(&self) -> Result<u64, DataError> {
-        self.ensure_open().await?;
-        let entries: Vec<ScanIndexEntry> = self.read_all(STORE_SCAN_INDEX).await?;
-        let total: u64 = entries.iter().map(|e| e.total_size_bytes).sum();
-        Ok(total)
-    }
-
-    /// Gets scans sorted by last_accessed_at (oldest first) for LRU eviction.
-    pub async fn get_lru_scans(&self, limit: u32) -> Result<Vec<ScanIndexEntry>, DataError> {
-        self.ensure_open().await?;
-        let mut scans: Vec<ScanIndexEntry> = self.read_all(STORE_SCAN_INDEX).await?;
-        scans.sort_by_key(|s| s.last_accessed_at.0);
-        scans.truncate(limit as usize);
-        Ok(scans)
-    }
-
-    /// Deletes a scan and all its sweep blobs.
-    /// Returns the number of bytes freed.
-    pub async fn delete_scan(&self, scan: &ScanKey) -> Result<u64, DataError> {
+        sweep_keys: &[String],
+        index_key: &str,
+    ) -> Result<(), DataError> {
         self.ensure_open().await?;
-
-        let scan_storage_key = scan.to_storage_key();
-
-        // Get the scan entry to know its size and elevation structure
-        let scan_entry = self.scan_availability(scan).await?;
-        let bytes_freed = scan_entry.as_ref().map(|e| e.total_size_bytes).unwrap_or(0);
-
-        // Build list of all possible sweep keys for this scan
-        let sweep_keys: Vec<String> = if let Some(ref entry) = scan_entry {
-            if let Some(ref sweeps) = entry.sweeps {
-                let mut keys = Vec::new();
-                for sweep in sweeps {
-                    for product in ALL_PRODUCTS {
-                        let key = SweepDataKey::new(scan.clone(), sweep.elevation_number, *product);
-                        keys.push(key.to_storage_key());
-                    }
-                }
-                keys
-            } else {
-                Vec::new()
-            }
-        } else {
-            Vec::new()
-        };
-
-        // Delete all sweep blobs and scan index entry in one transaction
         self.write_tx_multi(&[STORE_SWEEPS, STORE_SCAN_INDEX], |wtx| {
             let sweeps_store = wtx.object_store(STORE_SWEEPS)?;
             let scan_store = wtx.object_store(STORE_SCAN_INDEX)?;
 
-            for key in &sweep_keys {
+            for key in sweep_keys {
                 sweeps_store
                     .delete(&JsValue::from_str(key))
                     .map_err(|e| DataError::RequestFailed(format!("{:?}", e)))?;
             }
 
             scan_store
-                .delete(&JsValue::from_str(&scan_storage_key))
-                .map_err(|e| DataError::RequestFailed(format!("{:?}", e)))?;
-            Ok(())
-        })
-        .await?;
-
-        log::debug!(
-            "Deleted scan {} ({} sweep blobs, {} bytes freed)",
-            scan,
-            sweep_keys.len(),
-            bytes_freed
-        );
-        Ok(bytes_freed)
-    }
-
-    /// Evicts scans until total cache size is below target_bytes.
-    /// Returns the number of scans evicted.
-    pub async fn evict_to_size(&self, target_bytes: u64) -> Result<u32, DataError> {
-        let mut current_size = self.total_cache_size().await?;
-        let mut evicted_count = 0u32;
-
-        while current_size > target_bytes {
-            let lru_scans = self.get_lru_scans(1).await?;
-
-            if lru_scans.is_empty() {
-                break;
-            }
-
-            let oldest = &lru_scans[0];
-            let bytes_freed = self.delete_scan(&oldest.scan).await?;
-
-            current_size = current_size.saturating_sub(bytes_freed);
-            evicted_count += 1;
-
-            log::debug!(
-                "Evicted scan {} (freed {} bytes, {} remaining)",
-                oldest.scan,
-                bytes_freed,
-                current_size
-            );
-        }
-
-        if evicted_count > 0 {
-            log::info!(
-                "LRU eviction complete: evicted {} scans, cache now {} bytes",
-                evicted_count,
-                current_size
-            );
-        }
-
-        Ok(evicted_count)
-    }
-
-    /// Merges incremental data into an existing scan index entry, or creates one
-    /// if it doesn't exist yet. Used by per-chunk ingest to build up scan metadata
-    /// incrementally as elevations complete.
-    ///
-    /// Two-transaction pattern: read in readonly first, then write the merged
-    /// result in a separate readwrite transaction (no await inside readwrite).
-    pub async fn merge_scan_index_entry(
-        &self,
-        partial: &ScanIndexEntry,
-        new_records: u32,
-        new_size_bytes: u64,
-        new_sweeps: &[SweepMeta],
-    ) -> Result<(), DataError> {
-        self.ensure_open().await?;
-        let storage_key = partial.storage_key();
-
-        // --- Readonly tx: read existing entry ---
-        let existing: Option<ScanIndexEntry> = {
-            let result = self.read_one(STORE_SCAN_INDEX, &storage_key).await?;
-            deserialize_js_value(&result)
-        };
-
-        // --- Merge in memory ---
-        let merged = if let Some(mut entry) = existing {
-            entry.present_records += new_records;
-            entry.total_size_bytes += new_size_bytes;
-            entry.updated_at = UnixMillis::now();
-            entry.has_precomputed_sweeps = true;
-
-            // Merge VCP if newly available
-            if !entry.has_vcp && partial.has_vcp {
-                entry.has_vcp = true;
-                entry.vcp = partial.vcp.clone();
-                if let Some(ref vcp) = entry.vcp {
-                    entry.expected_records = Some(vcp.elevations.len() as u32);
-                }
-            }
-
-            // Merge file_name if not set
-            if entry.file_name.is_none() {
-                entry.file_name = partial.file_name.clone();
-            }
-
-            // Append new sweeps
-            if !new_sweeps.is_empty() {
-                let sweeps = entry.sweeps.get_or_insert_with(Vec::new);
-                sweeps.extend_from_slice(new_sweeps);
-            }
-
-            // Update end timestamp to max
-            if let Some(new_end) = partial.end_timestamp_secs {
-                entry.end_timestamp_secs = Some(
-                    entry
-                        .end_timestamp_secs
-                        .map(|old| old.max(new_end))
-                        .unwrap_or(new_end),
-                );
-            }
-
-            entry
-        } else {
-            // No existing entry — create from partial
-            let mut entry = partial.clone();
-            entry.present_records = new_records;
-            entry.total_size_bytes = new_size_bytes;
-            entry.has_precomputed_sweeps = true;
-            if !new_sweeps.is_empty() {
-                entry.sweeps = Some(new_sweeps.to_vec());
-            }
-            entry
-        };
-
-        // --- Readwrite tx: write merged entry ---
-        let json = serde_json::to_string(&merged)
-            .map_err(|e| DataError::DeserializationError(format!("{}", e)))?;
-        let js = js_sys::JSON::parse(&json)
-            .map_err(|e| DataError::DeserializationError(format!("{:?}", e)))?;
-
-        self.write_tx(STORE_SCAN_INDEX, |wtx| {
-            let store = wtx.object_store(STORE_SCAN_INDEX)?;
-            store
-                .put_with_key(&js, &JsValue::from_str(&storage_key))
+                .delete(&JsValue::from_str(index_key))
                 .map_err(|e| DataError::RequestFailed(format!("{:?}", e)))?;
             Ok(())
         })
         .await
     }
 
-    /// Deletes all existing scans for a site whose time range overlaps with the
-    /// given archive scan. Returns the number of scans deleted.
-    ///
-    /// A scan overlaps if its [start, end] range intersects the archive scan's
-    /// range. Scans without an end timestamp use start as the end.
-    /// The scan matching `exclude_key` (the archive scan itself) is skipped.
-    pub async fn delete_overlapping_scans(
-        &self,
-        site: &SiteId,
-        archive_start: UnixMillis,
-        archive_end_ms: i64,
-        exclude_key: &ScanKey,
-    ) -> Result<u32, DataError> {
-        self.ensure_open().await?;
-
-        // Read all scan index entries
-        let all_entries: Vec<ScanIndexEntry> = self.read_all(STORE_SCAN_INDEX).await?;
-
-        // Find overlapping scans for this site
-        let mut to_delete: Vec<ScanKey> = Vec::new();
-        for entry in &all_entries {
-            if entry.scan.site.0 != site.0 {
-                continue;
-            }
-            if entry.scan == *exclude_key {
-                continue;
-            }
-            let existing_start = entry.scan.scan_start.0;
-            let existing_end = entry
-                .end_timestamp_secs
-                .map(|s| s * 1000)
-                .unwrap_or(existing_start);
-
-            // Two ranges overlap if start_a <= end_b AND start_b <= end_a
-            if archive_start.0 <= existing_end && existing_start <= archive_end_ms {
-                to_delete.push(entry.scan.clone());
-            }
-        }
-
-        if to_delete.is_empty() {
-            return Ok(0);
-        }
-
-        let count = to_delete.len() as u32;
-        for scan in &to_delete {
-            log::debug!("Deleting overlapping scan {} (replaced by archive)", scan);
-            self.delete_scan(scan).await?;
-        }
-
-        Ok(count)
-    }
-
-    /// Queries the browser's storage quota via `navigator.storage.estimate()`.
-    ///
-    /// Works in both Window and Worker contexts. Returns `None` if the
-    /// Storage API is unavailable (e.g. older browsers, opaque origins).
-    pub async fn estimate_storage_quota() -> Option<StorageQuotaEstimate> {
-        estimate_browser_quota().await
-    }
-
     /// Clears all data from all stores.
-    pub async fn clear_all(&self) -> Result<(), DataError> {
+    async fn clear_all(&self) -> Result<(), DataError> {
         // Clear each object store rather than deleting the database.
         // deleteDatabase would hang if any other connection (e.g. the worker)
         // is still open, because the delete is blocked until ALL connections close.
@@ -666,6 +364,14 @@ impl IndexedDbRecordStore {
         log::info!("Cleared all IndexedDB stores");
         Ok(())
     }
+
+    /// Queries the browser's storage quota via `navigator.storage.estimate()`.
+    ///
+    /// Works in both Window and Worker contexts. Returns `None` if the
+    /// Storage API is unavailable (e.g. older browsers, opaque origins).
+    async fn storage_quota(&self) -> Option<StorageQuotaEstimate> {
+        estimate_browser_quota().await
+    }
 }
 
 // ============================================================================
@@ -46,7 +46,12 @@ pub struct UnixMillis(pub i64);
 
 impl UnixMillis {
     pub fn now() -> Self {
-        Self(js_sys::Date::now() as i64)
+        // web_time reads Date.now() in the browser and the system clock
+        // natively.
+        let since_epoch = web_time::SystemTime::now()
+            .duration_since(web_time::UNIX_EPOCH)
+            .unwrap_or_default();
+        Self(since_epoch.as_millis() as i64)
     }
 
     pub fn from_secs(secs: i64) -> Self {
@@ -169,6 +174,15 @@ impl GateValues {
             GateValues::U16(_) => 2,
         }
     }
+
+    /// Widen to 16-bit storage. Raw values (and therefore scale/offset) are
+    /// unchanged.
+    pub fn into_u16(self) -> GateValues {
+        match self {
+            GateValues::U8(v) => GateValues::U16(v.into_iter().map(u16::from).collect()),
+            u16 @ GateValues::U16(_) => u16,
+        }
+    }
 }
 
 /// Pre-computed sweep data ready for GPU rendering.
@@ -176,7 +190,8 @@ impl GateValues {
 /// Binary layout (little-endian, 72-byte header):
 /// - Header (72 bytes): azimuth_count, gate_count, first_gate_range_km,
 ///   gate_interval_km, max_range_km, scale, offset, radial_count,
-///   data_word_size, mean_elevation, sweep_start_secs, sweep_end_secs
+///   data_word_size, mean_elevation, nyquist_velocity, sweep_start_secs,
+///   sweep_end_secs
 /// - Azimuths: f32 × azimuth_count (sorted)
 /// - Gate data: u8 or u16 × azimuth_count × gate_count (row-major)
 pub struct PrecomputedSweep {
@@ -189,6 +204,9 @@ pub struct PrecomputedSweep {
     pub offset: f32,
     pub radial_count: u32,
     pub mean_elevation: f32,
+    /// Nyquist velocity in m/s from the Archive II radial data block, when
+    /// the source carries one.
+    pub nyquist_velocity: Option<f32>,
     pub sweep_start_secs: f64,
     pub sweep_end_secs: f64,
     pub azimuths: Vec<f32>,
@@ -212,7 +230,7 @@ pub struct PrecomputedSweep {
 ///  45..46   format_version (u8: 0 = legacy, 1 = has radial_times)
 ///  46..48   reserved (2 bytes)
 ///  48..52   mean_elevation (f32)
-///  52..56   reserved (4 bytes, f64 alignment pad)
+///  52..56   nyquist_velocity (f32, m/s; 0 = unknown)
 ///  56..64   sweep_start_secs (f64)
 ///  64..72   sweep_end_secs (f64)
 ///
@@ -254,7 +272,7 @@ impl PrecomputedSweep {
         buf.push(format_version); // 45
         buf.extend_from_slice(&[0u8; 2]); // 46..48 reserved
         buf.extend_from_slice(&self.mean_elevation.to_le_bytes()); // 48..52
-        buf.extend_from_slice(&[0u8; 4]); // 52..56 alignment pad
+        buf.extend_from_slice(&self.nyquist_velocity.unwrap_or(0.0).to_le_bytes()); // 52..56
         buf.extend_from_slice(&self.sweep_start_secs.to_le_bytes()); // 56..64
         buf.extend_from_slice(&self.sweep_end_secs.to_le_bytes()); // 64..72
 
@@ -284,6 +302,182 @@ impl PrecomputedSweep {
 
         buf
     }
+
+    /// Deserialize a blob written by [`to_bytes`](Self::to_bytes).
+    pub fn from_bytes(data: &[u8]) -> Result<Self, String> {
+        let header = parse_sweep_header(data)?;
+        let az = header.azimuth_count as usize;
+        let values = az * header.gate_count as usize;
+        let gv_off = header.gate_values_offset as usize;
+        let expected = gv_off + values * header.data_word_size as usize;
+        if data.len() < expected {
+            return Err(format!(
+                "Sweep blob too small: {} < {} expected",
+                data.len(),
+                expected
+            ));
+        }
+
+        let az_off = header.azimuths_offset as usize;
+        let azimuths = data[az_off..az_off + az * 4]
+            .chunks_exact(4)
+            .map(|b| f32::from_le_bytes(b.try_into().unwrap()))
+            .collect();
+        let radial_times = if header.radial_times_offset > 0 {
+            let rt_off = header.radial_times_offset as usize;
+            data[rt_off..rt_off + az * 8]
+                .chunks_exact(8)
+                .map(|b| f64::from_le_bytes(b.try_into().unwrap()))
+                .collect()
+        } else {
+            Vec::new()
+        };
+        let gate_values = if header.data_word_size == 1 {
+            GateValues::U8(data[gv_off..gv_off + values].to_vec())
+        } else {
+            GateValues::U16(
+                data[gv_off..gv_off + values * 2]
+                    .chunks_exact(2)
+                    .map(|b| u16::from_le_bytes([b[0], b[1]]))
+                    .collect(),
+            )
+        };
+
+        Ok(Self {
+            azimuth_count: header.azimuth_count,
+            gate_count: header.gate_count,
+            first_gate_range_km: header.first_gate_range_km,
+            gate_interval_km: header.gate_interval_km,
+            max_range_km: header.max_range_km,
+            scale: header.scale,
+            offset: header.offset,
+            radial_count: header.radial_count,
+            mean_elevation: header.mean_elevation,
+            nyquist_velocity: header.nyquist_velocity,
+            sweep_start_secs: header.sweep_start_secs,
+            sweep_end_secs: header.sweep_end_secs,
+            azimuths,
+            radial_times,
+            gate_values,
+        })
+    }
+}
+
+/// Builds [`PrecomputedSweep`] test fixtures: evenly spaced radials centred
+/// in their bins, gates from the radar outwards. Defaults to the 8-bit REF
+/// encoding (scale 2, offset 66) on a 0.5° tilt.
+#[cfg(test)]
+pub(crate) struct SweepFixture {
+    azimuth_count: usize,
+    gate_count: usize,
+    first_gate_km: f64,
+    gate_km: f64,
+    scale: f32,
+    offset: f32,
+    elevation: f32,
+    nyquist_velocity: Option<f32>,
+    start_secs: f64,
+    end_secs: f64,
+}
+
+#[cfg(test)]
+impl SweepFixture {
+    pub(crate) fn new(azimuth_count: usize, gate_count: usize, gate_km: f64) -> Self {
+        Self {
+            azimuth_count,
+            gate_count,
+            first_gate_km: 0.0,
+            gate_km,
+            scale: 2.0,
+            offset: 66.0,
+            elevation: 0.5,
+            nyquist_velocity: None,
+            start_secs: 0.0,
+            end_secs: 0.0,
+        }
+    }
+
+    pub(crate) fn first_gate_km(mut self, km: f64) -> Self {
+        self.first_gate_km = km;
+        self
+    }
+
+    /// Raw encoding, `raw = physical * scale + offset`.
+    pub(crate) fn encoding(mut self, scale: f32, offset: f32) -> Self {
+        self.scale = scale;
+        self.offset = offset;
+        self
+    }
+
+    pub(crate) fn elevation(mut self, deg: f32) -> Self {
+        self.elevation = deg;
+        self
+    }
+
+    pub(crate) fn nyquist(mut self, vn: f32) -> Self {
+        self.nyquist_velocity = Some(vn);
+        self
+    }
+
+    pub(crate) fn times(mut self, start_secs: f64, end_secs: f64) -> Self {
+        self.start_secs = start_secs;
+        self.end_secs = end_secs;
+        self
+    }
+
+    /// Radial azimuths, degrees.
+    fn azimuths(&self) -> Vec<f32> {
+        let step = 360.0 / self.azimuth_count as f32;
+        (0..self.azimuth_count)
+            .map(|i| (i as f32 + 0.5) * step)
+            .collect()
+    }
+
+    /// 8-bit gates of `value(azimuth_deg, range_km)`; `None` is no data.
+    pub(crate) fn u8(self, value: impl Fn(f32, f64) -> Option<f32>) -> PrecomputedSweep {
+        let raw = self.encode(value, u8::MAX as f32);
+        self.raw(GateValues::U8(raw.into_iter().map(|r| r as u8).collect()))
+    }
+
+    /// 16-bit gates of `value(azimuth_deg, range_km)`; `None` is no data.
+    pub(crate) fn u16(self, value: impl Fn(f32, f64) -> Option<f32>) -> PrecomputedSweep {
+        let raw = self.encode(value, u16::MAX as f32);
+        self.raw(GateValues::U16(raw.into_iter().map(|r| r as u16).collect()))
+    }
+
+    /// Sweep carrying already-encoded, row-major `gate_values`.
+    pub(crate) fn raw(self, gate_values: GateValues) -> PrecomputedSweep {
+        PrecomputedSweep {
+            azimuth_count: self.azimuth_count as u32,
+            gate_count: self.gate_count as u32,
+            first_gate_range_km: self.first_gate_km,
+            gate_interval_km: self.gate_km,
+            max_range_km: self.first_gate_km + self.gate_count as f64 * self.gate_km,
+            scale: self.scale,
+            offset: self.offset,
+            radial_count: self.azimuth_count as u32,
+            mean_elevation: self.elevation,
+            nyquist_velocity: self.nyquist_velocity,
+            sweep_start_secs: self.start_secs,
+            sweep_end_secs: self.end_secs,
+            azimuths: self.azimuths(),
+            radial_times: Vec::new(),
+            gate_values,
+        }
+    }
+
+    fn encode(&self, value: impl Fn(f32, f64) -> Option<f32>, max_raw: f32) -> Vec<f32> {
+        let mut raw = Vec::with_capacity(self.azimuth_count * self.gate_count);
+        for az in self.azimuths() {
+            for g in 0..self.gate_count {
+                let range_km = self.first_gate_km + (g as f64 + 0.5) * self.gate_km;
+                raw.push(value(az, range_km).map_or(0.0, |v| {
+                    (v * self.scale + self.offset).round().clamp(2.0, max_raw)
+                }));
+            }
+        }
+        raw
+    }
 }
 
 /// Parsed header from a serialized sweep blob, with byte offsets for zero-copy access.
@@ -299,6 +493,8 @@ pub struct SweepHeader {
     /// Bytes per gate value (1 for u8, 2 for u16).
     pub data_word_size: u8,
     pub mean_elevation: f32,
+    /// Nyquist velocity in m/s, `None` when the blob doesn't record one.
+    pub nyquist_velocity: Option<f32>,
     pub sweep_start_secs: f64,
     pub sweep_end_secs: f64,
     /// Byte offset to azimuths array (f32 × azimuth_count)
@@ -332,6 +528,8 @@ pub fn parse_sweep_header(data: &[u8]) -> Result<SweepHeader, String> {
     let data_word_size = data[44];
     let format_version = data[45];
     let mean_elevation = f32::from_le_bytes(data[48..52].try_into().unwrap());
+    let nyquist_velocity = f32::from_le_bytes(data[52..56].try_into().unwrap());
+    let nyquist_velocity = (nyquist_velocity > 0.0).then_some(nyquist_velocity);
     let sweep_start_secs = f64::from_le_bytes(data[56..64].try_into().unwrap());
     let sweep_end_secs = f64::from_le_bytes(data[64..72].try_into().unwrap());
 
@@ -357,6 +555,7 @@ pub fn parse_sweep_header(data: &[u8]) -> Result<SweepHeader, String> {
         radial_count,
         data_word_size,
         mean_elevation,
+        nyquist_velocity,
         sweep_start_secs,
         sweep_end_secs,
         azimuths_offset: azimuths_offset as u32,
@@ -610,10 +809,29 @@ impl ScanIndexEntry {
 pub const ALL_PRODUCTS: &[&str] = &[
     "reflectivity",
     "velocity",
+    "dealiased_velocity",
     "spectrum_width",
     "differential_reflectivity",
     "correlation_coefficient",
     "differential_phase",
+    "clutter_filter_power",
+    "hydrometeor_class",
+    "composite_reflectivity",
+    "echo_tops_18",
+    "echo_tops_30",
+    "echo_tops_50",
+    "vil",
+    "l3_n0q",
+    "l3_n0u",
+    "l3_n0c",
+    "l3_n0x",
+    "l3_dvl",
+    "l3_eet",
+    "l3_ntp",
+    "l3_ncr",
+    "l3_tz0",
+    "l3_tv0",
+    "l3_tzl",
 ];
 
 #[cfg(test)]
@@ -643,6 +861,40 @@ mod tests {
         assert_eq!(key.to_storage_key(), "KDMX|1700000000000|1|reflectivity");
     }
 
+    #[test]
+    fn test_sweep_blob_round_trip() {
+        let sweep = PrecomputedSweep {
+            azimuth_count: 2,
+            gate_count: 3,
+            first_gate_range_km: 2.125,
+            gate_interval_km: 0.25,
+            max_range_km: 2.875,
+            scale: 2.0,
+            offset: 66.0,
+            radial_count: 2,
+            mean_elevation: 0.5,
+            nyquist_velocity: Some(24.5),
+            sweep_start_secs: 1700000000.0,
+            sweep_end_secs: 1700000020.5,
+            azimuths: vec![0.25, 180.75],
+            radial_times: vec![1700000000.0, 1700000010.0],
+            gate_values: GateValues::U16(vec![0, 1, 2, 300, 400, 65535]),
+        };
+        let parsed = PrecomputedSweep::from_bytes(&sweep.to_bytes()).unwrap();
+        assert_eq!(parsed.gate_count, 3);
+        assert_eq!(parsed.azimuths, sweep.azimuths);
+        assert_eq!(parsed.radial_times, sweep.radial_times);
+        assert_eq!(parsed.sweep_end_secs, sweep.sweep_end_secs);
+        assert_eq!(parsed.nyquist_velocity, Some(24.5));
+        let GateValues::U16(values) = parsed.gate_values else {
+            panic!("word size lost");
+        };
+        assert_eq!(values, vec![0, 1, 2, 300, 400, 65535]);
+
+        let bytes = sweep.to_bytes();
+        assert!(PrecomputedSweep::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+    }
+
     #[test]
     fn test_completeness_computation() {
         // Missing
@@ -730,6 +982,7 @@ mod tests {
             offset: 66.0,
             radial_count: 720,
             mean_elevation: 0.5,
+            nyquist_velocity: None,
             sweep_start_secs: 1700000000.5,
             sweep_end_secs: 1700000020.3,
             azimuths: (0..720).map(|i| i as f32 * 0.5).collect(),
@@ -750,6 +1003,7 @@ mod tests {
         assert_eq!(header.radial_count, 720);
         assert_eq!(header.data_word_size, 1);
         assert!((header.mean_elevation - 0.5).abs() < 1e-6);
+        assert_eq!(header.nyquist_velocity, None);
         assert!((header.sweep_start_secs - 1700000000.5).abs() < 1e-10);
         assert!((header.sweep_end_secs - 1700000020.3).abs() < 1e-10);
         assert_eq!(header.azimuths_offset, 72);
@@ -770,6 +1024,7 @@ mod tests {
             offset: 0.0,
             radial_count: 4,
             mean_elevation: 0.5,
+            nyquist_velocity: None,
             sweep_start_secs: 100.0,
             sweep_end_secs: 110.0,
             azimuths: vec![0.0, 90.0, 180.0, 270.0],
@@ -809,6 +1064,7 @@ mod tests {
             offset: 0.0,
             radial_count: 4,
             mean_elevation: 1.3,
+            nyquist_velocity: None,
             sweep_start_secs: 100.0,
             sweep_end_secs: 110.0,
             azimuths: vec![0.0, 90.0, 180.0, 270.0],
@@ -14,24 +14,36 @@
 //! - `SiteId`: Radar site identifier (e.g., "KDMX")
 //! - `ScanKey`: Identifies a volume scan (site + start time)
 //! - `SweepDataKey`: Identifies a sweep (scan + elevation + product)
-//! - `PrecomputedSweep`: GPU-ready sweep data (azimuths, gates, metadata)
+//! - `PrecomputedSweep`: GPU-ready sweep data (azimuths, gates, metadata);
+//!   `moments` decodes it to physical units
 //!
 //! ### Storage Hierarchy
 //! ```text
-//! IndexedDB "nexrad-workbench"
+//! IndexedDB "nexrad-workbench"   (or ~/.cache/nexrad-workbench on the desktop)
 //! ├── sweeps     - Pre-computed sweep blobs (binary, GPU-ready)
 //! └── scan_index - Per-scan metadata with completeness tracking
 //! ```
+//!
+//! ### Backends
+//! `DataFacade` sits on a `RecordStore` (see `store`): IndexedDB in the
+//! browser, an on-disk directory store on the desktop, and an in-memory
+//! store for tests. All share the same eviction and index-merge logic.
 
+#[cfg_attr(target_arch = "wasm32", allow(dead_code))] // Used by tests and the desktop build.
+pub(crate) mod directory;
 pub(crate) mod facade;
+#[cfg(target_arch = "wasm32")]
 pub(crate) mod indexeddb;
 pub(crate) mod keys;
+pub(crate) mod moments;
 pub(crate) mod sites;
+pub(crate) mod store;
 pub(crate) mod vcp;
 
 // Re-export static site data
-pub use sites::{all_sites_sorted, get_site, nearest_site, NEXRAD_SITES};
+pub use sites::{all_sites, all_sites_sorted, get_site, nearest_site, sites_within, RadarType};
 
 // Re-export cache types
 pub use facade::*;
 pub use keys::*;
+pub use store::RecordStore;
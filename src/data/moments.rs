@@ -0,0 +1,132 @@
+//! Physical-unit access to [`PrecomputedSweep`] moments.
+//!
+//! Products computed in the worker from stored sweeps (hydrometeor
+//! classification, QPE, dealiasing, cross-sections) decode raw gates to
+//! physical values, line up moments recorded on different gate grids, and
+//! derive KDP from ΦDP. These helpers keep that in one place.
+
+use super::keys::{GateValues, PrecomputedSweep};
+
+/// Along-radial window length (km) for the KDP slope fit.
+const KDP_WINDOW_KM: f64 = 2.0;
+
+/// Raw gate values → physical, NaN for sentinels (0 = below threshold,
+/// 1 = range folded).
+pub(crate) fn decode_physical(sweep: &PrecomputedSweep) -> Vec<f32> {
+    let convert = |raw: f32| {
+        if raw <= 1.0 {
+            f32::NAN
+        } else {
+            (raw - sweep.offset) / sweep.scale
+        }
+    };
+    match &sweep.gate_values {
+        GateValues::U8(v) => v.iter().map(|&r| convert(r as f32)).collect(),
+        GateValues::U16(v) => v.iter().map(|&r| convert(r as f32)).collect(),
+    }
+}
+
+/// Specific differential phase (°/km) on the reflectivity gate grid, NaN
+/// where ΦDP is too sparse to fit.
+pub(crate) fn specific_differential_phase(
+    reflectivity: &PrecomputedSweep,
+    differential_phase: &PrecomputedSweep,
+) -> Vec<f32> {
+    kdp_from_phase(&resample(differential_phase, reflectivity), reflectivity)
+}
+
+/// KDP as half the range slope of ΦDP already resampled onto `grid`'s gates.
+pub(crate) fn kdp_from_phase(phi: &[f32], grid: &PrecomputedSweep) -> Vec<f32> {
+    let gate_count = grid.gate_count as usize;
+    if phi.is_empty() || gate_count == 0 {
+        return Vec::new();
+    }
+    let half = window_gates(KDP_WINDOW_KM, grid.gate_interval_km);
+    phi.chunks(gate_count)
+        .flat_map(|row| {
+            (0..row.len()).map(move |g| 0.5 * slope(row, g, half, grid.gate_interval_km))
+        })
+        .collect()
+}
+
+/// Nearest-neighbour resample of `src` onto the gate grid of `target`, in
+/// physical units.
+pub(crate) fn resample(src: &PrecomputedSweep, target: &PrecomputedSweep) -> Vec<f32> {
+    let values = decode_physical(src);
+    let src_gates = src.gate_count as usize;
+    let tgt_gates = target.gate_count as usize;
+    if src.azimuths.is_empty() || src_gates == 0 || src.gate_interval_km <= 0.0 {
+        return vec![f32::NAN; target.azimuths.len() * tgt_gates];
+    }
+    let mut out = Vec::with_capacity(target.azimuths.len() * tgt_gates);
+    for &az in &target.azimuths {
+        let a = nearest_azimuth(&src.azimuths, az);
+        for g in 0..tgt_gates {
+            let range_km = target.first_gate_range_km + (g as f64 + 0.5) * target.gate_interval_km;
+            let sg = ((range_km - src.first_gate_range_km) / src.gate_interval_km).floor();
+            out.push(if sg >= 0.0 && (sg as usize) < src_gates {
+                values[a * src_gates + sg as usize]
+            } else {
+                f32::NAN
+            });
+        }
+    }
+    out
+}
+
+/// Index of the radial in sorted `azimuths` closest to `az` (wrapping).
+fn nearest_azimuth(azimuths: &[f32], az: f32) -> usize {
+    let n = azimuths.len();
+    let i = azimuths.partition_point(|&a| a < az);
+    let dist = |j: usize| {
+        let d = (azimuths[j % n] - az).abs();
+        d.min(360.0 - d)
+    };
+    let (lo, hi) = ((i + n - 1) % n, i % n);
+    if dist(lo) <= dist(hi) {
+        lo
+    } else {
+        hi
+    }
+}
+
+/// Half-window in gates for a window of `km` (at least one gate).
+pub(crate) fn window_gates(km: f64, gate_interval_km: f64) -> usize {
+    ((km / gate_interval_km.max(1e-3)) * 0.5).round().max(1.0) as usize
+}
+
+/// Least-squares slope (per km) of `row` around gate `g`, skipping NaNs.
+fn slope(row: &[f32], g: usize, half: usize, gate_km: f64) -> f32 {
+    let (lo, hi) = (g.saturating_sub(half), (g + half + 1).min(row.len()));
+    let (mut n, mut sx, mut sy, mut sxx, mut sxy) = (0.0_f64, 0.0, 0.0, 0.0, 0.0);
+    for (i, &v) in row[lo..hi].iter().enumerate() {
+        if v.is_nan() {
+            continue;
+        }
+        let x = i as f64 * gate_km;
+        let y = v as f64;
+        n += 1.0;
+        sx += x;
+        sy += y;
+        sxx += x * x;
+        sxy += x * y;
+    }
+    let denom = n * sxx - sx * sx;
+    if n < 3.0 || denom.abs() < 1e-12 {
+        return f32::NAN;
+    }
+    ((n * sxy - sx * sy) / denom) as f32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn kdp_from_phase_slope() {
+        // ΦDP rising 4°/km → KDP 2°/km.
+        let gate_km = 0.25;
+        let row: Vec<f32> = (0..20).map(|g| g as f32 * gate_km as f32 * 4.0).collect();
+        assert!((0.5 * slope(&row, 10, 4, gate_km) - 2.0).abs() < 1e-3);
+    }
+}
@@ -1,4 +1,4 @@
-//! NEXRAD radar site locations.
+//! NEXRAD and TDWR radar site locations.
 //!
 //! Data sourced from NOAA/NCEI NEXRAD Stations ArcGIS Feature Service.
 
@@ -16,11 +16,50 @@ pub struct NexradSite {
     /// Longitude in decimal degrees
     pub lon: f64,
     /// Elevation in feet above sea level
-    #[allow(dead_code)]
     pub elevation_ft: i32,
 }
 
+/// Radar network a site belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RadarType {
+    /// WSR-88D (NEXRAD): 250 m gates, Level II archive and real-time feed.
+    Wsr88d,
+    /// Terminal Doppler Weather Radar: 150 m gates over a shorter range,
+    /// Level III products only.
+    Tdwr,
+}
+
+impl RadarType {
+    pub fn label(self) -> &'static str {
+        match self {
+            RadarType::Wsr88d => "WSR-88D",
+            RadarType::Tdwr => "TDWR",
+        }
+    }
+
+    /// Nominal range of the Doppler products, km.
+    pub fn coverage_range_km(self) -> f64 {
+        match self {
+            RadarType::Wsr88d => crate::nexrad::RADAR_COVERAGE_RANGE_KM,
+            RadarType::Tdwr => 90.0,
+        }
+    }
+}
+
 impl NexradSite {
+    /// Site elevation above sea level, km.
+    pub fn altitude_km(&self) -> f64 {
+        self.elevation_ft as f64 * 0.3048e-3
+    }
+
+    pub fn radar_type(&self) -> RadarType {
+        if TDWR_SITES.iter().any(|site| site.id == self.id) {
+            RadarType::Tdwr
+        } else {
+            RadarType::Wsr88d
+        }
+    }
+
     /// Returns a display label for the site (e.g., "KDMX - Des Moines, IA")
     pub fn display_label(&self) -> String {
         match self.state {
@@ -46,7 +85,7 @@ fn title_case(s: &str) -> String {
         .join(" ")
 }
 
-/// All NEXRAD radar sites.
+/// All WSR-88D radar sites.
 pub static NEXRAD_SITES: &[NexradSite] = &[
     // CONUS - WSR-88D sites (K prefix)
     NexradSite {
@@ -1342,7 +1381,11 @@ pub static NEXRAD_SITES: &[NexradSite] = &[
         lon: -27.32167,
         elevation_ft: 3334,
     },
-    // TDWR sites (Terminal Doppler Weather Radar - T prefix)
+];
+
+/// Terminal Doppler Weather Radars at major airports. Their data is only
+/// distributed as Level III products.
+pub static TDWR_SITES: &[NexradSite] = &[
     NexradSite {
         id: "TADW",
         name: "ANDREWS AFB",
@@ -1705,20 +1748,26 @@ pub static NEXRAD_SITES: &[NexradSite] = &[
     },
 ];
 
+/// Every site of both radar types.
+pub fn all_sites() -> impl Iterator<Item = &'static NexradSite> {
+    NEXRAD_SITES.iter().chain(TDWR_SITES)
+}
+
 /// Look up a site by its ID (case-insensitive).
 pub fn get_site(id: &str) -> Option<&'static NexradSite> {
     let id_upper = id.to_uppercase();
-    NEXRAD_SITES.iter().find(|site| site.id == id_upper)
+    all_sites().find(|site| site.id == id_upper)
 }
 
 /// Get all sites sorted by ID.
 pub fn all_sites_sorted() -> Vec<&'static NexradSite> {
-    let mut sites: Vec<_> = NEXRAD_SITES.iter().collect();
+    let mut sites: Vec<_> = all_sites().collect();
     sites.sort_by_key(|s| s.id);
     sites
 }
 
-/// Find the nearest NEXRAD site to a given latitude/longitude.
+/// Find the nearest WSR-88D site to a given latitude/longitude. TDWRs are
+/// skipped: they have no Level II data to start from.
 pub fn nearest_site(lat: f64, lon: f64) -> Option<&'static NexradSite> {
     NEXRAD_SITES.iter().min_by(|a, b| {
         haversine_distance(lat, lon, a.lat, a.lon)
@@ -1727,6 +1776,18 @@ pub fn nearest_site(lat: f64, lon: f64) -> Option<&'static NexradSite> {
     })
 }
 
+/// WSR-88D sites within `max_km` of a latitude/longitude with their
+/// distance in kilometres, nearest first.
+pub fn sites_within(lat: f64, lon: f64, max_km: f64) -> Vec<(&'static NexradSite, f64)> {
+    let mut sites: Vec<_> = NEXRAD_SITES
+        .iter()
+        .map(|site| (site, haversine_distance(lat, lon, site.lat, site.lon)))
+        .filter(|(_, km)| *km <= max_km)
+        .collect();
+    sites.sort_by(|a, b| a.1.total_cmp(&b.1));
+    sites
+}
+
 /// Haversine distance between two lat/lon points in kilometers.
 fn haversine_distance(lat1: f64, lon1: f64, lat2: f64, lon2: f64) -> f64 {
     let r = 6371.0; // Earth radius in km
@@ -1757,5 +1818,28 @@ mod tests {
     #[test]
     fn test_site_count() {
         assert!(NEXRAD_SITES.len() > 150, "Should have many NEXRAD sites");
+        assert!(TDWR_SITES.len() > 40, "Should have the TDWR network");
+    }
+
+    #[test]
+    fn test_radar_types() {
+        assert_eq!(get_site("TOKC").unwrap().radar_type(), RadarType::Tdwr);
+        assert_eq!(get_site("TJUA").unwrap().radar_type(), RadarType::Wsr88d);
+        assert_eq!(all_sites().count(), NEXRAD_SITES.len() + TDWR_SITES.len());
+        // TOKC is closer to downtown Oklahoma City, but only WSR-88Ds are
+        // suggested.
+        assert_eq!(nearest_site(35.47, -97.52).unwrap().id, "KTLX");
+    }
+
+    #[test]
+    fn test_sites_within() {
+        let ktlx = get_site("KTLX").unwrap();
+        let nearby = sites_within(ktlx.lat, ktlx.lon, 250.0);
+        assert_eq!(nearby[0].0.id, "KTLX");
+        assert!(nearby.iter().any(|(site, _)| site.id == "KINX"));
+        assert!(nearby
+            .iter()
+            .all(|(site, km)| { site.radar_type() == RadarType::Wsr88d && *km <= 250.0 }));
+        assert!(nearby.windows(2).all(|w| w[0].1 <= w[1].1));
     }
 }
@@ -0,0 +1,578 @@
+//! Storage backends for the sweep cache.
+//!
+//! [`RecordStore`] is the handful of primitives a backend provides: sweep
+//! blobs and scan index entries by key, a combined delete, and a bulk clear.
+//! The cache semantics — listing, LRU eviction, incremental index merges and
+//! replacing overlapping scans — are provided methods built on those
+//! primitives, so every backend behaves the same.
+//!
+//! Backends:
+//! - [`IndexedDbRecordStore`](super::indexeddb::IndexedDbRecordStore): the browser cache
+//! - `MemoryRecordStore`: process-local, for tests
+//! - [`DirectoryRecordStore`](super::directory::DirectoryRecordStore): files under a
+//!   directory, for native builds
+
+use crate::data::keys::*;
+#[cfg(test)]
+use std::{cell::RefCell, collections::HashMap, rc::Rc};
+
+/// Structured error type for storage operations.
+#[derive(Debug)]
+#[allow(dead_code)]
+pub enum DataError {
+    /// The database has not been opened yet.
+    NotOpen,
+    /// A transaction failed.
+    TransactionFailed(String),
+    /// A request failed.
+    RequestFailed(String),
+    /// Storage quota exceeded.
+    QuotaExceeded { available_mb: f64, required_mb: f64 },
+    /// The requested key was not found.
+    NotFound,
+    /// Deserialization of stored data failed.
+    DeserializationError(String),
+    /// Filesystem error from the directory store.
+    Io(String),
+}
+
+impl std::fmt::Display for DataError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DataError::NotOpen => write!(f, "Database not open"),
+            DataError::TransactionFailed(msg) => write!(f, "Transaction failed: {}", msg),
+            DataError::RequestFailed(msg) => write!(f, "Request failed: {}", msg),
+            DataError::QuotaExceeded {
+                available_mb,
+                required_mb,
+            } => write!(
+                f,
+                "Insufficient storage quota: {:.1} MB available, {:.1} MB required",
+                available_mb, required_mb
+            ),
+            DataError::NotFound => write!(f, "Not found"),
+            DataError::DeserializationError(msg) => write!(f, "Deserialization error: {}", msg),
+            DataError::Io(msg) => write!(f, "I/O error: {}", msg),
+        }
+    }
+}
+
+/// Storage quota estimate, e.g. from `navigator.storage.estimate()`.
+#[derive(Debug, Clone, Copy)]
+pub struct StorageQuotaEstimate {
+    /// Total quota granted (bytes).
+    pub quota: u64,
+    /// Current usage across all storage mechanisms (bytes).
+    pub usage: u64,
+}
+
+impl StorageQuotaEstimate {
+    /// Remaining bytes available.
+    pub fn remaining(&self) -> u64 {
+        self.quota.saturating_sub(self.usage)
+    }
+}
+
+/// A sweep cache backend.
+///
+/// Stores are cheap handles (`Clone` shares the underlying storage) used
+/// from a single thread, so the futures carry no `Send` bound.
+// Never used as `dyn` and never sent across threads, so the un-nameable
+// future types the lint warns about are fine.
+#[allow(async_fn_in_trait)]
+pub trait RecordStore: Clone {
+    /// Opens the store, creating its schema or layout as needed. Safe to
+    /// call repeatedly.
+    async fn open(&self) -> Result<(), DataError>;
+
+    /// Stores several sweep blobs in one write.
+    async fn put_sweeps_batch(&self, items: &[(String, Vec<u8>)]) -> Result<(), DataError>;
+
+    /// Gets a sweep blob by storage key.
+    async fn get_sweep(&self, key: &str) -> Result<Option<Vec<u8>>, DataError>;
+
+    /// Gets a scan index entry by storage key.
+    async fn get_scan_index_entry(
+        &self,
+        storage_key: &str,
+    ) -> Result<Option<ScanIndexEntry>, DataError>;
+
+    /// Writes or updates a scan index entry.
+    async fn put_scan_index_entry(&self, entry: &ScanIndexEntry) -> Result<(), DataError>;
+
+    /// Every scan index entry, in no particular order.
+    async fn all_scan_index_entries(&self) -> Result<Vec<ScanIndexEntry>, DataError>;
+
+    /// Deletes sweep blobs and a scan index entry together (in one
+    /// transaction where the backend has them). Missing keys are ignored.
+    async fn delete_records(&self, sweep_keys: &[String], index_key: &str)
+        -> Result<(), DataError>;
+
+    /// Clears all data from all stores.
+    async fn clear_all(&self) -> Result<(), DataError>;
+
+    /// Quota imposed outside the app's own limit, `None` if there isn't one
+    /// to report.
+    async fn storage_quota(&self) -> Option<StorageQuotaEstimate> {
+        None
+    }
+
+    // ========================================================================
+    // Cache semantics, shared by every backend
+    // ========================================================================
+
+    /// Gets scan availability information.
+    async fn scan_availability(&self, scan: &ScanKey) -> Result<Option<ScanIndexEntry>, DataError> {
+        self.get_scan_index_entry(&scan.to_storage_key()).await
+    }
+
+    /// Lists all scans for a site within a time window.
+    async fn list_scans(
+        &self,
+        site: &SiteId,
+        start: UnixMillis,
+        end: UnixMillis,
+    ) -> Result<Vec<ScanIndexEntry>, DataError> {
+        let entries = self.all_scan_index_entries().await?;
+
+        let mut scans: Vec<ScanIndexEntry> = entries
+            .into_iter()
+            .filter(|entry| {
+                entry.scan.site.0 == site.0
+                    && entry.scan.scan_start >= start
+                    && entry.scan.scan_start <= end
+            })
+            .collect();
+
+        scans.sort_by_key(|s| s.scan.scan_start.0);
+        Ok(scans)
+    }
+
+    /// Gets total cache size across all scans.
+    async fn total_cache_size(&self) -> Result<u64, DataError> {
+        let entries = self.all_scan_index_entries().await?;
+        Ok(entries.iter().map(|e| e.total_size_bytes).sum())
+    }
+
+    /// Gets scans sorted by last_accessed_at (oldest first) for LRU eviction.
+    async fn get_lru_scans(&self, limit: u32) -> Result<Vec<ScanIndexEntry>, DataError> {
+        let mut scans = self.all_scan_index_entries().await?;
+        scans.sort_by_key(|s| s.last_accessed_at.0);
+        scans.truncate(limit as usize);
+        Ok(scans)
+    }
+
+    /// Deletes a scan and all its sweep blobs.
+    /// Returns the number of bytes freed.
+    async fn delete_scan(&self, scan: &ScanKey) -> Result<u64, DataError> {
+        // Get the scan entry to know its size and elevation structure
+        let scan_entry = self.scan_availability(scan).await?;
+        let bytes_freed = scan_entry.as_ref().map(|e| e.total_size_bytes).unwrap_or(0);
+
+        // Build list of all possible sweep keys for this scan
+        let sweep_keys: Vec<String> = scan_entry
+            .as_ref()
+            .and_then(|entry| entry.sweeps.as_ref())
+            .map(|sweeps| {
+                sweeps
+                    .iter()
+                    .flat_map(|sweep| {
+                        ALL_PRODUCTS.iter().map(|product| {
+                            SweepDataKey::new(scan.clone(), sweep.elevation_number, *product)
+                                .to_storage_key()
+                        })
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        self.delete_records(&sweep_keys, &scan.to_storage_key())
+            .await?;
+
+        log::debug!(
+            "Deleted scan {} ({} sweep blobs, {} bytes freed)",
+            scan,
+            sweep_keys.len(),
+            bytes_freed
+        );
+        Ok(bytes_freed)
+    }
+
+    /// Evicts scans until total cache size is below target_bytes.
+    /// Returns the number of scans evicted.
+    async fn evict_to_size(&self, target_bytes: u64) -> Result<u32, DataError> {
+        let mut current_size = self.total_cache_size().await?;
+        let mut evicted_count = 0u32;
+
+        while current_size > target_bytes {
+            let lru_scans = self.get_lru_scans(1).await?;
+
+            if lru_scans.is_empty() {
+                break;
+            }
+
+            let oldest = &lru_scans[0];
+            let bytes_freed = self.delete_scan(&oldest.scan).await?;
+
+            current_size = current_size.saturating_sub(bytes_freed);
+            evicted_count += 1;
+
+            log::debug!(
+                "Evicted scan {} (freed {} bytes, {} remaining)",
+                oldest.scan,
+                bytes_freed,
+                current_size
+            );
+        }
+
+        if evicted_count > 0 {
+            log::info!(
+                "LRU eviction complete: evicted {} scans, cache now {} bytes",
+                evicted_count,
+                current_size
+            );
+        }
+
+        Ok(evicted_count)
+    }
+
+    /// Merges incremental data into an existing scan index entry, or creates one
+    /// if it doesn't exist yet. Used by per-chunk ingest to build up scan metadata
+    /// incrementally as elevations complete.
+    ///
+    /// Reads and writes separately (no await inside an IndexedDB readwrite
+    /// transaction).
+    async fn merge_scan_index_entry(
+        &self,
+        partial: &ScanIndexEntry,
+        new_records: u32,
+        new_size_bytes: u64,
+        new_sweeps: &[SweepMeta],
+    ) -> Result<(), DataError> {
+        let existing = self.get_scan_index_entry(&partial.storage_key()).await?;
+        let merged = merge_index_entry(existing, partial, new_records, new_size_bytes, new_sweeps);
+        self.put_scan_index_entry(&merged).await
+    }
+
+    /// Deletes all existing scans for a site whose time range overlaps with the
+    /// given archive scan. Returns the number of scans deleted.
+    ///
+    /// A scan overlaps if its [start, end] range intersects the archive scan's
+    /// range. Scans without an end timestamp use start as the end.
+    /// The scan matching `exclude_key` (the archive scan itself) is skipped.
+    async fn delete_overlapping_scans(
+        &self,
+        site: &SiteId,
+        archive_start: UnixMillis,
+        archive_end_ms: i64,
+        exclude_key: &ScanKey,
+    ) -> Result<u32, DataError> {
+        let all_entries = self.all_scan_index_entries().await?;
+
+        // Find overlapping scans for this site
+        let mut to_delete: Vec<ScanKey> = Vec::new();
+        for entry in &all_entries {
+            if entry.scan.site.0 != site.0 {
+                continue;
+            }
+            if entry.scan == *exclude_key {
+                continue;
+            }
+            let existing_start = entry.scan.scan_start.0;
+            let existing_end = entry
+                .end_timestamp_secs
+                .map(|s| s * 1000)
+                .unwrap_or(existing_start);
+
+            // Two ranges overlap if start_a <= end_b AND start_b <= end_a
+            if archive_start.0 <= existing_end && existing_start <= archive_end_ms {
+                to_delete.push(entry.scan.clone());
+            }
+        }
+
+        if to_delete.is_empty() {
+            return Ok(0);
+        }
+
+        let count = to_delete.len() as u32;
+        for scan in &to_delete {
+            log::debug!("Deleting overlapping scan {} (replaced by archive)", scan);
+            self.delete_scan(scan).await?;
+        }
+
+        Ok(count)
+    }
+}
+
+/// Fold a partial index entry from one ingest step into what's stored.
+fn merge_index_entry(
+    existing: Option<ScanIndexEntry>,
+    partial: &ScanIndexEntry,
+    new_records: u32,
+    new_size_bytes: u64,
+    new_sweeps: &[SweepMeta],
+) -> ScanIndexEntry {
+    let Some(mut entry) = existing else {
+        // No existing entry — create from partial
+        let mut entry = partial.clone();
+        entry.present_records = new_records;
+        entry.total_size_bytes = new_size_bytes;
+        entry.has_precomputed_sweeps = true;
+        if !new_sweeps.is_empty() {
+            entry.sweeps = Some(new_sweeps.to_vec());
+        }
+        return entry;
+    };
+
+    entry.present_records += new_records;
+    entry.total_size_bytes += new_size_bytes;
+    entry.updated_at = UnixMillis::now();
+    entry.has_precomputed_sweeps = true;
+
+    // Merge VCP if newly available
+    if !entry.has_vcp && partial.has_vcp {
+        entry.has_vcp = true;
+        entry.vcp = partial.vcp.clone();
+        if let Some(ref vcp) = entry.vcp {
+            entry.expected_records = Some(vcp.elevations.len() as u32);
+        }
+    }
+
+    // Merge file_name if not set
+    if entry.file_name.is_none() {
+        entry.file_name = partial.file_name.clone();
+    }
+
+    // Append new sweeps. A sweep already in the index (same elevation and
+    // start) is re-sent when products are added to it later, e.g. volume
+    // products at the end of the volume.
+    if !new_sweeps.is_empty() {
+        let sweeps = entry.sweeps.get_or_insert_with(Vec::new);
+        for new_sweep in new_sweeps {
+            if let Some(existing) = sweeps.iter_mut().find(|s| {
+                s.elevation_number == new_sweep.elevation_number && s.start == new_sweep.start
+            }) {
+                for product in &new_sweep.available_products {
+                    if !existing.available_products.contains(product) {
+                        existing.available_products.push(product.clone());
+                    }
+                }
+            } else {
+                sweeps.push(new_sweep.clone());
+            }
+        }
+    }
+
+    // Update end timestamp to max
+    if let Some(new_end) = partial.end_timestamp_secs {
+        entry.end_timestamp_secs = Some(
+            entry
+                .end_timestamp_secs
+                .map(|old| old.max(new_end))
+                .unwrap_or(new_end),
+        );
+    }
+
+    entry
+}
+
+// ============================================================================
+// In-memory backend
+// ============================================================================
+
+#[cfg(test)]
+#[derive(Default)]
+struct MemoryTables {
+    sweeps: HashMap<String, Vec<u8>>,
+    scan_index: HashMap<String, ScanIndexEntry>,
+}
+
+/// Sweep store held in memory. Clones share the same tables; nothing
+/// survives the process.
+#[cfg(test)]
+#[derive(Clone, Default)]
+pub struct MemoryRecordStore {
+    tables: Rc<RefCell<MemoryTables>>,
+}
+
+#[cfg(test)]
+impl MemoryRecordStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[cfg(test)]
+impl RecordStore for MemoryRecordStore {
+    async fn open(&self) -> Result<(), DataError> {
+        Ok(())
+    }
+
+    async fn put_sweeps_batch(&self, items: &[(String, Vec<u8>)]) -> Result<(), DataError> {
+        let mut tables = self.tables.borrow_mut();
+        for (key, data) in items {
+            tables.sweeps.insert(key.clone(), data.clone());
+        }
+        Ok(())
+    }
+
+    async fn get_sweep(&self, key: &str) -> Result<Option<Vec<u8>>, DataError> {
+        Ok(self.tables.borrow().sweeps.get(key).cloned())
+    }
+
+    async fn get_scan_index_entry(
+        &self,
+        storage_key: &str,
+    ) -> Result<Option<ScanIndexEntry>, DataError> {
+        Ok(self.tables.borrow().scan_index.get(storage_key).cloned())
+    }
+
+    async fn put_scan_index_entry(&self, entry: &ScanIndexEntry) -> Result<(), DataError> {
+        self.tables
+            .borrow_mut()
+            .scan_index
+            .insert(entry.storage_key(), entry.clone());
+        Ok(())
+    }
+
+    async fn all_scan_index_entries(&self) -> Result<Vec<ScanIndexEntry>, DataError> {
+        Ok(self.tables.borrow().scan_index.values().cloned().collect())
+    }
+
+    async fn delete_records(
+        &self,
+        sweep_keys: &[String],
+        index_key: &str,
+    ) -> Result<(), DataError> {
+        let mut tables = self.tables.borrow_mut();
+        for key in sweep_keys {
+            tables.sweeps.remove(key);
+        }
+        tables.scan_index.remove(index_key);
+        Ok(())
+    }
+
+    async fn clear_all(&self) -> Result<(), DataError> {
+        let mut tables = self.tables.borrow_mut();
+        tables.sweeps.clear();
+        tables.scan_index.clear();
+        Ok(())
+    }
+}
+
+/// Drive a store future to completion. The memory and directory stores never
+/// yield, so a single poll finishes them.
+#[cfg(test)]
+pub(crate) fn block_on<F: std::future::Future>(future: F) -> F::Output {
+    let mut context = std::task::Context::from_waker(std::task::Waker::noop());
+    match std::pin::pin!(future).poll(&mut context) {
+        std::task::Poll::Ready(output) => output,
+        std::task::Poll::Pending => panic!("store future yielded"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(site: &str, start_secs: i64, end_secs: i64, size: u64) -> ScanIndexEntry {
+        let mut entry = ScanIndexEntry::new(ScanKey::from_secs(site, start_secs));
+        entry.end_timestamp_secs = Some(end_secs);
+        entry.total_size_bytes = size;
+        entry.last_accessed_at = UnixMillis::from_secs(start_secs);
+        entry.sweeps = Some(vec![sweep(1, start_secs as f64, &["reflectivity"])]);
+        entry
+    }
+
+    fn sweep(elevation_number: u8, start: f64, products: &[&str]) -> SweepMeta {
+        SweepMeta {
+            start,
+            end: start + 20.0,
+            elevation: 0.5,
+            elevation_number,
+            start_azimuth: 0.0,
+            available_products: products.iter().map(|p| p.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn evicts_least_recently_accessed_scans_with_their_sweeps() {
+        let store = MemoryRecordStore::new();
+        for (start, size) in [(1000, 40), (2000, 40), (3000, 40)] {
+            let e = entry("KTLX", start, start + 300, size);
+            let key = SweepDataKey::new(e.scan.clone(), 1, "reflectivity").to_storage_key();
+            block_on(store.put_sweeps_batch(&[(key, vec![0u8; 4])])).unwrap();
+            block_on(store.put_scan_index_entry(&e)).unwrap();
+        }
+
+        assert_eq!(block_on(store.evict_to_size(80)).unwrap(), 1);
+        let oldest = ScanKey::from_secs("KTLX", 1000);
+        assert!(block_on(store.scan_availability(&oldest))
+            .unwrap()
+            .is_none());
+        let oldest_sweep = SweepDataKey::new(oldest, 1, "reflectivity").to_storage_key();
+        assert!(block_on(store.get_sweep(&oldest_sweep)).unwrap().is_none());
+        assert_eq!(block_on(store.total_cache_size()).unwrap(), 80);
+    }
+
+    #[test]
+    fn merge_accumulates_sweeps_products_and_size() {
+        let store = MemoryRecordStore::new();
+        let partial = entry("KTLX", 1000, 1100, 0);
+        let first = [sweep(1, 1000.0, &["reflectivity"])];
+        block_on(store.merge_scan_index_entry(&partial, 2, 100, &first)).unwrap();
+
+        let mut later = partial.clone();
+        later.end_timestamp_secs = Some(1300);
+        let more = [
+            sweep(1, 1000.0, &["reflectivity", "velocity"]),
+            sweep(2, 1030.0, &["reflectivity"]),
+        ];
+        block_on(store.merge_scan_index_entry(&later, 1, 50, &more)).unwrap();
+
+        let merged = block_on(store.scan_availability(&partial.scan))
+            .unwrap()
+            .unwrap();
+        assert_eq!(merged.present_records, 3);
+        assert_eq!(merged.total_size_bytes, 150);
+        assert_eq!(merged.end_timestamp_secs, Some(1300));
+        let sweeps = merged.sweeps.unwrap();
+        assert_eq!(sweeps.len(), 2);
+        assert_eq!(sweeps[0].available_products, ["reflectivity", "velocity"]);
+    }
+
+    #[test]
+    fn overlapping_scans_are_replaced_but_other_sites_kept() {
+        let store = MemoryRecordStore::new();
+        for e in [
+            entry("KTLX", 1000, 1200, 10),
+            entry("KTLX", 1250, 1500, 10),
+            entry("KTLX", 2000, 2300, 10),
+            entry("KINX", 1000, 1200, 10),
+        ] {
+            block_on(store.put_scan_index_entry(&e)).unwrap();
+        }
+
+        let archive = ScanKey::from_secs("KTLX", 1000);
+        let deleted = block_on(store.delete_overlapping_scans(
+            &SiteId("KTLX".to_string()),
+            UnixMillis::from_secs(1100),
+            1_400_000,
+            &archive,
+        ))
+        .unwrap();
+        assert_eq!(deleted, 1);
+
+        let site = SiteId("KTLX".to_string());
+        let remaining: Vec<i64> = block_on(store.list_scans(
+            &site,
+            UnixMillis::from_secs(0),
+            UnixMillis::from_secs(10_000),
+        ))
+        .unwrap()
+        .iter()
+        .map(|e| e.scan.scan_start.as_secs())
+        .collect();
+        assert_eq!(remaining, [1000, 2000]);
+    }
+}
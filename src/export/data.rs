@@ -0,0 +1,61 @@
+//! Data exports: the cached volume as CF-Radial, and the rendered product
+//! gridded to GeoTIFF or CSV.
+//!
+//! CF-Radial files are written by a decode worker from IndexedDB (see
+//! `nexrad::cf_radial`) and only downloaded here. Grids are built on the
+//! main thread from the renderer's copy of the displayed sweep, so they
+//! hold exactly the values the inspector reports, storm motion included.
+
+use super::download::{save_array, save_bytes};
+use super::geotiff::encode_geotiff;
+use super::grid::{encode_csv, grid_sweep, GRID_CELL_DEG};
+use crate::nexrad::polar::PolarSweep;
+use crate::nexrad::CfRadialData;
+use crate::state::{format_bytes, ExportFormat};
+
+/// Download a CF-Radial file returned by the worker.
+pub fn save_cf_radial(data: &CfRadialData) -> Result<String, String> {
+    let filename = format!(
+        "{}.{}",
+        data.request.file_stem,
+        ExportFormat::CfRadial.extension()
+    );
+    save_array(&filename, ExportFormat::CfRadial.mime_type(), &data.bytes)?;
+    let size = format_bytes(data.byte_len() as u64);
+    log::info!(
+        "export: saved {} ({} sweeps, {})",
+        filename,
+        data.sweep_count,
+        size
+    );
+    Ok(format!("Saved {} ({})", filename, size))
+}
+
+/// Grid `sweep` around the site and download it as GeoTIFF or CSV.
+/// `value_name` heads the CSV value column.
+pub fn save_grid(
+    format: ExportFormat,
+    sweep: &PolarSweep,
+    site_lat: f64,
+    site_lon: f64,
+    value_name: &str,
+    file_stem: &str,
+) -> Result<String, String> {
+    let grid = grid_sweep(sweep, site_lat, site_lon, GRID_CELL_DEG);
+    let bytes = match format {
+        ExportFormat::GeoTiff => encode_geotiff(&grid)?,
+        ExportFormat::Csv => encode_csv(&grid, value_name).into_bytes(),
+        _ => return Err(format!("{} is not a grid format", format.label())),
+    };
+    let filename = format!("{}.{}", file_stem, format.extension());
+    save_bytes(&filename, format.mime_type(), &bytes)?;
+    let size = format_bytes(bytes.len() as u64);
+    log::info!(
+        "export: saved {} ({}x{} grid, {})",
+        filename,
+        grid.columns,
+        grid.rows,
+        size
+    );
+    Ok(format!("Saved {} ({})", filename, size))
+}
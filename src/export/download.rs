@@ -0,0 +1,99 @@
+//! Hand an encoded file to the user: a browser download on the web, a file
+//! in the downloads directory on the desktop.
+
+#[cfg(not(target_arch = "wasm32"))]
+pub use native::{save_array, save_bytes};
+#[cfg(target_arch = "wasm32")]
+pub use web::{save_array, save_bytes};
+
+#[cfg(target_arch = "wasm32")]
+mod web {
+    use wasm_bindgen::prelude::*;
+    use wasm_bindgen::JsCast;
+
+    /// How long the object URL outlives the click, so the browser has started
+    /// reading the blob before it is revoked.
+    const REVOKE_DELAY_MS: i32 = 10_000;
+
+    /// Save `bytes` as `filename` through a temporary object URL and anchor.
+    pub fn save_bytes(filename: &str, mime_type: &str, bytes: &[u8]) -> Result<(), String> {
+        save_array(filename, mime_type, &js_sys::Uint8Array::from(bytes))
+    }
+
+    /// Like [`save_bytes`] for data already in JS memory, such as a buffer
+    /// posted back by a worker, without copying it through WASM.
+    pub fn save_array(
+        filename: &str,
+        mime_type: &str,
+        bytes: &js_sys::Uint8Array,
+    ) -> Result<(), String> {
+        let window = web_sys::window().ok_or("no window")?;
+        let document = window.document().ok_or("no document")?;
+
+        let parts = js_sys::Array::of1(bytes);
+        let options = web_sys::BlobPropertyBag::new();
+        options.set_type(mime_type);
+        let blob = web_sys::Blob::new_with_u8_array_sequence_and_options(&parts, &options)
+            .map_err(|_| "create Blob failed")?;
+        let url = web_sys::Url::create_object_url_with_blob(&blob)
+            .map_err(|_| "createObjectURL failed")?;
+
+        let anchor: web_sys::HtmlAnchorElement = document
+            .create_element("a")
+            .map_err(|_| "create anchor failed")?
+            .dyn_into()
+            .map_err(|_| "element was not an anchor")?;
+        anchor.set_href(&url);
+        anchor.set_download(filename);
+        anchor.click();
+
+        let revoke = Closure::once_into_js(move || {
+            let _ = web_sys::Url::revoke_object_url(&url);
+        });
+        window
+            .set_timeout_with_callback_and_timeout_and_arguments_0(
+                revoke.unchecked_ref(),
+                REVOKE_DELAY_MS,
+            )
+            .map_err(|_| "setTimeout failed")?;
+        Ok(())
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+mod native {
+    use std::path::{Path, PathBuf};
+
+    /// Write `bytes` to `filename` in the downloads directory, adding a
+    /// numeric suffix rather than overwriting an earlier export.
+    pub fn save_bytes(filename: &str, _mime_type: &str, bytes: &[u8]) -> Result<(), String> {
+        let dir = crate::platform::download_dir();
+        std::fs::create_dir_all(&dir).map_err(|e| format!("{}: {}", dir.display(), e))?;
+        let path = unused_path(&dir, filename);
+        std::fs::write(&path, bytes).map_err(|e| format!("{}: {}", path.display(), e))?;
+        log::info!("Saved {}", path.display());
+        Ok(())
+    }
+
+    /// Worker results are already in process memory on the desktop, so
+    /// this is [`save_bytes`].
+    pub fn save_array(filename: &str, mime_type: &str, bytes: &[u8]) -> Result<(), String> {
+        save_bytes(filename, mime_type, bytes)
+    }
+
+    /// `dir/filename`, or `dir/stem (n).ext` for the first free `n`.
+    fn unused_path(dir: &Path, filename: &str) -> PathBuf {
+        let path = dir.join(filename);
+        if !path.exists() {
+            return path;
+        }
+        let (stem, ext) = match filename.rsplit_once('.') {
+            Some((stem, ext)) if !stem.is_empty() => (stem, format!(".{}", ext)),
+            _ => (filename, String::new()),
+        };
+        (1..)
+            .map(|n| dir.join(format!("{} ({}){}", stem, n, ext)))
+            .find(|p| !p.exists())
+            .unwrap_or(path)
+    }
+}
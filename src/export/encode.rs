@@ -0,0 +1,230 @@
+//! Image encoding for exports.
+//!
+//! Everything here works on plain RGBA8 buffers and has no browser or GPU
+//! dependency: captured frames are fitted to the export resolution, then
+//! written as a PNG still or an infinitely looping animated GIF.
+
+/// A straight (non-premultiplied) RGBA8 image, row-major from the top.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RgbaImage {
+    pub width: usize,
+    pub height: usize,
+    pub pixels: Vec<u8>,
+}
+
+impl RgbaImage {
+    pub fn new(width: usize, height: usize, pixels: Vec<u8>) -> Self {
+        debug_assert_eq!(pixels.len(), width * height * 4);
+        Self {
+            width,
+            height,
+            pixels,
+        }
+    }
+
+    fn pixel(&self, x: usize, y: usize) -> &[u8] {
+        let i = (y * self.width + x) * 4;
+        &self.pixels[i..i + 4]
+    }
+}
+
+/// Scale `image` to fit inside `width`×`height`, centred, and fill the
+/// margins with `background`.
+///
+/// Letterboxing rather than cropping keeps the overlays at the canvas edges
+/// (color scale, info box, compass) in frame. Downscaling averages every
+/// source pixel under each output pixel, so thin map lines and text stay
+/// legible; upscaling repeats pixels.
+pub fn fit_to(image: &RgbaImage, width: usize, height: usize, background: [u8; 4]) -> RgbaImage {
+    let mut pixels = background.repeat(width * height);
+    if image.width == 0 || image.height == 0 || width == 0 || height == 0 {
+        return RgbaImage::new(width, height, pixels);
+    }
+
+    let scale = (width as f64 / image.width as f64).min(height as f64 / image.height as f64);
+    let fit_w = ((image.width as f64 * scale).round() as usize).clamp(1, width);
+    let fit_h = ((image.height as f64 * scale).round() as usize).clamp(1, height);
+    let (left, top) = ((width - fit_w) / 2, (height - fit_h) / 2);
+
+    let sx = image.width as f64 / fit_w as f64;
+    let sy = image.height as f64 / fit_h as f64;
+    let span = |dst: usize, step: f64, limit: usize| {
+        let start = ((dst as f64 * step) as usize).min(limit - 1);
+        let end = (((dst + 1) as f64 * step).ceil() as usize).clamp(start + 1, limit);
+        start..end
+    };
+
+    for y in 0..fit_h {
+        let rows = span(y, sy, image.height);
+        for x in 0..fit_w {
+            let cols = span(x, sx, image.width);
+            let mut sum = [0u32; 4];
+            for row in rows.clone() {
+                for col in cols.clone() {
+                    for (s, &c) in sum.iter_mut().zip(image.pixel(col, row)) {
+                        *s += c as u32;
+                    }
+                }
+            }
+            let n = (rows.len() * cols.len()) as u32;
+            let i = ((top + y) * width + left + x) * 4;
+            for (out, s) in pixels[i..i + 4].iter_mut().zip(sum) {
+                *out = ((s + n / 2) / n) as u8;
+            }
+        }
+    }
+    RgbaImage::new(width, height, pixels)
+}
+
+/// Encode a still as an RGBA PNG.
+pub fn encode_png(image: &RgbaImage) -> Result<Vec<u8>, String> {
+    let mut out = Vec::new();
+    let mut encoder = png::Encoder::new(&mut out, image.width as u32, image.height as u32);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    let mut writer = encoder
+        .write_header()
+        .map_err(|e| format!("PNG header: {}", e))?;
+    writer
+        .write_image_data(&image.pixels)
+        .map_err(|e| format!("PNG data: {}", e))?;
+    writer.finish().map_err(|e| format!("PNG finish: {}", e))?;
+    Ok(out)
+}
+
+/// NeuQuant sampling factor for GIF palettes (1 = best, 30 = fastest).
+const GIF_QUANTIZE_SPEED: i32 = 10;
+
+/// Encode `frames` as a looping animated GIF showing each for `delay_ms`.
+///
+/// Every frame gets its own 256-color palette, so a product's color table
+/// survives even when consecutive frames differ a lot. All frames must share
+/// one size.
+pub fn encode_gif(frames: &[RgbaImage], delay_ms: u32) -> Result<Vec<u8>, String> {
+    let first = frames.first().ok_or("GIF needs at least one frame")?;
+    let (width, height) = (first.width, first.height);
+    if frames
+        .iter()
+        .any(|f| f.width != width || f.height != height)
+    {
+        return Err("GIF frames differ in size".into());
+    }
+    let (w, h) = match (u16::try_from(width), u16::try_from(height)) {
+        (Ok(w), Ok(h)) if w > 0 && h > 0 => (w, h),
+        _ => return Err(format!("GIF size {}x{} out of range", width, height)),
+    };
+    // GIF delays are in centiseconds.
+    let delay = (delay_ms / 10).clamp(1, u16::MAX as u32) as u16;
+
+    let mut out = Vec::new();
+    {
+        let mut encoder =
+            gif::Encoder::new(&mut out, w, h, &[]).map_err(|e| format!("GIF header: {}", e))?;
+        encoder
+            .set_repeat(gif::Repeat::Infinite)
+            .map_err(|e| format!("GIF header: {}", e))?;
+        for image in frames {
+            // Captures are opaque; force it so no pixel becomes transparent.
+            let mut pixels = image.pixels.clone();
+            pixels.chunks_exact_mut(4).for_each(|p| p[3] = 255);
+            let mut frame = gif::Frame::from_rgba_speed(w, h, &mut pixels, GIF_QUANTIZE_SPEED);
+            frame.delay = delay;
+            encoder
+                .write_frame(&frame)
+                .map_err(|e| format!("GIF frame: {}", e))?;
+        }
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn solid(width: usize, height: usize, rgba: [u8; 4]) -> RgbaImage {
+        RgbaImage::new(width, height, rgba.repeat(width * height))
+    }
+
+    #[test]
+    fn fit_to_letterboxes_to_target_aspect() {
+        // A 4x2 image in a 2x2 frame: scaled to 2x1, centred in rows 0..1
+        // with the background row below (rounding puts the padding last).
+        let image = solid(4, 2, [255, 0, 0, 255]);
+        let fitted = fit_to(&image, 2, 2, [0, 0, 0, 255]);
+        assert_eq!(fitted.pixel(0, 0), &[255, 0, 0, 255]);
+        assert_eq!(fitted.pixel(1, 0), &[255, 0, 0, 255]);
+        assert_eq!(fitted.pixel(0, 1), &[0, 0, 0, 255]);
+
+        // Wider frame: padding left and right.
+        let image = solid(2, 2, [0, 255, 0, 255]);
+        let fitted = fit_to(&image, 4, 2, [9, 9, 9, 255]);
+        assert_eq!(fitted.pixel(0, 0), &[9, 9, 9, 255]);
+        assert_eq!(fitted.pixel(1, 1), &[0, 255, 0, 255]);
+        assert_eq!(fitted.pixel(2, 0), &[0, 255, 0, 255]);
+        assert_eq!(fitted.pixel(3, 1), &[9, 9, 9, 255]);
+    }
+
+    #[test]
+    fn fit_to_averages_when_downscaling() {
+        // Alternating black/white columns blend to mid grey.
+        let mut image = solid(8, 8, [0, 0, 0, 255]);
+        for y in 0..8 {
+            for x in (1..8).step_by(2) {
+                let i = (y * 8 + x) * 4;
+                image.pixels[i..i + 3].copy_from_slice(&[255, 255, 255]);
+            }
+        }
+        let fitted = fit_to(&image, 2, 2, [0; 4]);
+        assert_eq!((fitted.width, fitted.height), (2, 2));
+        assert!(fitted
+            .pixels
+            .chunks_exact(4)
+            .all(|p| p == [128, 128, 128, 255]));
+    }
+
+    #[test]
+    fn fit_to_upscales_by_repeating() {
+        let image = solid(1, 1, [10, 20, 30, 255]);
+        let fitted = fit_to(&image, 3, 3, [0; 4]);
+        assert_eq!(fitted, solid(3, 3, [10, 20, 30, 255]));
+    }
+
+    #[test]
+    fn png_round_trips() {
+        let image = solid(5, 3, [1, 2, 3, 255]);
+        let bytes = encode_png(&image).unwrap();
+        let decoder = png::Decoder::new(bytes.as_slice());
+        let mut reader = decoder.read_info().unwrap();
+        let mut buf = vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut buf).unwrap();
+        assert_eq!((info.width, info.height), (5, 3));
+        assert_eq!(&buf[..info.buffer_size()], image.pixels.as_slice());
+    }
+
+    #[test]
+    fn gif_has_every_frame_and_delay() {
+        let frames = [
+            solid(4, 4, [255, 0, 0, 255]),
+            solid(4, 4, [0, 255, 0, 255]),
+            solid(4, 4, [0, 0, 255, 255]),
+        ];
+        let bytes = encode_gif(&frames, 250).unwrap();
+        let mut options = gif::DecodeOptions::new();
+        options.set_color_output(gif::ColorOutput::RGBA);
+        let mut decoder = options.read_info(bytes.as_slice()).unwrap();
+        let mut decoded = Vec::new();
+        while let Some(frame) = decoder.read_next_frame().unwrap() {
+            decoded.push((frame.delay, frame.buffer[..4].to_vec()));
+        }
+        assert_eq!(decoded.len(), 3);
+        assert!(decoded.iter().all(|(delay, _)| *delay == 25));
+        assert_eq!(decoded[1].1, vec![0, 255, 0, 255]);
+    }
+
+    #[test]
+    fn gif_rejects_mismatched_frames() {
+        assert!(encode_gif(&[], 100).is_err());
+        let frames = [solid(4, 4, [0; 4]), solid(2, 2, [0; 4])];
+        assert!(encode_gif(&frames, 100).is_err());
+    }
+}
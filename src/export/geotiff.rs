@@ -0,0 +1,115 @@
+//! GeoTIFF encoding of latitude/longitude grids.
+//!
+//! Writes a single-band float32 image in geographic WGS 84 coordinates
+//! (EPSG:4326) with the three GeoTIFF tags GIS tools need: pixel scale,
+//! a tie point at the north-west corner, and a key directory naming the
+//! CRS. Empty cells hold [`NODATA`], advertised through the GDAL nodata
+//! tag.
+
+use std::io::Cursor;
+
+use tiff::encoder::{colortype, TiffEncoder};
+use tiff::tags::Tag;
+
+use super::grid::LatLonGrid;
+
+/// Value written to cells without data.
+pub const NODATA: f32 = -9999.0;
+
+/// GeoKeyDirectory: version 1.1.0 with four keys —
+/// GTModelType = geographic, GTRasterType = PixelIsArea,
+/// GeographicType = WGS 84, GeogAngularUnits = degrees.
+const GEO_KEYS: [u16; 20] = [
+    1, 1, 0, 4, //
+    1024, 0, 1, 2, //
+    1025, 0, 1, 1, //
+    2048, 0, 1, 4326, //
+    2054, 0, 1, 9102,
+];
+
+/// Encode `grid` as a georeferenced float32 GeoTIFF.
+pub fn encode_geotiff(grid: &LatLonGrid) -> Result<Vec<u8>, String> {
+    let (width, height) = match (u32::try_from(grid.columns), u32::try_from(grid.rows)) {
+        (Ok(w), Ok(h)) if w > 0 && h > 0 => (w, h),
+        _ => return Err(format!("grid {}x{} out of range", grid.columns, grid.rows)),
+    };
+    let values: Vec<f32> = grid
+        .values
+        .iter()
+        .map(|&v| if v.is_nan() { NODATA } else { v })
+        .collect();
+
+    let mut out = Cursor::new(Vec::new());
+    let mut encoder = TiffEncoder::new(&mut out).map_err(|e| format!("TIFF header: {}", e))?;
+    let mut image = encoder
+        .new_image::<colortype::Gray32Float>(width, height)
+        .map_err(|e| format!("TIFF image: {}", e))?;
+    let tags = image.encoder();
+    let tag_err = |e: tiff::TiffError| format!("GeoTIFF tags: {}", e);
+    tags.write_tag(
+        Tag::ModelPixelScaleTag,
+        &[grid.cell_deg, grid.cell_deg, 0.0][..],
+    )
+    .map_err(tag_err)?;
+    tags.write_tag(
+        Tag::ModelTiepointTag,
+        &[0.0, 0.0, 0.0, grid.west, grid.north, 0.0][..],
+    )
+    .map_err(tag_err)?;
+    tags.write_tag(Tag::GeoKeyDirectoryTag, &GEO_KEYS[..])
+        .map_err(tag_err)?;
+    tags.write_tag(Tag::GdalNodata, NODATA.to_string().as_str())
+        .map_err(tag_err)?;
+    image
+        .write_data(&values)
+        .map_err(|e| format!("TIFF data: {}", e))?;
+    Ok(out.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tiff::decoder::{Decoder, DecodingResult};
+
+    #[test]
+    fn writes_georeferenced_float_image() {
+        let grid = LatLonGrid {
+            west: -98.0,
+            north: 36.0,
+            cell_deg: 0.25,
+            columns: 3,
+            rows: 2,
+            values: vec![1.0, f32::NAN, 3.0, 4.0, 5.0, -6.5],
+        };
+        let bytes = encode_geotiff(&grid).unwrap();
+        let mut decoder = Decoder::new(Cursor::new(bytes)).unwrap();
+        assert_eq!(decoder.dimensions().unwrap(), (3, 2));
+
+        let tiepoint = decoder.get_tag_f64_vec(Tag::ModelTiepointTag).unwrap();
+        assert_eq!(tiepoint, vec![0.0, 0.0, 0.0, -98.0, 36.0, 0.0]);
+        let scale = decoder.get_tag_f64_vec(Tag::ModelPixelScaleTag).unwrap();
+        assert_eq!(scale, vec![0.25, 0.25, 0.0]);
+        let keys = decoder.get_tag_u16_vec(Tag::GeoKeyDirectoryTag).unwrap();
+        assert_eq!(keys, GEO_KEYS.to_vec());
+        let nodata = decoder.get_tag_ascii_string(Tag::GdalNodata).unwrap();
+        assert_eq!(nodata, "-9999");
+
+        let DecodingResult::F32(values) = decoder.read_image().unwrap() else {
+            panic!("expected float32 samples");
+        };
+        assert_eq!(values, vec![1.0, NODATA, 3.0, 4.0, 5.0, -6.5]);
+    }
+
+    #[test]
+    fn rejects_empty_grid() {
+        let grid = LatLonGrid {
+            west: 0.0,
+            north: 0.0,
+            cell_deg: 1.0,
+            columns: 0,
+            rows: 0,
+            values: Vec::new(),
+        };
+        assert!(encode_geotiff(&grid).is_err());
+    }
+}
@@ -0,0 +1,148 @@
+//! Resample a polar sweep onto a regular latitude/longitude grid.
+//!
+//! Cells are sampled nearest-neighbour at their centres, using the same
+//! flat-earth range and azimuth as the canvas inspector, so a grid cell
+//! holds the value the inspector shows at that point.
+
+use std::fmt::Write;
+
+use crate::geo::KM_PER_DEG;
+use crate::nexrad::polar::PolarSweep;
+
+/// Grid spacing in degrees of latitude and longitude (about 1.1 km N-S).
+pub const GRID_CELL_DEG: f64 = 0.01;
+
+/// A north-up grid of values, NaN where there is no data.
+pub struct LatLonGrid {
+    /// Longitude of the grid's west edge.
+    pub west: f64,
+    /// Latitude of the grid's north edge.
+    pub north: f64,
+    pub cell_deg: f64,
+    pub columns: usize,
+    pub rows: usize,
+    /// Row-major from the north-west corner.
+    pub values: Vec<f32>,
+}
+
+impl LatLonGrid {
+    /// Latitude and longitude of the centre of cell (`column`, `row`).
+    pub fn cell_center(&self, column: usize, row: usize) -> (f64, f64) {
+        (
+            self.north - (row as f64 + 0.5) * self.cell_deg,
+            self.west + (column as f64 + 0.5) * self.cell_deg,
+        )
+    }
+}
+
+/// Grid `sweep` around the radar at `site_lat`, `site_lon`, covering the
+/// sweep's full range.
+pub fn grid_sweep(sweep: &PolarSweep, site_lat: f64, site_lon: f64, cell_deg: f64) -> LatLonGrid {
+    let max_range_km = sweep.first_gate_km + sweep.gate_count as f64 * sweep.gate_interval_km;
+    let cos_lat = site_lat.to_radians().cos().max(0.01);
+    let half_lat = max_range_km / KM_PER_DEG;
+    let half_lon = half_lat / cos_lat;
+    let rows = (2.0 * half_lat / cell_deg).ceil() as usize;
+    let columns = (2.0 * half_lon / cell_deg).ceil() as usize;
+
+    let mut grid = LatLonGrid {
+        west: site_lon - columns as f64 * cell_deg / 2.0,
+        north: site_lat + rows as f64 * cell_deg / 2.0,
+        cell_deg,
+        columns,
+        rows,
+        values: Vec::with_capacity(columns * rows),
+    };
+    let index = sweep.azimuth_index();
+    for row in 0..rows {
+        for column in 0..columns {
+            let (lat, lon) = grid.cell_center(column, row);
+            let dlat = lat - site_lat;
+            let dlon = (lon - site_lon) * cos_lat;
+            let range_km = (dlat * dlat + dlon * dlon).sqrt() * KM_PER_DEG;
+            let azimuth_deg = dlon.atan2(dlat).to_degrees().rem_euclid(360.0);
+            grid.values
+                .push(sweep.value_at(&index, azimuth_deg as f32, range_km));
+        }
+    }
+    grid
+}
+
+/// Long-format CSV, one `latitude,longitude,<value_name>` row per cell
+/// with data. Empty cells are left out.
+pub fn encode_csv(grid: &LatLonGrid, value_name: &str) -> String {
+    let mut out = format!("latitude,longitude,{}\n", value_name);
+    for row in 0..grid.rows {
+        for column in 0..grid.columns {
+            let value = grid.values[row * grid.columns + column];
+            if value.is_nan() {
+                continue;
+            }
+            let (lat, lon) = grid.cell_center(column, row);
+            let _ = writeln!(out, "{:.5},{:.5},{:.3}", lat, lon, value);
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Sixty 6° radials, gates of 10 km from 0 km, each holding its radial
+    /// number, except radial 7 gate 0 which is empty.
+    fn sweep() -> PolarSweep {
+        let gate_count = 5;
+        let mut values: Vec<f32> = (0..60)
+            .flat_map(|r| std::iter::repeat_n(r as f32, gate_count))
+            .collect();
+        values[7 * gate_count] = f32::NAN;
+        PolarSweep {
+            azimuths: (0..60).map(|r| r as f32 * 6.0 + 3.0).collect(),
+            gate_count,
+            first_gate_km: 0.0,
+            gate_interval_km: 10.0,
+            values,
+        }
+    }
+
+    #[test]
+    fn grid_covers_the_sweep_around_the_site() {
+        let grid = grid_sweep(&sweep(), 0.0, 10.0, 0.1);
+        // 50 km each way at 111 km/deg: ±0.45°.
+        assert_eq!((grid.columns, grid.rows), (10, 10));
+        assert!((grid.north - 0.5).abs() < 1e-9);
+        assert!((grid.west - 9.5).abs() < 1e-9);
+        assert_eq!(grid.values.len(), 100);
+    }
+
+    #[test]
+    fn cells_take_the_radial_under_their_centre() {
+        let grid = grid_sweep(&sweep(), 0.0, 10.0, 0.1);
+        let at = |column: usize, row: usize| grid.values[row * grid.columns + column];
+        // Centre at 59° from the site: nearest radial is 9 (57°).
+        assert_eq!(at(7, 3), 9.0);
+        // Due north-east: radial 7 (45°), gate 2.
+        assert_eq!(at(6, 3), 7.0);
+        // Corners are beyond 50 km.
+        assert!(at(0, 0).is_nan());
+        // Radial 7 gate 0 is empty.
+        assert!(at(5, 4).is_nan());
+    }
+
+    #[test]
+    fn csv_lists_only_cells_with_data() {
+        let grid = LatLonGrid {
+            west: -100.0,
+            north: 40.0,
+            cell_deg: 0.5,
+            columns: 2,
+            rows: 1,
+            values: vec![f32::NAN, 12.5],
+        };
+        assert_eq!(
+            encode_csv(&grid, "reflectivity_dbz"),
+            "latitude,longitude,reflectivity_dbz\n39.75000,-99.25000,12.500\n"
+        );
+    }
+}
@@ -0,0 +1,297 @@
+//! Runs export jobs: steps playback through the frames, waits for each one
+//! to finish rendering, then captures the canvas.
+//!
+//! Capture uses an egui screenshot, which reads back the composited frame,
+//! so the image holds exactly what the canvas shows: the radar texture, geo
+//! layers and every overlay (color scale, info, scale bar, compass). The
+//! region under the canvas is cut out and letterboxed to the export size.
+
+use std::sync::Arc;
+
+use eframe::egui;
+
+use super::download::save_bytes;
+use super::encode::{encode_gif, encode_png, fit_to, RgbaImage};
+use super::file_stem;
+use crate::state::{format_bytes, AppState, ElevationSelection, ExportFormat, ExportProgress};
+use crate::ui::colors::canvas as canvas_colors;
+
+/// Upper bound on GIF length. Frames are held uncompressed until encoding.
+const MAX_GIF_FRAMES: usize = 200;
+
+/// Consecutive idle frames required before a seeked frame is captured, so
+/// the GPU upload that follows a decode has been painted.
+const SETTLE_FRAMES: u32 = 3;
+
+/// Capture anyway if a frame has not rendered after this long.
+const FRAME_TIMEOUT_MS: f64 = 5_000.0;
+
+/// A rendered sweep ending within this many seconds of a frame time is
+/// that frame.
+const FRAME_MATCH_SECS: f64 = 1.0;
+
+/// Screenshot tag, so only our own captures are consumed.
+struct ExportCapture;
+
+enum Phase {
+    /// Move playback to the next frame time.
+    Seek,
+    /// Waiting for the seeked frame to render.
+    Settle { started_ms: f64, idle_frames: u32 },
+    /// Ask egui for a screenshot of the next paint.
+    RequestCapture,
+    /// Waiting for the screenshot to arrive.
+    Capture,
+}
+
+struct Job {
+    format: ExportFormat,
+    /// Output size; taken from the first capture when the resolution is
+    /// "Canvas size".
+    size: Option<(usize, usize)>,
+    delay_ms: u32,
+    /// Sweep end times to capture. Empty for a still.
+    times: Vec<f64>,
+    frames: Vec<RgbaImage>,
+    phase: Phase,
+    /// Playback position to return to when the job ends.
+    restore_position: f64,
+    file_stem: String,
+}
+
+#[derive(Default)]
+pub struct ExportManager {
+    job: Option<Job>,
+}
+
+impl ExportManager {
+    /// Called every frame, after playback has advanced and renders have been
+    /// requested.
+    pub fn tick(&mut self, ctx: &egui::Context, state: &mut AppState) {
+        if std::mem::take(&mut state.export.cancel_requested) {
+            if let Some(job) = self.job.take() {
+                end_job(&job, state);
+                state.export.last_result = Some(Err("Export cancelled".into()));
+            }
+        }
+        // Data formats are started by the app, which owns the renderer and
+        // the workers.
+        if state.export.format.is_image()
+            && std::mem::take(&mut state.export.start_requested)
+            && self.job.is_none()
+        {
+            match start_job(state) {
+                Ok(job) => self.job = Some(job),
+                Err(message) => state.export.last_result = Some(Err(message)),
+            }
+        }
+
+        let Some(job) = self.job.as_mut() else {
+            return;
+        };
+        ctx.request_repaint();
+
+        match job.phase {
+            Phase::Seek => {
+                let target = job.times[job.frames.len()];
+                state.playback_state.playing = false;
+                state.playback_state.set_playback_position(target);
+                job.phase = Phase::Settle {
+                    started_ms: crate::platform::now_ms(),
+                    idle_frames: 0,
+                };
+            }
+            Phase::Settle {
+                started_ms,
+                ref mut idle_frames,
+            } => {
+                let target = job.times[job.frames.len()];
+                let rendered = state
+                    .viz_state
+                    .rendered_sweep_end_secs
+                    .is_some_and(|end| (end - target).abs() <= FRAME_MATCH_SECS);
+                if rendered && !state.session_stats.pipeline.is_active() {
+                    *idle_frames += 1;
+                } else {
+                    *idle_frames = 0;
+                }
+                let timed_out = crate::platform::now_ms() - started_ms > FRAME_TIMEOUT_MS;
+                if timed_out {
+                    log::warn!("export: frame at {} did not render in time", target);
+                }
+                if *idle_frames >= SETTLE_FRAMES || timed_out {
+                    job.phase = Phase::RequestCapture;
+                }
+            }
+            Phase::RequestCapture => {
+                ctx.send_viewport_cmd(egui::ViewportCommand::Screenshot(egui::UserData::new(
+                    ExportCapture,
+                )));
+                job.phase = Phase::Capture;
+            }
+            Phase::Capture => {
+                let Some(image) = take_screenshot(ctx) else {
+                    return;
+                };
+                let Some(canvas_rect) = state.export.canvas_rect else {
+                    return;
+                };
+                let region = image.region(&canvas_rect, Some(ctx.pixels_per_point()));
+                let captured =
+                    RgbaImage::new(region.size[0], region.size[1], region.as_raw().to_vec());
+                let (width, height) = *job.size.get_or_insert((captured.width, captured.height));
+                let background = canvas_colors::background(state.is_dark).to_array();
+                job.frames
+                    .push(fit_to(&captured, width, height, background));
+
+                let total = job.times.len().max(1);
+                state.export.progress = Some(ExportProgress {
+                    captured: job.frames.len(),
+                    total,
+                });
+                if job.frames.len() < total {
+                    job.phase = Phase::Seek;
+                    return;
+                }
+
+                let job = self.job.take().expect("job checked above");
+                end_job(&job, state);
+                state.export.last_result = Some(save(&job));
+            }
+        }
+    }
+}
+
+/// Validate the request and set up a job for it.
+fn start_job(state: &mut AppState) -> Result<Job, String> {
+    if state.export.canvas_rect.is_none() {
+        return Err("Nothing to export yet".into());
+    }
+    let format = state.export.format;
+    let times = match format {
+        ExportFormat::Png => Vec::new(),
+        ExportFormat::Gif => {
+            if state.live_mode_state.is_active() {
+                return Err("Stop live mode to export an animation".into());
+            }
+            let range = state
+                .playback_state
+                .selection_range()
+                .ok_or("Select a time range on the timeline first")?;
+            let times = frame_times(state, range);
+            if times.is_empty() {
+                return Err("No cached sweeps in the selected range".into());
+            }
+            if times.len() > MAX_GIF_FRAMES {
+                return Err(format!(
+                    "{} frames selected; the limit is {}",
+                    times.len(),
+                    MAX_GIF_FRAMES
+                ));
+            }
+            times
+        }
+        _ => return Err(format!("{} is not an image format", format.label())),
+    };
+
+    let stem_time = times
+        .first()
+        .copied()
+        .or(state.viz_state.rendered_sweep_end_secs)
+        .unwrap_or_else(|| state.playback_state.playback_position());
+    let file_stem = file_stem(
+        &state.viz_state.site_id,
+        state.viz_state.product.short_code(),
+        stem_time,
+    );
+
+    state.export.progress = Some(ExportProgress {
+        captured: 0,
+        total: times.len().max(1),
+    });
+    state.export.last_result = None;
+    state.playback_state.playing = false;
+
+    Ok(Job {
+        format,
+        size: state.export.resolution.size(),
+        delay_ms: state.export.frame_delay_ms,
+        phase: if times.is_empty() {
+            Phase::RequestCapture
+        } else {
+            Phase::Seek
+        },
+        times,
+        frames: Vec::new(),
+        restore_position: state.playback_state.playback_position(),
+        file_stem,
+    })
+}
+
+/// Sweep end times inside `range` that macro playback would step through
+/// with the current elevation selection.
+fn frame_times(state: &AppState, range: (f64, f64)) -> Vec<f64> {
+    let timeline = &state.radar_timeline;
+    let mut times = match &state.viz_state.elevation_selection {
+        ElevationSelection::Fixed {
+            elevation_number, ..
+        } => timeline.matching_sweep_end_times_by_number(*elevation_number, Some(range)),
+        ElevationSelection::Latest | ElevationSelection::LowestAvailable => {
+            timeline.all_sweep_end_times(Some(range))
+        }
+    };
+    times.retain(|t| (range.0..=range.1).contains(t));
+    times.sort_by(f64::total_cmp);
+    times.dedup();
+    times
+}
+
+/// The export screenshot delivered this frame, if any.
+fn take_screenshot(ctx: &egui::Context) -> Option<Arc<egui::ColorImage>> {
+    ctx.input(|i| {
+        i.raw.events.iter().find_map(|event| match event {
+            egui::Event::Screenshot {
+                user_data, image, ..
+            } if user_data
+                .data
+                .as_ref()
+                .is_some_and(|data| data.is::<ExportCapture>()) =>
+            {
+                Some(image.clone())
+            }
+            _ => None,
+        })
+    })
+}
+
+/// Clear progress and put playback back where the user left it.
+fn end_job(job: &Job, state: &mut AppState) {
+    state.export.progress = None;
+    if !job.times.is_empty() {
+        state
+            .playback_state
+            .set_playback_position(job.restore_position);
+    }
+}
+
+/// Encode the captured frames and hand the file to the browser.
+fn save(job: &Job) -> Result<String, String> {
+    let bytes = match job.format {
+        ExportFormat::Png => encode_png(job.frames.first().ok_or("No frame captured")?)?,
+        ExportFormat::Gif => encode_gif(&job.frames, job.delay_ms)?,
+        _ => return Err(format!("{} is not an image format", job.format.label())),
+    };
+    let filename = format!("{}.{}", job.file_stem, job.format.extension());
+    save_bytes(&filename, job.format.mime_type(), &bytes)?;
+    log::info!(
+        "export: saved {} ({} frames, {})",
+        filename,
+        job.frames.len(),
+        format_bytes(bytes.len() as u64)
+    );
+    Ok(format!(
+        "Saved {} ({})",
+        filename,
+        format_bytes(bytes.len() as u64)
+    ))
+}
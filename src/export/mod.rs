@@ -0,0 +1,34 @@
+//! Export of the map view and of radar data.
+//!
+//! Images: a still exports the canvas as it is now; an animation steps
+//! playback through every sweep in the timeline selection and captures each
+//! one. Frames are fitted to a fixed output size and encoded in pure Rust
+//! (see `encode`).
+//!
+//! Data: the cached volume as CF-Radial NetCDF, or the rendered product on
+//! a lat/lon grid as GeoTIFF or CSV (see `data`).
+//!
+//! Everything is handed to the browser as a file download.
+
+mod data;
+mod download;
+pub(crate) mod encode;
+mod geotiff;
+pub(crate) mod grid;
+mod manager;
+pub(crate) mod netcdf;
+
+pub use data::{save_cf_radial, save_grid};
+pub use manager::ExportManager;
+
+/// Download file name stem: `SITE_CODE_YYYYmmdd_HHMMSS`.
+pub fn file_stem(site_id: &str, code: &str, secs: f64) -> String {
+    format!(
+        "{}_{}_{}",
+        site_id,
+        code,
+        chrono::DateTime::from_timestamp(secs as i64, 0)
+            .map(|t| t.format("%Y%m%d_%H%M%S").to_string())
+            .unwrap_or_default(),
+    )
+}
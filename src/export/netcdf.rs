@@ -0,0 +1,399 @@
+//! Minimal NetCDF classic writer.
+//!
+//! Writes the 64-bit-offset variant of the classic format (CDF-2), which
+//! every NetCDF reader accepts and which needs no HDF5. Only fixed-size
+//! dimensions are supported; there is no record (unlimited) dimension.
+//! Layout follows the NetCDF classic format specification: a big-endian
+//! header listing dimensions, global attributes and variables, then each
+//! variable's data in order, padded to four bytes.
+
+const MAGIC: &[u8; 4] = b"CDF\x02";
+const NC_DIMENSION: u32 = 0x0A;
+const NC_VARIABLE: u32 = 0x0B;
+const NC_ATTRIBUTE: u32 = 0x0C;
+
+/// Typed values of an attribute or variable.
+#[derive(Clone, Debug, PartialEq)]
+pub enum NcData {
+    Byte(Vec<i8>),
+    Char(Vec<u8>),
+    Short(Vec<i16>),
+    Int(Vec<i32>),
+    Float(Vec<f32>),
+    Double(Vec<f64>),
+}
+
+impl NcData {
+    /// Fixed-width, zero-padded strings for a `(n, string_length)` char
+    /// variable. Longer strings are truncated.
+    pub fn strings<S: AsRef<str>>(values: &[S], width: usize) -> Self {
+        let mut bytes = vec![0u8; values.len() * width];
+        for (chunk, value) in bytes.chunks_exact_mut(width).zip(values) {
+            let value = value.as_ref().as_bytes();
+            let n = value.len().min(width);
+            chunk[..n].copy_from_slice(&value[..n]);
+        }
+        NcData::Char(bytes)
+    }
+
+    fn nc_type(&self) -> u32 {
+        match self {
+            NcData::Byte(_) => 1,
+            NcData::Char(_) => 2,
+            NcData::Short(_) => 3,
+            NcData::Int(_) => 4,
+            NcData::Float(_) => 5,
+            NcData::Double(_) => 6,
+        }
+    }
+
+    fn len(&self) -> usize {
+        match self {
+            NcData::Byte(v) => v.len(),
+            NcData::Char(v) => v.len(),
+            NcData::Short(v) => v.len(),
+            NcData::Int(v) => v.len(),
+            NcData::Float(v) => v.len(),
+            NcData::Double(v) => v.len(),
+        }
+    }
+
+    fn element_size(&self) -> usize {
+        match self {
+            NcData::Byte(_) | NcData::Char(_) => 1,
+            NcData::Short(_) => 2,
+            NcData::Int(_) | NcData::Float(_) => 4,
+            NcData::Double(_) => 8,
+        }
+    }
+
+    /// Values big-endian, padded with zeros to a multiple of four bytes.
+    fn write(&self, out: &mut Vec<u8>) {
+        let start = out.len();
+        match self {
+            NcData::Byte(v) => out.extend(v.iter().map(|&b| b as u8)),
+            NcData::Char(v) => out.extend_from_slice(v),
+            NcData::Short(v) => v.iter().for_each(|x| out.extend(x.to_be_bytes())),
+            NcData::Int(v) => v.iter().for_each(|x| out.extend(x.to_be_bytes())),
+            NcData::Float(v) => v.iter().for_each(|x| out.extend(x.to_be_bytes())),
+            NcData::Double(v) => v.iter().for_each(|x| out.extend(x.to_be_bytes())),
+        }
+        pad(out, out.len() - start);
+    }
+}
+
+impl From<&str> for NcData {
+    fn from(value: &str) -> Self {
+        NcData::Char(value.as_bytes().to_vec())
+    }
+}
+
+impl From<String> for NcData {
+    fn from(value: String) -> Self {
+        NcData::Char(value.into_bytes())
+    }
+}
+
+impl From<i8> for NcData {
+    fn from(value: i8) -> Self {
+        NcData::Byte(vec![value])
+    }
+}
+
+impl From<i16> for NcData {
+    fn from(value: i16) -> Self {
+        NcData::Short(vec![value])
+    }
+}
+
+impl From<i32> for NcData {
+    fn from(value: i32) -> Self {
+        NcData::Int(vec![value])
+    }
+}
+
+impl From<f32> for NcData {
+    fn from(value: f32) -> Self {
+        NcData::Float(vec![value])
+    }
+}
+
+impl From<f64> for NcData {
+    fn from(value: f64) -> Self {
+        NcData::Double(vec![value])
+    }
+}
+
+pub struct NcVariable {
+    name: String,
+    dims: Vec<usize>,
+    attributes: Vec<(String, NcData)>,
+    data: NcData,
+}
+
+impl NcVariable {
+    /// Add an attribute, returning the variable for chaining.
+    pub fn attr(&mut self, name: &str, value: impl Into<NcData>) -> &mut Self {
+        self.attributes.push((name.to_string(), value.into()));
+        self
+    }
+
+    #[cfg(test)]
+    pub fn data(&self) -> &NcData {
+        &self.data
+    }
+
+    #[cfg(test)]
+    pub fn dims(&self) -> &[usize] {
+        &self.dims
+    }
+
+    #[cfg(test)]
+    pub fn attribute(&self, name: &str) -> Option<&NcData> {
+        find_attribute(&self.attributes, name)
+    }
+
+    /// Bytes the data occupies on disk, including padding.
+    fn vsize(&self) -> usize {
+        (self.data.len() * self.data.element_size()).div_ceil(4) * 4
+    }
+}
+
+/// An in-memory NetCDF dataset.
+#[derive(Default)]
+pub struct NcFile {
+    dims: Vec<(String, usize)>,
+    attributes: Vec<(String, NcData)>,
+    variables: Vec<NcVariable>,
+}
+
+impl NcFile {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a fixed-size dimension and return its id.
+    pub fn add_dimension(&mut self, name: &str, len: usize) -> usize {
+        self.dims.push((name.to_string(), len));
+        self.dims.len() - 1
+    }
+
+    /// Add a global attribute.
+    pub fn set_attribute(&mut self, name: &str, value: impl Into<NcData>) {
+        self.attributes.push((name.to_string(), value.into()));
+    }
+
+    /// Add a variable over `dims` (outermost first; empty for a scalar).
+    /// `data` must hold exactly one value per element.
+    pub fn add_variable(
+        &mut self,
+        name: &str,
+        dims: &[usize],
+        data: NcData,
+    ) -> Result<&mut NcVariable, String> {
+        let mut expected = 1usize;
+        for &dim in dims {
+            let (_, len) = self
+                .dims
+                .get(dim)
+                .ok_or_else(|| format!("{}: unknown dimension {}", name, dim))?;
+            expected *= len;
+        }
+        if data.len() != expected {
+            return Err(format!(
+                "{}: {} values for {} elements",
+                name,
+                data.len(),
+                expected
+            ));
+        }
+        self.variables.push(NcVariable {
+            name: name.to_string(),
+            dims: dims.to_vec(),
+            attributes: Vec::new(),
+            data,
+        });
+        Ok(self.variables.last_mut().expect("just pushed"))
+    }
+
+    /// Serialize the dataset. Each variable's values are dropped once
+    /// written, so large datasets are not held twice.
+    pub fn into_bytes(self) -> Vec<u8> {
+        // The header's size does not depend on the data offsets it holds,
+        // so lay it out once to find where the data starts.
+        let header_len = self.header(&vec![0; self.variables.len()]).len();
+        let mut offsets = Vec::with_capacity(self.variables.len());
+        let mut offset = header_len as u64;
+        for var in &self.variables {
+            offsets.push(offset);
+            offset += var.vsize() as u64;
+        }
+
+        let mut out = self.header(&offsets);
+        out.reserve(offset as usize - header_len);
+        for var in self.variables {
+            var.data.write(&mut out);
+        }
+        out
+    }
+
+    /// Look up a variable by name.
+    #[cfg(test)]
+    pub fn variable(&self, name: &str) -> Option<&NcVariable> {
+        self.variables.iter().find(|v| v.name == name)
+    }
+
+    /// Look up a global attribute by name.
+    #[cfg(test)]
+    pub fn attribute(&self, name: &str) -> Option<&NcData> {
+        find_attribute(&self.attributes, name)
+    }
+
+    fn header(&self, offsets: &[u64]) -> Vec<u8> {
+        let mut out = Vec::new();
+        out.extend_from_slice(MAGIC);
+        put_u32(&mut out, 0); // numrecs
+
+        list_tag(&mut out, NC_DIMENSION, self.dims.len());
+        for (name, len) in &self.dims {
+            put_name(&mut out, name);
+            put_u32(&mut out, *len as u32);
+        }
+
+        put_attributes(&mut out, &self.attributes);
+
+        list_tag(&mut out, NC_VARIABLE, self.variables.len());
+        for (var, &offset) in self.variables.iter().zip(offsets) {
+            put_name(&mut out, &var.name);
+            put_u32(&mut out, var.dims.len() as u32);
+            for &dim in &var.dims {
+                put_u32(&mut out, dim as u32);
+            }
+            put_attributes(&mut out, &var.attributes);
+            put_u32(&mut out, var.data.nc_type());
+            // Sizes past 32 bits are only legal for the last variable,
+            // which readers then size from its dimensions.
+            put_u32(&mut out, u32::try_from(var.vsize()).unwrap_or(u32::MAX));
+            out.extend(offset.to_be_bytes());
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+fn find_attribute<'a>(attributes: &'a [(String, NcData)], name: &str) -> Option<&'a NcData> {
+    attributes.iter().find(|(n, _)| n == name).map(|(_, v)| v)
+}
+
+fn put_u32(out: &mut Vec<u8>, value: u32) {
+    out.extend(value.to_be_bytes());
+}
+
+/// Zero bytes up to the next multiple of four after `written` bytes.
+fn pad(out: &mut Vec<u8>, written: usize) {
+    out.resize(out.len() + (4 - written % 4) % 4, 0);
+}
+
+fn put_name(out: &mut Vec<u8>, name: &str) {
+    put_u32(out, name.len() as u32);
+    out.extend_from_slice(name.as_bytes());
+    pad(out, name.len());
+}
+
+/// List header: tag and count, or ABSENT (two zero words) when empty.
+fn list_tag(out: &mut Vec<u8>, tag: u32, count: usize) {
+    put_u32(out, if count == 0 { 0 } else { tag });
+    put_u32(out, count as u32);
+}
+
+fn put_attributes(out: &mut Vec<u8>, attributes: &[(String, NcData)]) {
+    list_tag(out, NC_ATTRIBUTE, attributes.len());
+    for (name, value) in attributes {
+        put_name(out, name);
+        put_u32(out, value.nc_type());
+        put_u32(out, value.len() as u32);
+        value.write(out);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn writes_classic_layout() {
+        let mut nc = NcFile::new();
+        let x = nc.add_dimension("x", 3);
+        nc.set_attribute("title", "t");
+        nc.add_variable("v", &[x], NcData::Short(vec![1, -2, 3]))
+            .unwrap()
+            .attr("units", "m");
+        let bytes = nc.into_bytes();
+
+        #[rustfmt::skip]
+        let mut expected: Vec<u8> = vec![
+            b'C', b'D', b'F', 2,
+            0, 0, 0, 0,                                 // numrecs
+            0, 0, 0, 0x0A, 0, 0, 0, 1,                  // dimensions
+            0, 0, 0, 1, b'x', 0, 0, 0, 0, 0, 0, 3,
+            0, 0, 0, 0x0C, 0, 0, 0, 1,                  // global attributes
+            0, 0, 0, 5, b't', b'i', b't', b'l', b'e', 0, 0, 0,
+            0, 0, 0, 2, 0, 0, 0, 1, b't', 0, 0, 0,
+            0, 0, 0, 0x0B, 0, 0, 0, 1,                  // variables
+            0, 0, 0, 1, b'v', 0, 0, 0,
+            0, 0, 0, 1, 0, 0, 0, 0,                     // one dim: x
+            0, 0, 0, 0x0C, 0, 0, 0, 1,
+            0, 0, 0, 5, b'u', b'n', b'i', b't', b's', 0, 0, 0,
+            0, 0, 0, 2, 0, 0, 0, 1, b'm', 0, 0, 0,
+            0, 0, 0, 3,                                 // short
+            0, 0, 0, 8,                                 // vsize
+        ];
+        let begin = expected.len() as u64 + 8;
+        expected.extend(begin.to_be_bytes());
+        expected.extend([0, 1, 0xFF, 0xFE, 0, 3, 0, 0]);
+        assert_eq!(bytes, expected);
+    }
+
+    #[test]
+    fn empty_lists_are_absent() {
+        let bytes = NcFile::new().into_bytes();
+        assert_eq!(&bytes[..4], MAGIC);
+        assert_eq!(bytes.len(), 8 + 3 * 8);
+        assert!(bytes[4..].iter().all(|&b| b == 0));
+    }
+
+    #[test]
+    fn scalars_and_offsets_follow_each_other() {
+        let mut nc = NcFile::new();
+        nc.add_variable("a", &[], NcData::Double(vec![1.5]))
+            .unwrap();
+        nc.add_variable("b", &[], NcData::Byte(vec![-1])).unwrap();
+        let bytes = nc.into_bytes();
+        // a: 8 bytes, then b padded to 4.
+        assert_eq!(
+            &bytes[bytes.len() - 12..bytes.len() - 4],
+            &1.5f64.to_be_bytes()
+        );
+        assert_eq!(&bytes[bytes.len() - 4..], &[0xFF, 0, 0, 0]);
+    }
+
+    #[test]
+    fn rejects_wrong_value_count() {
+        let mut nc = NcFile::new();
+        let x = nc.add_dimension("x", 2);
+        let y = nc.add_dimension("y", 3);
+        assert!(nc
+            .add_variable("v", &[x, y], NcData::Float(vec![0.0; 5]))
+            .is_err());
+        assert!(nc
+            .add_variable("v", &[x, y], NcData::Float(vec![0.0; 6]))
+            .is_ok());
+        assert!(nc.add_variable("w", &[7], NcData::Float(vec![])).is_err());
+    }
+
+    #[test]
+    fn strings_are_fixed_width() {
+        let data = NcData::strings(&["ab", "abcdef"], 4);
+        assert_eq!(data, NcData::Char(b"ab\0\0abcd".to_vec()));
+    }
+}
@@ -209,6 +209,16 @@ fn cast_u32_to_u8(data: &[u32]) -> &[u8] {
     unsafe { std::slice::from_raw_parts(data.as_ptr() as *const u8, data.len() * 4) }
 }
 
+/// Adapt a `#version 300 es` shader to the current context. The desktop
+/// build runs on a desktop GL core context, which takes GLSL 3.30 but does not
+/// reliably accept ES sources; the shaders are otherwise valid as either.
+pub(crate) fn shader_source<'a>(gl: &glow::Context, src: &'a str) -> std::borrow::Cow<'a, str> {
+    match src.strip_prefix("#version 300 es") {
+        Some(rest) if !gl.version().is_embedded => format!("#version 330 core{}", rest).into(),
+        _ => src.into(),
+    }
+}
+
 /// Compile a GLSL program from vertex and fragment source.
 pub(crate) unsafe fn compile_program(
     gl: &glow::Context,
@@ -218,14 +228,14 @@ pub(crate) unsafe fn compile_program(
     let program = gl.create_program().expect("create program");
 
     let vs = gl.create_shader(glow::VERTEX_SHADER).expect("create vs");
-    gl.shader_source(vs, vert_src);
+    gl.shader_source(vs, &shader_source(gl, vert_src));
     gl.compile_shader(vs);
     if !gl.get_shader_compile_status(vs) {
         log::error!("Globe VS compile error: {}", gl.get_shader_info_log(vs));
     }
 
     let fs = gl.create_shader(glow::FRAGMENT_SHADER).expect("create fs");
-    gl.shader_source(fs, frag_src);
+    gl.shader_source(fs, &shader_source(gl, frag_src));
     gl.compile_shader(fs);
     if !gl.get_shader_compile_status(fs) {
         log::error!("Globe FS compile error: {}", gl.get_shader_info_log(fs));
@@ -17,3 +17,8 @@ pub use globe_renderer::GlobeRenderer;
 pub use layer::{GeoFeature, GeoLayer, GeoLayerSet, GeoLayerType};
 pub use projection::{MapProjection, ProjectionFingerprint};
 pub use renderer::render_geo_layers;
+
+/// Approximate kilometres per degree of latitude. The flat-earth lat/lon ↔ km
+/// conversions across the app (inspector, detection, exports, mosaic) all
+/// scale by this, with longitude further scaled by `cos(latitude)`.
+pub const KM_PER_DEG: f64 = 111.0;
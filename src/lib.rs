@@ -0,0 +1,33 @@
+#![warn(clippy::all)]
+
+//! NEXRAD Workbench — a NEXRAD weather radar visualization tool for the
+//! browser and the Linux desktop.
+//!
+//! The library holds the whole application. `src/main.rs` is the windowed
+//! entry point (see `app`); `src/bin/nexrad-workbench-cli.rs` is a headless
+//! one for batch ingest and rendering on the desktop (see `cli`).
+//!
+//! Heavy data operations run in a pool of decode workers — Web Workers in the
+//! browser, threads on the desktop (see `nexrad::decode_worker` and
+//! `nexrad::worker_api`). The main thread is a thin UI shell that uploads
+//! worker results to the GPU and paints the interface. Host services that
+//! differ between the two builds live in `platform`.
+
+mod alerts;
+mod app;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod cli;
+mod data;
+mod export;
+mod geo;
+mod nexrad;
+mod platform;
+mod state;
+mod ui;
+
+#[cfg(not(target_arch = "wasm32"))]
+pub use app::run_native;
+#[cfg(target_arch = "wasm32")]
+pub use app::run_web;
+
+// Worker exports (worker_ingest, worker_render) are in nexrad::worker_api.
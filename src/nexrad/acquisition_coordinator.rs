@@ -1,6 +1,7 @@
 //! Acquisition coordinator: owns the download pipeline and archive index.
 //!
-//! Consolidates download channel, cache load channel, download queue,
+//! Consolidates download channel, cache load channel, local import channel,
+//! Level III channel, mosaic and pinned-site channels, download queue,
 //! archive index, and current scan into a single owner.
 
 use crate::data::DataFacade;
@@ -8,6 +9,9 @@ use crate::nexrad::archive_index::ArchiveIndex;
 use crate::nexrad::cache_channel::{CacheLoadChannel, CacheLoadResult};
 use crate::nexrad::download::{DownloadChannel, NetworkStats};
 use crate::nexrad::download_queue::DownloadQueueManager;
+use crate::nexrad::level3::Level3Channel;
+use crate::nexrad::local_import::LocalImportChannel;
+use crate::nexrad::neighbour_fetch::NeighbourChannel;
 use crate::nexrad::types::{CachedScan, DownloadResult};
 use crate::nexrad::ListingResult;
 use crate::nexrad::ScanBoundary;
@@ -28,6 +32,16 @@ pub struct AcquisitionCoordinator {
     pub(crate) download_channel: DownloadChannel,
     /// Channel for async cache metadata loading.
     pub(crate) cache_load_channel: CacheLoadChannel,
+    /// Channel for files picked or dropped for local import.
+    pub(crate) local_import_channel: LocalImportChannel,
+    /// Channel for `.pal` color tables picked for import.
+    pub(crate) palette_import_channel: LocalImportChannel,
+    /// Channel for Level III product listings and downloads.
+    pub(crate) level3_channel: Level3Channel,
+    /// Channel for neighbouring sites' volumes fetched for the mosaic.
+    pub(crate) mosaic_channel: NeighbourChannel,
+    /// Channel for pinned sites' volumes and cached scan lists.
+    pub(crate) pinned_channel: NeighbourChannel,
     /// Manages the queue of files to download.
     pub(crate) download_queue: DownloadQueueManager,
     /// Cache for archive file listings (by site/date).
@@ -51,6 +65,11 @@ impl AcquisitionCoordinator {
         Self {
             download_channel,
             cache_load_channel,
+            local_import_channel: LocalImportChannel::new(),
+            palette_import_channel: LocalImportChannel::new(),
+            level3_channel: Level3Channel::new(),
+            mosaic_channel: NeighbourChannel::new(),
+            pinned_channel: NeighbourChannel::new(),
             download_queue: DownloadQueueManager::new(),
             archive_index: ArchiveIndex::new(),
             current_scan: None,
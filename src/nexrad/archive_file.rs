@@ -0,0 +1,125 @@
+//! Archive II file containers and the volume header record.
+//!
+//! Files from AWS are bare Archive II volumes: a 24-byte volume header
+//! followed by bzip2-compressed LDM records. Files from other sources are
+//! often wrapped whole in gzip or bzip2, and carry no site or time outside
+//! the header record, so local imports unwrap them and read both from it.
+
+use std::io::Read;
+
+/// Length of the Archive II volume header record.
+pub const VOLUME_HEADER_LEN: usize = 24;
+
+const GZIP_MAGIC: &[u8] = &[0x1f, 0x8b];
+const BZIP2_MAGIC: &[u8] = b"BZh";
+
+/// Outer wrappers nested deeper than this are rejected.
+const MAX_WRAPPERS: usize = 3;
+
+/// Site and start time from a volume header record.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VolumeHeader {
+    /// Four-letter ICAO identifier, e.g. `KTLX`.
+    pub site_id: String,
+    /// Volume start, Unix seconds.
+    pub scan_start_secs: i64,
+}
+
+/// Strip whole-file gzip or bzip2 wrappers. Bare volumes are returned as-is.
+pub fn unwrap_container(mut data: Vec<u8>) -> Result<Vec<u8>, String> {
+    for _ in 0..MAX_WRAPPERS {
+        data = if data.starts_with(GZIP_MAGIC) {
+            let mut out = Vec::with_capacity(data.len() * 4);
+            flate2::read::MultiGzDecoder::new(data.as_slice())
+                .read_to_end(&mut out)
+                .map_err(|e| format!("Failed to gunzip archive: {}", e))?;
+            out
+        } else if data.starts_with(BZIP2_MAGIC) {
+            let mut out = Vec::with_capacity(data.len() * 4);
+            bzip2_rs::DecoderReader::new(data.as_slice())
+                .read_to_end(&mut out)
+                .map_err(|e| format!("Failed to bunzip2 archive: {}", e))?;
+            out
+        } else {
+            return Ok(data);
+        };
+    }
+    Err("Archive is wrapped too many times".to_string())
+}
+
+/// Parse the volume header at the start of `data`.
+///
+/// The record is `AR2V00nn.nnn`, the NEXRAD-modified Julian date (day 1 is
+/// 1970-01-01), milliseconds past midnight, and the ICAO, all big-endian.
+/// Returns `None` when `data` doesn't start with one.
+pub fn parse_volume_header(data: &[u8]) -> Option<VolumeHeader> {
+    let header = data.get(..VOLUME_HEADER_LEN)?;
+    if !header.starts_with(b"AR2V") {
+        return None;
+    }
+    let julian_date = u32::from_be_bytes(header[12..16].try_into().ok()?);
+    let millis = u32::from_be_bytes(header[16..20].try_into().ok()?);
+    let icao = &header[20..24];
+    if julian_date == 0 || !icao.iter().all(u8::is_ascii_alphanumeric) {
+        return None;
+    }
+    Some(VolumeHeader {
+        site_id: String::from_utf8_lossy(icao).to_ascii_uppercase(),
+        scan_start_secs: (julian_date as i64 - 1) * 86_400 + millis as i64 / 1000,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    /// Header for KTLX at 2013-05-20 20:00:04 UTC.
+    fn header() -> Vec<u8> {
+        let mut h = b"AR2V0006.123".to_vec();
+        h.extend_from_slice(&15846u32.to_be_bytes());
+        h.extend_from_slice(&72_004_500u32.to_be_bytes());
+        h.extend_from_slice(b"KTLX");
+        h
+    }
+
+    #[test]
+    fn parses_site_and_start_time() {
+        let mut data = header();
+        data.extend_from_slice(&[0, 0, 0, 8]);
+        assert_eq!(
+            parse_volume_header(&data),
+            Some(VolumeHeader {
+                site_id: "KTLX".to_string(),
+                scan_start_secs: 1_369_080_004,
+            })
+        );
+    }
+
+    #[test]
+    fn rejects_data_without_a_header() {
+        assert_eq!(parse_volume_header(&header()[..20]), None);
+        let mut other = header();
+        other[..4].copy_from_slice(b"ARCH");
+        assert_eq!(parse_volume_header(&other), None);
+        let mut bad_site = header();
+        bad_site[23] = 0;
+        assert_eq!(parse_volume_header(&bad_site), None);
+    }
+
+    #[test]
+    fn unwraps_gzip_and_passes_bare_volumes_through() {
+        let volume = header();
+        assert_eq!(unwrap_container(volume.clone()).unwrap(), volume);
+
+        let mut gz = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::fast());
+        gz.write_all(&volume).unwrap();
+        let wrapped = gz.finish().unwrap();
+        assert_eq!(unwrap_container(wrapped).unwrap(), volume);
+    }
+
+    #[test]
+    fn reports_corrupt_gzip() {
+        assert!(unwrap_container(vec![0x1f, 0x8b, 8, 0, 1, 2]).is_err());
+    }
+}
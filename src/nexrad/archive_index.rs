@@ -248,7 +248,7 @@ impl ArchiveIndex {
 
 /// Get current timestamp in seconds.
 pub fn current_timestamp_secs() -> f64 {
-    js_sys::Date::now() / 1000.0
+    crate::platform::now_secs()
 }
 
 #[cfg(test)]
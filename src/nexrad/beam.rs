@@ -0,0 +1,52 @@
+//! Radar beam geometry under the 4/3 effective earth radius model.
+//!
+//! Standard atmospheric refraction bends the beam towards the ground; the
+//! usual approximation treats it as a straight line over an earth whose
+//! radius is 4/3 of the real one. Heights here are above radar level.
+
+/// Effective earth radius (4/3 refraction model), km.
+pub(crate) const EFFECTIVE_EARTH_RADIUS_KM: f64 = 6371.0 * 4.0 / 3.0;
+
+/// Beam-centre height above the radar (km) of a tilt at `ground_km`.
+pub(crate) fn beam_height_km(ground_km: f64, elevation_deg: f32) -> f64 {
+    let re = EFFECTIVE_EARTH_RADIUS_KM;
+    let elev_rad = (elevation_deg as f64).to_radians();
+    re * elev_rad.cos() / (elev_rad + ground_km / re).cos() - re
+}
+
+/// Ground range (km) under a tilt's beam at slant range `slant_km`.
+pub(crate) fn ground_range_km(slant_km: f64, elevation_deg: f32) -> f64 {
+    let re = EFFECTIVE_EARTH_RADIUS_KM;
+    let elev_rad = (elevation_deg as f64).to_radians();
+    let height_km =
+        (slant_km * slant_km + re * re + 2.0 * slant_km * re * elev_rad.sin()).sqrt() - re;
+    re * (slant_km * elev_rad.cos() / (re + height_km)).asin()
+}
+
+/// Elevation angle (degrees) and slant range (km) from the radar to a point
+/// `height_km` above the ground `ground_km` away.
+pub(crate) fn elevation_and_slant(ground_km: f64, height_km: f64) -> (f32, f64) {
+    let re = EFFECTIVE_EARTH_RADIUS_KM;
+    let phi = ground_km / re;
+    let horizontal = (re + height_km) * phi.sin();
+    let vertical = (re + height_km) * phi.cos() - re;
+    (
+        vertical.atan2(horizontal).to_degrees() as f32,
+        horizontal.hypot(vertical),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn beam_height_matches_slant_geometry() {
+        let h = beam_height_km(100.0, 0.5);
+        // ~0.87 km from the tilt plus ~0.59 km of earth curvature.
+        assert!((h - 1.46).abs() < 0.05, "height {}", h);
+        let (elevation, slant) = elevation_and_slant(100.0, h);
+        assert!((elevation - 0.5).abs() < 1e-3, "elevation {}", elevation);
+        assert!((ground_range_km(slant, 0.5) - 100.0).abs() < 1e-6);
+    }
+}
@@ -61,7 +61,7 @@ impl CacheLoadChannel {
         let receiver = self.receiver.clone();
         let loading = self.loading.clone();
 
-        wasm_bindgen_futures::spawn_local(async move {
+        crate::platform::spawn_local(async move {
             let t_total = web_time::Instant::now();
             log::debug!("Loading cache metadata for site: {}", site_id);
 
@@ -134,7 +134,7 @@ impl CacheLoadChannel {
         let receiver = self.receiver.clone();
         let loading = self.loading.clone();
 
-        wasm_bindgen_futures::spawn_local(async move {
+        crate::platform::spawn_local(async move {
             log::debug!("Clearing cache...");
 
             let result = match facade.clear_all().await {
@@ -0,0 +1,724 @@
+//! CF-Radial 1.4 export of a cached volume.
+//!
+//! Every cached elevation becomes one sweep and every base moment one field
+//! on a shared `(time, range)` grid, so the file opens directly in Py-ART,
+//! LROSE and other CF-Radial readers. Rays come from the first moment
+//! present at each elevation (reflectivity where available); the other
+//! moments are matched to those rays by azimuth and to the common range
+//! axis by nearest gate. Split-cut elevations that lack a moment leave its
+//! field empty there.
+//!
+//! Fields keep the archive's encoding where they can: when a moment has the
+//! same word size, scale and offset at every elevation, the raw values are
+//! stored as packed bytes or shorts with `scale_factor` / `add_offset`
+//! taken from the sweep header. Otherwise they are written as physical
+//! floats. Raw values 0 and 1 (below threshold, range folded) become
+//! `_FillValue` either way.
+
+use crate::data::keys::{GateValues, PrecomputedSweep};
+use crate::export::netcdf::{NcData, NcFile};
+use crate::nexrad::dealias::DEALIASED_VELOCITY;
+use crate::nexrad::polar::AzimuthIndex;
+
+/// Width of the `string_length` dimension.
+const STRING_LENGTH: usize = 32;
+
+/// Fill value of fields written as floats.
+const FLOAT_FILL: f32 = -9999.0;
+
+/// A moment exported as a CF-Radial field.
+struct FieldInfo {
+    product: &'static str,
+    name: &'static str,
+    standard_name: Option<&'static str>,
+    long_name: &'static str,
+    units: &'static str,
+}
+
+/// Exported moments, in file order. The first present at an elevation
+/// defines its rays.
+const FIELDS: &[FieldInfo] = &[
+    FieldInfo {
+        product: "reflectivity",
+        name: "DBZ",
+        standard_name: Some("equivalent_reflectivity_factor"),
+        long_name: "Equivalent reflectivity factor",
+        units: "dBZ",
+    },
+    FieldInfo {
+        product: "velocity",
+        name: "VEL",
+        standard_name: Some("radial_velocity_of_scatterers_away_from_instrument"),
+        long_name: "Radial velocity",
+        units: "m/s",
+    },
+    FieldInfo {
+        product: DEALIASED_VELOCITY,
+        name: "VEL_DEALIASED",
+        standard_name: Some("corrected_radial_velocity_of_scatterers_away_from_instrument"),
+        long_name: "Dealiased radial velocity",
+        units: "m/s",
+    },
+    FieldInfo {
+        product: "spectrum_width",
+        name: "WIDTH",
+        standard_name: Some("doppler_spectrum_width"),
+        long_name: "Doppler spectrum width",
+        units: "m/s",
+    },
+    FieldInfo {
+        product: "differential_reflectivity",
+        name: "ZDR",
+        standard_name: Some("log_differential_reflectivity_hv"),
+        long_name: "Differential reflectivity",
+        units: "dB",
+    },
+    FieldInfo {
+        product: "correlation_coefficient",
+        name: "RHOHV",
+        standard_name: Some("cross_correlation_ratio_hv"),
+        long_name: "Copolar correlation coefficient",
+        units: "unitless",
+    },
+    FieldInfo {
+        product: "differential_phase",
+        name: "PHIDP",
+        standard_name: Some("differential_phase_hv"),
+        long_name: "Differential phase",
+        units: "degrees",
+    },
+    FieldInfo {
+        product: "clutter_filter_power",
+        name: "CFP",
+        standard_name: None,
+        long_name: "Clutter filter power removed",
+        units: "dB",
+    },
+];
+
+/// Products to load for a CF-Radial export.
+pub(crate) fn cf_radial_products() -> impl Iterator<Item = &'static str> {
+    FIELDS.iter().map(|f| f.product)
+}
+
+/// Radar location for the file's platform variables.
+pub(crate) struct CfRadialSite {
+    pub id: String,
+    pub name: String,
+    pub latitude: f64,
+    pub longitude: f64,
+    pub altitude_m: f64,
+}
+
+/// The cached moments of one elevation.
+pub(crate) struct CfRadialSweep {
+    /// `(product, sweep)` pairs; products outside the field table are
+    /// ignored.
+    pub moments: Vec<(String, PrecomputedSweep)>,
+}
+
+impl CfRadialSweep {
+    fn moment(&self, product: &str) -> Option<&PrecomputedSweep> {
+        self.moments
+            .iter()
+            .find(|(name, _)| name == product)
+            .map(|(_, sweep)| sweep)
+    }
+
+    /// The sweep whose rays this elevation uses.
+    fn reference(&self) -> Option<&PrecomputedSweep> {
+        FIELDS.iter().find_map(|f| self.moment(f.product))
+    }
+}
+
+/// How a field's values are stored.
+#[derive(Clone, Copy, PartialEq)]
+enum Packing {
+    /// Raw bytes shifted by −128 into NC_BYTE.
+    Byte { scale: f32, offset: f32 },
+    /// Raw words shifted by −32768 into NC_SHORT.
+    Short { scale: f32, offset: f32 },
+    /// Physical values as NC_FLOAT.
+    Float,
+}
+
+impl Packing {
+    /// Packed if every sweep of the moment shares one encoding.
+    fn for_moment<'a>(mut sweeps: impl Iterator<Item = &'a PrecomputedSweep>) -> Self {
+        let encoding = |s: &PrecomputedSweep| (s.gate_values.word_size(), s.scale, s.offset);
+        let Some(first) = sweeps.next().map(encoding) else {
+            return Packing::Float;
+        };
+        if first.1 == 0.0 || sweeps.any(|s| encoding(s) != first) {
+            return Packing::Float;
+        }
+        let (word_size, scale, offset) = first;
+        if word_size == 1 {
+            Packing::Byte { scale, offset }
+        } else {
+            Packing::Short { scale, offset }
+        }
+    }
+}
+
+/// Ray and gate geometry shared by all fields.
+struct Rays {
+    /// `(sweep, radial index in the reference moment)` per ray.
+    rays: Vec<(usize, usize)>,
+    /// Range gate centres, km.
+    range_km: Vec<f64>,
+}
+
+/// Build a CF-Radial dataset from `sweeps`, ordered by elevation number.
+pub(crate) fn build_cf_radial(
+    site: &CfRadialSite,
+    sweeps: &[CfRadialSweep],
+) -> Result<NcFile, String> {
+    let sweeps: Vec<&CfRadialSweep> = sweeps.iter().filter(|s| s.reference().is_some()).collect();
+    if sweeps.is_empty() {
+        return Err("No cached sweeps to export".into());
+    }
+    let geometry = ray_geometry(&sweeps)?;
+    let ray_count = geometry.rays.len();
+    let gate_count = geometry.range_km.len();
+
+    let mut nc = NcFile::new();
+    let time = nc.add_dimension("time", ray_count);
+    let range = nc.add_dimension("range", gate_count);
+    let sweep_dim = nc.add_dimension("sweep", sweeps.len());
+    let string_length = nc.add_dimension("string_length", STRING_LENGTH);
+
+    // Ray times relative to the first whole second of the volume.
+    let ray_times: Vec<f64> = geometry
+        .rays
+        .iter()
+        .map(|&(s, r)| ray_time(sweeps[s].reference().expect("filtered above"), r))
+        .collect();
+    let start = ray_times
+        .iter()
+        .copied()
+        .fold(f64::INFINITY, f64::min)
+        .floor();
+    let end = ray_times
+        .iter()
+        .copied()
+        .fold(f64::NEG_INFINITY, f64::max)
+        .ceil();
+    let start_iso = iso_time(start);
+    let end_iso = iso_time(end);
+
+    nc.set_attribute("Conventions", "CF/Radial instrument_parameters");
+    nc.set_attribute("version", "1.4");
+    nc.set_attribute("title", format!("{} volume scan", site.id));
+    nc.set_attribute("source", "NEXRAD Level II");
+    nc.set_attribute("history", "Exported by nexrad-workbench");
+    nc.set_attribute("instrument_name", site.id.as_str());
+    nc.set_attribute("site_name", site.name.as_str());
+    nc.set_attribute("time_coverage_start", start_iso.as_str());
+    nc.set_attribute("time_coverage_end", end_iso.as_str());
+
+    nc.add_variable("volume_number", &[], NcData::Int(vec![0]))?
+        .attr("long_name", "data_volume_index_number");
+    for (name, value) in [
+        ("time_coverage_start", &start_iso),
+        ("time_coverage_end", &end_iso),
+    ] {
+        nc.add_variable(
+            name,
+            &[string_length],
+            NcData::strings(&[value], STRING_LENGTH),
+        )?
+        .attr("standard_name", name)
+        .attr("comment", "ray times are relative to start time in secs");
+    }
+    nc.add_variable(
+        "instrument_type",
+        &[string_length],
+        NcData::strings(&["radar"], STRING_LENGTH),
+    )?;
+    nc.add_variable(
+        "platform_type",
+        &[string_length],
+        NcData::strings(&["fixed"], STRING_LENGTH),
+    )?;
+    nc.add_variable(
+        "primary_axis",
+        &[string_length],
+        NcData::strings(&["axis_z"], STRING_LENGTH),
+    )?;
+
+    nc.add_variable("latitude", &[], site.latitude.into())?
+        .attr("standard_name", "latitude")
+        .attr("units", "degrees_north");
+    nc.add_variable("longitude", &[], site.longitude.into())?
+        .attr("standard_name", "longitude")
+        .attr("units", "degrees_east");
+    nc.add_variable("altitude", &[], site.altitude_m.into())?
+        .attr("standard_name", "altitude")
+        .attr("units", "meters")
+        .attr("positive", "up");
+
+    // Sweep table.
+    let mut start_ray = Vec::with_capacity(sweeps.len());
+    let mut end_ray = Vec::with_capacity(sweeps.len());
+    let mut first = 0i32;
+    for s in 0..sweeps.len() {
+        let rays = geometry.rays.iter().filter(|&&(rs, _)| rs == s).count() as i32;
+        start_ray.push(first);
+        end_ray.push(first + rays - 1);
+        first += rays;
+    }
+    nc.add_variable(
+        "sweep_number",
+        &[sweep_dim],
+        NcData::Int((0..sweeps.len() as i32).collect()),
+    )?
+    .attr("long_name", "sweep_index_number_0_based");
+    nc.add_variable(
+        "sweep_mode",
+        &[sweep_dim, string_length],
+        NcData::strings(&vec!["azimuth_surveillance"; sweeps.len()], STRING_LENGTH),
+    )?
+    .attr("long_name", "scan_mode_for_sweep");
+    nc.add_variable(
+        "fixed_angle",
+        &[sweep_dim],
+        NcData::Float(
+            sweeps
+                .iter()
+                .map(|s| s.reference().expect("filtered above").mean_elevation)
+                .collect(),
+        ),
+    )?
+    .attr("long_name", "ray_target_fixed_angle")
+    .attr("units", "degrees");
+    nc.add_variable(
+        "sweep_start_ray_index",
+        &[sweep_dim],
+        NcData::Int(start_ray),
+    )?
+    .attr("long_name", "index_of_first_ray_in_sweep");
+    nc.add_variable("sweep_end_ray_index", &[sweep_dim], NcData::Int(end_ray))?
+        .attr("long_name", "index_of_last_ray_in_sweep");
+
+    // Coordinates.
+    nc.add_variable(
+        "time",
+        &[time],
+        NcData::Double(ray_times.iter().map(|t| t - start).collect()),
+    )?
+    .attr("standard_name", "time")
+    .attr("long_name", "time_in_seconds_since_volume_start")
+    .attr("units", format!("seconds since {}", start_iso))
+    .attr("calendar", "gregorian");
+    nc.add_variable(
+        "range",
+        &[range],
+        NcData::Float(
+            geometry
+                .range_km
+                .iter()
+                .map(|r| (r * 1000.0) as f32)
+                .collect(),
+        ),
+    )?
+    .attr("standard_name", "projection_range_coordinate")
+    .attr("long_name", "range_to_measurement_volume")
+    .attr("units", "meters")
+    .attr("axis", "radial_range_coordinate")
+    .attr("spacing_is_constant", "true")
+    .attr(
+        "meters_to_center_of_first_gate",
+        (geometry.range_km[0] * 1000.0) as f32,
+    )
+    .attr(
+        "meters_between_gates",
+        ((geometry.range_km.get(1).unwrap_or(&0.0) - geometry.range_km[0]) * 1000.0) as f32,
+    );
+    nc.add_variable(
+        "azimuth",
+        &[time],
+        NcData::Float(
+            geometry
+                .rays
+                .iter()
+                .map(|&(s, r)| sweeps[s].reference().expect("filtered above").azimuths[r])
+                .collect(),
+        ),
+    )?
+    .attr("standard_name", "ray_azimuth_angle")
+    .attr("long_name", "azimuth_angle_from_true_north")
+    .attr("units", "degrees")
+    .attr("axis", "radial_azimuth_coordinate");
+    nc.add_variable(
+        "elevation",
+        &[time],
+        NcData::Float(
+            geometry
+                .rays
+                .iter()
+                .map(|&(s, _)| {
+                    sweeps[s]
+                        .reference()
+                        .expect("filtered above")
+                        .mean_elevation
+                })
+                .collect(),
+        ),
+    )?
+    .attr("standard_name", "ray_elevation_angle")
+    .attr("long_name", "elevation_angle_from_horizontal_plane")
+    .attr("units", "degrees")
+    .attr("axis", "radial_elevation_coordinate");
+
+    // Fields.
+    for field in FIELDS {
+        if !sweeps.iter().any(|s| s.moment(field.product).is_some()) {
+            continue;
+        }
+        let packing = Packing::for_moment(sweeps.iter().filter_map(|s| s.moment(field.product)));
+        let data = field_data(field.product, packing, &sweeps, &geometry);
+        let var = nc.add_variable(field.name, &[time, range], data)?;
+        var.attr("long_name", field.long_name);
+        if let Some(standard_name) = field.standard_name {
+            var.attr("standard_name", standard_name);
+        }
+        var.attr("units", field.units);
+        match packing {
+            Packing::Byte { scale, offset } => {
+                var.attr("_FillValue", i8::MIN)
+                    .attr("scale_factor", 1.0 / scale)
+                    .attr("add_offset", (128.0 - offset) / scale);
+            }
+            Packing::Short { scale, offset } => {
+                var.attr("_FillValue", i16::MIN)
+                    .attr("scale_factor", 1.0 / scale)
+                    .attr("add_offset", (32768.0 - offset) / scale);
+            }
+            Packing::Float => {
+                var.attr("_FillValue", FLOAT_FILL);
+            }
+        }
+        var.attr("coordinates", "elevation azimuth range");
+    }
+
+    Ok(nc)
+}
+
+/// Rays of every sweep, in order, and a range axis spanning all moments at
+/// the finest gate spacing.
+fn ray_geometry(sweeps: &[&CfRadialSweep]) -> Result<Rays, String> {
+    let mut first_km = f64::INFINITY;
+    let mut interval_km = f64::INFINITY;
+    let mut end_km: f64 = 0.0;
+    for (_, m) in sweeps.iter().flat_map(|s| &s.moments) {
+        if m.gate_count == 0 || m.gate_interval_km <= 0.0 {
+            continue;
+        }
+        first_km = first_km.min(m.first_gate_range_km);
+        interval_km = interval_km.min(m.gate_interval_km);
+        end_km = end_km.max(m.first_gate_range_km + (m.gate_count - 1) as f64 * m.gate_interval_km);
+    }
+    if !first_km.is_finite() || !interval_km.is_finite() {
+        return Err("Cached sweeps have no gates".into());
+    }
+    let gate_count = ((end_km - first_km) / interval_km).round() as usize + 1;
+    let range_km = (0..gate_count)
+        .map(|g| first_km + g as f64 * interval_km)
+        .collect();
+
+    let rays = sweeps
+        .iter()
+        .enumerate()
+        .flat_map(|(s, sweep)| {
+            let reference = sweep.reference().expect("filtered by caller");
+            reference
+                .azimuths
+                .iter()
+                .take(reference.azimuth_count as usize)
+                .enumerate()
+                .filter(|(_, &az)| az >= 0.0)
+                .map(move |(r, _)| (s, r))
+        })
+        .collect();
+    Ok(Rays { rays, range_km })
+}
+
+/// Collection time of radial `index`, interpolated across the sweep when
+/// per-radial times were not stored.
+fn ray_time(sweep: &PrecomputedSweep, index: usize) -> f64 {
+    if let Some(&t) = sweep.radial_times.get(index) {
+        return t;
+    }
+    let n = sweep.azimuth_count.max(1) as f64;
+    sweep.sweep_start_secs + (sweep.sweep_end_secs - sweep.sweep_start_secs) * index as f64 / n
+}
+
+/// One field's `(time, range)` values in its storage encoding.
+fn field_data(
+    product: &str,
+    packing: Packing,
+    sweeps: &[&CfRadialSweep],
+    geometry: &Rays,
+) -> NcData {
+    let gate_count = geometry.range_km.len();
+    let cells = geometry.rays.len() * gate_count;
+    let mut out = match packing {
+        Packing::Byte { .. } => NcData::Byte(vec![i8::MIN; cells]),
+        Packing::Short { .. } => NcData::Short(vec![i16::MIN; cells]),
+        Packing::Float => NcData::Float(vec![FLOAT_FILL; cells]),
+    };
+
+    let mut row = 0;
+    for (s, sweep) in sweeps.iter().enumerate() {
+        let reference = sweep.reference().expect("filtered by caller");
+        let rays: Vec<usize> = geometry
+            .rays
+            .iter()
+            .filter(|&&(rs, _)| rs == s)
+            .map(|&(_, r)| r)
+            .collect();
+        let Some(moment) = sweep.moment(product) else {
+            row += rays.len();
+            continue;
+        };
+        let is_reference = std::ptr::eq(moment, reference);
+        let index = (!is_reference)
+            .then(|| AzimuthIndex::new(&moment.azimuths, moment.azimuth_count as usize));
+        // Source gate for each output gate, nearest by range.
+        let gates: Vec<Option<usize>> = geometry
+            .range_km
+            .iter()
+            .map(|r| {
+                let g = ((r - moment.first_gate_range_km) / moment.gate_interval_km).round();
+                (g >= 0.0 && g < moment.gate_count as f64).then_some(g as usize)
+            })
+            .collect();
+
+        for r in rays {
+            let radial = match &index {
+                None => Some(r),
+                Some(index) => index.radial(reference.azimuths[r]),
+            };
+            if let Some(radial) = radial {
+                let base = radial * moment.gate_count as usize;
+                for (g, source) in gates.iter().enumerate() {
+                    let Some(source) = source else { continue };
+                    let raw = match &moment.gate_values {
+                        GateValues::U8(v) => v.get(base + source).map(|&x| x as u16),
+                        GateValues::U16(v) => v.get(base + source).copied(),
+                    };
+                    if let Some(raw) = raw.filter(|&raw| raw > 1) {
+                        store(&mut out, row * gate_count + g, raw, moment);
+                    }
+                }
+            }
+            row += 1;
+        }
+    }
+    out
+}
+
+/// Write `raw` into cell `i` of `out` in its encoding.
+fn store(out: &mut NcData, i: usize, raw: u16, sweep: &PrecomputedSweep) {
+    match out {
+        NcData::Byte(v) => v[i] = (raw as i16 - 128) as i8,
+        NcData::Short(v) => v[i] = (raw as i32 - 32768) as i16,
+        NcData::Float(v) => {
+            v[i] = if sweep.scale == 0.0 {
+                raw as f32
+            } else {
+                (raw as f32 - sweep.offset) / sweep.scale
+            }
+        }
+        _ => {}
+    }
+}
+
+/// `YYYY-MM-DDTHH:MM:SSZ` for Unix seconds.
+fn iso_time(secs: f64) -> String {
+    chrono::DateTime::from_timestamp(secs as i64, 0)
+        .map(|t| t.format("%Y-%m-%dT%H:%M:%SZ").to_string())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::SweepFixture;
+
+    const T0: f64 = 1_700_000_000.0;
+
+    /// Four 90° radials of `gates` gates from 2 km at 0.25 km; raw value
+    /// `radial * 10 + gate + 2` so every gate has data.
+    fn moment(gate_km: f64, gates: usize, word: u8, scale: f32, offset: f32) -> PrecomputedSweep {
+        let raw: Vec<u16> = (0..4)
+            .flat_map(|r| (0..gates).map(move |g| (r * 10 + g + 2) as u16))
+            .collect();
+        SweepFixture::new(4, gates, gate_km)
+            .first_gate_km(2.0)
+            .encoding(scale, offset)
+            .times(T0, T0 + 20.0)
+            .raw(if word == 1 {
+                GateValues::U8(raw.iter().map(|&v| v as u8).collect())
+            } else {
+                GateValues::U16(raw)
+            })
+    }
+
+    fn site() -> CfRadialSite {
+        CfRadialSite {
+            id: "KTLX".into(),
+            name: "Oklahoma City".into(),
+            latitude: 35.33,
+            longitude: -97.28,
+            altitude_m: 370.0,
+        }
+    }
+
+    fn sweep(moments: Vec<(&str, PrecomputedSweep)>) -> CfRadialSweep {
+        CfRadialSweep {
+            moments: moments
+                .into_iter()
+                .map(|(p, s)| (p.to_string(), s))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn layout_covers_all_sweeps_and_moments() {
+        let sweeps = [
+            sweep(vec![("reflectivity", moment(0.25, 4, 1, 2.0, 66.0))]),
+            sweep(vec![
+                ("reflectivity", moment(0.25, 4, 1, 2.0, 66.0)),
+                ("velocity", moment(0.25, 8, 1, 2.0, 129.0)),
+            ]),
+        ];
+        let nc = build_cf_radial(&site(), &sweeps).unwrap();
+
+        let ints = |name: &str| match nc.variable(name).unwrap().data() {
+            NcData::Int(v) => v.clone(),
+            _ => panic!("{} is not int", name),
+        };
+        assert_eq!(ints("sweep_start_ray_index"), vec![0, 4]);
+        assert_eq!(ints("sweep_end_ray_index"), vec![3, 7]);
+        // Range spans the longer velocity sweep.
+        let NcData::Float(range) = nc.variable("range").unwrap().data() else {
+            panic!("range is not float");
+        };
+        assert_eq!(range.len(), 8);
+        assert_eq!(range[0], 2000.0);
+        assert_eq!(range[7], 3750.0);
+
+        let dbz = nc.variable("DBZ").unwrap();
+        assert_eq!(dbz.dims().len(), 2);
+        assert_eq!(
+            dbz.attribute("scale_factor"),
+            Some(&NcData::Float(vec![0.5]))
+        );
+        // physical = stored·0.5 + 31 = (raw − 128)·0.5 + 31 = (raw − 66)/2.
+        assert_eq!(
+            dbz.attribute("add_offset"),
+            Some(&NcData::Float(vec![31.0]))
+        );
+        let NcData::Byte(dbz) = dbz.data() else {
+            panic!("DBZ is not packed to bytes");
+        };
+        // Ray 1 (radial 1), gate 2: raw 14, stored 14 − 128.
+        assert_eq!(dbz[8 + 2], -114);
+        // Beyond reflectivity's four gates.
+        assert_eq!(dbz[8 + 5], i8::MIN);
+
+        // Velocity only exists on the second sweep.
+        let NcData::Byte(vel) = nc.variable("VEL").unwrap().data() else {
+            panic!("VEL is not packed to bytes");
+        };
+        assert!(vel[..4 * 8].iter().all(|&v| v == i8::MIN));
+        // Ray 5 (radial 1), gate 7: raw 19.
+        assert_eq!(vel[4 * 8 + 8 + 7], -109);
+    }
+
+    #[test]
+    fn mixed_encodings_fall_back_to_float() {
+        let sweeps = [
+            sweep(vec![("velocity", moment(0.25, 4, 1, 2.0, 129.0))]),
+            sweep(vec![("velocity", moment(0.25, 4, 1, 1.0, 129.0))]),
+        ];
+        let nc = build_cf_radial(&site(), &sweeps).unwrap();
+        let vel = nc.variable("VEL").unwrap();
+        assert_eq!(
+            vel.attribute("_FillValue"),
+            Some(&NcData::Float(vec![FLOAT_FILL]))
+        );
+        let NcData::Float(vel) = vel.data() else {
+            panic!("VEL is not float");
+        };
+        // Sweep 1, radial 0, gate 0: raw 2 at 0.5 m/s → −63.5.
+        assert_eq!(vel[0], (2.0 - 129.0) / 2.0);
+        // Sweep 2 at 1 m/s.
+        assert_eq!(vel[16], 2.0 - 129.0);
+    }
+
+    #[test]
+    fn below_threshold_gates_are_fill() {
+        let mut refl = moment(0.25, 4, 2, 100.0, 0.0);
+        if let GateValues::U16(v) = &mut refl.gate_values {
+            v[0] = 0;
+            v[1] = 1;
+        }
+        let nc = build_cf_radial(&site(), &[sweep(vec![("reflectivity", refl)])]).unwrap();
+        let NcData::Short(dbz) = nc.variable("DBZ").unwrap().data() else {
+            panic!("DBZ is not packed to shorts");
+        };
+        // Gate 2 holds raw 4, stored 4 − 32768.
+        assert_eq!(&dbz[..3], &[i16::MIN, i16::MIN, -32764]);
+    }
+
+    #[test]
+    fn other_moments_follow_reference_rays_by_azimuth() {
+        let mut vel = moment(0.25, 4, 1, 2.0, 129.0);
+        // Same radials, listed starting from 135°.
+        vel.azimuths.rotate_left(1);
+        let sweeps = [sweep(vec![
+            ("velocity", vel),
+            ("reflectivity", moment(0.25, 4, 1, 2.0, 66.0)),
+        ])];
+        let nc = build_cf_radial(&site(), &sweeps).unwrap();
+        let NcData::Byte(vel) = nc.variable("VEL").unwrap().data() else {
+            panic!("VEL is not packed to bytes");
+        };
+        // Ray 0 is reflectivity's 45° radial, which is velocity radial 3:
+        // raw 32, stored 32 − 128.
+        assert_eq!(vel[0], -96);
+    }
+
+    #[test]
+    fn times_are_relative_to_volume_start() {
+        let nc = build_cf_radial(
+            &site(),
+            &[sweep(vec![("reflectivity", moment(0.25, 4, 1, 2.0, 66.0))])],
+        )
+        .unwrap();
+        let NcData::Double(times) = nc.variable("time").unwrap().data() else {
+            panic!("time is not double");
+        };
+        assert_eq!(times, &vec![0.0, 5.0, 10.0, 15.0]);
+        assert_eq!(
+            nc.attribute("time_coverage_start"),
+            Some(&NcData::from("2023-11-14T22:13:20Z"))
+        );
+    }
+
+    #[test]
+    fn empty_volume_is_an_error() {
+        assert!(build_cf_radial(&site(), &[]).is_err());
+        assert!(build_cf_radial(
+            &site(),
+            &[sweep(vec![(
+                "hydrometeor_class",
+                moment(0.25, 4, 1, 1.0, 0.0)
+            )])]
+        )
+        .is_err());
+    }
+}
@@ -2,7 +2,12 @@
 //!
 //! Pure functions for building color lookup tables (no GL dependency).
 //! Used by `gpu_renderer` for texture uploads and by `canvas` for legend rendering.
+//! User `.pal` palettes from [`crate::nexrad::palette`] override the tables here.
 
+use crate::nexrad::hydro_class::{HydroClass, HYDROMETEOR_CLASS};
+use crate::nexrad::level3::Level3Product;
+use crate::nexrad::palette;
+use crate::nexrad::qpe::is_qpe_product;
 use nexrad_render::{Color as NrColor, ColorScale, ColorStop, ContinuousColorScale, Product};
 
 /// Default value ranges per product (used for color LUT normalization).
@@ -18,9 +23,126 @@ pub fn product_value_range(product: Product) -> (f32, f32) {
     }
 }
 
+/// Value range for a worker product string. Derived products (volume
+/// products, hydrometeor classes, accumulations) carry their own units; everything else
+/// follows its base moment.
+pub fn value_range_for(product_str: &str) -> (f32, f32) {
+    match product_str {
+        HYDROMETEOR_CLASS => (0.0, HydroClass::ALL.len() as f32),
+        s if s.starts_with("echo_tops_") => (0.0, 20.0),
+        "vil" => (0.0, 80.0),
+        s if is_qpe_product(s) => (0.0, 254.0),
+        s => match Level3Product::from_worker_string(s) {
+            Some(product) => value_range_for(product.base_product()),
+            None => product_value_range(product_from_str(s)),
+        },
+    }
+}
+
+/// Build the 1024-entry RGBA LUT for a worker product string, spanning
+/// [`value_range_for`]. Shared by the GPU color texture and the legend.
+/// A palette the user picked for the product's moment takes precedence
+/// over the built-in tables.
+pub fn build_product_lut(product_str: &str) -> Vec<u8> {
+    let (min_val, max_val) = value_range_for(product_str);
+    if let Some(palette) = palette::active_for(product_str) {
+        return palette.build_lut(min_val, max_val);
+    }
+    if is_categorical(product_str) {
+        return build_hydro_class_lut();
+    }
+    if let Some(product) = Level3Product::from_worker_string(product_str) {
+        // Level III reflectivity keeps the stepped NWS table it is issued
+        // with; the other products share their Level II palette.
+        return match product {
+            Level3Product::N0q | Level3Product::Ncr | Level3Product::Tz0 | Level3Product::Tzl => {
+                build_stepped_lut(min_val, max_val, NWS_REFLECTIVITY_STEPS)
+            }
+            _ => build_product_lut(product.base_product()),
+        };
+    }
+    let color_scale = match product_str {
+        s if s.starts_with("echo_tops_") => echo_tops_color_scale(),
+        "vil" => vil_color_scale(),
+        s if is_qpe_product(s) => accumulation_color_scale(),
+        s => {
+            let product = product_from_str(s);
+            if matches!(product, Product::Reflectivity) {
+                // OKLab-interpolated reflectivity palette with alpha ramp
+                return build_reflectivity_lut(min_val, max_val);
+            }
+            continuous_color_scale(product)
+        }
+    };
+
+    // Continuous gradient + GL_LINEAR = zero visible quantization
+    let lut_size = 1024usize;
+    let mut data = Vec::with_capacity(lut_size * 4);
+    for i in 0..lut_size {
+        let t = i as f32 / (lut_size - 1) as f32;
+        let value = min_val + t * (max_val - min_val);
+        data.extend_from_slice(&color_scale.color(value).to_rgba8());
+    }
+    data
+}
+
+/// Official NWS reflectivity table: each color covers 5 dBZ from its lower
+/// bound; values below 5 dBZ are transparent.
+const NWS_REFLECTIVITY_STEPS: &[(f32, [u8; 3])] = &[
+    (5.0, [0x04, 0xE9, 0xE7]),
+    (10.0, [0x01, 0x9F, 0xF4]),
+    (15.0, [0x03, 0x00, 0xF4]),
+    (20.0, [0x02, 0xFD, 0x02]),
+    (25.0, [0x01, 0xC5, 0x01]),
+    (30.0, [0x00, 0x8E, 0x00]),
+    (35.0, [0xFD, 0xF8, 0x02]),
+    (40.0, [0xE5, 0xBC, 0x00]),
+    (45.0, [0xFD, 0x95, 0x00]),
+    (50.0, [0xFD, 0x00, 0x00]),
+    (55.0, [0xD4, 0x00, 0x00]),
+    (60.0, [0xBC, 0x00, 0x00]),
+    (65.0, [0xF8, 0x00, 0xFD]),
+    (70.0, [0x98, 0x54, 0xC6]),
+    (75.0, [0xFD, 0xFD, 0xFD]),
+];
+
+/// Flat colors over `[min_val, max_val]`, each step holding from its lower
+/// bound to the next one.
+fn build_stepped_lut(min_val: f32, max_val: f32, steps: &[(f32, [u8; 3])]) -> Vec<u8> {
+    let lut_size = 1024usize;
+    let mut data = Vec::with_capacity(lut_size * 4);
+    for i in 0..lut_size {
+        let value = min_val + i as f32 / (lut_size - 1) as f32 * (max_val - min_val);
+        match steps.iter().rev().find(|(bound, _)| value >= *bound) {
+            Some((_, [r, g, b])) => data.extend_from_slice(&[*r, *g, *b, 255]),
+            None => data.extend_from_slice(&[0, 0, 0, 0]),
+        }
+    }
+    data
+}
+
+/// Whether a product's values are class codes rather than a continuous
+/// quantity. Categorical products must be sampled without interpolation.
+pub fn is_categorical(product_str: &str) -> bool {
+    product_str == HYDROMETEOR_CLASS
+}
+
+/// One flat colour per hydrometeor class over `[class, class + 1)`.
+fn build_hydro_class_lut() -> Vec<u8> {
+    let lut_size = 1024usize;
+    let classes = HydroClass::ALL.len();
+    let mut data = Vec::with_capacity(lut_size * 4);
+    for i in 0..lut_size {
+        let idx = (i * classes / lut_size).min(classes - 1);
+        let [r, g, b] = HydroClass::ALL[idx].color();
+        data.extend_from_slice(&[r, g, b, 255]);
+    }
+    data
+}
+
 pub fn product_from_str(s: &str) -> Product {
     match s {
-        "velocity" => Product::Velocity,
+        "velocity" | "dealiased_velocity" => Product::Velocity,
         "spectrum_width" => Product::SpectrumWidth,
         "differential_reflectivity" => Product::DifferentialReflectivity,
         "differential_phase" => Product::DifferentialPhase,
@@ -257,16 +379,66 @@ pub fn continuous_color_scale(product: Product) -> ColorScale {
             ColorStop::new(270.0, NrColor::rgb(1.0000, 0.0000, 0.0000)),
             ColorStop::new(315.0, NrColor::rgb(1.0000, 0.0000, 1.0000)),
         ],
+        // Diverging around 0 dB with a narrow neutral band.
         Product::ClutterFilterPower => vec![
-            ColorStop::new(-20.0, NrColor::rgb(0.0000, 0.0000, 0.5451)),
-            ColorStop::new(-10.0, NrColor::rgb(0.0000, 0.0000, 0.8039)),
-            ColorStop::new(-5.0, NrColor::rgb(0.6784, 0.8471, 0.9020)),
-            ColorStop::new(-1.0, NrColor::rgb(0.6627, 0.6627, 0.6627)),
-            ColorStop::new(1.0, NrColor::rgb(0.6627, 0.6627, 0.6627)),
-            ColorStop::new(5.0, NrColor::rgb(1.0000, 0.7529, 0.7961)),
-            ColorStop::new(10.0, NrColor::rgb(1.0000, 0.4118, 0.4118)),
-            ColorStop::new(20.0, NrColor::rgb(0.8039, 0.0000, 0.0000)),
+            ColorStop::new(-20.0, NrColor::rgb(0.05, 0.10, 0.40)), // deep blue
+            ColorStop::new(-12.0, NrColor::rgb(0.10, 0.30, 0.75)), // blue
+            ColorStop::new(-5.0, NrColor::rgb(0.45, 0.65, 0.90)),  // light blue
+            ColorStop::new(-1.0, NrColor::rgb(0.62, 0.64, 0.68)),  // blue-grey
+            ColorStop::new(0.0, NrColor::rgb(0.60, 0.60, 0.60)),   // neutral grey
+            ColorStop::new(1.0, NrColor::rgb(0.68, 0.64, 0.62)),   // red-grey
+            ColorStop::new(5.0, NrColor::rgb(0.92, 0.60, 0.45)),   // light red
+            ColorStop::new(12.0, NrColor::rgb(0.80, 0.18, 0.12)),  // red
+            ColorStop::new(20.0, NrColor::rgb(0.42, 0.02, 0.04)),  // deep red
         ],
     };
     ColorScale::Continuous(ContinuousColorScale::new(stops))
 }
+
+/// Echo tops (km MSL), loosely following the NWS EET palette.
+fn echo_tops_color_scale() -> ColorScale {
+    ColorScale::Continuous(ContinuousColorScale::new(vec![
+        ColorStop::new(0.0, NrColor::rgba(0.20, 0.20, 0.30, 0.0)),
+        ColorStop::new(1.5, NrColor::rgb(0.35, 0.40, 0.58)),
+        ColorStop::new(3.0, NrColor::rgb(0.00, 0.55, 0.85)),
+        ColorStop::new(6.0, NrColor::rgb(0.10, 0.75, 0.20)),
+        ColorStop::new(9.0, NrColor::rgb(0.90, 0.88, 0.10)),
+        ColorStop::new(12.0, NrColor::rgb(0.92, 0.50, 0.08)),
+        ColorStop::new(15.0, NrColor::rgb(0.85, 0.12, 0.10)),
+        ColorStop::new(18.0, NrColor::rgb(0.95, 0.25, 0.80)),
+        ColorStop::new(20.0, NrColor::rgb(1.00, 1.00, 1.00)),
+    ]))
+}
+
+/// Vertically integrated liquid (kg/m²).
+fn vil_color_scale() -> ColorScale {
+    ColorScale::Continuous(ContinuousColorScale::new(vec![
+        ColorStop::new(0.0, NrColor::rgba(0.20, 0.22, 0.32, 0.0)),
+        ColorStop::new(1.0, NrColor::rgb(0.35, 0.40, 0.58)),
+        ColorStop::new(5.0, NrColor::rgb(0.15, 0.72, 0.15)),
+        ColorStop::new(15.0, NrColor::rgb(0.05, 0.45, 0.08)),
+        ColorStop::new(25.0, NrColor::rgb(0.90, 0.88, 0.10)),
+        ColorStop::new(35.0, NrColor::rgb(0.92, 0.58, 0.08)),
+        ColorStop::new(45.0, NrColor::rgb(0.85, 0.12, 0.10)),
+        ColorStop::new(60.0, NrColor::rgb(0.95, 0.25, 0.55)),
+        ColorStop::new(80.0, NrColor::rgb(0.68, 0.20, 0.85)),
+    ]))
+}
+
+/// Rainfall accumulation (mm), stops at the usual 0.1"–10" breakpoints.
+fn accumulation_color_scale() -> ColorScale {
+    ColorScale::Continuous(ContinuousColorScale::new(vec![
+        ColorStop::new(0.0, NrColor::rgba(0.20, 0.22, 0.32, 0.0)),
+        ColorStop::new(2.5, NrColor::rgb(0.55, 0.85, 0.55)),
+        ColorStop::new(6.4, NrColor::rgb(0.15, 0.65, 0.15)),
+        ColorStop::new(12.7, NrColor::rgb(0.05, 0.40, 0.10)),
+        ColorStop::new(25.4, NrColor::rgb(0.25, 0.55, 0.90)),
+        ColorStop::new(38.1, NrColor::rgb(0.10, 0.25, 0.75)),
+        ColorStop::new(50.8, NrColor::rgb(0.90, 0.88, 0.10)),
+        ColorStop::new(76.2, NrColor::rgb(0.92, 0.55, 0.08)),
+        ColorStop::new(101.6, NrColor::rgb(0.85, 0.12, 0.10)),
+        ColorStop::new(152.4, NrColor::rgb(0.95, 0.25, 0.80)),
+        ColorStop::new(203.2, NrColor::rgb(0.60, 0.20, 0.85)),
+        ColorStop::new(254.0, NrColor::rgb(1.00, 1.00, 1.00)),
+    ]))
+}
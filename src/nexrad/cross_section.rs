@@ -0,0 +1,280 @@
+//! Vertical cross-sections (range-height slices) through a volume.
+//!
+//! A cross-section samples every tilt of one product along a straight line on
+//! the ground. Each cell of the output grid is a (distance along the line,
+//! height above the radar) pair; its elevation angle and slant range follow
+//! from the 4/3 effective earth radius model, and the value is interpolated
+//! linearly in elevation angle between the two tilts that bracket it.
+//! Cells more than half a beamwidth outside the lowest or highest tilt, or
+//! between tilts where neither has data, are left empty.
+//!
+//! Line endpoints are radar-relative `(east, north)` kilometres.
+
+use super::beam::elevation_and_slant;
+use super::polar::AzimuthIndex;
+use crate::data::keys::{GateValues, PrecomputedSweep};
+
+/// Output grid size: columns along the line, rows of height.
+const COLUMNS: usize = 400;
+const ROWS: usize = 180;
+
+/// Height of the top row's upper edge above the radar, km (~59 kft).
+const TOP_KM: f64 = 18.0;
+
+/// Half the WSR-88D beamwidth, degrees. A tilt still covers cells this far
+/// above or below its beam centre.
+const HALF_BEAMWIDTH_DEG: f32 = 0.5;
+
+/// Tilts within this many degrees of an already-used one are treated as
+/// repeats (split cuts, SAILS re-scans of the base tilt).
+const MIN_TILT_STEP_DEG: f32 = 0.05;
+
+/// A range-height grid of physical values.
+pub struct CrossSection {
+    pub columns: usize,
+    pub rows: usize,
+    /// Ground length of the line, km.
+    pub length_km: f64,
+    /// Height of the top edge of the grid, km.
+    pub top_km: f64,
+    /// Row-major values, row 0 at the top. NaN where no tilt covers a cell.
+    pub values: Vec<f32>,
+    /// Elevation angles of the tilts that contributed, ascending.
+    pub tilts_deg: Vec<f32>,
+}
+
+impl CrossSection {
+    /// Value at `(column, row)`, `None` when the cell is empty.
+    pub fn value(&self, column: usize, row: usize) -> Option<f32> {
+        let v = *self.values.get(row * self.columns + column)?;
+        (!v.is_nan()).then_some(v)
+    }
+
+    /// Whether no cell has data.
+    pub fn is_empty(&self) -> bool {
+        self.values.iter().all(|v| v.is_nan())
+    }
+}
+
+/// One usable tilt with a nearest-radial lookup for fast sampling.
+struct Tilt<'a> {
+    sweep: &'a PrecomputedSweep,
+    elevation: f32,
+    radials: AzimuthIndex,
+}
+
+impl<'a> Tilt<'a> {
+    fn new(sweep: &'a PrecomputedSweep) -> Self {
+        Self {
+            sweep,
+            elevation: sweep.mean_elevation,
+            radials: AzimuthIndex::new(&sweep.azimuths, sweep.azimuth_count as usize),
+        }
+    }
+
+    /// Physical value at `azimuth_deg`, `slant_km`, or `None` without data.
+    fn sample(&self, azimuth_deg: f32, slant_km: f64) -> Option<f32> {
+        let s = self.sweep;
+        let radial = self.radials.radial(azimuth_deg)?;
+        let gate = (slant_km - s.first_gate_range_km) / s.gate_interval_km;
+        if gate < 0.0 || gate >= s.gate_count as f64 {
+            return None;
+        }
+        let idx = radial * s.gate_count as usize + gate as usize;
+        let raw = match &s.gate_values {
+            GateValues::U8(v) => *v.get(idx)? as f32,
+            GateValues::U16(v) => *v.get(idx)? as f32,
+        };
+        if raw <= 1.0 {
+            return None;
+        }
+        Some(if s.scale == 0.0 {
+            raw
+        } else {
+            (raw - s.offset) / s.scale
+        })
+    }
+}
+
+/// Slice `sweeps` (one product, any order) along the line from `start_km`
+/// to `end_km`.
+///
+/// `storm_motion_ms` (east, north) is subtracted from each sample's radial
+/// component, for storm-relative velocity; pass zeros otherwise. Categorical
+/// products take the nearest tilt instead of interpolating.
+pub(crate) fn build_cross_section(
+    sweeps: &[PrecomputedSweep],
+    start_km: (f64, f64),
+    end_km: (f64, f64),
+    storm_motion_ms: [f32; 2],
+    categorical: bool,
+) -> CrossSection {
+    let mut usable: Vec<&PrecomputedSweep> = sweeps
+        .iter()
+        .filter(|s| s.azimuth_count > 0 && s.gate_count > 0)
+        .collect();
+    usable.sort_by(|a, b| a.mean_elevation.total_cmp(&b.mean_elevation));
+    let mut tilts: Vec<Tilt> = Vec::with_capacity(usable.len());
+    for sweep in usable {
+        if tilts
+            .last()
+            .is_some_and(|t| sweep.mean_elevation <= t.elevation + MIN_TILT_STEP_DEG)
+        {
+            continue;
+        }
+        tilts.push(Tilt::new(sweep));
+    }
+
+    let (dx, dy) = (end_km.0 - start_km.0, end_km.1 - start_km.1);
+    let length_km = dx.hypot(dy);
+    let mut values = vec![f32::NAN; COLUMNS * ROWS];
+
+    for col in 0..COLUMNS {
+        let t = (col as f64 + 0.5) / COLUMNS as f64;
+        let (x, y) = (start_km.0 + t * dx, start_km.1 + t * dy);
+        let ground_km = x.hypot(y);
+        let azimuth = x.atan2(y).to_degrees().rem_euclid(360.0) as f32;
+        let (sin, cos) = azimuth.to_radians().sin_cos();
+        let motion = storm_motion_ms[0] * sin + storm_motion_ms[1] * cos;
+
+        for row in 0..ROWS {
+            let height_km = TOP_KM * (1.0 - (row as f64 + 0.5) / ROWS as f64);
+            let (elevation, slant_km) = elevation_and_slant(ground_km, height_km);
+            let value = if categorical {
+                sample_nearest(&tilts, elevation, azimuth, slant_km)
+            } else {
+                sample_interpolated(&tilts, elevation, azimuth, slant_km)
+            };
+            if let Some(v) = value {
+                values[row * COLUMNS + col] = v - motion;
+            }
+        }
+    }
+
+    CrossSection {
+        columns: COLUMNS,
+        rows: ROWS,
+        length_km,
+        top_km: TOP_KM,
+        values,
+        tilts_deg: tilts.iter().map(|t| t.elevation).collect(),
+    }
+}
+
+/// Value from the tilt whose beam centre is closest to `elevation`, if the
+/// point lies within its beam.
+fn sample_nearest(tilts: &[Tilt], elevation: f32, azimuth: f32, slant_km: f64) -> Option<f32> {
+    let tilt = tilts.iter().min_by(|a, b| {
+        (a.elevation - elevation)
+            .abs()
+            .total_cmp(&(b.elevation - elevation).abs())
+    })?;
+    if (tilt.elevation - elevation).abs() > HALF_BEAMWIDTH_DEG {
+        return None;
+    }
+    tilt.sample(azimuth, slant_km)
+}
+
+/// Value linearly interpolated in elevation angle between the bracketing
+/// tilts. Where only one of them has data, it is used if the point lies
+/// within its beam.
+fn sample_interpolated(tilts: &[Tilt], elevation: f32, azimuth: f32, slant_km: f64) -> Option<f32> {
+    let upper = tilts.partition_point(|t| t.elevation < elevation);
+    let below = upper.checked_sub(1).map(|i| &tilts[i]);
+    let above = tilts.get(upper);
+    let within_beam = |t: &Tilt| (t.elevation - elevation).abs() <= HALF_BEAMWIDTH_DEG;
+
+    let low = below.and_then(|t| Some((t.elevation, t.sample(azimuth, slant_km)?)));
+    let high = above.and_then(|t| Some((t.elevation, t.sample(azimuth, slant_km)?)));
+    match (low, high) {
+        (Some((e0, v0)), Some((e1, v1))) => {
+            let w = if e1 > e0 {
+                (elevation - e0) / (e1 - e0)
+            } else {
+                0.0
+            };
+            Some(v0 + (v1 - v0) * w)
+        }
+        (Some((_, v)), None) if below.is_some_and(within_beam) => Some(v),
+        (None, Some((_, v))) if above.is_some_and(within_beam) => Some(v),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::SweepFixture;
+    use crate::nexrad::beam::beam_height_km;
+
+    const AZ_COUNT: usize = 360;
+    const GATE_COUNT: usize = 400;
+    const GATE_KM: f64 = 0.5;
+
+    /// Tilt with the same value at every gate, encoded like the 8-bit REF
+    /// moment (`dbz = (raw - 66) / 2`).
+    fn tilt(elevation: f32, dbz: f32) -> PrecomputedSweep {
+        SweepFixture::new(AZ_COUNT, GATE_COUNT, GATE_KM)
+            .elevation(elevation)
+            .u8(|_, _| Some(dbz))
+    }
+
+    /// Row whose centre is closest to `height_km`.
+    fn row_at(height_km: f64) -> usize {
+        ((1.0 - height_km / TOP_KM) * ROWS as f64) as usize
+    }
+
+    #[test]
+    fn interpolates_between_tilts() {
+        let sweeps = [tilt(0.5, 20.0), tilt(1.5, 40.0)];
+        let section = build_cross_section(&sweeps, (0.0, 50.0), (0.0, 150.0), [0.0; 2], false);
+        assert_eq!(section.tilts_deg, vec![0.5, 1.5]);
+        assert!((section.length_km - 100.0).abs() < 1e-9);
+
+        // Halfway between the two beams at 100 km.
+        let col = COLUMNS / 2;
+        let height = (beam_height_km(100.0, 0.5) + beam_height_km(100.0, 1.5)) / 2.0;
+        let v = section.value(col, row_at(height)).expect("between tilts");
+        assert!((v - 30.0).abs() < 2.0, "value {}", v);
+
+        // Far above the top tilt and at the ground far out: no coverage.
+        assert_eq!(section.value(col, row_at(10.0)), None);
+        assert_eq!(section.value(COLUMNS - 1, ROWS - 1), None);
+    }
+
+    #[test]
+    fn one_sided_data_only_within_beam() {
+        let mut upper = tilt(1.5, 40.0);
+        upper.gate_values = GateValues::U8(vec![0; AZ_COUNT * GATE_COUNT]);
+        let sweeps = [tilt(0.5, 20.0), upper];
+        let section = build_cross_section(&sweeps, (0.0, 50.0), (0.0, 150.0), [0.0; 2], false);
+        let col = COLUMNS / 2;
+        let on_beam = section.value(col, row_at(beam_height_km(100.0, 0.5)));
+        assert!(on_beam.is_some_and(|v| (v - 20.0).abs() < 0.5));
+        assert_eq!(section.value(col, row_at(beam_height_km(100.0, 1.4))), None);
+    }
+
+    #[test]
+    fn repeated_base_tilt_is_skipped() {
+        let sweeps = [tilt(0.5, 20.0), tilt(0.52, 60.0), tilt(1.5, 40.0)];
+        let section = build_cross_section(&sweeps, (0.0, 50.0), (0.0, 150.0), [0.0; 2], true);
+        assert_eq!(section.tilts_deg, vec![0.5, 1.5]);
+    }
+
+    #[test]
+    fn storm_motion_is_removed_along_the_radial() {
+        let sweeps = [tilt(0.5, 10.0)];
+        // Line due north of the radar: 10 m/s northward motion is fully radial.
+        let section = build_cross_section(&sweeps, (0.0, 50.0), (0.0, 150.0), [0.0, 10.0], false);
+        let col = COLUMNS / 2;
+        let v = section.value(col, row_at(beam_height_km(100.0, 0.5)));
+        assert!(v.is_some_and(|v| v.abs() < 0.5), "value {:?}", v);
+    }
+
+    #[test]
+    fn empty_without_tilts() {
+        let section = build_cross_section(&[], (0.0, 0.0), (10.0, 0.0), [0.0; 2], false);
+        assert!(section.is_empty());
+        assert!(section.tilts_deg.is_empty());
+    }
+}
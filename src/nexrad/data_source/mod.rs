@@ -0,0 +1,398 @@
+//! Where Level II archive volumes, real-time chunks and Level III products are
+//! fetched from.
+//!
+//! By default all three come from the public Unidata buckets on AWS. Any can be
+//! pointed at another S3-compatible base URL: an internal mirror, MinIO, or
+//! `scripts/fixture_server.py` serving a local directory laid out like the
+//! bucket. The real-time bucket can also be swapped for a [`replay`] that
+//! cuts archive volumes back into chunks and releases them on a clock.
+//!
+//! The functions here stand in for the `nexrad_data::aws` helpers of the same
+//! names and return the same result types, so a chunk that isn't there yet is
+//! still `AWSError::S3ObjectNotFound` to the streaming loop. The active source
+//! is process-wide and set from [`DataSourceSettings`] with [`configure`].
+
+mod replay;
+
+use crate::state::DataSourceSettings;
+use chrono::{DateTime, NaiveDate, Utc};
+use nexrad_data::aws::archive::Identifier;
+use nexrad_data::aws::realtime::{Chunk, ChunkIdentifier, VolumeIndex};
+use nexrad_data::result::{aws::AWSError, Error, Result};
+use nexrad_data::volume::File;
+use replay::Replay;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// The public Level II archive bucket.
+pub const AWS_ARCHIVE_URL: &str = "https://unidata-nexrad-level2.s3.amazonaws.com";
+
+/// The public real-time chunk bucket.
+pub const AWS_REALTIME_URL: &str = "https://unidata-nexrad-level2-chunks.s3.amazonaws.com";
+
+/// The public Level III product bucket.
+pub const AWS_LEVEL3_URL: &str = "https://unidata-nexrad-level3.s3.amazonaws.com";
+
+/// The configured source. Cloned out of [`ACTIVE`] per call, so a
+/// [`configure`] mid-request doesn't affect requests already running.
+struct Source {
+    archive_url: String,
+    realtime: RealtimeSource,
+    level3_url: String,
+}
+
+enum RealtimeSource {
+    Bucket(String),
+    Replay(Replay),
+}
+
+// WASM is single-threaded so thread_local! is safe.
+thread_local! {
+    static ACTIVE: RefCell<Rc<Source>> = RefCell::new(Rc::new(Source {
+        archive_url: AWS_ARCHIVE_URL.to_string(),
+        realtime: RealtimeSource::Bucket(AWS_REALTIME_URL.to_string()),
+        level3_url: AWS_LEVEL3_URL.to_string(),
+    }));
+}
+
+fn active() -> Rc<Source> {
+    ACTIVE.with(|active| active.borrow().clone())
+}
+
+/// Trimmed `url` without a trailing slash, or `default` when it's empty.
+fn base_url(url: &str, default: &str) -> String {
+    match url.trim().trim_end_matches('/') {
+        "" => default.to_string(),
+        url => url.to_string(),
+    }
+}
+
+/// Point every following fetch at the source in `settings`. A replay starts
+/// its clock now.
+pub fn configure(settings: &DataSourceSettings) {
+    let archive_url = base_url(&settings.archive_url, AWS_ARCHIVE_URL);
+    let realtime = match settings.replay {
+        Some(replay) => {
+            let now = crate::platform::now_secs();
+            log::info!(
+                "Data source: replaying archive from {} at {}x",
+                replay.start_secs,
+                replay.speed
+            );
+            RealtimeSource::Replay(Replay::new(replay.start_secs as f64, now, replay.speed))
+        }
+        None => RealtimeSource::Bucket(base_url(&settings.realtime_url, AWS_REALTIME_URL)),
+    };
+    let level3_url = base_url(&settings.level3_url, AWS_LEVEL3_URL);
+    log::info!("Data source: archive at {}", archive_url);
+    log::info!("Data source: Level III at {}", level3_url);
+    ACTIVE.with(|active| {
+        *active.borrow_mut() = Rc::new(Source {
+            archive_url,
+            realtime,
+            level3_url,
+        })
+    });
+}
+
+/// Archive files for `site` on `date`, like `nexrad_data::aws::archive::list_files`.
+pub async fn list_archive_files(site: &str, date: &NaiveDate) -> Result<Vec<Identifier>> {
+    let source = active();
+    let prefix = format!("{}/{}/", date.format("%Y/%m/%d"), site);
+    let objects = list_objects(&source.archive_url, &prefix, None).await?;
+    Ok(objects
+        .into_iter()
+        .map(|object| Identifier::new(object_name(&object.key).to_string()))
+        .collect())
+}
+
+/// Download an archive file, like `nexrad_data::aws::archive::download_file`.
+pub async fn download_archive_file(identifier: Identifier) -> Result<File> {
+    let source = active();
+    let key = archive_key(&identifier)?;
+    let data = get_object(&source.archive_url, &key).await?;
+    Ok(File::new(data))
+}
+
+/// Chunks in a real-time volume directory, like
+/// `nexrad_data::aws::realtime::list_chunks_in_volume`.
+pub async fn list_chunks_in_volume(
+    site: &str,
+    volume: VolumeIndex,
+    max_keys: usize,
+) -> Result<Vec<ChunkIdentifier>> {
+    match &active().realtime {
+        RealtimeSource::Bucket(url) => {
+            let prefix = format!("{}/{}/", site, volume.as_number());
+            list_objects(url, &prefix, Some(max_keys))
+                .await?
+                .into_iter()
+                .map(|object| {
+                    ChunkIdentifier::from_name(
+                        site.to_string(),
+                        volume,
+                        object_name(&object.key).to_string(),
+                        object.last_modified,
+                    )
+                })
+                .collect()
+        }
+        RealtimeSource::Replay(replay) => replay.list_chunks(site, volume, max_keys).await,
+    }
+}
+
+/// Download a real-time chunk, like `nexrad_data::aws::realtime::download_chunk`.
+pub async fn download_chunk(
+    site: &str,
+    chunk_id: &ChunkIdentifier,
+) -> Result<(ChunkIdentifier, Chunk<'static>)> {
+    match &active().realtime {
+        RealtimeSource::Bucket(url) => {
+            let key = format!(
+                "{}/{}/{}",
+                site,
+                chunk_id.volume().as_number(),
+                chunk_id.name()
+            );
+            let (data, last_modified) = get_object_with_time(url, &key).await?;
+            let identifier = ChunkIdentifier::from_name(
+                site.to_string(),
+                *chunk_id.volume(),
+                chunk_id.name().to_string(),
+                last_modified,
+            )?;
+            Ok((identifier, Chunk::new(data)?))
+        }
+        RealtimeSource::Replay(replay) => replay.download_chunk(site, chunk_id).await,
+    }
+}
+
+/// Keys of every Level III product under `prefix`, across all listing pages.
+pub async fn list_level3_keys(prefix: &str) -> Result<Vec<String>> {
+    let source = active();
+    let objects = list_objects(&source.level3_url, prefix, None).await?;
+    Ok(objects.into_iter().map(|object| object.key).collect())
+}
+
+/// Download a Level III product file.
+pub async fn download_level3_file(key: &str) -> Result<Vec<u8>> {
+    let source = active();
+    get_object(&source.level3_url, key).await
+}
+
+/// Bucket key of an archive file: `YYYY/MM/DD/SITE/NAME`.
+fn archive_key(identifier: &Identifier) -> Result<String> {
+    let date = identifier
+        .date_time()
+        .ok_or_else(|| Error::AWS(AWSError::DateTime(identifier.name().to_string())))?;
+    let site = identifier.site().ok_or_else(|| {
+        Error::AWS(AWSError::InvalidSiteIdentifier(
+            identifier.name().to_string(),
+        ))
+    })?;
+    Ok(format!(
+        "{}/{}/{}",
+        date.format("%Y/%m/%d"),
+        site,
+        identifier.name()
+    ))
+}
+
+/// Last path segment of an object key.
+fn object_name(key: &str) -> &str {
+    key.rsplit('/').next().unwrap_or(key)
+}
+
+// ── S3 over fetch ──────────────────────────────────────────────────────
+
+/// An entry in a `ListObjectsV2` response.
+#[derive(Debug, PartialEq)]
+struct ListedObject {
+    key: String,
+    last_modified: Option<DateTime<Utc>>,
+}
+
+/// One page of a `ListObjectsV2` response.
+#[derive(Debug, PartialEq)]
+struct ListPage {
+    objects: Vec<ListedObject>,
+    /// Token for the next page when the listing was truncated.
+    continuation: Option<String>,
+}
+
+/// Text of the first `<name>` element in `xml`.
+fn element<'a>(xml: &'a str, name: &str) -> Option<&'a str> {
+    let open = format!("<{name}>");
+    let close = format!("</{name}>");
+    let start = xml.find(&open)? + open.len();
+    let len = xml[start..].find(&close)?;
+    Some(&xml[start..start + len])
+}
+
+fn unescape_xml(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+fn parse_list_page(xml: &str) -> Option<ListPage> {
+    if !xml.contains("<ListBucketResult") {
+        return None;
+    }
+    let objects = xml
+        .split("<Contents>")
+        .skip(1)
+        .filter_map(|rest| {
+            let contents = rest.split("</Contents>").next()?;
+            Some(ListedObject {
+                key: unescape_xml(element(contents, "Key")?),
+                last_modified: element(contents, "LastModified")
+                    .and_then(|t| DateTime::parse_from_rfc3339(t).ok())
+                    .map(|t| t.with_timezone(&Utc)),
+            })
+        })
+        .collect();
+    let continuation = (element(xml, "IsTruncated") == Some("true"))
+        .then(|| element(xml, "NextContinuationToken").map(unescape_xml))
+        .flatten();
+    Some(ListPage {
+        objects,
+        continuation,
+    })
+}
+
+/// Percent-encode a query parameter value.
+fn encode_query(value: &str) -> String {
+    value
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' | b'/' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{b:02X}"),
+        })
+        .collect()
+}
+
+/// List objects under `prefix`. With `max_keys`, only the first page of at
+/// most that many; otherwise every page.
+async fn list_objects(
+    base_url: &str,
+    prefix: &str,
+    max_keys: Option<usize>,
+) -> Result<Vec<ListedObject>> {
+    let mut objects = Vec::new();
+    let mut continuation: Option<String> = None;
+    loop {
+        let mut url = format!("{}/?list-type=2&prefix={}", base_url, encode_query(prefix));
+        if let Some(max_keys) = max_keys {
+            url.push_str(&format!("&max-keys={max_keys}"));
+        }
+        if let Some(token) = &continuation {
+            url.push_str(&format!("&continuation-token={}", encode_query(token)));
+        }
+        let (body, _) = fetch(&url).await.map_err(|e| e.into_error(&url))?;
+        let page = parse_list_page(&String::from_utf8_lossy(&body))
+            .ok_or(Error::AWS(AWSError::S3ListObjectsDecoding))?;
+        objects.extend(page.objects);
+        continuation = page.continuation;
+        if continuation.is_none() || max_keys.is_some() {
+            return Ok(objects);
+        }
+    }
+}
+
+async fn get_object(base_url: &str, key: &str) -> Result<Vec<u8>> {
+    get_object_with_time(base_url, key)
+        .await
+        .map(|(data, _)| data)
+}
+
+/// An object's bytes and its `Last-Modified` time.
+async fn get_object_with_time(
+    base_url: &str,
+    key: &str,
+) -> Result<(Vec<u8>, Option<DateTime<Utc>>)> {
+    let url = format!("{}/{}", base_url, key);
+    fetch(&url).await.map_err(|e| e.into_error(&url))
+}
+
+enum FetchError {
+    NotFound,
+    Failed(String),
+}
+
+impl FetchError {
+    fn into_error(self, url: &str) -> Error {
+        match self {
+            FetchError::NotFound => Error::AWS(AWSError::S3ObjectNotFound),
+            FetchError::Failed(message) => {
+                // nexrad-data's error doesn't print its detail, so log it here.
+                log::warn!("Request to {} failed: {}", url, message);
+                Error::AWS(AWSError::S3GetObject(Some(message)))
+            }
+        }
+    }
+}
+
+async fn fetch(url: &str) -> std::result::Result<(Vec<u8>, Option<DateTime<Utc>>), FetchError> {
+    let resp = crate::platform::http_get(url, &[])
+        .await
+        .map_err(FetchError::Failed)?;
+    if resp.status == 404 {
+        return Err(FetchError::NotFound);
+    }
+    if !resp.ok() {
+        return Err(FetchError::Failed(format!("HTTP {}", resp.status)));
+    }
+    let last_modified = resp
+        .header("Last-Modified")
+        .and_then(|t| DateTime::parse_from_rfc2822(t).ok())
+        .map(|t| t.with_timezone(&Utc));
+    Ok((resp.body, last_modified))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_list_pages() {
+        let xml = "<?xml version=\"1.0\"?><ListBucketResult><IsTruncated>true</IsTruncated>\
+                   <Contents><Key>KTLX/42/20240501-120000-001-S</Key>\
+                   <LastModified>2024-05-01T12:00:04.000Z</LastModified><Size>9</Size></Contents>\
+                   <Contents><Key>KTLX/42/a&amp;b</Key></Contents>\
+                   <NextContinuationToken>1x+y=</NextContinuationToken></ListBucketResult>";
+        let page = parse_list_page(xml).unwrap();
+        assert_eq!(page.objects.len(), 2);
+        assert_eq!(object_name(&page.objects[0].key), "20240501-120000-001-S");
+        assert_eq!(
+            page.objects[0].last_modified.map(|t| t.timestamp()),
+            Some(1_714_564_804)
+        );
+        assert_eq!(page.objects[1].key, "KTLX/42/a&b");
+        assert_eq!(page.objects[1].last_modified, None);
+        assert_eq!(page.continuation.as_deref(), Some("1x+y="));
+        assert_eq!(encode_query("1x+y="), "1x%2By%3D");
+
+        let last = "<ListBucketResult><IsTruncated>false</IsTruncated></ListBucketResult>";
+        assert_eq!(parse_list_page(last).unwrap().continuation, None);
+        assert!(parse_list_page("<Error><Code>NoSuchBucket</Code></Error>").is_none());
+    }
+
+    #[test]
+    fn builds_archive_keys_and_base_urls() {
+        let id = Identifier::new("KTLX20130520_200356_V06".to_string());
+        assert_eq!(
+            archive_key(&id).unwrap(),
+            "2013/05/20/KTLX/KTLX20130520_200356_V06"
+        );
+        assert_eq!(base_url("  ", AWS_ARCHIVE_URL), AWS_ARCHIVE_URL);
+        assert_eq!(
+            base_url("http://localhost:9000/level2/", AWS_ARCHIVE_URL),
+            "http://localhost:9000/level2"
+        );
+    }
+}
@@ -0,0 +1,443 @@
+//! Simulated real-time feed cut from archive volumes.
+//!
+//! An Archive II file is its real-time chunks concatenated: the volume
+//! header and metadata record make the Start chunk, and every following LDM
+//! record is one more chunk. The replay cuts volumes back apart and releases
+//! their chunks on a [`ReplayClock`], spreading each volume's chunks evenly
+//! until the next volume begins. Volumes are numbered in order from the first
+//! file of the start day, wrapping like the real bucket's 999 directories.
+//!
+//! Chunk names and upload times are in wall-clock time, as a live feed would
+//! have them, so volume discovery and chunk timing run unchanged. The radials
+//! inside keep their archive timestamps.
+
+use super::{download_archive_file, list_archive_files};
+use crate::nexrad::archive_index::ArchiveFileMeta;
+use chrono::{DateTime, Days, NaiveDate, NaiveDateTime};
+use futures_util::future::{FutureExt, LocalBoxFuture, Shared};
+use nexrad_data::aws::realtime::{Chunk, ChunkIdentifier, ChunkType, VolumeIndex};
+use nexrad_data::result::{aws::AWSError, Error, Result};
+use nexrad_data::volume::{File, Header};
+use std::cell::RefCell;
+use std::collections::{HashMap, VecDeque};
+use std::rc::Rc;
+
+/// Number of volume directories in the real-time bucket.
+const VOLUME_COUNT: usize = 999;
+
+/// Span given to a volume whose successor isn't known.
+const DEFAULT_VOLUME_SECS: f64 = 300.0;
+
+/// Bounds on a volume's span, so gaps in the archive don't stretch one
+/// volume's chunks over hours.
+const MIN_VOLUME_SECS: f64 = 60.0;
+const MAX_VOLUME_SECS: f64 = 900.0;
+
+/// Wall-clock seconds between listings of today, when the replay is close
+/// enough to the present to run out of archived volumes.
+const RELIST_SECS: f64 = 60.0;
+
+/// Cut volumes kept in memory.
+const CACHED_VOLUMES: usize = 4;
+
+/// Maps wall-clock time to archive time.
+#[derive(Clone, Copy, Debug)]
+pub struct ReplayClock {
+    /// Archive time at `wall_start`, Unix seconds.
+    archive_start: f64,
+    /// Wall-clock time the replay started, Unix seconds.
+    wall_start: f64,
+    /// Archive seconds per wall-clock second.
+    speed: f64,
+}
+
+impl ReplayClock {
+    pub fn new(archive_start: f64, wall_start: f64, speed: f64) -> Self {
+        Self {
+            archive_start,
+            wall_start,
+            speed: speed.max(0.01),
+        }
+    }
+
+    pub fn archive_time(&self, wall: f64) -> f64 {
+        self.archive_start + (wall - self.wall_start) * self.speed
+    }
+
+    pub fn wall_time(&self, archive: f64) -> f64 {
+        self.wall_start + (archive - self.archive_start) / self.speed
+    }
+}
+
+/// An archive file in a site's replay timeline.
+#[derive(Clone, Debug)]
+struct ArchiveVolume {
+    name: String,
+    /// Volume start from the file name, Unix seconds.
+    start: f64,
+}
+
+/// A site's archive files from the start day on, oldest first.
+#[derive(Default)]
+struct Timeline {
+    volumes: Vec<ArchiveVolume>,
+    listed_through: Option<NaiveDate>,
+    /// Wall-clock time `listed_through` was listed, Unix seconds.
+    listed_at: f64,
+}
+
+/// Chunks of a volume, downloaded and cut once however many callers ask.
+type CutVolume = Shared<LocalBoxFuture<'static, std::result::Result<Rc<Vec<Vec<u8>>>, String>>>;
+
+#[derive(Default)]
+struct ReplayCache {
+    timelines: HashMap<String, Timeline>,
+    volumes: HashMap<String, CutVolume>,
+    /// Keys of `volumes`, oldest first.
+    order: VecDeque<String>,
+}
+
+pub(super) struct Replay {
+    clock: ReplayClock,
+    cache: RefCell<ReplayCache>,
+}
+
+impl Replay {
+    pub fn new(archive_start: f64, wall_start: f64, speed: f64) -> Self {
+        Self {
+            clock: ReplayClock::new(archive_start, wall_start, speed),
+            cache: RefCell::default(),
+        }
+    }
+
+    fn now(&self) -> f64 {
+        self.clock.archive_time(crate::platform::now_secs())
+    }
+
+    /// Chunks of the newest volume in `volume`'s directory, as listed now.
+    pub async fn list_chunks(
+        &self,
+        site: &str,
+        volume: VolumeIndex,
+        max_keys: usize,
+    ) -> Result<Vec<ChunkIdentifier>> {
+        let now = self.now();
+        let volumes = self.timeline(site, now).await?;
+        let Some(position) = latest_in_volume(&volumes, volume.as_number(), now) else {
+            return Ok(Vec::new());
+        };
+        let start = volumes[position].start;
+        // The Start chunk is due at the volume start, so a one-key probe
+        // doesn't need the file.
+        if max_keys <= 1 {
+            return Ok(vec![self.identifier(site, volume, start, 1, 0, start)]);
+        }
+
+        let chunks = self.cut_volume(&volumes[position].name).await?;
+        let times = chunk_times(start, volume_span(&volumes, position), chunks.len());
+        Ok(times
+            .iter()
+            .take_while(|&&t| t <= now)
+            .take(max_keys)
+            .enumerate()
+            .map(|(i, &t)| self.identifier(site, volume, start, i + 1, chunks.len(), t))
+            .collect())
+    }
+
+    /// A chunk, or `S3ObjectNotFound` if it isn't due yet.
+    pub async fn download_chunk(
+        &self,
+        site: &str,
+        chunk_id: &ChunkIdentifier,
+    ) -> Result<(ChunkIdentifier, Chunk<'static>)> {
+        let now = self.now();
+        let volumes = self.timeline(site, now).await?;
+        let volume = *chunk_id.volume();
+        let position = (0..volumes.len())
+            .rev()
+            .find(|&p| {
+                volume_number(p) == volume.as_number()
+                    && volumes[p].start <= now
+                    && self.name_prefix(volumes[p].start) == *chunk_id.date_time_prefix()
+            })
+            .ok_or(Error::AWS(AWSError::S3ObjectNotFound))?;
+
+        let start = volumes[position].start;
+        let chunks = self.cut_volume(&volumes[position].name).await?;
+        let times = chunk_times(start, volume_span(&volumes, position), chunks.len());
+        let sequence = chunk_id.sequence();
+        let due = sequence
+            .checked_sub(1)
+            .and_then(|i| times.get(i))
+            .filter(|&&t| t <= now)
+            .ok_or(Error::AWS(AWSError::S3ObjectNotFound))?;
+
+        let identifier = self.identifier(site, volume, start, sequence, chunks.len(), *due);
+        Ok((identifier, Chunk::new(chunks[sequence - 1].clone())?))
+    }
+
+    /// Chunk name prefix of a volume starting at archive time `start`.
+    fn name_prefix(&self, start: f64) -> NaiveDateTime {
+        DateTime::from_timestamp(self.clock.wall_time(start).floor() as i64, 0)
+            .unwrap_or_default()
+            .naive_utc()
+    }
+
+    /// Identifier of chunk `sequence` of `count` (0 when unknown), uploaded
+    /// at archive time `due`.
+    fn identifier(
+        &self,
+        site: &str,
+        volume: VolumeIndex,
+        start: f64,
+        sequence: usize,
+        count: usize,
+        due: f64,
+    ) -> ChunkIdentifier {
+        let chunk_type = if sequence == 1 {
+            ChunkType::Start
+        } else if sequence == count {
+            ChunkType::End
+        } else {
+            ChunkType::Intermediate
+        };
+        ChunkIdentifier::new(
+            site.to_string(),
+            volume,
+            self.name_prefix(start),
+            sequence,
+            chunk_type,
+            DateTime::from_timestamp_millis((self.clock.wall_time(due) * 1000.0) as i64),
+        )
+    }
+
+    /// The site's timeline, listed far enough to know the successor of any
+    /// volume started by `now`.
+    async fn timeline(&self, site: &str, now: f64) -> Result<Vec<ArchiveVolume>> {
+        let wall_now = crate::platform::now_secs();
+        let today = date_of(wall_now);
+        let first_day = date_of(self.clock.archive_start);
+        // Days past today have nothing archived yet.
+        let last_day = date_of(now + MAX_VOLUME_SECS).min(today);
+        loop {
+            let day = {
+                let cache = self.cache.borrow();
+                let timeline = cache.timelines.get(site);
+                let known_end = timeline
+                    .and_then(|t| t.volumes.last())
+                    .map_or(f64::MIN, |v| v.start + DEFAULT_VOLUME_SECS);
+                match timeline.and_then(|t| t.listed_through.map(|d| (d, t.listed_at))) {
+                    None => first_day,
+                    Some((day, _)) if day < last_day => day + Days::new(1),
+                    // Today's listing grows as volumes are archived, so look
+                    // again once the replay has caught up with it.
+                    Some((day, listed_at))
+                        if day == today
+                            && now > known_end
+                            && wall_now - listed_at >= RELIST_SECS =>
+                    {
+                        day
+                    }
+                    _ => break,
+                }
+            };
+
+            let listed = list_archive_files(site, &day).await?;
+            let mut day_volumes: Vec<ArchiveVolume> = listed
+                .iter()
+                .map(|id| id.name())
+                .filter(|name| !name.ends_with("_MDM"))
+                .filter_map(|name| {
+                    let start = ArchiveFileMeta::parse_timestamp_from_name(name, &day)?;
+                    Some(ArchiveVolume {
+                        name: name.to_string(),
+                        start: start as f64,
+                    })
+                })
+                .collect();
+            day_volumes.sort_by(|a, b| a.start.total_cmp(&b.start));
+
+            let mut cache = self.cache.borrow_mut();
+            let timeline = cache.timelines.entry(site.to_string()).or_default();
+            // Only ever append, so positions (and volume numbers) stay put.
+            let known_end = timeline.volumes.last().map_or(f64::MIN, |v| v.start);
+            let before = timeline.volumes.len();
+            timeline
+                .volumes
+                .extend(day_volumes.into_iter().filter(|v| v.start > known_end));
+            timeline.listed_through = Some(day);
+            timeline.listed_at = wall_now;
+            log::debug!(
+                "replay: {} new volume(s) for {} on {}",
+                timeline.volumes.len() - before,
+                site,
+                day
+            );
+        }
+        Ok(self
+            .cache
+            .borrow()
+            .timelines
+            .get(site)
+            .map(|t| t.volumes.clone())
+            .unwrap_or_default())
+    }
+
+    /// Download and cut an archive file, once.
+    async fn cut_volume(&self, name: &str) -> Result<Rc<Vec<Vec<u8>>>> {
+        let future = {
+            let mut cache = self.cache.borrow_mut();
+            if let Some(future) = cache.volumes.get(name) {
+                future.clone()
+            } else {
+                let identifier = nexrad_data::aws::archive::Identifier::new(name.to_string());
+                let future = async move {
+                    let file = download_archive_file(identifier)
+                        .await
+                        .map_err(|e| e.to_string())?;
+                    cut_chunks(file).map(Rc::new)
+                }
+                .boxed_local()
+                .shared();
+                cache.volumes.insert(name.to_string(), future.clone());
+                cache.order.push_back(name.to_string());
+                while cache.order.len() > CACHED_VOLUMES {
+                    if let Some(oldest) = cache.order.pop_front() {
+                        cache.volumes.remove(&oldest);
+                    }
+                }
+                future
+            }
+        };
+
+        future.await.map_err(|message| {
+            // Drop the failure so the next request tries again.
+            let mut cache = self.cache.borrow_mut();
+            cache.volumes.remove(name);
+            cache.order.retain(|n| n != name);
+            log::warn!("replay: can't use {}: {}", name, message);
+            Error::AWS(AWSError::S3GetObject(Some(message)))
+        })
+    }
+}
+
+fn date_of(secs: f64) -> NaiveDate {
+    DateTime::from_timestamp(secs as i64, 0)
+        .unwrap_or_default()
+        .date_naive()
+}
+
+/// Real-time directory (1–999) of the volume at `position` in a timeline.
+fn volume_number(position: usize) -> usize {
+    position % VOLUME_COUNT + 1
+}
+
+/// Newest volume in directory `volume` that had started by `now`.
+fn latest_in_volume(volumes: &[ArchiveVolume], volume: usize, now: f64) -> Option<usize> {
+    (0..volumes.len())
+        .rev()
+        .find(|&p| volume_number(p) == volume && volumes[p].start <= now)
+}
+
+/// Seconds until the next volume starts, within bounds.
+fn volume_span(volumes: &[ArchiveVolume], position: usize) -> f64 {
+    volumes
+        .get(position + 1)
+        .map_or(DEFAULT_VOLUME_SECS, |next| {
+            next.start - volumes[position].start
+        })
+        .clamp(MIN_VOLUME_SECS, MAX_VOLUME_SECS)
+}
+
+/// Archive times `count` chunks of a volume come due, the first at `start`.
+fn chunk_times(start: f64, span: f64, count: usize) -> Vec<f64> {
+    (0..count)
+        .map(|i| start + span * i as f64 / count as f64)
+        .collect()
+}
+
+/// Cut an Archive II file into real-time chunks.
+fn cut_chunks(file: File) -> std::result::Result<Vec<Vec<u8>>, String> {
+    let file = file.decompress().map_err(|e| e.to_string())?;
+    let records = file.records().map_err(|e| e.to_string())?;
+    let Some((first, rest)) = records.split_first() else {
+        return Err("volume has no records".to_string());
+    };
+    if first.data().get(4..6) != Some(b"BZ".as_slice()) {
+        return Err("volume isn't in bzip2 LDM records, so it can't be cut into chunks".into());
+    }
+
+    let header_len = std::mem::size_of::<Header>();
+    let mut start_chunk = file.data()[..header_len].to_vec();
+    start_chunk.extend_from_slice(first.data());
+    let mut chunks = vec![start_chunk];
+    chunks.extend(rest.iter().map(|record| record.data().to_vec()));
+    Ok(chunks)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn volume(start: f64) -> ArchiveVolume {
+        ArchiveVolume {
+            name: String::new(),
+            start,
+        }
+    }
+
+    #[test]
+    fn clock_round_trips() {
+        let clock = ReplayClock::new(1_369_080_000.0, 2_000_000_000.0, 4.0);
+        assert_eq!(clock.archive_time(2_000_000_010.0), 1_369_080_040.0);
+        assert_eq!(clock.wall_time(1_369_080_040.0), 2_000_000_010.0);
+    }
+
+    #[test]
+    fn finds_volumes_and_spreads_chunks() {
+        let volumes: Vec<_> = (0..1001).map(|i| volume(i as f64 * 300.0)).collect();
+        assert_eq!(volume_number(0), 1);
+        assert_eq!(volume_number(999), 1);
+        // Directory 1 holds position 999 once it has started, position 0 before.
+        assert_eq!(latest_in_volume(&volumes, 1, 999.0 * 300.0), Some(999));
+        assert_eq!(latest_in_volume(&volumes, 1, 999.0 * 300.0 - 1.0), Some(0));
+        assert_eq!(latest_in_volume(&volumes, 3, 100.0), None);
+
+        assert_eq!(volume_span(&volumes, 0), 300.0);
+        assert_eq!(volume_span(&volumes, 1000), DEFAULT_VOLUME_SECS);
+        let gap = [volume(0.0), volume(7200.0)];
+        assert_eq!(volume_span(&gap, 0), MAX_VOLUME_SECS);
+
+        assert_eq!(chunk_times(100.0, 300.0, 3), vec![100.0, 200.0, 300.0]);
+    }
+
+    #[test]
+    fn cuts_files_into_chunks() {
+        let record = |fill: u8| {
+            let mut bytes = 6i32.to_be_bytes().to_vec();
+            bytes.extend_from_slice(b"BZh9");
+            bytes.extend_from_slice(&[fill; 2]);
+            bytes
+        };
+        let mut data = b"AR2V0006.001".to_vec();
+        data.resize(24, 0);
+        for fill in 0..3 {
+            data.extend(record(fill));
+        }
+
+        let chunks = cut_chunks(File::new(data.clone())).unwrap();
+        assert_eq!(chunks.len(), 3);
+        assert_eq!(chunks[0], [&data[..24], &record(0)[..]].concat());
+        assert_eq!(chunks[2], record(2));
+        assert!(matches!(
+            Chunk::new(chunks[0].clone()).unwrap(),
+            Chunk::Start(_)
+        ));
+        assert!(matches!(
+            Chunk::new(chunks[1].clone()).unwrap(),
+            Chunk::IntermediateOrEnd(_)
+        ));
+        // The whole file is the chunks back to back.
+        assert_eq!(chunks.concat(), data);
+    }
+}
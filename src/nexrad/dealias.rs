@@ -0,0 +1,537 @@
+//! Region-based radial velocity dealiasing.
+//!
+//! Runs during ingest on each velocity sweep and produces a second,
+//! unfolded copy stored under [`DEALIASED_VELOCITY`] next to the raw
+//! `velocity` blob. The raw blob is never modified.
+//!
+//! Algorithm:
+//! 1. Take the Nyquist velocity from the sweep's radial data block. Sources
+//!    without one (legacy Message 1 data) fall back to an estimate from the
+//!    sweep itself: folded data saturates at ±Vn, so a high percentile of
+//!    |v| approximates it — but only when the sweep actually aliases.
+//! 2. Grow regions of gates whose neighbours differ by less than half the
+//!    Nyquist interval — aliasing boundaries show up as ~2·Vn jumps and
+//!    therefore split regions.
+//! 3. Give the largest region an unfold count from the reference field
+//!    (previous sweep, or an environmental VAD wind fitted from the sweep's
+//!    own near-radar gates) and propagate unfold counts outward across
+//!    region boundaries, choosing the multiple of 2·Vn that best matches the
+//!    already-unfolded neighbours.
+//! 4. Regions not connected to anything fall back to the reference field.
+
+use crate::data::keys::{GateValues, PrecomputedSweep};
+use crate::data::moments::decode_physical;
+use std::collections::{HashMap, VecDeque};
+
+/// Product key for the dealiased velocity blob in the `sweep_data` store.
+pub(crate) const DEALIASED_VELOCITY: &str = "dealiased_velocity";
+
+/// Raw-value offset of the dealiased U16 encoding. Unfolded velocities can
+/// exceed the 8-bit range of the source moment, so the blob is re-encoded
+/// with the source scale around this midpoint.
+const DEALIASED_OFFSET: f32 = 32768.0;
+
+/// Neighbouring gates closer than this fraction of Vn join the same region.
+const REGION_CONTINUITY_FRACTION: f32 = 0.5;
+
+/// Percentile of |v| used as the Nyquist velocity estimate when the sweep
+/// doesn't carry one.
+const NYQUIST_PERCENTILE: f64 = 0.999;
+
+/// Sweeps with a smaller |v| spread carry no usable velocity signal.
+const MIN_NYQUIST_MS: f32 = 5.0;
+
+/// Near-radar ring used for the environmental VAD wind fit.
+const VAD_MIN_RANGE_KM: f64 = 5.0;
+const VAD_MAX_RANGE_KM: f64 = 30.0;
+
+/// Minimum gates in the VAD ring for the fit to be trusted.
+const VAD_MIN_GATES: usize = 200;
+
+/// Dealiased field of one sweep, kept to seed the next sweep.
+pub(crate) struct DealiasReference {
+    azimuths: Vec<f32>,
+    first_gate_km: f64,
+    gate_interval_km: f64,
+    gate_count: usize,
+    /// Physical unfolded velocity per gate, NaN where invalid.
+    values: Vec<f32>,
+}
+
+impl DealiasReference {
+    /// Reference velocity at (`azimuth_deg`, `range_km`), nearest gate.
+    fn sample(&self, azimuth_deg: f32, range_km: f64) -> Option<f32> {
+        if self.azimuths.is_empty() || self.gate_interval_km <= 0.0 {
+            return None;
+        }
+        let gate = ((range_km - self.first_gate_km) / self.gate_interval_km).floor();
+        if gate < 0.0 || gate as usize >= self.gate_count {
+            return None;
+        }
+        let idx = self.azimuths.partition_point(|&a| a < azimuth_deg);
+        let candidates = [
+            idx % self.azimuths.len(),
+            (idx + self.azimuths.len() - 1) % self.azimuths.len(),
+        ];
+        let az_idx = candidates
+            .into_iter()
+            .min_by(|&a, &b| {
+                angle_diff(self.azimuths[a], azimuth_deg)
+                    .partial_cmp(&angle_diff(self.azimuths[b], azimuth_deg))
+                    .unwrap_or(std::cmp::Ordering::Equal)
+            })
+            .expect("two candidates");
+        if angle_diff(self.azimuths[az_idx], azimuth_deg) > 2.0 {
+            return None;
+        }
+        let v = self.values[az_idx * self.gate_count + gate as usize];
+        (!v.is_nan()).then_some(v)
+    }
+}
+
+/// Environmental wind estimate: `v_r(az) = a + b·sin(az) + c·cos(az)`.
+struct VadFit {
+    a: f64,
+    b: f64,
+    c: f64,
+}
+
+impl VadFit {
+    fn expected(&self, azimuth_deg: f32) -> f32 {
+        let (s, c) = (azimuth_deg as f64).to_radians().sin_cos();
+        (self.a + self.b * s + self.c * c) as f32
+    }
+}
+
+/// Dealias one velocity sweep.
+///
+/// `reference` is the dealiased previous sweep (lower tilt of the same
+/// volume, or the last flushed sweep while streaming). Returns the unfolded
+/// sweep and the reference to pass to the next call, or `None` if the sweep
+/// carries no usable velocity data.
+pub(crate) fn dealias_sweep(
+    sweep: &PrecomputedSweep,
+    reference: Option<&DealiasReference>,
+) -> Option<(PrecomputedSweep, DealiasReference)> {
+    let az_count = sweep.azimuth_count as usize;
+    let gate_count = sweep.gate_count as usize;
+    if az_count < 2 || gate_count == 0 || sweep.scale == 0.0 {
+        return None;
+    }
+
+    let values = decode_physical(sweep);
+    if values.iter().all(|v| v.is_nan()) {
+        return None;
+    }
+    let nyquist = match sweep.nyquist_velocity {
+        Some(vn) if vn > 0.0 => vn,
+        _ => estimate_nyquist(&values)?,
+    };
+
+    let regions = label_regions(&values, az_count, gate_count, nyquist);
+    let range_km = |g: usize| sweep.first_gate_range_km + (g as f64 + 0.5) * sweep.gate_interval_km;
+
+    // Expected velocity per gate from the reference field, if any.
+    let vad = if reference.is_none() {
+        fit_vad(&values, &sweep.azimuths, gate_count, range_km)
+    } else {
+        None
+    };
+    let expected = |az_idx: usize, g: usize| -> Option<f32> {
+        match (reference, &vad) {
+            (Some(r), _) => r.sample(sweep.azimuths[az_idx], range_km(g)),
+            (None, Some(fit)) => Some(fit.expected(sweep.azimuths[az_idx])),
+            (None, None) => None,
+        }
+    };
+
+    let unfold = assign_unfold_counts(&values, &regions, az_count, gate_count, nyquist, expected);
+
+    let interval = 2.0 * nyquist;
+    let unfolded: Vec<f32> = values
+        .iter()
+        .zip(&regions.label)
+        .map(|(&v, &label)| match label {
+            Some(r) => v + unfold[r] as f32 * interval,
+            None => f32::NAN,
+        })
+        .collect();
+
+    let gate_values = GateValues::U16(
+        unfolded
+            .iter()
+            .map(|&v| {
+                if v.is_nan() {
+                    0
+                } else {
+                    (v * sweep.scale + DEALIASED_OFFSET)
+                        .round()
+                        .clamp(2.0, 65535.0) as u16
+                }
+            })
+            .collect(),
+    );
+
+    let dealiased = PrecomputedSweep {
+        azimuth_count: sweep.azimuth_count,
+        gate_count: sweep.gate_count,
+        first_gate_range_km: sweep.first_gate_range_km,
+        gate_interval_km: sweep.gate_interval_km,
+        max_range_km: sweep.max_range_km,
+        scale: sweep.scale,
+        offset: DEALIASED_OFFSET,
+        radial_count: sweep.radial_count,
+        mean_elevation: sweep.mean_elevation,
+        nyquist_velocity: sweep.nyquist_velocity,
+        sweep_start_secs: sweep.sweep_start_secs,
+        sweep_end_secs: sweep.sweep_end_secs,
+        azimuths: sweep.azimuths.clone(),
+        radial_times: sweep.radial_times.clone(),
+        gate_values,
+    };
+    let next_reference = DealiasReference {
+        azimuths: sweep.azimuths.clone(),
+        first_gate_km: sweep.first_gate_range_km,
+        gate_interval_km: sweep.gate_interval_km,
+        gate_count,
+        values: unfolded,
+    };
+    Some((dealiased, next_reference))
+}
+
+fn estimate_nyquist(values: &[f32]) -> Option<f32> {
+    let mut mags: Vec<f32> = values
+        .iter()
+        .filter(|v| !v.is_nan())
+        .map(|v| v.abs())
+        .collect();
+    if mags.is_empty() {
+        return None;
+    }
+    let k = ((mags.len() - 1) as f64 * NYQUIST_PERCENTILE) as usize;
+    let (_, nth, _) = mags.select_nth_unstable_by(k, |a, b| {
+        a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal)
+    });
+    (*nth >= MIN_NYQUIST_MS).then_some(*nth)
+}
+
+/// Connected regions of smoothly varying velocity.
+struct Regions {
+    /// Region index per gate, `None` for invalid gates.
+    label: Vec<Option<usize>>,
+    /// Gate count per region.
+    sizes: Vec<usize>,
+}
+
+/// 4-neighbour flood fill; the azimuth axis wraps.
+fn label_regions(values: &[f32], az_count: usize, gate_count: usize, nyquist: f32) -> Regions {
+    let max_step = nyquist * REGION_CONTINUITY_FRACTION;
+    let mut label: Vec<Option<usize>> = vec![None; values.len()];
+    let mut sizes = Vec::new();
+    let mut stack = Vec::new();
+
+    for start in 0..values.len() {
+        if label[start].is_some() || values[start].is_nan() {
+            continue;
+        }
+        let region = sizes.len();
+        let mut size = 0;
+        label[start] = Some(region);
+        stack.push(start);
+        while let Some(idx) = stack.pop() {
+            size += 1;
+            for nidx in neighbours(idx, az_count, gate_count) {
+                if label[nidx].is_none()
+                    && !values[nidx].is_nan()
+                    && (values[nidx] - values[idx]).abs() < max_step
+                {
+                    label[nidx] = Some(region);
+                    stack.push(nidx);
+                }
+            }
+        }
+        sizes.push(size);
+    }
+
+    Regions { label, sizes }
+}
+
+/// 4-neighbourhood of a gate index (wrapping in azimuth).
+fn neighbours(idx: usize, az_count: usize, gate_count: usize) -> impl Iterator<Item = usize> {
+    let az = idx / gate_count;
+    let g = idx % gate_count;
+    let prev_az = (az + az_count - 1) % az_count;
+    let next_az = (az + 1) % az_count;
+    [
+        Some(prev_az * gate_count + g),
+        Some(next_az * gate_count + g),
+        (g > 0).then(|| idx - 1),
+        (g + 1 < gate_count).then(|| idx + 1),
+    ]
+    .into_iter()
+    .flatten()
+}
+
+/// Least-squares VAD fit over the near-radar ring.
+fn fit_vad(
+    values: &[f32],
+    azimuths: &[f32],
+    gate_count: usize,
+    range_km: impl Fn(usize) -> f64,
+) -> Option<VadFit> {
+    // Normal equations for [1, sin, cos].
+    let mut ata = [[0.0_f64; 3]; 3];
+    let mut atb = [0.0_f64; 3];
+    let mut n = 0usize;
+    for (az_idx, &az) in azimuths.iter().enumerate() {
+        let (s, c) = (az as f64).to_radians().sin_cos();
+        let row = [1.0, s, c];
+        for g in 0..gate_count {
+            let r = range_km(g);
+            if r < VAD_MIN_RANGE_KM {
+                continue;
+            }
+            if r > VAD_MAX_RANGE_KM {
+                break;
+            }
+            let v = values[az_idx * gate_count + g];
+            if v.is_nan() {
+                continue;
+            }
+            for i in 0..3 {
+                for j in 0..3 {
+                    ata[i][j] += row[i] * row[j];
+                }
+                atb[i] += row[i] * v as f64;
+            }
+            n += 1;
+        }
+    }
+    if n < VAD_MIN_GATES {
+        return None;
+    }
+    let [a, b, c] = solve3(ata, atb)?;
+    Some(VadFit { a, b, c })
+}
+
+/// Solve a 3×3 linear system by Cramer's rule.
+fn solve3(m: [[f64; 3]; 3], rhs: [f64; 3]) -> Option<[f64; 3]> {
+    let det = |m: &[[f64; 3]; 3]| {
+        m[0][0] * (m[1][1] * m[2][2] - m[1][2] * m[2][1])
+            - m[0][1] * (m[1][0] * m[2][2] - m[1][2] * m[2][0])
+            + m[0][2] * (m[1][0] * m[2][1] - m[1][1] * m[2][0])
+    };
+    let d = det(&m);
+    if d.abs() < 1e-9 {
+        return None;
+    }
+    let mut out = [0.0; 3];
+    for (col, slot) in out.iter_mut().enumerate() {
+        let mut mc = m;
+        for row in 0..3 {
+            mc[row][col] = rhs[row];
+        }
+        *slot = det(&mc) / d;
+    }
+    Some(out)
+}
+
+/// Choose the number of Nyquist intervals to add to each region.
+fn assign_unfold_counts(
+    values: &[f32],
+    regions: &Regions,
+    az_count: usize,
+    gate_count: usize,
+    nyquist: f32,
+    expected: impl Fn(usize, usize) -> Option<f32>,
+) -> Vec<i32> {
+    let region_count = regions.sizes.len();
+    let interval = 2.0 * nyquist as f64;
+
+    // Boundary statistics between region pairs: (pair count, Σ(v_s - v_r))
+    // keyed by (r, s), plus the adjacency list for propagation.
+    let mut boundary: HashMap<(usize, usize), (u32, f64)> = HashMap::new();
+    let mut adjacent: Vec<Vec<usize>> = vec![Vec::new(); region_count];
+    // Reference statistics per region: Σ(expected - v) samples.
+    let mut ref_diffs: Vec<Vec<f32>> = vec![Vec::new(); region_count];
+
+    for idx in 0..values.len() {
+        let Some(r) = regions.label[idx] else {
+            continue;
+        };
+        if let Some(e) = expected(idx / gate_count, idx % gate_count) {
+            ref_diffs[r].push(e - values[idx]);
+        }
+        for nidx in neighbours(idx, az_count, gate_count) {
+            let Some(s) = regions.label[nidx] else {
+                continue;
+            };
+            if s == r {
+                continue;
+            }
+            let entry = boundary.entry((r, s)).or_insert_with(|| {
+                adjacent[r].push(s);
+                (0, 0.0)
+            });
+            entry.0 += 1;
+            entry.1 += (values[nidx] - values[idx]) as f64;
+        }
+    }
+
+    let reference_count = |diffs: &mut Vec<f32>| -> Option<i32> {
+        if diffs.is_empty() {
+            return None;
+        }
+        let mid = diffs.len() / 2;
+        let (_, median, _) = diffs.select_nth_unstable_by(mid, |a, b| {
+            a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal)
+        });
+        Some((*median as f64 / interval).round() as i32)
+    };
+
+    let mut order: Vec<usize> = (0..region_count).collect();
+    order.sort_by(|&a, &b| regions.sizes[b].cmp(&regions.sizes[a]));
+
+    let mut unfold: Vec<Option<i32>> = vec![None; region_count];
+    let mut queue = VecDeque::new();
+    for &seed in &order {
+        if unfold[seed].is_some() {
+            continue;
+        }
+        // Each disconnected cluster is seeded at its largest region from the
+        // reference field, then grown outward by boundary continuity.
+        unfold[seed] = Some(reference_count(&mut ref_diffs[seed]).unwrap_or(0));
+        queue.push_back(seed);
+        while let Some(r) = queue.pop_front() {
+            for &s in &adjacent[r] {
+                if unfold[s].is_some() {
+                    continue;
+                }
+                // Vote over every already-unfolded neighbour of `s`.
+                let (mut count, mut sum) = (0u32, 0.0f64);
+                for &t in &adjacent[s] {
+                    if let (Some(n_t), Some(&(c, d))) = (unfold[t], boundary.get(&(s, t))) {
+                        count += c;
+                        sum += d + n_t as f64 * interval * c as f64;
+                    }
+                }
+                if count == 0 {
+                    continue;
+                }
+                unfold[s] = Some((sum / count as f64 / interval).round() as i32);
+                queue.push_back(s);
+            }
+        }
+    }
+
+    unfold.into_iter().map(|n| n.unwrap_or(0)).collect()
+}
+
+fn angle_diff(a: f32, b: f32) -> f32 {
+    let d = (a - b).rem_euclid(360.0);
+    d.min(360.0 - d)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::SweepFixture;
+
+    const AZ_COUNT: usize = 360;
+    const GATE_COUNT: usize = 240;
+    const GATE_KM: f64 = 0.5;
+    const NYQUIST: f32 = 25.0;
+
+    fn fold(v: f32) -> f32 {
+        (v + NYQUIST).rem_euclid(2.0 * NYQUIST) - NYQUIST
+    }
+
+    /// Uniform wind from the west whose radial component grows with range,
+    /// so the far field aliases while the near field does not.
+    fn truth(az_deg: f32, range_km: f64) -> f32 {
+        let speed = 10.0 + range_km as f32 * 0.3; // 10 → 46 m/s
+        speed * az_deg.to_radians().sin()
+    }
+
+    /// Encoded like the 8-bit VEL moment (0.5 m/s resolution, offset 129).
+    fn sweep(values: impl Fn(f32, f64) -> f32) -> PrecomputedSweep {
+        SweepFixture::new(AZ_COUNT, GATE_COUNT, GATE_KM)
+            .encoding(2.0, 129.0)
+            .nyquist(NYQUIST)
+            .u8(|az, r| Some(values(az, r)))
+    }
+
+    fn max_error(out: &PrecomputedSweep, truth: impl Fn(f32, f64) -> f32) -> f32 {
+        let GateValues::U16(raw) = &out.gate_values else {
+            panic!("dealiased sweep is U16");
+        };
+        let mut worst = 0.0f32;
+        for (a, &az) in out.azimuths.iter().enumerate() {
+            for g in 0..GATE_COUNT {
+                let r = raw[a * GATE_COUNT + g];
+                if r <= 1 {
+                    continue;
+                }
+                let v = (r as f32 - out.offset) / out.scale;
+                let t = truth(az, (g as f64 + 0.5) * GATE_KM);
+                worst = worst.max((v - t).abs());
+            }
+        }
+        worst
+    }
+
+    #[test]
+    fn unfolds_with_environmental_seed() {
+        let folded = sweep(|az, r| fold(truth(az, r)));
+        let (out, _) = dealias_sweep(&folded, None).unwrap();
+        let error = max_error(&out, truth);
+        assert!(error < 1.0, "max error {}", error);
+    }
+
+    #[test]
+    fn estimates_nyquist_without_radial_data_block() {
+        let mut folded = sweep(|az, r| fold(truth(az, r)));
+        folded.nyquist_velocity = None;
+        let (out, _) = dealias_sweep(&folded, None).unwrap();
+        let error = max_error(&out, truth);
+        assert!(error < 1.0, "max error {}", error);
+    }
+
+    #[test]
+    fn unfolds_with_previous_sweep_seed() {
+        let (_, reference) = dealias_sweep(&sweep(truth), None).unwrap();
+        let folded = sweep(|az, r| fold(truth(az, r)));
+        let (out, _) = dealias_sweep(&folded, Some(&reference)).unwrap();
+        let error = max_error(&out, truth);
+        assert!(error < 1.0, "max error {}", error);
+    }
+
+    #[test]
+    fn leaves_unaliased_sweep_unchanged() {
+        let clean = sweep(|az, _| 12.0 * az.to_radians().sin());
+        let (out, _) = dealias_sweep(&clean, None).unwrap();
+        let GateValues::U16(raw) = &out.gate_values else {
+            panic!("dealiased sweep is U16");
+        };
+        let v = (raw[90 * GATE_COUNT + 50] as f32 - out.offset) / out.scale;
+        assert!((v - 12.0).abs() < 0.5);
+    }
+
+    #[test]
+    fn leaves_weak_unaliased_flow_unchanged() {
+        // A 14 m/s shear line in flow far below Vn. The jump is twice the
+        // sweep's own peak |v|, so a percentile estimate would read it as a
+        // fold; with the radial data block's Vn it is left alone.
+        let shear = |_: f32, r: f64| if r < 60.0 { 7.0 } else { -7.0 };
+        let (out, _) = dealias_sweep(&sweep(shear), None).unwrap();
+        let error = max_error(&out, shear);
+        assert!(error < 0.5, "max error {}", error);
+    }
+
+    #[test]
+    fn empty_sweep_is_skipped() {
+        let mut empty = sweep(|_, _| 0.0);
+        empty.gate_values = GateValues::U8(vec![0; AZ_COUNT * GATE_COUNT]);
+        assert!(dealias_sweep(&empty, None).is_none());
+    }
+}
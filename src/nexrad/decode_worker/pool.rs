@@ -3,17 +3,20 @@
 //! Dispatch strategy:
 //! - `ingest` (archive) — round-robin across all workers so concurrent downloads
 //!   don't serialize on a single bzip2/decode pipeline.
-//! - `render`, `render_volume` — round-robin; render requests just read from
-//!   IDB and every worker has its own connection.
+//! - `render`, `render_volume`, `render_qpe`, `render_lowest_available`,
+//!   `cross_section`, `mosaic`, `cf_radial` — round-robin; these just read
+//!   from IDB and every worker has its own connection.
+//! - `ingest_level3`, mosaic and pinned-site archive ingest — round-robin
+//!   with archive ingest.
 //! - `ingest_chunk` and `render_live` — pinned to worker 0 because the live
 //!   accumulator (`CHUNK_ACCUM`) is a per-worker thread-local.
 //!
-//! Each worker in the pool owns its own JS `Worker` handle and its own
-//! `pending_*` maps, so message correlation remains per-worker. `try_recv`
+//! Each worker in the pool owns its own Web Worker or decode thread and its
+//! own pending maps, so message correlation remains per-worker. `try_recv`
 //! drains outcomes from every worker into a single vector.
 
 use super::DecodeWorker;
-use super::WorkerOutcome;
+use super::{LowestAvailableSweep, QpeScan, WorkerOutcome};
 use eframe::egui;
 
 /// Index of the worker that exclusively handles live chunk ingest and
@@ -79,6 +82,39 @@ impl WorkerPool {
         self.workers[idx].ingest(data, site_id, timestamp_secs, file_name, fetch_latency_ms);
     }
 
+    /// Submit a file read from disk for ingest — round-robined like archives.
+    pub fn import(&mut self, data: Vec<u8>, file_name: String) {
+        let idx = self.next_ingest_index();
+        self.workers[idx].import(data, file_name);
+    }
+
+    /// Submit a neighbouring site's archive for the mosaic — round-robined
+    /// like archives.
+    pub fn ingest_mosaic(
+        &mut self,
+        data: Vec<u8>,
+        site_id: String,
+        timestamp_secs: i64,
+        file_name: String,
+        fetch_latency_ms: f64,
+    ) {
+        let idx = self.next_ingest_index();
+        self.workers[idx].ingest_mosaic(data, site_id, timestamp_secs, file_name, fetch_latency_ms);
+    }
+
+    /// Submit a pinned site's archive — round-robined like archives.
+    pub fn ingest_pinned(
+        &mut self,
+        data: Vec<u8>,
+        site_id: String,
+        timestamp_secs: i64,
+        file_name: String,
+        fetch_latency_ms: f64,
+    ) {
+        let idx = self.next_ingest_index();
+        self.workers[idx].ingest_pinned(data, site_id, timestamp_secs, file_name, fetch_latency_ms);
+    }
+
     /// Submit a per-chunk ingest — pinned to the live-worker slot so the
     /// accumulator thread-local stays consistent across chunks of the same
     /// volume.
@@ -126,6 +162,54 @@ impl WorkerPool {
         self.workers[idx].render_volume(scan_key, product, elevation_numbers);
     }
 
+    /// Submit a rainfall accumulation — round-robined across workers.
+    pub fn render_qpe(
+        &mut self,
+        product: String,
+        scans: Vec<QpeScan>,
+        settings: crate::nexrad::qpe::QpeSettings,
+    ) {
+        let idx = self.next_render_index();
+        self.workers[idx].render_qpe(product, scans, settings);
+    }
+
+    /// Submit a lowest-available composite — round-robined across workers.
+    pub fn render_lowest_available(&mut self, product: String, sweeps: Vec<LowestAvailableSweep>) {
+        let idx = self.next_render_index();
+        self.workers[idx].render_lowest_available(product, sweeps);
+    }
+
+    /// Submit a vertical cross-section — round-robined across workers.
+    pub fn cross_section(&mut self, request: crate::nexrad::render_request::CrossSectionRequest) {
+        let idx = self.next_render_index();
+        self.workers[idx].cross_section(request);
+    }
+
+    /// Submit a regional mosaic — round-robined across workers.
+    pub fn mosaic(&mut self, request: crate::nexrad::render_request::MosaicRequest) {
+        let idx = self.next_render_index();
+        self.workers[idx].mosaic(request);
+    }
+
+    /// Submit a CF-Radial export — round-robined across workers.
+    pub fn cf_radial(&mut self, request: crate::nexrad::render_request::CfRadialRequest) {
+        let idx = self.next_render_index();
+        self.workers[idx].cf_radial(request);
+    }
+
+    /// Submit a Level III product — round-robined with archive ingest.
+    pub fn ingest_level3(&mut self, data: Vec<u8>, file: crate::nexrad::level3::Level3File) {
+        let idx = self.next_ingest_index();
+        self.workers[idx].ingest_level3(data, file);
+    }
+
+    /// Enable or disable velocity dealiasing on every worker's ingest path.
+    pub fn set_dealias_velocity(&mut self, enabled: bool) {
+        for worker in &mut self.workers {
+            worker.set_dealias_velocity(enabled);
+        }
+    }
+
     /// Drain pending outcomes from every worker.
     pub fn try_recv(&mut self) -> Vec<WorkerOutcome> {
         let mut out = Vec::new();
@@ -136,15 +220,13 @@ impl WorkerPool {
     }
 }
 
-/// Determine a sensible worker pool size from `navigator.hardwareConcurrency`.
+/// Determine a sensible worker pool size from the host's logical core count.
 ///
 /// We reserve one core for the UI thread and cap the pool at 4 workers to
 /// avoid the diminishing returns of over-subscription on laptops. Fallback is
-/// 2 workers when the browser doesn't expose hardware concurrency.
+/// 2 workers when the host doesn't expose its core count.
 pub fn default_pool_size() -> usize {
-    let hw = web_sys::window()
-        .map(|w| w.navigator().hardware_concurrency() as usize)
-        .unwrap_or(0);
+    let hw = crate::platform::hardware_concurrency();
 
     if hw == 0 {
         return 2;
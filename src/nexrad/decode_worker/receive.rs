@@ -1,156 +1,72 @@
-//! Worker message reception: onmessage callback setup and result deserialization.
-#![allow(clippy::too_many_arguments)]
+//! Worker result handling shared by the browser and desktop backends.
+//!
+//! Each backend decodes a reply into the typed `worker_api` response plus its
+//! binary arrays, then hands it to [`Pending`], which pairs it with the
+//! request's context and queues a [`WorkerOutcome`] for `try_recv`.
 
 use std::cell::RefCell;
 use std::collections::HashMap;
-use std::rc::Rc;
-use wasm_bindgen::prelude::*;
-use wasm_bindgen::JsCast;
-use web_sys::MessageEvent;
 
 use super::types::*;
 use crate::data::keys::ScanKey;
-
-// ---------------------------------------------------------------------------
-// onmessage callback setup (called from DecodeWorker::new)
-// ---------------------------------------------------------------------------
-
-/// Install the `onmessage` callback on the worker.
-///
-/// This is extracted from `DecodeWorker::new` so the constructor stays concise.
-pub(super) fn setup_onmessage(
-    worker: &web_sys::Worker,
-    ctx: &eframe::egui::Context,
-    ready: &Rc<RefCell<bool>>,
-    pending_ingest: &Rc<RefCell<HashMap<RequestId, IngestContext>>>,
-    pending_chunk_ingest: &Rc<RefCell<HashMap<RequestId, ChunkIngestContext>>>,
-    pending_render: &Rc<RefCell<HashMap<RequestId, RenderContext>>>,
-    pending_render_live: &Rc<RefCell<HashMap<RequestId, RenderContext>>>,
-    pending_volume: &Rc<RefCell<HashMap<RequestId, VolumeRenderContext>>>,
-    results: &Rc<RefCell<Vec<WorkerOutcome>>>,
-) {
-    let ready_c = ready.clone();
-    let pending_ingest_c = pending_ingest.clone();
-    let pending_chunk_ingest_c = pending_chunk_ingest.clone();
-    let pending_render_c = pending_render.clone();
-    let pending_render_live_c = pending_render_live.clone();
-    let pending_volume_c = pending_volume.clone();
-    let pending_ingest_err = pending_ingest.clone();
-    let pending_chunk_ingest_err = pending_chunk_ingest.clone();
-    let pending_render_err = pending_render.clone();
-    let pending_render_live_err = pending_render_live.clone();
-    let pending_volume_err = pending_volume.clone();
-    let results_c = results.clone();
-    let ctx_c = ctx.clone();
-
-    let onmessage = Closure::<dyn Fn(MessageEvent)>::new(move |event: MessageEvent| {
-        let data = event.data();
-        let msg_type = js_sys::Reflect::get(&data, &"type".into())
-            .ok()
-            .and_then(|v| v.as_string());
-
-        match msg_type.as_deref() {
-            Some("ready") => {
-                *ready_c.borrow_mut() = true;
-                log::debug!("Decode worker ready");
-            }
-            Some("ingested") => {
-                handle_ingested_message(&data, &pending_ingest_c, &results_c);
-                ctx_c.request_repaint();
-            }
-            Some("chunk_ingested") => {
-                handle_chunk_ingested_message(&data, &pending_chunk_ingest_c, &results_c);
-                ctx_c.request_repaint();
-            }
-            Some("decoded") => {
-                handle_decoded_message(&data, &pending_render_c, &results_c);
-                ctx_c.request_repaint();
-            }
-            Some("live_decoded") => {
-                handle_live_decoded_message(&data, &pending_render_live_c, &results_c);
-                ctx_c.request_repaint();
-            }
-            Some("volume_decoded") => {
-                handle_volume_decoded_message(&data, &pending_volume_c, &results_c);
-                ctx_c.request_repaint();
-            }
-            Some("error") => {
-                handle_error_message(
-                    &data,
-                    &pending_ingest_err,
-                    &pending_chunk_ingest_err,
-                    &pending_render_err,
-                    &pending_render_live_err,
-                    &pending_volume_err,
-                    &results_c,
-                );
-                ctx_c.request_repaint();
-            }
-            other => {
-                log::warn!("Unknown worker message type: {:?}", other);
-            }
-        }
-    });
-
-    worker.set_onmessage(Some(onmessage.as_ref().unchecked_ref()));
-    onmessage.forget(); // Lives for app lifetime
+use crate::nexrad::cross_section::CrossSection;
+use crate::nexrad::level3::Level3File;
+use crate::nexrad::mosaic::MosaicGrid;
+use crate::nexrad::render_request::{CfRadialRequest, CrossSectionRequest, MosaicRequest};
+use crate::nexrad::worker_api::{
+    CfRadialResponse, ChunkIngestResponse, CrossSectionResponse, IngestResponse, Level3Response,
+    MosaicResponse, RenderResponse, VolumeRenderResponse,
+};
+
+/// Binary arrays of a sweep render reply.
+#[derive(Default)]
+pub(super) struct SweepArrays {
+    pub azimuths: Vec<f32>,
+    pub gate_values: Vec<f32>,
+    pub radial_times: Vec<f64>,
+    /// Only set by lowest-available composites.
+    pub gate_times: Vec<f64>,
 }
 
-// ---------------------------------------------------------------------------
-// Shared deserialization helpers
-// ---------------------------------------------------------------------------
+/// Contexts of in-flight requests, keyed by request id, and the outcomes
+/// completed since the last `try_recv`.
+#[derive(Default)]
+pub(super) struct Pending {
+    pub ingest: RefCell<HashMap<RequestId, IngestContext>>,
+    pub chunk_ingest: RefCell<HashMap<RequestId, ChunkIngestContext>>,
+    pub render: RefCell<HashMap<RequestId, RenderContext>>,
+    pub render_live: RefCell<HashMap<RequestId, RenderContext>>,
+    pub volume: RefCell<HashMap<RequestId, VolumeRenderContext>>,
+    pub qpe: RefCell<HashMap<RequestId, RenderContext>>,
+    pub lowest_available: RefCell<HashMap<RequestId, RenderContext>>,
+    pub cross_section: RefCell<HashMap<RequestId, CrossSectionRequest>>,
+    pub mosaic: RefCell<HashMap<RequestId, MosaicRequest>>,
+    pub cf_radial: RefCell<HashMap<RequestId, CfRadialRequest>>,
+    pub level3: RefCell<HashMap<RequestId, Level3File>>,
+    pub results: RefCell<Vec<WorkerOutcome>>,
+}
 
-fn extract_pending_context<C>(
-    data: &JsValue,
+fn take_context<C>(
+    pending: &RefCell<HashMap<RequestId, C>>,
+    id: RequestId,
     msg_type: &str,
-    pending: &Rc<RefCell<HashMap<RequestId, C>>>,
 ) -> Option<C> {
-    let envelope: MessageEnvelope = match serde_wasm_bindgen::from_value(data.clone()) {
-        Ok(e) => e,
-        Err(e) => {
-            log::warn!("Failed to parse {} envelope: {}", msg_type, e);
-            return None;
-        }
-    };
-    let id = envelope.id;
-
-    match pending.borrow_mut().remove(&id) {
-        Some(ctx) => Some(ctx),
-        None => {
-            log::warn!("Received {} message for unknown request {}", msg_type, id);
-            None
-        }
+    let context = pending.borrow_mut().remove(&id);
+    if context.is_none() {
+        log::warn!("Received {} message for unknown request {}", msg_type, id);
     }
-}
-
-fn extract_decode_arrays(data: &JsValue) -> (Vec<f32>, Vec<f32>, Vec<f64>) {
-    let az_buffer = js_sys::Reflect::get(data, &"azimuths".into()).unwrap_or(JsValue::NULL);
-    let azimuths = js_sys::Float32Array::new(&az_buffer).to_vec();
-
-    let val_buffer = js_sys::Reflect::get(data, &"gateValues".into()).unwrap_or(JsValue::NULL);
-    let gate_values = js_sys::Float32Array::new(&val_buffer).to_vec();
-
-    let rt_js = js_sys::Reflect::get(data, &"radialTimes".into()).unwrap_or(JsValue::NULL);
-    let radial_times = if rt_js.is_object() && !rt_js.is_null() {
-        js_sys::Float64Array::new(&rt_js).to_vec()
-    } else {
-        Vec::new()
-    };
-
-    (azimuths, gate_values, radial_times)
+    context
 }
 
 fn build_decode_result(
     context: RenderContext,
-    r: DecodedResultMsg,
-    azimuths: Vec<f32>,
-    gate_values: Vec<f32>,
-    radial_times: Vec<f64>,
+    r: RenderResponse,
+    arrays: SweepArrays,
 ) -> DecodeResult {
     DecodeResult {
         context,
-        azimuths,
-        gate_values,
+        azimuths: arrays.azimuths,
+        gate_values: arrays.gate_values,
         azimuth_count: r.azimuth_count,
         gate_count: r.gate_count,
         first_gate_range_km: r.first_gate_range_km,
@@ -162,55 +78,42 @@ fn build_decode_result(
         deser_ms: r.deser_ms,
         marshal_ms: r.marshal_ms,
         total_ms: r.total_ms,
-        scale: r.scale,
-        offset: r.offset,
-        mean_elevation: r.mean_elevation,
+        scale: r.scale as f32,
+        offset: r.offset as f32,
+        mean_elevation: r.mean_elevation as f32,
         sweep_start_secs: r.sweep_start_secs,
         sweep_end_secs: r.sweep_end_secs,
-        radial_times,
+        radial_times: arrays.radial_times,
         azimuth_spacing_deg: r.azimuth_spacing_deg,
+        gate_times: arrays.gate_times,
+        nyquist_velocity: r.nyquist_velocity,
     }
 }
 
-// ---------------------------------------------------------------------------
-// Per-message-type handlers
-// ---------------------------------------------------------------------------
-
-fn handle_ingested_message(
-    data: &JsValue,
-    pending: &Rc<RefCell<HashMap<RequestId, IngestContext>>>,
-    results: &Rc<RefCell<Vec<WorkerOutcome>>>,
-) {
-    let context = match extract_pending_context(data, "ingested", pending) {
-        Some(ctx) => ctx,
-        None => return,
-    };
-
-    let result_obj = js_sys::Reflect::get(data, &"result".into()).unwrap_or(JsValue::NULL);
-    let r: IngestResultMsg = match serde_wasm_bindgen::from_value(result_obj) {
-        Ok(r) => r,
-        Err(e) => {
-            log::error!("Failed to parse ingest result: {}", e);
+impl Pending {
+    fn push(&self, outcome: WorkerOutcome) {
+        self.results.borrow_mut().push(outcome);
+    }
+
+    pub fn ingested(&self, id: RequestId, r: IngestResponse) {
+        let Some(context) = take_context(&self.ingest, id, "ingested") else {
             return;
-        }
-    };
-
-    log::debug!(
-        "Worker ingest complete: {} ({} records, {} elevations, {} sweeps, vcp={}, {:.0}ms)",
-        r.scan_key,
-        r.records_stored,
-        r.elevation_numbers.len(),
-        r.sweeps.len(),
-        r.vcp
-            .as_ref()
-            .map(|v| v.number.to_string())
-            .unwrap_or_else(|| "none".to_string()),
-        r.total_ms,
-    );
-
-    results
-        .borrow_mut()
-        .push(WorkerOutcome::Ingested(IngestResult {
+        };
+
+        log::debug!(
+            "Worker ingest complete: {} ({} records, {} elevations, {} sweeps, vcp={}, {:.0}ms)",
+            r.scan_key,
+            r.records_stored,
+            r.elevation_numbers.len(),
+            r.sweeps.len(),
+            r.vcp
+                .as_ref()
+                .map(|v| v.number.to_string())
+                .unwrap_or_else(|| "none".to_string()),
+            r.total_ms,
+        );
+
+        self.push(WorkerOutcome::Ingested(IngestResult {
             context,
             scan_key: r.scan_key,
             records_stored: r.records_stored,
@@ -225,51 +128,37 @@ fn handle_ingested_message(
             store_ms: r.store_ms,
             index_ms: r.index_ms,
         }));
-}
+    }
 
-fn handle_chunk_ingested_message(
-    data: &JsValue,
-    pending: &Rc<RefCell<HashMap<RequestId, ChunkIngestContext>>>,
-    results: &Rc<RefCell<Vec<WorkerOutcome>>>,
-) {
-    let context = match extract_pending_context(data, "chunk_ingested", pending) {
-        Some(ctx) => ctx,
-        None => return,
-    };
-
-    let result_obj = js_sys::Reflect::get(data, &"result".into()).unwrap_or(JsValue::NULL);
-    let r: ChunkIngestResultMsg = match serde_wasm_bindgen::from_value(result_obj) {
-        Ok(r) => r,
-        Err(e) => {
-            log::error!("Failed to parse chunk ingest result: {}", e);
+    pub fn chunk_ingested(&self, id: RequestId, r: ChunkIngestResponse) {
+        let Some(context) = take_context(&self.chunk_ingest, id, "chunk_ingested") else {
             return;
-        }
-    };
-
-    // Log chunk receipt with per-elevation azimuth ranges
-    {
-        let elev_summary: Vec<String> = r
-            .chunk_elev_az_ranges
-            .iter()
-            .map(|(elev, az_start, az_end)| format!("e{}:[{:.1}..{:.1}]", elev, az_start, az_end))
-            .collect();
-        let completed = if r.elevations_completed.is_empty() {
-            String::new()
-        } else {
-            format!(" completed={:?}", r.elevations_completed)
         };
-        log::debug!(
-            "Chunk received #{}: elevations=[{}]{} {:.0}ms",
-            context.chunk_index,
-            elev_summary.join(", "),
-            completed,
-            r.total_ms,
-        );
-    }
 
-    results
-        .borrow_mut()
-        .push(WorkerOutcome::ChunkIngested(ChunkIngestResult {
+        // Log chunk receipt with per-elevation azimuth ranges
+        {
+            let elev_summary: Vec<String> = r
+                .chunk_elev_az_ranges
+                .iter()
+                .map(|(elev, az_start, az_end)| {
+                    format!("e{}:[{:.1}..{:.1}]", elev, az_start, az_end)
+                })
+                .collect();
+            let completed = if r.elevations_completed.is_empty() {
+                String::new()
+            } else {
+                format!(" completed={:?}", r.elevations_completed)
+            };
+            log::debug!(
+                "Chunk received #{}: elevations=[{}]{} {:.0}ms",
+                context.chunk_index,
+                elev_summary.join(", "),
+                completed,
+                r.total_ms,
+            );
+        }
+
+        self.push(WorkerOutcome::ChunkIngested(ChunkIngestResult {
             context,
             scan_key: r.scan_key,
             elevations_completed: r.elevations_completed,
@@ -286,197 +175,301 @@ fn handle_chunk_ingested_message(
             chunk_elev_spans: r.chunk_elev_spans,
             chunk_elev_az_ranges: r.chunk_elev_az_ranges,
         }));
-}
+    }
 
-fn handle_decoded_message(
-    data: &JsValue,
-    pending: &Rc<RefCell<HashMap<RequestId, RenderContext>>>,
-    results: &Rc<RefCell<Vec<WorkerOutcome>>>,
-) {
-    let context = match extract_pending_context(data, "decoded", pending) {
-        Some(ctx) => ctx,
-        None => return,
-    };
-
-    let (azimuths, gate_values, radial_times) = extract_decode_arrays(data);
-
-    let r: DecodedResultMsg = match serde_wasm_bindgen::from_value(data.clone()) {
-        Ok(r) => r,
-        Err(e) => {
-            log::error!("Failed to parse decoded result: {}", e);
+    pub fn decoded(&self, id: RequestId, r: RenderResponse, arrays: SweepArrays) {
+        let Some(context) = take_context(&self.render, id, "decoded") else {
             return;
-        }
-    };
-
-    log::debug!(
-        "Worker decode: {}x{}, {} radials, {}, {:.0}ms (fetch: {:.1}, marshal: {:.1})",
-        r.azimuth_count,
-        r.gate_count,
-        r.radial_count,
-        r.product,
-        r.total_ms,
-        r.fetch_ms,
-        r.marshal_ms,
-    );
-
-    results
-        .borrow_mut()
-        .push(WorkerOutcome::Decoded(build_decode_result(
-            context,
-            r,
-            azimuths,
-            gate_values,
-            radial_times,
+        };
+
+        log::debug!(
+            "Worker decode: {}x{}, {} radials, {}, {:.0}ms (fetch: {:.1}, marshal: {:.1})",
+            r.azimuth_count,
+            r.gate_count,
+            r.radial_count,
+            r.product,
+            r.total_ms,
+            r.fetch_ms,
+            r.marshal_ms,
+        );
+
+        self.push(WorkerOutcome::Decoded(build_decode_result(
+            context, r, arrays,
         )));
-}
+    }
 
-fn handle_live_decoded_message(
-    data: &JsValue,
-    pending: &Rc<RefCell<HashMap<RequestId, RenderContext>>>,
-    results: &Rc<RefCell<Vec<WorkerOutcome>>>,
-) {
-    let context = match extract_pending_context(data, "live_decoded", pending) {
-        Some(ctx) => ctx,
-        None => return,
-    };
-
-    let (azimuths, gate_values, radial_times) = extract_decode_arrays(data);
-
-    let r: DecodedResultMsg = match serde_wasm_bindgen::from_value(data.clone()) {
-        Ok(r) => r,
-        Err(e) => {
-            log::error!("Failed to parse live_decoded result: {}", e);
+    pub fn live_decoded(&self, id: RequestId, r: RenderResponse, arrays: SweepArrays) {
+        let Some(context) = take_context(&self.render_live, id, "live_decoded") else {
             return;
-        }
-    };
-
-    log::debug!(
-        "Worker live_decoded: {}x{}, {} radials, {}, {:.0}ms",
-        r.azimuth_count,
-        r.gate_count,
-        r.radial_count,
-        r.product,
-        r.total_ms,
-    );
-
-    results
-        .borrow_mut()
-        .push(WorkerOutcome::LiveDecoded(build_decode_result(
-            context,
-            r,
-            azimuths,
-            gate_values,
-            radial_times,
+        };
+
+        log::debug!(
+            "Worker live_decoded: {}x{}, {} radials, {}, {:.0}ms",
+            r.azimuth_count,
+            r.gate_count,
+            r.radial_count,
+            r.product,
+            r.total_ms,
+        );
+
+        self.push(WorkerOutcome::LiveDecoded(build_decode_result(
+            context, r, arrays,
         )));
-}
+    }
+
+    pub fn qpe_decoded(&self, id: RequestId, r: RenderResponse, arrays: SweepArrays) {
+        let Some(context) = take_context(&self.qpe, id, "qpe_decoded") else {
+            return;
+        };
+
+        log::debug!(
+            "Worker qpe_decoded: {}x{}, {}, {:.0}ms",
+            r.azimuth_count,
+            r.gate_count,
+            r.product,
+            r.total_ms,
+        );
 
-fn handle_volume_decoded_message(
-    data: &JsValue,
-    pending: &Rc<RefCell<HashMap<RequestId, VolumeRenderContext>>>,
-    results: &Rc<RefCell<Vec<WorkerOutcome>>>,
-) {
-    let _volume_ctx = match extract_pending_context(data, "volume_decoded", pending) {
-        Some(ctx) => ctx,
-        None => return,
-    };
-
-    let r: VolumeDecodedResultMsg = match serde_wasm_bindgen::from_value(data.clone()) {
-        Ok(r) => r,
-        Err(e) => {
-            log::error!("Failed to parse volume decoded result: {}", e);
+        self.push(WorkerOutcome::QpeDecoded(build_decode_result(
+            context, r, arrays,
+        )));
+    }
+
+    pub fn lowest_available_decoded(&self, id: RequestId, r: RenderResponse, arrays: SweepArrays) {
+        let Some(context) = take_context(&self.lowest_available, id, "lowest_available_decoded")
+        else {
+            return;
+        };
+
+        log::debug!(
+            "Worker lowest_available_decoded: {}x{}, {}, {:.0}ms",
+            r.azimuth_count,
+            r.gate_count,
+            r.product,
+            r.total_ms,
+        );
+
+        self.push(WorkerOutcome::LowestAvailableDecoded(build_decode_result(
+            context, r, arrays,
+        )));
+    }
+
+    pub fn volume_decoded(&self, id: RequestId, r: VolumeRenderResponse, buffer: Vec<u8>) {
+        if take_context(&self.volume, id, "volume_decoded").is_none() {
             return;
         }
-    };
-    let word_size = r.word_size;
-
-    let buf_js = js_sys::Reflect::get(data, &"buffer".into()).unwrap_or(JsValue::NULL);
-    let buffer = if !buf_js.is_null() && !buf_js.is_undefined() {
-        let u8_view = js_sys::Uint8Array::new(&buf_js);
-        u8_view.to_vec()
-    } else {
-        Vec::new()
-    };
-
-    let sweeps: Vec<VolumeSweepMeta> = r
-        .sweep_meta
-        .into_iter()
-        .map(|s| VolumeSweepMeta {
-            elevation_deg: s.elevation_deg,
-            azimuth_count: s.azimuth_count,
-            gate_count: s.gate_count,
-            first_gate_km: s.first_gate_km,
-            gate_interval_km: s.gate_interval_km,
-            max_range_km: s.max_range_km,
-            data_offset: s.data_offset,
-            scale: s.scale,
-            offset: s.offset,
-        })
-        .collect();
-
-    log::debug!(
-        "Worker volume decode: {} sweeps, {:.1}KB buffer, product={}, {:.0}ms",
-        sweeps.len(),
-        buffer.len() as f64 / 1024.0,
-        r.product,
-        r.total_ms,
-    );
-
-    results
-        .borrow_mut()
-        .push(WorkerOutcome::VolumeDecoded(VolumeData {
+
+        let sweeps: Vec<VolumeSweepMeta> = r
+            .sweep_meta
+            .into_iter()
+            .map(|s| VolumeSweepMeta {
+                elevation_deg: s.elevation_deg as f32,
+                azimuth_count: s.azimuth_count,
+                gate_count: s.gate_count,
+                first_gate_km: s.first_gate_km as f32,
+                gate_interval_km: s.gate_interval_km as f32,
+                data_offset: s.data_offset,
+                scale: s.scale as f32,
+                offset: s.offset as f32,
+            })
+            .collect();
+
+        log::debug!(
+            "Worker volume decode: {} sweeps, {:.1}KB buffer, product={}, {:.0}ms",
+            sweeps.len(),
+            buffer.len() as f64 / 1024.0,
+            r.product,
+            r.total_ms,
+        );
+
+        self.push(WorkerOutcome::VolumeDecoded(VolumeData {
             buffer,
-            word_size,
+            word_size: r.word_size,
             sweeps,
             product: r.product,
             total_ms: r.total_ms,
         }));
-}
+    }
+
+    pub fn cross_section_decoded(&self, id: RequestId, r: CrossSectionResponse, values: Vec<f32>) {
+        let Some(request) = take_context(&self.cross_section, id, "cross_section_decoded") else {
+            return;
+        };
+
+        log::debug!(
+            "Worker cross_section_decoded: {}x{}, {} tilts, {:.0}ms",
+            r.columns,
+            r.rows,
+            r.tilts_deg.len(),
+            r.total_ms,
+        );
+
+        self.push(WorkerOutcome::CrossSectionDecoded(CrossSectionData {
+            request,
+            section: CrossSection {
+                columns: r.columns as usize,
+                rows: r.rows as usize,
+                length_km: r.length_km,
+                top_km: r.top_km,
+                values,
+                tilts_deg: r.tilts_deg,
+            },
+        }));
+    }
+
+    pub fn mosaic_decoded(&self, id: RequestId, r: MosaicResponse, values: Vec<f32>) {
+        let Some(request) = take_context(&self.mosaic, id, "mosaic_decoded") else {
+            return;
+        };
+
+        log::debug!(
+            "Worker mosaic_decoded: {}x{} from {} scans, {:.0}ms",
+            r.columns,
+            r.rows,
+            r.scan_keys.len(),
+            r.total_ms,
+        );
+
+        self.push(WorkerOutcome::MosaicDecoded(MosaicData {
+            request,
+            grid: MosaicGrid {
+                west: r.west,
+                north: r.north,
+                cell_deg: r.cell_deg,
+                columns: r.columns as usize,
+                rows: r.rows as usize,
+                values,
+            },
+            scan_keys: r.scan_keys,
+        }));
+    }
+
+    pub fn cf_radial_encoded(&self, id: RequestId, r: CfRadialResponse, bytes: CfRadialBytes) {
+        let Some(request) = take_context(&self.cf_radial, id, "cf_radial_encoded") else {
+            return;
+        };
+
+        let data = CfRadialData {
+            request,
+            bytes,
+            sweep_count: r.sweep_count,
+        };
+        log::debug!(
+            "Worker cf_radial_encoded: {} sweeps, {:.1}KB, {:.0}ms",
+            data.sweep_count,
+            data.byte_len() as f64 / 1024.0,
+            r.total_ms,
+        );
+
+        self.push(WorkerOutcome::CfRadialEncoded(data));
+    }
 
-/// Handle an "error" message from the worker.
-///
-/// Looks up the failing request id across all pending maps and removes it —
-/// otherwise a failed ingest would leak its context indefinitely. When the
-/// request can be correlated, the associated scan's start timestamp is
-/// surfaced on the outcome so the main loop can reset per-scan UI state
-/// (e.g. the timeline "processing" ghost) for the correct scan rather than
-/// guessing from `displayed_scan_timestamp`.
-#[allow(clippy::too_many_arguments)]
-fn handle_error_message(
-    data: &JsValue,
-    pending_ingest: &Rc<RefCell<HashMap<RequestId, IngestContext>>>,
-    pending_chunk_ingest: &Rc<RefCell<HashMap<RequestId, ChunkIngestContext>>>,
-    pending_render: &Rc<RefCell<HashMap<RequestId, RenderContext>>>,
-    pending_render_live: &Rc<RefCell<HashMap<RequestId, RenderContext>>>,
-    pending_volume: &Rc<RefCell<HashMap<RequestId, VolumeRenderContext>>>,
-    results: &Rc<RefCell<Vec<WorkerOutcome>>>,
-) {
-    let e: ErrorMsg = match serde_wasm_bindgen::from_value(data.clone()) {
-        Ok(e) => e,
-        Err(err) => {
-            log::error!("Failed to parse error message: {}", err);
+    pub fn level3_ingested(&self, id: RequestId, r: Level3Response) {
+        let Some(file) = take_context(&self.level3, id, "level3_ingested") else {
+            return;
+        };
+
+        log::debug!(
+            "Worker level3_ingested: {} -> {} ({}), {:.0}ms",
+            file.key,
+            r.scan_key,
+            r.product,
+            r.total_ms,
+        );
+
+        self.push(WorkerOutcome::Level3Ingested(Level3IngestResult {
+            file,
+            scan_key: r.scan_key,
+            product: r.product,
+            total_ms: r.total_ms,
+        }));
+    }
+
+    /// Handle a failed request.
+    ///
+    /// Looks up the failing request id across all pending maps and removes it —
+    /// otherwise a failed ingest would leak its context indefinitely. When the
+    /// request can be correlated, the associated scan's start timestamp is
+    /// surfaced on the outcome so the main loop can reset per-scan UI state
+    /// (e.g. the timeline "processing" ghost) for the correct scan rather than
+    /// guessing from `displayed_scan_timestamp`.
+    pub fn failed(&self, id: RequestId, message: String) {
+        log::warn!("Worker error (request {}): {}", id, message);
+
+        if let Some(request) = self.cross_section.borrow_mut().remove(&id) {
+            self.push(WorkerOutcome::CrossSectionFailed { request, message });
+            return;
+        }
+
+        if let Some(request) = self.mosaic.borrow_mut().remove(&id) {
+            self.push(WorkerOutcome::MosaicFailed { request, message });
+            return;
+        }
+
+        if let Some(request) = self.cf_radial.borrow_mut().remove(&id) {
+            self.push(WorkerOutcome::CfRadialFailed { request, message });
             return;
         }
-    };
-
-    log::warn!("Worker error (request {}): {}", e.id, e.message);
-
-    let failed_scan_timestamp_secs = if let Some(ctx) = pending_ingest.borrow_mut().remove(&e.id) {
-        Some(ctx.timestamp_secs)
-    } else if let Some(ctx) = pending_chunk_ingest.borrow_mut().remove(&e.id) {
-        Some(ctx.timestamp_secs)
-    } else if let Some(ctx) = pending_render.borrow_mut().remove(&e.id) {
-        ScanKey::from_storage_key(&ctx.scan_key).map(|k| k.scan_start.as_secs())
-    } else if let Some(ctx) = pending_render_live.borrow_mut().remove(&e.id) {
-        ScanKey::from_storage_key(&ctx.scan_key).map(|k| k.scan_start.as_secs())
-    } else if let Some(ctx) = pending_volume.borrow_mut().remove(&e.id) {
-        ScanKey::from_storage_key(&ctx.scan_key).map(|k| k.scan_start.as_secs())
-    } else {
-        None
-    };
-
-    results.borrow_mut().push(WorkerOutcome::WorkerError {
-        id: e.id,
-        message: e.message,
-        failed_scan_timestamp_secs,
-    });
+
+        if let Some(file) = self.level3.borrow_mut().remove(&id) {
+            self.push(WorkerOutcome::Level3Failed { file, message });
+            return;
+        }
+
+        let ingest = self.ingest.borrow_mut().remove(&id);
+        if let Some(ctx) = ingest.as_ref().filter(|ctx| ctx.imported) {
+            self.push(WorkerOutcome::ImportFailed {
+                file_name: ctx.file_name.clone(),
+                message,
+            });
+            return;
+        }
+
+        if let Some(ctx) = ingest.as_ref().filter(|ctx| ctx.mosaic) {
+            self.push(WorkerOutcome::MosaicIngestFailed {
+                file_name: ctx.file_name.clone(),
+                message,
+            });
+            return;
+        }
+
+        if let Some(ctx) = ingest.as_ref().filter(|ctx| ctx.pinned) {
+            self.push(WorkerOutcome::PinnedIngestFailed {
+                file_name: ctx.file_name.clone(),
+                message,
+            });
+            return;
+        }
+
+        let scan_secs = |ctx: &RenderContext| {
+            ScanKey::from_storage_key(&ctx.scan_key).map(|k| k.scan_start.as_secs())
+        };
+        let mut failed_render = None;
+        let failed_scan_timestamp_secs = if let Some(ctx) = ingest {
+            Some(ctx.timestamp_secs)
+        } else if let Some(ctx) = self.chunk_ingest.borrow_mut().remove(&id) {
+            Some(ctx.timestamp_secs)
+        } else if let Some(ctx) = self.render.borrow_mut().remove(&id) {
+            let secs = scan_secs(&ctx);
+            failed_render = Some(ctx);
+            secs
+        } else if let Some(ctx) = self.render_live.borrow_mut().remove(&id) {
+            scan_secs(&ctx)
+        } else if let Some(ctx) = self.volume.borrow_mut().remove(&id) {
+            ScanKey::from_storage_key(&ctx.scan_key).map(|k| k.scan_start.as_secs())
+        } else if let Some(ctx) = self.qpe.borrow_mut().remove(&id) {
+            scan_secs(&ctx)
+        } else if let Some(ctx) = self.lowest_available.borrow_mut().remove(&id) {
+            scan_secs(&ctx)
+        } else {
+            None
+        };
+
+        self.push(WorkerOutcome::WorkerError {
+            id,
+            message,
+            failed_scan_timestamp_secs,
+            failed_render,
+        });
+    }
 }
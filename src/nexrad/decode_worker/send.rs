@@ -1,8 +1,9 @@
-//! Methods and helpers for sending messages to the Web Worker.
+//! Request methods on the decode worker.
 
 use super::types::*;
-use super::DecodeWorker;
-use web_sys::Worker;
+use super::{DecodeWorker, Request};
+use crate::nexrad::level3::Level3File;
+use crate::nexrad::render_request::{CfRadialRequest, CrossSectionRequest, MosaicRequest};
 
 // ---------------------------------------------------------------------------
 // DecodeWorker send methods
@@ -18,101 +19,256 @@ impl DecodeWorker {
         file_name: String,
         fetch_latency_ms: f64,
     ) {
-        let id = self.next_request_id();
-        self.pending_ingest.borrow_mut().insert(
-            id,
-            IngestContext {
-                timestamp_secs,
-                file_name: file_name.clone(),
-                fetch_latency_ms,
-            },
+        let context = IngestContext {
+            timestamp_secs,
+            file_name: file_name.clone(),
+            fetch_latency_ms,
+            imported: false,
+            mosaic: false,
+            pinned: false,
+        };
+        self.submit_ingest(
+            data,
+            Some(site_id),
+            Some(timestamp_secs),
+            file_name,
+            context,
         );
+    }
 
-        if *self.ready.borrow() {
-            send_ingest_request(
-                &self.worker,
-                id,
-                &data,
-                &site_id,
-                timestamp_secs,
-                &file_name,
-            );
-        } else {
-            self.queue.push(super::QueuedRequest::Ingest(
-                id,
+    /// Submit a file read from disk for ingestion. The worker takes the site
+    /// and scan time from its volume header.
+    pub fn import(&mut self, data: Vec<u8>, file_name: String) {
+        let context = IngestContext {
+            timestamp_secs: 0,
+            file_name: file_name.clone(),
+            fetch_latency_ms: 0.0,
+            imported: true,
+            mosaic: false,
+            pinned: false,
+        };
+        self.submit_ingest(data, None, None, file_name, context);
+    }
+
+    /// Submit a neighbouring site's archive fetched for the regional mosaic.
+    pub fn ingest_mosaic(
+        &mut self,
+        data: Vec<u8>,
+        site_id: String,
+        timestamp_secs: i64,
+        file_name: String,
+        fetch_latency_ms: f64,
+    ) {
+        let context = IngestContext {
+            timestamp_secs,
+            file_name: file_name.clone(),
+            fetch_latency_ms,
+            imported: false,
+            mosaic: true,
+            pinned: false,
+        };
+        self.submit_ingest(
+            data,
+            Some(site_id),
+            Some(timestamp_secs),
+            file_name,
+            context,
+        );
+    }
+
+    /// Submit a pinned secondary site's archive.
+    pub fn ingest_pinned(
+        &mut self,
+        data: Vec<u8>,
+        site_id: String,
+        timestamp_secs: i64,
+        file_name: String,
+        fetch_latency_ms: f64,
+    ) {
+        let context = IngestContext {
+            timestamp_secs,
+            file_name: file_name.clone(),
+            fetch_latency_ms,
+            imported: false,
+            mosaic: false,
+            pinned: true,
+        };
+        self.submit_ingest(
+            data,
+            Some(site_id),
+            Some(timestamp_secs),
+            file_name,
+            context,
+        );
+    }
+
+    fn submit_ingest(
+        &mut self,
+        data: Vec<u8>,
+        site_id: Option<String>,
+        timestamp_secs: Option<i64>,
+        file_name: String,
+        context: IngestContext,
+    ) {
+        let id = self.next_request_id();
+        self.pending.ingest.borrow_mut().insert(id, context);
+
+        self.submit(
+            id,
+            Request::Ingest {
                 data,
                 site_id,
                 timestamp_secs,
                 file_name,
-            ));
-        }
+            },
+        );
     }
 
     /// Submit a decode request: fetch records from IDB, decode target elevation, return raw data.
     pub fn render(&mut self, scan_key: String, elevation_number: u8, product: String) {
         let id = self.next_request_id();
-        self.pending_render.borrow_mut().insert(
+        self.pending.render.borrow_mut().insert(
             id,
             RenderContext {
                 scan_key: scan_key.clone(),
                 elevation_number,
+                product: product.clone(),
             },
         );
 
-        if *self.ready.borrow() {
-            send_render_request(&self.worker, id, &scan_key, elevation_number, &product);
-        } else {
-            self.queue.push(super::QueuedRequest::Render(
-                id,
+        self.submit(
+            id,
+            Request::Render {
                 scan_key,
                 elevation_number,
                 product,
-            ));
-        }
+            },
+        );
     }
 
     /// Submit a live (partial sweep) render request: reads from in-memory accumulator.
     pub fn render_live(&mut self, elevation_number: u8, product: String) {
         let id = self.next_request_id();
-        self.pending_render_live.borrow_mut().insert(
+        self.pending.render_live.borrow_mut().insert(
             id,
             RenderContext {
                 scan_key: String::new(), // Not used for live renders
                 elevation_number,
+                product: product.clone(),
             },
         );
 
-        if *self.ready.borrow() {
-            send_render_live_request(&self.worker, id, elevation_number, &product);
-        } else {
-            self.queue.push(super::QueuedRequest::RenderLive(
-                id,
+        self.submit(
+            id,
+            Request::RenderLive {
                 elevation_number,
                 product,
-            ));
-        }
+            },
+        );
     }
 
     /// Submit a volume render request: fetch all elevations, pack for ray marching.
     pub fn render_volume(&mut self, scan_key: String, product: String, elevation_numbers: Vec<u8>) {
         let id = self.next_request_id();
-        self.pending_volume.borrow_mut().insert(
+        self.pending.volume.borrow_mut().insert(
             id,
             VolumeRenderContext {
                 scan_key: scan_key.clone(),
             },
         );
 
-        if *self.ready.borrow() {
-            send_render_volume_request(&self.worker, id, &scan_key, &product, &elevation_numbers);
-        } else {
-            self.queue.push(super::QueuedRequest::RenderVolume(
-                id,
+        self.submit(
+            id,
+            Request::RenderVolume {
                 scan_key,
                 product,
                 elevation_numbers,
-            ));
-        }
+            },
+        );
+    }
+
+    /// Submit a rainfall accumulation over cached scans. The result is
+    /// attributed to the last (most recent) scan in `scans`.
+    pub fn render_qpe(
+        &mut self,
+        product: String,
+        scans: Vec<QpeScan>,
+        settings: crate::nexrad::qpe::QpeSettings,
+    ) {
+        let id = self.next_request_id();
+        let latest = scans.last();
+        self.pending.qpe.borrow_mut().insert(
+            id,
+            RenderContext {
+                scan_key: latest.map(|s| s.scan_key.clone()).unwrap_or_default(),
+                elevation_number: latest.map(|s| s.elevation_number).unwrap_or(1),
+                product: product.clone(),
+            },
+        );
+
+        self.submit(
+            id,
+            Request::RenderQpe {
+                product,
+                scans,
+                settings,
+            },
+        );
+    }
+
+    /// Submit a lowest-available composite over cached sweeps. The result
+    /// is attributed to the first sweep, the newest of the lowest tilt.
+    pub fn render_lowest_available(&mut self, product: String, sweeps: Vec<LowestAvailableSweep>) {
+        let id = self.next_request_id();
+        let base = sweeps.first();
+        self.pending.lowest_available.borrow_mut().insert(
+            id,
+            RenderContext {
+                scan_key: base.map(|s| s.scan_key.clone()).unwrap_or_default(),
+                elevation_number: base.map(|s| s.elevation_number).unwrap_or(1),
+                product: product.clone(),
+            },
+        );
+
+        self.submit(id, Request::RenderLowestAvailable { product, sweeps });
+    }
+
+    /// Submit a vertical cross-section through a cached volume.
+    pub fn cross_section(&mut self, request: CrossSectionRequest) {
+        let id = self.next_request_id();
+        self.pending
+            .cross_section
+            .borrow_mut()
+            .insert(id, request.clone());
+
+        self.submit(id, Request::CrossSection(request));
+    }
+
+    /// Submit a regional mosaic of several sites' cached scans.
+    pub fn mosaic(&mut self, request: MosaicRequest) {
+        let id = self.next_request_id();
+        self.pending.mosaic.borrow_mut().insert(id, request.clone());
+
+        self.submit(id, Request::Mosaic(request));
+    }
+
+    /// Submit a CF-Radial export of a cached volume.
+    pub fn cf_radial(&mut self, request: CfRadialRequest) {
+        let id = self.next_request_id();
+        self.pending
+            .cf_radial
+            .borrow_mut()
+            .insert(id, request.clone());
+
+        self.submit(id, Request::CfRadial(request));
+    }
+
+    /// Submit a downloaded Level III product for decoding and storage.
+    pub fn ingest_level3(&mut self, data: Vec<u8>, file: Level3File) {
+        let id = self.next_request_id();
+        self.pending.level3.borrow_mut().insert(id, file.clone());
+
+        self.submit(id, Request::Level3 { data, file });
     }
 
     /// Submit a single real-time chunk for incremental ingest.
@@ -130,7 +286,7 @@ impl DecodeWorker {
         is_last_in_sweep: bool,
     ) {
         let id = self.next_request_id();
-        self.pending_chunk_ingest.borrow_mut().insert(
+        self.pending.chunk_ingest.borrow_mut().insert(
             id,
             ChunkIngestContext {
                 site_id: site_id.clone(),
@@ -140,23 +296,9 @@ impl DecodeWorker {
             },
         );
 
-        if *self.ready.borrow() {
-            send_ingest_chunk_request(
-                &self.worker,
-                id,
-                &data,
-                &site_id,
-                timestamp_secs,
-                chunk_index,
-                is_start,
-                is_end,
-                &file_name,
-                skip_overlap_delete,
-                is_last_in_sweep,
-            );
-        } else {
-            self.queue.push(super::QueuedRequest::IngestChunk(
-                id,
+        self.submit(
+            id,
+            Request::IngestChunk {
                 data,
                 site_id,
                 timestamp_secs,
@@ -166,178 +308,7 @@ impl DecodeWorker {
                 file_name,
                 skip_overlap_delete,
                 is_last_in_sweep,
-            ));
-        }
-    }
-}
-
-// ---------------------------------------------------------------------------
-// Free-standing send helpers (used by both DecodeWorker methods and flush_queue)
-// ---------------------------------------------------------------------------
-
-/// Send an ingest request to the worker.
-pub(super) fn send_ingest_request(
-    worker: &Worker,
-    id: u64,
-    data: &[u8],
-    site_id: &str,
-    timestamp_secs: i64,
-    file_name: &str,
-) {
-    let request = IngestRequestMsg {
-        msg_type: "ingest",
-        id: id as f64,
-        site_id,
-        timestamp_secs: timestamp_secs as f64,
-        file_name,
-    };
-    let msg = match serde_wasm_bindgen::to_value(&request) {
-        Ok(v) => v,
-        Err(e) => {
-            log::error!("Failed to serialize ingest request {}: {}", id, e);
-            return;
-        }
-    };
-
-    // ArrayBuffer must be set directly for zero-copy transfer
-    let array = js_sys::Uint8Array::from(data);
-    let buffer = array.buffer();
-    js_sys::Reflect::set(&msg, &"data".into(), &buffer).ok();
-
-    let transfer = js_sys::Array::new();
-    transfer.push(&buffer);
-
-    if let Err(e) = worker.post_message_with_transfer(&msg, &transfer) {
-        log::error!("Failed to send ingest request {}: {:?}", id, e);
-    }
-}
-
-/// Send a chunk ingest request to the worker.
-#[allow(clippy::too_many_arguments)]
-pub(super) fn send_ingest_chunk_request(
-    worker: &Worker,
-    id: u64,
-    data: &[u8],
-    site_id: &str,
-    timestamp_secs: i64,
-    chunk_index: u32,
-    is_start: bool,
-    is_end: bool,
-    file_name: &str,
-    skip_overlap_delete: bool,
-    is_last_in_sweep: bool,
-) {
-    let request = IngestChunkRequestMsg {
-        msg_type: "ingest_chunk",
-        id: id as f64,
-        site_id,
-        timestamp_secs: timestamp_secs as f64,
-        chunk_index: chunk_index as f64,
-        is_start,
-        is_end,
-        file_name,
-        skip_overlap_delete,
-        is_last_in_sweep,
-    };
-    let msg = match serde_wasm_bindgen::to_value(&request) {
-        Ok(v) => v,
-        Err(e) => {
-            log::error!("Failed to serialize ingest_chunk request {}: {}", id, e);
-            return;
-        }
-    };
-
-    // ArrayBuffer must be set directly for zero-copy transfer
-    let array = js_sys::Uint8Array::from(data);
-    let buffer = array.buffer();
-    js_sys::Reflect::set(&msg, &"data".into(), &buffer).ok();
-
-    let transfer = js_sys::Array::new();
-    transfer.push(&buffer);
-
-    if let Err(e) = worker.post_message_with_transfer(&msg, &transfer) {
-        log::error!("Failed to send ingest_chunk request {}: {:?}", id, e);
-    }
-}
-
-/// Send a render request to the worker.
-pub(super) fn send_render_request(
-    worker: &Worker,
-    id: u64,
-    scan_key: &str,
-    elevation_number: u8,
-    product: &str,
-) {
-    let request = RenderRequestMsg {
-        msg_type: "render",
-        id: id as f64,
-        scan_key,
-        elevation_number,
-        product,
-    };
-    let msg = match serde_wasm_bindgen::to_value(&request) {
-        Ok(v) => v,
-        Err(e) => {
-            log::error!("Failed to serialize render request {}: {}", id, e);
-            return;
-        }
-    };
-
-    if let Err(e) = worker.post_message(&msg) {
-        log::error!("Failed to send render request {}: {:?}", id, e);
-    }
-}
-
-/// Send a render_live request to the worker.
-pub(super) fn send_render_live_request(
-    worker: &Worker,
-    id: u64,
-    elevation_number: u8,
-    product: &str,
-) {
-    let request = RenderLiveRequestMsg {
-        msg_type: "render_live",
-        id: id as f64,
-        elevation_number,
-        product,
-    };
-    let msg = match serde_wasm_bindgen::to_value(&request) {
-        Ok(v) => v,
-        Err(e) => {
-            log::error!("Failed to serialize render_live request {}: {}", id, e);
-            return;
-        }
-    };
-
-    if let Err(e) = worker.post_message(&msg) {
-        log::error!("Failed to send render_live request {}: {:?}", id, e);
-    }
-}
-
-/// Send a render_volume request to the worker.
-pub(super) fn send_render_volume_request(
-    worker: &Worker,
-    id: u64,
-    scan_key: &str,
-    product: &str,
-    elevation_numbers: &[u8],
-) {
-    let request = RenderVolumeRequestMsg {
-        msg_type: "render_volume",
-        id: id as f64,
-        scan_key,
-        product,
-        elevation_numbers,
-    };
-    let msg = match serde_wasm_bindgen::to_value(&request) {
-        Ok(v) => v,
-        Err(e) => {
-            log::error!("Failed to serialize render_volume request {}: {}", id, e);
-            return;
-        }
-    };
-
-    if let Err(e) = worker.post_message(&msg) {
-        log::error!("Failed to send render_volume request {}: {:?}", id, e);
+            },
+        );
     }
 }
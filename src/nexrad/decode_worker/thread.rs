@@ -0,0 +1,304 @@
+//! Desktop backend: a dedicated decode thread.
+//!
+//! The thread owns its own record store and live-sweep accumulator, just as
+//! each Web Worker does, and runs one request at a time by calling the
+//! `worker_api` operations directly. Results travel back over a channel and
+//! are drained into [`Pending`] from `try_recv`.
+
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::mpsc;
+
+use super::receive::{Pending, SweepArrays};
+use super::types::RequestId;
+use super::Request;
+use crate::nexrad::worker_api::{
+    self, Array, CfRadialParams, CfRadialResponse, ChunkIngestResponse, CrossSectionParams,
+    CrossSectionResponse, IngestChunkParams, IngestParams, IngestResponse, Level3Params,
+    Level3Response, MosaicParams, MosaicResponse, RenderLiveParams, RenderLowestAvailableParams,
+    RenderParams, RenderQpeParams, RenderResponse, RenderVolumeParams, Reply, VolumeRenderResponse,
+};
+
+/// A completed operation, before it is paired with its request context.
+enum Response {
+    Ingested(IngestResponse),
+    ChunkIngested(ChunkIngestResponse),
+    Decoded(Reply<RenderResponse>),
+    LiveDecoded(Reply<RenderResponse>),
+    VolumeDecoded(Reply<VolumeRenderResponse>),
+    QpeDecoded(Reply<RenderResponse>),
+    LowestAvailableDecoded(Reply<RenderResponse>),
+    CrossSectionDecoded(Reply<CrossSectionResponse>),
+    MosaicDecoded(Reply<MosaicResponse>),
+    CfRadialEncoded(Reply<CfRadialResponse>),
+    Level3Ingested(Level3Response),
+}
+
+type Job = (RequestId, Request, bool);
+type Done = (RequestId, Result<Response, String>);
+
+/// Channels to one decode thread.
+pub(super) struct Worker {
+    jobs: mpsc::Sender<Job>,
+    done: mpsc::Receiver<Done>,
+}
+
+impl Worker {
+    /// Start the decode thread. It exits when the worker is dropped.
+    pub fn new(ctx: eframe::egui::Context) -> Result<Self, String> {
+        let (jobs, job_rx) = mpsc::channel::<Job>();
+        let (done_tx, done) = mpsc::channel::<Done>();
+
+        std::thread::Builder::new()
+            .name("nexrad-decode".to_string())
+            .spawn(move || {
+                for (id, request, dealias_velocity) in job_rx {
+                    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+                        crate::platform::block_on(run(request, dealias_velocity))
+                    }))
+                    .unwrap_or_else(|_| Err("Decode thread panicked".to_string()));
+                    if done_tx.send((id, result)).is_err() {
+                        break;
+                    }
+                    ctx.request_repaint();
+                }
+            })
+            .map_err(|e| format!("Failed to start decode thread: {}", e))?;
+
+        Ok(Self { jobs, done })
+    }
+
+    /// The thread accepts requests as soon as it starts.
+    pub fn is_ready(&self) -> bool {
+        true
+    }
+
+    /// Hand finished requests to `pending`.
+    pub fn poll(&self, pending: &Pending) {
+        while let Ok((id, result)) = self.done.try_recv() {
+            match result {
+                Ok(response) => deliver(pending, id, response),
+                Err(message) => pending.failed(id, message),
+            }
+        }
+    }
+
+    /// Queue a request on the thread.
+    pub fn post(&self, id: RequestId, request: Request, dealias_velocity: bool) {
+        if self.jobs.send((id, request, dealias_velocity)).is_err() {
+            log::error!("Failed to send request {}: decode thread has exited", id);
+        }
+    }
+}
+
+/// Run one request on the decode thread.
+async fn run(request: Request, dealias_velocity: bool) -> Result<Response, String> {
+    let store = worker_api::worker_store().await?;
+    Ok(match request {
+        Request::Ingest {
+            data,
+            site_id,
+            timestamp_secs,
+            file_name,
+        } => {
+            let params = IngestParams {
+                site_id,
+                timestamp_secs: timestamp_secs.map(|t| t as f64),
+                file_name,
+                dealias_velocity,
+            };
+            Response::Ingested(worker_api::ingest(&store, data, params).await?)
+        }
+        Request::IngestChunk {
+            data,
+            site_id,
+            timestamp_secs,
+            chunk_index,
+            is_start,
+            is_end,
+            file_name,
+            skip_overlap_delete,
+            is_last_in_sweep,
+        } => {
+            let params = IngestChunkParams {
+                site_id,
+                timestamp_secs: timestamp_secs as f64,
+                chunk_index,
+                is_start,
+                is_end,
+                file_name,
+                skip_overlap_delete,
+                is_last_in_sweep,
+                dealias_velocity,
+            };
+            Response::ChunkIngested(worker_api::ingest_chunk(&store, data, params).await?)
+        }
+        Request::Render {
+            scan_key,
+            elevation_number,
+            product,
+        } => {
+            let params = RenderParams {
+                scan_key,
+                elevation_number,
+                product,
+            };
+            Response::Decoded(worker_api::render(&store, params).await?)
+        }
+        Request::RenderLive {
+            elevation_number,
+            product,
+        } => {
+            let params = RenderLiveParams {
+                product,
+                elevation_number: Some(elevation_number),
+            };
+            Response::LiveDecoded(worker_api::render_live(params)?)
+        }
+        Request::RenderVolume {
+            scan_key,
+            product,
+            elevation_numbers,
+        } => {
+            let params = RenderVolumeParams {
+                scan_key,
+                product,
+                elevation_numbers,
+            };
+            Response::VolumeDecoded(worker_api::render_volume(&store, params).await?)
+        }
+        Request::RenderQpe {
+            product,
+            scans,
+            settings,
+        } => {
+            let params = RenderQpeParams {
+                product,
+                scans,
+                settings,
+            };
+            Response::QpeDecoded(worker_api::render_qpe(&store, params).await?)
+        }
+        Request::RenderLowestAvailable { product, sweeps } => {
+            let params = RenderLowestAvailableParams { product, sweeps };
+            Response::LowestAvailableDecoded(
+                worker_api::render_lowest_available(&store, params).await?,
+            )
+        }
+        Request::CrossSection(request) => {
+            let params = CrossSectionParams {
+                scan_key: request.scan_key,
+                product: request.product,
+                elevation_numbers: request.elevation_numbers,
+                start_km: request.start_km,
+                end_km: request.end_km,
+                storm_motion_ms: request.storm_motion_ms,
+            };
+            Response::CrossSectionDecoded(worker_api::cross_section(&store, params).await?)
+        }
+        Request::Mosaic(request) => {
+            let params = MosaicParams {
+                site_ids: request.site_ids,
+                time_secs: request.time_secs,
+                max_age_secs: request.max_age_secs,
+                product: request.product,
+                mode: request.mode,
+            };
+            Response::MosaicDecoded(worker_api::mosaic(&store, params).await?)
+        }
+        Request::CfRadial(request) => {
+            let params = CfRadialParams {
+                scan_key: request.scan_key,
+                elevation_numbers: request.elevation_numbers,
+                site_id: request.site_id,
+                site_name: request.site_name,
+                latitude: request.latitude,
+                longitude: request.longitude,
+                altitude_m: request.altitude_m,
+            };
+            Response::CfRadialEncoded(worker_api::cf_radial(&store, params).await?)
+        }
+        Request::Level3 { data, file } => {
+            let params = Level3Params {
+                site_id: file.site_id,
+                file_name: file.key,
+            };
+            Response::Level3Ingested(worker_api::ingest_level3(&store, &data, params).await?)
+        }
+    })
+}
+
+/// Pair a finished response with its pending request.
+fn deliver(pending: &Pending, id: RequestId, response: Response) {
+    match response {
+        Response::Ingested(r) => pending.ingested(id, r),
+        Response::ChunkIngested(r) => pending.chunk_ingested(id, r),
+        Response::Decoded(mut reply) => {
+            let arrays = sweep_arrays(&mut reply.arrays);
+            pending.decoded(id, reply.fields, arrays);
+        }
+        Response::LiveDecoded(mut reply) => {
+            let arrays = sweep_arrays(&mut reply.arrays);
+            pending.live_decoded(id, reply.fields, arrays);
+        }
+        Response::QpeDecoded(mut reply) => {
+            let arrays = sweep_arrays(&mut reply.arrays);
+            pending.qpe_decoded(id, reply.fields, arrays);
+        }
+        Response::LowestAvailableDecoded(mut reply) => {
+            let arrays = sweep_arrays(&mut reply.arrays);
+            pending.lowest_available_decoded(id, reply.fields, arrays);
+        }
+        Response::VolumeDecoded(mut reply) => {
+            let buffer = take_u8(&mut reply.arrays, "buffer");
+            pending.volume_decoded(id, reply.fields, buffer);
+        }
+        Response::CrossSectionDecoded(mut reply) => {
+            let values = take_f32(&mut reply.arrays, "values");
+            pending.cross_section_decoded(id, reply.fields, values);
+        }
+        Response::MosaicDecoded(mut reply) => {
+            let values = take_f32(&mut reply.arrays, "values");
+            pending.mosaic_decoded(id, reply.fields, values);
+        }
+        Response::CfRadialEncoded(mut reply) => {
+            let bytes = take_u8(&mut reply.arrays, "data");
+            pending.cf_radial_encoded(id, reply.fields, bytes);
+        }
+        Response::Level3Ingested(r) => pending.level3_ingested(id, r),
+    }
+}
+
+fn sweep_arrays(arrays: &mut Vec<(&'static str, Array)>) -> SweepArrays {
+    SweepArrays {
+        azimuths: take_f32(arrays, "azimuths"),
+        gate_values: take_f32(arrays, "gateValues"),
+        radial_times: take_f64(arrays, "radialTimes"),
+        gate_times: take_f64(arrays, "gateTimes"),
+    }
+}
+
+fn take(arrays: &mut Vec<(&'static str, Array)>, name: &str) -> Option<Array> {
+    let index = arrays.iter().position(|(n, _)| *n == name)?;
+    Some(arrays.swap_remove(index).1)
+}
+
+fn take_u8(arrays: &mut Vec<(&'static str, Array)>, name: &str) -> Vec<u8> {
+    match take(arrays, name) {
+        Some(Array::U8(v)) => v,
+        _ => Vec::new(),
+    }
+}
+
+fn take_f32(arrays: &mut Vec<(&'static str, Array)>, name: &str) -> Vec<f32> {
+    match take(arrays, name) {
+        Some(Array::F32(v)) => v,
+        _ => Vec::new(),
+    }
+}
+
+fn take_f64(arrays: &mut Vec<(&'static str, Array)>, name: &str) -> Vec<f64> {
+    match take(arrays, name) {
+        Some(Array::F64(v)) => v,
+        _ => Vec::new(),
+    }
+}
@@ -1,256 +1,11 @@
-//! Type definitions for worker message payloads and public result types.
+//! Public request, context and result types for the decode workers.
 
+use crate::nexrad::cross_section::CrossSection;
+use crate::nexrad::level3::Level3File;
+use crate::nexrad::mosaic::MosaicGrid;
+use crate::nexrad::render_request::{CfRadialRequest, CrossSectionRequest, MosaicRequest};
 use serde::{Deserialize, Serialize};
 
-// ---------------------------------------------------------------------------
-// Internal message types (serde-wasm-bindgen deserialization)
-// ---------------------------------------------------------------------------
-
-/// Envelope for all worker response messages (type + id).
-#[derive(Deserialize)]
-pub(super) struct MessageEnvelope {
-    pub id: u64,
-}
-
-/// Ingest result payload from the worker.
-#[derive(Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub(super) struct IngestResultMsg {
-    pub scan_key: String,
-    pub records_stored: u32,
-    #[serde(default)]
-    pub elevation_numbers: Vec<u8>,
-    #[serde(default)]
-    pub total_ms: f64,
-    #[serde(default)]
-    pub split_ms: f64,
-    #[serde(default)]
-    pub decompress_ms: f64,
-    #[serde(default)]
-    pub decode_ms: f64,
-    #[serde(default)]
-    pub extract_ms: f64,
-    #[serde(default)]
-    pub store_ms: f64,
-    #[serde(default)]
-    pub index_ms: f64,
-    #[serde(default)]
-    pub sweeps: Vec<crate::data::SweepMeta>,
-    #[serde(default)]
-    pub vcp: Option<crate::data::keys::ExtractedVcp>,
-}
-
-/// Chunk ingest result payload from the worker.
-#[derive(Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub(super) struct ChunkIngestResultMsg {
-    pub scan_key: String,
-    #[serde(default)]
-    pub sweeps_stored: u32,
-    #[serde(default)]
-    pub is_end: bool,
-    #[serde(default)]
-    pub total_ms: f64,
-    #[serde(default)]
-    pub elevations_completed: Vec<u8>,
-    #[serde(default)]
-    pub sweeps: Vec<crate::data::SweepMeta>,
-    #[serde(default)]
-    pub vcp: Option<crate::data::keys::ExtractedVcp>,
-    #[serde(default)]
-    pub current_elevation: Option<u8>,
-    #[serde(default)]
-    pub current_elevation_radials: Option<u32>,
-    #[serde(default)]
-    pub last_radial_azimuth: Option<f32>,
-    #[serde(default)]
-    pub last_radial_time_secs: Option<f64>,
-    #[serde(default)]
-    pub volume_header_time_secs: Option<f64>,
-    #[serde(default)]
-    pub chunk_elev_spans: Vec<(u8, f64, f64, u32)>,
-    #[serde(default)]
-    pub chunk_elev_az_ranges: Vec<(u8, f32, f32)>,
-}
-
-/// Scalar fields of the decoded sweep response from the worker.
-/// ArrayBuffer fields (azimuths, gateValues, radialTimes) are extracted separately.
-#[derive(Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub(super) struct DecodedResultMsg {
-    #[serde(default)]
-    pub azimuth_count: u32,
-    #[serde(default)]
-    pub gate_count: u32,
-    #[serde(default)]
-    pub first_gate_range_km: f64,
-    #[serde(default)]
-    pub gate_interval_km: f64,
-    #[serde(default)]
-    pub max_range_km: f64,
-    #[serde(default = "default_product")]
-    pub product: String,
-    #[serde(default)]
-    pub radial_count: u32,
-    #[serde(default)]
-    pub fetch_ms: f64,
-    #[serde(default)]
-    pub deser_ms: f64,
-    #[serde(default)]
-    pub marshal_ms: f64,
-    #[serde(default)]
-    pub total_ms: f64,
-    #[serde(default = "default_scale")]
-    pub scale: f32,
-    #[serde(default)]
-    pub offset: f32,
-    #[serde(default)]
-    pub mean_elevation: f32,
-    #[serde(default)]
-    pub sweep_start_secs: f64,
-    #[serde(default)]
-    pub sweep_end_secs: f64,
-    #[serde(default = "default_azimuth_spacing_deg")]
-    pub azimuth_spacing_deg: f32,
-}
-
-fn default_azimuth_spacing_deg() -> f32 {
-    1.0
-}
-
-fn default_product() -> String {
-    "reflectivity".to_string()
-}
-
-fn default_scale() -> f32 {
-    1.0
-}
-
-/// Per-sweep metadata in a volume decoded response.
-#[derive(Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub(super) struct VolumeSweepMetaMsg {
-    #[serde(default)]
-    pub elevation_deg: f32,
-    #[serde(default)]
-    pub azimuth_count: u32,
-    #[serde(default)]
-    pub gate_count: u32,
-    #[serde(default)]
-    pub first_gate_km: f32,
-    #[serde(default)]
-    pub gate_interval_km: f32,
-    #[serde(default)]
-    pub max_range_km: f32,
-    #[serde(default)]
-    pub data_offset: u32,
-    #[serde(default)]
-    pub scale: f32,
-    #[serde(default)]
-    pub offset: f32,
-}
-
-/// Scalar fields of the volume decoded response.
-/// The `buffer` ArrayBuffer and `sweepMeta` array are extracted separately.
-#[derive(Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub(super) struct VolumeDecodedResultMsg {
-    #[serde(default)]
-    pub total_ms: f64,
-    #[serde(default = "default_product")]
-    pub product: String,
-    #[serde(default = "default_word_size")]
-    pub word_size: u8,
-    #[serde(default)]
-    pub sweep_meta: Vec<VolumeSweepMetaMsg>,
-}
-
-fn default_word_size() -> u8 {
-    2
-}
-
-/// Error message from the worker.
-#[derive(Deserialize)]
-pub(super) struct ErrorMsg {
-    pub id: u64,
-    #[serde(default = "default_error_message")]
-    pub message: String,
-}
-
-fn default_error_message() -> String {
-    "Unknown worker error".to_string()
-}
-
-// ---------------------------------------------------------------------------
-// Outgoing request message types (main → worker)
-// ---------------------------------------------------------------------------
-
-/// Request message sent to the worker for ingest operations.
-/// The `data` ArrayBuffer is set separately for zero-copy transfer.
-#[derive(Serialize)]
-#[serde(rename_all = "camelCase")]
-pub(super) struct IngestRequestMsg<'a> {
-    #[serde(rename = "type")]
-    pub msg_type: &'a str,
-    pub id: f64,
-    pub site_id: &'a str,
-    pub timestamp_secs: f64,
-    pub file_name: &'a str,
-}
-
-/// Request message sent to the worker for chunk ingest operations.
-/// The `data` ArrayBuffer is set separately for zero-copy transfer.
-#[derive(Serialize)]
-#[serde(rename_all = "camelCase")]
-pub(super) struct IngestChunkRequestMsg<'a> {
-    #[serde(rename = "type")]
-    pub msg_type: &'a str,
-    pub id: f64,
-    pub site_id: &'a str,
-    pub timestamp_secs: f64,
-    pub chunk_index: f64,
-    pub is_start: bool,
-    pub is_end: bool,
-    pub file_name: &'a str,
-    pub skip_overlap_delete: bool,
-    pub is_last_in_sweep: bool,
-}
-
-/// Request message sent to the worker for render operations.
-#[derive(Serialize)]
-#[serde(rename_all = "camelCase")]
-pub(super) struct RenderRequestMsg<'a> {
-    #[serde(rename = "type")]
-    pub msg_type: &'a str,
-    pub id: f64,
-    pub scan_key: &'a str,
-    pub elevation_number: u8,
-    pub product: &'a str,
-}
-
-/// Request message sent to the worker for volume render operations.
-#[derive(Serialize)]
-#[serde(rename_all = "camelCase")]
-pub(super) struct RenderVolumeRequestMsg<'a> {
-    #[serde(rename = "type")]
-    pub msg_type: &'a str,
-    pub id: f64,
-    pub scan_key: &'a str,
-    pub product: &'a str,
-    pub elevation_numbers: &'a [u8],
-}
-
-/// Request message sent to the worker for live render operations.
-#[derive(Serialize)]
-#[serde(rename_all = "camelCase")]
-pub(super) struct RenderLiveRequestMsg<'a> {
-    #[serde(rename = "type")]
-    pub msg_type: &'a str,
-    pub id: f64,
-    pub elevation_number: u8,
-    pub product: &'a str,
-}
-
 // ---------------------------------------------------------------------------
 // Public result/context types
 // ---------------------------------------------------------------------------
@@ -264,6 +19,13 @@ pub struct IngestContext {
     pub timestamp_secs: i64,
     pub file_name: String,
     pub fetch_latency_ms: f64,
+    /// A local file import. The site and scan time are only known from the
+    /// result's scan key; `timestamp_secs` is 0.
+    pub imported: bool,
+    /// A neighbouring site's volume fetched for the regional mosaic.
+    pub mosaic: bool,
+    /// A pinned secondary site's volume.
+    pub pinned: bool,
 }
 
 /// Successful ingest result from the worker.
@@ -297,6 +59,15 @@ pub struct IngestResult {
     pub index_ms: f64,
 }
 
+/// A Level III product stored by the worker.
+pub struct Level3IngestResult {
+    pub file: Level3File,
+    /// Scan the product was filed under.
+    pub scan_key: String,
+    pub product: String,
+    pub total_ms: f64,
+}
+
 /// Context for a per-chunk ingest request (real-time streaming).
 #[allow(dead_code)]
 pub struct ChunkIngestContext {
@@ -348,6 +119,29 @@ pub struct RenderContext {
     pub scan_key: String,
     /// Elevation number being rendered.
     pub elevation_number: u8,
+    /// Product requested.
+    pub product: String,
+}
+
+/// One cached scan contributing to a rainfall accumulation.
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QpeScan {
+    /// Scan storage key.
+    pub scan_key: String,
+    /// Elevation number of the scan's lowest tilt.
+    pub elevation_number: u8,
+    /// How long this scan's rain rate holds within the window, in hours.
+    pub hours: f64,
+}
+
+/// One cached sweep offered to a lowest-available composite.
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LowestAvailableSweep {
+    /// Scan storage key.
+    pub scan_key: String,
+    pub elevation_number: u8,
 }
 
 /// Decoded radar sweep data from the worker (raw data for GPU rendering).
@@ -388,6 +182,19 @@ pub struct DecodeResult {
     /// Median angular spacing between adjacent sorted radials, in degrees.
     /// Used by the shader's search threshold instead of deriving from azimuth_count.
     pub azimuth_spacing_deg: f32,
+    /// Per-gate collection timestamps in Unix seconds (parallel to
+    /// gate_values, NaN = no data). Only set for lowest-available
+    /// composites, whose gates come from different sweeps.
+    pub gate_times: Vec<f64>,
+    /// Nyquist velocity (m/s) recorded with a velocity sweep.
+    pub nyquist_velocity: Option<f32>,
+}
+
+impl DecodeResult {
+    /// Nyquist velocity if the gate values are aliased (raw) velocity.
+    pub fn aliased_nyquist(&self) -> Option<f32> {
+        self.nyquist_velocity.filter(|_| self.product == "velocity")
+    }
 }
 
 /// Per-sweep metadata for the volume ray marcher.
@@ -397,7 +204,6 @@ pub struct VolumeSweepMeta {
     pub gate_count: u32,
     pub first_gate_km: f32,
     pub gate_interval_km: f32,
-    pub max_range_km: f32,
     pub data_offset: u32,
     pub scale: f32,
     pub offset: f32,
@@ -416,6 +222,44 @@ pub struct VolumeData {
     pub total_ms: f64,
 }
 
+/// A computed cross-section and the request it answers.
+pub struct CrossSectionData {
+    pub request: CrossSectionRequest,
+    pub section: CrossSection,
+}
+
+/// A composited mosaic and the request it answers.
+pub struct MosaicData {
+    pub request: MosaicRequest,
+    pub grid: MosaicGrid,
+    /// Storage keys of the scans composited, one per site with data.
+    pub scan_keys: Vec<String>,
+}
+
+/// Encoded CF-Radial file contents. On the web the bytes stay in JS memory;
+/// they are only handed to the browser as a download.
+#[cfg(target_arch = "wasm32")]
+pub type CfRadialBytes = js_sys::Uint8Array;
+#[cfg(not(target_arch = "wasm32"))]
+pub type CfRadialBytes = Vec<u8>;
+
+/// An encoded CF-Radial file and the request it answers.
+pub struct CfRadialData {
+    pub request: CfRadialRequest,
+    pub bytes: CfRadialBytes,
+    pub sweep_count: u32,
+}
+
+impl CfRadialData {
+    /// Size of the encoded file in bytes.
+    pub fn byte_len(&self) -> usize {
+        #[cfg(target_arch = "wasm32")]
+        return self.bytes.length() as usize;
+        #[cfg(not(target_arch = "wasm32"))]
+        return self.bytes.len();
+    }
+}
+
 /// Outcome of any worker operation.
 pub enum WorkerOutcome {
     /// Archive ingest completed.
@@ -428,6 +272,42 @@ pub enum WorkerOutcome {
     LiveDecoded(DecodeResult),
     /// Volume decode completed (all elevations packed for ray marching).
     VolumeDecoded(VolumeData),
+    /// Rainfall accumulation computed from cached scans (values in mm).
+    QpeDecoded(DecodeResult),
+    /// Lowest-available composite of recent sweeps, with per-gate times.
+    LowestAvailableDecoded(DecodeResult),
+    /// Vertical cross-section computed from the cached volume.
+    CrossSectionDecoded(CrossSectionData),
+    /// A cross-section request failed.
+    CrossSectionFailed {
+        request: CrossSectionRequest,
+        message: String,
+    },
+    /// Regional mosaic composited from several sites' cached scans.
+    MosaicDecoded(MosaicData),
+    /// A mosaic request failed.
+    MosaicFailed {
+        request: MosaicRequest,
+        message: String,
+    },
+    /// CF-Radial file written from the cached volume.
+    CfRadialEncoded(CfRadialData),
+    /// A CF-Radial export failed.
+    CfRadialFailed {
+        request: CfRadialRequest,
+        message: String,
+    },
+    /// A Level III product was decoded and stored.
+    Level3Ingested(Level3IngestResult),
+    /// A Level III product could not be decoded or stored.
+    Level3Failed { file: Level3File, message: String },
+    /// A local file import could not be ingested.
+    ImportFailed { file_name: String, message: String },
+    /// A neighbouring site's volume fetched for the mosaic could not be
+    /// ingested.
+    MosaicIngestFailed { file_name: String, message: String },
+    /// A pinned site's volume could not be ingested.
+    PinnedIngestFailed { file_name: String, message: String },
     /// Error from any operation.
     WorkerError {
         id: u64,
@@ -437,6 +317,9 @@ pub enum WorkerOutcome {
         /// render. Lets callers clean up per-scan UI state (e.g. timeline
         /// ghosts) without guessing from global state.
         failed_scan_timestamp_secs: Option<i64>,
+        /// Context of the failed request when it was a single-sweep archive
+        /// render, so callers can tell which view asked for it.
+        failed_render: Option<RenderContext>,
     },
 }
 
@@ -0,0 +1,627 @@
+//! Browser backend: a module Web Worker running worker.js.
+//!
+//! Requests are posted as plain objects with their input bytes transferred
+//! as ArrayBuffers; replies come back through `onmessage` and are decoded
+//! into the `worker_api` response types for [`Pending`].
+
+use std::cell::Cell;
+use std::rc::Rc;
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use web_sys::{MessageEvent, WorkerOptions, WorkerType};
+
+use super::receive::{Pending, SweepArrays};
+use super::types::*;
+use super::Request;
+use crate::nexrad::mosaic::MosaicMode;
+
+// ---------------------------------------------------------------------------
+// Message types
+// ---------------------------------------------------------------------------
+
+/// Envelope for all worker response messages (type + id).
+#[derive(Deserialize)]
+struct MessageEnvelope {
+    id: u64,
+}
+
+/// Error message from the worker.
+#[derive(Deserialize)]
+struct ErrorMsg {
+    id: u64,
+    #[serde(default = "default_error_message")]
+    message: String,
+}
+
+fn default_error_message() -> String {
+    "Unknown worker error".to_string()
+}
+
+/// Request message sent to the worker for ingest operations.
+/// The `data` ArrayBuffer is set separately for zero-copy transfer.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct IngestRequestMsg<'a> {
+    #[serde(rename = "type")]
+    msg_type: &'a str,
+    id: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    site_id: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    timestamp_secs: Option<f64>,
+    file_name: &'a str,
+    dealias_velocity: bool,
+}
+
+/// Request message sent to the worker for chunk ingest operations.
+/// The `data` ArrayBuffer is set separately for zero-copy transfer.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct IngestChunkRequestMsg<'a> {
+    #[serde(rename = "type")]
+    msg_type: &'a str,
+    id: f64,
+    site_id: &'a str,
+    timestamp_secs: f64,
+    chunk_index: f64,
+    is_start: bool,
+    is_end: bool,
+    file_name: &'a str,
+    skip_overlap_delete: bool,
+    is_last_in_sweep: bool,
+    dealias_velocity: bool,
+}
+
+/// Request message sent to the worker for render operations.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct RenderRequestMsg<'a> {
+    #[serde(rename = "type")]
+    msg_type: &'a str,
+    id: f64,
+    scan_key: &'a str,
+    elevation_number: u8,
+    product: &'a str,
+}
+
+/// Request message sent to the worker for volume render operations.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct RenderVolumeRequestMsg<'a> {
+    #[serde(rename = "type")]
+    msg_type: &'a str,
+    id: f64,
+    scan_key: &'a str,
+    product: &'a str,
+    elevation_numbers: &'a [u8],
+}
+
+/// Request message sent to the worker for live render operations.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct RenderLiveRequestMsg<'a> {
+    #[serde(rename = "type")]
+    msg_type: &'a str,
+    id: f64,
+    elevation_number: u8,
+    product: &'a str,
+}
+
+/// Request message sent to the worker for rainfall accumulations.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct RenderQpeRequestMsg<'a> {
+    #[serde(rename = "type")]
+    msg_type: &'a str,
+    id: f64,
+    product: &'a str,
+    scans: &'a [QpeScan],
+    settings: &'a crate::nexrad::qpe::QpeSettings,
+}
+
+/// Request message sent to the worker for lowest-available composites.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct RenderLowestAvailableRequestMsg<'a> {
+    #[serde(rename = "type")]
+    msg_type: &'a str,
+    id: f64,
+    product: &'a str,
+    sweeps: &'a [LowestAvailableSweep],
+}
+
+/// Request message sent to the worker for vertical cross-sections.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CrossSectionRequestMsg<'a> {
+    #[serde(rename = "type")]
+    msg_type: &'a str,
+    id: f64,
+    scan_key: &'a str,
+    product: &'a str,
+    elevation_numbers: &'a [u8],
+    start_km: (f64, f64),
+    end_km: (f64, f64),
+    storm_motion_ms: [f32; 2],
+}
+
+/// Request message sent to the worker for regional mosaics.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct MosaicRequestMsg<'a> {
+    #[serde(rename = "type")]
+    msg_type: &'a str,
+    id: f64,
+    site_ids: &'a [String],
+    time_secs: i64,
+    max_age_secs: i64,
+    product: &'a str,
+    mode: MosaicMode,
+}
+
+/// Request message sent to the worker for CF-Radial exports.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CfRadialRequestMsg<'a> {
+    #[serde(rename = "type")]
+    msg_type: &'a str,
+    id: f64,
+    scan_key: &'a str,
+    elevation_numbers: &'a [u8],
+    site_id: &'a str,
+    site_name: &'a str,
+    latitude: f64,
+    longitude: f64,
+    altitude_m: f64,
+}
+
+/// Request message sent to the worker to store a Level III product.
+/// The `data` ArrayBuffer is set separately for zero-copy transfer.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Level3RequestMsg<'a> {
+    #[serde(rename = "type")]
+    msg_type: &'a str,
+    id: f64,
+    site_id: &'a str,
+    file_name: &'a str,
+}
+
+// ---------------------------------------------------------------------------
+// Worker handle
+// ---------------------------------------------------------------------------
+
+/// A module Web Worker and its readiness flag.
+pub(super) struct Worker {
+    worker: web_sys::Worker,
+    ready: Rc<Cell<bool>>,
+}
+
+impl Worker {
+    /// Create the worker and send its init message.
+    ///
+    /// Discovers the WASM/JS URLs from the current page's `<link>` tags
+    /// (generated by Trunk), creates a module worker, and sends the init message.
+    /// The worker will post a "ready" message once WASM is initialized.
+    pub fn new(ctx: eframe::egui::Context, pending: Rc<Pending>) -> Result<Self, String> {
+        let js_url =
+            discover_js_url().ok_or_else(|| "Could not find JS module URL in DOM".to_string())?;
+        let wasm_url =
+            discover_wasm_url().ok_or_else(|| "Could not find WASM URL in DOM".to_string())?;
+
+        log::debug!(
+            "Creating decode worker with JS={}, WASM={}",
+            js_url,
+            wasm_url
+        );
+
+        // Create an ES module worker
+        let mut opts = WorkerOptions::new();
+        #[allow(deprecated)]
+        opts.type_(WorkerType::Module);
+
+        let worker = web_sys::Worker::new_with_options("worker.js", &opts)
+            .map_err(|e| format!("Failed to create Worker: {:?}", e))?;
+
+        let ready = Rc::new(Cell::new(false));
+        setup_onmessage(&worker, ctx, ready.clone(), pending);
+
+        // Set up onerror handler
+        {
+            let onerror =
+                Closure::<dyn Fn(web_sys::ErrorEvent)>::new(move |event: web_sys::ErrorEvent| {
+                    log::error!(
+                        "Decode worker error: {} ({}:{})",
+                        event.message(),
+                        event.filename(),
+                        event.lineno()
+                    );
+                });
+
+            worker.set_onerror(Some(onerror.as_ref().unchecked_ref()));
+            onerror.forget();
+        }
+
+        // Send init message with the WASM/JS URLs
+        let init_msg = js_sys::Object::new();
+        js_sys::Reflect::set(&init_msg, &"type".into(), &"init".into()).ok();
+        js_sys::Reflect::set(&init_msg, &"jsUrl".into(), &js_url.into()).ok();
+        js_sys::Reflect::set(&init_msg, &"wasmUrl".into(), &wasm_url.into()).ok();
+
+        worker
+            .post_message(&init_msg)
+            .map_err(|e| format!("Failed to send init message: {:?}", e))?;
+
+        Ok(Self { worker, ready })
+    }
+
+    /// Whether the worker has loaded its WASM module.
+    pub fn is_ready(&self) -> bool {
+        self.ready.get()
+    }
+
+    /// Replies arrive through `onmessage`, so there is nothing to poll.
+    pub fn poll(&self, _pending: &Pending) {}
+
+    /// Post a request to the worker.
+    pub fn post(&self, id: RequestId, request: Request, dealias_velocity: bool) {
+        match request {
+            Request::Ingest {
+                data,
+                site_id,
+                timestamp_secs,
+                file_name,
+            } => {
+                let msg = IngestRequestMsg {
+                    msg_type: "ingest",
+                    id: id as f64,
+                    site_id: site_id.as_deref(),
+                    timestamp_secs: timestamp_secs.map(|t| t as f64),
+                    file_name: &file_name,
+                    dealias_velocity,
+                };
+                self.post_message(id, msg.msg_type, &msg, Some(&data));
+            }
+            Request::IngestChunk {
+                data,
+                site_id,
+                timestamp_secs,
+                chunk_index,
+                is_start,
+                is_end,
+                file_name,
+                skip_overlap_delete,
+                is_last_in_sweep,
+            } => {
+                let msg = IngestChunkRequestMsg {
+                    msg_type: "ingest_chunk",
+                    id: id as f64,
+                    site_id: &site_id,
+                    timestamp_secs: timestamp_secs as f64,
+                    chunk_index: chunk_index as f64,
+                    is_start,
+                    is_end,
+                    file_name: &file_name,
+                    skip_overlap_delete,
+                    is_last_in_sweep,
+                    dealias_velocity,
+                };
+                self.post_message(id, msg.msg_type, &msg, Some(&data));
+            }
+            Request::Render {
+                scan_key,
+                elevation_number,
+                product,
+            } => {
+                let msg = RenderRequestMsg {
+                    msg_type: "render",
+                    id: id as f64,
+                    scan_key: &scan_key,
+                    elevation_number,
+                    product: &product,
+                };
+                self.post_message(id, msg.msg_type, &msg, None);
+            }
+            Request::RenderLive {
+                elevation_number,
+                product,
+            } => {
+                let msg = RenderLiveRequestMsg {
+                    msg_type: "render_live",
+                    id: id as f64,
+                    elevation_number,
+                    product: &product,
+                };
+                self.post_message(id, msg.msg_type, &msg, None);
+            }
+            Request::RenderVolume {
+                scan_key,
+                product,
+                elevation_numbers,
+            } => {
+                let msg = RenderVolumeRequestMsg {
+                    msg_type: "render_volume",
+                    id: id as f64,
+                    scan_key: &scan_key,
+                    product: &product,
+                    elevation_numbers: &elevation_numbers,
+                };
+                self.post_message(id, msg.msg_type, &msg, None);
+            }
+            Request::RenderQpe {
+                product,
+                scans,
+                settings,
+            } => {
+                let msg = RenderQpeRequestMsg {
+                    msg_type: "render_qpe",
+                    id: id as f64,
+                    product: &product,
+                    scans: &scans,
+                    settings: &settings,
+                };
+                self.post_message(id, msg.msg_type, &msg, None);
+            }
+            Request::RenderLowestAvailable { product, sweeps } => {
+                let msg = RenderLowestAvailableRequestMsg {
+                    msg_type: "render_lowest_available",
+                    id: id as f64,
+                    product: &product,
+                    sweeps: &sweeps,
+                };
+                self.post_message(id, msg.msg_type, &msg, None);
+            }
+            Request::CrossSection(request) => {
+                let msg = CrossSectionRequestMsg {
+                    msg_type: "cross_section",
+                    id: id as f64,
+                    scan_key: &request.scan_key,
+                    product: &request.product,
+                    elevation_numbers: &request.elevation_numbers,
+                    start_km: request.start_km,
+                    end_km: request.end_km,
+                    storm_motion_ms: request.storm_motion_ms,
+                };
+                self.post_message(id, msg.msg_type, &msg, None);
+            }
+            Request::Mosaic(request) => {
+                let msg = MosaicRequestMsg {
+                    msg_type: "mosaic",
+                    id: id as f64,
+                    site_ids: &request.site_ids,
+                    time_secs: request.time_secs,
+                    max_age_secs: request.max_age_secs,
+                    product: &request.product,
+                    mode: request.mode,
+                };
+                self.post_message(id, msg.msg_type, &msg, None);
+            }
+            Request::CfRadial(request) => {
+                let msg = CfRadialRequestMsg {
+                    msg_type: "cf_radial",
+                    id: id as f64,
+                    scan_key: &request.scan_key,
+                    elevation_numbers: &request.elevation_numbers,
+                    site_id: &request.site_id,
+                    site_name: &request.site_name,
+                    latitude: request.latitude,
+                    longitude: request.longitude,
+                    altitude_m: request.altitude_m,
+                };
+                self.post_message(id, msg.msg_type, &msg, None);
+            }
+            Request::Level3 { data, file } => {
+                let msg = Level3RequestMsg {
+                    msg_type: "ingest_level3",
+                    id: id as f64,
+                    site_id: &file.site_id,
+                    file_name: &file.key,
+                };
+                self.post_message(id, msg.msg_type, &msg, Some(&data));
+            }
+        }
+    }
+
+    /// Serialize and post one request. Input bytes are set directly as an
+    /// ArrayBuffer and transferred for zero-copy.
+    fn post_message<T: Serialize>(
+        &self,
+        id: RequestId,
+        msg_type: &str,
+        msg: &T,
+        data: Option<&[u8]>,
+    ) {
+        let msg = match serde_wasm_bindgen::to_value(msg) {
+            Ok(v) => v,
+            Err(e) => {
+                log::error!("Failed to serialize {} request {}: {}", msg_type, id, e);
+                return;
+            }
+        };
+
+        let sent = match data {
+            Some(data) => {
+                let buffer = js_sys::Uint8Array::from(data).buffer();
+                js_sys::Reflect::set(&msg, &"data".into(), &buffer).ok();
+                let transfer = js_sys::Array::new();
+                transfer.push(&buffer);
+                self.worker.post_message_with_transfer(&msg, &transfer)
+            }
+            None => self.worker.post_message(&msg),
+        };
+        if let Err(e) = sent {
+            log::error!("Failed to send {} request {}: {:?}", msg_type, id, e);
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// onmessage callback
+// ---------------------------------------------------------------------------
+
+/// Install the `onmessage` callback on the worker.
+fn setup_onmessage(
+    worker: &web_sys::Worker,
+    ctx: eframe::egui::Context,
+    ready: Rc<Cell<bool>>,
+    pending: Rc<Pending>,
+) {
+    let onmessage = Closure::<dyn Fn(MessageEvent)>::new(move |event: MessageEvent| {
+        let data = event.data();
+        let msg_type = js_sys::Reflect::get(&data, &"type".into())
+            .ok()
+            .and_then(|v| v.as_string());
+
+        match msg_type.as_deref() {
+            Some("ready") => {
+                ready.set(true);
+                log::debug!("Decode worker ready");
+                return;
+            }
+            Some("error") => match serde_wasm_bindgen::from_value::<ErrorMsg>(data) {
+                Ok(e) => pending.failed(e.id, e.message),
+                Err(err) => log::error!("Failed to parse error message: {}", err),
+            },
+            Some(msg_type) => handle_reply(&pending, msg_type, &data),
+            None => {
+                log::warn!("Unknown worker message type: {:?}", msg_type);
+                return;
+            }
+        }
+        ctx.request_repaint();
+    });
+
+    worker.set_onmessage(Some(onmessage.as_ref().unchecked_ref()));
+    onmessage.forget(); // Lives for app lifetime
+}
+
+/// Decode a successful reply and hand it to the matching pending request.
+/// A reply that fails to parse fails its request.
+fn handle_reply(pending: &Pending, msg_type: &str, data: &JsValue) {
+    let id = match serde_wasm_bindgen::from_value::<MessageEnvelope>(data.clone()) {
+        Ok(envelope) => envelope.id,
+        Err(e) => {
+            log::warn!("Failed to parse {} envelope: {}", msg_type, e);
+            return;
+        }
+    };
+
+    let parsed = match msg_type {
+        "ingested" => parse(&get(data, "result")).map(|r| pending.ingested(id, r)),
+        "chunk_ingested" => parse(&get(data, "result")).map(|r| pending.chunk_ingested(id, r)),
+        "decoded" => parse(data).map(|r| pending.decoded(id, r, sweep_arrays(data))),
+        "live_decoded" => parse(data).map(|r| pending.live_decoded(id, r, sweep_arrays(data))),
+        "qpe_decoded" => parse(data).map(|r| pending.qpe_decoded(id, r, sweep_arrays(data))),
+        "lowest_available_decoded" => parse(data).map(|r| {
+            let mut arrays = sweep_arrays(data);
+            arrays.gate_times = f64_array(data, "gateTimes");
+            pending.lowest_available_decoded(id, r, arrays)
+        }),
+        "volume_decoded" => {
+            parse(data).map(|r| pending.volume_decoded(id, r, u8_array(data, "buffer")))
+        }
+        "cross_section_decoded" => {
+            parse(data).map(|r| pending.cross_section_decoded(id, r, f32_array(data, "values")))
+        }
+        "mosaic_decoded" => {
+            parse(data).map(|r| pending.mosaic_decoded(id, r, f32_array(data, "values")))
+        }
+        "cf_radial_encoded" => parse(data).map(|r| {
+            let bytes = js_sys::Uint8Array::new(&get(data, "data"));
+            pending.cf_radial_encoded(id, r, bytes)
+        }),
+        "level3_ingested" => parse(data).map(|r| pending.level3_ingested(id, r)),
+        other => {
+            log::warn!("Unknown worker message type: {:?}", other);
+            return;
+        }
+    };
+
+    if let Err(e) = parsed {
+        pending.failed(id, format!("Failed to parse {} result: {}", msg_type, e));
+    }
+}
+
+fn parse<T: DeserializeOwned>(value: &JsValue) -> Result<T, serde_wasm_bindgen::Error> {
+    serde_wasm_bindgen::from_value(value.clone())
+}
+
+fn get(data: &JsValue, name: &str) -> JsValue {
+    js_sys::Reflect::get(data, &name.into()).unwrap_or(JsValue::NULL)
+}
+
+fn sweep_arrays(data: &JsValue) -> SweepArrays {
+    SweepArrays {
+        azimuths: f32_array(data, "azimuths"),
+        gate_values: f32_array(data, "gateValues"),
+        radial_times: f64_array(data, "radialTimes"),
+        gate_times: Vec::new(),
+    }
+}
+
+fn u8_array(data: &JsValue, name: &str) -> Vec<u8> {
+    let buffer = get(data, name);
+    if buffer.is_object() {
+        js_sys::Uint8Array::new(&buffer).to_vec()
+    } else {
+        Vec::new()
+    }
+}
+
+fn f32_array(data: &JsValue, name: &str) -> Vec<f32> {
+    let buffer = get(data, name);
+    if buffer.is_object() {
+        js_sys::Float32Array::new(&buffer).to_vec()
+    } else {
+        Vec::new()
+    }
+}
+
+fn f64_array(data: &JsValue, name: &str) -> Vec<f64> {
+    let buffer = get(data, name);
+    if buffer.is_object() {
+        js_sys::Float64Array::new(&buffer).to_vec()
+    } else {
+        Vec::new()
+    }
+}
+
+// ---------------------------------------------------------------------------
+// DOM discovery helpers
+// ---------------------------------------------------------------------------
+
+/// Discover the Trunk-generated JS module URL from DOM `<link rel="modulepreload">` tags.
+fn discover_js_url() -> Option<String> {
+    let document = web_sys::window()?.document()?;
+    let links = document
+        .query_selector_all("link[rel='modulepreload']")
+        .ok()?;
+    for i in 0..links.length() {
+        if let Some(el) = links.get(i) {
+            let el: &web_sys::Element = el.unchecked_ref();
+            if let Some(href) = el.get_attribute("href") {
+                // Find the main app module (not snippet/helper modules)
+                if href.contains("nexrad-workbench") && href.ends_with(".js") {
+                    return Some(href);
+                }
+            }
+        }
+    }
+    None
+}
+
+/// Discover the Trunk-generated WASM URL from DOM `<link rel="preload">` tags.
+fn discover_wasm_url() -> Option<String> {
+    let document = web_sys::window()?.document()?;
+    let links = document
+        .query_selector_all("link[rel='preload'][type='application/wasm']")
+        .ok()?;
+    if let Some(el) = links.get(0) {
+        let el: &web_sys::Element = el.unchecked_ref();
+        return el.get_attribute("href");
+    }
+    None
+}
@@ -0,0 +1,386 @@
+//! Volume-derived products: composite reflectivity, echo tops and VIL.
+//!
+//! These combine every reflectivity tilt of a volume into a single 2-D field
+//! and are computed in the worker during ingest, after the per-tilt sweep
+//! blobs. The result is stored as ordinary `PrecomputedSweep` blobs under
+//! their own product strings at the volume's lowest elevation number, so the
+//! normal render path can draw them like any other sweep.
+//!
+//! Tilts are projected onto a fixed polar ground-range grid (0.5° × 1 km)
+//! using the 4/3 effective earth radius model. Each grid cell keeps the
+//! maximum reflectivity seen in it per tilt; the tilt maxima are then
+//! combined bottom-up:
+//!
+//! - composite reflectivity: maximum over all tilts,
+//! - echo tops: highest beam-centre height above mean sea level (site
+//!   elevation plus beam height) where reflectivity reaches 18, 30 and
+//!   50 dBZ,
+//! - VIL: `Σ 3.44e-6 · ((Zᵢ + Zᵢ₊₁) / 2)^(4/7) · Δh` over consecutive tilts,
+//!   with reflectivity capped at 56 dBZ to limit hail contamination.
+
+use super::beam::{beam_height_km, ground_range_km};
+use crate::data::keys::{GateValues, PrecomputedSweep};
+use crate::data::moments::decode_physical;
+
+/// Product key for composite (column-maximum) reflectivity.
+pub(crate) const COMPOSITE_REFLECTIVITY: &str = "composite_reflectivity";
+
+/// Echo top thresholds (dBZ) and their product keys.
+pub(crate) const ECHO_TOPS: [(f32, &str); 3] = [
+    (18.0, "echo_tops_18"),
+    (30.0, "echo_tops_30"),
+    (50.0, "echo_tops_50"),
+];
+
+/// Product key for vertically integrated liquid.
+pub(crate) const VIL: &str = "vil";
+
+/// Every product string produced by [`VolumeProductAccumulator::finish`].
+pub(crate) const VOLUME_PRODUCTS: &[&str] = &[
+    COMPOSITE_REFLECTIVITY,
+    "echo_tops_18",
+    "echo_tops_30",
+    "echo_tops_50",
+    VIL,
+];
+
+/// Whether `product` is a volume-derived product rather than a single tilt.
+pub(crate) fn is_volume_product(product: &str) -> bool {
+    VOLUME_PRODUCTS.contains(&product)
+}
+
+/// Output grid: azimuth bins of 0.5° and ground-range bins of 1 km. Shared
+/// with the QPE accumulations.
+pub(super) const GRID_AZIMUTHS: usize = 720;
+pub(super) const GRID_AZ_DEG: f32 = 360.0 / GRID_AZIMUTHS as f32;
+pub(super) const GRID_GATE_KM: f64 = 1.0;
+pub(super) const GRID_GATES: usize = 460;
+
+/// Tilts within this many degrees of the last processed one are treated as
+/// repeats (split cuts, SAILS / MESO-SAILS re-scans of the base tilt).
+const MIN_TILT_STEP_DEG: f32 = 0.05;
+
+/// Reflectivity cap used in the VIL integral (hail mitigation).
+const VIL_MAX_DBZ: f32 = 56.0;
+
+/// U16 encodings (`physical = (raw - offset) / scale`, raw 0 = no data).
+const DBZ_SCALE: f32 = 2.0;
+const DBZ_OFFSET: f32 = 66.0;
+const ECHO_TOP_SCALE: f32 = 100.0; // 10 m resolution
+const VIL_SCALE: f32 = 10.0; // 0.1 kg/m² resolution
+const HEIGHT_OFFSET: f32 = 2.0;
+
+/// Builds the volume products one reflectivity tilt at a time.
+///
+/// Tilts must be added in collection order; lower repeats of the base tilt
+/// are skipped. Grid buffers are allocated on the first accepted tilt.
+#[derive(Default)]
+pub(crate) struct VolumeProductAccumulator {
+    composite: Vec<f32>,
+    echo_tops: [Vec<f32>; 3],
+    vil: Vec<f32>,
+    /// Per-cell maximum dBZ and beam height of the previous tilt, for the
+    /// VIL layer integral.
+    prev_dbz: Vec<f32>,
+    prev_height_km: Vec<f32>,
+    last_elevation: Option<f32>,
+    tilt_count: u32,
+    start_secs: f64,
+    end_secs: f64,
+}
+
+impl VolumeProductAccumulator {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    /// Fold one reflectivity sweep into the volume products.
+    pub(crate) fn add_sweep(&mut self, sweep: &PrecomputedSweep) {
+        let elevation = sweep.mean_elevation;
+        if let Some(last) = self.last_elevation {
+            if elevation <= last + MIN_TILT_STEP_DEG {
+                return;
+            }
+        }
+        let az_count = sweep.azimuth_count as usize;
+        let gate_count = sweep.gate_count as usize;
+        if az_count == 0 || gate_count == 0 || sweep.scale == 0.0 {
+            return;
+        }
+        self.last_elevation = Some(elevation);
+        self.ensure_allocated();
+
+        let tilt = max_dbz_grid(sweep);
+
+        for g in 0..GRID_GATES {
+            let ground_km = (g as f64 + 0.5) * GRID_GATE_KM;
+            let height_km = beam_height_km(ground_km, elevation) as f32;
+            for a in 0..GRID_AZIMUTHS {
+                let idx = a * GRID_GATES + g;
+                let dbz = tilt[idx];
+
+                if !dbz.is_nan() {
+                    let c = &mut self.composite[idx];
+                    *c = if c.is_nan() { dbz } else { c.max(dbz) };
+                    for (tops, &(threshold, _)) in self.echo_tops.iter_mut().zip(ECHO_TOPS.iter()) {
+                        if dbz >= threshold {
+                            tops[idx] = if tops[idx].is_nan() {
+                                height_km
+                            } else {
+                                tops[idx].max(height_km)
+                            };
+                        }
+                    }
+                }
+
+                let prev_dbz = self.prev_dbz[idx];
+                let prev_height = self.prev_height_km[idx];
+                if !prev_height.is_nan() && (!dbz.is_nan() || !prev_dbz.is_nan()) {
+                    let layer = vil_layer(prev_dbz, dbz, (height_km - prev_height) * 1000.0);
+                    let v = &mut self.vil[idx];
+                    *v = if v.is_nan() { layer } else { *v + layer };
+                }
+                self.prev_dbz[idx] = dbz;
+                self.prev_height_km[idx] = height_km;
+            }
+        }
+
+        if self.tilt_count == 0 {
+            self.start_secs = sweep.sweep_start_secs;
+        }
+        self.end_secs = self.end_secs.max(sweep.sweep_end_secs);
+        self.tilt_count += 1;
+    }
+
+    /// Encode the accumulated products, lifting echo tops from radar level
+    /// to MSL by the antenna's `site_altitude_km`. Empty if no tilt was
+    /// added.
+    pub(crate) fn finish(self, site_altitude_km: f64) -> Vec<(&'static str, PrecomputedSweep)> {
+        if self.tilt_count == 0 {
+            return Vec::new();
+        }
+        let mut out = vec![(
+            COMPOSITE_REFLECTIVITY,
+            self.encode(&self.composite, DBZ_SCALE, DBZ_OFFSET),
+        )];
+        for (tops, &(_, name)) in self.echo_tops.iter().zip(ECHO_TOPS.iter()) {
+            let msl: Vec<f32> = tops.iter().map(|&h| h + site_altitude_km as f32).collect();
+            out.push((name, self.encode(&msl, ECHO_TOP_SCALE, HEIGHT_OFFSET)));
+        }
+        out.push((VIL, self.encode(&self.vil, VIL_SCALE, HEIGHT_OFFSET)));
+        out
+    }
+
+    fn ensure_allocated(&mut self) {
+        if !self.composite.is_empty() {
+            return;
+        }
+        let cells = GRID_AZIMUTHS * GRID_GATES;
+        self.composite = vec![f32::NAN; cells];
+        self.echo_tops = std::array::from_fn(|_| vec![f32::NAN; cells]);
+        self.vil = vec![f32::NAN; cells];
+        self.prev_dbz = vec![f32::NAN; cells];
+        self.prev_height_km = vec![f32::NAN; cells];
+    }
+
+    fn encode(&self, values: &[f32], scale: f32, offset: f32) -> PrecomputedSweep {
+        let raw = values
+            .iter()
+            .map(|&v| {
+                if v.is_nan() {
+                    0
+                } else {
+                    (v * scale + offset).round().clamp(2.0, u16::MAX as f32) as u16
+                }
+            })
+            .collect();
+        PrecomputedSweep {
+            azimuth_count: GRID_AZIMUTHS as u32,
+            gate_count: GRID_GATES as u32,
+            first_gate_range_km: 0.0,
+            gate_interval_km: GRID_GATE_KM,
+            max_range_km: GRID_GATES as f64 * GRID_GATE_KM,
+            scale,
+            offset,
+            radial_count: GRID_AZIMUTHS as u32,
+            mean_elevation: 0.0,
+            nyquist_velocity: None,
+            sweep_start_secs: self.start_secs,
+            sweep_end_secs: self.end_secs,
+            azimuths: (0..GRID_AZIMUTHS)
+                .map(|i| (i as f32 + 0.5) * GRID_AZ_DEG)
+                .collect(),
+            radial_times: Vec::new(),
+            gate_values: GateValues::U16(raw),
+        }
+    }
+}
+
+/// Liquid water content of one layer (kg/m²) between two reflectivities
+/// `depth_m` apart. Missing values count as no echo.
+fn vil_layer(lower_dbz: f32, upper_dbz: f32, depth_m: f32) -> f32 {
+    let z = |dbz: f32| {
+        if dbz.is_nan() {
+            0.0
+        } else {
+            10f32.powf(dbz.min(VIL_MAX_DBZ) / 10.0)
+        }
+    };
+    let mean_z = (z(lower_dbz) + z(upper_dbz)) * 0.5;
+    3.44e-6 * mean_z.powf(4.0 / 7.0) * depth_m.max(0.0)
+}
+
+/// Maximum dBZ per output grid cell of one tilt (NaN where the tilt has no
+/// valid gate).
+fn max_dbz_grid(sweep: &PrecomputedSweep) -> Vec<f32> {
+    let values = decode_physical(sweep);
+    let mut grid = vec![f32::NAN; GRID_AZIMUTHS * GRID_GATES];
+    project_tilt(sweep, |cell, gate| {
+        let dbz = values[gate];
+        if !dbz.is_nan() {
+            let c = &mut grid[cell];
+            *c = if c.is_nan() { dbz } else { c.max(dbz) };
+        }
+    });
+    grid
+}
+
+/// Map one sweep's gates onto the output grid, calling `visit(cell, gate)`
+/// for every grid cell a gate covers. `gate` indexes the sweep's gate
+/// values; gates beyond the grid are skipped.
+pub(super) fn project_tilt(sweep: &PrecomputedSweep, mut visit: impl FnMut(usize, usize)) {
+    let az_count = sweep.azimuth_count as usize;
+    let gate_count = sweep.gate_count as usize;
+    if az_count == 0 {
+        return;
+    }
+
+    // Ground-range bin of every source gate (None beyond the grid).
+    let gate_bins: Vec<Option<usize>> = (0..gate_count)
+        .map(|g| {
+            let slant_km = sweep.first_gate_range_km + (g as f64 + 0.5) * sweep.gate_interval_km;
+            let ground_km = ground_range_km(slant_km, sweep.mean_elevation);
+            let bin = (ground_km / GRID_GATE_KM) as usize;
+            (bin < GRID_GATES).then_some(bin)
+        })
+        .collect();
+
+    // Each radial covers half its spacing on either side.
+    let half_width = 180.0 / az_count as f32;
+
+    for (a, &az) in sweep.azimuths.iter().enumerate().take(az_count) {
+        if az < 0.0 {
+            continue;
+        }
+        let first_bin = ((az - half_width) / GRID_AZ_DEG).round() as i32;
+        let last_bin = ((az + half_width) / GRID_AZ_DEG).round() as i32;
+        for (g, bin) in gate_bins.iter().enumerate() {
+            let Some(bin) = *bin else {
+                continue;
+            };
+            for az_bin in first_bin..last_bin.max(first_bin + 1) {
+                let az_bin = az_bin.rem_euclid(GRID_AZIMUTHS as i32) as usize;
+                visit(az_bin * GRID_GATES + bin, a * gate_count + g);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::SweepFixture;
+
+    const AZ_COUNT: usize = 360;
+    const GATE_COUNT: usize = 400;
+    const GATE_KM: f64 = 0.5;
+
+    /// Reflectivity tilt filled with `dbz(range_km)` at every azimuth,
+    /// encoded like the 8-bit REF moment.
+    fn tilt(elevation: f32, dbz: impl Fn(f64) -> Option<f32>) -> PrecomputedSweep {
+        SweepFixture::new(AZ_COUNT, GATE_COUNT, GATE_KM)
+            .elevation(elevation)
+            .u8(|_, r| dbz(r))
+    }
+
+    /// Physical value of a finished product at (azimuth bin, ground km).
+    fn value_at(sweep: &PrecomputedSweep, az_bin: usize, km: usize) -> Option<f32> {
+        let GateValues::U16(raw) = &sweep.gate_values else {
+            panic!("volume products are U16");
+        };
+        let r = raw[az_bin * GRID_GATES + km];
+        (r > 1).then(|| (r as f32 - sweep.offset) / sweep.scale)
+    }
+
+    fn product<'a>(
+        products: &'a [(&'static str, PrecomputedSweep)],
+        name: &str,
+    ) -> &'a PrecomputedSweep {
+        &products.iter().find(|(n, _)| *n == name).unwrap().1
+    }
+
+    #[test]
+    fn composite_is_column_maximum() {
+        let mut acc = VolumeProductAccumulator::new();
+        acc.add_sweep(&tilt(0.5, |_| Some(20.0)));
+        acc.add_sweep(&tilt(1.5, |r| (r > 50.0 && r < 60.0).then_some(45.0)));
+        let products = acc.finish(0.0);
+        let cref = product(&products, COMPOSITE_REFLECTIVITY);
+        assert_eq!(value_at(cref, 100, 30), Some(20.0));
+        assert_eq!(value_at(cref, 100, 55), Some(45.0));
+    }
+
+    #[test]
+    fn echo_tops_follow_beam_height() {
+        let mut acc = VolumeProductAccumulator::new();
+        for elev in [0.5, 1.5, 2.5, 3.5] {
+            // Only the lowest two tilts reach 30 dBZ.
+            let dbz = if elev < 2.0 { 35.0 } else { 20.0 };
+            acc.add_sweep(&tilt(elev, |_| Some(dbz)));
+        }
+        // Antenna 400 m above sea level.
+        let products = acc.finish(0.4);
+        let et18 = value_at(product(&products, "echo_tops_18"), 0, 100).unwrap();
+        let et30 = value_at(product(&products, "echo_tops_30"), 0, 100).unwrap();
+        assert!(value_at(product(&products, "echo_tops_50"), 0, 100).is_none());
+        // 3.5° and 1.5° beams at 100 km ground range sit near 6.7 and 3.2 km
+        // above the radar.
+        assert!((et18 - 7.1).abs() < 0.2, "{et18}");
+        assert!((et30 - 3.6).abs() < 0.2, "{et30}");
+    }
+
+    #[test]
+    fn vil_integrates_between_tilts() {
+        let mut acc = VolumeProductAccumulator::new();
+        acc.add_sweep(&tilt(0.5, |_| Some(50.0)));
+        acc.add_sweep(&tilt(2.5, |_| Some(50.0)));
+        let products = acc.finish(0.0);
+        let vil = value_at(product(&products, VIL), 0, 50).unwrap();
+        // Uniform 50 dBZ over the ~1.75 km between the two beams at 50 km.
+        let expected = 3.44e-6 * 1e5f32.powf(4.0 / 7.0) * 1750.0;
+        assert!(
+            (vil - expected).abs() < expected * 0.1,
+            "{vil} vs {expected}"
+        );
+    }
+
+    #[test]
+    fn repeated_base_tilts_are_skipped() {
+        let mut acc = VolumeProductAccumulator::new();
+        acc.add_sweep(&tilt(0.5, |_| Some(10.0)));
+        acc.add_sweep(&tilt(1.5, |_| Some(10.0)));
+        // SAILS re-scan of the base tilt after a higher one.
+        acc.add_sweep(&tilt(0.5, |_| Some(60.0)));
+        assert_eq!(acc.tilt_count, 2);
+        let products = acc.finish(0.0);
+        assert_eq!(
+            value_at(product(&products, COMPOSITE_REFLECTIVITY), 0, 20),
+            Some(10.0)
+        );
+    }
+
+    #[test]
+    fn no_tilts_produce_nothing() {
+        assert!(VolumeProductAccumulator::new().finish(0.0).is_empty());
+    }
+}
@@ -96,7 +96,7 @@ pub(super) fn label(
 /// `result[i] == true` iff azimuth index `i` is spatially adjacent to
 /// index `(i + 1) % az_count` — i.e. the angular gap between those two
 /// sorted radials is within `MAX_GAP_FACTOR × median_spacing`.
-fn precompute_az_adjacency(azimuths: &[f32], azimuth_count: usize) -> Vec<bool> {
+pub(super) fn precompute_az_adjacency(azimuths: &[f32], azimuth_count: usize) -> Vec<bool> {
     const MAX_GAP_FACTOR: f32 = 2.0;
 
     if azimuth_count <= 1 {
@@ -8,12 +8,9 @@
 
 use super::components::Pixel;
 use super::DetectionInput;
+use crate::geo::KM_PER_DEG;
 use crate::state::StormCellInfo;
 
-/// Approximate kilometers per degree of latitude. Matches the
-/// equirectangular approximation used in `canvas_inspector.rs`.
-const KM_PER_DEG: f64 = 111.0;
-
 pub(super) fn summarize(
     pixels: &[Pixel],
     grid: &[f32],
@@ -133,6 +130,7 @@ pub(super) fn summarize(
         orientation_deg,
         elongation,
         gate_count: pixels.len() as u32,
+        track: None,
     }
 }
 
@@ -2,7 +2,10 @@
 //!
 //! Threshold + connected-component analysis on reflectivity gates in polar
 //! space, followed by per-cell feature extraction (area, centroid, bounds,
-//! bearing/range from radar, major-axis orientation, elongation).
+//! bearing/range from radar, major-axis orientation, elongation). The
+//! `tracking` submodule links cells across consecutive scans to give each
+//! one a persistent ID and motion vector, and `rotation` runs an
+//! azimuthal-shear couplet detector over velocity sweeps.
 //!
 //! Operates directly on the CPU-side shadow of the rendered sweep, so no
 //! decode / marshal work is needed. Keeping the algorithm in-tree lets us
@@ -12,10 +15,16 @@
 
 mod components;
 mod features;
+mod rotation;
+mod tracking;
+
+pub use rotation::{detect_rotation, RotationParams};
+pub use tracking::StormTracker;
 
 use crate::state::StormCellInfo;
 
-/// Borrowed view of the sweep data needed to run detection.
+/// Borrowed view of the sweep data needed to run detection. Used for both
+/// reflectivity (cells) and velocity (rotation) sweeps.
 pub struct DetectionInput<'a> {
     /// Sorted azimuth angles (degrees, 0..360). Negative values mark padded
     /// slots from partial live sweeps and are skipped.
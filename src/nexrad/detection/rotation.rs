@@ -0,0 +1,457 @@
+//! Mesocyclone / rotation detection on velocity sweeps.
+//!
+//! Gate-to-gate azimuthal-shear couplet detector, loosely following the
+//! first stages of the NSSL Mesocyclone Detection Algorithm:
+//!
+//! 1. For each gate (constant range), walk the radials clockwise and find
+//!    runs of increasing radial velocity — inbound on the counter-clockwise
+//!    side, outbound on the clockwise side, which is the signature of a
+//!    cyclonic vortex. Each run that is strong and compact enough becomes a
+//!    *pattern vector*. On aliased velocity a jump of about 2·Vn between
+//!    neighbouring radials is a fold, not shear, and ends the run.
+//! 2. Pattern vectors at neighbouring ranges whose azimuth spans overlap are
+//!    grouped into one feature (union-find).
+//! 3. Each feature is summarised into a `RotationInfo`: rotational velocity
+//!    (half the strongest velocity difference), core diameter, location, and
+//!    beam height at the feature's range.
+//!
+//! Only cyclonic rotation is reported; anticyclonic couplets are rare in
+//! the CONUS and mostly noise at single-tilt resolution.
+
+use super::components::precompute_az_adjacency;
+use super::DetectionInput;
+use crate::geo::KM_PER_DEG;
+use crate::nexrad::beam::{beam_height_km, ground_range_km};
+use crate::state::RotationInfo;
+
+/// Tuning knobs for the rotation detector.
+pub struct RotationParams {
+    /// Minimum velocity difference across a pattern vector (m/s).
+    pub min_delta_v_ms: f32,
+    /// Minimum azimuthal shear across a pattern vector (s⁻¹).
+    pub min_shear_per_s: f32,
+    /// Pattern vectors wider than this are broad shear, not a vortex.
+    pub max_diameter_km: f64,
+    /// Inside this range a pattern vector's arc covers so much azimuth
+    /// that the broad-scale wind alone passes the shear test.
+    pub min_range_km: f64,
+    /// Beyond this range the beam is too wide to resolve mesocyclones.
+    pub max_range_km: f64,
+    /// Pattern vectors spanning more azimuth than this are the background
+    /// wind turning with azimuth, not a vortex.
+    pub max_span_deg: f64,
+    /// Features built from fewer pattern vectors are dropped as noise.
+    pub min_pattern_vectors: usize,
+    /// Velocity drop tolerated inside a run before it is closed. Absorbs
+    /// gate-level noise without splitting a couplet in two.
+    pub run_tolerance_ms: f32,
+    /// On aliased velocity, a step between neighbouring radials larger than
+    /// this fraction of the 2·Vn folding interval is taken as a fold.
+    pub fold_step_fraction: f32,
+}
+
+impl Default for RotationParams {
+    fn default() -> Self {
+        Self {
+            min_delta_v_ms: 20.0,
+            min_shear_per_s: 0.003,
+            max_diameter_km: 10.0,
+            min_range_km: 10.0,
+            max_range_km: 230.0,
+            max_span_deg: 20.0,
+            min_pattern_vectors: 3,
+            run_tolerance_ms: 3.0,
+            fold_step_fraction: 0.5,
+        }
+    }
+}
+
+/// One run of increasing velocity along a single gate.
+struct PatternVector {
+    gate: usize,
+    /// Unwrapped azimuth indices of the run's trough and peak. May exceed
+    /// `azimuth_count` for runs crossing the end of the sorted radials.
+    start_k: usize,
+    end_k: usize,
+    delta_v: f32,
+    diameter_km: f64,
+    /// Radar-local Cartesian midpoint (km, x = east, y = north).
+    x_km: f64,
+    y_km: f64,
+}
+
+/// Run rotation detection over a velocity sweep collected at
+/// `elevation_deg`. `nyquist_ms` is the sweep's Nyquist velocity when the
+/// values are aliased; pass `None` for dealiased velocity.
+pub fn detect_rotation(
+    input: &DetectionInput,
+    elevation_deg: f32,
+    nyquist_ms: Option<f32>,
+    params: &RotationParams,
+) -> Vec<RotationInfo> {
+    let n = input.azimuth_count;
+    if n < 2 || input.gate_count == 0 || input.azimuths.len() < n {
+        return Vec::new();
+    }
+    if input.gate_values.len() < n * input.gate_count {
+        return Vec::new();
+    }
+
+    let max_step = nyquist_ms.map(|vn| 2.0 * vn * params.fold_step_fraction);
+    let vectors = find_pattern_vectors(input, max_step, params);
+    if vectors.is_empty() {
+        return Vec::new();
+    }
+
+    group_vectors(&vectors, n)
+        .into_iter()
+        .filter(|group| group.len() >= params.min_pattern_vectors)
+        .map(|group| summarize(&group, &vectors, input, elevation_deg))
+        .collect()
+}
+
+/// Physical velocity at (azimuth index, gate), or `None` for sentinels and
+/// padded rows.
+fn velocity_at(input: &DetectionInput, az_idx: usize, gate: usize) -> Option<f32> {
+    if input.azimuths[az_idx] < 0.0 {
+        return None;
+    }
+    let raw = input.gate_values[az_idx * input.gate_count + gate];
+    if raw <= 1.0 {
+        return None;
+    }
+    if input.data_scale == 0.0 {
+        Some(raw)
+    } else {
+        Some((raw - input.data_offset) / input.data_scale)
+    }
+}
+
+/// `max_step` is the largest velocity step between neighbouring radials
+/// that still counts as shear, if the sweep may be folded.
+fn find_pattern_vectors(
+    input: &DetectionInput,
+    max_step: Option<f32>,
+    params: &RotationParams,
+) -> Vec<PatternVector> {
+    let n = input.azimuth_count;
+    let adjacent = precompute_az_adjacency(input.azimuths, n);
+    let mut vectors = Vec::new();
+    let mut run: Vec<f32> = Vec::new();
+
+    for gate in 0..input.gate_count {
+        let range_km = input.first_gate_km + (gate as f64 + 0.5) * input.gate_interval_km;
+        if range_km > params.max_range_km {
+            break;
+        }
+        if range_km < params.min_range_km {
+            continue;
+        }
+
+        let mut k = 0;
+        while k < n {
+            let Some(v0) = velocity_at(input, k, gate) else {
+                k += 1;
+                continue;
+            };
+
+            // Extend the run clockwise while velocity keeps rising (within
+            // tolerance), wrapping past the last radial at most once.
+            run.clear();
+            run.push(v0);
+            let mut peak = v0;
+            let mut last = v0;
+            let mut j = k;
+            while j + 1 < k + n && adjacent[j % n] {
+                let Some(v) = velocity_at(input, (j + 1) % n, gate) else {
+                    break;
+                };
+                if v < peak - params.run_tolerance_ms {
+                    break;
+                }
+                if max_step.is_some_and(|step| (v - last).abs() > step) {
+                    break;
+                }
+                if span_deg(input, k, j + 1) > params.max_span_deg
+                    || arc_km(input, k, j + 1, range_km) > params.max_diameter_km
+                {
+                    break;
+                }
+                j += 1;
+                run.push(v);
+                peak = peak.max(v);
+                last = v;
+            }
+
+            // Trough must precede the peak: take the lowest value before
+            // the last occurrence of the maximum.
+            let peak_off = run
+                .iter()
+                .rposition(|&v| v == peak)
+                .expect("peak is in run");
+            let (trough_off, trough) = run[..=peak_off].iter().copied().enumerate().fold(
+                (0, f32::INFINITY),
+                |best, (i, v)| {
+                    if v < best.1 {
+                        (i, v)
+                    } else {
+                        best
+                    }
+                },
+            );
+
+            let delta_v = peak - trough;
+            let start_k = k + trough_off;
+            let end_k = k + peak_off;
+            if delta_v >= params.min_delta_v_ms && end_k > start_k {
+                let diameter_km = arc_km(input, start_k, end_k, range_km);
+                let shear = delta_v as f64 / (diameter_km * 1000.0).max(1.0);
+                if diameter_km > 0.0 && shear >= params.min_shear_per_s as f64 {
+                    let a = input.azimuths[start_k % n] as f64;
+                    let mid_az = (a + mid_angle(a, input.azimuths[end_k % n] as f64))
+                        .rem_euclid(360.0)
+                        .to_radians();
+                    vectors.push(PatternVector {
+                        gate,
+                        start_k,
+                        end_k,
+                        delta_v,
+                        diameter_km,
+                        x_km: range_km * mid_az.sin(),
+                        y_km: range_km * mid_az.cos(),
+                    });
+                }
+            }
+
+            k = (k + peak_off).max(k + 1);
+        }
+    }
+
+    vectors
+}
+
+/// Half the clockwise angle from `a` to `b` (degrees).
+fn mid_angle(a: f64, b: f64) -> f64 {
+    (b - a).rem_euclid(360.0) * 0.5
+}
+
+/// Clockwise azimuth span (degrees) between unwrapped azimuth indices.
+fn span_deg(input: &DetectionInput, from_k: usize, to_k: usize) -> f64 {
+    let n = input.azimuth_count;
+    let a = input.azimuths[from_k % n] as f64;
+    let b = input.azimuths[to_k % n] as f64;
+    (b - a).rem_euclid(360.0)
+}
+
+/// Arc length (km) at `range_km` between unwrapped azimuth indices.
+fn arc_km(input: &DetectionInput, from_k: usize, to_k: usize, range_km: f64) -> f64 {
+    span_deg(input, from_k, to_k).to_radians() * range_km
+}
+
+/// Group pattern vectors whose gates are within two of each other and whose
+/// azimuth spans overlap. Returns index lists into `vectors`.
+fn group_vectors(vectors: &[PatternVector], n: usize) -> Vec<Vec<usize>> {
+    let mut parent: Vec<usize> = (0..vectors.len()).collect();
+    fn find(parent: &mut [usize], mut i: usize) -> usize {
+        while parent[i] != i {
+            parent[i] = parent[parent[i]];
+            i = parent[i];
+        }
+        i
+    }
+
+    // Vectors are emitted in gate order, so only a short window behind each
+    // one needs checking.
+    for i in 0..vectors.len() {
+        for j in (0..i).rev() {
+            if vectors[i].gate - vectors[j].gate > 2 {
+                break;
+            }
+            if spans_overlap(&vectors[i], &vectors[j], n) {
+                let (ri, rj) = (find(&mut parent, i), find(&mut parent, j));
+                if ri != rj {
+                    parent[ri] = rj;
+                }
+            }
+        }
+    }
+
+    let mut groups: std::collections::HashMap<usize, Vec<usize>> = Default::default();
+    for i in 0..vectors.len() {
+        let root = find(&mut parent, i);
+        groups.entry(root).or_default().push(i);
+    }
+    groups.into_values().collect()
+}
+
+fn spans_overlap(a: &PatternVector, b: &PatternVector, n: usize) -> bool {
+    [0isize, n as isize, -(n as isize)].iter().any(|&shift| {
+        let (bs, be) = (b.start_k as isize + shift, b.end_k as isize + shift);
+        (a.start_k as isize) <= be && bs <= a.end_k as isize
+    })
+}
+
+fn summarize(
+    group: &[usize],
+    vectors: &[PatternVector],
+    input: &DetectionInput,
+    elevation_deg: f32,
+) -> RotationInfo {
+    // Location: ΔV-weighted mean of the pattern-vector midpoints.
+    let (mut sum_w, mut sum_x, mut sum_y) = (0.0_f64, 0.0_f64, 0.0_f64);
+    let mut strongest = &vectors[group[0]];
+    let mut max_shear = 0.0_f64;
+    for &i in group {
+        let v = &vectors[i];
+        let w = v.delta_v as f64;
+        sum_w += w;
+        sum_x += w * v.x_km;
+        sum_y += w * v.y_km;
+        if v.delta_v > strongest.delta_v {
+            strongest = v;
+        }
+        max_shear = max_shear.max(v.delta_v as f64 / (v.diameter_km * 1000.0));
+    }
+    let x_km = sum_x / sum_w;
+    let y_km = sum_y / sum_w;
+    let range_km = (x_km * x_km + y_km * y_km).sqrt();
+    let cos_lat = input.radar_lat.to_radians().cos().max(1e-6);
+
+    RotationInfo {
+        lat: input.radar_lat + y_km / KM_PER_DEG,
+        lon: input.radar_lon + x_km / (KM_PER_DEG * cos_lat),
+        bearing_from_radar_deg: ((x_km.atan2(y_km).to_degrees() + 360.0) % 360.0) as f32,
+        range_from_radar_km: range_km as f32,
+        rotational_velocity_ms: strongest.delta_v * 0.5,
+        delta_v_ms: strongest.delta_v,
+        diameter_km: strongest.diameter_km as f32,
+        height_km: beam_height_km(ground_range_km(range_km, elevation_deg), elevation_deg) as f32,
+        max_shear_per_s: max_shear as f32,
+        pattern_vector_count: group.len() as u32,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const AZ_COUNT: usize = 360;
+    const GATE_COUNT: usize = 200;
+    const GATE_KM: f64 = 0.5;
+    const NYQUIST: f64 = 26.0;
+
+    /// Rankine vortex centred at `az_deg`, `range_km`: (az_deg, range_km,
+    /// peak tangential wind m/s, core radius km). Negative wind spins
+    /// anticyclonically.
+    type Vortex = (f64, f64, f64, f64);
+
+    /// Sweep of a northward wind whose speed depends on range, plus an
+    /// optional vortex, folded into ±`nyquist` if given. Values are offset
+    /// so none hit the sentinels.
+    fn sweep(
+        wind_ms: impl Fn(f64) -> f64,
+        vortex: Option<Vortex>,
+        nyquist: Option<f64>,
+    ) -> (Vec<f32>, Vec<f32>) {
+        let azimuths: Vec<f32> = (0..AZ_COUNT).map(|i| i as f32 + 0.5).collect();
+        let mut values = vec![0.0_f32; AZ_COUNT * GATE_COUNT];
+        for (a, &az) in azimuths.iter().enumerate() {
+            let az_rad = (az as f64).to_radians();
+            for g in 0..GATE_COUNT {
+                let r = (g as f64 + 0.5) * GATE_KM;
+                let (x, y) = (r * az_rad.sin(), r * az_rad.cos());
+                let mut v = wind_ms(r) * az_rad.cos();
+                if let Some((vaz, vr, vmax, radius)) = vortex {
+                    let vaz = vaz.to_radians();
+                    let (cx, cy) = (vr * vaz.sin(), vr * vaz.cos());
+                    let (dx, dy) = (x - cx, y - cy);
+                    let d = (dx * dx + dy * dy).sqrt();
+                    let vt = if d < radius {
+                        vmax * d / radius
+                    } else {
+                        vmax * radius / d
+                    };
+                    // Counter-clockwise tangential wind, projected onto
+                    // the radial direction.
+                    if d > 1e-6 {
+                        let (ux, uy) = (-dy / d * vt, dx / d * vt);
+                        v += (ux * x + uy * y) / r;
+                    }
+                }
+                if let Some(vn) = nyquist {
+                    v = (v + vn).rem_euclid(2.0 * vn) - vn;
+                }
+                values[a * GATE_COUNT + g] = (v + 100.0) as f32;
+            }
+        }
+        (azimuths, values)
+    }
+
+    fn run(azimuths: &[f32], values: &[f32], nyquist: Option<f64>) -> Vec<RotationInfo> {
+        let input = DetectionInput {
+            azimuths,
+            gate_values: values,
+            azimuth_count: AZ_COUNT,
+            gate_count: GATE_COUNT,
+            first_gate_km: 0.0,
+            gate_interval_km: GATE_KM,
+            data_scale: 1.0,
+            data_offset: 100.0,
+            radar_lat: 35.0,
+            radar_lon: -97.0,
+        };
+        detect_rotation(
+            &input,
+            0.5,
+            nyquist.map(|vn| vn as f32),
+            &RotationParams::default(),
+        )
+    }
+
+    /// Wind strengthening from 20 m/s at the radar to 46 m/s at 100 km,
+    /// enough to fold beyond ~23 km at a 26 m/s Nyquist velocity.
+    fn strengthening(r: f64) -> f64 {
+        20.0 + 26.0 * r / 100.0
+    }
+
+    #[test]
+    fn finds_cyclonic_vortex() {
+        let (az, vals) = sweep(|_| 0.0, Some((90.0, 60.0, 25.0, 1.5)), None);
+        let found = run(&az, &vals, None);
+        assert_eq!(found.len(), 1);
+        let meso = &found[0];
+        assert!((meso.bearing_from_radar_deg - 90.0).abs() < 3.0);
+        assert!((meso.range_from_radar_km - 60.0).abs() < 3.0);
+        assert!(meso.rotational_velocity_ms > 15.0);
+        assert!(meso.diameter_km < 5.0);
+        assert!(meso.height_km > 0.5 && meso.height_km < 1.0);
+    }
+
+    #[test]
+    fn finds_vortex_in_folded_flow() {
+        // Crosswind at 90° leaves the vortex itself unfolded.
+        let vortex = Some((90.0, 60.0, 25.0, 1.5));
+        let (az, vals) = sweep(strengthening, vortex, Some(NYQUIST));
+        let found = run(&az, &vals, Some(NYQUIST));
+        assert_eq!(found.len(), 1);
+        assert!((found[0].bearing_from_radar_deg - 90.0).abs() < 3.0);
+    }
+
+    #[test]
+    fn ignores_uniform_flow() {
+        let (az, vals) = sweep(|_| 20.0, None, None);
+        assert!(run(&az, &vals, None).is_empty());
+    }
+
+    #[test]
+    fn ignores_velocity_folds() {
+        let (az, vals) = sweep(strengthening, None, Some(NYQUIST));
+        assert!(run(&az, &vals, Some(NYQUIST)).is_empty());
+    }
+
+    #[test]
+    fn ignores_anticyclones() {
+        let (az, vals) = sweep(|_| 20.0, Some((90.0, 60.0, -25.0, 1.5)), None);
+        assert!(run(&az, &vals, None).is_empty());
+    }
+}
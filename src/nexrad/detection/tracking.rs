@@ -0,0 +1,358 @@
+//! Cross-scan storm cell tracking.
+//!
+//! Detection runs on one sweep at a time; this module stitches those
+//! per-sweep detections into tracks. Each (site, elevation, product) series
+//! keeps a bounded set of detection frames keyed by sweep start time, and
+//! track identities are re-derived by walking the frames chronologically on
+//! every update. That makes IDs a pure function of the cached frames, so a
+//! playback loop that revisits the same scans sees the same IDs every pass
+//! regardless of the order the frames were detected in.
+//!
+//! Matching between consecutive frames is greedy nearest-neighbor against
+//! each track's motion-extrapolated position (SCIT-style). Motion is a
+//! least-squares fit over the last few observations of the track.
+
+use std::collections::{BTreeMap, HashMap};
+
+use crate::geo::KM_PER_DEG;
+use crate::state::{CellMotion, CellTrack, StormCellInfo};
+
+/// Frames further apart than this start fresh tracks rather than matching
+/// (data gaps, site outages, scrubbing across unrelated events).
+const MAX_FRAME_GAP_SECS: f64 = 20.0 * 60.0;
+
+/// Search radius around a predicted position at zero lead time.
+const MATCH_RADIUS_BASE_KM: f64 = 6.0;
+
+/// Additional search radius per minute between frames. ~1.2 km/min covers
+/// storms up to ~70 km/h when no motion estimate exists yet.
+const MATCH_RADIUS_KM_PER_MIN: f64 = 1.2;
+
+/// Number of most recent observations used to fit a track's motion.
+const MOTION_FIT_POINTS: usize = 4;
+
+/// Observations must span at least this long before motion is reported,
+/// otherwise the fit is dominated by centroid jitter.
+const MIN_MOTION_SPAN_SECS: f64 = 60.0;
+
+/// Past positions retained per track for drawing.
+const MAX_HISTORY_POINTS: usize = 12;
+
+/// Frames retained per series. ~3 hours of 5-minute volume scans.
+const MAX_FRAMES_PER_SERIES: usize = 36;
+
+/// Identifies one tracked series: site, elevation number, and product code.
+pub type TrackSeriesKey = (String, u8, &'static str);
+
+/// One detection pass over one sweep.
+struct Frame {
+    time_secs: f64,
+    cells: Vec<StormCellInfo>,
+}
+
+/// A track being followed while walking frames chronologically.
+struct ActiveTrack {
+    id: u32,
+    /// (time_secs, lat, lon) observations, oldest first.
+    observations: Vec<(f64, f64, f64)>,
+}
+
+impl ActiveTrack {
+    fn last(&self) -> (f64, f64, f64) {
+        *self
+            .observations
+            .last()
+            .expect("active track always has an observation")
+    }
+
+    /// Extrapolated (lat, lon) at `time_secs`, using the fitted motion when
+    /// one is available and the last observed position otherwise.
+    fn predict(&self, time_secs: f64) -> (f64, f64) {
+        let (t, lat, lon) = self.last();
+        match fit_motion(&self.observations) {
+            Some(motion) => motion.extrapolate(lat, lon, (time_secs - t) / 60.0),
+            None => (lat, lon),
+        }
+    }
+}
+
+/// Cross-scan storm cell tracker. Owned by `VizState`.
+#[derive(Default)]
+pub struct StormTracker {
+    series: HashMap<TrackSeriesKey, BTreeMap<i64, Frame>>,
+}
+
+impl StormTracker {
+    /// Record the cells detected in the sweep starting at `sweep_start_secs`
+    /// and return them annotated with track IDs, motion, and past positions.
+    ///
+    /// Re-detecting the same sweep (threshold change, live chunk arrival)
+    /// replaces its frame instead of appending a duplicate.
+    pub fn update(
+        &mut self,
+        key: TrackSeriesKey,
+        sweep_start_secs: f64,
+        cells: Vec<StormCellInfo>,
+    ) -> Vec<StormCellInfo> {
+        let frames = self.series.entry(key).or_default();
+        let frame_key = sweep_start_secs.round() as i64;
+        frames.insert(
+            frame_key,
+            Frame {
+                time_secs: sweep_start_secs,
+                cells,
+            },
+        );
+
+        // Bound memory by dropping whichever frame is furthest in time from
+        // the one just inserted, so scrubbing back to an old event does not
+        // evict the frames the user is looking at.
+        while frames.len() > MAX_FRAMES_PER_SERIES {
+            let (&oldest, _) = frames.first_key_value().expect("non-empty");
+            let (&newest, _) = frames.last_key_value().expect("non-empty");
+            let drop = if frame_key - oldest >= newest - frame_key {
+                oldest
+            } else {
+                newest
+            };
+            frames.remove(&drop);
+        }
+
+        annotate_frame(frames, frame_key)
+    }
+
+    /// Drop all tracking history (site change, cache wipe).
+    pub fn clear(&mut self) {
+        self.series.clear();
+    }
+}
+
+/// Walk `frames` chronologically up to and including `target`, assigning
+/// track IDs, and return the target frame's cells with their tracks filled in.
+fn annotate_frame(frames: &BTreeMap<i64, Frame>, target: i64) -> Vec<StormCellInfo> {
+    let mut active: Vec<ActiveTrack> = Vec::new();
+    let mut next_id: u32 = 1;
+    let mut prev_time: Option<f64> = None;
+
+    for (&frame_key, frame) in frames.range(..=target) {
+        if prev_time.is_some_and(|t| frame.time_secs - t > MAX_FRAME_GAP_SECS) {
+            active.clear();
+        }
+
+        let assignments = match_cells(&active, frame);
+
+        let mut next_active: Vec<ActiveTrack> = Vec::with_capacity(frame.cells.len());
+        let mut output: Vec<StormCellInfo> = Vec::new();
+        for (cell_idx, cell) in frame.cells.iter().enumerate() {
+            let mut track = match assignments[cell_idx] {
+                Some(track_idx) => ActiveTrack {
+                    id: active[track_idx].id,
+                    observations: std::mem::take(&mut active[track_idx].observations),
+                },
+                None => {
+                    let id = next_id;
+                    next_id += 1;
+                    ActiveTrack {
+                        id,
+                        observations: Vec::new(),
+                    }
+                }
+            };
+
+            if frame_key == target {
+                let mut annotated = cell.clone();
+                annotated.track = Some(CellTrack {
+                    id: track.id,
+                    motion: {
+                        let mut obs = track.observations.clone();
+                        obs.push((frame.time_secs, cell.lat, cell.lon));
+                        fit_motion(&obs)
+                    },
+                    past_positions: track
+                        .observations
+                        .iter()
+                        .map(|&(_, lat, lon)| (lat, lon))
+                        .collect(),
+                });
+                output.push(annotated);
+            }
+
+            track
+                .observations
+                .push((frame.time_secs, cell.lat, cell.lon));
+            if track.observations.len() > MAX_HISTORY_POINTS {
+                track.observations.remove(0);
+            }
+            next_active.push(track);
+        }
+
+        if frame_key == target {
+            return output;
+        }
+
+        active = next_active;
+        prev_time = Some(frame.time_secs);
+    }
+
+    Vec::new()
+}
+
+/// Greedy nearest-neighbor assignment of `frame` cells to `active` tracks.
+/// Returns, per cell, the index into `active` it continues (if any).
+fn match_cells(active: &[ActiveTrack], frame: &Frame) -> Vec<Option<usize>> {
+    let mut assignments = vec![None; frame.cells.len()];
+    if active.is_empty() {
+        return assignments;
+    }
+
+    let mut candidates: Vec<(f64, usize, usize)> = Vec::new();
+    for (track_idx, track) in active.iter().enumerate() {
+        let (last_t, _, _) = track.last();
+        let dt_min = ((frame.time_secs - last_t) / 60.0).max(0.0);
+        let radius_km = MATCH_RADIUS_BASE_KM + MATCH_RADIUS_KM_PER_MIN * dt_min;
+        let (pred_lat, pred_lon) = track.predict(frame.time_secs);
+        for (cell_idx, cell) in frame.cells.iter().enumerate() {
+            let d = distance_km(pred_lat, pred_lon, cell.lat, cell.lon);
+            if d <= radius_km {
+                candidates.push((d, track_idx, cell_idx));
+            }
+        }
+    }
+    candidates.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(std::cmp::Ordering::Equal));
+
+    let mut track_used = vec![false; active.len()];
+    for (_, track_idx, cell_idx) in candidates {
+        if track_used[track_idx] || assignments[cell_idx].is_some() {
+            continue;
+        }
+        track_used[track_idx] = true;
+        assignments[cell_idx] = Some(track_idx);
+    }
+    assignments
+}
+
+/// Least-squares velocity over the last [`MOTION_FIT_POINTS`] observations.
+fn fit_motion(observations: &[(f64, f64, f64)]) -> Option<CellMotion> {
+    let recent = &observations[observations.len().saturating_sub(MOTION_FIT_POINTS)..];
+    if recent.len() < 2 {
+        return None;
+    }
+    let (t0, lat0, lon0) = recent[0];
+    let span = recent[recent.len() - 1].0 - t0;
+    if span < MIN_MOTION_SPAN_SECS {
+        return None;
+    }
+
+    // Regress east/north offsets (km) from the first observation against
+    // elapsed time (hours).
+    let n = recent.len() as f64;
+    let cos_lat = lat0.to_radians().cos().max(1e-6);
+    let (mut st, mut sx, mut sy, mut stt, mut stx, mut sty) = (0.0, 0.0, 0.0, 0.0, 0.0, 0.0);
+    for &(t, lat, lon) in recent {
+        let th = (t - t0) / 3600.0;
+        let x = (lon - lon0) * KM_PER_DEG * cos_lat;
+        let y = (lat - lat0) * KM_PER_DEG;
+        st += th;
+        sx += x;
+        sy += y;
+        stt += th * th;
+        stx += th * x;
+        sty += th * y;
+    }
+    let denom = n * stt - st * st;
+    if denom.abs() < 1e-12 {
+        return None;
+    }
+    let east_kmh = (n * stx - st * sx) / denom;
+    let north_kmh = (n * sty - st * sy) / denom;
+    Some(CellMotion::from_components(
+        east_kmh as f32,
+        north_kmh as f32,
+    ))
+}
+
+/// Equirectangular distance in km, adequate at storm-cell scales.
+fn distance_km(lat1: f64, lon1: f64, lat2: f64, lon2: f64) -> f64 {
+    let cos_lat = ((lat1 + lat2) * 0.5).to_radians().cos();
+    let dx = (lon2 - lon1) * KM_PER_DEG * cos_lat;
+    let dy = (lat2 - lat1) * KM_PER_DEG;
+    (dx * dx + dy * dy).sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cell(lat: f64, lon: f64) -> StormCellInfo {
+        StormCellInfo {
+            lat,
+            lon,
+            max_dbz: 50.0,
+            mean_dbz: 40.0,
+            area_km2: 50.0,
+            bounds: (lat, lon, lat, lon),
+            bearing_from_radar_deg: 0.0,
+            range_from_radar_km: 0.0,
+            orientation_deg: 0.0,
+            elongation: 1.0,
+            gate_count: 100,
+            track: None,
+        }
+    }
+
+    fn key() -> TrackSeriesKey {
+        ("KTLX".to_string(), 1, "REF")
+    }
+
+    #[test]
+    fn ids_persist_across_scans() {
+        let mut tracker = StormTracker::default();
+        // Two cells ~100 km apart, each moving ~4 km east per 5 minutes.
+        let dlon = 4.0 / (KM_PER_DEG * 35f64.to_radians().cos());
+        let a = tracker.update(key(), 0.0, vec![cell(35.0, -97.0), cell(36.0, -97.0)]);
+        let b = tracker.update(
+            key(),
+            300.0,
+            vec![cell(36.0, -97.0 + dlon), cell(35.0, -97.0 + dlon)],
+        );
+        let id = |c: &StormCellInfo| c.track.as_ref().unwrap().id;
+        assert_eq!(id(&a[0]), id(&b[1]));
+        assert_eq!(id(&a[1]), id(&b[0]));
+        assert_eq!(b[1].track.as_ref().unwrap().past_positions.len(), 1);
+    }
+
+    #[test]
+    fn motion_points_downstream() {
+        let mut tracker = StormTracker::default();
+        let dlat = 5.0 / KM_PER_DEG; // 5 km north per 5 minutes = 60 km/h
+        let mut last = Vec::new();
+        for i in 0..3 {
+            last = tracker.update(
+                key(),
+                i as f64 * 300.0,
+                vec![cell(35.0 + dlat * i as f64, -97.0)],
+            );
+        }
+        let motion = last[0].track.as_ref().unwrap().motion.unwrap();
+        assert!((motion.speed_kmh - 60.0).abs() < 1.0);
+        assert!(motion.heading_deg < 1.0 || motion.heading_deg > 359.0);
+    }
+
+    #[test]
+    fn redetecting_a_frame_replaces_it() {
+        let mut tracker = StormTracker::default();
+        tracker.update(key(), 0.0, vec![cell(35.0, -97.0)]);
+        let again = tracker.update(key(), 0.0, vec![cell(35.0, -97.0)]);
+        let track = again[0].track.as_ref().unwrap();
+        assert_eq!(track.id, 1);
+        assert!(track.past_positions.is_empty());
+    }
+
+    #[test]
+    fn large_gap_starts_new_tracks() {
+        let mut tracker = StormTracker::default();
+        tracker.update(key(), 0.0, vec![cell(35.0, -97.0)]);
+        let later = tracker.update(key(), 3600.0, vec![cell(35.0, -97.0)]);
+        assert_eq!(later[0].track.as_ref().unwrap().id, 2);
+    }
+}
@@ -1,11 +1,14 @@
-//! AWS download pipeline for NEXRAD archive data.
+//! Download pipeline for NEXRAD archive data.
 //!
+//! Fetches go through [`super::data_source`], which defaults to the AWS bucket.
 //! Uses channel-based communication to bridge async downloads
 //! with egui's synchronous update loop.
 
 use super::archive_index::{current_timestamp_secs, ArchiveFileMeta, ArchiveListing};
+use super::data_source;
 use super::types::{CachedScan, DownloadResult};
 use crate::data::{DataFacade, ScanCompleteness, ScanKey};
+use crate::platform::sleep_ms;
 use chrono::NaiveDate;
 use eframe::egui;
 use std::cell::RefCell;
@@ -147,7 +150,7 @@ impl DownloadChannel {
         let pending = self.pending_downloads.clone();
         let stats = self.stats.clone();
 
-        wasm_bindgen_futures::spawn_local(async move {
+        crate::platform::spawn_local(async move {
             let result =
                 download_specific_file(&site_id, date, &file_name, timestamp, facade, stats).await;
 
@@ -190,7 +193,7 @@ impl DownloadChannel {
         // Track request start
         stats.request_started();
 
-        wasm_bindgen_futures::spawn_local(async move {
+        crate::platform::spawn_local(async move {
             let result = fetch_archive_listing(&site_id, date).await;
 
             // Remove from pending set
@@ -225,12 +228,10 @@ impl DownloadChannel {
 
 /// Fetches the archive listing for a site/date.
 async fn fetch_archive_listing(site_id: &str, date: NaiveDate) -> ListingResult {
-    use nexrad::data::aws::archive;
-
     log::debug!("Fetching archive listing for {}/{}", site_id, date);
 
     let files = match with_timeout(
-        archive::list_files(site_id, &date),
+        data_source::list_archive_files(site_id, &date),
         REQUEST_TIMEOUT_MS,
         "Archive listing",
     )
@@ -278,10 +279,10 @@ async fn fetch_archive_listing(site_id: &str, date: NaiveDate) -> ListingResult
 }
 
 /// Timeout duration for individual network requests (listing + download).
-const REQUEST_TIMEOUT_MS: u32 = 30_000; // 30 seconds
+pub(super) const REQUEST_TIMEOUT_MS: u32 = 30_000; // 30 seconds
 
 /// Run a future with a timeout. Returns `Err(msg)` if the timeout fires first.
-async fn with_timeout<T>(
+pub(super) async fn with_timeout<T>(
     future: impl std::future::Future<Output = T>,
     timeout_ms: u32,
     label: &str,
@@ -302,23 +303,6 @@ async fn with_timeout<T>(
     }
 }
 
-async fn sleep_ms(ms: u32) {
-    use wasm_bindgen::prelude::*;
-
-    #[wasm_bindgen]
-    extern "C" {
-        #[wasm_bindgen(js_name = setTimeout)]
-        fn set_timeout(closure: &Closure<dyn FnMut()>, millis: u32) -> i32;
-    }
-
-    let (tx, rx) = futures_channel::oneshot::channel::<()>();
-    let closure = Closure::once(move || {
-        let _ = tx.send(());
-    });
-    set_timeout(&closure, ms);
-    let _ = rx.await;
-}
-
 /// Downloads a specific file from the archive.
 async fn download_specific_file(
     site_id: &str,
@@ -328,8 +312,6 @@ async fn download_specific_file(
     facade: DataFacade,
     stats: NetworkStats,
 ) -> DownloadResult {
-    use nexrad::data::aws::archive;
-
     // Check cache first (no network call).
     let scan_key = ScanKey::from_secs(site_id, timestamp);
     if let Ok(Some(entry)) = facade.scan_availability(&scan_key).await {
@@ -345,7 +327,7 @@ async fn download_specific_file(
     // Request 1: List files to find the one we want
     stats.request_started();
     let files = match with_timeout(
-        archive::list_files(site_id, &date),
+        data_source::list_archive_files(site_id, &date),
         REQUEST_TIMEOUT_MS,
         "Archive listing",
     )
@@ -386,7 +368,7 @@ async fn download_specific_file(
     stats.request_started();
     let fetch_start = web_time::Instant::now();
     let file = match with_timeout(
-        archive::download_file(file_meta),
+        data_source::download_archive_file(file_meta),
         REQUEST_TIMEOUT_MS,
         "File download",
     )
@@ -0,0 +1,382 @@
+//! Motion-compensated frames between two sweeps of the same tilt.
+//!
+//! Both sweeps are resampled onto a coarse Cartesian grid and matched block
+//! by block: each block of the earlier sweep gets the displacement, within a
+//! search window, whose neighbourhood in the later sweep looks most alike.
+//! Blocks without enough echo borrow their neighbours' vectors and the field
+//! is smoothed once. An intermediate frame at fraction `t` samples the
+//! earlier sweep `t` of the way back along the flow and the later one the
+//! rest of the way forward, then cross-fades the two.
+//!
+//! Frames are built on the earlier sweep's polar grid and encoded with its
+//! scale and offset, so they upload like any decoded sweep.
+
+use super::polar::AzimuthIndex;
+
+/// Edge of a flow-grid cell, km.
+const FLOW_CELL_KM: f64 = 2.0;
+/// Edge of a matched block, cells.
+const BLOCK_CELLS: usize = 8;
+/// Largest displacement tried in each direction, cells (±12 km).
+const SEARCH_CELLS: isize = 6;
+/// Flow is only estimated this far from the radar, km.
+const MAX_FLOW_RANGE_KM: f64 = 240.0;
+/// Share of a block's cells that must hold data for it to get a vector.
+const MIN_ECHO_FRACTION: f32 = 0.25;
+
+/// A decoded sweep: raw gate values (0 = below threshold, 1 = range
+/// folded) on its polar grid.
+pub(crate) struct RawSweep<'a> {
+    /// Radial azimuths in degrees; negative entries are empty slots.
+    pub azimuths: &'a [f32],
+    pub gate_values: &'a [f32],
+    pub gate_count: usize,
+    pub first_gate_km: f64,
+    pub gate_interval_km: f64,
+    pub offset: f32,
+    pub scale: f32,
+}
+
+impl RawSweep<'_> {
+    fn azimuth_index(&self) -> AzimuthIndex {
+        AzimuthIndex::new(self.azimuths, self.azimuths.len())
+    }
+
+    fn max_range_km(&self) -> f64 {
+        self.first_gate_km + self.gate_count as f64 * self.gate_interval_km
+    }
+
+    /// Physical value at `(x, y)` km east and north of the radar, NaN where
+    /// there is no data.
+    fn value_at(&self, index: &AzimuthIndex, x_km: f64, y_km: f64) -> f32 {
+        let range_km = x_km.hypot(y_km);
+        let gate = (range_km - self.first_gate_km) / self.gate_interval_km;
+        if !(0.0..self.gate_count as f64).contains(&gate) {
+            return f32::NAN;
+        }
+        let azimuth = x_km.atan2(y_km).to_degrees() as f32;
+        let raw = index
+            .radial(azimuth)
+            .and_then(|radial| {
+                self.gate_values
+                    .get(radial * self.gate_count + gate as usize)
+            })
+            .copied()
+            .unwrap_or(0.0);
+        if raw < 2.0 {
+            f32::NAN
+        } else {
+            (raw - self.offset) / self.scale
+        }
+    }
+
+    /// Resample onto a square grid of `cells`×`cells` cells centred on the
+    /// radar, row-major from the south-west corner.
+    fn resample(&self, cells: usize) -> Vec<f32> {
+        let index = self.azimuth_index();
+        let half = cells as f64 * FLOW_CELL_KM / 2.0;
+        let mut values = Vec::with_capacity(cells * cells);
+        for row in 0..cells {
+            let y = (row as f64 + 0.5) * FLOW_CELL_KM - half;
+            for col in 0..cells {
+                let x = (col as f64 + 0.5) * FLOW_CELL_KM - half;
+                values.push(self.value_at(&index, x, y));
+            }
+        }
+        values
+    }
+}
+
+/// Displacement from the earlier sweep to the later one at block centres.
+pub(crate) struct FlowField {
+    /// Blocks per side.
+    blocks: usize,
+    /// Distance from the radar to the grid's west and south edges, km.
+    half_km: f64,
+    /// Eastward and northward displacement per block, km.
+    u: Vec<f32>,
+    v: Vec<f32>,
+}
+
+impl FlowField {
+    /// Displacement at `(x, y)` km from the radar, bilinear between block
+    /// centres and held constant past the outer ones.
+    pub(crate) fn at(&self, x_km: f64, y_km: f64) -> (f32, f32) {
+        if self.blocks == 0 {
+            return (0.0, 0.0);
+        }
+        let block_km = FLOW_CELL_KM * BLOCK_CELLS as f64;
+        let max = (self.blocks - 1) as f64;
+        let fx = ((x_km + self.half_km) / block_km - 0.5).clamp(0.0, max);
+        let fy = ((y_km + self.half_km) / block_km - 0.5).clamp(0.0, max);
+        let (c0, r0) = (fx as usize, fy as usize);
+        let (c1, r1) = ((c0 + 1).min(self.blocks - 1), (r0 + 1).min(self.blocks - 1));
+        let (tx, ty) = ((fx - c0 as f64) as f32, (fy - r0 as f64) as f32);
+        let lerp = |field: &[f32]| {
+            let top = field[r0 * self.blocks + c0] * (1.0 - tx) + field[r0 * self.blocks + c1] * tx;
+            let bottom =
+                field[r1 * self.blocks + c0] * (1.0 - tx) + field[r1 * self.blocks + c1] * tx;
+            top * (1.0 - ty) + bottom * ty
+        };
+        (lerp(&self.u), lerp(&self.v))
+    }
+}
+
+/// Estimate the motion from sweep `a` to sweep `b` by block matching.
+pub(crate) fn estimate_flow(a: &RawSweep, b: &RawSweep) -> FlowField {
+    let range_km = a
+        .max_range_km()
+        .min(b.max_range_km())
+        .min(MAX_FLOW_RANGE_KM);
+    let blocks = (2.0 * range_km / (FLOW_CELL_KM * BLOCK_CELLS as f64)).ceil() as usize;
+    let cells = blocks * BLOCK_CELLS;
+    let half_km = cells as f64 * FLOW_CELL_KM / 2.0;
+    let grid_a = a.resample(cells);
+    let grid_b = b.resample(cells);
+
+    // Echo appearing or vanishing costs more than any change in value, so
+    // blocks match on the shape of the echo first.
+    let (lo, hi) = grid_a
+        .iter()
+        .chain(&grid_b)
+        .filter(|v| !v.is_nan())
+        .fold((f32::INFINITY, f32::NEG_INFINITY), |(lo, hi), &v| {
+            (lo.min(v), hi.max(v))
+        });
+    let mismatch = if hi > lo { 2.0 * (hi - lo) } else { 1.0 };
+
+    let min_echo = (MIN_ECHO_FRACTION * (BLOCK_CELLS * BLOCK_CELLS) as f32) as usize;
+    let mut vectors: Vec<Option<(f32, f32)>> = Vec::with_capacity(blocks * blocks);
+    for block_row in 0..blocks {
+        for block_col in 0..blocks {
+            let cells_in_block = || {
+                (0..BLOCK_CELLS).flat_map(move |r| {
+                    (0..BLOCK_CELLS)
+                        .map(move |c| (block_row * BLOCK_CELLS + r, block_col * BLOCK_CELLS + c))
+                })
+            };
+            let echo = cells_in_block()
+                .filter(|&(r, c)| !grid_a[r * cells + c].is_nan())
+                .count();
+            if echo < min_echo {
+                vectors.push(None);
+                continue;
+            }
+            let mut best = (f32::INFINITY, 0isize, 0isize);
+            for dy in -SEARCH_CELLS..=SEARCH_CELLS {
+                for dx in -SEARCH_CELLS..=SEARCH_CELLS {
+                    let mut sum = 0.0f32;
+                    let mut counted = 0u32;
+                    for (r, c) in cells_in_block() {
+                        let va = grid_a[r * cells + c];
+                        let (rb, cb) = (r as isize + dy, c as isize + dx);
+                        let vb = if (0..cells as isize).contains(&rb)
+                            && (0..cells as isize).contains(&cb)
+                        {
+                            grid_b[rb as usize * cells + cb as usize]
+                        } else {
+                            f32::NAN
+                        };
+                        match (va.is_nan(), vb.is_nan()) {
+                            (true, true) => continue,
+                            (false, false) => sum += (va - vb).abs(),
+                            _ => sum += mismatch,
+                        }
+                        counted += 1;
+                    }
+                    // Prefer the shorter of equally good displacements.
+                    let cost = sum / counted.max(1) as f32 + 1e-3 * (dx * dx + dy * dy) as f32;
+                    if cost < best.0 {
+                        best = (cost, dx, dy);
+                    }
+                }
+            }
+            vectors.push(Some((
+                best.1 as f32 * FLOW_CELL_KM as f32,
+                best.2 as f32 * FLOW_CELL_KM as f32,
+            )));
+        }
+    }
+
+    let (u, v) = fill_and_smooth(&mut vectors, blocks);
+    FlowField {
+        blocks,
+        half_km,
+        u,
+        v,
+    }
+}
+
+/// Give blocks without a vector the mean of their neighbours', growing
+/// outwards from the blocks that have one, then average each block with its
+/// neighbours once. No vectors at all means no motion.
+fn fill_and_smooth(vectors: &mut [Option<(f32, f32)>], blocks: usize) -> (Vec<f32>, Vec<f32>) {
+    let neighbour_mean = |vectors: &[Option<(f32, f32)>], row: usize, col: usize| {
+        let mut sum = (0.0, 0.0);
+        let mut n = 0;
+        for r in row.saturating_sub(1)..=(row + 1).min(blocks - 1) {
+            for c in col.saturating_sub(1)..=(col + 1).min(blocks - 1) {
+                if let Some((u, v)) = vectors[r * blocks + c] {
+                    sum = (sum.0 + u, sum.1 + v);
+                    n += 1;
+                }
+            }
+        }
+        (n > 0).then(|| (sum.0 / n as f32, sum.1 / n as f32))
+    };
+
+    if vectors.iter().all(Option::is_none) {
+        return (vec![0.0; vectors.len()], vec![0.0; vectors.len()]);
+    }
+    while vectors.iter().any(Option::is_none) {
+        let filled: Vec<Option<(f32, f32)>> = (0..vectors.len())
+            .map(|i| vectors[i].or_else(|| neighbour_mean(vectors, i / blocks, i % blocks)))
+            .collect();
+        vectors.copy_from_slice(&filled);
+    }
+    (0..vectors.len())
+        .map(|i| neighbour_mean(vectors, i / blocks, i % blocks).unwrap_or((0.0, 0.0)))
+        .unzip()
+}
+
+/// The frame `t` (0 = `a`, 1 = `b`) of the way from `a` to `b`, as raw
+/// values on `a`'s grid.
+///
+/// Where only one sweep has echo, it is kept while its side of the
+/// cross-fade weighs at least half. Categorical products are not blended:
+/// each gate takes the nearer sweep's class.
+pub(crate) fn interpolate_frame(
+    a: &RawSweep,
+    b: &RawSweep,
+    flow: &FlowField,
+    t: f32,
+    categorical: bool,
+) -> Vec<f32> {
+    let index_a = a.azimuth_index();
+    let index_b = b.azimuth_index();
+    let back = t as f64;
+    let forward = 1.0 - back;
+    let mut out = vec![0.0; a.azimuths.len() * a.gate_count];
+    for (radial, &azimuth) in a.azimuths.iter().enumerate() {
+        if azimuth < 0.0 {
+            continue;
+        }
+        let (sin, cos) = (azimuth as f64).to_radians().sin_cos();
+        for gate in 0..a.gate_count {
+            let range_km = a.first_gate_km + (gate as f64 + 0.5) * a.gate_interval_km;
+            let (x, y) = (range_km * sin, range_km * cos);
+            let (u, v) = flow.at(x, y);
+            let (u, v) = (u as f64, v as f64);
+            let va = a.value_at(&index_a, x - back * u, y - back * v);
+            let vb = b.value_at(&index_b, x + forward * u, y + forward * v);
+            let value = if categorical {
+                if t < 0.5 {
+                    va
+                } else {
+                    vb
+                }
+            } else {
+                match (va.is_nan(), vb.is_nan()) {
+                    (false, false) => va + (vb - va) * t,
+                    (false, true) if t <= 0.5 => va,
+                    (true, false) if t >= 0.5 => vb,
+                    _ => f32::NAN,
+                }
+            };
+            if !value.is_nan() {
+                out[radial * a.gate_count + gate] = (value * a.scale + a.offset)
+                    .round()
+                    .clamp(2.0, u16::MAX as f32);
+            }
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCALE: f32 = 2.0;
+    const OFFSET: f32 = 66.0;
+
+    /// One-degree radials of one-km gates out to 100 km, with `value` dBZ
+    /// inside a 10 km disc centred `(x, y)` km from the radar.
+    fn disc(x: f64, y: f64, value: f32) -> (Vec<f32>, Vec<f32>) {
+        let azimuths: Vec<f32> = (0..360).map(|a| a as f32 + 0.5).collect();
+        let mut gates = vec![0.0; 360 * 100];
+        for (radial, &azimuth) in azimuths.iter().enumerate() {
+            let (sin, cos) = (azimuth as f64).to_radians().sin_cos();
+            for gate in 0..100 {
+                let range = gate as f64 + 0.5;
+                if (range * sin - x).hypot(range * cos - y) < 10.0 {
+                    gates[radial * 100 + gate] = value * SCALE + OFFSET;
+                }
+            }
+        }
+        (azimuths, gates)
+    }
+
+    fn sweep<'a>(azimuths: &'a [f32], gates: &'a [f32]) -> RawSweep<'a> {
+        RawSweep {
+            azimuths,
+            gate_values: gates,
+            gate_count: 100,
+            first_gate_km: 0.0,
+            gate_interval_km: 1.0,
+            offset: OFFSET,
+            scale: SCALE,
+        }
+    }
+
+    /// Physical value of the gate nearest `(x, y)` km.
+    fn value_near(frame: &[f32], x: f64, y: f64) -> Option<f32> {
+        let radial = (x.atan2(y).to_degrees().rem_euclid(360.0)) as usize;
+        let gate = x.hypot(y) as usize;
+        let raw = frame[radial * 100 + gate];
+        (raw >= 2.0).then(|| (raw - OFFSET) / SCALE)
+    }
+
+    #[test]
+    fn finds_the_displacement_of_a_moving_echo() {
+        let (az_a, gates_a) = disc(20.0, 40.0, 40.0);
+        let (az_b, gates_b) = disc(28.0, 36.0, 40.0);
+        let flow = estimate_flow(&sweep(&az_a, &gates_a), &sweep(&az_b, &gates_b));
+        let (u, v) = flow.at(20.0, 40.0);
+        assert!((u - 8.0).abs() <= 2.0, "u = {u}");
+        assert!((v + 4.0).abs() <= 2.0, "v = {v}");
+        // Blocks without echo take the motion of the echo.
+        let (u, v) = flow.at(-60.0, -60.0);
+        assert!((u - 8.0).abs() <= 2.0 && (v + 4.0).abs() <= 2.0);
+    }
+
+    #[test]
+    fn moves_and_blends_the_echo_between_sweeps() {
+        let (az_a, gates_a) = disc(20.0, 40.0, 30.0);
+        let (az_b, gates_b) = disc(32.0, 40.0, 50.0);
+        let (a, b) = (sweep(&az_a, &gates_a), sweep(&az_b, &gates_b));
+        let flow = estimate_flow(&a, &b);
+
+        let start = interpolate_frame(&a, &b, &flow, 0.0, false);
+        assert_eq!(value_near(&start, 20.0, 40.0), Some(30.0));
+        assert_eq!(value_near(&start, 28.0, 40.0), Some(30.0));
+        assert_eq!(value_near(&start, 33.0, 40.0), None);
+
+        let middle = interpolate_frame(&a, &b, &flow, 0.5, false);
+        assert_eq!(value_near(&middle, 26.0, 40.0), Some(40.0));
+        assert_eq!(value_near(&middle, 14.0, 40.0), None);
+        assert_eq!(value_near(&middle, 34.0, 40.0), Some(40.0));
+        assert_eq!(value_near(&middle, 38.0, 40.0), None);
+
+        let categorical = interpolate_frame(&a, &b, &flow, 0.75, true);
+        assert_eq!(value_near(&categorical, 29.0, 40.0), Some(50.0));
+    }
+
+    #[test]
+    fn stands_still_without_echo() {
+        let azimuths: Vec<f32> = (0..360).map(|a| a as f32 + 0.5).collect();
+        let gates = vec![0.0; 360 * 100];
+        let flow = estimate_flow(&sweep(&azimuths, &gates), &sweep(&azimuths, &gates));
+        assert_eq!(flow.at(10.0, -30.0), (0.0, 0.0));
+    }
+}
@@ -104,7 +104,11 @@ void main() {{
         if (is_valid(v11)) {{ sum += v11 * w11; wsum += w11; }}
 
         if (wsum < 0.001) {{
-            discard;
+            fragColor = sentinel_color(nearest_corner(v00, v10, v01, v11, g_frac, az_frac));
+            if (fragColor.a == 0.0) {{
+                discard;
+            }}
+            return;
         }}
         value = sum / wsum;
     }} else {{
@@ -117,7 +121,11 @@ void main() {{
     }}
 
     if (!is_valid(value)) {{
-        discard;
+        fragColor = sentinel_color(value);
+        if (fragColor.a == 0.0) {{
+            discard;
+        }}
+        return;
     }}
 
 {RAW_TO_PHYSICAL}
@@ -150,6 +158,8 @@ pub struct GlobeRadarRenderer {
     u_offset: glow::UniformLocation,
     u_scale: glow::UniformLocation,
     u_azimuth_spacing_deg: glow::UniformLocation,
+    u_storm_motion: glow::UniformLocation,
+    u_range_folded_color: glow::UniformLocation,
 
     /// Radar site location (for rebuilding mesh when site changes).
     site_lat: f64,
@@ -188,6 +198,10 @@ impl GlobeRadarRenderer {
         let u_azimuth_spacing_deg = gl
             .get_uniform_location(program, "u_azimuth_spacing_deg")
             .unwrap();
+        let u_storm_motion = gl.get_uniform_location(program, "u_storm_motion").unwrap();
+        let u_range_folded_color = gl
+            .get_uniform_location(program, "u_range_folded_color")
+            .unwrap();
 
         // Bind texture samplers
         gl.use_program(Some(program));
@@ -242,6 +256,8 @@ impl GlobeRadarRenderer {
             u_offset,
             u_scale,
             u_azimuth_spacing_deg,
+            u_storm_motion,
+            u_range_folded_color,
             site_lat: 0.0,
             site_lon: 0.0,
             mesh_range_km: 0.0,
@@ -353,11 +369,10 @@ impl GlobeRadarRenderer {
             gl.uniform_1_f32(Some(&self.u_value_range), flat_renderer.value_range());
 
             // Processing uniforms
-            let interp_mode = match processing.interpolation {
-                crate::state::InterpolationMode::Nearest => 0,
-                crate::state::InterpolationMode::Bilinear => 1,
-            };
-            gl.uniform_1_i32(Some(&self.u_interpolation), interp_mode);
+            gl.uniform_1_i32(
+                Some(&self.u_interpolation),
+                flat_renderer.interpolation_mode(processing),
+            );
             gl.uniform_1_f32(Some(&self.u_opacity), processing.opacity);
 
             // Raw-to-physical conversion
@@ -367,6 +382,10 @@ impl GlobeRadarRenderer {
                 Some(&self.u_azimuth_spacing_deg),
                 flat_renderer.azimuth_spacing_deg(),
             );
+            let [storm_east, storm_north] = flat_renderer.storm_motion_ms();
+            gl.uniform_2_f32(Some(&self.u_storm_motion), storm_east, storm_north);
+            let [r, g, b, a] = flat_renderer.range_folded_color();
+            gl.uniform_4_f32(Some(&self.u_range_folded_color), r, g, b, a);
 
             gl.draw_elements(glow::TRIANGLES, self.index_count, glow::UNSIGNED_INT, 0);
 
@@ -1,9 +1,11 @@
-//! CPU-side inspection methods: value lookups and storm cell detection.
+//! CPU-side inspection methods: value lookups, sweep snapshots for export,
+//! storm cell and rotation detection.
 
 use super::{find_nearest_azimuth_index, RadarGpuRenderer};
 
 impl RadarGpuRenderer {
-    /// Look up the raw data value at a given polar coordinate.
+    /// Look up the physical data value at a given polar coordinate, made
+    /// storm-relative when a storm motion is set.
     ///
     /// When `sweep_params` is `Some((sweep_azimuth, sweep_start))`, determines
     /// whether the queried position falls in the previous-sweep region and
@@ -52,11 +54,12 @@ impl RadarGpuRenderer {
             return None;
         }
 
-        if self.current.data_scale == 0.0 {
-            Some(raw)
+        let physical = if self.current.data_scale == 0.0 {
+            raw
         } else {
-            Some((raw - self.current.data_offset) / self.current.data_scale)
-        }
+            (raw - self.current.data_offset) / self.current.data_scale
+        };
+        Some(self.storm_relative(physical, azimuth_deg))
     }
 
     /// Look up the radial collection timestamp (Unix seconds) at a given azimuth.
@@ -89,6 +92,34 @@ impl RadarGpuRenderer {
         self.cpu.radial_times.get(az_idx).copied()
     }
 
+    /// Look up the collection time (Unix seconds) of the gate at a polar
+    /// coordinate. Only composites carry per-gate times; `None` otherwise,
+    /// and where the gate has no data.
+    pub fn gate_time_at_polar(&self, azimuth_deg: f32, range_km: f64) -> Option<f64> {
+        if self.cpu.gate_times.is_empty() || self.cpu.azimuths.is_empty() {
+            return None;
+        }
+        if range_km < self.current.first_gate_km || range_km >= self.current.max_range_km {
+            return None;
+        }
+        let az_idx = find_nearest_azimuth_index(
+            &self.cpu.azimuths,
+            self.current.azimuth_count as usize,
+            azimuth_deg,
+        )?;
+        let gate_count = self.current.gate_count as usize;
+        let gate_idx = ((range_km - self.current.first_gate_km) / self.current.gate_interval_km)
+            .floor() as usize;
+        if gate_idx >= gate_count {
+            return None;
+        }
+        self.cpu
+            .gate_times
+            .get(az_idx * gate_count + gate_idx)
+            .copied()
+            .filter(|t| !t.is_nan())
+    }
+
     /// Look up value in the previous sweep's CPU data using evenly-spaced azimuth indexing.
     fn prev_value_at_polar(&self, azimuth_deg: f32, range_km: f64) -> Option<f32> {
         let az_count = self.prev.azimuth_count as usize;
@@ -120,11 +151,12 @@ impl RadarGpuRenderer {
             return None;
         }
 
-        if self.prev.data_scale == 0.0 {
-            Some(raw)
+        let physical = if self.prev.data_scale == 0.0 {
+            raw
         } else {
-            Some((raw - self.prev.data_offset) / self.prev.data_scale)
-        }
+            (raw - self.prev.data_offset) / self.prev.data_scale
+        };
+        Some(self.storm_relative(physical, azimuth_deg))
     }
 
     /// Look up collection time in the previous sweep's CPU data.
@@ -138,6 +170,41 @@ impl RadarGpuRenderer {
         self.prev_cpu.radial_times.get(az_idx).copied()
     }
 
+    /// Copy of the current sweep in physical units (storm-relative when a
+    /// storm motion is set), for gridded exports. `None` without data.
+    pub(crate) fn polar_sweep(&self) -> Option<crate::nexrad::polar::PolarSweep> {
+        if !self.has_data || self.cpu.azimuths.is_empty() {
+            return None;
+        }
+        let gate_count = self.current.gate_count as usize;
+        let values = self
+            .cpu
+            .gate_values
+            .chunks_exact(gate_count.max(1))
+            .zip(&self.cpu.azimuths)
+            .flat_map(|(radial, &azimuth)| {
+                radial.iter().map(move |&raw| {
+                    if raw <= 1.0 {
+                        return f32::NAN;
+                    }
+                    let physical = if self.current.data_scale == 0.0 {
+                        raw
+                    } else {
+                        (raw - self.current.data_offset) / self.current.data_scale
+                    };
+                    self.storm_relative(physical, azimuth)
+                })
+            })
+            .collect();
+        Some(crate::nexrad::polar::PolarSweep {
+            azimuths: self.cpu.azimuths.clone(),
+            gate_count,
+            first_gate_km: self.current.first_gate_km,
+            gate_interval_km: self.current.gate_interval_km,
+            values,
+        })
+    }
+
     /// Detect storm cells from the current CPU-side data.
     ///
     /// Thin adapter over `crate::nexrad::detection` — packages the shadow
@@ -188,4 +255,52 @@ impl RadarGpuRenderer {
 
         result
     }
+
+    /// Detect rotation couplets from the current CPU-side data, which must
+    /// be a velocity sweep collected at `elevation_deg`.
+    pub fn detect_rotation(
+        &self,
+        radar_lat: f64,
+        radar_lon: f64,
+        elevation_deg: f32,
+    ) -> Vec<crate::state::RotationInfo> {
+        if !self.has_data || self.cpu.azimuths.is_empty() {
+            return Vec::new();
+        }
+
+        let t_total = web_time::Instant::now();
+
+        let az_count = self.current.azimuth_count as usize;
+        let gate_count = self.current.gate_count as usize;
+
+        let input = crate::nexrad::detection::DetectionInput {
+            azimuths: &self.cpu.azimuths,
+            gate_values: &self.cpu.gate_values,
+            azimuth_count: az_count,
+            gate_count,
+            first_gate_km: self.current.first_gate_km,
+            gate_interval_km: self.current.gate_interval_km,
+            data_scale: self.current.data_scale,
+            data_offset: self.current.data_offset,
+            radar_lat,
+            radar_lon,
+        };
+
+        let result = crate::nexrad::detection::detect_rotation(
+            &input,
+            elevation_deg,
+            self.current.nyquist_velocity,
+            &crate::nexrad::detection::RotationParams::default(),
+        );
+
+        log::debug!(
+            "detect_rotation: {}x{} grid, {} features, {:.1}ms",
+            az_count,
+            gate_count,
+            result.len(),
+            t_total.elapsed().as_secs_f64() * 1000.0,
+        );
+
+        result
+    }
 }
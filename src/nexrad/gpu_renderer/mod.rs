@@ -83,6 +83,8 @@ struct UniformLocations {
     sweep_chunk_boundary: glow::UniformLocation,
     azimuth_spacing_deg: glow::UniformLocation,
     prev_azimuth_spacing_deg: glow::UniformLocation,
+    storm_motion: glow::UniformLocation,
+    range_folded_color: glow::UniformLocation,
 }
 
 /// Spatial metadata for a single sweep (current or previous).
@@ -99,6 +101,9 @@ struct SweepState {
     /// azimuth_count (which would be wrong for partial/clustered sweeps).
     azimuth_spacing_deg: f32,
     sweep_id: Option<String>,
+    /// Nyquist velocity (m/s) of aliased velocity data; `None` for other
+    /// products, dealiased velocity, or when unknown.
+    nyquist_velocity: Option<f32>,
 }
 
 impl Default for SweepState {
@@ -113,6 +118,7 @@ impl Default for SweepState {
             data_scale: 1.0,
             azimuth_spacing_deg: 1.0,
             sweep_id: None,
+            nyquist_velocity: None,
         }
     }
 }
@@ -123,6 +129,8 @@ struct CpuShadowData {
     azimuths: Vec<f32>,
     gate_values: Vec<f32>,
     radial_times: Vec<f64>,
+    /// Per-gate collection times (lowest-available composites only).
+    gate_times: Vec<f64>,
 }
 
 /// GPU-based radar renderer using WebGL2 shaders.
@@ -149,6 +157,18 @@ pub struct RadarGpuRenderer {
     has_data: bool,
     value_min: f32,
     value_range: f32,
+    /// Storm motion (east, north) in m/s for storm-relative velocity;
+    /// zero when SRM is not displayed.
+    storm_motion_ms: [f32; 2],
+    /// Current color table is categorical (class codes); data must be
+    /// sampled nearest-neighbour regardless of the interpolation setting.
+    categorical: bool,
+    /// Worker product string the color table was built for, so it can be
+    /// rebuilt when the user changes palettes.
+    lut_product: Option<String>,
+    /// Straight RGBA (0..1) for range-folded gates; transparent unless the
+    /// product's palette has an `RF` color.
+    range_folded_color: [f32; 4],
 }
 
 impl RadarGpuRenderer {
@@ -240,6 +260,8 @@ impl RadarGpuRenderer {
                 sweep_chunk_boundary: uniform("u_sweep_chunk_boundary")?,
                 azimuth_spacing_deg: uniform("u_azimuth_spacing_deg")?,
                 prev_azimuth_spacing_deg: uniform("u_prev_azimuth_spacing_deg")?,
+                storm_motion: uniform("u_storm_motion")?,
+                range_folded_color: uniform("u_range_folded_color")?,
             };
 
             // Create placeholders for previous sweep textures
@@ -265,6 +287,10 @@ impl RadarGpuRenderer {
                 has_data: false,
                 value_min: 0.0,
                 value_range: 1.0,
+                storm_motion_ms: [0.0, 0.0],
+                categorical: false,
+                lut_product: None,
+                range_folded_color: [0.0; 4],
             })
         }
     }
@@ -312,6 +338,34 @@ impl RadarGpuRenderer {
     pub fn azimuth_texture(&self) -> glow::Texture {
         self.azimuth_texture
     }
+    pub fn storm_motion_ms(&self) -> [f32; 2] {
+        self.storm_motion_ms
+    }
+    pub fn range_folded_color(&self) -> [f32; 4] {
+        self.range_folded_color
+    }
+
+    /// Interpolation mode uniform value: 0 = nearest, 1 = bilinear.
+    /// Categorical products always use nearest.
+    pub fn interpolation_mode(&self, processing: &RenderProcessing) -> i32 {
+        match processing.interpolation {
+            _ if self.categorical => 0,
+            crate::state::InterpolationMode::Nearest => 0,
+            crate::state::InterpolationMode::Bilinear => 1,
+        }
+    }
+
+    /// Set the storm motion `(east, north)` in m/s removed from velocity
+    /// gates for storm-relative display. `None` shows ground-relative data.
+    pub fn set_storm_motion(&mut self, motion_ms: Option<[f32; 2]>) {
+        self.storm_motion_ms = motion_ms.unwrap_or([0.0, 0.0]);
+    }
+
+    /// Remove the storm motion's radial component from a velocity value.
+    fn storm_relative(&self, value: f32, azimuth_deg: f32) -> f32 {
+        let (sin, cos) = azimuth_deg.to_radians().sin_cos();
+        value - (self.storm_motion_ms[0] * sin + self.storm_motion_ms[1] * cos)
+    }
 
     /// Render the radar data using the current GL context.
     ///
@@ -397,11 +451,10 @@ impl RadarGpuRenderer {
             );
 
             // Processing uniforms
-            let interp_mode = match processing.interpolation {
-                crate::state::InterpolationMode::Nearest => 0,
-                crate::state::InterpolationMode::Bilinear => 1,
-            };
-            gl.uniform_1_i32(Some(&self.uniforms.interpolation), interp_mode);
+            gl.uniform_1_i32(
+                Some(&self.uniforms.interpolation),
+                self.interpolation_mode(processing),
+            );
             gl.uniform_1_f32(Some(&self.uniforms.opacity), processing.opacity);
             gl.uniform_1_i32(
                 Some(&self.uniforms.data_age_desaturation),
@@ -456,6 +509,13 @@ impl RadarGpuRenderer {
                 Some(&self.uniforms.prev_azimuth_spacing_deg),
                 self.prev.azimuth_spacing_deg,
             );
+            gl.uniform_2_f32(
+                Some(&self.uniforms.storm_motion),
+                self.storm_motion_ms[0],
+                self.storm_motion_ms[1],
+            );
+            let [r, g, b, a] = self.range_folded_color;
+            gl.uniform_4_f32(Some(&self.uniforms.range_folded_color), r, g, b, a);
 
             // Draw fullscreen quad
             gl.draw_arrays(glow::TRIANGLES, 0, 6);
@@ -4,6 +4,7 @@
 //! and the flat-mode fragment shader builder.
 
 use super::RadarGpuRenderer;
+use crate::geo::globe_renderer::shader_source;
 use glow::HasContext;
 use std::sync::Arc;
 
@@ -35,6 +36,13 @@ uniform float u_scale;
 // and non-uniform sweeps get a correct reject distance).
 uniform float u_azimuth_spacing_deg;
 
+// Storm motion (east, north) in m/s whose radial component is removed from
+// every gate for storm-relative velocity. Zero for all other products.
+uniform vec2 u_storm_motion;
+
+// Straight RGBA for range-folded gates; alpha 0 keeps them hidden.
+uniform vec4 u_range_folded_color;
+
 const float PI = 3.14159265359;
 ";
 
@@ -57,6 +65,24 @@ pub(crate) const IS_VALID: &str = "\
 bool is_valid(float v) {
     return v > 1.5;
 }
+
+// Premultiplied output for a sentinel: the palette's range-folded color,
+// otherwise transparent.
+vec4 sentinel_color(float v) {
+    if (v > 0.5 && v < 1.5) {
+        float a = u_range_folded_color.a * u_opacity;
+        return vec4(u_range_folded_color.rgb * a, a);
+    }
+    return vec4(0.0);
+}
+
+// The raw value of the corner nearest to a bilinear sample point.
+float nearest_corner(float v00, float v10, float v01, float v11, float g_frac, float az_frac) {
+    if (az_frac < 0.5) {
+        return g_frac < 0.5 ? v00 : v10;
+    }
+    return g_frac < 0.5 ? v01 : v11;
+}
 ";
 
 pub(crate) const FIND_NEAREST_AZ_P: &str = "\
@@ -170,6 +196,9 @@ pub(crate) const RAW_TO_PHYSICAL: &str = "\
     } else {
         physical = (value - s_offset) / s_scale;
     }
+    // Storm-relative velocity: subtract the motion's component along the beam
+    float az_rad = radians(azimuth_deg);
+    physical -= dot(u_storm_motion, vec2(sin(az_rad), cos(az_rad)));
 ";
 
 pub(crate) const COLOR_LOOKUP: &str = "\
@@ -369,7 +398,7 @@ void main() {{
         if (is_valid(v11)) {{ sum += v11 * w11; wsum += w11; }}
 
         if (wsum < 0.001) {{
-            fragColor = vec4(0.0);
+            fragColor = sentinel_color(nearest_corner(v00, v10, v01, v11, g_frac, az_frac));
             return;
         }}
         value = sum / wsum;
@@ -394,7 +423,7 @@ void main() {{
     }}
 
     if (!is_valid(value)) {{
-        fragColor = vec4(0.0);
+        fragColor = sentinel_color(value);
         return;
     }}
 
@@ -427,7 +456,7 @@ impl RadarGpuRenderer {
             let vert = gl
                 .create_shader(glow::VERTEX_SHADER)
                 .map_err(|e| format!("Cannot create vertex shader: {}", e))?;
-            gl.shader_source(vert, VERTEX_SHADER);
+            gl.shader_source(vert, &shader_source(gl, VERTEX_SHADER));
             gl.compile_shader(vert);
             if !gl.get_shader_compile_status(vert) {
                 let info = gl.get_shader_info_log(vert);
@@ -438,7 +467,7 @@ impl RadarGpuRenderer {
                 .create_shader(glow::FRAGMENT_SHADER)
                 .map_err(|e| format!("Cannot create fragment shader: {}", e))?;
             let frag_src = build_flat_fragment_shader();
-            gl.shader_source(frag, &frag_src);
+            gl.shader_source(frag, &shader_source(gl, &frag_src));
             gl.compile_shader(frag);
             if !gl.get_shader_compile_status(frag) {
                 let info = gl.get_shader_info_log(frag);
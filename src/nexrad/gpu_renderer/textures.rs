@@ -1,11 +1,9 @@
 //! Texture creation, upload, and state management for the GPU radar renderer.
 
 use super::{create_r32f_texture, create_rgba8_texture, RadarGpuRenderer};
-use crate::nexrad::color_table::{
-    build_reflectivity_lut, continuous_color_scale, product_from_str, product_value_range,
-};
+use crate::nexrad::color_table::{build_product_lut, is_categorical, value_range_for};
+use crate::nexrad::palette;
 use glow::HasContext;
-use nexrad_render::Product;
 
 impl RadarGpuRenderer {
     /// Upload decoded radar data to GPU textures.
@@ -50,6 +48,7 @@ impl RadarGpuRenderer {
         self.cpu.azimuths = azimuths.to_vec();
         self.cpu.gate_values = gate_values.to_vec();
         self.cpu.radial_times = radial_times.to_vec();
+        self.cpu.gate_times.clear();
         let copy_ms = t_copy.elapsed().as_secs_f64() * 1000.0;
 
         if !self.has_data {
@@ -184,9 +183,16 @@ impl RadarGpuRenderer {
         self.cpu.azimuths.clear();
         self.cpu.gate_values.clear();
         self.cpu.radial_times.clear();
+        self.cpu.gate_times.clear();
         self.clear_previous_data();
     }
 
+    /// Attach per-gate collection times to the data just uploaded, for a
+    /// composite whose gates come from different sweeps.
+    pub fn set_gate_times(&mut self, gate_times: &[f64]) {
+        self.cpu.gate_times = gate_times.to_vec();
+    }
+
     /// Clear the previous-sweep texture so the shader composites against
     /// transparent until a new previous sweep is loaded. Zeroes the spatial
     /// metadata so the shader's range/gate check bails out on the prev branch
@@ -221,34 +227,30 @@ impl RadarGpuRenderer {
         self.current.sweep_id = id;
     }
 
+    /// Set the Nyquist velocity of the current sweep if it holds aliased
+    /// velocity (called after `update_data`).
+    pub fn set_current_nyquist_velocity(&mut self, nyquist_velocity: Option<f32>) {
+        self.current.nyquist_velocity = nyquist_velocity;
+    }
+
     /// Build and upload a color lookup table for the given product.
     pub fn update_color_table(&mut self, gl: &glow::Context, product_str: &str) {
         let t_total = web_time::Instant::now();
 
-        let product = product_from_str(product_str);
-        let (min_val, max_val) = product_value_range(product);
+        let (min_val, max_val) = value_range_for(product_str);
         self.value_min = min_val;
         self.value_range = max_val - min_val;
+        self.categorical = is_categorical(product_str);
+        self.lut_product = Some(product_str.to_string());
+        self.range_folded_color = palette::active_for(product_str)
+            .and_then(|p| p.range_folded)
+            .map_or([0.0; 4], |c| c.map(|v| v as f32 / 255.0));
 
         let t_build = web_time::Instant::now();
 
-        // Build 1024-entry RGBA LUT (continuous gradient + GL_LINEAR = zero visible quantization)
+        // Build 1024-entry RGBA LUT
         let lut_size = 1024usize;
-        let lut_data = if matches!(product, Product::Reflectivity) {
-            // OKLab-interpolated reflectivity palette with alpha ramp
-            build_reflectivity_lut(min_val, max_val)
-        } else {
-            let color_scale = continuous_color_scale(product);
-            let mut data = Vec::with_capacity(lut_size * 4);
-            for i in 0..lut_size {
-                let t = i as f32 / (lut_size - 1) as f32;
-                let value = min_val + t * (max_val - min_val);
-                let color = color_scale.color(value);
-                let rgba = color.to_rgba8();
-                data.extend_from_slice(&rgba);
-            }
-            data
-        };
+        let lut_data = build_product_lut(product_str);
         let build_ms = t_build.elapsed().as_secs_f64() * 1000.0;
 
         let t_upload = web_time::Instant::now();
@@ -260,8 +262,8 @@ impl RadarGpuRenderer {
         let total_ms = t_total.elapsed().as_secs_f64() * 1000.0;
 
         log::debug!(
-            "GPU update_color_table: {:?} ({:.1}..{:.1}), {:.1}ms (build: {:.1}ms, upload: {:.1}ms)",
-            product,
+            "GPU update_color_table: {} ({:.1}..{:.1}), {:.1}ms (build: {:.1}ms, upload: {:.1}ms)",
+            product_str,
             min_val,
             max_val,
             total_ms,
@@ -269,4 +271,11 @@ impl RadarGpuRenderer {
             upload_ms,
         );
     }
+
+    /// Rebuild the current color table, e.g. after the palettes change.
+    pub fn refresh_color_table(&mut self, gl: &glow::Context) {
+        if let Some(product) = self.lut_product.clone() {
+            self.update_color_table(gl, &product);
+        }
+    }
 }
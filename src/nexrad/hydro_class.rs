@@ -0,0 +1,442 @@
+//! Fuzzy-logic hydrometeor classification (HCA) from dual-pol moments.
+//!
+//! Runs during ingest on every elevation that carries reflectivity plus at
+//! least one dual-pol moment, and stores a categorical sweep under
+//! [`HYDROMETEOR_CLASS`]. The scheme follows the structure of the NSSL / WSR-88D
+//! HCA (Park et al. 2009) with simplified, range-independent membership
+//! functions and no melting-layer input:
+//!
+//! 1. ZDR, CC and ΦDP are resampled onto the reflectivity gate grid.
+//! 2. Along each radial, KDP is derived as half the least-squares slope of
+//!    ΦDP, and the textures SD(Z) and SD(ΦDP) are computed over short windows.
+//! 3. Each class scores `Σ wᵢ·μᵢ / Σ wᵢ` over the variables present at the
+//!    gate, with trapezoidal memberships μ. The best-scoring class wins and
+//!    its score is the confidence; weak winners are reported as unknown.
+//!
+//! The output encodes both values in one U16 gate so the normal render path
+//! can draw it: `physical = class + 0.25 + 0.5·confidence`. The colour table
+//! assigns one colour per unit interval, and the ±0.25 margin keeps linear
+//! LUT sampling from bleeding between neighbouring classes.
+
+use crate::data::keys::{GateValues, PrecomputedSweep};
+use crate::data::moments::{decode_physical, kdp_from_phase, resample, window_gates};
+
+/// Product key for the classification blob in the `sweep_data` store.
+pub(crate) const HYDROMETEOR_CLASS: &str = "hydrometeor_class";
+
+/// Hydrometeor classes, in encoding order.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum HydroClass {
+    Biological,
+    GroundClutter,
+    IceCrystals,
+    DrySnow,
+    WetSnow,
+    Rain,
+    HeavyRain,
+    Graupel,
+    Hail,
+    Unknown,
+}
+
+impl HydroClass {
+    pub(crate) const ALL: [HydroClass; 10] = [
+        HydroClass::Biological,
+        HydroClass::GroundClutter,
+        HydroClass::IceCrystals,
+        HydroClass::DrySnow,
+        HydroClass::WetSnow,
+        HydroClass::Rain,
+        HydroClass::HeavyRain,
+        HydroClass::Graupel,
+        HydroClass::Hail,
+        HydroClass::Unknown,
+    ];
+
+    pub(crate) fn label(self) -> &'static str {
+        match self {
+            HydroClass::Biological => "Biological",
+            HydroClass::GroundClutter => "Ground Clutter",
+            HydroClass::IceCrystals => "Ice Crystals",
+            HydroClass::DrySnow => "Dry Snow",
+            HydroClass::WetSnow => "Wet Snow",
+            HydroClass::Rain => "Rain",
+            HydroClass::HeavyRain => "Heavy Rain",
+            HydroClass::Graupel => "Graupel",
+            HydroClass::Hail => "Hail",
+            HydroClass::Unknown => "Unknown",
+        }
+    }
+
+    /// Legend colour (sRGB).
+    pub(crate) fn color(self) -> [u8; 3] {
+        match self {
+            HydroClass::Biological => [156, 156, 156],
+            HydroClass::GroundClutter => [102, 102, 102],
+            HydroClass::IceCrystals => [230, 150, 230],
+            HydroClass::DrySnow => [150, 180, 255],
+            HydroClass::WetSnow => [40, 90, 230],
+            HydroClass::Rain => [110, 210, 110],
+            HydroClass::HeavyRain => [20, 130, 20],
+            HydroClass::Graupel => [240, 220, 90],
+            HydroClass::Hail => [220, 30, 30],
+            HydroClass::Unknown => [60, 40, 70],
+        }
+    }
+
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+/// Decode a physical gate value into its class and confidence (0–1).
+pub(crate) fn decode(value: f32) -> Option<(HydroClass, f32)> {
+    if !(0.0..HydroClass::ALL.len() as f32).contains(&value) {
+        return None;
+    }
+    let class = HydroClass::ALL[value as usize];
+    let confidence = ((value.fract() - 0.25) / 0.5).clamp(0.0, 1.0);
+    Some((class, confidence))
+}
+
+/// Winners scoring below this are reported as [`HydroClass::Unknown`].
+const MIN_CONFIDENCE: f32 = 0.5;
+
+/// Along-radial window length (km) for the texture fields.
+const TEXTURE_WINDOW_KM: f64 = 1.0;
+
+/// U16 encoding of `class + 0.25 + 0.5·confidence`.
+const CLASS_SCALE: f32 = 1000.0;
+const CLASS_OFFSET: f32 = 2.0;
+
+/// Trapezoidal membership: 0 outside `[x1, x4]`, 1 on `[x2, x3]`.
+type Trapezoid = [f32; 4];
+
+/// Membership functions for Z, ZDR, CC, KDP, SD(Z), SD(ΦDP).
+struct ClassMembership {
+    class: HydroClass,
+    mf: [Trapezoid; 6],
+}
+
+/// Per-variable weights, same order as `ClassMembership::mf`.
+const WEIGHTS: [f32; 6] = [1.0, 0.8, 0.8, 0.6, 0.5, 0.5];
+
+const WIDE: Trapezoid = [-1e9, -1e8, 1e8, 1e9];
+/// Texture of meteorological echo: smooth along the radial.
+const SMOOTH_Z: Trapezoid = [-1.0, 0.0, 3.0, 6.0];
+const SMOOTH_PHI: Trapezoid = [-1.0, 0.0, 12.0, 25.0];
+
+const MEMBERSHIPS: [ClassMembership; 9] = [
+    ClassMembership {
+        class: HydroClass::Biological,
+        mf: [
+            [0.0, 5.0, 20.0, 30.0],
+            [0.0, 2.0, 10.0, 12.0],
+            [0.3, 0.5, 0.8, 0.83],
+            WIDE,
+            [1.0, 2.0, 4.0, 7.0],
+            [8.0, 15.0, 40.0, 60.0],
+        ],
+    },
+    ClassMembership {
+        class: HydroClass::GroundClutter,
+        mf: [
+            [15.0, 20.0, 70.0, 80.0],
+            [-4.0, -2.0, 1.0, 2.0],
+            [0.5, 0.6, 0.9, 0.95],
+            WIDE,
+            [2.0, 4.0, 10.0, 15.0],
+            [20.0, 30.0, 60.0, 80.0],
+        ],
+    },
+    ClassMembership {
+        class: HydroClass::IceCrystals,
+        mf: [
+            [-10.0, 0.0, 20.0, 25.0],
+            [0.1, 0.4, 3.0, 3.3],
+            [0.95, 0.98, 1.0, 1.05],
+            [-0.5, 0.0, 0.2, 0.4],
+            SMOOTH_Z,
+            SMOOTH_PHI,
+        ],
+    },
+    ClassMembership {
+        class: HydroClass::DrySnow,
+        mf: [
+            [5.0, 10.0, 35.0, 40.0],
+            [-0.3, 0.0, 0.3, 0.6],
+            [0.95, 0.98, 1.0, 1.05],
+            [-0.5, 0.0, 0.1, 0.2],
+            SMOOTH_Z,
+            SMOOTH_PHI,
+        ],
+    },
+    ClassMembership {
+        class: HydroClass::WetSnow,
+        mf: [
+            [25.0, 30.0, 40.0, 50.0],
+            [0.5, 1.0, 2.0, 3.0],
+            [0.88, 0.92, 0.95, 0.985],
+            [-0.5, 0.0, 0.5, 1.0],
+            SMOOTH_Z,
+            SMOOTH_PHI,
+        ],
+    },
+    ClassMembership {
+        class: HydroClass::Rain,
+        mf: [
+            [5.0, 10.0, 40.0, 45.0],
+            [0.0, 0.3, 2.5, 3.5],
+            [0.95, 0.97, 1.0, 1.05],
+            [-0.5, 0.0, 1.0, 2.0],
+            SMOOTH_Z,
+            SMOOTH_PHI,
+        ],
+    },
+    ClassMembership {
+        class: HydroClass::HeavyRain,
+        mf: [
+            [40.0, 45.0, 55.0, 60.0],
+            [1.0, 1.5, 4.0, 5.0],
+            [0.92, 0.95, 1.0, 1.05],
+            [0.5, 1.5, 6.0, 8.0],
+            SMOOTH_Z,
+            SMOOTH_PHI,
+        ],
+    },
+    ClassMembership {
+        class: HydroClass::Graupel,
+        mf: [
+            [25.0, 35.0, 50.0, 55.0],
+            [-0.3, 0.0, 0.8, 1.3],
+            [0.9, 0.97, 1.0, 1.05],
+            [-0.5, 0.0, 1.5, 2.5],
+            SMOOTH_Z,
+            SMOOTH_PHI,
+        ],
+    },
+    ClassMembership {
+        class: HydroClass::Hail,
+        mf: [
+            [50.0, 55.0, 75.0, 85.0],
+            [-0.5, -0.3, 1.0, 2.0],
+            [0.75, 0.8, 0.95, 0.97],
+            [-10.0, -4.0, 5.0, 10.0],
+            SMOOTH_Z,
+            SMOOTH_PHI,
+        ],
+    },
+];
+
+fn membership(t: &Trapezoid, x: f32) -> f32 {
+    let [x1, x2, x3, x4] = *t;
+    if x <= x1 || x >= x4 {
+        0.0
+    } else if x < x2 {
+        (x - x1) / (x2 - x1)
+    } else if x <= x3 {
+        1.0
+    } else {
+        (x4 - x) / (x4 - x3)
+    }
+}
+
+/// Score every class and return the winner with its confidence.
+fn classify_gate(vars: &[f32; 6]) -> (HydroClass, f32) {
+    let mut best = (HydroClass::Unknown, 0.0_f32);
+    for m in &MEMBERSHIPS {
+        let (mut num, mut den) = (0.0_f32, 0.0_f32);
+        for ((t, &w), &x) in m.mf.iter().zip(WEIGHTS.iter()).zip(vars.iter()) {
+            if x.is_nan() {
+                continue;
+            }
+            num += w * membership(t, x);
+            den += w;
+        }
+        let score = if den > 0.0 { num / den } else { 0.0 };
+        if score > best.1 {
+            best = (m.class, score);
+        }
+    }
+    if best.1 < MIN_CONFIDENCE {
+        (HydroClass::Unknown, best.1)
+    } else {
+        best
+    }
+}
+
+/// The moments of one elevation that feed the classifier.
+pub(crate) struct DualPolMoments<'a> {
+    pub reflectivity: &'a PrecomputedSweep,
+    pub differential_reflectivity: Option<&'a PrecomputedSweep>,
+    pub correlation_coefficient: Option<&'a PrecomputedSweep>,
+    pub differential_phase: Option<&'a PrecomputedSweep>,
+}
+
+/// Classify one elevation on the reflectivity gate grid. `None` when the
+/// elevation has no dual-pol moment (legacy data, some split cuts).
+pub(crate) fn classify_sweep(moments: &DualPolMoments) -> Option<PrecomputedSweep> {
+    let refl = moments.reflectivity;
+    if moments.differential_reflectivity.is_none()
+        && moments.correlation_coefficient.is_none()
+        && moments.differential_phase.is_none()
+    {
+        return None;
+    }
+    let az_count = refl.azimuth_count as usize;
+    let gate_count = refl.gate_count as usize;
+    if az_count == 0 || gate_count == 0 || refl.scale == 0.0 {
+        return None;
+    }
+
+    let z = decode_physical(refl);
+    let zdr = moments
+        .differential_reflectivity
+        .map(|s| resample(s, refl))
+        .unwrap_or_default();
+    let cc = moments
+        .correlation_coefficient
+        .map(|s| resample(s, refl))
+        .unwrap_or_default();
+    let phi = moments
+        .differential_phase
+        .map(|s| resample(s, refl))
+        .unwrap_or_default();
+    let kdp = kdp_from_phase(&phi, refl);
+
+    let tex_half = window_gates(TEXTURE_WINDOW_KM, refl.gate_interval_km);
+
+    let mut raw = vec![0u16; az_count * gate_count];
+    for a in 0..az_count {
+        let row = a * gate_count..(a + 1) * gate_count;
+        let z_row = &z[row.clone()];
+        let phi_row = phi.get(row.clone());
+        for g in 0..gate_count {
+            if z_row[g].is_nan() {
+                continue;
+            }
+            let idx = a * gate_count + g;
+            let vars = [
+                z_row[g],
+                zdr.get(idx).copied().unwrap_or(f32::NAN),
+                cc.get(idx).copied().unwrap_or(f32::NAN),
+                kdp.get(idx).copied().unwrap_or(f32::NAN),
+                std_dev(z_row, g, tex_half),
+                phi_row.map(|p| std_dev(p, g, tex_half)).unwrap_or(f32::NAN),
+            ];
+            let (class, confidence) = classify_gate(&vars);
+            let value = class.index() as f32 + 0.25 + 0.5 * confidence;
+            raw[idx] = (value * CLASS_SCALE + CLASS_OFFSET).round() as u16;
+        }
+    }
+
+    Some(PrecomputedSweep {
+        azimuth_count: refl.azimuth_count,
+        gate_count: refl.gate_count,
+        first_gate_range_km: refl.first_gate_range_km,
+        gate_interval_km: refl.gate_interval_km,
+        max_range_km: refl.max_range_km,
+        scale: CLASS_SCALE,
+        offset: CLASS_OFFSET,
+        radial_count: refl.radial_count,
+        mean_elevation: refl.mean_elevation,
+        nyquist_velocity: None,
+        sweep_start_secs: refl.sweep_start_secs,
+        sweep_end_secs: refl.sweep_end_secs,
+        azimuths: refl.azimuths.clone(),
+        radial_times: refl.radial_times.clone(),
+        gate_values: GateValues::U16(raw),
+    })
+}
+
+/// Standard deviation of `row` around gate `g`, skipping NaNs.
+fn std_dev(row: &[f32], g: usize, half: usize) -> f32 {
+    let (lo, hi) = (g.saturating_sub(half), (g + half + 1).min(row.len()));
+    let (mut n, mut sum, mut sum_sq) = (0.0_f32, 0.0_f32, 0.0_f32);
+    for &v in row[lo..hi].iter().filter(|v| !v.is_nan()) {
+        n += 1.0;
+        sum += v;
+        sum_sq += v * v;
+    }
+    if n < 2.0 {
+        return f32::NAN;
+    }
+    let mean = sum / n;
+    (sum_sq / n - mean * mean).max(0.0).sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::SweepFixture;
+
+    const AZ_COUNT: usize = 360;
+    const GATE_COUNT: usize = 200;
+    const GATE_KM: f64 = 0.25;
+
+    /// Uniform U16 sweep of `value` with the given encoding.
+    fn field(value: f32, scale: f32, offset: f32) -> PrecomputedSweep {
+        SweepFixture::new(AZ_COUNT, GATE_COUNT, GATE_KM)
+            .first_gate_km(2.0)
+            .encoding(scale, offset)
+            .u16(|_, _| Some(value))
+    }
+
+    fn classify(z: f32, zdr: f32, cc: f32) -> (HydroClass, f32) {
+        let refl = field(z, 2.0, 66.0);
+        let zdr = field(zdr, 16.0, 128.0);
+        let cc = field(cc, 300.0, -60.0);
+        let phi = field(40.0, 2.8, 2.0);
+        let out = classify_sweep(&DualPolMoments {
+            reflectivity: &refl,
+            differential_reflectivity: Some(&zdr),
+            correlation_coefficient: Some(&cc),
+            differential_phase: Some(&phi),
+        })
+        .unwrap();
+        let GateValues::U16(raw) = &out.gate_values else {
+            panic!("classification is U16");
+        };
+        let r = raw[10 * GATE_COUNT + 100];
+        decode((r as f32 - out.offset) / out.scale).unwrap()
+    }
+
+    #[test]
+    fn separates_rain_heavy_rain_and_hail() {
+        assert_eq!(classify(30.0, 1.0, 0.99).0, HydroClass::Rain);
+        assert_eq!(classify(52.0, 3.0, 0.98).0, HydroClass::HeavyRain);
+        let (class, confidence) = classify(62.0, 0.2, 0.9);
+        assert_eq!(class, HydroClass::Hail);
+        assert!(confidence > 0.6, "{confidence}");
+    }
+
+    #[test]
+    fn low_cc_high_zdr_is_biological() {
+        assert_eq!(classify(12.0, 6.0, 0.6).0, HydroClass::Biological);
+    }
+
+    #[test]
+    fn reflectivity_only_is_skipped() {
+        let refl = field(30.0, 2.0, 66.0);
+        assert!(classify_sweep(&DualPolMoments {
+            reflectivity: &refl,
+            differential_reflectivity: None,
+            correlation_coefficient: None,
+            differential_phase: None,
+        })
+        .is_none());
+    }
+
+    #[test]
+    fn encoding_round_trips() {
+        for class in HydroClass::ALL {
+            for confidence in [0.0, 0.5, 1.0] {
+                let value = class.index() as f32 + 0.25 + 0.5 * confidence;
+                let (c, conf) = decode(value).unwrap();
+                assert_eq!(c, class);
+                assert!((conf - confidence).abs() < 1e-4);
+            }
+        }
+        assert!(decode(-1.0).is_none());
+    }
+}
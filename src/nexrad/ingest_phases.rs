@@ -1,10 +1,14 @@
 //! Archive and chunk ingestion phases.
 //!
-//! Provides the core decode pipeline that runs inside the Web Worker:
+//! Provides the core decode pipeline that runs inside the decode workers:
 //! decompression, VCP extraction, radial grouping by elevation, and
-//! pre-computed sweep blob generation for IndexedDB storage.
+//! pre-computed sweep blob generation for the record store.
 
+use crate::data::get_site;
 use crate::data::keys::*;
+use crate::nexrad::dealias::{dealias_sweep, DealiasReference, DEALIASED_VELOCITY};
+use crate::nexrad::derived::VolumeProductAccumulator;
+use crate::nexrad::hydro_class::{classify_sweep, DualPolMoments, HYDROMETEOR_CLASS};
 use std::collections::HashMap;
 
 pub(crate) const PRODUCTS: &[(nexrad_render::Product, &str)] = &[
@@ -23,11 +27,88 @@ pub(crate) const PRODUCTS: &[(nexrad_render::Product, &str)] = &[
         nexrad_render::Product::DifferentialPhase,
         "differential_phase",
     ),
+    (
+        nexrad_render::Product::ClutterFilterPower,
+        "clutter_filter_power",
+    ),
 ];
 
+/// Nyquist velocity in m/s per elevation number, taken from the radial data
+/// blocks of the decoded messages. The model radials don't carry it.
+pub(crate) type NyquistByElevation = HashMap<u8, f32>;
+
+/// Extract one product's sweep from sorted radials. CFP is always stored as
+/// 16-bit so its blobs have a fixed layout regardless of the source word size.
+/// Velocity sweeps record the elevation's Nyquist velocity for dealiasing.
+fn extract_product_sweep(
+    sorted_radials: &[&::nexrad::model::data::Radial],
+    product: nexrad_render::Product,
+    product_name: &str,
+    nyquist_velocity: Option<f32>,
+) -> Option<PrecomputedSweep> {
+    let mut sweep =
+        crate::nexrad::record_decode::extract_sweep_data_from_sorted(sorted_radials, product)?;
+    if product_name == "clutter_filter_power" {
+        sweep.gate_values = sweep.gate_values.into_u16();
+    }
+    if product_name == "velocity" {
+        sweep.nyquist_velocity = nyquist_velocity;
+    }
+    Some(sweep)
+}
+
+/// Convert a radar data message into a radial, first noting the Nyquist
+/// velocity from a Message 31 radial data block. Legacy Message 1 data
+/// leaves `nyquist` untouched.
+fn push_radial(
+    msg: nexrad_decode::messages::Message<'_>,
+    radials: &mut Vec<::nexrad::model::data::Radial>,
+    nyquist: &mut NyquistByElevation,
+) {
+    use nexrad_decode::messages::MessageContents;
+
+    match msg.into_contents() {
+        MessageContents::DigitalRadarData(m) => {
+            if let Some(block) = m.radial_data_block() {
+                let raw = block.nyquist_velocity_raw();
+                if raw > 0 {
+                    // Scaled by 100 (0.01 m/s units).
+                    nyquist.insert(m.header().elevation_number(), raw as f32 * 0.01);
+                }
+            }
+            if let Ok(radial) = m.into_radial() {
+                radials.push(radial);
+            }
+        }
+        MessageContents::DigitalRadarDataLegacy(m) => {
+            if let Ok(radial) = m.into_radial() {
+                radials.push(radial);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Decode a record's radials. Unlike `Record::radials`, this also records
+/// each elevation's Nyquist velocity, and skips radials that fail to decode
+/// instead of dropping the whole record.
+pub(crate) fn decode_radials(
+    record: &nexrad_data::volume::Record<'_>,
+    nyquist: &mut NyquistByElevation,
+) -> Vec<::nexrad::model::data::Radial> {
+    let mut radials = Vec::new();
+    if let Ok(messages) = record.messages() {
+        for msg in messages {
+            push_radial(msg, &mut radials, nyquist);
+        }
+    }
+    radials
+}
+
 pub(crate) fn decode_with_vcp_extraction<'a>(
     messages: impl IntoIterator<Item = nexrad_decode::messages::Message<'a>>,
     extracted_vcp: &mut Option<ExtractedVcp>,
+    nyquist: &mut NyquistByElevation,
 ) -> Vec<::nexrad::model::data::Radial> {
     use nexrad_decode::messages::MessageContents;
 
@@ -92,19 +173,7 @@ pub(crate) fn decode_with_vcp_extraction<'a>(
             }
             _ => {}
         }
-        match msg.into_contents() {
-            MessageContents::DigitalRadarData(m) => {
-                if let Ok(radial) = m.into_radial() {
-                    radials.push(radial);
-                }
-            }
-            MessageContents::DigitalRadarDataLegacy(m) => {
-                if let Ok(radial) = m.into_radial() {
-                    radials.push(radial);
-                }
-            }
-            _ => {}
-        }
+        push_radial(msg, &mut radials, nyquist);
     }
     radials
 }
@@ -117,19 +186,19 @@ pub(crate) struct DecodeResult {
     pub compressed_count: u32,
     pub extracted_vcp: Option<ExtractedVcp>,
     pub has_vcp: bool,
+    pub nyquist: NyquistByElevation,
 }
 
 pub(crate) fn decompress_and_decode_records(
     records: &[nexrad_data::volume::Record<'_>],
-) -> Result<DecodeResult, wasm_bindgen::JsValue> {
-    use crate::nexrad::record_decode::decode_record_to_radials;
-
+) -> Result<DecodeResult, String> {
     let mut decompress_ms_total = 0.0f64;
     let mut decode_only_ms = 0.0f64;
     let mut all_radials: Vec<::nexrad::model::data::Radial> = Vec::new();
     let mut radial_metas: Vec<(i64, u8, f32, f32)> = Vec::new();
     let mut has_vcp = false;
     let mut extracted_vcp: Option<ExtractedVcp> = None;
+    let mut nyquist = NyquistByElevation::new();
     let mut compressed_count = 0u32;
 
     for (record_id, record) in records.iter().enumerate() {
@@ -138,12 +207,9 @@ pub(crate) fn decompress_and_decode_records(
         let radials = if record.compressed() {
             compressed_count += 1;
             let t_decompress = web_time::Instant::now();
-            let decompressed = record.decompress().map_err(|e| {
-                wasm_bindgen::JsValue::from_str(&format!(
-                    "Failed to decompress record {}: {}",
-                    record_id, e
-                ))
-            })?;
+            let decompressed = record
+                .decompress()
+                .map_err(|e| format!("Failed to decompress record {}: {}", record_id, e))?;
             decompress_ms_total += t_decompress.elapsed().as_secs_f64() * 1000.0;
             let t_radials = web_time::Instant::now();
 
@@ -153,18 +219,18 @@ pub(crate) fn decompress_and_decode_records(
                 .unwrap_or(true);
             let r = if needs_vcp {
                 match decompressed.messages() {
-                    Ok(msgs) => decode_with_vcp_extraction(msgs, &mut extracted_vcp),
+                    Ok(msgs) => decode_with_vcp_extraction(msgs, &mut extracted_vcp, &mut nyquist),
                     Err(_) => Vec::new(),
                 }
             } else {
-                decompressed.radials().unwrap_or_default()
+                decode_radials(&decompressed, &mut nyquist)
             };
 
             decode_only_ms += t_radials.elapsed().as_secs_f64() * 1000.0;
             r
         } else {
             let t_radials = web_time::Instant::now();
-            let r = decode_record_to_radials(record.data()).unwrap_or_default();
+            let r = decode_radials(record, &mut nyquist);
             decode_only_ms += t_radials.elapsed().as_secs_f64() * 1000.0;
             r
         };
@@ -194,6 +260,7 @@ pub(crate) fn decompress_and_decode_records(
         compressed_count,
         extracted_vcp,
         has_vcp,
+        nyquist,
     })
 }
 
@@ -226,44 +293,139 @@ pub(crate) fn extract_sweep_blobs(
     by_elevation: &HashMap<u8, Vec<&::nexrad::model::data::Radial>>,
     elevation_numbers: &[u8],
     scan_key: &ScanKey,
+    dealias_velocity: bool,
+    nyquist: &NyquistByElevation,
 ) -> ExtractedSweepBlobs {
-    use crate::nexrad::record_decode::extract_sweep_data_from_sorted;
-
     let mut blobs: Vec<(String, Vec<u8>)> = Vec::new();
     let mut products_by_elev: HashMap<u8, Vec<String>> = HashMap::new();
+    // Elevations are walked bottom-up so each velocity tilt is dealiased
+    // against the one below it.
+    let mut dealias_reference: Option<DealiasReference> = None;
+    let mut volume = VolumeProductAccumulator::new();
     for &elev_num in elevation_numbers {
         if let Some(sorted_radials) = by_elevation.get(&elev_num) {
+            let mut moments: Vec<(&str, PrecomputedSweep)> = Vec::new();
             for (product, product_name) in PRODUCTS {
-                if let Some(sweep) = extract_sweep_data_from_sorted(sorted_radials, *product) {
+                if let Some(sweep) = extract_product_sweep(
+                    sorted_radials,
+                    *product,
+                    product_name,
+                    nyquist.get(&elev_num).copied(),
+                ) {
                     let key = SweepDataKey::new(scan_key.clone(), elev_num, *product_name);
                     blobs.push((key.to_storage_key(), sweep.to_bytes()));
-                    products_by_elev
-                        .entry(elev_num)
-                        .or_default()
-                        .push((*product_name).to_string());
+                    let products = products_by_elev.entry(elev_num).or_default();
+                    products.push((*product_name).to_string());
+
+                    if *product_name == "reflectivity" {
+                        volume.add_sweep(&sweep);
+                    }
+
+                    if dealias_velocity && *product_name == "velocity" {
+                        if let Some(blob) =
+                            dealias_blob(&sweep, scan_key, elev_num, &mut dealias_reference)
+                        {
+                            blobs.push(blob);
+                            products.push(DEALIASED_VELOCITY.to_string());
+                        }
+                    }
+                    moments.push((*product_name, sweep));
                 }
             }
+
+            if let Some(blob) = hydro_class_blob(&moments, scan_key, elev_num) {
+                blobs.push(blob);
+                products_by_elev
+                    .entry(elev_num)
+                    .or_default()
+                    .push(HYDROMETEOR_CLASS.to_string());
+            }
         }
     }
+
+    if let Some(&base_elev) = elevation_numbers.first() {
+        let (volume_blobs, volume_products) = volume_product_blobs(volume, scan_key, base_elev);
+        blobs.extend(volume_blobs);
+        products_by_elev
+            .entry(base_elev)
+            .or_default()
+            .extend(volume_products);
+    }
+
     ExtractedSweepBlobs {
         blobs,
         products_by_elev,
     }
 }
 
+/// Encode the finished volume products as `sweep_data` blobs stored at
+/// `base_elev` (the volume's lowest elevation number). Also returns the
+/// product strings to list in that elevation's `available_products`.
+pub(crate) fn volume_product_blobs(
+    volume: VolumeProductAccumulator,
+    scan_key: &ScanKey,
+    base_elev: u8,
+) -> (Vec<(String, Vec<u8>)>, Vec<String>) {
+    // Sites missing from the catalog leave echo tops above radar level.
+    let site_altitude_km = get_site(&scan_key.site.0).map_or(0.0, |site| site.altitude_km());
+    volume
+        .finish(site_altitude_km)
+        .into_iter()
+        .map(|(product_name, sweep)| {
+            let key = SweepDataKey::new(scan_key.clone(), base_elev, product_name);
+            (
+                (key.to_storage_key(), sweep.to_bytes()),
+                product_name.to_string(),
+            )
+        })
+        .unzip()
+}
+
+/// Classify hydrometeors from one elevation's extracted moments and encode
+/// the result as a `sweep_data` blob.
+fn hydro_class_blob(
+    moments: &[(&str, PrecomputedSweep)],
+    scan_key: &ScanKey,
+    elev_num: u8,
+) -> Option<(String, Vec<u8>)> {
+    let find = |name: &str| moments.iter().find(|(n, _)| *n == name).map(|(_, s)| s);
+    let sweep = classify_sweep(&DualPolMoments {
+        reflectivity: find("reflectivity")?,
+        differential_reflectivity: find("differential_reflectivity"),
+        correlation_coefficient: find("correlation_coefficient"),
+        differential_phase: find("differential_phase"),
+    })?;
+    let key = SweepDataKey::new(scan_key.clone(), elev_num, HYDROMETEOR_CLASS);
+    Some((key.to_storage_key(), sweep.to_bytes()))
+}
+
+/// Dealias a velocity sweep and encode it as a `sweep_data` blob, advancing
+/// `reference` to the new sweep.
+fn dealias_blob(
+    sweep: &PrecomputedSweep,
+    scan_key: &ScanKey,
+    elev_num: u8,
+    reference: &mut Option<DealiasReference>,
+) -> Option<(String, Vec<u8>)> {
+    let (dealiased, next_reference) = dealias_sweep(sweep, reference.as_ref())?;
+    *reference = Some(next_reference);
+    let key = SweepDataKey::new(scan_key.clone(), elev_num, DEALIASED_VELOCITY);
+    Some((key.to_storage_key(), dealiased.to_bytes()))
+}
+
 pub(crate) struct ChunkDecodeResult {
     pub chunk_radials: Vec<::nexrad::model::data::Radial>,
     pub chunk_vcp: Option<ExtractedVcp>,
     pub chunk_has_vcp: bool,
+    pub chunk_nyquist: NyquistByElevation,
     pub volume_header_time_secs: Option<f64>,
 }
 
 pub(crate) fn decode_start_chunk(data: Vec<u8>, accum_has_full_vcp: bool) -> ChunkDecodeResult {
-    use crate::nexrad::record_decode::decode_record_to_radials;
-
     let mut chunk_radials: Vec<::nexrad::model::data::Radial> = Vec::new();
     let mut chunk_vcp: Option<ExtractedVcp> = None;
     let mut chunk_has_vcp = false;
+    let mut chunk_nyquist = NyquistByElevation::new();
     let mut volume_header_time_secs: Option<f64> = None;
 
     let file = nexrad_data::volume::File::new(data);
@@ -282,6 +444,7 @@ pub(crate) fn decode_start_chunk(data: Vec<u8>, accum_has_full_vcp: bool) -> Chu
                 chunk_radials,
                 chunk_vcp,
                 chunk_has_vcp,
+                chunk_nyquist,
                 volume_header_time_secs,
             };
         }
@@ -298,11 +461,15 @@ pub(crate) fn decode_start_chunk(data: Vec<u8>, accum_has_full_vcp: bool) -> Chu
                             .unwrap_or(true)
                     {
                         if let Ok(msgs) = decompressed.messages() {
-                            let r = decode_with_vcp_extraction(msgs, &mut chunk_vcp);
+                            let r = decode_with_vcp_extraction(
+                                msgs,
+                                &mut chunk_vcp,
+                                &mut chunk_nyquist,
+                            );
                             chunk_radials.extend(r);
                         }
                     } else {
-                        chunk_radials.extend(decompressed.radials().unwrap_or_default());
+                        chunk_radials.extend(decode_radials(&decompressed, &mut chunk_nyquist));
                     }
                 }
                 Err(e) => {
@@ -310,7 +477,7 @@ pub(crate) fn decode_start_chunk(data: Vec<u8>, accum_has_full_vcp: bool) -> Chu
                 }
             }
         } else {
-            chunk_radials.extend(decode_record_to_radials(record.data()).unwrap_or_default());
+            chunk_radials.extend(decode_radials(record, &mut chunk_nyquist));
         }
         if chunk_vcp.is_some() {
             chunk_has_vcp = true;
@@ -321,6 +488,7 @@ pub(crate) fn decode_start_chunk(data: Vec<u8>, accum_has_full_vcp: bool) -> Chu
         chunk_radials,
         chunk_vcp,
         chunk_has_vcp,
+        chunk_nyquist,
         volume_header_time_secs,
     }
 }
@@ -330,11 +498,11 @@ pub(crate) fn decode_subsequent_chunk(
     accum_has_full_vcp: bool,
     chunk_index: u32,
 ) -> ChunkDecodeResult {
-    use crate::nexrad::record_decode::decode_record_to_radials;
     use nexrad_data::volume::Record;
 
     let mut chunk_radials: Vec<::nexrad::model::data::Radial> = Vec::new();
     let mut chunk_vcp: Option<ExtractedVcp> = None;
+    let mut chunk_nyquist = NyquistByElevation::new();
 
     let record = Record::from_slice(data);
 
@@ -343,11 +511,12 @@ pub(crate) fn decode_subsequent_chunk(
             Ok(decompressed) => {
                 if !accum_has_full_vcp {
                     if let Ok(msgs) = decompressed.messages() {
-                        let r = decode_with_vcp_extraction(msgs, &mut chunk_vcp);
+                        let r =
+                            decode_with_vcp_extraction(msgs, &mut chunk_vcp, &mut chunk_nyquist);
                         chunk_radials.extend(r);
                     }
                 } else {
-                    chunk_radials.extend(decompressed.radials().unwrap_or_default());
+                    chunk_radials.extend(decode_radials(&decompressed, &mut chunk_nyquist));
                 }
             }
             Err(e) => {
@@ -355,13 +524,14 @@ pub(crate) fn decode_subsequent_chunk(
             }
         }
     } else {
-        chunk_radials.extend(decode_record_to_radials(record.data()).unwrap_or_default());
+        chunk_radials.extend(decode_radials(&record, &mut chunk_nyquist));
     }
 
     ChunkDecodeResult {
         chunk_radials,
         chunk_vcp: chunk_vcp.clone(),
         chunk_has_vcp: chunk_vcp.is_some(),
+        chunk_nyquist,
         volume_header_time_secs: None,
     }
 }
@@ -443,14 +613,17 @@ pub(crate) fn compute_chunk_time_spans(
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 pub(crate) fn build_flush_sweep_blobs(
     all_radials: &[::nexrad::model::data::Radial],
     radial_metas: &[(i64, u8, f32, f32)],
     newly_completed: &[u8],
     scan_key: &ScanKey,
+    dealias_velocity: bool,
+    nyquist: &NyquistByElevation,
+    dealias_reference: &mut Option<DealiasReference>,
+    volume: &mut VolumeProductAccumulator,
 ) -> (Vec<(String, Vec<u8>)>, Vec<SweepMeta>) {
-    use crate::nexrad::record_decode::extract_sweep_data_from_sorted;
-
     let by_elevation = group_radials_by_elevation(all_radials);
 
     let mut blobs: Vec<(String, Vec<u8>)> = Vec::new();
@@ -459,14 +632,39 @@ pub(crate) fn build_flush_sweep_blobs(
     for &elev_num in newly_completed {
         if let Some(sorted_radials) = by_elevation.get(&elev_num) {
             let mut available_products: Vec<String> = Vec::new();
+            let mut moments: Vec<(&str, PrecomputedSweep)> = Vec::new();
             for (product, product_name) in PRODUCTS {
-                if let Some(sweep) = extract_sweep_data_from_sorted(sorted_radials, *product) {
+                if let Some(sweep) = extract_product_sweep(
+                    sorted_radials,
+                    *product,
+                    product_name,
+                    nyquist.get(&elev_num).copied(),
+                ) {
                     let key = SweepDataKey::new(scan_key.clone(), elev_num, *product_name);
                     blobs.push((key.to_storage_key(), sweep.to_bytes()));
                     available_products.push((*product_name).to_string());
+
+                    if *product_name == "reflectivity" {
+                        volume.add_sweep(&sweep);
+                    }
+
+                    if dealias_velocity && *product_name == "velocity" {
+                        if let Some(blob) =
+                            dealias_blob(&sweep, scan_key, elev_num, dealias_reference)
+                        {
+                            blobs.push(blob);
+                            available_products.push(DEALIASED_VELOCITY.to_string());
+                        }
+                    }
+                    moments.push((*product_name, sweep));
                 }
             }
 
+            if let Some(blob) = hydro_class_blob(&moments, scan_key, elev_num) {
+                blobs.push(blob);
+                available_products.push(HYDROMETEOR_CLASS.to_string());
+            }
+
             let elev_metas: Vec<&(i64, u8, f32, f32)> = radial_metas
                 .iter()
                 .filter(|(_, en, _, _)| *en == elev_num)
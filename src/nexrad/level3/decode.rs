@@ -0,0 +1,614 @@
+//! Level III product decoding.
+//!
+//! A product is an optional WMO/AWIPS text header followed by the message
+//! header (18 bytes), the product description block (102 bytes) and the
+//! symbology block, which digital products bzip2-compress. The first
+//! symbology layer holds one data packet:
+//!
+//! - `16`: digital radial data, one byte per range bin,
+//! - `AF1F`: run-length encoded radials of 16 levels,
+//! - `BA0F` / `BA07`: run-length encoded raster rows of 16 levels.
+//!
+//! Data levels become physical values through the product's threshold
+//! halfwords (linear, scale/offset, VIL, echo-top or 16-level tables) and
+//! are stored as an ordinary `PrecomputedSweep`. Rasters are resampled to
+//! a polar grid around the radar so the renderer can draw them like any
+//! other sweep.
+
+use super::products::Level3Product;
+use crate::data::keys::{GateValues, PrecomputedSweep};
+use std::io::Read;
+
+/// Message header plus product description block.
+const HEADER_LEN: usize = 120;
+
+/// Longest WMO/AWIPS text header searched for the message start.
+const MAX_TEXT_HEADER: usize = 128;
+
+/// Products carry no end time; the sweep spans from the volume start to
+/// the generation time, capped at this many seconds.
+const MAX_PRODUCT_SPAN_SECS: i64 = 600;
+
+/// Raster products are resampled to this many azimuths.
+const RASTER_AZIMUTHS: usize = 720;
+
+/// A decoded product.
+pub struct Level3Data {
+    pub product: Level3Product,
+    /// Start of the volume scan the product was built from, Unix seconds.
+    pub volume_start_secs: i64,
+    pub sweep: PrecomputedSweep,
+}
+
+/// Decode a Level III product file.
+pub fn decode_product(data: &[u8]) -> Result<Level3Data, String> {
+    let start = find_message(data).ok_or("no Level III message header")?;
+    let message = &data[start..];
+
+    let code = halfword(message, 30)?;
+    let product = Level3Product::ALL
+        .into_iter()
+        .find(|p| p.product_code() == code)
+        .ok_or_else(|| format!("unsupported product code {}", code))?;
+
+    let volume_start_secs = julian_secs(halfword(message, 40)?, word(message, 42)?);
+    let generated_secs = julian_secs(halfword(message, 46)?, word(message, 48)?);
+    let end_secs = generated_secs.clamp(
+        volume_start_secs + 1,
+        volume_start_secs + MAX_PRODUCT_SPAN_SECS,
+    );
+    // Tilt-based products carry their elevation; the rest are 2-D fields.
+    let elevation_deg = match product {
+        Level3Product::Dvl | Level3Product::Eet | Level3Product::Ntp | Level3Product::Ncr => 0.0,
+        _ => halfword(message, 58)? as f32 / 10.0,
+    };
+    let thresholds = Thresholds::read(product, &message[60..92]);
+
+    let compressed = halfword(message, 100)? == 1 && message[HEADER_LEN..].starts_with(b"BZh");
+    let body = if compressed {
+        let mut body = message[..HEADER_LEN].to_vec();
+        bzip2_rs::DecoderReader::new(&message[HEADER_LEN..])
+            .read_to_end(&mut body)
+            .map_err(|e| format!("failed to decompress symbology: {}", e))?;
+        body
+    } else {
+        message.to_vec()
+    };
+
+    let symbology = word(&body, 108)? as usize * 2;
+    if symbology < HEADER_LEN || halfword(&body, symbology)? != -1 {
+        return Err("no symbology block".to_string());
+    }
+    let packet = symbology + 16;
+    let values = Encoder::new(product, &thresholds);
+    let mut sweep = match halfword(&body, packet)? as u16 {
+        0x0010 => {
+            let radials = read_digital_radials(&body, packet)?;
+            values.radial_sweep(&radials)
+        }
+        0xAF1F => {
+            let radials = read_rle_radials(&body, packet)?;
+            values.radial_sweep(&radials)
+        }
+        0xBA0F | 0xBA07 => {
+            let rows = read_raster(&body, packet)?;
+            values.raster_sweep(&rows)
+        }
+        other => return Err(format!("unsupported data packet {:#06X}", other)),
+    };
+    sweep.mean_elevation = elevation_deg;
+    sweep.sweep_start_secs = volume_start_secs as f64;
+    sweep.sweep_end_secs = end_secs as f64;
+
+    Ok(Level3Data {
+        product,
+        volume_start_secs,
+        sweep,
+    })
+}
+
+/// Offset of the message header: the first position where a plausible
+/// product code is followed, 18 bytes on, by the description block's -1
+/// divider.
+fn find_message(data: &[u8]) -> Option<usize> {
+    let last = MAX_TEXT_HEADER.min(data.len().checked_sub(HEADER_LEN)?);
+    (0..=last).find(|&o| {
+        let code = i16::from_be_bytes([data[o], data[o + 1]]);
+        (16..=299).contains(&code) && data[o + 18..o + 20] == [0xFF, 0xFF]
+    })
+}
+
+fn halfword(data: &[u8], offset: usize) -> Result<i16, String> {
+    data.get(offset..offset + 2)
+        .map(|b| i16::from_be_bytes([b[0], b[1]]))
+        .ok_or_else(|| format!("product truncated at byte {}", offset))
+}
+
+fn word(data: &[u8], offset: usize) -> Result<i32, String> {
+    data.get(offset..offset + 4)
+        .map(|b| i32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+        .ok_or_else(|| format!("product truncated at byte {}", offset))
+}
+
+/// Unix seconds from a NEXRAD date (day 1 is 1970-01-01) and seconds of day.
+fn julian_secs(date: i16, secs: i32) -> i64 {
+    (date as i64 - 1) * 86_400 + secs as i64
+}
+
+/// Decoder for the 16-bit floats of the DVL thresholds: sign, 5-bit
+/// exponent biased by 16, 10-bit fraction.
+fn nexrad_f16(bits: u16) -> f32 {
+    let sign = if bits & 0x8000 != 0 { -1.0 } else { 1.0 };
+    let exponent = ((bits >> 10) & 0x1F) as i32;
+    let fraction = (bits & 0x3FF) as f32 / 1024.0;
+    if exponent == 0 {
+        sign * 2.0 * fraction
+    } else {
+        sign * 2f32.powi(exponent - 16) * (1.0 + fraction)
+    }
+}
+
+/// One entry of a 16-level threshold table: flags in the high byte, value
+/// in the low byte. Special entries (no data, range folded) have no value.
+fn legacy_level(entry: u16) -> Option<f32> {
+    let flags = (entry >> 8) as u8;
+    if flags & 0x80 != 0 {
+        return None;
+    }
+    let mut value = (entry & 0xFF) as f32;
+    if flags & 0x40 != 0 {
+        value /= 100.0;
+    } else if flags & 0x20 != 0 {
+        value /= 20.0;
+    } else if flags & 0x10 != 0 {
+        value /= 10.0;
+    }
+    if flags & 0x01 != 0 {
+        value = -value;
+    }
+    Some(value)
+}
+
+/// How data levels map to values, read from the 16 threshold halfwords.
+/// Levels 0 and 1 of the digital products are flags (below threshold,
+/// range folded) and carry no value.
+#[derive(Debug, PartialEq)]
+enum Thresholds {
+    Linear {
+        min: f32,
+        increment: f32,
+    },
+    ScaleOffset {
+        scale: f32,
+        offset: f32,
+    },
+    Vil {
+        linear_scale: f32,
+        linear_offset: f32,
+        log_start: u16,
+        log_scale: f32,
+        log_offset: f32,
+    },
+    EchoTops {
+        mask: u16,
+        scale: f32,
+        offset: f32,
+    },
+    Levels([Option<f32>; 16]),
+}
+
+impl Thresholds {
+    fn read(product: Level3Product, raw: &[u8]) -> Self {
+        let hw = |i: usize| u16::from_be_bytes([raw[i * 2], raw[i * 2 + 1]]);
+        let f32_at = |i: usize| f32::from_bits(((hw(i) as u32) << 16) | hw(i + 1) as u32);
+        match product {
+            Level3Product::N0q
+            | Level3Product::N0u
+            | Level3Product::Tz0
+            | Level3Product::Tv0
+            | Level3Product::Tzl => Thresholds::Linear {
+                min: hw(0) as i16 as f32 / 10.0,
+                increment: hw(1) as i16 as f32 / 10.0,
+            },
+            Level3Product::N0c | Level3Product::N0x => Thresholds::ScaleOffset {
+                scale: f32_at(0),
+                offset: f32_at(2),
+            },
+            Level3Product::Dvl => Thresholds::Vil {
+                linear_scale: nexrad_f16(hw(0)),
+                linear_offset: nexrad_f16(hw(1)),
+                log_start: hw(2),
+                log_scale: nexrad_f16(hw(3)),
+                log_offset: nexrad_f16(hw(4)),
+            },
+            Level3Product::Eet => Thresholds::EchoTops {
+                mask: hw(0),
+                scale: hw(1).max(1) as f32,
+                offset: hw(2) as f32,
+            },
+            Level3Product::Ntp | Level3Product::Ncr => {
+                Thresholds::Levels(std::array::from_fn(|i| legacy_level(hw(i))))
+            }
+        }
+    }
+
+    /// Value of a data level in the product's native unit.
+    fn value(&self, level: u8) -> Option<f32> {
+        let n = level as f32;
+        match *self {
+            Thresholds::Levels(ref levels) => levels.get(level as usize).copied().flatten(),
+            _ if level < 2 => None,
+            Thresholds::Linear { min, increment } => Some(min + (n - 2.0) * increment),
+            Thresholds::ScaleOffset { scale, offset } => Some((n - offset) / scale),
+            Thresholds::Vil {
+                linear_scale,
+                linear_offset,
+                log_start,
+                log_scale,
+                log_offset,
+            } => Some(if (level as u16) < log_start {
+                (n - linear_offset) / linear_scale
+            } else {
+                ((n - log_offset) / log_scale).exp()
+            }),
+            Thresholds::EchoTops {
+                mask,
+                scale,
+                offset,
+            } => Some(((level as u16 & mask) as f32 / scale) - offset),
+        }
+    }
+}
+
+/// Radials read from a radial data packet.
+struct Radials {
+    first_bin: u16,
+    bins: usize,
+    /// `(start azimuth, width, levels)` per radial, degrees.
+    radials: Vec<(f32, f32, Vec<u8>)>,
+}
+
+/// Packet 16: header, then per radial a byte count, start angle and width
+/// (tenths of a degree) and one byte per bin, padded to a halfword.
+fn read_digital_radials(body: &[u8], packet: usize) -> Result<Radials, String> {
+    read_radials(body, packet, |bytes, _| bytes.to_vec(), 1)
+}
+
+/// Packet AF1F: like packet 16, but the count is in halfwords and each byte
+/// is a run length (high nibble) and level (low nibble).
+fn read_rle_radials(body: &[u8], packet: usize) -> Result<Radials, String> {
+    read_radials(body, packet, expand_runs, 2)
+}
+
+fn read_radials(
+    body: &[u8],
+    packet: usize,
+    levels: impl Fn(&[u8], usize) -> Vec<u8>,
+    count_unit: usize,
+) -> Result<Radials, String> {
+    let first_bin = halfword(body, packet + 2)?.max(0) as u16;
+    let bins = halfword(body, packet + 4)?.max(0) as usize;
+    let count = halfword(body, packet + 12)?.max(0) as usize;
+    let mut radials = Vec::with_capacity(count);
+    let mut at = packet + 14;
+    for _ in 0..count {
+        let len = halfword(body, at)?.max(0) as usize * count_unit;
+        let start = halfword(body, at + 2)? as f32 / 10.0;
+        let width = halfword(body, at + 4)? as f32 / 10.0;
+        let bytes = body
+            .get(at + 6..at + 6 + len)
+            .ok_or("radial data truncated")?;
+        let mut row = levels(bytes, bins);
+        row.resize(bins, 0);
+        radials.push((start, width, row));
+        at += 6 + len + len % 2;
+    }
+    Ok(Radials {
+        first_bin,
+        bins,
+        radials,
+    })
+}
+
+/// Expand run-length bytes into at most `limit` levels.
+fn expand_runs(bytes: &[u8], limit: usize) -> Vec<u8> {
+    let mut levels = Vec::with_capacity(bytes.len() * 15);
+    for &b in bytes {
+        let run = (b >> 4) as usize;
+        levels.extend(std::iter::repeat_n(b & 0x0F, run));
+    }
+    levels.truncate(limit);
+    levels
+}
+
+/// Raster packet: header, then per row a byte count and run-length bytes.
+fn read_raster(body: &[u8], packet: usize) -> Result<Vec<Vec<u8>>, String> {
+    let rows = halfword(body, packet + 18)?.max(0) as usize;
+    let mut out = Vec::with_capacity(rows);
+    let mut at = packet + 22;
+    for _ in 0..rows {
+        let len = halfword(body, at)?.max(0) as usize;
+        let bytes = body
+            .get(at + 2..at + 2 + len)
+            .ok_or("raster row truncated")?;
+        out.push(expand_runs(bytes, usize::MAX));
+        at += 2 + len;
+    }
+    Ok(out)
+}
+
+/// Turns data levels into stored values for one product.
+struct Encoder {
+    product: Level3Product,
+    /// Stored value of every data level (0 = no data).
+    raw_by_level: [u16; 256],
+}
+
+impl Encoder {
+    fn new(product: Level3Product, thresholds: &Thresholds) -> Self {
+        let (scale, offset) = product.encoding();
+        let factor = product.unit_factor();
+        let raw_by_level = std::array::from_fn(|level| {
+            thresholds
+                .value(level as u8)
+                .map(|v| {
+                    (v * factor * scale + offset)
+                        .round()
+                        .clamp(2.0, u16::MAX as f32) as u16
+                })
+                .unwrap_or(0)
+        });
+        Self {
+            product,
+            raw_by_level,
+        }
+    }
+
+    fn sweep(
+        &self,
+        azimuths: Vec<f32>,
+        gate_count: usize,
+        first_gate_km: f64,
+        raw: Vec<u16>,
+    ) -> PrecomputedSweep {
+        let (scale, offset) = self.product.encoding();
+        let gate_km = self.product.gate_km();
+        PrecomputedSweep {
+            azimuth_count: azimuths.len() as u32,
+            gate_count: gate_count as u32,
+            first_gate_range_km: first_gate_km,
+            gate_interval_km: gate_km,
+            max_range_km: first_gate_km + gate_count as f64 * gate_km,
+            scale,
+            offset,
+            radial_count: azimuths.len() as u32,
+            mean_elevation: 0.0,
+            nyquist_velocity: None,
+            sweep_start_secs: 0.0,
+            sweep_end_secs: 0.0,
+            azimuths,
+            radial_times: Vec::new(),
+            gate_values: GateValues::U16(raw),
+        }
+    }
+
+    /// Radials sorted by their centre azimuth.
+    fn radial_sweep(&self, radials: &Radials) -> PrecomputedSweep {
+        let mut order: Vec<(f32, &Vec<u8>)> = radials
+            .radials
+            .iter()
+            .map(|(start, width, levels)| ((start + width / 2.0).rem_euclid(360.0), levels))
+            .collect();
+        order.sort_by(|a, b| a.0.total_cmp(&b.0));
+        let raw = order
+            .iter()
+            .flat_map(|(_, levels)| levels.iter().map(|&l| self.raw_by_level[l as usize]))
+            .collect();
+        let first_gate_km = radials.first_bin as f64 * self.product.gate_km();
+        self.sweep(
+            order.iter().map(|(az, _)| *az).collect(),
+            radials.bins,
+            first_gate_km,
+            raw,
+        )
+    }
+
+    /// Raster centred on the radar, row 0 to the north, resampled by
+    /// nearest cell onto half-degree radials out to the nearest edge.
+    fn raster_sweep(&self, rows: &[Vec<u8>]) -> PrecomputedSweep {
+        let columns = rows.iter().map(Vec::len).max().unwrap_or(0);
+        let gates = rows.len().min(columns) / 2;
+        let (center_row, center_col) = (rows.len() as f64 / 2.0, columns as f64 / 2.0);
+        let azimuths: Vec<f32> = (0..RASTER_AZIMUTHS)
+            .map(|i| (i as f32 + 0.5) * 360.0 / RASTER_AZIMUTHS as f32)
+            .collect();
+        let mut raw = Vec::with_capacity(RASTER_AZIMUTHS * gates);
+        for az in &azimuths {
+            let (sin, cos) = (*az as f64).to_radians().sin_cos();
+            for gate in 0..gates {
+                let r = gate as f64 + 0.5;
+                let row = (center_row - r * cos) as usize;
+                let col = (center_col + r * sin) as usize;
+                let level = rows
+                    .get(row)
+                    .and_then(|cells| cells.get(col))
+                    .copied()
+                    .unwrap_or(0);
+                raw.push(self.raw_by_level[level as usize]);
+            }
+        }
+        self.sweep(azimuths, gates, 0.0, raw)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A product with a WMO header, no compression and `packet` as its
+    /// only symbology packet. Volume 2024-05-01 12:00:00, generated 30 s later.
+    fn product(code: i16, thresholds: [u16; 16], elevation_tenths: i16, packet: &[u8]) -> Vec<u8> {
+        let mut m = vec![0u8; HEADER_LEN];
+        let put16 =
+            |m: &mut Vec<u8>, at: usize, v: u16| m[at..at + 2].copy_from_slice(&v.to_be_bytes());
+        let put32 =
+            |m: &mut Vec<u8>, at: usize, v: u32| m[at..at + 4].copy_from_slice(&v.to_be_bytes());
+        put16(&mut m, 0, code as u16);
+        put16(&mut m, 18, 0xFFFF);
+        put16(&mut m, 30, code as u16);
+        put16(&mut m, 40, 19_845);
+        put32(&mut m, 42, 43_200);
+        put16(&mut m, 46, 19_845);
+        put32(&mut m, 48, 43_230);
+        put16(&mut m, 58, elevation_tenths as u16);
+        for (i, t) in thresholds.iter().enumerate() {
+            put16(&mut m, 60 + i * 2, *t);
+        }
+        put32(&mut m, 108, (HEADER_LEN / 2) as u32);
+        // Symbology block and layer headers.
+        m.extend_from_slice(&[0xFF, 0xFF, 0, 1, 0, 0, 0, 0, 0, 1, 0xFF, 0xFF, 0, 0, 0, 0]);
+        m.extend_from_slice(packet);
+        let mut file = b"SDUS54 KOUN 011200\r\r\nN0QTLX\r\r\n".to_vec();
+        file.extend_from_slice(&m);
+        file
+    }
+
+    fn be(values: &[i16]) -> Vec<u8> {
+        values.iter().flat_map(|v| v.to_be_bytes()).collect()
+    }
+
+    fn raw_at(sweep: &PrecomputedSweep, radial: usize, gate: usize) -> u16 {
+        match &sweep.gate_values {
+            GateValues::U16(v) => v[radial * sweep.gate_count as usize + gate],
+            GateValues::U8(v) => v[radial * sweep.gate_count as usize + gate] as u16,
+        }
+    }
+
+    fn value_at(sweep: &PrecomputedSweep, radial: usize, gate: usize) -> Option<f32> {
+        let raw = raw_at(sweep, radial, gate);
+        (raw > 1).then(|| (raw as f32 - sweep.offset) / sweep.scale)
+    }
+
+    #[test]
+    fn decodes_digital_radials() {
+        let mut thresholds = [0u16; 16];
+        thresholds[0] = (-320i16) as u16;
+        thresholds[1] = 5;
+        // Two radials given out of order, three bins each (odd, so padded).
+        let mut packet = be(&[0x0010, 4, 3, 0, 0, 999, 2]);
+        packet.extend(be(&[3, 10, 10]));
+        packet.extend([0, 2, 106, 0]);
+        packet.extend(be(&[3, 3595, 10]));
+        packet.extend([1, 130, 255, 0]);
+        let data = decode_product(&product(94, thresholds, 5, &packet)).unwrap();
+
+        assert_eq!(data.product, Level3Product::N0q);
+        assert_eq!(data.volume_start_secs, 1_714_564_800);
+        let sweep = &data.sweep;
+        assert_eq!(
+            (sweep.sweep_start_secs, sweep.sweep_end_secs),
+            (1_714_564_800.0, 1_714_564_830.0)
+        );
+        assert_eq!(sweep.mean_elevation, 0.5);
+        assert_eq!(sweep.azimuths, vec![0.0, 1.5]);
+        assert_eq!((sweep.gate_count, sweep.first_gate_range_km), (3, 1.0));
+        assert_eq!(value_at(sweep, 1, 0), None);
+        assert_eq!(value_at(sweep, 1, 1), Some(-32.0));
+        assert_eq!(value_at(sweep, 1, 2), Some(20.0));
+        assert_eq!(raw_at(sweep, 0, 0), 0);
+        assert_eq!(value_at(sweep, 0, 1), Some(32.0));
+        assert_eq!(value_at(sweep, 0, 2), Some(94.5));
+    }
+
+    #[test]
+    fn keeps_tdwr_gate_spacing() {
+        let mut thresholds = [0u16; 16];
+        thresholds[0] = (-640i16) as u16;
+        thresholds[1] = 5;
+        let mut packet = be(&[0x0010, 10, 4, 0, 0, 999, 1]);
+        packet.extend(be(&[4, 100, 10]));
+        packet.extend([0, 2, 162, 255]);
+        let data = decode_product(&product(182, thresholds, 3, &packet)).unwrap();
+
+        let sweep = &data.sweep;
+        assert_eq!(data.product, Level3Product::Tv0);
+        assert_eq!(sweep.mean_elevation, 0.3);
+        assert_eq!(sweep.gate_interval_km, 0.15);
+        assert_eq!((sweep.first_gate_range_km, sweep.max_range_km), (1.5, 2.1));
+        assert_eq!(value_at(sweep, 0, 1), Some(-64.0));
+        assert_eq!(value_at(sweep, 0, 2), Some(16.0));
+    }
+
+    #[test]
+    fn decodes_run_length_radials_with_level_table() {
+        // Levels: 0 = no data, then 0.0", 0.5", 1.0" ...
+        let mut thresholds = [0x8002u16; 16];
+        thresholds[1] = 0x1000;
+        thresholds[2] = 0x1005;
+        thresholds[3] = 0x100A;
+        let mut packet = be(&[-0x50E1, 0, 6, 0, 0, 999, 1]);
+        packet.extend(be(&[2, 900, 10]));
+        packet.extend([0x20, 0x11, 0x12, 0x13]);
+        let data = decode_product(&product(80, thresholds, 0, &packet)).unwrap();
+
+        let sweep = &data.sweep;
+        assert_eq!(data.product, Level3Product::Ntp);
+        assert_eq!(sweep.azimuths, vec![90.5]);
+        assert_eq!(sweep.gate_interval_km, 2.0);
+        assert_eq!(value_at(sweep, 0, 0), None);
+        assert_eq!(value_at(sweep, 0, 2), Some(0.0));
+        assert_eq!(value_at(sweep, 0, 3), Some(12.7));
+        assert_eq!(value_at(sweep, 0, 4), Some(25.4));
+        assert_eq!(value_at(sweep, 0, 5), None);
+    }
+
+    #[test]
+    fn resamples_rasters_around_the_radar() {
+        let mut thresholds = [0x8002u16; 16];
+        thresholds[1] = 20;
+        thresholds[2] = 50;
+        // 4x4 raster: north half level 1, south half level 2.
+        let mut packet = be(&[-0x45F1, -0x8000, 0x00C0, 0, 0, 1, 0, 1, 0, 4, 2]);
+        for level in [1u8, 1, 2, 2] {
+            packet.extend(be(&[1]));
+            packet.push(0x40 | level);
+        }
+        let data = decode_product(&product(37, thresholds, 0, &packet)).unwrap();
+
+        let sweep = &data.sweep;
+        assert_eq!((sweep.azimuth_count, sweep.gate_count), (720, 2));
+        assert_eq!(value_at(sweep, 0, 1), Some(20.0));
+        assert_eq!(value_at(sweep, 360, 1), Some(50.0));
+    }
+
+    #[test]
+    fn reads_threshold_encodings() {
+        assert_eq!(nexrad_f16(0x4000), 1.0);
+        assert_eq!(nexrad_f16(0x0200), 1.0);
+        assert_eq!(nexrad_f16(0xC400), -2.0);
+        assert_eq!(legacy_level(0x1019), Some(2.5));
+        assert_eq!(legacy_level(0x0105), Some(-5.0));
+        assert_eq!(legacy_level(0x8003), None);
+
+        let eet = Thresholds::EchoTops {
+            mask: 0x7F,
+            scale: 1.0,
+            offset: 2.0,
+        };
+        assert_eq!(eet.value(1), None);
+        assert_eq!(eet.value(0x80 | 52), Some(50.0));
+    }
+
+    #[test]
+    fn rejects_unknown_and_truncated_products() {
+        assert!(decode_product(b"not a product").is_err());
+        let packet = be(&[0x0010, 0, 3, 0, 0, 999, 1]);
+        let mut unknown = product(94, [0; 16], 5, &packet);
+        unknown[30 + 30..30 + 32].copy_from_slice(&19i16.to_be_bytes());
+        assert_eq!(
+            decode_product(&unknown).err().as_deref(),
+            Some("unsupported product code 19")
+        );
+        assert!(decode_product(&product(94, [0; 16], 5, &packet)).is_err());
+    }
+}
@@ -0,0 +1,253 @@
+//! Level III downloads from the configured [`data_source`], by default the
+//! public Unidata bucket on AWS.
+//!
+//! Objects are named `{SSS}_{PPP}_{YYYY}_{MM}_{DD}_{hh}_{mm}_{ss}`, with
+//! the three-letter site and product identifiers, so one prefix listing
+//! per day finds every file of a product. Files are downloaded one at a
+//! time and handed to the UI thread, which sends them to a decode worker.
+
+use super::products::Level3Product;
+use crate::nexrad::data_source;
+use crate::nexrad::download::{with_timeout, NetworkStats, REQUEST_TIMEOUT_MS};
+use chrono::{DateTime, NaiveDate, NaiveDateTime};
+use eframe::egui;
+use std::sync::mpsc::{channel, Receiver, Sender};
+
+/// Most files fetched per request; the latest ones in the window are kept.
+const MAX_FILES: usize = 150;
+
+/// A product file in the bucket.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Level3File {
+    /// Four-letter ICAO identifier, e.g. `KTLX`.
+    pub site_id: String,
+    pub product: Level3Product,
+    pub key: String,
+    /// Product time from the key, Unix seconds.
+    pub time_secs: i64,
+}
+
+/// Progress of a fetch, delivered through [`Level3Channel::try_recv`].
+pub enum Level3Event {
+    /// The listing found `count` files to download.
+    Listed {
+        count: usize,
+    },
+    Downloaded {
+        file: Level3File,
+        data: Vec<u8>,
+    },
+    /// A listing or download failed; `name` is the day or object key.
+    Failed {
+        name: String,
+        message: String,
+    },
+}
+
+/// Listing prefix of one site, product and UTC day.
+fn day_prefix(site_id: &str, product: Level3Product, date: NaiveDate) -> String {
+    let site = site_id.get(1..).unwrap_or(site_id);
+    format!("{}_{}_{}", site, product.code(), date.format("%Y_%m_%d"))
+}
+
+/// Product time encoded in an object key.
+fn key_time(key: &str) -> Option<i64> {
+    let stamp = key.get(8..)?;
+    NaiveDateTime::parse_from_str(stamp, "%Y_%m_%d_%H_%M_%S")
+        .ok()
+        .map(|t| t.and_utc().timestamp())
+}
+
+/// Files of `product` at `site_id` between `start` and `end` (Unix seconds)
+/// in a listing, oldest first, limited to the latest [`MAX_FILES`].
+fn select_files(
+    keys: Vec<String>,
+    site_id: &str,
+    product: Level3Product,
+    start: i64,
+    end: i64,
+) -> Vec<Level3File> {
+    let mut files: Vec<Level3File> = keys
+        .into_iter()
+        .filter_map(|key| {
+            let time_secs = key_time(&key)?;
+            (start..=end).contains(&time_secs).then(|| Level3File {
+                site_id: site_id.to_string(),
+                product,
+                key,
+                time_secs,
+            })
+        })
+        .collect();
+    files.sort_by_key(|f| f.time_secs);
+    files.dedup_by(|a, b| a.key == b.key);
+    let excess = files.len().saturating_sub(MAX_FILES);
+    files.drain(..excess);
+    files
+}
+
+/// Channel delivering Level III fetch progress to the UI thread.
+pub struct Level3Channel {
+    sender: Sender<Level3Event>,
+    receiver: Receiver<Level3Event>,
+}
+
+impl Default for Level3Channel {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Level3Channel {
+    pub fn new() -> Self {
+        let (sender, receiver) = channel();
+        Self { sender, receiver }
+    }
+
+    /// List and download every file of `product` at `site_id` between
+    /// `start` and `end` (Unix seconds).
+    pub fn fetch(
+        &self,
+        ctx: egui::Context,
+        site_id: String,
+        product: Level3Product,
+        start: i64,
+        end: i64,
+        stats: NetworkStats,
+    ) {
+        let sender = self.sender.clone();
+        crate::platform::spawn_local(async move {
+            let send = |event| {
+                let _ = sender.send(event);
+                ctx.request_repaint();
+            };
+
+            let mut keys = Vec::new();
+            let (Some(first), Some(last)) = (
+                DateTime::from_timestamp(start, 0),
+                DateTime::from_timestamp(end, 0),
+            ) else {
+                return;
+            };
+            for date in first.date_naive().iter_days() {
+                if date > last.date_naive() {
+                    break;
+                }
+                let prefix = day_prefix(&site_id, product, date);
+                stats.request_started();
+                let listing = with_timeout(
+                    data_source::list_level3_keys(&prefix),
+                    REQUEST_TIMEOUT_MS,
+                    "Level III listing",
+                )
+                .await;
+                stats.request_completed(0);
+                match listing {
+                    Ok(Ok(day)) => keys.extend(day),
+                    Ok(Err(e)) => send(Level3Event::Failed {
+                        name: prefix,
+                        message: e.to_string(),
+                    }),
+                    Err(message) => send(Level3Event::Failed {
+                        name: prefix,
+                        message,
+                    }),
+                }
+            }
+
+            let files = select_files(keys, &site_id, product, start, end);
+            log::info!(
+                "level3: {} {} file(s) for {}",
+                files.len(),
+                product.code(),
+                site_id
+            );
+            send(Level3Event::Listed { count: files.len() });
+            for file in files {
+                stats.request_started();
+                let result = with_timeout(
+                    data_source::download_level3_file(&file.key),
+                    REQUEST_TIMEOUT_MS,
+                    "Level III download",
+                )
+                .await;
+                stats.request_completed(match &result {
+                    Ok(Ok(data)) => data.len() as u64,
+                    _ => 0,
+                });
+                send(match result {
+                    Ok(Ok(data)) => Level3Event::Downloaded { file, data },
+                    Ok(Err(e)) => Level3Event::Failed {
+                        name: file.key,
+                        message: e.to_string(),
+                    },
+                    Err(message) => Level3Event::Failed {
+                        name: file.key,
+                        message,
+                    },
+                });
+            }
+        });
+    }
+
+    pub fn try_recv(&self) -> Option<Level3Event> {
+        self.receiver.try_recv().ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builds_day_prefixes_and_reads_key_times() {
+        let date = NaiveDate::from_ymd_opt(2024, 5, 1).unwrap();
+        assert_eq!(
+            day_prefix("KTLX", Level3Product::N0q, date),
+            "TLX_N0Q_2024_05_01"
+        );
+        assert_eq!(key_time("TLX_N0Q_2024_05_01_12_00_30"), Some(1_714_564_830));
+        assert_eq!(key_time("TLX_N0Q_2024_05_01"), None);
+    }
+
+    #[test]
+    fn selects_listed_files_in_the_window() {
+        let keys = [
+            "TLX_N0U_2024_05_01_12_10_00",
+            "TLX_N0U_2024_05_01_11_00_00",
+            "TLX_N0U_2024_05_01_12_05_00",
+        ]
+        .map(String::from)
+        .to_vec();
+        let files = select_files(
+            keys,
+            "KTLX",
+            Level3Product::N0u,
+            1_714_564_800,
+            1_714_565_400,
+        );
+        let names: Vec<&str> = files.iter().map(|f| f.key.as_str()).collect();
+        assert_eq!(
+            names,
+            ["TLX_N0U_2024_05_01_12_05_00", "TLX_N0U_2024_05_01_12_10_00"]
+        );
+        assert_eq!(files[0].site_id, "KTLX");
+    }
+
+    #[test]
+    fn keeps_the_latest_of_a_full_tdwr_day() {
+        // TDWR low tilts come every minute: 1440 keys, past one listing page.
+        let keys = (0..1440)
+            .map(|m| format!("OKC_TZ0_2024_05_01_{:02}_{:02}_00", m / 60, m % 60))
+            .collect();
+        let files = select_files(
+            keys,
+            "TOKC",
+            Level3Product::Tz0,
+            1_714_521_600,
+            1_714_607_999,
+        );
+        assert_eq!(files.len(), MAX_FILES);
+        assert_eq!(files.last().unwrap().key, "OKC_TZ0_2024_05_01_23_59_00");
+    }
+}
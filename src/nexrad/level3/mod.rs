@@ -0,0 +1,16 @@
+//! NEXRAD Level III products.
+//!
+//! RPG products (digital base moments, DVL, enhanced echo tops, storm
+//! total precipitation, composite reflectivity) and the TDWR base products
+//! are fetched from the Unidata bucket, decoded in a worker and stored as
+//! `PrecomputedSweep` blobs under `l3_*` product strings at the lowest
+//! elevation of the matching scan, like the volume-derived products. The render path and
+//! the timeline then treat them as any other cached product.
+
+mod decode;
+mod fetch;
+mod products;
+
+pub(crate) use decode::decode_product;
+pub use fetch::{Level3Channel, Level3Event, Level3File};
+pub use products::Level3Product;
@@ -0,0 +1,196 @@
+//! The Level III products the workbench fetches and how each is stored.
+
+use crate::data::RadarType;
+
+/// A supported Level III product.
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Level3Product {
+    /// Digital base reflectivity, 0.5° tilt.
+    #[default]
+    N0q,
+    /// Digital base velocity, 0.5° tilt.
+    N0u,
+    /// Digital correlation coefficient, 0.5° tilt.
+    N0c,
+    /// Digital differential reflectivity, 0.5° tilt.
+    N0x,
+    /// Digital vertically integrated liquid.
+    Dvl,
+    /// Enhanced echo tops.
+    Eet,
+    /// Storm total precipitation (16-level).
+    Ntp,
+    /// Composite reflectivity (16-level raster).
+    Ncr,
+    /// TDWR digital base reflectivity, lowest tilt, 150 m gates.
+    Tz0,
+    /// TDWR digital base velocity, lowest tilt, 150 m gates.
+    Tv0,
+    /// TDWR long-range digital reflectivity, 300 m gates.
+    Tzl,
+}
+
+impl Level3Product {
+    pub const ALL: [Level3Product; 11] = [
+        Level3Product::N0q,
+        Level3Product::N0u,
+        Level3Product::N0c,
+        Level3Product::N0x,
+        Level3Product::Dvl,
+        Level3Product::Eet,
+        Level3Product::Ntp,
+        Level3Product::Ncr,
+        Level3Product::Tz0,
+        Level3Product::Tv0,
+        Level3Product::Tzl,
+    ];
+
+    /// Three-letter AWIPS product identifier, as used in bucket keys.
+    pub fn code(self) -> &'static str {
+        match self {
+            Level3Product::N0q => "N0Q",
+            Level3Product::N0u => "N0U",
+            Level3Product::N0c => "N0C",
+            Level3Product::N0x => "N0X",
+            Level3Product::Dvl => "DVL",
+            Level3Product::Eet => "EET",
+            Level3Product::Ntp => "NTP",
+            Level3Product::Ncr => "NCR",
+            Level3Product::Tz0 => "TZ0",
+            Level3Product::Tv0 => "TV0",
+            Level3Product::Tzl => "TZL",
+        }
+    }
+
+    pub fn from_code(code: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|p| p.code() == code)
+    }
+
+    /// Numeric product code from the message header.
+    pub fn product_code(self) -> i16 {
+        match self {
+            Level3Product::N0q => 94,
+            Level3Product::N0u => 99,
+            Level3Product::N0c => 161,
+            Level3Product::N0x => 159,
+            Level3Product::Dvl => 134,
+            Level3Product::Eet => 135,
+            Level3Product::Ntp => 80,
+            Level3Product::Ncr => 37,
+            Level3Product::Tz0 => 180,
+            Level3Product::Tv0 => 182,
+            Level3Product::Tzl => 186,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Level3Product::N0q => "Base Reflectivity (N0Q)",
+            Level3Product::N0u => "Base Velocity (N0U)",
+            Level3Product::N0c => "Correlation Coefficient (N0C)",
+            Level3Product::N0x => "Differential Reflectivity (N0X)",
+            Level3Product::Dvl => "Digital VIL (DVL)",
+            Level3Product::Eet => "Enhanced Echo Tops (EET)",
+            Level3Product::Ntp => "Storm Total Precipitation (NTP)",
+            Level3Product::Ncr => "Composite Reflectivity (NCR)",
+            Level3Product::Tz0 => "TDWR Reflectivity (TZ0)",
+            Level3Product::Tv0 => "TDWR Velocity (TV0)",
+            Level3Product::Tzl => "TDWR Long-Range Reflectivity (TZL)",
+        }
+    }
+
+    /// Unit of the stored values. Echo tops are converted from kft to km
+    /// and precipitation from inches to mm to match the Level II products.
+    pub fn unit(self) -> &'static str {
+        match self {
+            Level3Product::N0q | Level3Product::Ncr => "dBZ",
+            Level3Product::Tz0 | Level3Product::Tzl => "dBZ",
+            Level3Product::N0u | Level3Product::Tv0 => "m/s",
+            Level3Product::N0c => "",
+            Level3Product::N0x => "dB",
+            Level3Product::Dvl => "kg/m\u{00B2}",
+            Level3Product::Eet => "km MSL",
+            Level3Product::Ntp => "mm",
+        }
+    }
+
+    /// Product string of the stored sweep blobs.
+    pub fn worker_string(self) -> &'static str {
+        match self {
+            Level3Product::N0q => "l3_n0q",
+            Level3Product::N0u => "l3_n0u",
+            Level3Product::N0c => "l3_n0c",
+            Level3Product::N0x => "l3_n0x",
+            Level3Product::Dvl => "l3_dvl",
+            Level3Product::Eet => "l3_eet",
+            Level3Product::Ntp => "l3_ntp",
+            Level3Product::Ncr => "l3_ncr",
+            Level3Product::Tz0 => "l3_tz0",
+            Level3Product::Tv0 => "l3_tv0",
+            Level3Product::Tzl => "l3_tzl",
+        }
+    }
+
+    pub fn from_worker_string(s: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|p| p.worker_string() == s)
+    }
+
+    /// Radar network that issues this product.
+    pub fn radar_type(self) -> RadarType {
+        match self {
+            Level3Product::Tz0 | Level3Product::Tv0 | Level3Product::Tzl => RadarType::Tdwr,
+            _ => RadarType::Wsr88d,
+        }
+    }
+
+    /// Level II product string whose value range and colors this product
+    /// shares. Reflectivity and velocity use the official NWS tables
+    /// instead (see `color_table`).
+    pub fn base_product(self) -> &'static str {
+        match self {
+            Level3Product::N0q | Level3Product::Ncr => "reflectivity",
+            Level3Product::Tz0 | Level3Product::Tzl => "reflectivity",
+            Level3Product::N0u | Level3Product::Tv0 => "velocity",
+            Level3Product::N0c => "correlation_coefficient",
+            Level3Product::N0x => "differential_reflectivity",
+            Level3Product::Dvl => "vil",
+            Level3Product::Eet => "echo_tops_18",
+            Level3Product::Ntp => "qpe_storm_total",
+        }
+    }
+
+    /// Range bin (or raster cell) size, km. Not carried in the product.
+    pub(super) fn gate_km(self) -> f64 {
+        match self {
+            Level3Product::N0q | Level3Product::N0u | Level3Product::N0c | Level3Product::N0x => {
+                0.25
+            }
+            Level3Product::Dvl | Level3Product::Eet | Level3Product::Ncr => 1.0,
+            Level3Product::Ntp => 2.0,
+            Level3Product::Tz0 | Level3Product::Tv0 => 0.15,
+            Level3Product::Tzl => 0.3,
+        }
+    }
+
+    /// Factor from the product's native unit to [`Self::unit`].
+    pub(super) fn unit_factor(self) -> f32 {
+        match self {
+            Level3Product::Eet => 0.3048,
+            Level3Product::Ntp => 25.4,
+            _ => 1.0,
+        }
+    }
+
+    /// `(scale, offset)` of the stored u16 values: `raw = value * scale + offset`.
+    pub(super) fn encoding(self) -> (f32, f32) {
+        match self {
+            Level3Product::N0q | Level3Product::Ncr => (2.0, 66.0),
+            Level3Product::Tz0 | Level3Product::Tzl => (2.0, 66.0),
+            Level3Product::N0u | Level3Product::Tv0 => (2.0, 130.0),
+            Level3Product::N0c => (1000.0, 2.0),
+            Level3Product::N0x => (100.0, 802.0),
+            Level3Product::Dvl | Level3Product::Ntp => (10.0, 2.0),
+            Level3Product::Eet => (100.0, 2.0),
+        }
+    }
+}
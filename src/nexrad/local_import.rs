@@ -0,0 +1,323 @@
+//! Import of Archive II volumes and real-time chunk files from disk.
+//!
+//! Files arrive from a file picker or from drag-and-drop. The desktop build
+//! asks `zenity` or `kdialog` for the picker and reads dropped paths itself. Real-time
+//! chunks (`YYYYMMDD-HHMMSS-NNN-S|I|E`, as in the AWS chunk bucket) are
+//! concatenated per volume: the start chunk carries the volume header and
+//! every other chunk is one LDM record, so the result is an ordinary
+//! Archive II file. Everything then goes through the worker's archive
+//! ingest, which reads the site and scan time from the header record.
+
+use eframe::egui;
+use std::collections::BTreeMap;
+use std::sync::mpsc::{channel, Receiver, Sender};
+
+/// A file read from disk.
+pub struct LocalFile {
+    pub name: String,
+    pub data: Vec<u8>,
+}
+
+/// An archive ready for ingest.
+pub struct LocalVolume {
+    /// Original file name, or the `YYYYMMDD-HHMMSS` prefix of joined chunks.
+    pub file_name: String,
+    pub data: Vec<u8>,
+}
+
+/// Chunk name parts: volume prefix (`YYYYMMDD-HHMMSS`) and sequence number.
+fn parse_chunk_name(name: &str) -> Option<(&str, u32)> {
+    let bytes = name.as_bytes();
+    if bytes.len() != 21 || !matches!(bytes[20], b'S' | b'I' | b'E') {
+        return None;
+    }
+    let digits = |range: std::ops::Range<usize>| bytes[range].iter().all(u8::is_ascii_digit);
+    let dashes = [8, 15, 19].iter().all(|&i| bytes[i] == b'-');
+    if !(dashes && digits(0..8) && digits(9..15) && digits(16..19)) {
+        return None;
+    }
+    Some((&name[..15], name[16..19].parse().ok()?))
+}
+
+/// Turn picked files into ingestable archives: chunk files are grouped by
+/// volume and joined in sequence order, anything else is passed through.
+/// Hidden files (such as `.DS_Store` in a picked folder) are skipped.
+pub fn assemble_volumes(files: Vec<LocalFile>) -> Vec<LocalVolume> {
+    let mut volumes = Vec::new();
+    let mut chunked: BTreeMap<String, Vec<(u32, Vec<u8>)>> = BTreeMap::new();
+    for file in files {
+        if file.name.starts_with('.') {
+            continue;
+        }
+        match parse_chunk_name(&file.name) {
+            Some((prefix, sequence)) => chunked
+                .entry(prefix.to_string())
+                .or_default()
+                .push((sequence, file.data)),
+            None => volumes.push(LocalVolume {
+                file_name: file.name,
+                data: file.data,
+            }),
+        }
+    }
+    for (prefix, mut chunks) in chunked {
+        chunks.sort_by_key(|(sequence, _)| *sequence);
+        volumes.push(LocalVolume {
+            file_name: prefix,
+            data: chunks.into_iter().flat_map(|(_, data)| data).collect(),
+        });
+    }
+    volumes
+}
+
+/// Files from something dropped on the window: the bytes the browser read,
+/// or on the desktop the file at the dropped path, or every file in a
+/// dropped folder.
+pub fn read_dropped(dropped: &egui::DroppedFile) -> Vec<LocalFile> {
+    if let Some(bytes) = &dropped.bytes {
+        return vec![LocalFile {
+            name: dropped.name.clone(),
+            data: bytes.to_vec(),
+        }];
+    }
+    match &dropped.path {
+        Some(path) => read_paths(std::slice::from_ref(path)),
+        None => Vec::new(),
+    }
+}
+
+/// Read each path, expanding directories one level like the browser's
+/// folder picker. Unreadable entries are logged and skipped.
+pub fn read_paths(paths: &[std::path::PathBuf]) -> Vec<LocalFile> {
+    let mut files = Vec::new();
+    for path in paths {
+        let entries: Vec<std::path::PathBuf> = if path.is_dir() {
+            match std::fs::read_dir(path) {
+                Ok(dir) => dir
+                    .flatten()
+                    .map(|e| e.path())
+                    .filter(|p| p.is_file())
+                    .collect(),
+                Err(e) => {
+                    log::warn!("import: failed to list {}: {}", path.display(), e);
+                    continue;
+                }
+            }
+        } else {
+            vec![path.clone()]
+        };
+        for entry in entries {
+            let name = entry
+                .file_name()
+                .map(|n| n.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            match std::fs::read(&entry) {
+                Ok(data) => files.push(LocalFile { name, data }),
+                Err(e) => log::warn!("import: failed to read {}: {}", entry.display(), e),
+            }
+        }
+    }
+    files
+}
+
+/// Channel delivering batches of files chosen in the file picker.
+pub struct LocalImportChannel {
+    sender: Sender<Vec<LocalFile>>,
+    receiver: Receiver<Vec<LocalFile>>,
+}
+
+impl Default for LocalImportChannel {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LocalImportChannel {
+    pub fn new() -> Self {
+        let (sender, receiver) = channel();
+        Self { sender, receiver }
+    }
+
+    /// Open the file picker; with `directory`, pick a folder and read every
+    /// file in it. The selection arrives through [`Self::try_recv`].
+    #[cfg(target_arch = "wasm32")]
+    pub fn open_picker(&self, ctx: egui::Context, directory: bool) -> Result<(), String> {
+        use wasm_bindgen::prelude::*;
+        use wasm_bindgen::JsCast;
+
+        let document = web_sys::window()
+            .and_then(|w| w.document())
+            .ok_or("no document")?;
+        let input: web_sys::HtmlInputElement = document
+            .create_element("input")
+            .map_err(|_| "create input failed")?
+            .dyn_into()
+            .map_err(|_| "element was not an input")?;
+        input.set_type("file");
+        input.set_multiple(true);
+        if directory {
+            input
+                .set_attribute("webkitdirectory", "")
+                .map_err(|_| "directory picking unsupported")?;
+        }
+
+        let sender = self.sender.clone();
+        let picked = input.clone();
+        let onchange = Closure::once_into_js(move || {
+            let Some(list) = picked.files() else {
+                return;
+            };
+            let files: Vec<web_sys::File> =
+                (0..list.length()).filter_map(|i| list.get(i)).collect();
+            crate::platform::spawn_local(async move {
+                let mut batch = Vec::with_capacity(files.len());
+                for file in files {
+                    match read_file(&file).await {
+                        Ok(data) => batch.push(LocalFile {
+                            name: file.name(),
+                            data,
+                        }),
+                        Err(e) => log::warn!("import: failed to read {}: {}", file.name(), e),
+                    }
+                }
+                let _ = sender.send(batch);
+                ctx.request_repaint();
+            });
+        });
+        input.set_onchange(Some(onchange.unchecked_ref()));
+        input.click();
+        Ok(())
+    }
+
+    /// Open the desktop's file dialog through `zenity`, falling back to
+    /// `kdialog`; with `directory`, pick a folder and read every file in it.
+    /// The dialog runs on its own thread and the selection arrives through
+    /// [`Self::try_recv`].
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn open_picker(&self, ctx: egui::Context, directory: bool) -> Result<(), String> {
+        use std::process::Command;
+
+        let zenity = {
+            let mut cmd = Command::new("zenity");
+            cmd.args(["--file-selection", "--separator=\n"]);
+            cmd.arg(if directory {
+                "--directory"
+            } else {
+                "--multiple"
+            });
+            cmd
+        };
+        let kdialog = {
+            let mut cmd = Command::new("kdialog");
+            if directory {
+                cmd.arg("--getexistingdirectory");
+            } else {
+                cmd.args(["--getopenfilename", ".", "--multiple", "--separate-output"]);
+            }
+            cmd
+        };
+        // Probe before spawning the thread so a missing dialog is reported
+        // to the user rather than only logged.
+        let mut command = [zenity, kdialog]
+            .into_iter()
+            .find(|cmd| {
+                let paths = std::env::var_os("PATH").unwrap_or_default();
+                std::env::split_paths(&paths).any(|dir| dir.join(cmd.get_program()).is_file())
+            })
+            .ok_or(
+                "no file dialog found (install zenity or kdialog), or drop files on the window",
+            )?;
+
+        let sender = self.sender.clone();
+        std::thread::spawn(move || {
+            let output = match command.output() {
+                Ok(output) => output,
+                Err(e) => {
+                    log::warn!("import: file dialog failed: {}", e);
+                    return;
+                }
+            };
+            // Cancelling the dialog exits non-zero with no selection.
+            if !output.status.success() {
+                return;
+            }
+            let paths: Vec<std::path::PathBuf> = String::from_utf8_lossy(&output.stdout)
+                .lines()
+                .filter(|l| !l.is_empty())
+                .map(std::path::PathBuf::from)
+                .collect();
+            let _ = sender.send(read_paths(&paths));
+            ctx.request_repaint();
+        });
+        Ok(())
+    }
+
+    /// Queue files that were read elsewhere, e.g. dropped on the window.
+    pub fn submit(&self, files: Vec<LocalFile>) {
+        let _ = self.sender.send(files);
+    }
+
+    /// Take the next batch of picked or dropped files.
+    pub fn try_recv(&self) -> Option<Vec<LocalFile>> {
+        self.receiver.try_recv().ok()
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+async fn read_file(file: &web_sys::File) -> Result<Vec<u8>, String> {
+    let buffer = wasm_bindgen_futures::JsFuture::from(file.array_buffer())
+        .await
+        .map_err(|e| format!("{:?}", e))?;
+    Ok(js_sys::Uint8Array::new(&buffer).to_vec())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(name: &str, data: &[u8]) -> LocalFile {
+        LocalFile {
+            name: name.to_string(),
+            data: data.to_vec(),
+        }
+    }
+
+    #[test]
+    fn recognises_chunk_names() {
+        assert_eq!(
+            parse_chunk_name("20240501-123456-001-S"),
+            Some(("20240501-123456", 1))
+        );
+        assert_eq!(
+            parse_chunk_name("20240501-123456-042-I"),
+            Some(("20240501-123456", 42))
+        );
+        assert_eq!(parse_chunk_name("20240501-123456-042-X"), None);
+        assert_eq!(parse_chunk_name("KTLX20240501_123456_V06"), None);
+        assert_eq!(parse_chunk_name("20240501-123456-001-S.gz"), None);
+    }
+
+    #[test]
+    fn joins_chunks_in_sequence_order_per_volume() {
+        let volumes = assemble_volumes(vec![
+            file("20240501-123456-003-E", b"c"),
+            file("KTLX20240501_120000_V06", b"archive"),
+            file("20240501-123456-001-S", b"a"),
+            file("20240501-124012-001-S", b"x"),
+            file(".DS_Store", b"junk"),
+            file("20240501-123456-002-I", b"b"),
+        ]);
+        let summary: Vec<(&str, &[u8])> = volumes
+            .iter()
+            .map(|v| (v.file_name.as_str(), v.data.as_slice()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("KTLX20240501_120000_V06", &b"archive"[..]),
+                ("20240501-123456", &b"abc"[..]),
+                ("20240501-124012", &b"x"[..]),
+            ]
+        );
+    }
+}
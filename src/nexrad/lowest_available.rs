@@ -0,0 +1,327 @@
+//! Lowest-available composite: the base tilt refreshed by every low-level
+//! sweep collected in a trailing window.
+//!
+//! Sweeps are taken bottom-up and, within a tier of repeated tilts (SAILS /
+//! MESO-SAILS / MRLE re-scans of the base elevation), newest first. Each gate
+//! keeps the first valid value found, so the lowest tilt always wins and the
+//! latest re-scan of it replaces older ones; higher tilts only fill what the
+//! lower ones left empty (range folding, missing radials, shorter range).
+//! Below-threshold gates count as valid — "no echo" is a measurement.
+//!
+//! The output uses the polar grid of the first sweep added, which should be
+//! the newest sweep of the lowest tier, and keeps each gate's collection time
+//! so the inspector can show how old it is.
+
+use super::polar::AzimuthIndex;
+use crate::data::keys::{GateValues, PrecomputedSweep};
+
+/// Tilts within this many degrees of a tier's lowest sweep belong to it.
+/// Covers the spread of repeated base scans (split cuts report 0.48°-0.53°).
+const TIER_TOLERANCE_DEG: f32 = 0.25;
+
+/// Order sweeps for [`LowestAvailableComposite`]: lowest tier first, newest
+/// first within a tier. `key` gives a sweep's elevation (degrees) and end
+/// time (Unix seconds).
+pub(crate) fn composite_order<T>(sweeps: &mut [T], key: impl Fn(&T) -> (f32, f64)) {
+    let mut elevations: Vec<f32> = sweeps.iter().map(|s| key(s).0).collect();
+    elevations.sort_by(f32::total_cmp);
+    // Each tier starts at the lowest tilt more than the tolerance above the
+    // previous tier's start.
+    let mut tier_bases: Vec<f32> = Vec::new();
+    for elevation in elevations {
+        if tier_bases
+            .last()
+            .is_none_or(|&base| elevation - base > TIER_TOLERANCE_DEG)
+        {
+            tier_bases.push(elevation);
+        }
+    }
+    let tier = |elevation: f32| tier_bases.partition_point(|&base| base <= elevation);
+    sweeps.sort_by(|a, b| {
+        let (a_elevation, a_end) = key(a);
+        let (b_elevation, b_end) = key(b);
+        tier(a_elevation)
+            .cmp(&tier(b_elevation))
+            .then(b_end.total_cmp(&a_end))
+    });
+}
+
+/// Gate state while compositing: not yet covered, range folded in every
+/// sweep so far, or filled.
+#[derive(Clone, Copy, PartialEq)]
+enum Gate {
+    Empty,
+    Folded,
+    Filled,
+}
+
+/// Fills the base tilt's polar grid one sweep at a time.
+pub(crate) struct LowestAvailableComposite {
+    azimuths: Vec<f32>,
+    gate_count: usize,
+    first_gate_km: f64,
+    gate_interval_km: f64,
+    max_range_km: f64,
+    /// Base sweep's encoding, reused for the output.
+    scale: f32,
+    offset: f32,
+    mean_elevation: f32,
+    gates: Vec<Gate>,
+    raw: Vec<u16>,
+    /// Collection time of each gate, Unix seconds (NaN until filled).
+    times: Vec<f64>,
+    open: usize,
+    sweep_count: u32,
+    start_secs: f64,
+    end_secs: f64,
+}
+
+impl LowestAvailableComposite {
+    /// Start from the base sweep, which defines the output grid.
+    pub(crate) fn new(base: &PrecomputedSweep) -> Self {
+        let total = base.azimuths.len() * base.gate_count as usize;
+        let mut composite = Self {
+            azimuths: base.azimuths.clone(),
+            gate_count: base.gate_count as usize,
+            first_gate_km: base.first_gate_range_km,
+            gate_interval_km: base.gate_interval_km,
+            max_range_km: base.max_range_km,
+            scale: base.scale,
+            offset: base.offset,
+            mean_elevation: base.mean_elevation,
+            gates: vec![Gate::Empty; total],
+            raw: vec![0; total],
+            times: vec![f64::NAN; total],
+            open: total,
+            sweep_count: 0,
+            start_secs: f64::INFINITY,
+            end_secs: f64::NEG_INFINITY,
+        };
+        composite.add_sweep(base);
+        composite
+    }
+
+    /// Fill every still-open gate that `sweep` has a valid value for. Gates
+    /// are matched by ground range, so higher tilts line up with the base.
+    pub(crate) fn add_sweep(&mut self, sweep: &PrecomputedSweep) {
+        let src_gates = sweep.gate_count as usize;
+        let src_azimuths = sweep.azimuths.len();
+        if self.open == 0 || src_gates == 0 || src_azimuths == 0 || sweep.gate_interval_km <= 0.0 {
+            return;
+        }
+        let index = AzimuthIndex::new(&sweep.azimuths, src_azimuths);
+        let base_cos = (self.mean_elevation as f64).to_radians().cos();
+        let src_cos = (sweep.mean_elevation as f64).to_radians().cos();
+        let sweep_time = (sweep.sweep_start_secs + sweep.sweep_end_secs) / 2.0;
+        let raw_at = |i: usize| -> u16 {
+            match &sweep.gate_values {
+                GateValues::U8(v) => v.get(i).map_or(0, |&r| r as u16),
+                GateValues::U16(v) => v.get(i).copied().unwrap_or(0),
+            }
+        };
+        let same_encoding = sweep.scale == self.scale && sweep.offset == self.offset;
+
+        let mut used = false;
+        for (a, &azimuth) in self.azimuths.iter().enumerate() {
+            if azimuth < 0.0 {
+                continue;
+            }
+            let Some(radial) = index.radial(azimuth) else {
+                continue;
+            };
+            let time = sweep
+                .radial_times
+                .get(radial)
+                .copied()
+                .unwrap_or(sweep_time);
+            for g in 0..self.gate_count {
+                let cell = a * self.gate_count + g;
+                if self.gates[cell] == Gate::Filled {
+                    continue;
+                }
+                let ground_km =
+                    (self.first_gate_km + (g as f64 + 0.5) * self.gate_interval_km) * base_cos;
+                let src_gate =
+                    (ground_km / src_cos - sweep.first_gate_range_km) / sweep.gate_interval_km;
+                if !(0.0..src_gates as f64).contains(&src_gate) {
+                    continue;
+                }
+                let raw = raw_at(radial * src_gates + src_gate as usize);
+                if raw == 1 {
+                    self.gates[cell] = Gate::Folded;
+                    continue;
+                }
+                self.raw[cell] = if raw == 0 || same_encoding {
+                    raw
+                } else {
+                    self.encode((raw as f32 - sweep.offset) / sweep.scale)
+                };
+                self.times[cell] = time;
+                self.gates[cell] = Gate::Filled;
+                self.open -= 1;
+                used = true;
+            }
+        }
+        if used {
+            self.sweep_count += 1;
+            self.start_secs = self.start_secs.min(sweep.sweep_start_secs);
+            self.end_secs = self.end_secs.max(sweep.sweep_end_secs);
+        }
+    }
+
+    /// Re-encode a physical value with the base sweep's scale and offset.
+    fn encode(&self, value: f32) -> u16 {
+        (value * self.scale + self.offset)
+            .round()
+            .clamp(2.0, u16::MAX as f32) as u16
+    }
+
+    /// Whether every gate has a value, so further sweeps can't change it.
+    pub(crate) fn is_complete(&self) -> bool {
+        self.open == 0
+    }
+
+    /// Sweeps that contributed at least one gate.
+    pub(crate) fn sweep_count(&self) -> u32 {
+        self.sweep_count
+    }
+
+    /// The composite as a U16 sweep, with each gate's collection time (NaN
+    /// where no sweep had data). Gates range folded everywhere stay folded.
+    pub(crate) fn finish(self) -> (PrecomputedSweep, Vec<f64>) {
+        let raw = self
+            .gates
+            .iter()
+            .zip(self.raw)
+            .map(|(gate, raw)| match gate {
+                Gate::Folded => 1,
+                _ => raw,
+            })
+            .collect();
+        let sweep = PrecomputedSweep {
+            azimuth_count: self.azimuths.len() as u32,
+            gate_count: self.gate_count as u32,
+            first_gate_range_km: self.first_gate_km,
+            gate_interval_km: self.gate_interval_km,
+            max_range_km: self.max_range_km,
+            scale: self.scale,
+            offset: self.offset,
+            radial_count: self.azimuths.len() as u32,
+            mean_elevation: self.mean_elevation,
+            nyquist_velocity: None,
+            sweep_start_secs: if self.start_secs.is_finite() {
+                self.start_secs
+            } else {
+                0.0
+            },
+            sweep_end_secs: if self.end_secs.is_finite() {
+                self.end_secs
+            } else {
+                0.0
+            },
+            azimuths: self.azimuths,
+            radial_times: Vec::new(),
+            gate_values: GateValues::U16(raw),
+        };
+        (sweep, self.times)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::SweepFixture;
+
+    /// A 4-radial sweep of 4 one-km gates, encoded with scale 2, offset 66.
+    fn sweep(elevation: f32, end_secs: f64, raw: [u8; 16]) -> PrecomputedSweep {
+        SweepFixture::new(4, 4, 1.0)
+            .elevation(elevation)
+            .times(end_secs - 20.0, end_secs)
+            .raw(GateValues::U8(raw.to_vec()))
+    }
+
+    #[test]
+    fn orders_lowest_tier_newest_first() {
+        let mut sweeps = vec![
+            (1.5, 100.0),
+            (0.48, 100.0),
+            (0.53, 300.0),
+            (0.9, 400.0),
+            (0.5, 200.0),
+        ];
+        composite_order(&mut sweeps, |s| *s);
+        assert_eq!(
+            sweeps,
+            vec![
+                (0.53, 300.0),
+                (0.5, 200.0),
+                (0.48, 100.0),
+                (0.9, 400.0),
+                (1.5, 100.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn keeps_the_first_valid_gate() {
+        // Newest base tilt: radial 0 range folded at gate 1, radial 2 below
+        // threshold everywhere, radial 3 folded at gate 3.
+        let newest = sweep(
+            0.5,
+            300.0,
+            [
+                100, 1, 100, 100, //
+                100, 100, 100, 100, //
+                0, 0, 0, 0, //
+                100, 100, 100, 1,
+            ],
+        );
+        let older = sweep(
+            0.5,
+            200.0,
+            [
+                120, 120, 120, 120, //
+                120, 120, 120, 120, //
+                120, 120, 120, 120, //
+                120, 120, 120, 1,
+            ],
+        );
+        let mut composite = LowestAvailableComposite::new(&newest);
+        assert!(!composite.is_complete());
+        composite.add_sweep(&older);
+        assert_eq!(composite.sweep_count(), 2);
+        let (result, times) = composite.finish();
+        let GateValues::U16(raw) = result.gate_values else {
+            panic!("composite is U16");
+        };
+        assert_eq!(&raw[0..4], &[100, 120, 100, 100]);
+        assert_eq!(&raw[8..12], &[0, 0, 0, 0]);
+        assert_eq!(raw[15], 1);
+        assert_eq!(times[0], 290.0);
+        assert_eq!(times[1], 190.0);
+        assert!(times[15].is_nan());
+        assert_eq!(result.sweep_start_secs, 180.0);
+        assert_eq!(result.sweep_end_secs, 300.0);
+    }
+
+    #[test]
+    fn re_encodes_other_scales_and_stops_when_full() {
+        let base = sweep(0.5, 300.0, [1; 16]);
+        let mut higher = sweep(1.5, 250.0, [0; 16]);
+        // 20 dBZ with scale 1, offset 33.
+        higher.scale = 1.0;
+        higher.offset = 33.0;
+        higher.gate_values = GateValues::U8(vec![53; 16]);
+        let mut composite = LowestAvailableComposite::new(&base);
+        assert_eq!(composite.sweep_count(), 0);
+        composite.add_sweep(&higher);
+        assert!(composite.is_complete());
+        composite.add_sweep(&sweep(2.4, 260.0, [200; 16]));
+        assert_eq!(composite.sweep_count(), 1);
+        let (result, _) = composite.finish();
+        let GateValues::U16(raw) = result.gate_values else {
+            panic!("composite is U16");
+        };
+        assert!(raw.iter().all(|&r| r == 106));
+    }
+}
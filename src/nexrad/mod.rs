@@ -1,7 +1,9 @@
 //! NEXRAD data integration module.
 //!
 //! This module provides the full data pipeline from network to pixels:
-//! - **Acquisition**: Archive downloads from AWS S3 and real-time chunk streaming
+//! - **Acquisition**: Archive downloads from AWS S3, real-time chunk streaming,
+//!   Level III product downloads, neighbouring sites' volumes for the regional
+//!   mosaic and pinned sites, and import of local archive and chunk files
 //! - **Ingestion**: Record splitting, bzip2 decompression, VCP extraction, and
 //!   pre-computed sweep storage in IndexedDB (runs in Web Worker)
 //! - **Rendering**: GPU-based radar rendering via WebGL2 shaders with polar-to-Cartesian
@@ -10,19 +12,36 @@
 //!   URL persistence, and service worker network monitoring
 
 pub(crate) mod acquisition_coordinator;
+pub(crate) mod archive_file;
 mod archive_index;
+pub(crate) mod beam;
 mod cache_channel;
+pub(crate) mod cf_radial;
 pub(crate) mod color_table;
+pub(crate) mod cross_section;
+pub(crate) mod data_source;
+pub(crate) mod dealias;
 mod decode_worker;
+pub(crate) mod derived;
 pub(crate) mod detection;
 mod download;
 pub(crate) mod download_queue;
+pub(crate) mod frame_interpolation;
 pub(crate) mod globe_radar_renderer;
 pub(crate) mod gpu_renderer;
+pub(crate) mod hydro_class;
 pub(crate) mod ingest_phases;
+pub(crate) mod level3;
+mod local_import;
+pub(crate) mod lowest_available;
+pub(crate) mod mosaic;
 mod national_mosaic;
+pub(crate) mod neighbour_fetch;
 pub(crate) mod network_monitor;
+pub(crate) mod palette;
 pub(crate) mod persistence_manager;
+pub(crate) mod polar;
+pub(crate) mod qpe;
 mod realtime;
 pub(crate) mod record_decode;
 pub(crate) mod render_coordinator;
@@ -32,18 +51,22 @@ mod streaming_state;
 mod types;
 mod volume_discovery;
 pub(crate) mod volume_ray_renderer;
-mod worker_api;
+pub(crate) mod worker_api;
 
 pub use acquisition_coordinator::AcquisitionCoordinator;
 pub use archive_index::ScanBoundary;
 pub use cache_channel::CacheLoadResult;
 pub use decode_worker::{
-    default_pool_size, ChunkIngestResult, DecodeResult, IngestResult, VolumeData, VolumeSweepMeta,
-    WorkerOutcome, WorkerPool,
+    default_pool_size, CfRadialData, ChunkIngestResult, CrossSectionData, DecodeResult,
+    IngestResult, Level3IngestResult, LowestAvailableSweep, MosaicData, QpeScan, RenderContext,
+    VolumeData, VolumeSweepMeta, WorkerOutcome, WorkerPool,
 };
 pub use download::{ListingResult, NetworkStats};
 pub use globe_radar_renderer::GlobeRadarRenderer;
 pub use gpu_renderer::RadarGpuRenderer;
+#[cfg(not(target_arch = "wasm32"))]
+pub use local_import::read_paths;
+pub use local_import::{assemble_volumes, read_dropped, LocalFile};
 pub use national_mosaic::NationalMosaic;
 pub use network_monitor::{
     is_cross_origin_isolated, NetworkAggregate, NetworkMonitor, NetworkRequest,
@@ -51,7 +74,9 @@ pub use network_monitor::{
 pub use persistence_manager::PersistenceManager;
 pub use realtime::{ChunkProjectionInfo, RealtimeChannel, RealtimeResult};
 pub use render_coordinator::RenderCoordinator;
-pub use render_request::RenderRequest;
+pub use render_request::{
+    CfRadialRequest, LowestAvailableRequest, MosaicRequest, QpeRenderRequest, RenderRequest,
+};
 pub use streaming_manager::{StreamingEvent, StreamingManager};
 pub use types::{DownloadResult, ScanMetadata};
 pub use volume_ray_renderer::VolumeRayRenderer;
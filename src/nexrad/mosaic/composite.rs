@@ -0,0 +1,243 @@
+//! Composite several radars' sweeps onto one latitude/longitude grid.
+//!
+//! Each cell takes its value from a single radar: the one whose beam passes
+//! lowest over the cell, or the nearest one, among those with data there.
+//! Ranges and azimuths are flat-earth, as in the canvas inspector and the
+//! gridded exports; beam heights follow the 4/3-earth model of the
+//! cross-sections, offset by each antenna's altitude so radars on high
+//! ground compare fairly.
+
+use crate::geo::KM_PER_DEG;
+use crate::nexrad::beam::beam_height_km;
+use crate::nexrad::polar::PolarSweep;
+use serde::{Deserialize, Serialize};
+
+/// Grid spacing in degrees (about 1.1 km N-S) when the sites' coverage
+/// fits in [`MAX_CELLS`].
+const CELL_DEG: f64 = 0.01;
+
+/// Most cells in one mosaic. Wider spreads of sites get coarser cells.
+const MAX_CELLS: f64 = 1_500_000.0;
+
+/// Which radar a mosaic cell takes its value from.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum MosaicMode {
+    /// The radar whose beam centre is lowest above sea level.
+    #[default]
+    LowestBeam,
+    /// The closest radar.
+    NearestRadar,
+}
+
+impl MosaicMode {
+    pub const ALL: [MosaicMode; 2] = [MosaicMode::LowestBeam, MosaicMode::NearestRadar];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            MosaicMode::LowestBeam => "Lowest beam",
+            MosaicMode::NearestRadar => "Nearest radar",
+        }
+    }
+}
+
+/// One radar's sweep and where it was taken from.
+pub(crate) struct MosaicSource {
+    pub lat: f64,
+    pub lon: f64,
+    /// Antenna altitude above sea level, km.
+    pub altitude_km: f64,
+    pub elevation_deg: f32,
+    pub sweep: PolarSweep,
+}
+
+/// A north-up grid of composited values, NaN where no radar has data.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MosaicGrid {
+    /// Longitude of the grid's west edge.
+    pub west: f64,
+    /// Latitude of the grid's north edge.
+    pub north: f64,
+    pub cell_deg: f64,
+    pub columns: usize,
+    pub rows: usize,
+    /// Row-major from the north-west corner.
+    pub values: Vec<f32>,
+}
+
+impl MosaicGrid {
+    /// Bounds as (min_lon, min_lat, max_lon, max_lat).
+    pub fn bounds(&self) -> (f64, f64, f64, f64) {
+        (
+            self.west,
+            self.north - self.rows as f64 * self.cell_deg,
+            self.west + self.columns as f64 * self.cell_deg,
+            self.north,
+        )
+    }
+}
+
+/// Composite `sources` over the union of their coverage.
+pub(crate) fn build_mosaic(sources: &[MosaicSource], mode: MosaicMode) -> MosaicGrid {
+    if sources.is_empty() {
+        return MosaicGrid::default();
+    }
+
+    let cos_lats: Vec<f64> = sources
+        .iter()
+        .map(|s| s.lat.to_radians().cos().max(0.01))
+        .collect();
+    let (mut west, mut south) = (f64::INFINITY, f64::INFINITY);
+    let (mut east, mut north) = (f64::NEG_INFINITY, f64::NEG_INFINITY);
+    for (s, cos_lat) in sources.iter().zip(&cos_lats) {
+        let max_range_km =
+            s.sweep.first_gate_km + s.sweep.gate_count as f64 * s.sweep.gate_interval_km;
+        let half_lat = max_range_km / KM_PER_DEG;
+        let half_lon = half_lat / cos_lat;
+        west = west.min(s.lon - half_lon);
+        east = east.max(s.lon + half_lon);
+        south = south.min(s.lat - half_lat);
+        north = north.max(s.lat + half_lat);
+    }
+    let cells = (east - west) * (north - south) / (CELL_DEG * CELL_DEG);
+    let cell_deg = CELL_DEG * (cells / MAX_CELLS).sqrt().max(1.0);
+    let columns = ((east - west) / cell_deg).ceil() as usize;
+    let rows = ((north - south) / cell_deg).ceil() as usize;
+
+    let indexes: Vec<_> = sources.iter().map(|s| s.sweep.azimuth_index()).collect();
+    let mut values = Vec::with_capacity(columns * rows);
+    for row in 0..rows {
+        let lat = north - (row as f64 + 0.5) * cell_deg;
+        for column in 0..columns {
+            let lon = west + (column as f64 + 0.5) * cell_deg;
+            let mut best_score = f64::INFINITY;
+            let mut best = f32::NAN;
+            for (i, s) in sources.iter().enumerate() {
+                let dlat = lat - s.lat;
+                let dlon = (lon - s.lon) * cos_lats[i];
+                let range_km = dlat.hypot(dlon) * KM_PER_DEG;
+                let score = match mode {
+                    MosaicMode::LowestBeam => {
+                        s.altitude_km + beam_height_km(range_km, s.elevation_deg)
+                    }
+                    MosaicMode::NearestRadar => range_km,
+                };
+                if score >= best_score {
+                    continue;
+                }
+                let azimuth_deg = dlon.atan2(dlat).to_degrees().rem_euclid(360.0);
+                let value = s.sweep.value_at(&indexes[i], azimuth_deg as f32, range_km);
+                if !value.is_nan() {
+                    best_score = score;
+                    best = value;
+                }
+            }
+            values.push(best);
+        }
+    }
+
+    MosaicGrid {
+        west,
+        north,
+        cell_deg,
+        columns,
+        rows,
+        values,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// One-degree radials, 1 km gates out to 100 km, all holding `value`
+    /// except the radials listed in `empty`.
+    fn source(lat: f64, lon: f64, altitude_km: f64, value: f32, empty: &[usize]) -> MosaicSource {
+        let gate_count = 100;
+        let values = (0..360)
+            .flat_map(|radial| {
+                let v = if empty.contains(&radial) {
+                    f32::NAN
+                } else {
+                    value
+                };
+                std::iter::repeat_n(v, gate_count)
+            })
+            .collect();
+        MosaicSource {
+            lat,
+            lon,
+            altitude_km,
+            elevation_deg: 0.5,
+            sweep: PolarSweep {
+                azimuths: (0..360).map(|a| a as f32 + 0.5).collect(),
+                gate_count,
+                first_gate_km: 0.0,
+                gate_interval_km: 1.0,
+                values,
+            },
+        }
+    }
+
+    fn value_near(grid: &MosaicGrid, lat: f64, lon: f64) -> f32 {
+        let column = ((lon - grid.west) / grid.cell_deg) as usize;
+        let row = ((grid.north - lat) / grid.cell_deg) as usize;
+        grid.values[row * grid.columns + column]
+    }
+
+    #[test]
+    fn covers_every_site_and_leaves_gaps_empty() {
+        let sources = [
+            source(35.0, -97.0, 0.0, 10.0, &[]),
+            source(35.0, -96.0, 0.0, 20.0, &[]),
+        ];
+        let grid = build_mosaic(&sources, MosaicMode::NearestRadar);
+        let (min_lon, min_lat, max_lon, max_lat) = grid.bounds();
+        assert!(min_lon < -98.0 && max_lon > -95.0);
+        assert!(min_lat < 34.2 && max_lat > 35.8);
+        assert_eq!(grid.values.len(), grid.columns * grid.rows);
+
+        assert_eq!(value_near(&grid, 35.0, -97.2), 10.0);
+        assert_eq!(value_near(&grid, 35.0, -95.8), 20.0);
+        // North of both radars, beyond 100 km.
+        assert!(value_near(&grid, 36.5, -96.5).is_nan());
+    }
+
+    #[test]
+    fn picks_the_lowest_beam_or_the_nearest_radar() {
+        // A point 51 km from the western radar and 40 km from the eastern
+        // one, which sits 1 km higher: its beam passes above the other's.
+        let sources = [
+            source(35.0, -97.0, 0.3, 10.0, &[]),
+            source(35.0, -96.0, 1.3, 20.0, &[]),
+        ];
+        let lon = -97.0 + 51.0 / (KM_PER_DEG * 35f64.to_radians().cos());
+        let nearest = build_mosaic(&sources, MosaicMode::NearestRadar);
+        assert_eq!(value_near(&nearest, 35.0, lon), 20.0);
+        let lowest = build_mosaic(&sources, MosaicMode::LowestBeam);
+        assert_eq!(value_near(&lowest, 35.0, lon), 10.0);
+    }
+
+    #[test]
+    fn falls_back_to_a_radar_with_data() {
+        // The western radar has no data looking east.
+        let sources = [
+            source(35.0, -97.0, 0.0, 10.0, &[88, 89, 90, 91]),
+            source(35.0, -96.0, 0.0, 20.0, &[]),
+        ];
+        let grid = build_mosaic(&sources, MosaicMode::LowestBeam);
+        assert_eq!(value_near(&grid, 35.0, -96.8), 20.0);
+        assert_eq!(value_near(&grid, 35.0, -97.2), 10.0);
+    }
+
+    #[test]
+    fn coarsens_cells_for_wide_spreads() {
+        let sources = [
+            source(30.0, -100.0, 0.0, 10.0, &[]),
+            source(45.0, -80.0, 0.0, 20.0, &[]),
+        ];
+        let grid = build_mosaic(&sources, MosaicMode::LowestBeam);
+        assert!(grid.cell_deg > CELL_DEG);
+        assert!((grid.columns * grid.rows) as f64 <= MAX_CELLS * 1.01);
+    }
+}
@@ -0,0 +1,13 @@
+//! Regional mosaic built from neighbouring radars' Level II data.
+//!
+//! The user picks several sites. `neighbour_fetch` downloads each one's
+//! archive volume at the playback time and the decode workers store it like
+//! any other scan; a worker then composites the lowest tilt of every site's
+//! latest cached scan onto one lat/lon grid (`composite`). The main thread
+//! colors the grid and paints it under the active radar, like the national
+//! mosaic.
+
+mod composite;
+
+pub(crate) use composite::{build_mosaic, MosaicSource};
+pub use composite::{MosaicGrid, MosaicMode};
@@ -7,12 +7,9 @@
 //! fetches back off so a broken endpoint does not retry every frame.
 
 use eframe::egui;
-use futures_channel::oneshot;
 use std::cell::RefCell;
 use std::rc::Rc;
 use std::sync::mpsc::{channel, Receiver, Sender};
-use wasm_bindgen::prelude::*;
-use wasm_bindgen::JsCast;
 
 /// NOAA NCEP GeoServer WMS endpoint serving the MRMS quality-controlled
 /// CONUS base reflectivity. Returns a single PNG rendered to whatever bbox
@@ -112,7 +109,7 @@ impl NationalMosaic {
             return;
         }
 
-        let now = js_sys::Date::now() / 1000.0;
+        let now = crate::platform::now_secs();
         let interval = if self.last_attempt_failed {
             FAILURE_BACKOFF_SECS
         } else {
@@ -130,16 +127,16 @@ impl NationalMosaic {
         let sender = self.sender.clone();
         let in_flight = self.in_flight.clone();
         let ctx_clone = ctx.clone();
-        wasm_bindgen_futures::spawn_local(async move {
+        crate::platform::spawn_local(async move {
             let outcome = match fetch_and_decode(MOSAIC_URL).await {
                 Ok(image) => FetchOutcome::Loaded {
                     image,
-                    fetched_at: js_sys::Date::now() / 1000.0,
+                    fetched_at: crate::platform::now_secs(),
                 },
                 Err(e) => {
                     log::warn!("National mosaic fetch failed: {}", e);
                     FetchOutcome::Failed {
-                        attempted_at: js_sys::Date::now() / 1000.0,
+                        attempted_at: crate::platform::now_secs(),
                     }
                 }
             };
@@ -163,7 +160,12 @@ impl NationalMosaic {
 /// Fetch a PNG via browser-native image decoding and convert to an
 /// `egui::ColorImage`. Runs on the main thread via an offscreen 2D canvas;
 /// avoids pulling the `image` crate into the WASM bundle.
+#[cfg(target_arch = "wasm32")]
 async fn fetch_and_decode(url: &str) -> Result<egui::ColorImage, String> {
+    use futures_channel::oneshot;
+    use wasm_bindgen::prelude::*;
+    use wasm_bindgen::JsCast;
+
     let window = web_sys::window().ok_or("no window")?;
     let document = window.document().ok_or("no document")?;
 
@@ -233,3 +235,40 @@ async fn fetch_and_decode(url: &str) -> Result<egui::ColorImage, String> {
         &bytes,
     ))
 }
+
+/// Fetch the PNG over HTTP and decode it with the `png` crate.
+#[cfg(not(target_arch = "wasm32"))]
+async fn fetch_and_decode(url: &str) -> Result<egui::ColorImage, String> {
+    let resp = crate::platform::http_get(url, &[]).await?;
+    if !resp.ok() {
+        return Err(format!("HTTP {}", resp.status));
+    }
+
+    let mut decoder = png::Decoder::new(resp.body.as_slice());
+    // Expand palette, tRNS and sub-byte depths so every frame is 8-bit.
+    decoder.set_transformations(png::Transformations::normalize_to_color8());
+    let mut reader = decoder.read_info().map_err(|e| e.to_string())?;
+    let mut buf = vec![0; reader.output_buffer_size()];
+    let info = reader.next_frame(&mut buf).map_err(|e| e.to_string())?;
+    let (w, h) = (info.width as usize, info.height as usize);
+    if w == 0 || h == 0 {
+        return Err("image has zero dimensions".into());
+    }
+    let pixels = &buf[..info.buffer_size()];
+
+    let rgba: Vec<u8> = match info.color_type {
+        png::ColorType::Rgba => pixels.to_vec(),
+        png::ColorType::Rgb => pixels
+            .chunks_exact(3)
+            .flat_map(|p| [p[0], p[1], p[2], 255])
+            .collect(),
+        png::ColorType::GrayscaleAlpha => pixels
+            .chunks_exact(2)
+            .flat_map(|p| [p[0], p[0], p[0], p[1]])
+            .collect(),
+        png::ColorType::Grayscale => pixels.iter().flat_map(|&v| [v, v, v, 255]).collect(),
+        png::ColorType::Indexed => return Err("palette was not expanded".into()),
+    };
+
+    Ok(egui::ColorImage::from_rgba_unmultiplied([w, h], &rgba))
+}
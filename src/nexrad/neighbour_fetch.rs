@@ -0,0 +1,281 @@
+//! Archive downloads for sites other than the active one.
+//!
+//! The regional mosaic and pinned secondary sites both need volumes from
+//! neighbouring radars. Each site's archive listing is searched for the
+//! volumes current over the requested window: every volume starting inside
+//! it, plus the last one starting before it within an age limit. Volumes
+//! already complete in the cache are skipped; the rest are downloaded one
+//! at a time and handed to the UI thread, which sends them to a decode
+//! worker like any other archive.
+
+use crate::data::{DataFacade, ScanCompleteness, ScanIndexEntry, ScanKey, SiteId, UnixMillis};
+use crate::nexrad::archive_index::ArchiveFileMeta;
+use crate::nexrad::data_source;
+use crate::nexrad::download::{with_timeout, NetworkStats, REQUEST_TIMEOUT_MS};
+use chrono::DateTime;
+use eframe::egui;
+use std::sync::mpsc::{channel, Receiver, Sender};
+
+/// Most volumes fetched per site; the latest ones in the window are kept.
+const MAX_VOLUMES: usize = 60;
+
+/// Progress of a fetch, delivered through [`NeighbourChannel::try_recv`].
+pub enum NeighbourEvent {
+    /// The site's listing found the volumes to fetch, as (file name, start
+    /// in Unix seconds), or failed.
+    Listed {
+        site_id: String,
+        result: Result<Vec<(String, i64)>, String>,
+    },
+    /// A volume was downloaded and needs ingesting.
+    Downloaded {
+        site_id: String,
+        file_name: String,
+        /// Volume start, Unix seconds.
+        timestamp: i64,
+        fetch_latency_ms: f64,
+        data: Vec<u8>,
+    },
+    /// A volume is already complete in the cache.
+    Cached { site_id: String, file_name: String },
+    Failed {
+        site_id: String,
+        file_name: String,
+        message: String,
+    },
+    /// Every scan of a site in the cache, from [`NeighbourChannel::list_cached`].
+    Scans {
+        site_id: String,
+        result: Result<Vec<ScanIndexEntry>, String>,
+    },
+}
+
+/// Indices of the volumes in `files` (name, start time) current between
+/// `start` and `end` (Unix seconds), oldest first, limited to the latest
+/// [`MAX_VOLUMES`]. Metadata-only files are skipped.
+fn pick_volumes(files: &[(String, i64)], start: i64, end: i64, max_age_secs: i64) -> Vec<usize> {
+    let mut volumes: Vec<usize> = (0..files.len())
+        .filter(|&i| !files[i].0.ends_with("_MDM"))
+        .collect();
+    volumes.sort_by_key(|&i| files[i].1);
+    let first = volumes
+        .iter()
+        .rposition(|&i| files[i].1 <= start && files[i].1 >= start - max_age_secs)
+        .unwrap_or_else(|| volumes.partition_point(|&i| files[i].1 <= start));
+    let last = volumes.partition_point(|&i| files[i].1 <= end);
+    let mut picked = volumes[first..last.max(first)].to_vec();
+    let excess = picked.len().saturating_sub(MAX_VOLUMES);
+    picked.drain(..excess);
+    picked
+}
+
+/// Channel delivering neighbouring sites' volume downloads to the UI thread.
+pub struct NeighbourChannel {
+    sender: Sender<NeighbourEvent>,
+    receiver: Receiver<NeighbourEvent>,
+}
+
+impl Default for NeighbourChannel {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl NeighbourChannel {
+    pub fn new() -> Self {
+        let (sender, receiver) = channel();
+        Self { sender, receiver }
+    }
+
+    /// Find and download the volumes of every site in `site_ids` current
+    /// between `start` and `end` (Unix seconds). Sites are fetched
+    /// concurrently.
+    #[allow(clippy::too_many_arguments)]
+    pub fn fetch(
+        &self,
+        ctx: egui::Context,
+        site_ids: Vec<String>,
+        start: i64,
+        end: i64,
+        max_age_secs: i64,
+        facade: DataFacade,
+        stats: NetworkStats,
+    ) {
+        for site_id in site_ids {
+            let sender = self.sender.clone();
+            let ctx = ctx.clone();
+            let facade = facade.clone();
+            let stats = stats.clone();
+            crate::platform::spawn_local(async move {
+                let send = |event| {
+                    let _ = sender.send(event);
+                    ctx.request_repaint();
+                };
+                fetch_site(site_id, start, end, max_age_secs, facade, stats, send).await;
+            });
+        }
+    }
+
+    /// List every scan of `site_id` in the cache.
+    pub fn list_cached(&self, ctx: egui::Context, site_id: String, facade: DataFacade) {
+        let sender = self.sender.clone();
+        crate::platform::spawn_local(async move {
+            let result = facade
+                .list_scans(&SiteId::new(&site_id), UnixMillis(0), UnixMillis::now())
+                .await
+                .map_err(|e| format!("Failed to list cached scans: {}", e));
+            let _ = sender.send(NeighbourEvent::Scans { site_id, result });
+            ctx.request_repaint();
+        });
+    }
+
+    pub fn try_recv(&self) -> Option<NeighbourEvent> {
+        self.receiver.try_recv().ok()
+    }
+}
+
+async fn fetch_site(
+    site_id: String,
+    start: i64,
+    end: i64,
+    max_age_secs: i64,
+    facade: DataFacade,
+    stats: NetworkStats,
+    send: impl Fn(NeighbourEvent),
+) {
+    let (Some(first), Some(last)) = (
+        DateTime::from_timestamp(start - max_age_secs, 0),
+        DateTime::from_timestamp(end, 0),
+    ) else {
+        send(NeighbourEvent::Listed {
+            site_id,
+            result: Ok(Vec::new()),
+        });
+        return;
+    };
+
+    // The window can straddle midnight, so every day in it is listed.
+    let mut identifiers = Vec::new();
+    let mut files = Vec::new();
+    for date in first.date_naive().iter_days() {
+        if date > last.date_naive() {
+            break;
+        }
+        stats.request_started();
+        let listing = with_timeout(
+            data_source::list_archive_files(&site_id, &date),
+            REQUEST_TIMEOUT_MS,
+            "Archive listing",
+        )
+        .await;
+        stats.request_completed(0);
+        let listed = match listing {
+            Ok(Ok(listed)) => listed,
+            Ok(Err(e)) => {
+                let message = format!("Failed to list files: {}", e);
+                send(NeighbourEvent::Listed {
+                    site_id,
+                    result: Err(message),
+                });
+                return;
+            }
+            Err(timeout_msg) => {
+                send(NeighbourEvent::Listed {
+                    site_id,
+                    result: Err(timeout_msg),
+                });
+                return;
+            }
+        };
+        for identifier in listed {
+            let name = identifier.name().to_string();
+            if let Some(time) = ArchiveFileMeta::parse_timestamp_from_name(&name, &date) {
+                files.push((name, time));
+                identifiers.push(Some(identifier));
+            }
+        }
+    }
+
+    let picked = pick_volumes(&files, start, end, max_age_secs);
+    log::info!("neighbours: {} volume(s) for {}", picked.len(), site_id);
+    send(NeighbourEvent::Listed {
+        site_id: site_id.clone(),
+        result: Ok(picked.iter().map(|&i| files[i].clone()).collect()),
+    });
+
+    for index in picked {
+        let (file_name, timestamp) = files[index].clone();
+        let scan_key = ScanKey::from_secs(&site_id, timestamp);
+        if let Ok(Some(entry)) = facade.scan_availability(&scan_key).await {
+            if entry.completeness() == ScanCompleteness::Complete {
+                log::debug!("neighbours: cache hit for {}", scan_key);
+                send(NeighbourEvent::Cached {
+                    site_id: site_id.clone(),
+                    file_name,
+                });
+                continue;
+            }
+        }
+
+        let Some(identifier) = identifiers[index].take() else {
+            continue;
+        };
+        stats.request_started();
+        let fetch_start = web_time::Instant::now();
+        let download = with_timeout(
+            data_source::download_archive_file(identifier),
+            REQUEST_TIMEOUT_MS,
+            "File download",
+        )
+        .await;
+        let message = match download {
+            Ok(Ok(file)) => {
+                let data = file.data().to_vec();
+                stats.request_completed(data.len() as u64);
+                send(NeighbourEvent::Downloaded {
+                    site_id: site_id.clone(),
+                    file_name,
+                    timestamp,
+                    fetch_latency_ms: fetch_start.elapsed().as_secs_f64() * 1000.0,
+                    data,
+                });
+                continue;
+            }
+            Ok(Err(e)) => format!("Download failed: {}", e),
+            Err(timeout_msg) => timeout_msg,
+        };
+        stats.request_completed(0);
+        send(NeighbourEvent::Failed {
+            site_id: site_id.clone(),
+            file_name,
+            message,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn picks_the_volumes_current_over_the_window() {
+        let files = [
+            ("KTLX20240501_120500_V06".to_string(), 1_714_565_100),
+            ("KTLX20240501_115500_V06".to_string(), 1_714_564_500),
+            ("KTLX20240501_120000_V06_MDM".to_string(), 1_714_564_800),
+            ("KTLX20240501_120000_V06".to_string(), 1_714_564_800),
+            ("KTLX20240501_121000_V06".to_string(), 1_714_565_400),
+        ];
+        // A single time takes the last volume before it.
+        assert_eq!(pick_volumes(&files, 1_714_565_000, 1_714_565_000, 600), [3]);
+        assert_eq!(pick_volumes(&files, 1_714_565_100, 1_714_565_100, 600), [0]);
+        // A window adds every volume starting inside it.
+        assert_eq!(
+            pick_volumes(&files, 1_714_565_000, 1_714_565_400, 600),
+            [3, 0, 4]
+        );
+        // Nothing recent enough before the window.
+        assert!(pick_volumes(&files, 1_714_566_600, 1_714_566_600, 600).is_empty());
+        assert_eq!(pick_volumes(&files, 1_714_564_000, 1_714_564_600, 60), [1]);
+    }
+}
@@ -2,16 +2,20 @@
 //!
 //! Listens for `network-metric` messages from the service worker and
 //! accumulates per-request telemetry into a pending queue (for the UI
-//! request log) and aggregate session statistics.
+//! request log) and aggregate session statistics. The desktop build has no
+//! service worker, so there the monitor is never created.
 
 use std::cell::RefCell;
 use std::rc::Rc;
+#[cfg(target_arch = "wasm32")]
 use wasm_bindgen::prelude::*;
+#[cfg(target_arch = "wasm32")]
 use wasm_bindgen::JsCast;
 
 /// Cap on the pending buffer. The main loop drains each frame so this
 /// should never be reached in practice; it only bounds memory if the
 /// UI stalls for long enough that thousands of metrics accumulate.
+#[cfg(target_arch = "wasm32")]
 const MAX_PENDING_REQUESTS: usize = 500;
 
 /// A single completed network request reported by the service worker.
@@ -50,15 +54,26 @@ pub struct NetworkAggregate {
 /// Listens for service worker messages and accumulates network metrics.
 ///
 /// Holds a JS closure that prevents garbage collection of the event listener.
+#[cfg_attr(not(target_arch = "wasm32"), allow(dead_code))]
 pub struct NetworkMonitor {
     pending: Rc<RefCell<Vec<NetworkRequest>>>,
     aggregate: Rc<RefCell<NetworkAggregate>>,
+    #[cfg(target_arch = "wasm32")]
     _listener: Closure<dyn FnMut(web_sys::MessageEvent)>,
 }
 
+#[cfg_attr(not(target_arch = "wasm32"), allow(dead_code))]
 impl NetworkMonitor {
+    /// Requests made by the desktop build don't pass through a service
+    /// worker, so there is nothing to listen to.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn new() -> Option<Self> {
+        None
+    }
+
     /// Create a new monitor and attach a message listener to the service worker
     /// container. Returns `None` if service workers are not available.
+    #[cfg(target_arch = "wasm32")]
     pub fn new() -> Option<Self> {
         let window = web_sys::window()?;
         let navigator = window.navigator();
@@ -113,7 +128,7 @@ impl NetworkMonitor {
                     bytes,
                     duration_ms: duration,
                     ok,
-                    timestamp_ms: js_sys::Date::now(),
+                    timestamp_ms: crate::platform::now_ms(),
                     error,
                     operation_id: None,
                 };
@@ -174,10 +189,15 @@ impl NetworkMonitor {
 
 /// Check whether the current browsing context is cross-origin isolated
 /// (i.e., `self.crossOriginIsolated` is true), which means `SharedArrayBuffer`
-/// is available.
+/// is available. Always false on the desktop, where the flag has no meaning.
 pub fn is_cross_origin_isolated() -> bool {
-    js_sys::Reflect::get(&js_sys::global(), &"crossOriginIsolated".into())
-        .ok()
-        .and_then(|v| v.as_bool())
-        .unwrap_or(false)
+    #[cfg(target_arch = "wasm32")]
+    {
+        js_sys::Reflect::get(&js_sys::global(), &"crossOriginIsolated".into())
+            .ok()
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false)
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    false
 }
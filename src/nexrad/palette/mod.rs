@@ -0,0 +1,209 @@
+//! User color tables for the base moments.
+//!
+//! Palettes come from GRLevelX `.pal` files (see `pal`): a few are bundled,
+//! and users import their own, which are kept as source text in
+//! [`PaletteSettings`]. One palette can be chosen per moment; Level III and
+//! derived products that share a moment's table (composite reflectivity,
+//! dealiased velocity, N0Q, ...) follow it. [`configure`] installs the
+//! choices and `color_table::build_product_lut` consults [`active_for`]
+//! before falling back to the built-in tables.
+
+mod pal;
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use crate::nexrad::level3::Level3Product;
+use crate::state::PaletteSettings;
+
+pub use pal::Palette;
+
+/// Moments that take a palette: worker product string and panel label.
+pub const MOMENTS: &[(&str, &str)] = &[
+    ("reflectivity", "Reflectivity"),
+    ("velocity", "Velocity"),
+    ("spectrum_width", "Spectrum Width"),
+    ("differential_reflectivity", "ZDR"),
+    ("correlation_coefficient", "CC"),
+    ("differential_phase", "Differential Phase"),
+];
+
+/// Palettes shipped with the app, by name.
+pub(crate) const BUNDLED: &[(&str, &str)] = &[
+    (
+        "NWS Reflectivity",
+        include_str!("../../../assets/palettes/nws_reflectivity.pal"),
+    ),
+    (
+        "Viridis Reflectivity",
+        include_str!("../../../assets/palettes/viridis_reflectivity.pal"),
+    ),
+    (
+        "Classic Velocity (kt)",
+        include_str!("../../../assets/palettes/classic_velocity.pal"),
+    ),
+    (
+        "Blue-Orange Velocity (kt)",
+        include_str!("../../../assets/palettes/blue_orange_velocity.pal"),
+    ),
+    (
+        "Cividis CC",
+        include_str!("../../../assets/palettes/cividis_cc.pal"),
+    ),
+];
+
+// WASM is single-threaded so thread_local! is safe.
+thread_local! {
+    static ACTIVE: RefCell<HashMap<&'static str, Rc<Palette>>> = RefCell::new(HashMap::new());
+}
+
+/// Install the selected palettes. Moments without a selection, or whose
+/// palette no longer parses, use the built-in table.
+pub fn configure(settings: &PaletteSettings) {
+    let mut active = HashMap::new();
+    for &(moment, _) in MOMENTS {
+        let Some(name) = settings.selected.get(moment) else {
+            continue;
+        };
+        let Some(source) = source_for(settings, moment, name) else {
+            log::warn!("Palette {:?} for {} not found", name, moment);
+            continue;
+        };
+        match Palette::parse(name, source) {
+            Ok(palette) => {
+                active.insert(moment, Rc::new(palette));
+            }
+            Err(e) => log::warn!("Palette {:?} for {}: {}", name, moment, e),
+        }
+    }
+    ACTIVE.with(|a| *a.borrow_mut() = active);
+}
+
+/// The user's palette for a worker product string, if one is selected.
+pub fn active_for(product_str: &str) -> Option<Rc<Palette>> {
+    let moment = moment_of(product_str)?;
+    ACTIVE.with(|a| a.borrow().get(moment).cloned())
+}
+
+/// The moment whose palette colors `product_str`.
+pub fn moment_of(product_str: &str) -> Option<&'static str> {
+    let product_str = match product_str {
+        "composite_reflectivity" => "reflectivity",
+        "dealiased_velocity" => "velocity",
+        s => match Level3Product::from_worker_string(s) {
+            Some(product) => product.base_product(),
+            None => s,
+        },
+    };
+    MOMENTS
+        .iter()
+        .map(|&(moment, _)| moment)
+        .find(|&moment| moment == product_str)
+}
+
+/// The moment for a `.pal` `Product:` code, covering the codes GRLevelX
+/// and GR2Analyst write as well as Level III mnemonics.
+pub fn moment_for_code(code: &str) -> Option<&'static str> {
+    Some(match code.trim().to_ascii_uppercase().as_str() {
+        "BR" | "REF" | "DBZ" | "CR" | "N0Q" | "N0R" => "reflectivity",
+        "BV" | "VEL" | "SRV" | "N0U" | "N0V" => "velocity",
+        "SW" | "N0W" => "spectrum_width",
+        "ZDR" | "DR" | "N0X" => "differential_reflectivity",
+        "CC" | "RHO" | "N0C" => "correlation_coefficient",
+        "PHI" | "KDP" | "PHIDP" | "N0K" => "differential_phase",
+        _ => return None,
+    })
+}
+
+/// Names of the bundled and imported palettes available for `moment`.
+pub fn available(settings: &PaletteSettings, moment: &str) -> Vec<String> {
+    let bundled = BUNDLED
+        .iter()
+        .filter(|(_, source)| bundled_moment(source) == Some(moment))
+        .map(|(name, _)| name.to_string());
+    let imported = settings
+        .imported
+        .iter()
+        .filter(|p| p.moment == moment)
+        .map(|p| p.name.clone());
+    bundled.chain(imported).collect()
+}
+
+/// Source text of the palette `name` for `moment`. Imported palettes shadow
+/// bundled ones of the same name.
+fn source_for<'a>(settings: &'a PaletteSettings, moment: &str, name: &str) -> Option<&'a str> {
+    settings
+        .imported
+        .iter()
+        .find(|p| p.moment == moment && p.name == name)
+        .map(|p| p.source.as_str())
+        .or_else(|| {
+            BUNDLED
+                .iter()
+                .find(|(n, _)| *n == name)
+                .map(|(_, source)| *source)
+        })
+}
+
+/// Bundled palettes declare their moment on the `Product:` line.
+fn bundled_moment(source: &str) -> Option<&'static str> {
+    source.lines().find_map(|line| {
+        let (key, value) = line.split_once(':')?;
+        if key.trim().eq_ignore_ascii_case("product") {
+            moment_for_code(value)
+        } else {
+            None
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nexrad::color_table::{build_product_lut, value_range_for};
+
+    #[test]
+    fn derived_products_follow_their_moment() {
+        assert_eq!(moment_of("composite_reflectivity"), Some("reflectivity"));
+        assert_eq!(moment_of("dealiased_velocity"), Some("velocity"));
+        assert_eq!(
+            moment_of(Level3Product::N0c.worker_string()),
+            Some("correlation_coefficient")
+        );
+        assert_eq!(moment_of("vil"), None);
+        assert_eq!(moment_of("hydrometeor_class"), None);
+    }
+
+    #[test]
+    fn bundled_palettes_are_offered_for_their_moment() {
+        let settings = PaletteSettings::default();
+        assert_eq!(
+            available(&settings, "velocity"),
+            vec!["Classic Velocity (kt)", "Blue-Orange Velocity (kt)"]
+        );
+        assert!(available(&settings, "spectrum_width").is_empty());
+    }
+
+    #[test]
+    fn configured_palette_replaces_the_built_in_table() {
+        let built_in = build_product_lut("velocity");
+        let mut settings = PaletteSettings::default();
+        settings.selected.insert(
+            "velocity".to_string(),
+            "Blue-Orange Velocity (kt)".to_string(),
+        );
+        configure(&settings);
+
+        let palette = active_for("dealiased_velocity").unwrap();
+        assert_eq!(palette.range_folded, Some([140, 90, 160, 255]));
+        let (min_val, max_val) = value_range_for("velocity");
+        let lut = build_product_lut("velocity");
+        assert_eq!(lut, palette.build_lut(min_val, max_val));
+        assert_ne!(lut, built_in);
+        assert!(active_for("reflectivity").is_none());
+
+        configure(&PaletteSettings::default());
+        assert_eq!(build_product_lut("velocity"), built_in);
+    }
+}
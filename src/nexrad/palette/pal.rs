@@ -0,0 +1,294 @@
+//! Parser for the GRLevelX / GR2Analyst `.pal` color table format.
+//!
+//! A palette is a list of `key: value` lines; `;` starts a comment:
+//!
+//! ```text
+//! Product: BV
+//! Units: KTS
+//! Step: 10
+//! Scale: 1.943844
+//! Color: -60 0 200 0          ; gradient to the next entry's color
+//! Color: -10 0 90 0 110 110 110 ; gradient to an explicit end color
+//! SolidColor: 10 160 0 0      ; flat until the next entry
+//! Color4: 65 253 231 37 255   ; RGBA variants of both
+//! RF: 119 0 125               ; range-folded gates
+//! ```
+//!
+//! Entry values are in palette units, `physical * Scale + Offset`. Values
+//! below the first entry are transparent; the last entry's color holds to
+//! the top of the product's range. Keys the app has no use for (`Decimals`,
+//! `ND`, ...) are accepted and ignored.
+
+/// A parsed color table.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Palette {
+    /// Display name: the file stem for imported palettes.
+    pub name: String,
+    /// `Product:` code as written in the file (e.g. `BR`, `BV`, `CC`).
+    pub product: Option<String>,
+    /// `Units:` label, shown on the legend in place of the product's unit.
+    pub units: Option<String>,
+    /// `Step:` legend tick spacing, in palette units.
+    pub step: Option<f32>,
+    /// Palette units per physical unit, e.g. 1.9438 for knots.
+    pub scale: f32,
+    /// Added after scaling.
+    pub offset: f32,
+    /// Color of range-folded gates (`RF:`); they stay hidden without one.
+    pub range_folded: Option<[u8; 4]>,
+    /// Sorted by value.
+    entries: Vec<Entry>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Entry {
+    value: f32,
+    color: [u8; 4],
+    /// Color reached just before the next entry; `None` blends into the
+    /// next entry's color.
+    end: Option<[u8; 4]>,
+    solid: bool,
+}
+
+impl Palette {
+    /// Parse `.pal` source text. Errors name the offending line.
+    pub fn parse(name: &str, text: &str) -> Result<Self, String> {
+        let mut palette = Palette {
+            name: name.to_string(),
+            product: None,
+            units: None,
+            step: None,
+            scale: 1.0,
+            offset: 0.0,
+            range_folded: None,
+            entries: Vec::new(),
+        };
+
+        for (i, line) in text.lines().enumerate() {
+            let line = line.split(';').next().unwrap_or_default().trim();
+            if line.is_empty() {
+                continue;
+            }
+            let err = |e: String| format!("line {}: {}", i + 1, e);
+            let (key, value) = line
+                .split_once(':')
+                .ok_or_else(|| err(format!("expected `key: value`, got `{}`", line)))?;
+            let value = value.trim();
+            match key.trim().to_ascii_lowercase().as_str() {
+                "product" => palette.product = Some(value.to_string()),
+                "units" => palette.units = Some(value.to_string()).filter(|u| !u.is_empty()),
+                "step" => palette.step = Some(parse_number(value).map_err(err)?),
+                "scale" => {
+                    let scale = parse_number(value).map_err(err)?;
+                    if scale == 0.0 {
+                        return Err(err("scale must not be zero".to_string()));
+                    }
+                    palette.scale = scale;
+                }
+                "offset" => palette.offset = parse_number(value).map_err(err)?,
+                "rf" => {
+                    let fields = numbers(value).map_err(err)?;
+                    palette.range_folded = Some(match fields.as_slice() {
+                        [r, g, b] => rgba(&[*r, *g, *b, 255.0]).map_err(err)?,
+                        [r, g, b, a] => rgba(&[*r, *g, *b, *a]).map_err(err)?,
+                        _ => return Err(err("RF needs 3 or 4 components".to_string())),
+                    });
+                }
+                key @ ("color" | "color4" | "solidcolor" | "solidcolor4") => {
+                    let entry = parse_entry(key, value).map_err(err)?;
+                    palette.entries.push(entry);
+                }
+                _ => {}
+            }
+        }
+
+        if palette.entries.is_empty() {
+            return Err("no Color lines".to_string());
+        }
+        palette.entries.sort_by(|a, b| a.value.total_cmp(&b.value));
+        Ok(palette)
+    }
+
+    /// Palette units for a physical value.
+    pub fn to_display(&self, physical: f32) -> f32 {
+        physical * self.scale + self.offset
+    }
+
+    /// Physical value for a value in palette units.
+    pub fn to_physical(&self, display: f32) -> f32 {
+        (display - self.offset) / self.scale
+    }
+
+    /// RGBA at a value in palette units.
+    pub fn color_at(&self, display: f32) -> [u8; 4] {
+        let idx = self.entries.partition_point(|e| e.value <= display);
+        let Some(entry) = idx.checked_sub(1).map(|i| &self.entries[i]) else {
+            return [0, 0, 0, 0];
+        };
+        let Some(next) = self.entries.get(idx) else {
+            return entry.color;
+        };
+        if entry.solid {
+            return entry.color;
+        }
+        let end = entry.end.unwrap_or(next.color);
+        let t = (display - entry.value) / (next.value - entry.value);
+        std::array::from_fn(|c| {
+            let (a, b) = (entry.color[c] as f32, end[c] as f32);
+            (a + (b - a) * t).round() as u8
+        })
+    }
+
+    /// 1024-entry RGBA LUT over physical `[min_val, max_val]`, laid out like
+    /// the built-in tables in `color_table`.
+    pub fn build_lut(&self, min_val: f32, max_val: f32) -> Vec<u8> {
+        let lut_size = 1024usize;
+        let mut data = Vec::with_capacity(lut_size * 4);
+        for i in 0..lut_size {
+            let physical = min_val + i as f32 / (lut_size - 1) as f32 * (max_val - min_val);
+            data.extend_from_slice(&self.color_at(self.to_display(physical)));
+        }
+        data
+    }
+}
+
+/// One `Color`, `Color4`, `SolidColor` or `SolidColor4` line.
+fn parse_entry(key: &str, value: &str) -> Result<Entry, String> {
+    let fields = numbers(value)?;
+    let channels = if key.ends_with('4') { 4 } else { 3 };
+    let solid = key.starts_with("solid");
+    let color = |f: &[f32]| {
+        if channels == 4 {
+            rgba(f)
+        } else {
+            rgba(&[f[0], f[1], f[2], 255.0])
+        }
+    };
+    let (value, rest) = fields
+        .split_first()
+        .ok_or_else(|| "missing value".to_string())?;
+    let (color, end) = match rest.len() {
+        n if n == channels => (color(rest)?, None),
+        n if n == 2 * channels && !solid => {
+            (color(&rest[..channels])?, Some(color(&rest[channels..])?))
+        }
+        _ => {
+            return Err(format!(
+                "expected a value and {} color components",
+                if solid {
+                    channels.to_string()
+                } else {
+                    format!("{} or {}", channels, 2 * channels)
+                }
+            ))
+        }
+    };
+    Ok(Entry {
+        value: *value,
+        color,
+        end,
+        solid,
+    })
+}
+
+fn parse_number(s: &str) -> Result<f32, String> {
+    s.parse().map_err(|_| format!("bad number `{}`", s))
+}
+
+/// Whitespace- or comma-separated numbers.
+fn numbers(s: &str) -> Result<Vec<f32>, String> {
+    s.split(|c: char| c.is_whitespace() || c == ',')
+        .filter(|f| !f.is_empty())
+        .map(parse_number)
+        .collect()
+}
+
+fn rgba(f: &[f32]) -> Result<[u8; 4], String> {
+    let mut out = [0u8; 4];
+    for (o, &v) in out.iter_mut().zip(f) {
+        if !(0.0..=255.0).contains(&v) {
+            return Err(format!("color component {} out of range", v));
+        }
+        *o = v.round() as u8;
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gradients_blend_to_the_next_entry_or_their_end_color() {
+        let p = Palette::parse(
+            "test",
+            "Product: BR\nColor: 0 0 0 0\nColor: 10 100 100 100 200 0 0\nColor: 20 0 0 200",
+        )
+        .unwrap();
+        assert_eq!(p.color_at(-1.0), [0, 0, 0, 0]);
+        assert_eq!(p.color_at(5.0), [50, 50, 50, 255]);
+        assert_eq!(p.color_at(15.0), [150, 50, 50, 255]);
+        // The last entry holds above its value.
+        assert_eq!(p.color_at(80.0), [0, 0, 200, 255]);
+    }
+
+    #[test]
+    fn solid_and_rgba_entries() {
+        let p = Palette::parse(
+            "test",
+            "SolidColor4: 5 10 20 30 40\nsolidcolor: 10 1 2 3 ; comment\nColor4: 20 0 0 0 0",
+        )
+        .unwrap();
+        assert_eq!(p.color_at(7.0), [10, 20, 30, 40]);
+        assert_eq!(p.color_at(19.9), [1, 2, 3, 255]);
+        assert_eq!(p.color_at(20.0), [0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn header_fields() {
+        let p = Palette::parse(
+            "vel",
+            "; header\nProduct: BV\nUnits: KTS\nStep: 10\nScale: 2\nOffset: 1\n\
+             Decimals: 0\nRF: 119 0 125\nColor: 0 0 0 0",
+        )
+        .unwrap();
+        assert_eq!(p.product.as_deref(), Some("BV"));
+        assert_eq!(p.units.as_deref(), Some("KTS"));
+        assert_eq!(p.step, Some(10.0));
+        assert_eq!(p.range_folded, Some([119, 0, 125, 255]));
+        assert_eq!(p.to_display(10.0), 21.0);
+        assert_eq!(p.to_physical(21.0), 10.0);
+    }
+
+    #[test]
+    fn lut_is_built_in_physical_units() {
+        // Palette in units of twice the physical value: 10 palette = 5 physical.
+        let p = Palette::parse("test", "Scale: 2\nSolidColor: 10 255 0 0").unwrap();
+        let lut = p.build_lut(0.0, 10.23);
+        assert_eq!(&lut[4 * 490..4 * 491], &[0, 0, 0, 0]);
+        assert_eq!(&lut[4 * 510..4 * 511], &[255, 0, 0, 255]);
+    }
+
+    #[test]
+    fn errors_name_the_line() {
+        assert_eq!(
+            Palette::parse("bad", "Product: BR\nColor: 5 1 2").unwrap_err(),
+            "line 2: expected a value and 3 or 6 color components"
+        );
+        assert_eq!(
+            Palette::parse("bad", "Color: 5 1 2 300").unwrap_err(),
+            "line 1: color component 300 out of range"
+        );
+        assert_eq!(
+            Palette::parse("bad", "Units: dBZ").unwrap_err(),
+            "no Color lines"
+        );
+    }
+
+    #[test]
+    fn bundled_palettes_parse() {
+        for (name, source) in super::super::BUNDLED {
+            Palette::parse(name, source).unwrap_or_else(|e| panic!("{}: {}", name, e));
+        }
+    }
+}
@@ -0,0 +1,113 @@
+//! Polar sweep sampling.
+//!
+//! Sweeps list their radials in collection order with uneven spacing, so
+//! finding the radial under an azimuth is a linear scan. Code that samples
+//! a sweep many times (cross-sections, gridded exports) builds an
+//! [`AzimuthIndex`] once and looks radials up through fixed 0.5° bins.
+//! [`PolarSweep`] is a decoded sweep in physical units for such consumers.
+
+use crate::data::keys::PrecomputedSweep;
+use crate::data::moments::decode_physical;
+
+/// Lookup resolution, bins per degree.
+const BINS_PER_DEG: usize = 2;
+
+/// Nearest radial for every 0.5° azimuth bin.
+pub(crate) struct AzimuthIndex {
+    /// Radial index per bin (None = no radial near it).
+    bins: Vec<Option<usize>>,
+}
+
+impl AzimuthIndex {
+    /// Index the first `azimuth_count` entries of `azimuths`. Negative
+    /// azimuths mark empty padded slots and are skipped. A bin only gets a
+    /// radial within 1.5× the nominal spacing, so gaps stay empty.
+    pub(crate) fn new(azimuths: &[f32], azimuth_count: usize) -> Self {
+        let max_gap = 1.5 * 360.0 / azimuth_count.max(1) as f32;
+        let bins = (0..360 * BINS_PER_DEG)
+            .map(|bin| {
+                let target = (bin as f32 + 0.5) / BINS_PER_DEG as f32;
+                let (idx, dist) = azimuths
+                    .iter()
+                    .take(azimuth_count)
+                    .enumerate()
+                    .filter(|(_, &az)| az >= 0.0)
+                    .map(|(i, &az)| {
+                        let d = (target - az).rem_euclid(360.0);
+                        (i, d.min(360.0 - d))
+                    })
+                    .min_by(|a, b| a.1.total_cmp(&b.1))?;
+                (dist <= max_gap).then_some(idx)
+            })
+            .collect();
+        Self { bins }
+    }
+
+    /// Radial nearest to `azimuth_deg`, if any.
+    pub(crate) fn radial(&self, azimuth_deg: f32) -> Option<usize> {
+        let bin = (azimuth_deg.rem_euclid(360.0) * BINS_PER_DEG as f32) as usize;
+        *self.bins.get(bin)?
+    }
+}
+
+/// One sweep decoded to physical values, NaN where there is no data.
+pub(crate) struct PolarSweep {
+    /// Radial azimuths in degrees; negative entries are empty slots.
+    pub azimuths: Vec<f32>,
+    pub gate_count: usize,
+    pub first_gate_km: f64,
+    pub gate_interval_km: f64,
+    /// Physical values, `azimuths.len() × gate_count`, row-major by radial.
+    pub values: Vec<f32>,
+}
+
+impl PolarSweep {
+    /// Value at `azimuth_deg`, `range_km` through a prebuilt `index`. Gates
+    /// cover `[start, start + interval)`, as in the inspector.
+    pub(crate) fn value_at(&self, index: &AzimuthIndex, azimuth_deg: f32, range_km: f64) -> f32 {
+        let gate = (range_km - self.first_gate_km) / self.gate_interval_km;
+        if !(0.0..self.gate_count as f64).contains(&gate) {
+            return f32::NAN;
+        }
+        index
+            .radial(azimuth_deg)
+            .and_then(|radial| self.values.get(radial * self.gate_count + gate as usize))
+            .copied()
+            .unwrap_or(f32::NAN)
+    }
+
+    /// Azimuth index over this sweep's radials.
+    pub(crate) fn azimuth_index(&self) -> AzimuthIndex {
+        AzimuthIndex::new(&self.azimuths, self.azimuths.len())
+    }
+
+    /// Decode a stored sweep.
+    pub(crate) fn from_precomputed(sweep: &PrecomputedSweep) -> Self {
+        Self {
+            azimuths: sweep.azimuths.clone(),
+            gate_count: sweep.gate_count as usize,
+            first_gate_km: sweep.first_gate_range_km,
+            gate_interval_km: sweep.gate_interval_km,
+            values: decode_physical(sweep),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_nearest_radial_and_leaves_gaps_empty() {
+        // One-degree radials, except 90°-179° which are missing.
+        let azimuths: Vec<f32> = (0..360)
+            .filter(|a| !(90..180).contains(a))
+            .map(|a| a as f32 + 0.5)
+            .collect();
+        let index = AzimuthIndex::new(&azimuths, 360);
+        assert_eq!(index.radial(10.7), Some(10));
+        assert_eq!(index.radial(359.9), Some(azimuths.len() - 1));
+        assert_eq!(index.radial(-0.2), Some(azimuths.len() - 1));
+        assert_eq!(index.radial(135.0), None);
+    }
+}
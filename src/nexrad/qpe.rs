@@ -0,0 +1,358 @@
+//! Quantitative precipitation estimation (QPE): rainfall accumulated over a
+//! window of cached scans.
+//!
+//! Each scan's lowest reflectivity tilt is converted to a rain rate with a
+//! Z-R relationship (`Z = a·R^b`). Where dual-pol data shows a significant
+//! specific differential phase in heavy rain, `R(KDP)` replaces it, since it
+//! is immune to calibration offsets, partial beam blockage and hail. Rates
+//! are averaged onto the same 0.5° × 1 km polar grid as the volume products
+//! and held until the next scan, so the depth is `Σ Rᵢ · Δtᵢ`.
+//!
+//! Accumulations are computed on demand in the worker from sweeps already in
+//! IndexedDB and are never stored.
+
+use super::derived::{project_tilt, GRID_AZIMUTHS, GRID_AZ_DEG, GRID_GATES, GRID_GATE_KM};
+use crate::data::keys::{GateValues, PrecomputedSweep};
+use crate::data::moments::{decode_physical, specific_differential_phase};
+use serde::{Deserialize, Serialize};
+
+/// Product keys for the accumulations (worker protocol only, never in IDB).
+pub(crate) const QPE_1H: &str = "qpe_1h";
+pub(crate) const QPE_3H: &str = "qpe_3h";
+pub(crate) const QPE_STORM_TOTAL: &str = "qpe_storm_total";
+
+/// Whether `product` is a rainfall accumulation.
+pub(crate) fn is_qpe_product(product: &str) -> bool {
+    matches!(product, QPE_1H | QPE_3H | QPE_STORM_TOTAL)
+}
+
+/// Trailing window of a fixed-length accumulation, in seconds. `None` for
+/// the storm total, which spans the whole selection.
+pub(crate) fn window_secs(product: &str) -> Option<f64> {
+    match product {
+        QPE_1H => Some(3600.0),
+        QPE_3H => Some(3.0 * 3600.0),
+        _ => None,
+    }
+}
+
+/// Longest time one scan's rate is held. Gaps in the cache beyond this are
+/// left dry rather than filled with a single stale scan.
+const MAX_SCAN_HOLD_SECS: f64 = 15.0 * 60.0;
+
+/// Reflectivity cap (hail mitigation, as in the WSR-88D PPS).
+const MAX_RAIN_DBZ: f32 = 53.0;
+/// Echoes below this are treated as no rain (clear-air returns, drizzle).
+const MIN_RAIN_DBZ: f32 = 15.0;
+
+/// `R(KDP)` is used where KDP and reflectivity both exceed these.
+const KDP_MIN_DEG_PER_KM: f32 = 0.3;
+const KDP_MIN_DBZ: f32 = 35.0;
+
+/// U16 encoding of depth in mm (`physical = (raw - offset) / scale`).
+const DEPTH_SCALE: f32 = 100.0; // 0.01 mm resolution
+const DEPTH_OFFSET: f32 = 2.0;
+
+/// Z-R relationship `Z = a·R^b` (Z in mm⁶/m³, R in mm/h).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum ZrRelation {
+    /// `Z = 200 R^1.6`, stratiform and general rain.
+    #[default]
+    MarshallPalmer,
+    /// `Z = 250 R^1.2` (Rosenfeld), warm-rain tropical events.
+    Tropical,
+    Custom {
+        a: f32,
+        b: f32,
+    },
+}
+
+impl ZrRelation {
+    pub fn label(&self) -> &'static str {
+        match self {
+            ZrRelation::MarshallPalmer => "Marshall-Palmer",
+            ZrRelation::Tropical => "Tropical",
+            ZrRelation::Custom { .. } => "Custom",
+        }
+    }
+
+    /// `(a, b)` coefficients.
+    pub fn coefficients(&self) -> (f32, f32) {
+        match *self {
+            ZrRelation::MarshallPalmer => (200.0, 1.6),
+            ZrRelation::Tropical => (250.0, 1.2),
+            ZrRelation::Custom { a, b } => (a, b),
+        }
+    }
+
+    /// Rain rate (mm/h) for a reflectivity in dBZ.
+    pub fn rain_rate(&self, dbz: f32) -> f32 {
+        if dbz.is_nan() || dbz < MIN_RAIN_DBZ {
+            return 0.0;
+        }
+        let (a, b) = self.coefficients();
+        if a <= 0.0 || b <= 0.0 {
+            return 0.0;
+        }
+        let z = 10f32.powf(dbz.min(MAX_RAIN_DBZ) / 10.0);
+        (z / a).powf(1.0 / b)
+    }
+}
+
+/// Per-event QPE settings, sent with every accumulation request.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QpeSettings {
+    pub zr: ZrRelation,
+    /// Use `R(KDP)` where the specific differential phase is significant.
+    pub use_kdp: bool,
+}
+
+impl Default for QpeSettings {
+    fn default() -> Self {
+        Self {
+            zr: ZrRelation::default(),
+            use_kdp: true,
+        }
+    }
+}
+
+impl QpeSettings {
+    /// Rain rate (mm/h) at a gate. `kdp` is NaN where unavailable.
+    fn rain_rate(&self, dbz: f32, kdp: f32) -> f32 {
+        if self.use_kdp && kdp >= KDP_MIN_DEG_PER_KM && dbz >= KDP_MIN_DBZ {
+            // Sachidananda & Zrnić (1987), S band.
+            return 44.0 * kdp.powf(0.822);
+        }
+        self.zr.rain_rate(dbz)
+    }
+}
+
+/// Seconds each scan's rate counts toward an accumulation over
+/// `[start, end]`. `scan_starts` must be sorted; a scan holds until the next
+/// one starts, capped at [`MAX_SCAN_HOLD_SECS`] and clipped to the window.
+pub(crate) fn scan_durations(scan_starts: &[f64], start: f64, end: f64) -> Vec<f64> {
+    scan_starts
+        .iter()
+        .enumerate()
+        .map(|(i, &s)| {
+            let next = scan_starts.get(i + 1).copied().unwrap_or(end);
+            let to = next.min(end).min(s + MAX_SCAN_HOLD_SECS);
+            (to - s.max(start)).max(0.0)
+        })
+        .collect()
+}
+
+/// Sums rain depth over scans, one lowest-tilt sweep at a time.
+#[derive(Default)]
+pub(crate) struct QpeAccumulator {
+    depth_mm: Vec<f32>,
+    scan_count: u32,
+    start_secs: f64,
+    end_secs: f64,
+}
+
+impl QpeAccumulator {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add one scan whose rate holds for `hours`. `differential_phase` is
+    /// only used when `settings.use_kdp` is set.
+    pub(crate) fn add_scan(
+        &mut self,
+        reflectivity: &PrecomputedSweep,
+        differential_phase: Option<&PrecomputedSweep>,
+        hours: f32,
+        settings: &QpeSettings,
+    ) {
+        let az_count = reflectivity.azimuth_count as usize;
+        let gate_count = reflectivity.gate_count as usize;
+        if az_count == 0 || gate_count == 0 || reflectivity.scale == 0.0 || hours <= 0.0 {
+            return;
+        }
+        if self.depth_mm.is_empty() {
+            self.depth_mm = vec![0.0; GRID_AZIMUTHS * GRID_GATES];
+        }
+
+        let z = decode_physical(reflectivity);
+        let kdp = match differential_phase {
+            Some(phase) if settings.use_kdp => specific_differential_phase(reflectivity, phase),
+            _ => Vec::new(),
+        };
+
+        // Mean rate per grid cell.
+        let mut sum = vec![0.0f32; GRID_AZIMUTHS * GRID_GATES];
+        let mut count = vec![0u16; GRID_AZIMUTHS * GRID_GATES];
+        project_tilt(reflectivity, |cell, gate| {
+            let dbz = z[gate];
+            if dbz.is_nan() {
+                return;
+            }
+            let rate = settings.rain_rate(dbz, kdp.get(gate).copied().unwrap_or(f32::NAN));
+            sum[cell] += rate;
+            count[cell] = count[cell].saturating_add(1);
+        });
+
+        for ((depth, &s), &n) in self.depth_mm.iter_mut().zip(&sum).zip(&count) {
+            if n > 0 {
+                *depth += s / n as f32 * hours;
+            }
+        }
+
+        if self.scan_count == 0 {
+            self.start_secs = reflectivity.sweep_start_secs;
+        }
+        self.start_secs = self.start_secs.min(reflectivity.sweep_start_secs);
+        self.end_secs = self.end_secs.max(reflectivity.sweep_end_secs);
+        self.scan_count += 1;
+    }
+
+    /// Number of scans folded in so far.
+    pub(crate) fn scan_count(&self) -> u32 {
+        self.scan_count
+    }
+
+    /// Encode the depth field in mm. `None` if no scan was added. Cells with
+    /// less than the encoding resolution are left as "no data".
+    pub(crate) fn finish(self) -> Option<PrecomputedSweep> {
+        if self.scan_count == 0 {
+            return None;
+        }
+        let raw = self
+            .depth_mm
+            .iter()
+            .map(|&mm| {
+                let r = (mm * DEPTH_SCALE + DEPTH_OFFSET).round();
+                if r <= DEPTH_OFFSET {
+                    0
+                } else {
+                    r.min(u16::MAX as f32) as u16
+                }
+            })
+            .collect();
+        Some(PrecomputedSweep {
+            azimuth_count: GRID_AZIMUTHS as u32,
+            gate_count: GRID_GATES as u32,
+            first_gate_range_km: 0.0,
+            gate_interval_km: GRID_GATE_KM,
+            max_range_km: GRID_GATES as f64 * GRID_GATE_KM,
+            scale: DEPTH_SCALE,
+            offset: DEPTH_OFFSET,
+            radial_count: GRID_AZIMUTHS as u32,
+            mean_elevation: 0.0,
+            nyquist_velocity: None,
+            sweep_start_secs: self.start_secs,
+            sweep_end_secs: self.end_secs,
+            azimuths: (0..GRID_AZIMUTHS)
+                .map(|i| (i as f32 + 0.5) * GRID_AZ_DEG)
+                .collect(),
+            radial_times: Vec::new(),
+            gate_values: GateValues::U16(raw),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::SweepFixture;
+
+    const AZ_COUNT: usize = 720;
+    const GATE_COUNT: usize = 400;
+    const GATE_KM: f64 = 0.25;
+
+    /// Uniform U16 lowest tilt of `value` with the given encoding.
+    fn field(value: f32, scale: f32, offset: f32) -> PrecomputedSweep {
+        fixture(scale, offset).u16(|_, _| Some(value))
+    }
+
+    /// ΦDP rising `kdp` °/km along every radial (KDP is half the slope).
+    fn phase_ramp(kdp: f32) -> PrecomputedSweep {
+        fixture(2.8, 2.0).u16(|_, r| Some(20.0 + 2.0 * kdp * (r - 2.0) as f32))
+    }
+
+    fn fixture(scale: f32, offset: f32) -> SweepFixture {
+        SweepFixture::new(AZ_COUNT, GATE_COUNT, GATE_KM)
+            .first_gate_km(2.0)
+            .encoding(scale, offset)
+    }
+
+    fn depth_at(sweep: &PrecomputedSweep, az_bin: usize, km: usize) -> Option<f32> {
+        let GateValues::U16(raw) = &sweep.gate_values else {
+            panic!("accumulations are U16");
+        };
+        let r = raw[az_bin * GRID_GATES + km];
+        (r > 1).then(|| (r as f32 - sweep.offset) / sweep.scale)
+    }
+
+    #[test]
+    fn zr_presets() {
+        // 40 dBZ: ~11.5 mm/h Marshall-Palmer, ~21.6 mm/h tropical.
+        let mp = ZrRelation::MarshallPalmer.rain_rate(40.0);
+        assert!((mp - 11.53).abs() < 0.05, "{mp}");
+        let tropical = ZrRelation::Tropical.rain_rate(40.0);
+        assert!((tropical - 21.6).abs() < 0.1, "{tropical}");
+        let custom = ZrRelation::Custom { a: 300.0, b: 1.4 }.rain_rate(40.0);
+        assert!((custom - (1e4f32 / 300.0).powf(1.0 / 1.4)).abs() < 1e-3);
+        // Capped at 53 dBZ, nothing below 15 dBZ.
+        assert_eq!(
+            ZrRelation::MarshallPalmer.rain_rate(70.0),
+            ZrRelation::MarshallPalmer.rain_rate(MAX_RAIN_DBZ)
+        );
+        assert_eq!(ZrRelation::MarshallPalmer.rain_rate(10.0), 0.0);
+    }
+
+    #[test]
+    fn scan_durations_hold_until_next_scan() {
+        let starts = [0.0, 300.0, 600.0, 3000.0];
+        let d = scan_durations(&starts, 100.0, 3300.0);
+        // Clipped at the window start, held to the next scan, capped across
+        // the gap, and clipped at the window end.
+        assert_eq!(d, vec![200.0, 300.0, MAX_SCAN_HOLD_SECS, 300.0]);
+    }
+
+    #[test]
+    fn uniform_rain_accumulates_rate_times_time() {
+        let settings = QpeSettings::default();
+        let refl = field(40.0, 2.0, 66.0);
+        let mut acc = QpeAccumulator::new();
+        acc.add_scan(&refl, None, 0.5, &settings);
+        acc.add_scan(&refl, None, 0.5, &settings);
+        assert_eq!(acc.scan_count(), 2);
+        let out = acc.finish().unwrap();
+        let mm = depth_at(&out, 100, 50).unwrap();
+        assert!((mm - 11.53).abs() < 0.05, "{mm}");
+        // Beyond the tilt's range nothing was observed.
+        assert!(depth_at(&out, 100, 200).is_none());
+    }
+
+    #[test]
+    fn kdp_replaces_zr_in_heavy_rain() {
+        let refl = field(45.0, 2.0, 66.0);
+        let phase = phase_ramp(2.0);
+        let expected = 44.0 * 2.0f32.powf(0.822);
+
+        let mut acc = QpeAccumulator::new();
+        acc.add_scan(&refl, Some(&phase), 1.0, &QpeSettings::default());
+        let mm = depth_at(&acc.finish().unwrap(), 10, 50).unwrap();
+        assert!(
+            (mm - expected).abs() < expected * 0.05,
+            "{mm} vs {expected}"
+        );
+
+        let zr_only = QpeSettings {
+            use_kdp: false,
+            ..QpeSettings::default()
+        };
+        let mut acc = QpeAccumulator::new();
+        acc.add_scan(&refl, Some(&phase), 1.0, &zr_only);
+        let mm = depth_at(&acc.finish().unwrap(), 10, 50).unwrap();
+        assert!((mm - ZrRelation::MarshallPalmer.rain_rate(45.0)).abs() < 0.05);
+    }
+
+    #[test]
+    fn no_scans_produce_nothing() {
+        assert!(QpeAccumulator::new().finish().is_none());
+    }
+}
@@ -1,7 +1,7 @@
 //! Real-time NEXRAD streaming channel.
 //!
 //! Provides a channel-based interface for real-time NEXRAD data streaming
-//! from AWS. Uses our own [`super::volume_discovery::find_latest_volume`] +
+//! from the configured [`super::data_source`] (AWS by default). Uses our own [`super::volume_discovery::find_latest_volume`] +
 //! [`super::streaming_state::StreamingState`] instead of `ChunkIterator::start()`
 //! so we can resolve the current volume with 1-2 round trips of parallel
 //! probes instead of ~10 sequential binary-search LISTs.
@@ -17,6 +17,7 @@ use std::time::Duration;
 use eframe::egui;
 
 use crate::data::facade::DataFacade;
+use crate::platform::sleep_ms;
 
 /// Projected timing and structural info for a single chunk in the volume.
 ///
@@ -137,7 +138,7 @@ impl RealtimeChannel {
         let state = self.state.clone();
         let stats = self.stats.clone();
 
-        wasm_bindgen_futures::spawn_local(async move {
+        crate::platform::spawn_local(async move {
             streaming_loop(ctx, site_id, state, stats, facade).await;
         });
     }
@@ -207,7 +208,8 @@ async fn streaming_loop(
     stats: NetworkStats,
     _facade: DataFacade,
 ) {
-    use nexrad_data::aws::realtime::{download_chunk, list_chunks_in_volume, ChunkType};
+    use super::data_source::{download_chunk, list_chunks_in_volume};
+    use nexrad_data::aws::realtime::ChunkType;
 
     log::debug!("Starting realtime streaming for site: {}", site_id);
 
@@ -783,49 +785,26 @@ impl StatsTracker {
 }
 
 fn current_timestamp() -> i64 {
-    (js_sys::Date::now() / 1000.0) as i64
+    crate::platform::now_secs() as i64
 }
 
 /// Unix seconds with millisecond precision — for diagnostics timestamps.
 fn current_timestamp_f64() -> f64 {
-    js_sys::Date::now() / 1000.0
-}
-
-async fn sleep_ms(ms: u32) {
-    use wasm_bindgen::prelude::*;
-
-    #[wasm_bindgen]
-    extern "C" {
-        #[wasm_bindgen(js_name = setTimeout)]
-        fn set_timeout(closure: &Closure<dyn FnMut()>, millis: u32) -> i32;
-    }
-
-    let (tx, rx) = futures_channel::oneshot::channel::<()>();
-    let closure = Closure::once(move || {
-        let _ = tx.send(());
-    });
-    set_timeout(&closure, ms);
-    let _ = rx.await;
+    crate::platform::now_secs()
 }
 
 // ── Volume number cache ────────────────────────────────────────────────
 
-/// Cache the latest volume number in localStorage for fast resume.
+/// Cache the latest volume number in local storage for fast resume.
 fn cache_volume_number(site_id: &str, volume: nexrad_data::aws::realtime::VolumeIndex) {
     let key = format!("nexrad_volume_{}", site_id);
-    if let Some(window) = web_sys::window() {
-        if let Ok(Some(storage)) = window.local_storage() {
-            let _ = storage.set_item(&key, &volume.as_number().to_string());
-        }
-    }
+    let _ = crate::platform::storage_set(&key, &volume.as_number().to_string());
 }
 
-/// Read the cached volume number for a site from localStorage.
+/// Read the cached volume number for a site from local storage.
 fn get_cached_volume(site_id: &str) -> Option<nexrad_data::aws::realtime::VolumeIndex> {
     let key = format!("nexrad_volume_{}", site_id);
-    let window = web_sys::window()?;
-    let storage = window.local_storage().ok()??;
-    let raw = storage.get_item(&key).ok()??;
+    let raw = crate::platform::storage_get(&key)?;
     // Tolerate the legacy "VolumeIndex(N)" debug format that older builds wrote.
     let digits: String = raw.chars().filter(|c| c.is_ascii_digit()).collect();
     let n = digits.parse::<usize>().ok()?;
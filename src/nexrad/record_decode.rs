@@ -1,41 +1,13 @@
 //! Per-record decode utilities and sweep extraction.
 //!
-//! Provides functions to decompress and decode individual LDM records
-//! into radials, and to extract pre-computed sweep data from radials.
+//! Provides functions to extract volume metadata and pre-computed sweep
+//! data from decoded radials.
 
 use crate::data::keys::{GateValues, PrecomputedSweep};
 use ::nexrad::model::data::Radial;
-use nexrad_data::volume::Record;
 use nexrad_model::data::DataMoment;
 use nexrad_render::Product;
 
-/// Decode a single LDM record into radials.
-///
-/// Accepts either compressed records (4-byte size prefix + bzip2 data, as
-/// produced by `nexrad_data::volume::File::records()`) or decompressed
-/// records (raw message bytes, as stored in IndexedDB). Compressed records
-/// are decompressed automatically.
-///
-/// Returns the decoded radials (may be empty if the record contains only
-/// non-radial messages like VCP metadata).
-pub fn decode_record_to_radials(record_bytes: &[u8]) -> Result<Vec<Radial>, String> {
-    let record = Record::from_slice(record_bytes);
-
-    if !record.compressed() {
-        return record
-            .radials()
-            .map_err(|e| format!("Failed to decode uncompressed record: {}", e));
-    }
-
-    let decompressed = record
-        .decompress()
-        .map_err(|e| format!("Failed to decompress record: {}", e))?;
-
-    decompressed
-        .radials()
-        .map_err(|e| format!("Failed to decode record radials: {}", e))
-}
-
 /// Extract the volume start time from decoded radials.
 ///
 /// Looks for a radial whose status is `ScanStart` (the first radial of a new
@@ -185,6 +157,7 @@ fn build_precomputed_sweep(target: &[&Radial], product: Product) -> Option<Preco
         offset,
         radial_count: azimuth_count as u32,
         mean_elevation,
+        nyquist_velocity: None,
         sweep_start_secs: min_ts / 1000.0,
         sweep_end_secs: max_ts / 1000.0,
         azimuths,
@@ -4,7 +4,11 @@
 //! WorkbenchApp and Renderers into a single owner.
 
 use super::decode_worker::{default_pool_size, WorkerOutcome, WorkerPool};
-use super::render_request::{RenderRequest, VolumeRenderRequest};
+use super::render_request::{
+    CfRadialRequest, CrossSectionRequest, LowestAvailableRequest, MosaicRequest, QpeRenderRequest,
+    RenderRequest, VolumeRenderRequest,
+};
+use crate::state::{EXTRA_PANEL_COUNT, MAX_PINNED_SITES};
 
 /// Coordinates render requests to a pool of decode workers, deduplicating
 /// identical requests and owning the current scan/elevation state.
@@ -23,6 +27,22 @@ pub struct RenderCoordinator {
     last_render: Option<RenderRequest>,
     /// Previous volume render parameters for change detection.
     last_volume_render: Option<VolumeRenderRequest>,
+    /// Previous rainfall accumulation parameters for change detection.
+    last_qpe_render: Option<QpeRenderRequest>,
+    /// Previous lowest-available composite parameters for change detection.
+    last_lowest_available: Option<LowestAvailableRequest>,
+    /// Previous render parameters of each extra split-view panel. Also used
+    /// to route decoded results to the panels that asked for them.
+    last_panel_renders: [Option<RenderRequest>; EXTRA_PANEL_COUNT],
+    /// Previous render parameters of each pinned site's slot, routing
+    /// decoded results like the panels'.
+    last_pinned_renders: [Option<RenderRequest>; MAX_PINNED_SITES],
+    /// Previous cross-section parameters, for change detection and to match
+    /// results against the latest request.
+    last_cross_section: Option<CrossSectionRequest>,
+    /// Previous regional mosaic parameters, for change detection and to
+    /// match results against the latest request.
+    last_mosaic: Option<MosaicRequest>,
 }
 
 impl RenderCoordinator {
@@ -33,6 +53,12 @@ impl RenderCoordinator {
             available_elevations: Vec::new(),
             last_render: None,
             last_volume_render: None,
+            last_qpe_render: None,
+            last_lowest_available: None,
+            last_panel_renders: Default::default(),
+            last_pinned_renders: Default::default(),
+            last_cross_section: None,
+            last_mosaic: None,
         }
     }
 
@@ -83,18 +109,29 @@ impl RenderCoordinator {
         self.available_elevations.clear();
         self.last_render = None;
         self.last_volume_render = None;
+        self.last_qpe_render = None;
+        self.last_lowest_available = None;
+        self.last_panel_renders = Default::default();
+        self.last_cross_section = None;
     }
 
     /// Force the next render request to go through (clears dedup cache).
     pub fn force_fresh_render(&mut self) {
         self.last_render = None;
         self.last_volume_render = None;
+        self.last_qpe_render = None;
+        self.last_lowest_available = None;
+        self.last_panel_renders = Default::default();
+        self.last_pinned_renders = Default::default();
+        self.last_cross_section = None;
+        self.last_mosaic = None;
     }
 
     /// Clear only the scan key (e.g. when no scan is in range).
     pub fn clear_scan_key(&mut self) {
         self.current_scan_key = None;
         self.last_render = None;
+        self.last_panel_renders = Default::default();
     }
 
     /// Clear only the last-render dedup entry. Keeps `current_scan_key` so the
@@ -104,6 +141,17 @@ impl RenderCoordinator {
         self.last_render = None;
     }
 
+    /// Clear the accumulation dedup entry, e.g. when its window empties, so
+    /// returning to the same window renders it again.
+    pub fn clear_last_qpe_render(&mut self) {
+        self.last_qpe_render = None;
+    }
+
+    /// Clear the lowest-available dedup entry, e.g. when its window empties.
+    pub fn clear_last_lowest_available(&mut self) {
+        self.last_lowest_available = None;
+    }
+
     /// Pick the closest available elevation to the requested one.
     pub fn best_available_elevation(&self, requested: u8) -> u8 {
         self.available_elevations
@@ -143,10 +191,147 @@ impl RenderCoordinator {
 
         let scan_key = scan_key.clone();
         self.last_render = Some(request);
+        self.last_qpe_render = None;
+        self.last_lowest_available = None;
+        worker.render(scan_key, elevation_number, product.to_string());
+        true
+    }
+
+    /// Send a render request for extra split-view panel `panel` of the
+    /// current scan. Returns true if actually sent.
+    pub fn request_panel_render(
+        &mut self,
+        panel: usize,
+        elevation_number: u8,
+        product: &str,
+    ) -> bool {
+        let Some(ref scan_key) = self.current_scan_key else {
+            return false;
+        };
+        let Some(ref mut worker) = self.worker else {
+            return false;
+        };
+
+        let request = RenderRequest {
+            scan_key: scan_key.clone(),
+            elevation_number,
+            product: product.to_string(),
+            is_auto: false,
+        };
+        if self.last_panel_renders[panel].as_ref() == Some(&request) {
+            return false;
+        }
+
+        log::debug!(
+            "Requesting panel {} decode: {} elev={} product={}",
+            panel,
+            scan_key,
+            elevation_number,
+            product,
+        );
+
+        let scan_key = scan_key.clone();
+        self.last_panel_renders[panel] = Some(request);
         worker.render(scan_key, elevation_number, product.to_string());
         true
     }
 
+    /// Forget what panel `panel` last requested, e.g. once it is cleared.
+    pub fn clear_panel_render(&mut self, panel: usize) {
+        self.last_panel_renders[panel] = None;
+    }
+
+    /// Send a render request for pinned site slot `slot`. Unlike the other
+    /// renders this names its own scan, as pinned sites have their own.
+    /// Returns true if actually sent.
+    pub fn request_pinned_render(
+        &mut self,
+        slot: usize,
+        scan_key: &str,
+        elevation_number: u8,
+        product: &str,
+    ) -> bool {
+        let Some(ref mut worker) = self.worker else {
+            return false;
+        };
+
+        let request = RenderRequest {
+            scan_key: scan_key.to_string(),
+            elevation_number,
+            product: product.to_string(),
+            is_auto: false,
+        };
+        if self.last_pinned_renders[slot].as_ref() == Some(&request) {
+            return false;
+        }
+
+        log::debug!(
+            "Requesting pinned {} decode: {} elev={} product={}",
+            slot,
+            scan_key,
+            elevation_number,
+            product,
+        );
+
+        self.last_pinned_renders[slot] = Some(request);
+        worker.render(scan_key.to_string(), elevation_number, product.to_string());
+        true
+    }
+
+    /// Forget what pinned slot `slot` last requested.
+    pub fn clear_pinned_render(&mut self, slot: usize) {
+        self.last_pinned_renders[slot] = None;
+    }
+
+    /// Pinned slots whose latest request was this (scan, elevation, product).
+    pub fn pinned_awaiting(
+        &self,
+        scan_key: &str,
+        elevation_number: u8,
+        product: &str,
+    ) -> Vec<usize> {
+        self.last_pinned_renders
+            .iter()
+            .enumerate()
+            .filter(|(_, r)| {
+                r.as_ref().is_some_and(|r| {
+                    r.scan_key == scan_key
+                        && r.elevation_number == elevation_number
+                        && r.product == product
+                })
+            })
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    /// Whether the main view's latest request was this (scan, elevation, product).
+    pub fn is_last_render(&self, scan_key: &str, elevation_number: u8, product: &str) -> bool {
+        self.last_render.as_ref().is_some_and(|r| {
+            r.scan_key == scan_key && r.elevation_number == elevation_number && r.product == product
+        })
+    }
+
+    /// Extra panels whose latest request was this (scan, elevation, product).
+    pub fn panels_awaiting(
+        &self,
+        scan_key: &str,
+        elevation_number: u8,
+        product: &str,
+    ) -> Vec<usize> {
+        self.last_panel_renders
+            .iter()
+            .enumerate()
+            .filter(|(_, r)| {
+                r.as_ref().is_some_and(|r| {
+                    r.scan_key == scan_key
+                        && r.elevation_number == elevation_number
+                        && r.product == product
+                })
+            })
+            .map(|(i, _)| i)
+            .collect()
+    }
+
     /// Send a volume render request. Returns true if actually sent.
     pub fn request_volume_render(&mut self, product: &str) -> bool {
         let Some(ref scan_key) = self.current_scan_key else {
@@ -185,6 +370,153 @@ impl RenderCoordinator {
         true
     }
 
+    /// Send a rainfall accumulation request. Returns true if actually sent.
+    ///
+    /// Accumulations and single-sweep renders share the canvas, so sending
+    /// one invalidates the other's dedup entry. A failed accumulation is not
+    /// retried until its window or settings change.
+    pub fn request_qpe_render(&mut self, request: QpeRenderRequest) -> bool {
+        let Some(ref mut worker) = self.worker else {
+            return false;
+        };
+        if request.scans.is_empty() || self.last_qpe_render.as_ref() == Some(&request) {
+            return false;
+        }
+
+        log::debug!(
+            "Requesting rainfall accumulation: {} over {} scans",
+            request.product,
+            request.scans.len(),
+        );
+
+        self.last_qpe_render = Some(request.clone());
+        self.last_render = None;
+        self.last_lowest_available = None;
+        worker.render_qpe(request.product, request.scans, request.settings);
+        true
+    }
+
+    /// Send a lowest-available composite request. Returns true if actually
+    /// sent. Shares the canvas with single-sweep renders and accumulations
+    /// like [`Self::request_qpe_render`].
+    pub fn request_lowest_available_render(&mut self, request: LowestAvailableRequest) -> bool {
+        let Some(ref mut worker) = self.worker else {
+            return false;
+        };
+        if request.sweeps.is_empty() || self.last_lowest_available.as_ref() == Some(&request) {
+            return false;
+        }
+
+        log::debug!(
+            "Requesting lowest-available composite: {} over {} sweeps",
+            request.product,
+            request.sweeps.len(),
+        );
+
+        self.last_lowest_available = Some(request.clone());
+        self.last_render = None;
+        self.last_qpe_render = None;
+        worker.render_lowest_available(request.product, request.sweeps);
+        true
+    }
+
+    /// Build a cross-section request for the current scan. `None` without a
+    /// scan or elevations.
+    pub fn cross_section_request(
+        &self,
+        product: &str,
+        start_km: (f64, f64),
+        end_km: (f64, f64),
+        storm_motion_ms: [f32; 2],
+    ) -> Option<CrossSectionRequest> {
+        let scan_key = self.current_scan_key.clone()?;
+        if self.available_elevations.is_empty() {
+            return None;
+        }
+        Some(CrossSectionRequest {
+            scan_key,
+            product: product.to_string(),
+            elevation_numbers: self.available_elevations.clone(),
+            start_km,
+            end_km,
+            storm_motion_ms,
+        })
+    }
+
+    /// Send a cross-section request. Returns true if actually sent; a failed
+    /// section is not retried until its parameters change.
+    pub fn request_cross_section(&mut self, request: CrossSectionRequest) -> bool {
+        let Some(ref mut worker) = self.worker else {
+            return false;
+        };
+        if self.last_cross_section.as_ref() == Some(&request) {
+            return false;
+        }
+
+        log::debug!(
+            "Requesting cross-section: {} {} over {} elevations",
+            request.scan_key,
+            request.product,
+            request.elevation_numbers.len(),
+        );
+
+        self.last_cross_section = Some(request.clone());
+        worker.cross_section(request);
+        true
+    }
+
+    /// Whether `request` is the most recent cross-section sent.
+    pub fn is_latest_cross_section(&self, request: &CrossSectionRequest) -> bool {
+        self.last_cross_section.as_ref() == Some(request)
+    }
+
+    /// Send a regional mosaic request. Returns true if actually sent; a
+    /// failed mosaic is not retried until its parameters change.
+    pub fn request_mosaic(&mut self, request: MosaicRequest) -> bool {
+        let Some(ref mut worker) = self.worker else {
+            return false;
+        };
+        if request.site_ids.is_empty() || self.last_mosaic.as_ref() == Some(&request) {
+            return false;
+        }
+
+        log::debug!(
+            "Requesting mosaic: {} over {} sites at {}",
+            request.product,
+            request.site_ids.len(),
+            request.time_secs,
+        );
+
+        self.last_mosaic = Some(request.clone());
+        worker.mosaic(request);
+        true
+    }
+
+    /// Whether `request` is the most recent mosaic sent.
+    pub fn is_latest_mosaic(&self, request: &MosaicRequest) -> bool {
+        self.last_mosaic.as_ref() == Some(request)
+    }
+
+    /// Clear the mosaic dedup entry so the same mosaic is composited again,
+    /// e.g. after a neighbour's scan is stored.
+    pub fn clear_last_mosaic(&mut self) {
+        self.last_mosaic = None;
+    }
+
+    /// Send a CF-Radial export request. Returns false without a worker.
+    pub fn export_cf_radial(&mut self, request: CfRadialRequest) -> bool {
+        let Some(ref mut worker) = self.worker else {
+            return false;
+        };
+        log::debug!(
+            "Requesting CF-Radial export: {} over {} elevations",
+            request.scan_key,
+            request.elevation_numbers.len(),
+        );
+        worker.cf_radial(request);
+        true
+    }
+
     /// Send a live render request (partial sweep, no dedup).
     pub fn render_live(&mut self, elevation_number: u8, product: String) {
         if let Some(ref mut worker) = self.worker {
@@ -206,6 +538,48 @@ impl RenderCoordinator {
         }
     }
 
+    /// Forward a file read from disk to the worker for ingest.
+    pub fn import(&mut self, data: Vec<u8>, file_name: String) {
+        if let Some(ref mut worker) = self.worker {
+            worker.import(data, file_name);
+        }
+    }
+
+    /// Forward a neighbouring site's archive to a worker for ingest.
+    pub fn ingest_mosaic(
+        &mut self,
+        data: Vec<u8>,
+        site_id: String,
+        timestamp: i64,
+        file_name: String,
+        fetch_latency: f64,
+    ) {
+        if let Some(ref mut worker) = self.worker {
+            worker.ingest_mosaic(data, site_id, timestamp, file_name, fetch_latency);
+        }
+    }
+
+    /// Forward a pinned site's archive to a worker for ingest.
+    pub fn ingest_pinned(
+        &mut self,
+        data: Vec<u8>,
+        site_id: String,
+        timestamp: i64,
+        file_name: String,
+        fetch_latency: f64,
+    ) {
+        if let Some(ref mut worker) = self.worker {
+            worker.ingest_pinned(data, site_id, timestamp, file_name, fetch_latency);
+        }
+    }
+
+    /// Forward a downloaded Level III product to a worker for ingest.
+    pub fn ingest_level3(&mut self, data: Vec<u8>, file: crate::nexrad::level3::Level3File) {
+        if let Some(ref mut worker) = self.worker {
+            worker.ingest_level3(data, file);
+        }
+    }
+
     /// Forward a chunk to worker for incremental ingest.
     #[allow(clippy::too_many_arguments)]
     pub fn ingest_chunk(
@@ -235,6 +609,13 @@ impl RenderCoordinator {
         }
     }
 
+    /// Enable or disable velocity dealiasing for subsequent ingests.
+    pub fn set_dealias_velocity(&mut self, enabled: bool) {
+        if let Some(ref mut worker) = self.worker {
+            worker.set_dealias_velocity(enabled);
+        }
+    }
+
     /// Send a direct render request (used by prefetch/prev-sweep, bypasses dedup).
     pub fn render_direct(&mut self, scan_key: String, elevation_number: u8, product: String) {
         if let Some(ref mut worker) = self.worker {
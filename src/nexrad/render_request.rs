@@ -4,6 +4,8 @@
 //! worker render calls. `PartialEq` derives ensure new fields cause a mismatch
 //! automatically.
 
+use serde::Serialize;
+
 /// Parameters for a single-elevation render request. Adding a field here
 /// automatically breaks the `PartialEq` comparison, preventing silent omissions.
 #[derive(Clone, PartialEq)]
@@ -14,6 +16,66 @@ pub struct RenderRequest {
     pub is_auto: bool,
 }
 
+/// Parameters for a rainfall accumulation request. The scan list carries
+/// each scan's hold time, so moving the window or caching a new scan inside
+/// it produces a new request.
+#[derive(Clone, PartialEq)]
+pub struct QpeRenderRequest {
+    pub product: String,
+    pub scans: Vec<super::decode_worker::QpeScan>,
+    pub settings: super::qpe::QpeSettings,
+}
+
+/// Parameters for a lowest-available composite. The sweep list is in
+/// composite order, so a newly cached sweep inside the window, or the
+/// window moving past one, produces a new request.
+#[derive(Clone, PartialEq)]
+pub struct LowestAvailableRequest {
+    pub product: String,
+    pub sweeps: Vec<super::decode_worker::LowestAvailableSweep>,
+}
+
+/// Parameters for a vertical cross-section through the current volume.
+/// Endpoints are radar-relative (east, north) km.
+#[derive(Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CrossSectionRequest {
+    pub scan_key: String,
+    pub product: String,
+    pub elevation_numbers: Vec<u8>,
+    pub start_km: (f64, f64),
+    pub end_km: (f64, f64),
+    /// Storm motion (east, north) m/s removed from velocities; zero for
+    /// ground-relative products.
+    pub storm_motion_ms: [f32; 2],
+}
+
+/// Parameters for a regional mosaic of several sites' cached scans. Each
+/// site contributes its latest scan starting at most `max_age_secs` before
+/// `time_secs`.
+#[derive(Clone, PartialEq)]
+pub struct MosaicRequest {
+    pub site_ids: Vec<String>,
+    pub time_secs: i64,
+    pub max_age_secs: i64,
+    pub product: String,
+    pub mode: super::mosaic::MosaicMode,
+}
+
+/// Parameters for a CF-Radial export of every cached elevation of a scan.
+#[derive(Clone, PartialEq)]
+pub struct CfRadialRequest {
+    pub scan_key: String,
+    pub elevation_numbers: Vec<u8>,
+    pub site_id: String,
+    pub site_name: String,
+    pub latitude: f64,
+    pub longitude: f64,
+    pub altitude_m: f64,
+    /// Download file name without extension.
+    pub file_stem: String,
+}
+
 /// Parameters for a volume (all-elevations) render request.
 #[derive(Clone, PartialEq)]
 pub struct VolumeRenderRequest {
@@ -6,12 +6,13 @@
 //! init (fetch latest + optional start chunk, extract VCP), pull-based
 //! `try_next`, and timing/metadata accessors.
 
+use super::data_source::{download_chunk, list_chunks_in_volume};
 use chrono::{DateTime, Duration as ChronoDuration, Utc};
 use log::debug;
 use nexrad_data::aws::realtime::{
-    download_chunk, estimate_chunk_availability_time, list_chunks_in_volume, project_scan_timing,
-    Chunk, ChunkCharacteristics, ChunkIdentifier, ChunkMetadata, ChunkTimingStats, ChunkType,
-    DownloadedChunk, ElevationChunkMapper, NextChunk, ScanTimingProjection, VolumeIndex,
+    estimate_chunk_availability_time, project_scan_timing, Chunk, ChunkCharacteristics,
+    ChunkIdentifier, ChunkMetadata, ChunkTimingStats, ChunkType, DownloadedChunk,
+    ElevationChunkMapper, NextChunk, ScanTimingProjection, VolumeIndex,
 };
 use nexrad_data::result::{aws::AWSError, Error, Result};
 use nexrad_decode::messages::volume_coverage_pattern;
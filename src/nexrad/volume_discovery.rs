@@ -14,9 +14,10 @@
 //! see [`search`] below) is kept only as a defensive fallback for the rare
 //! case where even the coarse sweep finds no valid volumes.
 
+use super::data_source::list_chunks_in_volume;
 use chrono::{DateTime, Utc};
 use futures_util::future::join_all;
-use nexrad_data::aws::realtime::{list_chunks_in_volume, VolumeIndex};
+use nexrad_data::aws::realtime::VolumeIndex;
 use nexrad_data::result::Result;
 use std::collections::VecDeque;
 use std::future::Future;
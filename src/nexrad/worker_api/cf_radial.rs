@@ -0,0 +1,100 @@
+//! Writing a cached volume as a CF-Radial file.
+
+use super::*;
+use crate::nexrad::cf_radial::{build_cf_radial, cf_radial_products, CfRadialSite, CfRadialSweep};
+
+/// Parameters for `worker_cf_radial`.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct CfRadialParams {
+    pub scan_key: String,
+    pub elevation_numbers: Vec<u8>,
+    pub site_id: String,
+    #[serde(default)]
+    pub site_name: String,
+    pub latitude: f64,
+    pub longitude: f64,
+    pub altitude_m: f64,
+}
+
+/// Scalar fields of the CF-Radial response. The `data` ArrayBuffer is set
+/// separately.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct CfRadialResponse {
+    pub sweep_count: u32,
+    pub total_ms: f64,
+}
+
+/// Encode every cached elevation and base moment of a scan as CF-Radial.
+///
+/// Reads each listed elevation's moments from IndexedDB; missing ones are
+/// skipped. Returns `sweepCount` and `data` (ArrayBuffer, NetCDF classic
+/// 64-bit offset file).
+///
+/// Parameters (JS object): `{ scanKey, elevationNumbers, siteId, siteName, latitude, longitude, altitudeM }`
+#[cfg(target_arch = "wasm32")]
+#[wasm_bindgen::prelude::wasm_bindgen]
+pub fn worker_cf_radial(params: wasm_bindgen::JsValue) -> js_sys::Promise {
+    init_logger();
+    wasm_bindgen_futures::future_to_promise(async move {
+        let p: CfRadialParams = parse_params(params, "cf_radial")?;
+        let store = worker_store().await.map_err(js_error)?;
+        reply_to_js(cf_radial(&store, p).await.map_err(js_error)?)
+    })
+}
+
+/// Encode the listed elevations; see `worker_cf_radial`.
+pub(crate) async fn cf_radial(
+    store: &impl RecordStore,
+    p: CfRadialParams,
+) -> Result<Reply<CfRadialResponse>, String> {
+    let t_total = web_time::Instant::now();
+
+    let scan_key = ScanKey::from_storage_key(&p.scan_key).ok_or("Invalid scanKey format")?;
+
+    let mut elevation_numbers = p.elevation_numbers;
+    elevation_numbers.sort_unstable();
+    elevation_numbers.dedup();
+    let mut sweeps = Vec::with_capacity(elevation_numbers.len());
+    for elev_num in elevation_numbers {
+        let mut moments = Vec::new();
+        for product in cf_radial_products() {
+            let key = SweepDataKey::new(scan_key.clone(), elev_num, product);
+            if let Some(sweep) = load_sweep(store, key).await {
+                moments.push((product.to_string(), sweep));
+            }
+        }
+        if !moments.is_empty() {
+            sweeps.push(CfRadialSweep { moments });
+        }
+    }
+
+    let site = CfRadialSite {
+        id: p.site_id,
+        name: p.site_name,
+        latitude: p.latitude,
+        longitude: p.longitude,
+        altitude_m: p.altitude_m,
+    };
+    let sweep_count = sweeps.len() as u32;
+    let bytes = build_cf_radial(&site, &sweeps)?.into_bytes();
+    drop(sweeps);
+    let total_ms = t_total.elapsed().as_secs_f64() * 1000.0;
+
+    log::debug!(
+        "cf_radial: {} sweeps, {:.1}MB in {:.1}ms",
+        sweep_count,
+        bytes.len() as f64 / (1024.0 * 1024.0),
+        total_ms,
+    );
+
+    let response = CfRadialResponse {
+        sweep_count,
+        total_ms,
+    };
+    Ok(Reply {
+        fields: response,
+        arrays: vec![("data", Array::U8(bytes))],
+    })
+}
@@ -0,0 +1,98 @@
+//! Vertical cross-sections through a cached volume.
+
+use super::*;
+use crate::nexrad::color_table::is_categorical;
+use crate::nexrad::cross_section::build_cross_section;
+
+/// Parameters for `worker_cross_section`.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct CrossSectionParams {
+    pub scan_key: String,
+    pub product: String,
+    pub elevation_numbers: Vec<u8>,
+    /// Line endpoints, radar-relative (east, north) km.
+    pub start_km: (f64, f64),
+    pub end_km: (f64, f64),
+    #[serde(default)]
+    pub storm_motion_ms: [f32; 2],
+}
+
+/// Scalar fields of the cross-section response. The `values` ArrayBuffer is
+/// set separately.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct CrossSectionResponse {
+    pub columns: u32,
+    pub rows: u32,
+    pub length_km: f64,
+    pub top_km: f64,
+    pub tilts_deg: Vec<f32>,
+    pub total_ms: f64,
+}
+
+/// Slice every cached tilt of one product along a line.
+///
+/// Reads the listed elevations from IndexedDB; missing ones are skipped.
+/// Returns the grid's scalar fields plus `values` (Float32Array, row-major
+/// from the top, NaN = no data).
+///
+/// Parameters (JS object): `{ scanKey, product, elevationNumbers, startKm: [x, y], endKm: [x, y], stormMotionMs }`
+#[cfg(target_arch = "wasm32")]
+#[wasm_bindgen::prelude::wasm_bindgen]
+pub fn worker_cross_section(params: wasm_bindgen::JsValue) -> js_sys::Promise {
+    init_logger();
+    wasm_bindgen_futures::future_to_promise(async move {
+        let p: CrossSectionParams = parse_params(params, "cross_section")?;
+        let store = worker_store().await.map_err(js_error)?;
+        reply_to_js(cross_section(&store, p).await.map_err(js_error)?)
+    })
+}
+
+/// Slice the listed tilts; see `worker_cross_section`.
+pub(crate) async fn cross_section(
+    store: &impl RecordStore,
+    p: CrossSectionParams,
+) -> Result<Reply<CrossSectionResponse>, String> {
+    let t_total = web_time::Instant::now();
+
+    let scan_key = ScanKey::from_storage_key(&p.scan_key).ok_or("Invalid scanKey format")?;
+
+    let mut sweeps = Vec::with_capacity(p.elevation_numbers.len());
+    for &elev_num in &p.elevation_numbers {
+        let key = SweepDataKey::new(scan_key.clone(), elev_num, &p.product);
+        if let Some(sweep) = load_sweep(store, key).await {
+            sweeps.push(sweep);
+        }
+    }
+
+    let section = build_cross_section(
+        &sweeps,
+        p.start_km,
+        p.end_km,
+        p.storm_motion_ms,
+        is_categorical(&p.product),
+    );
+    let total_ms = t_total.elapsed().as_secs_f64() * 1000.0;
+
+    log::debug!(
+        "cross_section: {} over {} tilts, {:.0} km in {:.1}ms",
+        p.product,
+        section.tilts_deg.len(),
+        section.length_km,
+        total_ms,
+    );
+
+    let response = CrossSectionResponse {
+        columns: section.columns as u32,
+        rows: section.rows as u32,
+        length_km: section.length_km,
+        top_km: section.top_km,
+        tilts_deg: section.tilts_deg,
+        total_ms,
+    };
+    Ok(Reply {
+        fields: response,
+        arrays: vec![("values", Array::F32(section.values))],
+    })
+}
@@ -0,0 +1,136 @@
+//! Storing a Level III product.
+
+use super::*;
+use crate::nexrad::level3::decode_product;
+
+/// Level III products are filed under a cached scan starting within this
+/// many seconds of the product's volume time.
+const SCAN_MATCH_SECS: i64 = 60;
+
+/// Parameters for `worker_ingest_level3`. The `data` ArrayBuffer is
+/// extracted separately.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct Level3Params {
+    pub site_id: String,
+    #[serde(default)]
+    pub file_name: String,
+}
+
+/// Response from `worker_ingest_level3`.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct Level3Response {
+    pub scan_key: String,
+    pub product: String,
+    pub total_ms: f64,
+}
+
+/// Decode a Level III product and store it as a sweep blob.
+///
+/// The product is filed under the cached scan of the same volume, at its
+/// lowest elevation, or under a new scan entry when that volume isn't
+/// cached. The scan index lists it as an available product of that sweep.
+///
+/// Parameters (JS object): `{ data: ArrayBuffer, siteId, fileName }`
+/// Returns (JS object): `{ scanKey, product, totalMs }`
+#[cfg(target_arch = "wasm32")]
+#[wasm_bindgen::prelude::wasm_bindgen]
+pub fn worker_ingest_level3(params: wasm_bindgen::JsValue) -> js_sys::Promise {
+    init_logger();
+    wasm_bindgen_futures::future_to_promise(async move {
+        let data = extract_data_bytes(&params)?;
+        let p: Level3Params = parse_params(params, "level3")?;
+        let store = worker_store().await.map_err(js_error)?;
+        response_to_js(&ingest_level3(&store, &data, p).await.map_err(js_error)?)
+    })
+}
+
+/// Decode and file one product; see `worker_ingest_level3`.
+pub(crate) async fn ingest_level3(
+    store: &impl RecordStore,
+    data: &[u8],
+    p: Level3Params,
+) -> Result<Level3Response, String> {
+    let t_total = web_time::Instant::now();
+
+    let decoded = decode_product(data).map_err(|e| format!("{}: {}", p.file_name, e))?;
+    let product = decoded.product.worker_string().to_string();
+    let sweep = decoded.sweep;
+
+    let volume_start = UnixMillis::from_secs(decoded.volume_start_secs);
+    let window_ms = SCAN_MATCH_SECS * 1000;
+    let existing = store
+        .list_scans(
+            &SiteId(p.site_id.clone()),
+            UnixMillis(volume_start.0 - window_ms),
+            UnixMillis(volume_start.0 + window_ms),
+        )
+        .await
+        .map_err(|e| format!("Failed to list scans: {}", e))?
+        .into_iter()
+        .min_by_key(|entry| (entry.scan.scan_start.0 - volume_start.0).abs());
+
+    // Attach to the scan's lowest sweep so the render path finds it
+    // where it looks for volume products.
+    let lowest = existing.as_ref().and_then(|entry| {
+        let sweeps = entry.sweeps.as_ref()?;
+        let lowest = sweeps.iter().map(|s| s.elevation_number).min()?;
+        sweeps
+            .iter()
+            .find(|s| s.elevation_number == lowest)
+            .cloned()
+    });
+    let scan_key = existing
+        .as_ref()
+        .map(|entry| entry.scan.clone())
+        .unwrap_or_else(|| ScanKey::new(p.site_id.as_str(), volume_start));
+    let replaced = lowest
+        .as_ref()
+        .is_some_and(|s| s.available_products.contains(&product));
+    let meta = match lowest {
+        Some(mut meta) => {
+            meta.available_products = vec![product.clone()];
+            meta
+        }
+        None => SweepMeta {
+            start: sweep.sweep_start_secs,
+            end: sweep.sweep_end_secs,
+            elevation: sweep.mean_elevation,
+            elevation_number: 1,
+            start_azimuth: 0.0,
+            available_products: vec![product.clone()],
+        },
+    };
+
+    let key = SweepDataKey::new(scan_key.clone(), meta.elevation_number, &product);
+    let blob = sweep.to_bytes();
+    let size_bytes = if replaced { 0 } else { blob.len() as u64 };
+    store
+        .put_sweeps_batch(&[(key.to_storage_key(), blob)])
+        .await
+        .map_err(|e| format!("Failed to store sweep: {}", e))?;
+
+    let mut partial = ScanIndexEntry::new(scan_key.clone());
+    partial.file_name = Some(p.file_name.clone());
+    partial.end_timestamp_secs = Some(meta.end as i64);
+    store
+        .merge_scan_index_entry(&partial, 0, size_bytes, &[meta])
+        .await
+        .map_err(|e| format!("Failed to merge scan index: {}", e))?;
+
+    let total_ms = t_total.elapsed().as_secs_f64() * 1000.0;
+    log::debug!(
+        "level3: stored {} as {} under {} in {:.1}ms",
+        p.file_name,
+        product,
+        scan_key,
+        total_ms,
+    );
+
+    Ok(Level3Response {
+        scan_key: scan_key.to_storage_key(),
+        product,
+        total_ms,
+    })
+}
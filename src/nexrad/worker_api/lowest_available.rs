@@ -0,0 +1,117 @@
+//! Lowest-available composite over recent sweeps.
+
+use super::*;
+use crate::nexrad::decode_worker::LowestAvailableSweep;
+use crate::nexrad::lowest_available::LowestAvailableComposite;
+
+/// Parameters for `worker_render_lowest_available`.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct RenderLowestAvailableParams {
+    #[serde(default = "default_product")]
+    pub product: String,
+    /// Lowest tier first, newest first within a tier.
+    pub sweeps: Vec<LowestAvailableSweep>,
+}
+
+/// Composite the most recent valid gate of the lowest tilt over a list of
+/// cached sweeps.
+///
+/// The first sweep found in IndexedDB sets the polar grid; later ones only
+/// fill gates still empty, and loading stops once every gate has a value.
+/// Returns the same RenderResponse shape as `worker_render` plus
+/// `gateTimes` (Float64Array, collection time of each gate in Unix seconds,
+/// NaN where no sweep had data).
+///
+/// Parameters (JS object): `{ product: string, sweeps: [{ scanKey, elevationNumber }] }`
+#[cfg(target_arch = "wasm32")]
+#[wasm_bindgen::prelude::wasm_bindgen]
+pub fn worker_render_lowest_available(params: wasm_bindgen::JsValue) -> js_sys::Promise {
+    init_logger();
+    wasm_bindgen_futures::future_to_promise(async move {
+        let p: RenderLowestAvailableParams = parse_params(params, "lowest-available")?;
+        let store = worker_store().await.map_err(js_error)?;
+        reply_to_js(render_lowest_available(&store, p).await.map_err(js_error)?)
+    })
+}
+
+/// Composite the offered sweeps; see `worker_render_lowest_available`.
+pub(crate) async fn render_lowest_available(
+    store: &impl RecordStore,
+    p: RenderLowestAvailableParams,
+) -> Result<Reply<RenderResponse>, String> {
+    let t_total = web_time::Instant::now();
+
+    let t_fetch = web_time::Instant::now();
+    let mut composite: Option<LowestAvailableComposite> = None;
+    let mut loaded = 0usize;
+    for sweep in &p.sweeps {
+        if composite.as_ref().is_some_and(|c| c.is_complete()) {
+            break;
+        }
+        let Some(scan_key) = ScanKey::from_storage_key(&sweep.scan_key) else {
+            continue;
+        };
+        let key = SweepDataKey::new(scan_key, sweep.elevation_number, &p.product);
+        let Some(data) = load_sweep(store, key).await else {
+            continue;
+        };
+        loaded += 1;
+        match composite.as_mut() {
+            Some(c) => c.add_sweep(&data),
+            None => composite = Some(LowestAvailableComposite::new(&data)),
+        }
+    }
+    let fetch_ms = t_fetch.elapsed().as_secs_f64() * 1000.0;
+
+    let composite = composite.ok_or("No pre-computed sweep in the composite window")?;
+    let sweeps_used = composite.sweep_count();
+    let (sweep, gate_times) = composite.finish();
+
+    let t_marshal = web_time::Instant::now();
+    let GateValues::U16(raw) = &sweep.gate_values else {
+        return Err("Composite is not U16".to_string());
+    };
+    let gate_values_f32: Vec<f32> = raw.iter().map(|&v| v as f32).collect();
+    let marshal_ms = t_marshal.elapsed().as_secs_f64() * 1000.0;
+    let total_ms = t_total.elapsed().as_secs_f64() * 1000.0;
+
+    log::debug!(
+            "render_lowest_available: {} from {} of {} loaded / {} offered sweeps in {:.1}ms | fetch+composite {:.1}",
+            p.product,
+            sweeps_used,
+            loaded,
+            p.sweeps.len(),
+            total_ms,
+            fetch_ms,
+        );
+
+    let response = RenderResponse {
+        azimuth_count: sweep.azimuth_count,
+        gate_count: sweep.gate_count,
+        first_gate_range_km: sweep.first_gate_range_km,
+        gate_interval_km: sweep.gate_interval_km,
+        max_range_km: sweep.max_range_km,
+        product: p.product,
+        radial_count: sweep.radial_count,
+        scale: sweep.scale as f64,
+        offset: sweep.offset as f64,
+        mean_elevation: sweep.mean_elevation as f64,
+        sweep_start_secs: sweep.sweep_start_secs,
+        sweep_end_secs: sweep.sweep_end_secs,
+        fetch_ms,
+        deser_ms: 0.0,
+        total_ms,
+        marshal_ms,
+        azimuth_spacing_deg: 360.0 / sweep.azimuth_count.max(1) as f32,
+        nyquist_velocity: sweep.nyquist_velocity,
+    };
+    Ok(Reply {
+        fields: response,
+        arrays: vec![
+            ("azimuths", Array::F32(sweep.azimuths)),
+            ("gateValues", Array::F32(gate_values_f32)),
+            ("gateTimes", Array::F64(gate_times)),
+        ],
+    })
+}
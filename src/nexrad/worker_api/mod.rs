@@ -1,35 +1,68 @@
-//! WASM exports for the Web Worker.
+//! Heavy data operations run off the UI thread.
 //!
-//! These functions are called from worker.js to perform heavy data operations
-//! (ingest, render) in a background thread, keeping the main UI responsive.
+//! Each operation is an async function over a [`RecordStore`] returning a
+//! typed response. In the browser, `#[wasm_bindgen]` wrappers expose them to
+//! worker.js; on the desktop, `decode_worker`'s threads call them directly.
 
+mod cf_radial;
+mod cross_section;
 mod ingest;
+mod level3;
+mod lowest_available;
+mod mosaic;
+mod qpe;
 mod render;
 mod render_live;
 
-use crate::data::indexeddb::IndexedDbRecordStore;
+pub(crate) use cf_radial::CfRadialResponse;
+pub(crate) use cross_section::CrossSectionResponse;
+pub(crate) use level3::Level3Response;
+pub(crate) use mosaic::MosaicResponse;
+
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) use {
+    cf_radial::{cf_radial, CfRadialParams},
+    cross_section::{cross_section, CrossSectionParams},
+    ingest::{ingest, ingest_chunk},
+    level3::{ingest_level3, Level3Params},
+    lowest_available::{render_lowest_available, RenderLowestAvailableParams},
+    mosaic::{mosaic, MosaicParams},
+    qpe::{render_qpe, RenderQpeParams},
+    render::{render, render_volume},
+    render_live::{render_live, RenderLiveParams},
+};
+
 use crate::data::keys::*;
+use crate::data::RecordStore;
 use serde::{Deserialize, Serialize};
+#[cfg(target_arch = "wasm32")]
 use wasm_bindgen::JsValue;
 
 // ---------------------------------------------------------------------------
 // Typed input param structs — deserialized from JS objects via serde-wasm-bindgen
+// in the browser, built directly by the desktop decode threads
 // ---------------------------------------------------------------------------
 
 /// Parameters for `worker_ingest`. The `data` ArrayBuffer is extracted separately.
+/// Local imports omit the site and time; they come from the volume header.
 #[derive(Deserialize)]
 #[serde(rename_all = "camelCase")]
-pub(super) struct IngestParams {
-    pub site_id: String,
-    pub timestamp_secs: f64,
+pub(crate) struct IngestParams {
+    #[serde(default)]
+    pub site_id: Option<String>,
+    #[serde(default)]
+    pub timestamp_secs: Option<f64>,
     #[serde(default)]
     pub file_name: String,
+    /// Also store a dealiased copy of each velocity sweep.
+    #[serde(default)]
+    pub dealias_velocity: bool,
 }
 
 /// Parameters for `worker_render`.
 #[derive(Deserialize)]
 #[serde(rename_all = "camelCase")]
-pub(super) struct RenderParams {
+pub(crate) struct RenderParams {
     pub scan_key: String,
     pub elevation_number: u8,
     #[serde(default = "default_product")]
@@ -39,7 +72,7 @@ pub(super) struct RenderParams {
 /// Parameters for `worker_render_volume`.
 #[derive(Deserialize)]
 #[serde(rename_all = "camelCase")]
-pub(super) struct RenderVolumeParams {
+pub(crate) struct RenderVolumeParams {
     pub scan_key: String,
     #[serde(default = "default_product")]
     pub product: String,
@@ -49,7 +82,7 @@ pub(super) struct RenderVolumeParams {
 /// Parameters for `worker_ingest_chunk`. The `data` ArrayBuffer is extracted separately.
 #[derive(Deserialize)]
 #[serde(rename_all = "camelCase")]
-pub(super) struct IngestChunkParams {
+pub(crate) struct IngestChunkParams {
     pub site_id: String,
     pub timestamp_secs: f64,
     #[serde(default)]
@@ -67,30 +100,50 @@ pub(super) struct IngestChunkParams {
     /// waiting for the next elevation's first chunk.
     #[serde(default)]
     pub is_last_in_sweep: bool,
+    /// Also store a dealiased copy of each velocity sweep.
+    #[serde(default)]
+    pub dealias_velocity: bool,
 }
 
 pub(super) fn default_product() -> String {
     "reflectivity".to_string()
 }
 
-/// Extract the `data` ArrayBuffer field from a JS object as `Vec<u8>`.
-pub(super) fn extract_data_bytes(obj: &JsValue) -> Result<Vec<u8>, JsValue> {
-    let val = js_sys::Reflect::get(obj, &"data".into())
-        .map_err(|e| JsValue::from_str(&format!("Missing data: {:?}", e)))?;
-    Ok(js_sys::Uint8Array::new(&val).to_vec())
+/// Fetch and parse one sweep blob, `None` if it isn't cached.
+pub(crate) async fn load_sweep(
+    store: &impl RecordStore,
+    key: SweepDataKey,
+) -> Option<PrecomputedSweep> {
+    let bytes = store.get_sweep(&key.to_storage_key()).await.ok()??;
+    PrecomputedSweep::from_bytes(&bytes).ok()
 }
 
 // ---------------------------------------------------------------------------
-// Typed response structs — serialized to JS objects via serde-wasm-bindgen
+// Typed response structs — serialized to JS objects via serde-wasm-bindgen and
+// read back by `decode_worker`, or handed over as-is on the desktop
 // ---------------------------------------------------------------------------
 
+/// Binary array sent beside a response's fields. The browser transfers
+/// these as ArrayBuffers; the desktop threads move the vectors.
+pub(crate) enum Array {
+    U8(Vec<u8>),
+    F32(Vec<f32>),
+    F64(Vec<f64>),
+}
+
+/// A response's fields plus the named arrays that travel beside them.
+pub(crate) struct Reply<R> {
+    pub fields: R,
+    pub arrays: Vec<(&'static str, Array)>,
+}
+
 /// Response from `worker_ingest`.
-#[derive(Serialize)]
+#[derive(Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
-pub(super) struct IngestResponse<'a> {
+pub(crate) struct IngestResponse {
     pub records_stored: u32,
     pub scan_key: String,
-    pub elevation_numbers: &'a [u8],
+    pub elevation_numbers: Vec<u8>,
     pub total_ms: f64,
     pub split_ms: f64,
     pub decompress_ms: f64,
@@ -98,16 +151,16 @@ pub(super) struct IngestResponse<'a> {
     pub extract_ms: f64,
     pub store_ms: f64,
     pub index_ms: f64,
-    pub sweeps: &'a [SweepMeta],
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub vcp: Option<&'a ExtractedVcp>,
+    pub sweeps: Vec<SweepMeta>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vcp: Option<ExtractedVcp>,
 }
 
 /// Scalar fields of the response from `worker_render`.
 /// ArrayBuffer fields (azimuths, gateValues, radialTimes) are set separately.
-#[derive(Serialize)]
+#[derive(Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
-pub(super) struct RenderResponse {
+pub(crate) struct RenderResponse {
     pub azimuth_count: u32,
     pub gate_count: u32,
     pub first_gate_range_km: f64,
@@ -127,12 +180,15 @@ pub(super) struct RenderResponse {
     /// Median angular spacing between adjacent sorted radials, in degrees.
     /// Used by the shader's search threshold instead of deriving from azimuth_count.
     pub azimuth_spacing_deg: f32,
+    /// Nyquist velocity (m/s) recorded with a velocity sweep.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nyquist_velocity: Option<f32>,
 }
 
 /// Response from `worker_ingest_chunk`.
-#[derive(Serialize)]
+#[derive(Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
-pub(super) struct ChunkIngestResponse {
+pub(crate) struct ChunkIngestResponse {
     pub chunk_index: u32,
     pub radials_decoded: u32,
     pub sweeps_stored: u32,
@@ -141,32 +197,32 @@ pub(super) struct ChunkIngestResponse {
     pub total_ms: f64,
     pub sweeps: Vec<SweepMeta>,
     pub elevations_completed: Vec<u8>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub vcp: Option<ExtractedVcp>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub chunk_min_time_secs: Option<f64>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub chunk_max_time_secs: Option<f64>,
-    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub chunk_elev_spans: Vec<(u8, f64, f64, u32)>,
-    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub chunk_elev_az_ranges: Vec<(u8, f32, f32)>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub volume_header_time_secs: Option<f64>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub last_radial_azimuth: Option<f32>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub last_radial_time_secs: Option<f64>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub current_elevation: Option<u8>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub current_elevation_radials: Option<u32>,
 }
 
 /// Per-sweep metadata in the volume render response.
-#[derive(Serialize)]
+#[derive(Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
-pub(super) struct VolumeRenderSweepMeta {
+pub(crate) struct VolumeRenderSweepMeta {
     pub elevation_deg: f64,
     pub azimuth_count: u32,
     pub gate_count: u32,
@@ -180,9 +236,9 @@ pub(super) struct VolumeRenderSweepMeta {
 
 /// Scalar fields of the volume render response.
 /// The `buffer` ArrayBuffer is set separately for zero-copy transfer.
-#[derive(Serialize)]
+#[derive(Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
-pub(super) struct VolumeRenderResponse {
+pub(crate) struct VolumeRenderResponse {
     pub sweep_count: u32,
     pub word_size: u8,
     pub sweep_meta: Vec<VolumeRenderSweepMeta>,
@@ -191,38 +247,101 @@ pub(super) struct VolumeRenderResponse {
 }
 
 // ---------------------------------------------------------------------------
-// Worker-side cached IDB connection
+// Browser glue: JS objects in and out
 // ---------------------------------------------------------------------------
-// WASM is single-threaded so thread_local! is safe. We keep a single
-// IndexedDbRecordStore alive for the lifetime of the worker so that
-// subsequent ingest/render calls reuse the already-open IDB connection
-// instead of paying the ~60ms open+list overhead every time.
+
+/// Extract the `data` ArrayBuffer field from a JS object as `Vec<u8>`.
+#[cfg(target_arch = "wasm32")]
+pub(super) fn extract_data_bytes(obj: &JsValue) -> Result<Vec<u8>, JsValue> {
+    let val = js_sys::Reflect::get(obj, &"data".into())
+        .map_err(|e| JsValue::from_str(&format!("Missing data: {:?}", e)))?;
+    Ok(js_sys::Uint8Array::new(&val).to_vec())
+}
+
+/// An operation's error message as a JS exception value.
+#[cfg(target_arch = "wasm32")]
+pub(super) fn js_error(message: String) -> JsValue {
+    JsValue::from_str(&message)
+}
+
+/// Deserialize an operation's parameters from a JS object.
+#[cfg(target_arch = "wasm32")]
+pub(super) fn parse_params<T: serde::de::DeserializeOwned>(
+    params: JsValue,
+    operation: &str,
+) -> Result<T, JsValue> {
+    serde_wasm_bindgen::from_value(params)
+        .map_err(|e| JsValue::from_str(&format!("Invalid {} params: {}", operation, e)))
+}
+
+/// Serialize a response's fields to a JS object.
+#[cfg(target_arch = "wasm32")]
+pub(super) fn response_to_js<R: Serialize>(response: &R) -> Result<JsValue, JsValue> {
+    serde_wasm_bindgen::to_value(response)
+        .map_err(|e| JsValue::from_str(&format!("Failed to serialize response: {}", e)))
+}
+
+/// Serialize a reply's fields and attach its arrays as ArrayBuffers.
+#[cfg(target_arch = "wasm32")]
+pub(super) fn reply_to_js<R: Serialize>(reply: Reply<R>) -> Result<JsValue, JsValue> {
+    let result = response_to_js(&reply.fields)?;
+    // ArrayBuffer fields must be set directly (not serializable via serde)
+    for (name, array) in reply.arrays {
+        let buffer = match array {
+            Array::U8(v) => js_sys::Uint8Array::from(v.as_slice()).buffer(),
+            Array::F32(v) => js_sys::Float32Array::from(v.as_slice()).buffer(),
+            Array::F64(v) => js_sys::Float64Array::from(v.as_slice()).buffer(),
+        };
+        js_sys::Reflect::set(&result, &name.into(), &buffer).ok();
+    }
+    Ok(result)
+}
+
+// ---------------------------------------------------------------------------
+// Worker-side cached store
+// ---------------------------------------------------------------------------
+// Each worker (a Web Worker, or a decode thread on the desktop) runs one
+// operation at a time, so thread_local! is safe. We keep a single store
+// alive for the lifetime of the worker so that subsequent ingest/render
+// calls reuse the already-open IDB connection instead of paying the ~60ms
+// open+list overhead every time.
+
+#[cfg(target_arch = "wasm32")]
+pub(crate) type WorkerStore = crate::data::indexeddb::IndexedDbRecordStore;
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) type WorkerStore = crate::data::directory::DirectoryRecordStore;
 
 thread_local! {
-    pub(super) static WORKER_IDB: IndexedDbRecordStore = IndexedDbRecordStore::new();
+    #[cfg(target_arch = "wasm32")]
+    static WORKER_STORE: WorkerStore = WorkerStore::new();
+    #[cfg(not(target_arch = "wasm32"))]
+    static WORKER_STORE: WorkerStore = WorkerStore::new(crate::platform::cache_dir());
+    #[cfg(target_arch = "wasm32")]
     static WORKER_LOGGER_INIT: std::cell::Cell<bool> = const { std::cell::Cell::new(false) };
 }
 
-/// Initialize the log crate in the worker context (once).
+/// Initialize the log crate in the worker context (once). The desktop
+/// threads share the process logger set up in `main`.
+#[cfg(target_arch = "wasm32")]
 pub(super) fn init_logger() {
     WORKER_LOGGER_INIT.with(|init| {
         if !init.get() {
-            eframe::WebLogger::init(log::LevelFilter::Debug).ok();
+            crate::platform::init_logger();
             init.set(true);
         }
     });
 }
 
-/// Get (or lazily open) the shared worker IDB store.
+/// Get (or lazily open) the shared worker store.
 ///
 /// The store itself is eagerly constructed; `open()` is a no-op after the
 /// first successful call, and concurrent callers racing the first open
 /// coalesce inside `IndexedDbRecordStore::open`.
-pub(super) async fn idb_store() -> Result<IndexedDbRecordStore, wasm_bindgen::JsValue> {
-    let store = WORKER_IDB.with(|s| s.clone());
+pub(crate) async fn worker_store() -> Result<WorkerStore, String> {
+    let store = WORKER_STORE.with(|s| s.clone());
     store
         .open()
         .await
-        .map_err(|e| wasm_bindgen::JsValue::from_str(&format!("Failed to open IDB: {}", e)))?;
+        .map_err(|e| format!("Failed to open record store: {}", e))?;
     Ok(store)
 }
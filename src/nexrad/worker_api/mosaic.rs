@@ -0,0 +1,156 @@
+//! Regional mosaic of several sites' cached scans.
+
+use super::*;
+use crate::data::get_site;
+use crate::nexrad::mosaic::{build_mosaic, MosaicMode, MosaicSource};
+use crate::nexrad::polar::PolarSweep;
+
+/// Parameters for `worker_mosaic`.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct MosaicParams {
+    pub site_ids: Vec<String>,
+    pub time_secs: i64,
+    pub max_age_secs: i64,
+    #[serde(default = "default_product")]
+    pub product: String,
+    #[serde(default)]
+    pub mode: MosaicMode,
+}
+
+/// Scalar fields of the mosaic response. The `values` ArrayBuffer is set
+/// separately.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct MosaicResponse {
+    pub west: f64,
+    pub north: f64,
+    pub cell_deg: f64,
+    pub columns: u32,
+    pub rows: u32,
+    /// Storage keys of the scans composited, one per site with data.
+    pub scan_keys: Vec<String>,
+    pub total_ms: f64,
+}
+
+/// The lowest tilt carrying `product` of `site_id`'s latest cached scan
+/// starting within `max_age_secs` before `time_secs`.
+async fn latest_lowest_sweep(
+    store: &impl RecordStore,
+    site_id: &str,
+    time_secs: i64,
+    max_age_secs: i64,
+    product: &str,
+) -> Result<Option<(ScanKey, PrecomputedSweep)>, String> {
+    let mut scans = store
+        .list_scans(
+            &SiteId(site_id.to_string()),
+            UnixMillis::from_secs(time_secs - max_age_secs),
+            UnixMillis::from_secs(time_secs),
+        )
+        .await
+        .map_err(|e| format!("Failed to list scans: {}", e))?;
+    scans.sort_by_key(|entry| std::cmp::Reverse(entry.scan.scan_start.0));
+
+    for entry in scans {
+        let Some(sweeps) = entry.sweeps.as_ref() else {
+            continue;
+        };
+        // Legacy entries list no products; try them anyway.
+        let mut candidates: Vec<&SweepMeta> = sweeps
+            .iter()
+            .filter(|s| {
+                s.available_products.is_empty() || s.available_products.iter().any(|p| p == product)
+            })
+            .collect();
+        candidates.sort_by(|a, b| {
+            a.elevation
+                .total_cmp(&b.elevation)
+                .then(a.elevation_number.cmp(&b.elevation_number))
+        });
+        for meta in candidates {
+            let key = SweepDataKey::new(entry.scan.clone(), meta.elevation_number, product);
+            if let Some(sweep) = load_sweep(store, key).await {
+                return Ok(Some((entry.scan.clone(), sweep)));
+            }
+        }
+    }
+    Ok(None)
+}
+
+/// Composite the lowest tilt of several sites' scans onto one lat/lon grid.
+///
+/// Each site contributes its latest cached scan starting at most
+/// `maxAgeSecs` before `timeSecs`; sites without one are left out. Returns
+/// the grid's placement plus `values` (Float32Array, row-major from the
+/// north-west corner, NaN = no data).
+///
+/// Parameters (JS object): `{ siteIds, timeSecs, maxAgeSecs, product, mode }`
+#[cfg(target_arch = "wasm32")]
+#[wasm_bindgen::prelude::wasm_bindgen]
+pub fn worker_mosaic(params: wasm_bindgen::JsValue) -> js_sys::Promise {
+    init_logger();
+    wasm_bindgen_futures::future_to_promise(async move {
+        let p: MosaicParams = parse_params(params, "mosaic")?;
+        let store = worker_store().await.map_err(js_error)?;
+        reply_to_js(mosaic(&store, p).await.map_err(js_error)?)
+    })
+}
+
+/// Composite the listed sites; see `worker_mosaic`.
+pub(crate) async fn mosaic(
+    store: &impl RecordStore,
+    p: MosaicParams,
+) -> Result<Reply<MosaicResponse>, String> {
+    let t_total = web_time::Instant::now();
+
+    let mut sources = Vec::with_capacity(p.site_ids.len());
+    let mut scan_keys = Vec::with_capacity(p.site_ids.len());
+    for site_id in &p.site_ids {
+        let Some(site) = get_site(site_id) else {
+            log::warn!("mosaic: unknown site {}", site_id);
+            continue;
+        };
+        let found =
+            latest_lowest_sweep(store, site.id, p.time_secs, p.max_age_secs, &p.product).await?;
+        let Some((scan_key, sweep)) = found else {
+            continue;
+        };
+        scan_keys.push(scan_key.to_storage_key());
+        sources.push(MosaicSource {
+            lat: site.lat,
+            lon: site.lon,
+            altitude_km: site.altitude_km(),
+            elevation_deg: sweep.mean_elevation,
+            sweep: PolarSweep::from_precomputed(&sweep),
+        });
+    }
+
+    let grid = build_mosaic(&sources, p.mode);
+    let total_ms = t_total.elapsed().as_secs_f64() * 1000.0;
+
+    log::debug!(
+        "mosaic: {} from {} of {} sites, {}x{} cells of {:.3}\u{00B0} in {:.1}ms",
+        p.product,
+        sources.len(),
+        p.site_ids.len(),
+        grid.columns,
+        grid.rows,
+        grid.cell_deg,
+        total_ms,
+    );
+
+    let response = MosaicResponse {
+        west: grid.west,
+        north: grid.north,
+        cell_deg: grid.cell_deg,
+        columns: grid.columns as u32,
+        rows: grid.rows as u32,
+        scan_keys,
+        total_ms,
+    };
+    Ok(Reply {
+        fields: response,
+        arrays: vec![("values", Array::F32(grid.values))],
+    })
+}
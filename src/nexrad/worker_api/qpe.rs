@@ -0,0 +1,125 @@
+//! Rainfall accumulations (QPE) over cached scans.
+
+use super::*;
+use crate::nexrad::decode_worker::QpeScan;
+use crate::nexrad::qpe::{QpeAccumulator, QpeSettings};
+
+/// Parameters for `worker_render_qpe`.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct RenderQpeParams {
+    pub product: String,
+    pub scans: Vec<QpeScan>,
+    pub settings: QpeSettings,
+}
+
+/// Accumulate rainfall over a list of cached scans.
+///
+/// Reads each scan's lowest-tilt reflectivity (and differential phase when
+/// `settings.useKdp` is set) from IndexedDB — nothing is downloaded. Scans
+/// missing from the cache are skipped. Returns the same RenderResponse shape
+/// as `worker_render`, with gate values in millimetres.
+///
+/// Parameters (JS object): `{ product: string, scans: [{ scanKey, elevationNumber, hours }], settings }`
+#[cfg(target_arch = "wasm32")]
+#[wasm_bindgen::prelude::wasm_bindgen]
+pub fn worker_render_qpe(params: wasm_bindgen::JsValue) -> js_sys::Promise {
+    init_logger();
+    wasm_bindgen_futures::future_to_promise(async move {
+        let p: RenderQpeParams = parse_params(params, "render_qpe")?;
+        let store = worker_store().await.map_err(js_error)?;
+        reply_to_js(render_qpe(&store, p).await.map_err(js_error)?)
+    })
+}
+
+/// Accumulate rainfall over the listed scans; see `worker_render_qpe`.
+pub(crate) async fn render_qpe(
+    store: &impl RecordStore,
+    p: RenderQpeParams,
+) -> Result<Reply<RenderResponse>, String> {
+    let t_total = web_time::Instant::now();
+
+    let t_fetch = web_time::Instant::now();
+    let mut accumulator = QpeAccumulator::new();
+    for scan in &p.scans {
+        let Some(scan_key) = ScanKey::from_storage_key(&scan.scan_key) else {
+            continue;
+        };
+        let reflectivity = load_sweep(
+            store,
+            SweepDataKey::new(scan_key.clone(), scan.elevation_number, "reflectivity"),
+        )
+        .await;
+        let Some(reflectivity) = reflectivity else {
+            continue;
+        };
+        let differential_phase = if p.settings.use_kdp {
+            load_sweep(
+                store,
+                SweepDataKey::new(scan_key, scan.elevation_number, "differential_phase"),
+            )
+            .await
+        } else {
+            None
+        };
+        accumulator.add_scan(
+            &reflectivity,
+            differential_phase.as_ref(),
+            scan.hours as f32,
+            &p.settings,
+        );
+    }
+    let fetch_ms = t_fetch.elapsed().as_secs_f64() * 1000.0;
+
+    let scans_used = accumulator.scan_count();
+    let sweep = accumulator
+        .finish()
+        .ok_or("No pre-computed sweep in the accumulation window")?;
+
+    let t_marshal = web_time::Instant::now();
+    let GateValues::U16(raw) = &sweep.gate_values else {
+        return Err("Accumulation is not U16".to_string());
+    };
+    let gate_values_f32: Vec<f32> = raw.iter().map(|&v| v as f32).collect();
+    let marshal_ms = t_marshal.elapsed().as_secs_f64() * 1000.0;
+    let total_ms = t_total.elapsed().as_secs_f64() * 1000.0;
+
+    log::debug!(
+        "render_qpe: {} from {}/{} scans ({:?}, kdp={}) in {:.1}ms | fetch+accumulate {:.1}",
+        p.product,
+        scans_used,
+        p.scans.len(),
+        p.settings.zr,
+        p.settings.use_kdp,
+        total_ms,
+        fetch_ms,
+    );
+
+    let response = RenderResponse {
+        azimuth_count: sweep.azimuth_count,
+        gate_count: sweep.gate_count,
+        first_gate_range_km: sweep.first_gate_range_km,
+        gate_interval_km: sweep.gate_interval_km,
+        max_range_km: sweep.max_range_km,
+        product: p.product,
+        radial_count: sweep.radial_count,
+        scale: sweep.scale as f64,
+        offset: sweep.offset as f64,
+        mean_elevation: sweep.mean_elevation as f64,
+        sweep_start_secs: sweep.sweep_start_secs,
+        sweep_end_secs: sweep.sweep_end_secs,
+        fetch_ms,
+        deser_ms: 0.0,
+        total_ms,
+        marshal_ms,
+        azimuth_spacing_deg: 360.0 / sweep.azimuth_count.max(1) as f32,
+        nyquist_velocity: sweep.nyquist_velocity,
+    };
+    Ok(Reply {
+        fields: response,
+        arrays: vec![
+            ("azimuths", Array::F32(sweep.azimuths)),
+            ("gateValues", Array::F32(gate_values_f32)),
+        ],
+    })
+}
@@ -1,4 +1,8 @@
-//! WASM exports for render operations (single-elevation and volume).
+//! Render operations (single-elevation and volume).
+//!
+//! The browser versions view the IndexedDB ArrayBuffer in place so sweep
+//! data is never copied into WASM memory; the desktop versions read the
+//! blob from disk and parse it.
 
 use super::*;
 
@@ -9,14 +13,14 @@ use super::*;
 ///
 /// Parameters (JS object): `{ scanKey: string, elevationNumber: number, product: string }`
 /// Returns (JS object): `{ azimuths: Float32Array, gateValues: Float32Array, azimuthCount, gateCount, ... }`
+#[cfg(target_arch = "wasm32")]
 #[wasm_bindgen::prelude::wasm_bindgen]
 pub fn worker_render(params: wasm_bindgen::JsValue) -> js_sys::Promise {
     init_logger();
     wasm_bindgen_futures::future_to_promise(async move {
         let t_total = web_time::Instant::now();
 
-        let p: RenderParams = serde_wasm_bindgen::from_value(params)
-            .map_err(|e| JsValue::from_str(&format!("Invalid render params: {}", e)))?;
+        let p: RenderParams = parse_params(params, "render")?;
         let scan_key_str = p.scan_key;
         let elevation_number = p.elevation_number;
         let product_str = p.product;
@@ -24,7 +28,7 @@ pub fn worker_render(params: wasm_bindgen::JsValue) -> js_sys::Promise {
         let scan_key = ScanKey::from_storage_key(&scan_key_str)
             .ok_or_else(|| wasm_bindgen::JsValue::from_str("Invalid scanKey format"))?;
 
-        let store = idb_store().await?;
+        let store = worker_store().await.map_err(js_error)?;
 
         // Fetch raw IDB ArrayBuffer (no Rust-side copy)
         let t_fetch = web_time::Instant::now();
@@ -146,9 +150,9 @@ pub fn worker_render(params: wasm_bindgen::JsValue) -> js_sys::Promise {
             total_ms,
             marshal_ms,
             azimuth_spacing_deg,
+            nyquist_velocity: header.nyquist_velocity,
         };
-        let result = serde_wasm_bindgen::to_value(&response)
-            .map_err(|e| JsValue::from_str(&format!("Failed to serialize response: {}", e)))?;
+        let result = response_to_js(&response)?;
         // ArrayBuffer fields must be set directly (not serializable via serde)
         js_sys::Reflect::set(&result, &"azimuths".into(), &az_buf).ok();
         js_sys::Reflect::set(&result, &"gateValues".into(), &val_buf).ok();
@@ -168,14 +172,14 @@ pub fn worker_render(params: wasm_bindgen::JsValue) -> js_sys::Promise {
 ///
 /// Parameters (JS object): `{ scanKey: string, product: string, elevationNumbers: number[] }`
 /// Returns (JS object): `{ buffer: ArrayBuffer, sweepMeta: [...], product, totalMs }`
+#[cfg(target_arch = "wasm32")]
 #[wasm_bindgen::prelude::wasm_bindgen]
 pub fn worker_render_volume(params: wasm_bindgen::JsValue) -> js_sys::Promise {
     init_logger();
     wasm_bindgen_futures::future_to_promise(async move {
         let t_total = web_time::Instant::now();
 
-        let p: RenderVolumeParams = serde_wasm_bindgen::from_value(params)
-            .map_err(|e| JsValue::from_str(&format!("Invalid render_volume params: {}", e)))?;
+        let p: RenderVolumeParams = parse_params(params, "render_volume")?;
         let scan_key_str = p.scan_key;
         let product_str = p.product;
         let elevation_numbers = p.elevation_numbers;
@@ -183,7 +187,7 @@ pub fn worker_render_volume(params: wasm_bindgen::JsValue) -> js_sys::Promise {
         let scan_key = ScanKey::from_storage_key(&scan_key_str)
             .ok_or_else(|| wasm_bindgen::JsValue::from_str("Invalid scanKey format"))?;
 
-        let store = idb_store().await?;
+        let store = worker_store().await.map_err(js_error)?;
 
         // Collect all sweep data into a packed buffer.
         // We keep native word size when all sweeps are u8 to halve transfer cost.
@@ -307,8 +311,7 @@ pub fn worker_render_volume(params: wasm_bindgen::JsValue) -> js_sys::Promise {
             product: product_str,
             total_ms,
         };
-        let result = serde_wasm_bindgen::to_value(&response)
-            .map_err(|e| JsValue::from_str(&format!("Failed to serialize response: {}", e)))?;
+        let result = response_to_js(&response)?;
 
         // ArrayBuffer must be set directly for zero-copy transfer
         let packed_u8 = js_sys::Uint8Array::from(&packed_data[..]);
@@ -318,3 +321,174 @@ pub fn worker_render_volume(params: wasm_bindgen::JsValue) -> js_sys::Promise {
         Ok(result)
     })
 }
+
+/// Read one pre-computed sweep for GPU upload. The desktop counterpart of
+/// `worker_render`.
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) async fn render(
+    store: &impl RecordStore,
+    p: RenderParams,
+) -> Result<Reply<RenderResponse>, String> {
+    let t_total = web_time::Instant::now();
+
+    let scan_key = ScanKey::from_storage_key(&p.scan_key).ok_or("Invalid scanKey format")?;
+
+    let t_fetch = web_time::Instant::now();
+    let sweep_key = SweepDataKey::new(scan_key, p.elevation_number, &p.product);
+    let blob = store
+        .get_sweep(&sweep_key.to_storage_key())
+        .await
+        .map_err(|e| format!("Failed to fetch sweep: {}", e))?
+        .ok_or_else(|| {
+            format!(
+                "No pre-computed sweep for elev={} product={}",
+                p.elevation_number, p.product
+            )
+        })?;
+    let fetch_ms = t_fetch.elapsed().as_secs_f64() * 1000.0;
+
+    let t_deser = web_time::Instant::now();
+    let sweep = PrecomputedSweep::from_bytes(&blob)?;
+    let deser_ms = t_deser.elapsed().as_secs_f64() * 1000.0;
+
+    let t_marshal = web_time::Instant::now();
+    let gate_values: Vec<f32> = match &sweep.gate_values {
+        GateValues::U8(v) => v.iter().map(|&x| x as f32).collect(),
+        GateValues::U16(v) => v.iter().map(|&x| x as f32).collect(),
+    };
+    let marshal_ms = t_marshal.elapsed().as_secs_f64() * 1000.0;
+    let total_ms = t_total.elapsed().as_secs_f64() * 1000.0;
+
+    log::debug!(
+        "render: elev={} {} {}x{} ({:.1}KB) in {:.1}ms | fetch {:.1} | deser {:.1} | marshal {:.1}",
+        p.elevation_number,
+        p.product,
+        sweep.azimuth_count,
+        sweep.gate_count,
+        blob.len() as f64 / 1024.0,
+        total_ms,
+        fetch_ms,
+        deser_ms,
+        marshal_ms,
+    );
+
+    // Same spacing rule as the browser path: blobs cover a full rotation.
+    let azimuth_spacing_deg = if sweep.azimuth_count > 0 {
+        360.0f32 / sweep.azimuth_count as f32
+    } else {
+        1.0
+    };
+
+    Ok(Reply {
+        fields: RenderResponse {
+            azimuth_count: sweep.azimuth_count,
+            gate_count: sweep.gate_count,
+            first_gate_range_km: sweep.first_gate_range_km,
+            gate_interval_km: sweep.gate_interval_km,
+            max_range_km: sweep.max_range_km,
+            product: p.product,
+            radial_count: sweep.radial_count,
+            scale: sweep.scale as f64,
+            offset: sweep.offset as f64,
+            mean_elevation: sweep.mean_elevation as f64,
+            sweep_start_secs: sweep.sweep_start_secs,
+            sweep_end_secs: sweep.sweep_end_secs,
+            fetch_ms,
+            deser_ms,
+            total_ms,
+            marshal_ms,
+            azimuth_spacing_deg,
+            nyquist_velocity: sweep.nyquist_velocity,
+        },
+        arrays: vec![
+            ("azimuths", Array::F32(sweep.azimuths)),
+            ("gateValues", Array::F32(gate_values)),
+            ("radialTimes", Array::F64(sweep.radial_times)),
+        ],
+    })
+}
+
+/// Pack every listed elevation for ray marching. The desktop counterpart
+/// of `worker_render_volume`, with the same word-size rule.
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) async fn render_volume(
+    store: &impl RecordStore,
+    p: RenderVolumeParams,
+) -> Result<Reply<VolumeRenderResponse>, String> {
+    let t_total = web_time::Instant::now();
+
+    let scan_key = ScanKey::from_storage_key(&p.scan_key).ok_or("Invalid scanKey format")?;
+
+    let mut sweeps = Vec::with_capacity(p.elevation_numbers.len());
+    for &elev_num in &p.elevation_numbers {
+        let key = SweepDataKey::new(scan_key.clone(), elev_num, &p.product);
+        if let Some(sweep) = load_sweep(store, key).await {
+            sweeps.push(sweep);
+        }
+    }
+
+    // Keep u8 when every sweep is u8; widen to u16 only for mixed volumes.
+    let word_size: u8 = if sweeps
+        .iter()
+        .any(|s| matches!(s.gate_values, GateValues::U16(_)))
+    {
+        2
+    } else {
+        1
+    };
+
+    let mut packed_data: Vec<u8> = Vec::new();
+    let mut sweep_meta = Vec::with_capacity(sweeps.len());
+    let mut data_offset: u32 = 0;
+    for sweep in &sweeps {
+        let total_values = match &sweep.gate_values {
+            GateValues::U8(v) if word_size == 1 => {
+                packed_data.extend_from_slice(v);
+                v.len()
+            }
+            GateValues::U8(v) => {
+                packed_data.extend(v.iter().flat_map(|&x| (x as u16).to_le_bytes()));
+                v.len()
+            }
+            GateValues::U16(v) => {
+                packed_data.extend(v.iter().flat_map(|x| x.to_le_bytes()));
+                v.len()
+            }
+        };
+
+        sweep_meta.push(VolumeRenderSweepMeta {
+            elevation_deg: sweep.mean_elevation as f64,
+            azimuth_count: sweep.azimuth_count,
+            gate_count: sweep.gate_count,
+            first_gate_km: sweep.first_gate_range_km,
+            gate_interval_km: sweep.gate_interval_km,
+            max_range_km: sweep.max_range_km,
+            data_offset,
+            scale: sweep.scale as f64,
+            offset: sweep.offset as f64,
+        });
+        data_offset += total_values as u32;
+    }
+
+    let total_ms = t_total.elapsed().as_secs_f64() * 1000.0;
+
+    log::debug!(
+        "render_volume: {} sweeps, {} values packed ({:.1}KB, u{}) in {:.1}ms",
+        sweep_meta.len(),
+        data_offset,
+        packed_data.len() as f64 / 1024.0,
+        word_size * 8,
+        total_ms,
+    );
+
+    Ok(Reply {
+        fields: VolumeRenderResponse {
+            sweep_count: sweep_meta.len() as u32,
+            word_size,
+            sweep_meta,
+            product: p.product,
+            total_ms,
+        },
+        arrays: vec![("buffer", Array::U8(packed_data))],
+    })
+}
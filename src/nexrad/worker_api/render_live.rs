@@ -1,4 +1,4 @@
-//! WASM export for live (partial sweep) render from in-memory ChunkAccumulator.
+//! Live (partial sweep) render from the in-memory ChunkAccumulator.
 
 use super::ingest::CHUNK_ACCUM;
 use super::*;
@@ -6,11 +6,11 @@ use super::*;
 /// Parameters for `worker_render_live`.
 #[derive(Deserialize)]
 #[serde(rename_all = "camelCase")]
-struct RenderLiveParams {
+pub(crate) struct RenderLiveParams {
     #[serde(default = "default_product")]
-    product: String,
+    pub product: String,
     #[serde(default)]
-    elevation_number: Option<u8>,
+    pub elevation_number: Option<u8>,
 }
 
 /// Render the current partial sweep from the in-memory ChunkAccumulator.
@@ -19,18 +19,22 @@ struct RenderLiveParams {
 /// Returns the same RenderResponse shape as `worker_render`.
 ///
 /// Parameters (JS object): `{ product: string, elevationNumber?: number }`
+#[cfg(target_arch = "wasm32")]
 #[wasm_bindgen::prelude::wasm_bindgen]
 pub fn worker_render_live(params: wasm_bindgen::JsValue) -> Result<JsValue, JsValue> {
+    let p: RenderLiveParams = parse_params(params, "render_live")?;
+    reply_to_js(render_live(p).map_err(js_error)?)
+}
+
+/// Extract the accumulator's current (or requested) elevation.
+pub(crate) fn render_live(p: RenderLiveParams) -> Result<Reply<RenderResponse>, String> {
     use crate::nexrad::record_decode::extract_sweep_data_from_sorted;
     use nexrad_render::Product;
 
     let t_total = web_time::Instant::now();
 
-    let p: RenderLiveParams = serde_wasm_bindgen::from_value(params)
-        .map_err(|e| JsValue::from_str(&format!("Invalid render_live params: {}", e)))?;
-
     let product = match p.product.as_str() {
-        "velocity" => Product::Velocity,
+        "velocity" | "dealiased_velocity" => Product::Velocity,
         "spectrum_width" => Product::SpectrumWidth,
         "differential_reflectivity" => Product::DifferentialReflectivity,
         "differential_phase" => Product::DifferentialPhase,
@@ -43,12 +47,12 @@ pub fn worker_render_live(params: wasm_bindgen::JsValue) -> Result<JsValue, JsVa
         let borrow = cell.borrow();
         let accum = borrow
             .as_ref()
-            .ok_or_else(|| JsValue::from_str("No chunk accumulator active"))?;
+            .ok_or("No chunk accumulator active")?;
 
         let target_elev = p
             .elevation_number
             .or(accum.current_elevation)
-            .ok_or_else(|| JsValue::from_str("No elevation available in accumulator"))?;
+            .ok_or("No elevation available in accumulator")?;
 
         // With flush-on-transition, only the current elevation's radials
         // are in memory. Sort by azimuth for sweep extraction.
@@ -59,10 +63,10 @@ pub fn worker_render_live(params: wasm_bindgen::JsValue) -> Result<JsValue, JsVa
             .collect();
 
         if sorted.is_empty() {
-            return Err(JsValue::from_str(&format!(
+            return Err(format!(
                 "No radials for elevation {} in accumulator",
                 target_elev
-            )));
+            ));
         }
 
         sorted.sort_by(|a, b| {
@@ -71,14 +75,46 @@ pub fn worker_render_live(params: wasm_bindgen::JsValue) -> Result<JsValue, JsVa
                 .unwrap_or(std::cmp::Ordering::Equal)
         });
 
-        let sweep = extract_sweep_data_from_sorted(&sorted, product).ok_or_else(|| {
-            JsValue::from_str(&format!(
+        let mut sweep = extract_sweep_data_from_sorted(&sorted, product).ok_or_else(|| {
+            format!(
                 "No {} data for elevation {} in accumulator",
                 p.product, target_elev
-            ))
+            )
         })?;
 
-        // Marshal PrecomputedSweep into the same JS response format as worker_render
+        if matches!(product, Product::Velocity) {
+            sweep.nyquist_velocity = accum.nyquist.get(&target_elev).copied();
+        }
+
+        // The in-progress sweep hasn't been dealiased yet; unfold it against
+        // the last flushed sweep. Falls back to raw values if that fails.
+        let sweep = if p.product == crate::nexrad::dealias::DEALIASED_VELOCITY {
+            crate::nexrad::dealias::dealias_sweep(&sweep, accum.dealias_reference.as_ref())
+                .map(|(dealiased, _)| dealiased)
+                .unwrap_or(sweep)
+        } else if p.product == crate::nexrad::hydro_class::HYDROMETEOR_CLASS {
+            // `sweep` is the reflectivity base; classify it with whatever
+            // dual-pol moments the partial sweep carries.
+            let zdr = extract_sweep_data_from_sorted(&sorted, Product::DifferentialReflectivity);
+            let cc = extract_sweep_data_from_sorted(&sorted, Product::CorrelationCoefficient);
+            let phi = extract_sweep_data_from_sorted(&sorted, Product::DifferentialPhase);
+            crate::nexrad::hydro_class::classify_sweep(&crate::nexrad::hydro_class::DualPolMoments {
+                reflectivity: &sweep,
+                differential_reflectivity: zdr.as_ref(),
+                correlation_coefficient: cc.as_ref(),
+                differential_phase: phi.as_ref(),
+            })
+            .ok_or_else(|| {
+                format!(
+                    "No dual-pol data for elevation {} in accumulator",
+                    target_elev
+                )
+            })?
+        } else {
+            sweep
+        };
+
+        // Marshal PrecomputedSweep into the same response format as worker_render
         let t_marshal = web_time::Instant::now();
 
         // Convert gate values to f32 array
@@ -87,15 +123,6 @@ pub fn worker_render_live(params: wasm_bindgen::JsValue) -> Result<JsValue, JsVa
             crate::data::keys::GateValues::U16(v) => v.iter().map(|&x| x as f32).collect(),
         };
 
-        let az_array = js_sys::Float32Array::from(sweep.azimuths.as_slice());
-        let az_buf = az_array.buffer();
-
-        let val_array = js_sys::Float32Array::from(gate_values_f32.as_slice());
-        let val_buf = val_array.buffer();
-
-        let rt_array = js_sys::Float64Array::from(sweep.radial_times.as_slice());
-        let rt_buf = rt_array.buffer();
-
         let marshal_ms = t_marshal.elapsed().as_secs_f64() * 1000.0;
         let total_ms = t_total.elapsed().as_secs_f64() * 1000.0;
 
@@ -156,12 +183,15 @@ pub fn worker_render_live(params: wasm_bindgen::JsValue) -> Result<JsValue, JsVa
             total_ms,
             marshal_ms,
             azimuth_spacing_deg,
+            nyquist_velocity: sweep.nyquist_velocity,
         };
-        let result = serde_wasm_bindgen::to_value(&response)
-            .map_err(|e| JsValue::from_str(&format!("Failed to serialize response: {}", e)))?;
-        js_sys::Reflect::set(&result, &"azimuths".into(), &az_buf).ok();
-        js_sys::Reflect::set(&result, &"gateValues".into(), &val_buf).ok();
-        js_sys::Reflect::set(&result, &"radialTimes".into(), &rt_buf).ok();
-        Ok(result)
+        Ok(Reply {
+            fields: response,
+            arrays: vec![
+                ("azimuths", Array::F32(sweep.azimuths)),
+                ("gateValues", Array::F32(gate_values_f32)),
+                ("radialTimes", Array::F64(sweep.radial_times)),
+            ],
+        })
     })
 }
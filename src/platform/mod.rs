@@ -0,0 +1,85 @@
+//! Host services that differ between the browser and the desktop build.
+//!
+//! The rest of the crate calls these functions rather than reaching for
+//! `web_sys` or threads directly:
+//!
+//! | Service            | Browser (`web.rs`)           | Desktop (`native.rs`)                   |
+//! |--------------------|------------------------------|-----------------------------------------|
+//! | `spawn_local`      | `wasm_bindgen_futures`       | UI-thread executor polled every frame   |
+//! | `sleep_ms`         | `setTimeout`                 | tokio timer                             |
+//! | `http_get`         | `fetch`                      | reqwest on a shared tokio runtime       |
+//! | `storage_*`        | `localStorage`               | one file per key in the config dir      |
+//! | `init_logger`      | browser console              | stderr                                  |
+
+#[cfg(not(target_arch = "wasm32"))]
+mod native;
+#[cfg(target_arch = "wasm32")]
+mod web;
+
+#[cfg(not(target_arch = "wasm32"))]
+pub use native::*;
+#[cfg(target_arch = "wasm32")]
+pub use web::*;
+
+/// Current wall-clock time as Unix milliseconds.
+pub fn now_ms() -> f64 {
+    web_time::SystemTime::now()
+        .duration_since(web_time::UNIX_EPOCH)
+        .map(|d| d.as_secs_f64() * 1000.0)
+        .unwrap_or(0.0)
+}
+
+/// Current wall-clock time as Unix seconds with millisecond precision.
+pub fn now_secs() -> f64 {
+    now_ms() / 1000.0
+}
+
+/// A completed HTTP response. Non-2xx statuses are returned here rather
+/// than as errors so callers can handle 304 and 404 themselves.
+pub struct HttpResponse {
+    pub status: u16,
+    headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+impl HttpResponse {
+    /// Whether the status is 2xx.
+    pub fn ok(&self) -> bool {
+        (200..300).contains(&self.status)
+    }
+
+    /// Look up a response header by case-insensitive name.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+/// A Unix timestamp in the user's local time zone.
+pub fn local_datetime(unix_secs: f64) -> chrono::DateTime<chrono::Local> {
+    use chrono::TimeZone;
+    chrono::Local
+        .timestamp_millis_opt((unix_secs * 1000.0) as i64)
+        .earliest()
+        .unwrap_or_default()
+}
+
+/// Interpret a wall-clock date and time in the user's local time zone as
+/// Unix seconds. Returns `None` for invalid fields or a time skipped by a
+/// DST transition; ambiguous times resolve to the earlier instant.
+pub fn local_to_unix(
+    year: i32,
+    month: u32,
+    day: u32,
+    hour: u32,
+    minute: u32,
+    second: u32,
+) -> Option<f64> {
+    use chrono::TimeZone;
+    let naive =
+        chrono::NaiveDate::from_ymd_opt(year, month, day)?.and_hms_opt(hour, minute, second)?;
+    let local = chrono::Local.from_local_datetime(&naive).earliest()?;
+    Some(local.timestamp() as f64)
+}
@@ -0,0 +1,258 @@
+//! Desktop implementations of the platform services.
+//!
+//! UI code keeps the browser's single-threaded model: futures passed to
+//! [`spawn_local`] are `!Send`, hold `Rc` state and run on the UI thread.
+//! [`poll_tasks`] drives them once per frame, and their wakers request a
+//! repaint so a completed timer or download gets polled promptly even when
+//! nothing else is animating. A shared multi-threaded tokio runtime supplies
+//! the timer and socket reactors; it is entered while polling so reqwest and
+//! `tokio::time` work from those tasks and from the decode threads.
+
+use super::HttpResponse;
+use eframe::egui;
+use std::cell::RefCell;
+use std::future::Future;
+use std::path::PathBuf;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, OnceLock};
+use std::task::{Context, Poll, Wake, Waker};
+use std::time::Duration;
+
+/// Maximum rounds of re-polling woken tasks within one frame. Tasks that
+/// keep waking themselves beyond this are picked up next frame.
+const MAX_POLL_ROUNDS: usize = 16;
+
+/// Directory name used under the XDG config and cache roots.
+const APP_DIR: &str = "nexrad-workbench";
+
+static RUNTIME: OnceLock<tokio::runtime::Runtime> = OnceLock::new();
+static HTTP: OnceLock<reqwest::Client> = OnceLock::new();
+static REPAINT: OnceLock<egui::Context> = OnceLock::new();
+
+/// The shared tokio runtime for timers and HTTP.
+fn runtime() -> &'static tokio::runtime::Runtime {
+    RUNTIME.get_or_init(|| {
+        tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(2)
+            .thread_name("nexrad-io")
+            .enable_all()
+            .build()
+            .expect("failed to start tokio runtime")
+    })
+}
+
+fn request_repaint() {
+    if let Some(ctx) = REPAINT.get() {
+        ctx.request_repaint();
+    }
+}
+
+struct TaskWaker {
+    woken: AtomicBool,
+}
+
+impl Wake for TaskWaker {
+    fn wake(self: Arc<Self>) {
+        self.wake_by_ref();
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        self.woken.store(true, Ordering::Release);
+        request_repaint();
+    }
+}
+
+struct Task {
+    future: Pin<Box<dyn Future<Output = ()>>>,
+    waker: Arc<TaskWaker>,
+}
+
+// Only the UI thread spawns and polls tasks, so thread_local! storage is
+// enough and the futures never need to be Send.
+thread_local! {
+    static TASKS: RefCell<Vec<Task>> = const { RefCell::new(Vec::new()) };
+    static SPAWNED: RefCell<Vec<Task>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Queue a future to run on the UI thread. It is first polled on the next
+/// call to [`poll_tasks`].
+pub fn spawn_local<F>(future: F)
+where
+    F: Future<Output = ()> + 'static,
+{
+    let task = Task {
+        future: Box::pin(future),
+        waker: Arc::new(TaskWaker {
+            woken: AtomicBool::new(true),
+        }),
+    };
+    SPAWNED.with(|s| s.borrow_mut().push(task));
+    request_repaint();
+}
+
+/// Poll every woken task. Called at the top of each frame.
+pub fn poll_tasks(ctx: &egui::Context) {
+    REPAINT.get_or_init(|| ctx.clone());
+    let _guard = runtime().enter();
+
+    let mut tasks = TASKS.with(|t| std::mem::take(&mut *t.borrow_mut()));
+    for _ in 0..MAX_POLL_ROUNDS {
+        tasks.extend(SPAWNED.with(|s| std::mem::take(&mut *s.borrow_mut())));
+        let mut polled = false;
+        tasks.retain_mut(|task| {
+            if !task.waker.woken.swap(false, Ordering::AcqRel) {
+                return true;
+            }
+            polled = true;
+            let waker = Waker::from(task.waker.clone());
+            let mut cx = Context::from_waker(&waker);
+            task.future.as_mut().poll(&mut cx) == Poll::Pending
+        });
+        if !polled && SPAWNED.with(|s| s.borrow().is_empty()) {
+            break;
+        }
+    }
+    if tasks.iter().any(|t| t.waker.woken.load(Ordering::Acquire)) {
+        ctx.request_repaint();
+    }
+    TASKS.with(|t| *t.borrow_mut() = tasks);
+}
+
+/// Run a future to completion on the calling thread with the shared runtime
+/// entered. Used by the decode threads.
+pub fn block_on<F: Future>(future: F) -> F::Output {
+    runtime().block_on(future)
+}
+
+/// Resolve after `ms` milliseconds.
+pub async fn sleep_ms(ms: u32) {
+    tokio::time::sleep(Duration::from_millis(ms as u64)).await;
+}
+
+/// GET `url` with the given request headers.
+pub async fn http_get(url: &str, headers: &[(&str, &str)]) -> Result<HttpResponse, String> {
+    let client = HTTP.get_or_init(reqwest::Client::new);
+    let mut request = client.get(url);
+    for (name, value) in headers {
+        request = request.header(*name, *value);
+    }
+    let resp = request
+        .send()
+        .await
+        .map_err(|e| format!("network error: {}", e))?;
+
+    let status = resp.status().as_u16();
+    let response_headers = resp
+        .headers()
+        .iter()
+        .filter_map(|(k, v)| Some((k.to_string(), v.to_str().ok()?.to_string())))
+        .collect();
+    let body = resp
+        .bytes()
+        .await
+        .map_err(|_| "failed to read body".to_string())?;
+
+    Ok(HttpResponse {
+        status,
+        headers: response_headers,
+        body: body.to_vec(),
+    })
+}
+
+/// `$XDG_<var>` if set, else `$HOME/<fallback>`, joined with the app name.
+fn xdg_dir(var: &str, fallback: &str) -> PathBuf {
+    let base = std::env::var_os(var)
+        .filter(|v| !v.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|h| PathBuf::from(h).join(fallback)))
+        .unwrap_or_else(std::env::temp_dir);
+    base.join(APP_DIR)
+}
+
+/// Where settings are persisted (`~/.config/nexrad-workbench`).
+pub fn config_dir() -> PathBuf {
+    xdg_dir("XDG_CONFIG_HOME", ".config")
+}
+
+/// Where decoded sweeps are cached (`~/.cache/nexrad-workbench`).
+pub fn cache_dir() -> PathBuf {
+    xdg_dir("XDG_CACHE_HOME", ".cache")
+}
+
+/// Where exports are saved: `$XDG_DOWNLOAD_DIR`, else `~/Downloads`.
+pub fn download_dir() -> PathBuf {
+    std::env::var_os("XDG_DOWNLOAD_DIR")
+        .filter(|v| !v.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|h| PathBuf::from(h).join("Downloads")))
+        .unwrap_or_else(|| PathBuf::from("."))
+}
+
+/// Storage keys are fixed identifiers plus site codes, but keep them from
+/// escaping the config directory regardless.
+fn storage_path(key: &str) -> PathBuf {
+    let name: String = key
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '_' || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    config_dir().join(name)
+}
+
+/// Read a persisted value.
+pub fn storage_get(key: &str) -> Option<String> {
+    std::fs::read_to_string(storage_path(key)).ok()
+}
+
+/// Persist a value.
+pub fn storage_set(key: &str, value: &str) -> Result<(), String> {
+    std::fs::create_dir_all(config_dir()).map_err(|e| e.to_string())?;
+    std::fs::write(storage_path(key), value).map_err(|e| e.to_string())
+}
+
+/// Remove every persisted value.
+pub fn storage_clear() {
+    let _ = std::fs::remove_dir_all(config_dir());
+}
+
+/// Logical cores available to the process, or 0 if unknown.
+pub fn hardware_concurrency() -> usize {
+    std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(0)
+}
+
+struct StderrLogger;
+
+impl log::Log for StderrLogger {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        metadata.level() <= log::max_level()
+    }
+
+    fn log(&self, record: &log::Record) {
+        if self.enabled(record.metadata()) {
+            eprintln!("[{} {}] {}", record.level(), record.target(), record.args());
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+/// Route `log` output to stderr. `RUST_LOG` may name a level
+/// (`error` … `trace`); the default is `info`.
+pub fn init_logger() {
+    static LOGGER: StderrLogger = StderrLogger;
+    let level = std::env::var("RUST_LOG")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(log::LevelFilter::Info);
+    if log::set_logger(&LOGGER).is_ok() {
+        log::set_max_level(level);
+    }
+}
@@ -0,0 +1,129 @@
+//! Browser implementations of the platform services.
+
+use super::HttpResponse;
+use std::future::Future;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::JsFuture;
+
+/// Run a future on the browser's microtask queue.
+pub fn spawn_local<F>(future: F)
+where
+    F: Future<Output = ()> + 'static,
+{
+    wasm_bindgen_futures::spawn_local(future);
+}
+
+/// Drive spawned tasks. The browser's event loop already does this, so
+/// the web build has nothing to do here.
+pub fn poll_tasks(_ctx: &eframe::egui::Context) {}
+
+/// Resolve after `ms` milliseconds via `setTimeout`.
+pub async fn sleep_ms(ms: u32) {
+    #[wasm_bindgen]
+    extern "C" {
+        #[wasm_bindgen(js_name = setTimeout)]
+        fn set_timeout(closure: &Closure<dyn FnMut()>, millis: u32) -> i32;
+    }
+
+    let (tx, rx) = futures_channel::oneshot::channel::<()>();
+    let closure = Closure::once(move || {
+        let _ = tx.send(());
+    });
+    set_timeout(&closure, ms);
+    let _ = rx.await;
+}
+
+/// GET `url` through the Fetch API with the given request headers.
+pub async fn http_get(url: &str, headers: &[(&str, &str)]) -> Result<HttpResponse, String> {
+    let window = web_sys::window().ok_or_else(|| "no window".to_string())?;
+
+    let init = web_sys::RequestInit::new();
+    init.set_method("GET");
+    init.set_mode(web_sys::RequestMode::Cors);
+    if !headers.is_empty() {
+        let request_headers =
+            web_sys::Headers::new().map_err(|_| "failed to allocate headers".to_string())?;
+        for (name, value) in headers {
+            let _ = request_headers.set(name, value);
+        }
+        init.set_headers(&JsValue::from(request_headers));
+    }
+    let request = web_sys::Request::new_with_str_and_init(url, &init)
+        .map_err(|e| format!("request init failed: {}", err_text(e)))?;
+
+    let resp: web_sys::Response = JsFuture::from(window.fetch_with_request(&request))
+        .await
+        .map_err(|e| format!("network error: {}", err_text(e)))?
+        .dyn_into()
+        .map_err(|_| "invalid response".to_string())?;
+
+    let mut response_headers = Vec::new();
+    if let Ok(Some(iter)) = js_sys::try_iter(&resp.headers()) {
+        for entry in iter.flatten() {
+            let pair = js_sys::Array::from(&entry);
+            if let (Some(name), Some(value)) = (pair.get(0).as_string(), pair.get(1).as_string()) {
+                response_headers.push((name, value));
+            }
+        }
+    }
+
+    let buffer = resp
+        .array_buffer()
+        .map_err(|_| "failed to read body".to_string())?;
+    let buffer = JsFuture::from(buffer)
+        .await
+        .map_err(|_| "failed to read body".to_string())?;
+
+    Ok(HttpResponse {
+        status: resp.status(),
+        headers: response_headers,
+        body: js_sys::Uint8Array::new(&buffer).to_vec(),
+    })
+}
+
+fn err_text(v: JsValue) -> String {
+    v.as_string()
+        .or_else(|| {
+            js_sys::Reflect::get(&v, &JsValue::from_str("message"))
+                .ok()
+                .and_then(|m| m.as_string())
+        })
+        .unwrap_or_else(|| format!("{:?}", v))
+}
+
+fn local_storage() -> Option<web_sys::Storage> {
+    web_sys::window()?.local_storage().ok()?
+}
+
+/// Read a value from localStorage.
+pub fn storage_get(key: &str) -> Option<String> {
+    local_storage()?.get_item(key).ok()?
+}
+
+/// Write a value to localStorage.
+pub fn storage_set(key: &str, value: &str) -> Result<(), String> {
+    let storage = local_storage().ok_or("localStorage unavailable")?;
+    storage
+        .set_item(key, value)
+        .map_err(|e| format!("localStorage write failed: {}", err_text(e)))
+}
+
+/// Clear every value in localStorage.
+pub fn storage_clear() {
+    if let Some(storage) = local_storage() {
+        let _ = storage.clear();
+    }
+}
+
+/// Logical cores reported by `navigator.hardwareConcurrency`, or 0 if unknown.
+pub fn hardware_concurrency() -> usize {
+    web_sys::window()
+        .map(|w| w.navigator().hardware_concurrency() as usize)
+        .unwrap_or(0)
+}
+
+/// Route `log` output to the browser console.
+pub fn init_logger() {
+    eframe::WebLogger::init(log::LevelFilter::Debug).ok();
+}
@@ -178,7 +178,7 @@ impl AcquisitionState {
             id,
             kind,
             status: OperationStatus::Queued,
-            created_at_ms: js_sys::Date::now(),
+            created_at_ms: crate::platform::now_ms(),
             started_at_ms: None,
             completed_at_ms: None,
             network_request_ids: Vec::new(),
@@ -202,7 +202,7 @@ impl AcquisitionState {
     pub fn mark_active(&mut self, id: OperationId) {
         if let Some(op) = self.find_mut(id) {
             op.status = OperationStatus::Active;
-            op.started_at_ms = Some(js_sys::Date::now());
+            op.started_at_ms = Some(crate::platform::now_ms());
             op.phase = DownloadPhase::Downloading;
         }
     }
@@ -216,7 +216,7 @@ impl AcquisitionState {
 
     /// Mark an operation as completed.
     pub fn mark_completed(&mut self, id: OperationId, bytes: u64) {
-        let now = js_sys::Date::now();
+        let now = crate::platform::now_ms();
         if let Some(op) = self.find_mut(id) {
             let duration_ms = op.started_at_ms.map(|s| now - s).unwrap_or(0.0);
             op.status = OperationStatus::Completed { duration_ms, bytes };
@@ -230,7 +230,7 @@ impl AcquisitionState {
     pub fn mark_failed(&mut self, id: OperationId, error: String) {
         if let Some(op) = self.find_mut(id) {
             op.status = OperationStatus::Failed { error };
-            op.completed_at_ms = Some(js_sys::Date::now());
+            op.completed_at_ms = Some(crate::platform::now_ms());
             op.phase = DownloadPhase::Done;
         }
         self.queue_state = QueueState::ErrorPaused;
@@ -238,18 +238,29 @@ impl AcquisitionState {
         self.drawer_expanded = true;
     }
 
+    /// Mark an operation as failed without pausing the queue, for fetches
+    /// that run beside it (pinned sites).
+    pub fn mark_failed_unpaused(&mut self, id: OperationId, error: String) {
+        if let Some(op) = self.find_mut(id) {
+            op.status = OperationStatus::Failed { error };
+            op.completed_at_ms = Some(crate::platform::now_ms());
+            op.phase = DownloadPhase::Done;
+        }
+        self.update_queue_state();
+    }
+
     /// Cancel a specific operation.
     pub fn cancel_operation(&mut self, id: OperationId) {
         if let Some(op) = self.find_mut(id) {
             op.status = OperationStatus::Cancelled;
-            op.completed_at_ms = Some(js_sys::Date::now());
+            op.completed_at_ms = Some(crate::platform::now_ms());
         }
         self.update_queue_state();
     }
 
     /// Cancel all queued operations (e.g., on selection change).
     pub fn cancel_all_queued(&mut self) {
-        let now = js_sys::Date::now();
+        let now = crate::platform::now_ms();
         for op in self.operations.iter_mut() {
             if op.status == OperationStatus::Queued {
                 op.status = OperationStatus::Cancelled;
@@ -261,7 +272,7 @@ impl AcquisitionState {
 
     /// Cancel all pending and active operations (selection change: cancel all + rebuild).
     pub fn cancel_all(&mut self) {
-        let now = js_sys::Date::now();
+        let now = crate::platform::now_ms();
         for op in self.operations.iter_mut() {
             match &op.status {
                 OperationStatus::Queued | OperationStatus::Active => {
@@ -407,7 +418,7 @@ impl AcquisitionState {
         first_radial_secs: Option<f64>,
         last_radial_secs: Option<f64>,
     ) {
-        let now_ms = js_sys::Date::now();
+        let now_ms = crate::platform::now_ms();
         let metrics = ChunkLatencyMetrics {
             chunk_index,
             first_radial_time_secs: first_radial_secs,
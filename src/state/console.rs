@@ -0,0 +1,554 @@
+//! Command console: a small text language over the app's controls.
+//!
+//! Scripts are one command per line (or separated by `;`), with `#`
+//! starting a comment. Times are UTC. The commands:
+//!
+//! | Command | Effect |
+//! |---|---|
+//! | `site KTLX` | Switch to a radar site |
+//! | `goto 2013-05-20T20:00Z` | Seek the playback position |
+//! | `product VEL` | Pick a product by its short code |
+//! | `elev 0.5` / `elev lowest` / `elev latest` | Pick the nearest tilt, or an automatic mode |
+//! | `range <start> <end>` / `range clear` | Set the timeline selection; `<end>` may be `+30m`, `+2h`, `+90s` |
+//! | `download range` / `download here` | Fetch the selection, or the scan at the playback position |
+//! | `export png` | Export using the export panel's settings (`png`, `gif`, `nc`, `tif`, `csv`) |
+//! | `play` / `pause` | Start or stop playback |
+//! | `wait` / `wait 5` | Hold the script until downloads, decoding and exports go idle, or for N seconds |
+//! | `help` | List the commands |
+//!
+//! Times are `YYYY-MM-DDTHH:MM[:SS]` with an optional trailing `Z`, a date
+//! alone (midnight), an RFC 3339 stamp with offset, or Unix seconds.
+//!
+//! Commands run one per frame from [`ConsoleState::queue`], so everything a
+//! command pushes onto the app queue is dispatched before the next one runs.
+//! `site` holds the script until the new site's timeline has loaded.
+//!
+//! The same language is reachable from JavaScript: `workbench_run(script)`
+//! parses and queues a script, and `workbench_status()` reports how much of
+//! it is left and what failed. With Trunk these are on `window.wasmBindings`.
+
+use super::{ExportFormat, RadarProduct};
+use chrono::{DateTime, NaiveDate, NaiveDateTime};
+use std::cell::RefCell;
+use std::collections::VecDeque;
+
+/// Lines kept in the console's scrollback.
+const MAX_LOG_LINES: usize = 500;
+
+/// Submitted lines kept for Up/Down recall.
+const MAX_HISTORY: usize = 100;
+
+/// How long the pipeline must stay idle before `wait` resumes, covering the
+/// frame gap between queuing work and it showing up as active.
+pub const IDLE_SETTLE_MS: f64 = 750.0;
+
+/// Longest an idle `wait` holds the script before giving up.
+pub const IDLE_WAIT_TIMEOUT_MS: f64 = 5.0 * 60.0 * 1000.0;
+
+/// Command names and one-line usage, shown by `help`.
+pub const HELP: &[(&str, &str)] = &[
+    ("site <ID>", "Switch radar site (e.g. site KTLX)"),
+    (
+        "goto <time>",
+        "Seek to a UTC time (e.g. goto 2013-05-20T20:00Z)",
+    ),
+    (
+        "product <code>",
+        "Pick a product (REF, VEL, SW, ZDR, CC, ...)",
+    ),
+    (
+        "elev <deg|lowest|latest>",
+        "Pick the nearest tilt or an auto mode",
+    ),
+    ("range <start> <end|+dur>", "Set the timeline selection"),
+    ("range clear", "Clear the timeline selection"),
+    (
+        "download range|here",
+        "Fetch the selection or the current scan",
+    ),
+    ("export <format>", "Export png, gif, nc, tif or csv"),
+    ("play / pause", "Start or stop playback"),
+    ("wait [secs]", "Wait until idle, or for a number of seconds"),
+    ("help", "List commands"),
+];
+
+/// What `elev` selects.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ElevationTarget {
+    /// The tilt whose angle is nearest this many degrees.
+    Angle(f32),
+    /// Follow the most recent sweep.
+    Latest,
+    /// Lowest tilt with data at each gate.
+    Lowest,
+}
+
+/// What `download` fetches.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DownloadTarget {
+    /// Every scan in the timeline selection.
+    Range,
+    /// The scan at the playback position.
+    Here,
+}
+
+/// One parsed console command.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ConsoleCommand {
+    Site(String),
+    /// Unix seconds.
+    Goto(f64),
+    Product(RadarProduct),
+    Elevation(ElevationTarget),
+    /// Selection as Unix seconds, `None` to clear it.
+    Range(Option<(f64, f64)>),
+    Download(DownloadTarget),
+    Export(ExportFormat),
+    Play,
+    Pause,
+    /// Seconds to wait, `None` to wait for the pipeline to go idle.
+    Wait(Option<f64>),
+    Help,
+}
+
+/// A command with the source text it was parsed from, for the log.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ScriptLine {
+    pub text: String,
+    pub command: ConsoleCommand,
+}
+
+/// Parse a single command.
+pub fn parse_command(line: &str) -> Result<ConsoleCommand, String> {
+    let mut words = line.split_whitespace();
+    let Some(name) = words.next() else {
+        return Err("empty command".to_string());
+    };
+    let args: Vec<&str> = words.collect();
+    let name = name.to_ascii_lowercase();
+
+    let one_arg = |usage: &str| -> Result<&str, String> {
+        match args.as_slice() {
+            [arg] => Ok(*arg),
+            _ => Err(format!("usage: {usage}")),
+        }
+    };
+
+    match name.as_str() {
+        "site" => {
+            let id = one_arg("site <ID>")?.to_ascii_uppercase();
+            if crate::data::get_site(&id).is_none() {
+                return Err(format!("unknown site '{id}'"));
+            }
+            Ok(ConsoleCommand::Site(id))
+        }
+        "goto" => {
+            if args.is_empty() {
+                return Err("usage: goto <time>".to_string());
+            }
+            parse_time(&args.join(" ")).map(ConsoleCommand::Goto)
+        }
+        "product" => {
+            let code = one_arg("product <code>")?.to_ascii_uppercase();
+            RadarProduct::from_short_code(&code)
+                .map(ConsoleCommand::Product)
+                .ok_or_else(|| format!("unknown product '{code}'"))
+        }
+        "elev" | "elevation" => {
+            let arg = one_arg("elev <degrees|lowest|latest>")?;
+            let target = match arg.to_ascii_lowercase().as_str() {
+                "lowest" => ElevationTarget::Lowest,
+                "latest" | "auto" => ElevationTarget::Latest,
+                deg => ElevationTarget::Angle(
+                    deg.trim_end_matches('°')
+                        .parse()
+                        .map_err(|_| format!("bad elevation '{arg}'"))?,
+                ),
+            };
+            Ok(ConsoleCommand::Elevation(target))
+        }
+        "range" => match args.as_slice() {
+            [arg] if arg.eq_ignore_ascii_case("clear") => Ok(ConsoleCommand::Range(None)),
+            [start, end] => {
+                let start = parse_time(start)?;
+                let end = match end.strip_prefix('+') {
+                    Some(duration) => start + parse_duration(duration)?,
+                    None => parse_time(end)?,
+                };
+                if end <= start {
+                    return Err("range end must be after its start".to_string());
+                }
+                Ok(ConsoleCommand::Range(Some((start, end))))
+            }
+            _ => Err("usage: range <start> <end|+duration> | range clear".to_string()),
+        },
+        "download" => match one_arg("download range|here")?
+            .to_ascii_lowercase()
+            .as_str()
+        {
+            "range" | "selection" => Ok(ConsoleCommand::Download(DownloadTarget::Range)),
+            "here" | "scan" => Ok(ConsoleCommand::Download(DownloadTarget::Here)),
+            other => Err(format!("unknown download target '{other}'")),
+        },
+        "export" => {
+            let arg = one_arg("export <format>")?.to_ascii_lowercase();
+            let format = match arg.as_str() {
+                "cfradial" | "netcdf" => Some(ExportFormat::CfRadial),
+                "geotiff" | "tiff" => Some(ExportFormat::GeoTiff),
+                ext => ExportFormat::all()
+                    .iter()
+                    .copied()
+                    .find(|f| f.extension() == ext),
+            };
+            format
+                .map(ConsoleCommand::Export)
+                .ok_or_else(|| format!("unknown export format '{arg}'"))
+        }
+        "play" if args.is_empty() => Ok(ConsoleCommand::Play),
+        "pause" | "stop" if args.is_empty() => Ok(ConsoleCommand::Pause),
+        "wait" => match args.as_slice() {
+            [] => Ok(ConsoleCommand::Wait(None)),
+            [secs] => match secs.trim_end_matches('s').parse::<f64>() {
+                Ok(secs) if secs >= 0.0 => Ok(ConsoleCommand::Wait(Some(secs))),
+                _ => Err(format!("bad wait '{secs}'")),
+            },
+            _ => Err("usage: wait [seconds]".to_string()),
+        },
+        "help" | "?" => Ok(ConsoleCommand::Help),
+        "play" | "pause" | "stop" => Err(format!("'{name}' takes no arguments")),
+        _ => Err(format!("unknown command '{name}' (try 'help')")),
+    }
+}
+
+/// Parse a whole script. Fails on the first bad line, naming it, so nothing
+/// runs from a script with a typo in it.
+pub fn parse_script(script: &str) -> Result<Vec<ScriptLine>, String> {
+    let mut lines = Vec::new();
+    for (number, line) in script.lines().enumerate() {
+        let code = line.split('#').next().unwrap_or_default();
+        for text in code.split(';').map(str::trim).filter(|t| !t.is_empty()) {
+            let command = parse_command(text).map_err(|e| format!("line {}: {e}", number + 1))?;
+            lines.push(ScriptLine {
+                text: text.to_string(),
+                command,
+            });
+        }
+    }
+    Ok(lines)
+}
+
+/// Parse a UTC time into Unix seconds.
+pub(crate) fn parse_time(text: &str) -> Result<f64, String> {
+    let text = text.trim();
+    if let Ok(secs) = text.parse::<i64>() {
+        return Ok(secs as f64);
+    }
+    if let Ok(dt) = DateTime::parse_from_rfc3339(text) {
+        return Ok(dt.timestamp() as f64);
+    }
+    let naive = text.trim_end_matches(['Z', 'z']);
+    const FORMATS: &[&str] = &[
+        "%Y-%m-%dT%H:%M:%S",
+        "%Y-%m-%dT%H:%M",
+        "%Y-%m-%d %H:%M:%S",
+        "%Y-%m-%d %H:%M",
+    ];
+    if let Some(dt) = FORMATS
+        .iter()
+        .find_map(|f| NaiveDateTime::parse_from_str(naive, f).ok())
+    {
+        return Ok(dt.and_utc().timestamp() as f64);
+    }
+    if let Ok(date) = NaiveDate::parse_from_str(naive, "%Y-%m-%d") {
+        return Ok(date.and_time(Default::default()).and_utc().timestamp() as f64);
+    }
+    Err(format!(
+        "bad time '{text}' (expected e.g. 2013-05-20T20:00Z)"
+    ))
+}
+
+/// Parse `90s`, `30m` or `2h` into seconds.
+fn parse_duration(text: &str) -> Result<f64, String> {
+    let unit = text.chars().last().unwrap_or_default();
+    let number = &text[..text.len() - unit.len_utf8()];
+    let scale = match unit {
+        's' => 1.0,
+        'm' => 60.0,
+        'h' => 3600.0,
+        _ => return Err(format!("bad duration '{text}' (expected e.g. 30m)")),
+    };
+    match number.parse::<f64>() {
+        Ok(n) if n > 0.0 => Ok(n * scale),
+        _ => Err(format!("bad duration '{text}'")),
+    }
+}
+
+/// A line in the console's scrollback.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ConsoleLine {
+    /// A command as it started running.
+    Input(String),
+    Output(String),
+    Error(String),
+}
+
+/// What a running `wait` is holding out for.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ConsoleWait {
+    /// Wall-clock milliseconds to resume at.
+    Until(f64),
+    /// Pipeline idle, as of when the wait started and when idle was first
+    /// seen (milliseconds).
+    Idle {
+        started_ms: f64,
+        idle_since_ms: Option<f64>,
+    },
+}
+
+#[derive(Default)]
+pub struct ConsoleState {
+    /// Whether the console window is open.
+    pub open: bool,
+    /// Text in the input line.
+    pub input: String,
+    /// Scrollback, oldest first. Bounded by `MAX_LOG_LINES`.
+    pub log: VecDeque<ConsoleLine>,
+    /// Commands waiting to run.
+    pub queue: VecDeque<ScriptLine>,
+    /// Set while a `wait` (or a site change) holds the queue.
+    pub wait: Option<ConsoleWait>,
+    /// Set by `export` until the job's result has been logged.
+    pub watching_export: bool,
+    /// Submitted lines, oldest first.
+    history: Vec<String>,
+    /// Position while recalling history; `None` when editing a new line.
+    history_cursor: Option<usize>,
+}
+
+impl ConsoleState {
+    /// Parse the input line and queue it, recording it in history.
+    pub fn submit_input(&mut self) {
+        let text = std::mem::take(&mut self.input);
+        if text.trim().is_empty() {
+            return;
+        }
+        if self.history.last() != Some(&text) {
+            self.history.push(text.clone());
+            if self.history.len() > MAX_HISTORY {
+                self.history.remove(0);
+            }
+        }
+        self.history_cursor = None;
+        if let Err(e) = self.enqueue(&text) {
+            self.push_log(ConsoleLine::Input(text));
+            self.push_log(ConsoleLine::Error(e));
+        }
+    }
+
+    /// Parse and queue a script, returning how many commands it added.
+    pub fn enqueue(&mut self, script: &str) -> Result<usize, String> {
+        let lines = parse_script(script)?;
+        let count = lines.len();
+        self.queue.extend(lines);
+        Ok(count)
+    }
+
+    /// Commands still to run, counting a running wait as one.
+    pub fn pending(&self) -> usize {
+        self.queue.len() + usize::from(self.wait.is_some())
+    }
+
+    /// Drop the queue and any running wait.
+    pub fn cancel(&mut self) {
+        let dropped = self.pending();
+        self.queue.clear();
+        self.wait = None;
+        if dropped > 0 {
+            self.push_log(ConsoleLine::Output(format!(
+                "cancelled {dropped} command(s)"
+            )));
+        }
+    }
+
+    pub fn push_log(&mut self, line: ConsoleLine) {
+        if self.log.len() == MAX_LOG_LINES {
+            self.log.pop_front();
+        }
+        self.log.push_back(line);
+    }
+
+    /// Step through history; `older` moves back in time.
+    pub fn recall_history(&mut self, older: bool) {
+        if self.history.is_empty() {
+            return;
+        }
+        let last = self.history.len() - 1;
+        self.history_cursor = match (self.history_cursor, older) {
+            (None, true) => Some(last),
+            (None, false) => None,
+            (Some(i), true) => Some(i.saturating_sub(1)),
+            (Some(i), false) if i < last => Some(i + 1),
+            (Some(_), false) => None,
+        };
+        self.input = self
+            .history_cursor
+            .map(|i| self.history[i].clone())
+            .unwrap_or_default();
+    }
+}
+
+// ── JavaScript bridge ───────────────────────────────────────────────
+
+// WASM is single-threaded so thread_local! is safe. Scripts from JS land
+// here and are moved into `ConsoleState` by the update loop, which also
+// publishes what's left back for `workbench_status`.
+
+#[derive(Default)]
+struct ScriptBridge {
+    /// Parsed scripts not yet picked up by the app.
+    inbox: Vec<ScriptLine>,
+    /// Wakes the app when a script arrives.
+    repaint: Option<eframe::egui::Context>,
+    /// Commands left, as of the last frame.
+    pending: usize,
+    /// Errors raised since the last `workbench_run`.
+    errors: Vec<String>,
+}
+
+thread_local! {
+    static SCRIPT_BRIDGE: RefCell<ScriptBridge> = RefCell::default();
+}
+
+/// Hand scripts queued from JS to the console and remember the context to
+/// wake when more arrive. Call once per frame.
+pub fn take_bridge_scripts(ctx: &eframe::egui::Context) -> Vec<ScriptLine> {
+    SCRIPT_BRIDGE.with(|bridge| {
+        let mut bridge = bridge.borrow_mut();
+        if bridge.repaint.is_none() {
+            bridge.repaint = Some(ctx.clone());
+        }
+        std::mem::take(&mut bridge.inbox)
+    })
+}
+
+/// Publish the console's progress for `workbench_status`.
+pub fn publish_bridge_status(pending: usize, errors: Vec<String>) {
+    SCRIPT_BRIDGE.with(|bridge| {
+        let mut bridge = bridge.borrow_mut();
+        bridge.pending = pending;
+        bridge.errors.extend(errors);
+    });
+}
+
+/// Queue a console script from JavaScript. Throws on a parse error (nothing
+/// is queued); otherwise returns the number of commands queued.
+#[cfg(target_arch = "wasm32")]
+#[wasm_bindgen::prelude::wasm_bindgen]
+pub fn workbench_run(script: &str) -> Result<u32, wasm_bindgen::JsValue> {
+    let lines = parse_script(script).map_err(|e| js_sys::Error::new(&e))?;
+    let count = lines.len() as u32;
+    SCRIPT_BRIDGE.with(|bridge| {
+        let mut bridge = bridge.borrow_mut();
+        bridge.errors.clear();
+        bridge.inbox.extend(lines);
+        if let Some(ctx) = &bridge.repaint {
+            ctx.request_repaint();
+        }
+    });
+    Ok(count)
+}
+
+#[cfg(target_arch = "wasm32")]
+#[derive(serde::Serialize)]
+struct BridgeStatus {
+    pending: usize,
+    errors: Vec<String>,
+}
+
+/// `{ pending, errors }`: commands not yet finished (0 once a script has
+/// run to the end) and the errors raised since the last `workbench_run`.
+#[cfg(target_arch = "wasm32")]
+#[wasm_bindgen::prelude::wasm_bindgen]
+pub fn workbench_status() -> wasm_bindgen::JsValue {
+    SCRIPT_BRIDGE.with(|bridge| {
+        let bridge = bridge.borrow();
+        let status = BridgeStatus {
+            pending: bridge.pending + bridge.inbox.len(),
+            errors: bridge.errors.clone(),
+        };
+        serde_wasm_bindgen::to_value(&status).unwrap_or(wasm_bindgen::JsValue::NULL)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_case_review_script() {
+        let script = "site ktlx  # Moore\n\
+                      goto 2013-05-20T20:00Z; product VEL\n\
+                      elev 0.5\n\
+                      range 2013-05-20T19:30Z +1h\n\
+                      download range; wait\n\
+                      export png";
+        let commands: Vec<_> = parse_script(script)
+            .unwrap()
+            .into_iter()
+            .map(|l| l.command)
+            .collect();
+        assert_eq!(
+            commands,
+            vec![
+                ConsoleCommand::Site("KTLX".to_string()),
+                ConsoleCommand::Goto(1_369_080_000.0),
+                ConsoleCommand::Product(RadarProduct::Velocity),
+                ConsoleCommand::Elevation(ElevationTarget::Angle(0.5)),
+                ConsoleCommand::Range(Some((1_369_078_200.0, 1_369_081_800.0))),
+                ConsoleCommand::Download(DownloadTarget::Range),
+                ConsoleCommand::Wait(None),
+                ConsoleCommand::Export(ExportFormat::Png),
+            ]
+        );
+    }
+
+    #[test]
+    fn accepts_time_variants() {
+        let expected = Ok(1_369_080_000.0);
+        assert_eq!(parse_time("2013-05-20T20:00Z"), expected);
+        assert_eq!(parse_time("2013-05-20T20:00:00"), expected);
+        assert_eq!(parse_time("2013-05-20 20:00"), expected);
+        assert_eq!(parse_time("2013-05-20T15:00:00-05:00"), expected);
+        assert_eq!(parse_time("1369080000"), expected);
+        assert_eq!(parse_time("2013-05-20"), Ok(1_369_008_000.0));
+        assert!(parse_time("May 20").is_err());
+    }
+
+    #[test]
+    fn script_errors_name_the_line() {
+        let err = parse_script("site KTLX\nproduct XYZ").unwrap_err();
+        assert!(err.starts_with("line 2:"), "{err}");
+        assert!(parse_script("site KZZZ").is_err());
+        assert!(parse_script("range 2013-05-20T20:00Z 2013-05-20T19:00Z").is_err());
+        assert!(parse_script("download everything").is_err());
+        assert_eq!(parse_script("# nothing\n\n ; ").unwrap(), vec![]);
+    }
+
+    #[test]
+    fn history_recall_walks_back_and_forward() {
+        let mut console = ConsoleState::default();
+        for line in ["play", "pause"] {
+            console.input = line.to_string();
+            console.submit_input();
+        }
+        console.recall_history(true);
+        assert_eq!(console.input, "pause");
+        console.recall_history(true);
+        assert_eq!(console.input, "play");
+        console.recall_history(false);
+        assert_eq!(console.input, "pause");
+        console.recall_history(false);
+        assert_eq!(console.input, "");
+        assert_eq!(console.pending(), 2);
+    }
+}
@@ -0,0 +1,85 @@
+//! Vertical cross-section tool state.
+//!
+//! The line is placed with two clicks on the 2D map (like the distance
+//! tool); the section itself is computed in the worker from every cached
+//! tilt of the current scan and shown in its own window.
+
+use crate::geo::KM_PER_DEG;
+use crate::nexrad::CrossSectionData;
+
+#[derive(Default)]
+pub struct CrossSectionState {
+    /// Whether map clicks place the section line.
+    pub tool_active: bool,
+    /// First endpoint (lat, lon), "A".
+    pub start: Option<(f64, f64)>,
+    /// Second endpoint (lat, lon), "B".
+    pub end: Option<(f64, f64)>,
+    /// Whether the cross-section window is open.
+    pub window_open: bool,
+    /// Overlay each tilt's beam-centre height.
+    pub show_beam_lines: bool,
+    /// Label heights in kft instead of km.
+    pub height_in_kft: bool,
+    /// Latest computed section.
+    pub result: Option<CrossSectionData>,
+    /// Bumped whenever `result` or the palettes change, so the window can
+    /// rebuild its image.
+    pub revision: u64,
+    /// True while a request is in flight.
+    pub pending: bool,
+    /// Error from the latest request (cleared on success).
+    pub error: Option<String>,
+}
+
+impl CrossSectionState {
+    /// Both endpoints as radar-relative (east, north) km, once the line is
+    /// complete.
+    pub fn line_km(&self, radar_lat: f64, radar_lon: f64) -> Option<((f64, f64), (f64, f64))> {
+        let to_km = |(lat, lon): (f64, f64)| {
+            (
+                (lon - radar_lon) * radar_lat.to_radians().cos() * KM_PER_DEG,
+                (lat - radar_lat) * KM_PER_DEG,
+            )
+        };
+        Some((to_km(self.start?), to_km(self.end?)))
+    }
+
+    /// Store a finished section.
+    pub fn set_result(&mut self, data: CrossSectionData) {
+        self.result = Some(data);
+        self.revision += 1;
+        self.pending = false;
+        self.error = None;
+    }
+
+    /// Drop the line and any section computed from it.
+    pub fn clear(&mut self) {
+        self.start = None;
+        self.end = None;
+        self.result = None;
+        self.revision += 1;
+        self.pending = false;
+        self.error = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn line_km_is_radar_relative() {
+        let mut state = CrossSectionState {
+            start: Some((35.0, -97.0)),
+            ..Default::default()
+        };
+        assert!(state.line_km(35.0, -97.0).is_none());
+
+        state.end = Some((36.0, -96.0));
+        let (start, end) = state.line_km(35.0, -97.0).unwrap();
+        assert_eq!(start, (0.0, 0.0));
+        assert!((end.1 - KM_PER_DEG).abs() < 1e-9);
+        assert!((end.0 - KM_PER_DEG * 35f64.to_radians().cos()).abs() < 1e-9);
+    }
+}
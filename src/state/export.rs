@@ -0,0 +1,164 @@
+//! Export dialog settings and progress.
+//!
+//! The capture loop itself lives in [`crate::export::ExportManager`]; this
+//! holds what the UI edits and what the manager reports back. Data formats
+//! are written by the app directly (see `crate::export::data`).
+
+use eframe::egui::Rect;
+
+/// Output file type.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ExportFormat {
+    /// Still image of the current view.
+    #[default]
+    Png,
+    /// Loop over every frame in the timeline selection.
+    Gif,
+    /// Every cached elevation and moment of the scan, polar, as NetCDF.
+    CfRadial,
+    /// The rendered product on a lat/lon grid, georeferenced.
+    GeoTiff,
+    /// The rendered product on a lat/lon grid, one row per cell.
+    Csv,
+}
+
+impl ExportFormat {
+    pub fn all() -> &'static [ExportFormat] {
+        &[
+            ExportFormat::Png,
+            ExportFormat::Gif,
+            ExportFormat::CfRadial,
+            ExportFormat::GeoTiff,
+            ExportFormat::Csv,
+        ]
+    }
+
+    /// Whether this format captures the map view rather than writing data.
+    pub fn is_image(&self) -> bool {
+        matches!(self, ExportFormat::Png | ExportFormat::Gif)
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ExportFormat::Png => "PNG still",
+            ExportFormat::Gif => "Animated GIF",
+            ExportFormat::CfRadial => "CF-Radial volume",
+            ExportFormat::GeoTiff => "GeoTIFF grid",
+            ExportFormat::Csv => "CSV grid",
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Png => "png",
+            ExportFormat::Gif => "gif",
+            ExportFormat::CfRadial => "nc",
+            ExportFormat::GeoTiff => "tif",
+            ExportFormat::Csv => "csv",
+        }
+    }
+
+    pub fn mime_type(&self) -> &'static str {
+        match self {
+            ExportFormat::Png => "image/png",
+            ExportFormat::Gif => "image/gif",
+            ExportFormat::CfRadial => "application/x-netcdf",
+            ExportFormat::GeoTiff => "image/tiff",
+            ExportFormat::Csv => "text/csv",
+        }
+    }
+}
+
+/// Output size. Fixed sizes letterbox the canvas to their aspect.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ExportResolution {
+    /// The canvas at its current physical pixel size.
+    Canvas,
+    #[default]
+    Hd720,
+    Hd1080,
+    Square800,
+}
+
+impl ExportResolution {
+    pub fn all() -> &'static [ExportResolution] {
+        &[
+            ExportResolution::Canvas,
+            ExportResolution::Hd720,
+            ExportResolution::Hd1080,
+            ExportResolution::Square800,
+        ]
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ExportResolution::Canvas => "Canvas size",
+            ExportResolution::Hd720 => "1280 \u{00D7} 720",
+            ExportResolution::Hd1080 => "1920 \u{00D7} 1080",
+            ExportResolution::Square800 => "800 \u{00D7} 800",
+        }
+    }
+
+    /// Output width and height, `None` to keep the captured size.
+    pub fn size(&self) -> Option<(usize, usize)> {
+        match self {
+            ExportResolution::Canvas => None,
+            ExportResolution::Hd720 => Some((1280, 720)),
+            ExportResolution::Hd1080 => Some((1920, 1080)),
+            ExportResolution::Square800 => Some((800, 800)),
+        }
+    }
+}
+
+/// Progress of a running export.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ExportProgress {
+    pub captured: usize,
+    pub total: usize,
+}
+
+pub struct ExportState {
+    pub format: ExportFormat,
+    pub resolution: ExportResolution,
+    /// Draw the frame's site, product and time into the bottom-right corner.
+    pub burn_timestamp: bool,
+    /// How long each GIF frame is shown.
+    pub frame_delay_ms: u32,
+    /// Set by the UI; the export manager starts a job and clears it.
+    pub start_requested: bool,
+    /// Set by the UI; the export manager abandons the job and clears it.
+    pub cancel_requested: bool,
+    /// Whole canvas area in points, recorded by the canvas every frame.
+    pub canvas_rect: Option<Rect>,
+    /// `Some` while a job is capturing frames.
+    pub progress: Option<ExportProgress>,
+    /// Set while a worker writes a CF-Radial file.
+    pub data_pending: bool,
+    /// Outcome of the last job, shown under the export controls.
+    pub last_result: Option<Result<String, String>>,
+}
+
+impl Default for ExportState {
+    fn default() -> Self {
+        Self {
+            format: ExportFormat::default(),
+            resolution: ExportResolution::default(),
+            burn_timestamp: true,
+            frame_delay_ms: 250,
+            start_requested: false,
+            cancel_requested: false,
+            canvas_rect: None,
+            progress: None,
+            data_pending: false,
+            last_result: None,
+        }
+    }
+}
+
+impl ExportState {
+    /// Whether frames are being captured, so the canvas should draw the
+    /// burn-in.
+    pub fn is_capturing(&self) -> bool {
+        self.progress.is_some()
+    }
+}
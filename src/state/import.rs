@@ -0,0 +1,107 @@
+//! Progress of a local file import.
+//!
+//! Files are read in the browser and handed to the decode workers (see
+//! `nexrad::local_import`); this counts the answers so the app can report
+//! the batch and move the timeline to what was imported once it is done.
+
+/// One batch of imported files. A new batch starts when files arrive while
+/// nothing is pending.
+#[derive(Default)]
+pub struct ImportState {
+    /// Archives sent to the workers and not yet answered.
+    pub pending: usize,
+    /// Archives ingested in this batch.
+    pub imported: usize,
+    /// File names that failed, with the worker's message.
+    pub failures: Vec<(String, String)>,
+    /// Site of the first scan imported in this batch; the view switches to
+    /// it when the batch finishes.
+    pub site_id: Option<String>,
+    /// End of the latest scan imported for `site_id`, Unix seconds.
+    pub latest_end: Option<f64>,
+}
+
+impl ImportState {
+    /// Count `count` more archives in flight.
+    pub fn start(&mut self, count: usize) {
+        if self.pending == 0 {
+            *self = Self::default();
+        }
+        self.pending += count;
+    }
+
+    /// Record an ingested scan of `site_id` ending at `end`.
+    pub fn record_success(&mut self, site_id: &str, end: f64) {
+        self.pending = self.pending.saturating_sub(1);
+        self.imported += 1;
+        if self.site_id.get_or_insert_with(|| site_id.to_string()) == site_id {
+            self.latest_end = Some(self.latest_end.map_or(end, |e| e.max(end)));
+        }
+    }
+
+    /// Record a file the workers could not ingest.
+    pub fn record_failure(&mut self, file_name: String, message: String) {
+        self.pending = self.pending.saturating_sub(1);
+        self.failures.push((file_name, message));
+    }
+
+    pub fn is_busy(&self) -> bool {
+        self.pending > 0
+    }
+
+    /// One-line status for the batch.
+    pub fn summary(&self) -> String {
+        if self.is_busy() {
+            return format!(
+                "Importing\u{2026} {} done, {} remaining",
+                self.imported + self.failures.len(),
+                self.pending
+            );
+        }
+        let mut text = format!(
+            "Imported {} scan{}",
+            self.imported,
+            if self.imported == 1 { "" } else { "s" }
+        );
+        if let Some((file_name, message)) = self.failures.first() {
+            text.push_str(&format!(
+                ", {} failed ({}: {})",
+                self.failures.len(),
+                file_name,
+                message
+            ));
+        }
+        text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tracks_the_latest_scan_of_the_first_site() {
+        let mut import = ImportState::default();
+        import.start(4);
+        import.record_success("KTLX", 100.0);
+        import.record_success("KFDR", 500.0);
+        import.record_success("KTLX", 300.0);
+        import.record_failure("x.gz".into(), "bad".into());
+        assert!(!import.is_busy());
+        assert_eq!(import.site_id.as_deref(), Some("KTLX"));
+        assert_eq!(import.latest_end, Some(300.0));
+        assert_eq!(import.summary(), "Imported 3 scans, 1 failed (x.gz: bad)");
+    }
+
+    #[test]
+    fn new_batch_resets_once_idle() {
+        let mut import = ImportState::default();
+        import.start(1);
+        import.record_success("KTLX", 100.0);
+        import.start(2);
+        assert_eq!((import.pending, import.imported), (2, 0));
+        assert_eq!(import.site_id, None);
+        import.start(1);
+        assert_eq!(import.pending, 3);
+    }
+}
@@ -0,0 +1,121 @@
+//! Progress of a Level III product fetch.
+//!
+//! Files are listed and downloaded by `nexrad::level3` and stored by the
+//! decode workers; this counts both so the product section can report the
+//! batch.
+
+use crate::nexrad::level3::Level3Product;
+
+/// The Level III product picked for fetching and the current batch.
+#[derive(Default)]
+pub struct Level3State {
+    /// Product fetched by the next request.
+    pub product: Level3Product,
+    /// The bucket listing for the batch hasn't come back yet.
+    pub listing: bool,
+    /// Files listed and not yet stored or failed.
+    pub pending: usize,
+    /// Files stored in this batch.
+    pub stored: usize,
+    /// Object keys (or listing prefixes) that failed, with the message.
+    pub failures: Vec<(String, String)>,
+}
+
+impl Level3State {
+    /// Start a batch for `product`, waiting on its listing.
+    pub fn start(&mut self, product: Level3Product) {
+        *self = Self {
+            product,
+            listing: true,
+            ..Self::default()
+        };
+    }
+
+    /// The listing found `count` files to fetch.
+    pub fn record_listed(&mut self, count: usize) {
+        self.listing = false;
+        self.pending += count;
+    }
+
+    /// Record a file the workers stored.
+    pub fn record_success(&mut self) {
+        self.pending = self.pending.saturating_sub(1);
+        self.stored += 1;
+    }
+
+    /// Record a listing, download or decode failure.
+    pub fn record_failure(&mut self, name: String, message: String) {
+        if !self.listing {
+            self.pending = self.pending.saturating_sub(1);
+        }
+        self.failures.push((name, message));
+    }
+
+    pub fn is_busy(&self) -> bool {
+        self.listing || self.pending > 0
+    }
+
+    /// One-line status for the batch.
+    pub fn summary(&self) -> String {
+        if self.listing {
+            return format!("Listing {} files\u{2026}", self.product.code());
+        }
+        if self.is_busy() {
+            return format!(
+                "Fetching {}\u{2026} {} done, {} remaining",
+                self.product.code(),
+                self.stored + self.failures.len(),
+                self.pending
+            );
+        }
+        let mut text = format!(
+            "Stored {} {} product{}",
+            self.stored,
+            self.product.code(),
+            if self.stored == 1 { "" } else { "s" }
+        );
+        if let Some((name, message)) = self.failures.first() {
+            text.push_str(&format!(
+                ", {} failed ({}: {})",
+                self.failures.len(),
+                name,
+                message
+            ));
+        }
+        text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_a_batch_from_listing_to_storage() {
+        let mut level3 = Level3State::default();
+        level3.start(Level3Product::N0u);
+        level3.record_failure("TLX_N0U_2024_05_01".into(), "HTTP 503".into());
+        assert!(level3.is_busy());
+        assert_eq!(level3.summary(), "Listing N0U files\u{2026}");
+
+        level3.record_listed(3);
+        level3.record_success();
+        assert_eq!(level3.summary(), "Fetching N0U\u{2026} 2 done, 2 remaining");
+        level3.record_success();
+        level3.record_failure("TLX_N0U_2024_05_02_00_04_11".into(), "bad".into());
+        assert!(!level3.is_busy());
+        assert_eq!(
+            level3.summary(),
+            "Stored 2 N0U products, 2 failed (TLX_N0U_2024_05_01: HTTP 503)"
+        );
+    }
+
+    #[test]
+    fn empty_listing_finishes_the_batch() {
+        let mut level3 = Level3State::default();
+        level3.start(Level3Product::Eet);
+        level3.record_listed(0);
+        assert!(!level3.is_busy());
+        assert_eq!(level3.summary(), "Stored 0 EET products");
+    }
+}
@@ -12,7 +12,7 @@ use super::VcpPositionModel;
 ///
 /// Derived once per frame from [`LiveModeState`]. All fields reflect the same
 /// `now` timestamp, eliminating inconsistencies between components that would
-/// otherwise independently call `js_sys::Date::now()`.
+/// otherwise independently call `crate::platform::now_ms()`.
 #[derive(Clone, Debug, Default)]
 pub struct LiveRadarModel {
     /// Whether live streaming is active (not Idle, not Error).
@@ -8,9 +8,16 @@
 pub(crate) mod acquisition;
 mod alerts;
 mod app_mode;
+pub(crate) mod console;
+mod cross_section;
+mod export;
+mod import;
 mod layer;
+mod level3;
 mod live_mode;
 mod live_radar_model;
+mod mosaic;
+mod pinned_sites;
 mod playback;
 pub(crate) mod playback_manager;
 mod preferences;
@@ -32,16 +39,25 @@ pub use acquisition::{
 };
 pub use alerts::AlertsState;
 pub use app_mode::AppMode;
+pub use console::ConsoleState;
+pub use cross_section::CrossSectionState;
+pub use export::{ExportFormat, ExportProgress, ExportResolution, ExportState};
+pub use import::ImportState;
 pub use layer::{GeoLayerVisibility, LayerState};
+pub use level3::Level3State;
 pub use live_mode::{LiveExitReason, LiveModeState, LivePhase};
 pub use live_radar_model::LiveRadarModel;
+pub use mosaic::{MosaicState, MOSAIC_NEIGHBOUR_RANGE_KM};
+pub use pinned_sites::{PinnedSitesState, MAX_PINNED_SCAN_AGE_SECS, MAX_PINNED_SITES};
 pub use playback::{
     LoopMode, PlaybackMode, PlaybackSpeed, PlaybackState, TimeModel, MICRO_ZOOM_THRESHOLD,
 };
 pub use preferences::UserPreferences;
 pub use radar_data::RadarTimeline;
 pub use saved_events::{SavedEvent, SavedEvents};
-pub use settings::{format_bytes, StorageSettings};
+pub use settings::{
+    format_bytes, DataSourceSettings, PaletteSettings, ReplaySettings, StorageSettings,
+};
 pub use stats::{
     DownloadPhase, DownloadProgress, IngestTimingDetail, RenderTimingDetail, SessionStats,
 };
@@ -52,8 +68,10 @@ pub use vcp::get_vcp_definition;
 pub use vcp_forecast::{ChunkArrivalStat, RateSource, SweepForecast, VolumeForecastSnapshot};
 pub use vcp_position::{SweepPosition, SweepStatus, SweepTiming, VcpPositionModel};
 pub use viz::{
-    ElevationListEntry, ElevationSelection, InterpolationMode, RadarProduct, RenderProcessing,
-    StormCellInfo, ViewMode, VizState,
+    CellMotion, CellTrack, ElevationListEntry, ElevationSelection, InterpolatedFrame,
+    InterpolationMode, PanelLayout, PanelView, RadarProduct, RenderProcessing, RotationInfo,
+    RotationStrength, StormCellInfo, StormMotionSource, ViewMode, VizState, EXTRA_PANEL_COUNT,
+    STORM_FORECAST_LEAD_MINUTES,
 };
 
 /// Cap on the recent-network-requests ring used by the UI log.
@@ -91,12 +109,35 @@ pub enum AppCommand {
     CancelOperation(OperationId),
     /// Reorder an operation (delta: -1 = up, +1 = down).
     ReorderOperation(OperationId, isize),
+    /// Open the file picker to import local archives (`directory`: pick a
+    /// folder of real-time chunk files instead).
+    ImportFiles { directory: bool },
+    /// Fetch the picked Level III product over the selection, or the loaded
+    /// data when there is none.
+    FetchLevel3,
+    /// Fetch the mosaic's neighbouring sites' volumes over the selection, or
+    /// at the playback position when there is none.
+    FetchMosaicScans,
+    /// Pin a secondary site to the map and fetch its volume at the
+    /// playback position.
+    PinSite(String),
+    /// Unpin a secondary site and clear its GPU slot.
+    UnpinSite(String),
+    /// Fetch pinned sites' volumes over the selection, or at the playback
+    /// position when there is none.
+    FetchPinnedScans,
     /// Retry initializing the decode worker after a failure.
     RetryWorker,
     /// Request an immediate refresh of the NWS alerts feed.
     RefreshAlerts,
     /// Open the alert detail modal for a specific alert id.
     OpenAlert(String),
+    /// Switch fetches to the edited data source and save it.
+    ApplyDataSource,
+    /// Install the edited palette choices and recolor what's on screen.
+    ApplyPalettes,
+    /// Open the file picker to import `.pal` color tables.
+    ImportPalettes,
     /// Close any open alert modal (detail or list).
     #[allow(dead_code)] // Provided for symmetry; modals close via their own buttons.
     CloseAlert,
@@ -158,6 +199,14 @@ pub struct AppState {
     /// Storage settings (quota, eviction targets).
     pub storage_settings: StorageSettings,
 
+    /// Where archive and real-time data is fetched from. Edited in the
+    /// panel and applied with [`AppCommand::ApplyDataSource`].
+    pub data_source: DataSourceSettings,
+
+    /// Color table choices. Edited in the panel and applied with
+    /// [`AppCommand::ApplyPalettes`].
+    pub palettes: PaletteSettings,
+
     /// Whether the site selection modal is open.
     pub site_modal_open: bool,
 
@@ -240,6 +289,29 @@ pub struct AppState {
     /// NWS active alerts + related modal state.
     pub alerts: AlertsState,
 
+    /// Vertical cross-section tool: line endpoints and the latest section.
+    pub cross_section: CrossSectionState,
+
+    /// Command console: input line, scrollback and queued script commands.
+    pub console: ConsoleState,
+
+    /// Image/animation export settings and progress.
+    pub export: ExportState,
+
+    /// Progress of the current local file import.
+    pub import: ImportState,
+
+    /// Level III product picked for fetching and the current fetch.
+    pub level3: Level3State,
+
+    /// Regional mosaic sites, neighbour fetch progress and the latest
+    /// composited grid.
+    pub mosaic: MosaicState,
+
+    /// Secondary sites drawn under the active one, with their cached scans
+    /// and fetch progress.
+    pub pinned_sites: PinnedSitesState,
+
     /// Resolved mobile mode for the current frame. Computed by
     /// [`AppState::refresh_mobile_mode`] from viewport width and touch history.
     /// When true, panels collapse to the mobile chrome.
@@ -312,14 +384,13 @@ impl DateTimePickerState {
     /// Initialize the picker with a timestamp, respecting the timezone setting.
     pub fn init_from_timestamp(&mut self, ts: f64, use_local: bool) {
         if use_local {
-            let d = js_sys::Date::new_0();
-            d.set_time(ts * 1000.0);
-            self.year = format!("{:04}", d.get_full_year());
-            self.month = format!("{:02}", d.get_month() + 1); // JS months are 0-based
-            self.day = format!("{:02}", d.get_date());
-            self.hour = format!("{:02}", d.get_hours());
-            self.minute = format!("{:02}", d.get_minutes());
-            self.second = format!("{:02}", d.get_seconds());
+            let dt = crate::platform::local_datetime(ts);
+            self.year = dt.format("%Y").to_string();
+            self.month = dt.format("%m").to_string();
+            self.day = dt.format("%d").to_string();
+            self.hour = dt.format("%H").to_string();
+            self.minute = dt.format("%M").to_string();
+            self.second = dt.format("%S").to_string();
         } else {
             use chrono::{TimeZone, Utc};
             let dt = Utc.timestamp_opt(ts as i64, 0).unwrap();
@@ -343,20 +414,7 @@ impl DateTimePickerState {
         let second: u32 = self.second.parse().ok()?;
 
         if use_local {
-            // Construct a JS Date from local components and read back UTC millis
-            let d = js_sys::Date::new_0();
-            d.set_full_year(year as u32);
-            d.set_month(month.checked_sub(1)?); // JS months are 0-based
-            d.set_date(day);
-            d.set_hours(hour);
-            d.set_minutes(minute);
-            d.set_seconds(second);
-            d.set_milliseconds(0);
-            let ts = d.get_time(); // UTC milliseconds
-            if ts.is_nan() {
-                return None;
-            }
-            Some(ts / 1000.0)
+            crate::platform::local_to_unix(year, month, day, hour, minute, second)
         } else {
             use chrono::{TimeZone, Utc};
             let dt = Utc.with_ymd_and_hms(year, month, day, hour, minute, second);
@@ -376,7 +434,7 @@ impl DateTimePickerState {
 impl AppState {
     pub fn new() -> Self {
         // Use current time for initialization
-        let now = js_sys::Date::now() / 1000.0;
+        let now = crate::platform::now_secs();
 
         // Start with empty timeline - will be populated from cache
         let radar_timeline = RadarTimeline::default();
@@ -406,6 +464,7 @@ impl AppState {
             status_message: "Ready".to_string(),
             session_stats: SessionStats::new(),
             storage_settings,
+            data_source: DataSourceSettings::load(),
             saved_events,
             left_sidebar_visible: true,
             right_sidebar_visible: true,
@@ -438,7 +497,7 @@ impl AppState {
     /// Call once at the start of each UI frame so all consumers see consistent
     /// state derived from the same `now` timestamp.
     pub fn refresh_live_model(&mut self) {
-        let now = js_sys::Date::now() / 1000.0;
+        let now = crate::platform::now_secs();
         self.live_radar_model = self.live_mode_state.compute_model(now);
         self.app_mode = if self.live_mode_state.is_active() {
             AppMode::Live
@@ -479,16 +538,76 @@ impl AppState {
 
     /// Whether sweep animation is effectively enabled: requires both the user
     /// preference AND micro playback mode (zoomed in). In macro mode, sweep
-    /// animation is suppressed regardless of the user preference.
+    /// animation is suppressed regardless of the user preference. Volume
+    /// products have no sweep to animate and never use it.
     pub fn effective_sweep_animation(&self) -> bool {
         self.render_processing.sweep_animation
             && self.playback_state.playback_mode() == PlaybackMode::Micro
+            && !self.viz_state.product.is_volume_product()
+            && !self.viz_state.product.is_qpe_product()
+            && !self.viz_state.elevation_selection.is_lowest_available()
+    }
+
+    /// Whether frame interpolation is effectively enabled: requires the user
+    /// preference, macro playback of a fixed tilt and a single 2D panel.
+    /// Volume products, accumulations and Level III products are left alone.
+    pub fn effective_frame_interpolation(&self) -> bool {
+        self.render_processing.frame_interpolation
+            && self.playback_state.playback_mode() == PlaybackMode::Macro
+            && self
+                .viz_state
+                .elevation_selection
+                .elevation_number()
+                .is_some()
+            && self.viz_state.view_mode == ViewMode::Flat2D
+            && self.viz_state.visible_extra_panels().is_empty()
+            && !self.viz_state.product.is_volume_product()
+            && !self.viz_state.product.is_qpe_product()
+            && !self.viz_state.product.is_level3()
+            && !self.live_mode_state.is_active()
+    }
+
+    /// Time window `(start, end)` of the selected rainfall accumulation.
+    ///
+    /// The storm total spans the timeline selection. The 1- and 3-hour
+    /// totals trail the scan at the playback position, clipped to the
+    /// selection when there is one. `None` when the product isn't an
+    /// accumulation or the storm total has no selection to span.
+    pub fn qpe_window(&self) -> Option<(f64, f64)> {
+        let product = self.viz_state.product.to_worker_string();
+        if !crate::nexrad::qpe::is_qpe_product(product) {
+            return None;
+        }
+        let selection = self.playback_state.selection_range();
+        let Some(window) = crate::nexrad::qpe::window_secs(product) else {
+            return selection;
+        };
+
+        // End on a scan boundary so the window (and the render request built
+        // from it) only changes when playback crosses into a new scan.
+        let position = self.playback_state.playback_position();
+        let mut end = self
+            .radar_timeline
+            .find_recent_scan(position, window)
+            .map_or((position / 60.0).floor() * 60.0, |scan| scan.end_time);
+        let mut start = end - window;
+        if let Some((sel_start, sel_end)) = selection {
+            end = end.clamp(sel_start, sel_end);
+            start = start.max(sel_start);
+        }
+        (start < end).then_some((start, end))
+    }
+
+    /// Radar network of the current site (WSR-88D for unknown ids).
+    pub fn radar_type(&self) -> crate::data::RadarType {
+        crate::data::get_site(&self.viz_state.site_id)
+            .map_or(crate::data::RadarType::Wsr88d, |site| site.radar_type())
     }
 
     /// Set the status message and record the timestamp for auto-dismissal.
     #[allow(dead_code)]
     pub fn set_status(&mut self, msg: impl Into<String>) {
         self.status_message = msg.into();
-        self.status_message_set_ms = js_sys::Date::now();
+        self.status_message_set_ms = crate::platform::now_ms();
     }
 }
@@ -0,0 +1,261 @@
+//! Regional mosaic state.
+//!
+//! The sites are picked in the mosaic section. Their volumes are fetched by
+//! `nexrad::mosaic` and stored by the decode workers, which count here as a
+//! batch; the latest composited grid is kept as a texture for the canvas.
+
+use crate::data::sites_within;
+use crate::nexrad::color_table::{build_product_lut, is_categorical, value_range_for};
+use crate::nexrad::mosaic::MosaicMode;
+use crate::nexrad::MosaicData;
+use eframe::egui;
+
+/// Sites offered for the mosaic lie within this distance of the current one.
+pub const MOSAIC_NEIGHBOUR_RANGE_KM: f64 = 460.0;
+
+/// Neighbours picked with the current site when the mosaic is first enabled.
+const SEED_NEIGHBOURS: usize = 3;
+
+/// How often neighbour scans are fetched again while live, seconds.
+const LIVE_REFRESH_SECS: f64 = 300.0;
+
+pub struct MosaicState {
+    /// Show the mosaic on the map.
+    pub enabled: bool,
+    /// Sites composited, in the order picked.
+    pub site_ids: Vec<String>,
+    pub mode: MosaicMode,
+    /// Oldest scan used, minutes before the playback position.
+    pub max_age_mins: u32,
+    /// Site listings of the fetch not yet back.
+    pub listing: usize,
+    /// Volumes listed and not yet stored or failed.
+    pub pending: usize,
+    /// Volumes stored, or found in the cache, in this fetch.
+    pub stored: usize,
+    /// Sites or files that failed, with the message.
+    pub failures: Vec<(String, String)>,
+    /// When neighbour scans were last fetched in live mode.
+    pub last_live_fetch: Option<f64>,
+    /// A mosaic request is in flight.
+    pub compositing: bool,
+    /// Latest mosaic colored for the map, with its bounds as (min_lon,
+    /// min_lat, max_lon, max_lat).
+    pub texture: Option<(egui::TextureHandle, (f64, f64, f64, f64))>,
+    /// Scans in the latest mosaic.
+    pub scan_keys: Vec<String>,
+    /// Error from the latest mosaic request (cleared on success).
+    pub error: Option<String>,
+}
+
+impl Default for MosaicState {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            site_ids: Vec::new(),
+            mode: MosaicMode::default(),
+            max_age_mins: 10,
+            listing: 0,
+            pending: 0,
+            stored: 0,
+            failures: Vec::new(),
+            last_live_fetch: None,
+            compositing: false,
+            texture: None,
+            scan_keys: Vec::new(),
+            error: None,
+        }
+    }
+}
+
+impl MosaicState {
+    /// Pick `site_id` and its nearest neighbours when nothing is picked yet.
+    pub fn seed(&mut self, site_id: &str) {
+        if !self.site_ids.is_empty() {
+            return;
+        }
+        let Some(site) = crate::data::get_site(site_id) else {
+            return;
+        };
+        self.site_ids.push(site.id.to_string());
+        self.site_ids.extend(
+            sites_within(site.lat, site.lon, MOSAIC_NEIGHBOUR_RANGE_KM)
+                .into_iter()
+                .filter(|(s, _)| s.id != site.id)
+                .take(SEED_NEIGHBOURS)
+                .map(|(s, _)| s.id.to_string()),
+        );
+    }
+
+    /// Add or remove a site.
+    pub fn toggle_site(&mut self, site_id: &str) {
+        if let Some(i) = self.site_ids.iter().position(|s| s == site_id) {
+            self.site_ids.remove(i);
+        } else {
+            self.site_ids.push(site_id.to_string());
+        }
+    }
+
+    pub fn max_age_secs(&self) -> i64 {
+        self.max_age_mins as i64 * 60
+    }
+
+    /// Whether neighbour scans should be fetched again while live.
+    pub fn live_fetch_due(&self, now: f64) -> bool {
+        self.last_live_fetch
+            .is_none_or(|last| now - last >= LIVE_REFRESH_SECS)
+    }
+
+    /// Start a fetch over `site_count` sites.
+    pub fn start_fetch(&mut self, site_count: usize) {
+        self.listing = site_count;
+        self.pending = 0;
+        self.stored = 0;
+        self.failures.clear();
+    }
+
+    /// A site's listing came back with `Ok(count)` volumes to fetch, or
+    /// failed.
+    pub fn record_listed(&mut self, site_id: String, result: Result<usize, String>) {
+        self.listing = self.listing.saturating_sub(1);
+        match result {
+            Ok(count) => self.pending += count,
+            Err(message) => self.failures.push((site_id, message)),
+        }
+    }
+
+    /// Record a volume stored by the workers or found in the cache.
+    pub fn record_stored(&mut self) {
+        self.pending = self.pending.saturating_sub(1);
+        self.stored += 1;
+    }
+
+    /// Record a volume that failed to download or ingest.
+    pub fn record_failure(&mut self, file_name: String, message: String) {
+        self.pending = self.pending.saturating_sub(1);
+        self.failures.push((file_name, message));
+    }
+
+    pub fn is_fetching(&self) -> bool {
+        self.listing > 0 || self.pending > 0
+    }
+
+    /// One-line status for the fetch.
+    pub fn summary(&self) -> String {
+        if self.listing > 0 {
+            return format!(
+                "Listing {} site{}\u{2026}",
+                self.listing,
+                if self.listing == 1 { "" } else { "s" }
+            );
+        }
+        if self.is_fetching() {
+            return format!(
+                "Fetching neighbour scans\u{2026} {} done, {} remaining",
+                self.stored + self.failures.len(),
+                self.pending
+            );
+        }
+        let mut text = format!(
+            "{} neighbour scan{} ready",
+            self.stored,
+            if self.stored == 1 { "" } else { "s" }
+        );
+        if let Some((name, message)) = self.failures.first() {
+            text.push_str(&format!(
+                ", {} failed ({}: {})",
+                self.failures.len(),
+                name,
+                message
+            ));
+        }
+        text
+    }
+
+    /// Color a finished mosaic through its product's table and keep it for
+    /// the map. Empty cells are transparent.
+    pub fn set_result(&mut self, ctx: &egui::Context, data: MosaicData) {
+        self.compositing = false;
+        self.error = None;
+        self.scan_keys = data.scan_keys;
+        let grid = data.grid;
+        if grid.values.is_empty() {
+            self.texture = None;
+            return;
+        }
+
+        let product = data.request.product.as_str();
+        let lut = build_product_lut(product);
+        let (min_val, max_val) = value_range_for(product);
+        let lut_len = lut.len() / 4;
+        let mut pixels = Vec::with_capacity(grid.values.len() * 4);
+        for &v in &grid.values {
+            if v.is_nan() {
+                pixels.extend_from_slice(&[0, 0, 0, 0]);
+                continue;
+            }
+            let t = ((v - min_val) / (max_val - min_val)).clamp(0.0, 1.0);
+            let i = ((t * (lut_len - 1) as f32) as usize).min(lut_len - 1);
+            pixels.extend_from_slice(&lut[i * 4..i * 4 + 4]);
+        }
+        let image = egui::ColorImage::from_rgba_unmultiplied([grid.columns, grid.rows], &pixels);
+        let options = if is_categorical(product) {
+            egui::TextureOptions::NEAREST
+        } else {
+            egui::TextureOptions::LINEAR
+        };
+        let texture = ctx.load_texture("regional_mosaic", image, options);
+        self.texture = Some((texture, grid.bounds()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn seeds_the_current_site_and_its_neighbours_once() {
+        let mut mosaic = MosaicState::default();
+        mosaic.seed("ktlx");
+        assert_eq!(mosaic.site_ids.len(), 1 + SEED_NEIGHBOURS);
+        assert_eq!(mosaic.site_ids[0], "KTLX");
+        assert!(mosaic.site_ids.contains(&"KVNX".to_string()));
+
+        mosaic.toggle_site("KVNX");
+        assert!(!mosaic.site_ids.contains(&"KVNX".to_string()));
+        mosaic.seed("KDMX");
+        assert_eq!(mosaic.site_ids[0], "KTLX");
+        mosaic.toggle_site("KDMX");
+        assert_eq!(mosaic.site_ids.last().map(String::as_str), Some("KDMX"));
+    }
+
+    #[test]
+    fn counts_a_fetch_across_sites() {
+        let mut mosaic = MosaicState::default();
+        mosaic.start_fetch(2);
+        assert_eq!(mosaic.summary(), "Listing 2 sites\u{2026}");
+        mosaic.record_listed("KTLX".into(), Ok(2));
+        mosaic.record_listed("KINX".into(), Err("HTTP 503".into()));
+        assert!(mosaic.is_fetching());
+        mosaic.record_stored();
+        assert_eq!(
+            mosaic.summary(),
+            "Fetching neighbour scans\u{2026} 2 done, 1 remaining"
+        );
+        mosaic.record_failure("KTLX20240501_120000_V06".into(), "bad".into());
+        assert!(!mosaic.is_fetching());
+        assert_eq!(
+            mosaic.summary(),
+            "1 neighbour scan ready, 2 failed (KINX: HTTP 503)"
+        );
+    }
+
+    #[test]
+    fn refetches_live_scans_periodically() {
+        let mut mosaic = MosaicState::default();
+        assert!(mosaic.live_fetch_due(1000.0));
+        mosaic.last_live_fetch = Some(1000.0);
+        assert!(!mosaic.live_fetch_due(1000.0 + LIVE_REFRESH_SECS - 1.0));
+        assert!(mosaic.live_fetch_due(1000.0 + LIVE_REFRESH_SECS));
+    }
+}
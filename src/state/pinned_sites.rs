@@ -0,0 +1,253 @@
+//! Pinned secondary sites.
+//!
+//! Besides the active site, a few neighbouring radars can be pinned to the
+//! 2D map. Each keeps its own list of cached scans; the one current at the
+//! playback position is rendered into that site's GPU slot, under the
+//! active radar, at the site's own opacity. Their volumes are fetched by
+//! `nexrad::neighbour_fetch` and show in the acquisition queue.
+
+use super::OperationId;
+use crate::data::{ScanIndexEntry, SweepMeta};
+use std::collections::HashMap;
+
+/// Most sites that can be pinned; one GPU renderer is kept for each.
+pub const MAX_PINNED_SITES: usize = 3;
+
+/// A pinned site's scan is not drawn once the playback position is this
+/// long after its start, seconds.
+pub const MAX_PINNED_SCAN_AGE_SECS: i64 = 15 * 60;
+
+/// How often pinned sites' scans are fetched again while live, seconds.
+const LIVE_REFRESH_SECS: f64 = 300.0;
+
+/// A cached scan of a pinned site.
+#[derive(Clone, Debug)]
+pub struct PinnedScan {
+    /// Storage key ("SITE|TIMESTAMP_MS").
+    pub key: String,
+    /// Volume start, Unix seconds.
+    pub start_secs: i64,
+    pub sweeps: Vec<SweepMeta>,
+}
+
+impl PinnedScan {
+    pub fn from_entry(entry: &ScanIndexEntry) -> Self {
+        Self {
+            key: entry.scan.to_storage_key(),
+            start_secs: entry.scan.scan_start.as_secs(),
+            sweeps: entry.sweeps.clone().unwrap_or_default(),
+        }
+    }
+
+    /// Elevation number of the sweep carrying `product` closest to
+    /// `elevation_deg`. Sweeps listing no products are assumed to carry it.
+    pub fn sweep_near(&self, elevation_deg: f32, product: &str) -> Option<u8> {
+        self.sweeps
+            .iter()
+            .filter(|s| {
+                s.available_products.is_empty() || s.available_products.iter().any(|p| p == product)
+            })
+            .min_by(|a, b| {
+                (a.elevation - elevation_deg)
+                    .abs()
+                    .total_cmp(&(b.elevation - elevation_deg).abs())
+                    .then(a.elevation_number.cmp(&b.elevation_number))
+            })
+            .map(|s| s.elevation_number)
+    }
+}
+
+pub struct PinnedSite {
+    pub site_id: String,
+    /// Draw this site on the map.
+    pub visible: bool,
+    /// Opacity of this site's data (0.0..1.0), on top of the global one.
+    pub opacity: f32,
+    /// Cached scans, oldest first.
+    pub scans: Vec<PinnedScan>,
+    /// A cached scan listing is in flight.
+    pub loading: bool,
+    /// List the cached scans again once the listing in flight is back.
+    pub stale: bool,
+    /// Archive listing in flight.
+    pub listing: bool,
+    /// Volumes listed and not yet stored or failed.
+    pub pending: usize,
+    /// Latest listing or download failure.
+    pub error: Option<String>,
+    /// Storage key of the scan drawn, if any.
+    pub shown_scan: Option<String>,
+}
+
+impl PinnedSite {
+    fn new(site_id: &str) -> Self {
+        Self {
+            site_id: site_id.to_string(),
+            visible: true,
+            opacity: 0.8,
+            scans: Vec::new(),
+            loading: false,
+            stale: true,
+            listing: false,
+            pending: 0,
+            error: None,
+            shown_scan: None,
+        }
+    }
+
+    /// The latest scan starting at or before `position` (Unix seconds), if
+    /// it is recent enough to draw.
+    pub fn scan_at(&self, position: f64) -> Option<&PinnedScan> {
+        let position = position.floor() as i64;
+        let i = self.scans.partition_point(|s| s.start_secs <= position);
+        let scan = self.scans.get(i.checked_sub(1)?)?;
+        (position - scan.start_secs <= MAX_PINNED_SCAN_AGE_SECS).then_some(scan)
+    }
+
+    /// Replace the cached scan list.
+    pub fn set_scans(&mut self, entries: &[ScanIndexEntry]) {
+        self.scans = entries.iter().map(PinnedScan::from_entry).collect();
+        self.scans.sort_by_key(|s| s.start_secs);
+    }
+
+    pub fn is_fetching(&self) -> bool {
+        self.listing || self.pending > 0
+    }
+}
+
+#[derive(Default)]
+pub struct PinnedSitesState {
+    /// Pinned sites in pinning order; index `i` renders into GPU slot `i`.
+    pub sites: Vec<PinnedSite>,
+    /// Acquisition operation of each volume being fetched, by file name.
+    pub operations: HashMap<String, OperationId>,
+    /// When pinned sites' scans were last fetched in live mode.
+    pub last_live_fetch: Option<f64>,
+}
+
+impl PinnedSitesState {
+    /// Pin `site_id` unless it is the active site, already pinned, or every
+    /// slot is taken. Returns whether it was pinned.
+    pub fn pin(&mut self, site_id: &str, active_site_id: &str) -> bool {
+        let site_id = site_id.to_uppercase();
+        if site_id.eq_ignore_ascii_case(active_site_id)
+            || self.index_of(&site_id).is_some()
+            || self.sites.len() >= MAX_PINNED_SITES
+        {
+            return false;
+        }
+        self.sites.push(PinnedSite::new(&site_id));
+        true
+    }
+
+    /// Unpin `site_id`. Returns its slot, after which every later site has
+    /// moved down one.
+    pub fn unpin(&mut self, site_id: &str) -> Option<usize> {
+        let index = self.index_of(site_id)?;
+        self.sites.remove(index);
+        Some(index)
+    }
+
+    pub fn index_of(&self, site_id: &str) -> Option<usize> {
+        self.sites
+            .iter()
+            .position(|s| s.site_id.eq_ignore_ascii_case(site_id))
+    }
+
+    pub fn site_mut(&mut self, site_id: &str) -> Option<&mut PinnedSite> {
+        let index = self.index_of(site_id)?;
+        self.sites.get_mut(index)
+    }
+
+    /// The pinned site an archive belongs to; archive names start with the
+    /// site id.
+    pub fn site_for_file(&mut self, file_name: &str) -> Option<&mut PinnedSite> {
+        self.site_mut(file_name.get(..4)?)
+    }
+
+    pub fn is_fetching(&self) -> bool {
+        self.sites.iter().any(PinnedSite::is_fetching)
+    }
+
+    /// Whether pinned sites' scans should be fetched again while live.
+    pub fn live_fetch_due(&self, now: f64) -> bool {
+        !self.sites.is_empty()
+            && self
+                .last_live_fetch
+                .is_none_or(|last| now - last >= LIVE_REFRESH_SECS)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sweep(elevation_number: u8, elevation: f32, products: &[&str]) -> SweepMeta {
+        SweepMeta {
+            start: 0.0,
+            end: 0.0,
+            elevation,
+            elevation_number,
+            start_azimuth: 0.0,
+            available_products: products.iter().map(|p| p.to_string()).collect(),
+        }
+    }
+
+    fn scan(start_secs: i64) -> PinnedScan {
+        PinnedScan {
+            key: format!("KINX|{}", start_secs * 1000),
+            start_secs,
+            sweeps: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn pins_other_sites_up_to_the_limit() {
+        let mut pinned = PinnedSitesState::default();
+        assert!(!pinned.pin("ktlx", "KTLX"));
+        assert!(pinned.pin("kinx", "KTLX"));
+        assert!(!pinned.pin("KINX", "KTLX"));
+        assert!(pinned.pin("KVNX", "KTLX"));
+        assert!(pinned.pin("KFDR", "KTLX"));
+        assert!(!pinned.pin("KICT", "KTLX"));
+        assert_eq!(pinned.sites[0].site_id, "KINX");
+
+        assert_eq!(pinned.unpin("kvnx"), Some(1));
+        assert_eq!(pinned.index_of("KFDR"), Some(1));
+        assert_eq!(pinned.unpin("KVNX"), None);
+        assert!(pinned.pin("KICT", "KTLX"));
+    }
+
+    #[test]
+    fn draws_the_latest_recent_scan() {
+        let mut site = PinnedSite::new("KINX");
+        site.scans = vec![scan(1000), scan(1300), scan(1600)];
+        assert!(site.scan_at(999.0).is_none());
+        assert_eq!(site.scan_at(1000.0).map(|s| s.start_secs), Some(1000));
+        assert_eq!(site.scan_at(1599.5).map(|s| s.start_secs), Some(1300));
+        assert_eq!(
+            site.scan_at((1600 + MAX_PINNED_SCAN_AGE_SECS) as f64)
+                .map(|s| s.start_secs),
+            Some(1600)
+        );
+        assert!(site
+            .scan_at((1601 + MAX_PINNED_SCAN_AGE_SECS) as f64)
+            .is_none());
+    }
+
+    #[test]
+    fn picks_the_nearest_tilt_with_the_product() {
+        let mut s = scan(0);
+        s.sweeps = vec![
+            sweep(1, 0.5, &["reflectivity"]),
+            sweep(2, 0.5, &["velocity"]),
+            sweep(3, 1.5, &["reflectivity", "velocity"]),
+            sweep(4, 2.4, &[]),
+        ];
+        assert_eq!(s.sweep_near(0.48, "reflectivity"), Some(1));
+        assert_eq!(s.sweep_near(0.48, "velocity"), Some(2));
+        assert_eq!(s.sweep_near(1.3, "velocity"), Some(3));
+        assert_eq!(s.sweep_near(3.1, "velocity"), Some(4));
+        assert_eq!(scan(0).sweep_near(0.5, "velocity"), None);
+    }
+}
@@ -230,7 +230,7 @@ impl Default for TimeModel {
 impl TimeModel {
     /// Get current wall-clock time as Unix seconds.
     pub fn wall_clock_time() -> f64 {
-        js_sys::Date::now() / 1000.0
+        crate::platform::now_secs()
     }
 
     /// Create a new time model at the given position.
@@ -499,6 +499,24 @@ impl PlaybackState {
         self.step_macro_frame_internal(delta);
     }
 
+    /// The macro frames playback is moving between, as `(from, to, fraction)`:
+    /// the current frame's time, the next one's in the playback direction
+    /// and how far the accumulator has advanced towards it. `None` at either
+    /// end of the frame list.
+    pub fn macro_frame_transition(&self) -> Option<(f64, f64, f64)> {
+        let frames = &self.macro_playback.sweep_frames;
+        let idx = self.macro_playback.current_frame_index;
+        let next = match self.time_model.direction {
+            PlaybackDirection::Forward => idx.checked_add(1),
+            PlaybackDirection::Backward => idx.checked_sub(1),
+        }?;
+        Some((
+            *frames.get(idx)?,
+            *frames.get(next)?,
+            self.macro_playback.frame_accumulator.clamp(0.0, 1.0),
+        ))
+    }
+
     /// Internal frame step, returns false if playback should stop (Once mode at boundary).
     fn step_macro_frame_internal(&mut self, delta: isize) -> bool {
         let len = self.macro_playback.sweep_frames.len();
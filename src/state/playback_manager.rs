@@ -1,12 +1,17 @@
-//! Playback sweep cache and previous-sweep resolution logic.
+//! Playback sweep cache, previous-sweep resolution and frame interpolation.
 //!
 //! Extracted from `main.rs` to reduce the size of `WorkbenchApp` and group
 //! sweep-cache / sweep-animation helpers in one place.
 
 use std::collections::HashMap;
 
+use crate::nexrad::frame_interpolation::{self, FlowField, RawSweep};
 use crate::state::radar_data::{RadarTimeline, Scan, Sweep};
 
+/// Frames shown per macro step when interpolating: the observed sweep plus
+/// `INTERPOLATION_STEPS - 1` interpolated ones.
+const INTERPOLATION_STEPS: u32 = 4;
+
 // ---------------------------------------------------------------------------
 // Cached sweep data
 // ---------------------------------------------------------------------------
@@ -33,6 +38,20 @@ pub(crate) struct CachedSweepData {
     pub product: String,
 }
 
+impl CachedSweepData {
+    fn raw_sweep(&self) -> RawSweep<'_> {
+        RawSweep {
+            azimuths: &self.azimuths,
+            gate_values: &self.gate_values,
+            gate_count: self.gate_count as usize,
+            first_gate_km: self.first_gate_range_km,
+            gate_interval_km: self.gate_interval_km,
+            offset: self.offset,
+            scale: self.scale,
+        }
+    }
+}
+
 /// Build a sweep cache key from scan key and elevation number.
 pub(crate) fn sweep_cache_key(scan_key: &str, elevation_number: u8, product: &str) -> String {
     format!("{}|{}|{}", scan_key, elevation_number, product)
@@ -105,6 +124,39 @@ pub(crate) enum PrevSweepAction {
     Clear,
 }
 
+// ---------------------------------------------------------------------------
+// InterpolationAction
+// ---------------------------------------------------------------------------
+
+/// Action to take for the primary GPU texture during interpolated playback.
+pub(crate) enum InterpolationAction {
+    /// The wanted frame is on screen, or its sweeps are not ready yet.
+    Unchanged,
+    /// Upload this interpolated frame: raw gate values on the grid of the
+    /// cached sweep it starts from.
+    Upload {
+        from_key: String,
+        gate_values: Vec<f32>,
+    },
+    /// Put the observed sweep back in place of an interpolated frame.
+    Restore(String),
+    /// Request a decode of the sweep being interpolated towards.
+    FetchFromWorker {
+        scan_key: String,
+        elevation_number: u8,
+        product: String,
+    },
+}
+
+/// Flow between two cached sweeps and the step currently on screen.
+struct InterpolationPair {
+    from_key: String,
+    to_key: String,
+    flow: FlowField,
+    /// Step uploaded to the GPU; 0 = the observed `from` sweep.
+    step: u32,
+}
+
 // ---------------------------------------------------------------------------
 // PlaybackManager
 // ---------------------------------------------------------------------------
@@ -120,6 +172,10 @@ pub(crate) struct PlaybackManager {
     /// `resolve_prev_sweep` can skip work. Includes product so a product
     /// change invalidates the cache and re-resolves the prev texture.
     cached_prev_identity: Option<(String, u8, String)>,
+    /// Sweep pair of the interpolated playback, if any.
+    interpolation: Option<InterpolationPair>,
+    /// Next sweep requested from the worker for interpolation.
+    pending_next_sweep_key: Option<String>,
 }
 
 impl PlaybackManager {
@@ -128,6 +184,8 @@ impl PlaybackManager {
             sweep_cache: SweepDataCache::new(4),
             pending_prev_sweep_key: None,
             cached_prev_identity: None,
+            interpolation: None,
+            pending_next_sweep_key: None,
         }
     }
 
@@ -145,6 +203,8 @@ impl PlaybackManager {
     pub fn clear_cache(&mut self) {
         self.sweep_cache.clear();
         self.cached_prev_identity = None;
+        self.interpolation = None;
+        self.pending_next_sweep_key = None;
     }
 
     /// Get the pending prev sweep key.
@@ -266,6 +326,127 @@ impl PlaybackManager {
             product: product.to_string(),
         }
     }
+
+    /// Determine what the primary texture should show `fraction` of the way
+    /// from the observed sweep `from_key` on screen to the next one of the
+    /// same tilt.
+    ///
+    /// The fraction is quantised to `INTERPOLATION_STEPS` steps; step 0 is
+    /// the observed sweep. The flow between a pair is estimated once, on the
+    /// first interpolated step, and each step is built when it is reached.
+    pub fn resolve_interpolated_frame(
+        &mut self,
+        from_key: &str,
+        to_scan_key: &str,
+        to_elev_num: u8,
+        product: &str,
+        fraction: f64,
+        categorical: bool,
+    ) -> InterpolationAction {
+        let step = ((fraction * INTERPOLATION_STEPS as f64) as u32).min(INTERPOLATION_STEPS - 1);
+        let to_key = sweep_cache_key(to_scan_key, to_elev_num, product);
+
+        // A new pair drops the old flow; put the observed sweep back first
+        // if a frame of the old pair is still on screen.
+        if self
+            .interpolation
+            .as_ref()
+            .is_some_and(|pair| pair.from_key != from_key || pair.to_key != to_key)
+        {
+            if let Some(shown) = self.stop_interpolation() {
+                return InterpolationAction::Restore(shown);
+            }
+        }
+        if step == 0 {
+            return match self.interpolation.as_mut() {
+                Some(pair) if pair.step > 0 => {
+                    pair.step = 0;
+                    InterpolationAction::Restore(pair.from_key.clone())
+                }
+                _ => InterpolationAction::Unchanged,
+            };
+        }
+
+        let Some(to) = self.sweep_cache.get(&to_key) else {
+            if self.pending_next_sweep_key.as_deref() == Some(&to_key) {
+                return InterpolationAction::Unchanged; // already in flight
+            }
+            self.pending_next_sweep_key = Some(to_key);
+            return InterpolationAction::FetchFromWorker {
+                scan_key: to_scan_key.to_string(),
+                elevation_number: to_elev_num,
+                product: product.to_string(),
+            };
+        };
+        let Some(from) = self.sweep_cache.get(from_key) else {
+            return InterpolationAction::Unchanged;
+        };
+
+        let pair = self.interpolation.get_or_insert_with(|| {
+            let t_flow = web_time::Instant::now();
+            let flow = frame_interpolation::estimate_flow(&from.raw_sweep(), &to.raw_sweep());
+            log::debug!(
+                "Frame interpolation: flow {} -> {} in {:.0}ms",
+                from_key,
+                to_key,
+                t_flow.elapsed().as_secs_f64() * 1000.0,
+            );
+            InterpolationPair {
+                from_key: from_key.to_string(),
+                to_key: to_key.clone(),
+                flow,
+                step: 0,
+            }
+        });
+        if pair.step == step {
+            return InterpolationAction::Unchanged;
+        }
+        pair.step = step;
+        let gate_values = frame_interpolation::interpolate_frame(
+            &from.raw_sweep(),
+            &to.raw_sweep(),
+            &pair.flow,
+            step as f32 / INTERPOLATION_STEPS as f32,
+            categorical,
+        );
+        InterpolationAction::Upload {
+            from_key: from_key.to_string(),
+            gate_values,
+        }
+    }
+
+    /// Stop interpolating. Returns the observed sweep to put back when an
+    /// interpolated frame is on screen.
+    pub fn stop_interpolation(&mut self) -> Option<String> {
+        self.pending_next_sweep_key = None;
+        self.interpolation
+            .take()
+            .filter(|pair| pair.step > 0)
+            .map(|pair| pair.from_key)
+    }
+
+    /// Fraction of the way to the next sweep of the interpolated frame on
+    /// screen, if any.
+    pub fn interpolated_fraction(&self) -> Option<f32> {
+        self.interpolation
+            .as_ref()
+            .filter(|pair| pair.step > 0)
+            .map(|pair| pair.step as f32 / INTERPOLATION_STEPS as f32)
+    }
+
+    /// Note that a decoded sweep reached the primary texture, replacing any
+    /// interpolated frame, and clear the pending next-sweep request if it
+    /// was this one.
+    pub fn sweep_decoded(&mut self, key: &str, uploaded: bool) {
+        if uploaded {
+            if let Some(pair) = self.interpolation.as_mut() {
+                pair.step = 0;
+            }
+        }
+        if self.pending_next_sweep_key.as_deref() == Some(key) {
+            self.pending_next_sweep_key = None;
+        }
+    }
 }
 
 // ---------------------------------------------------------------------------
@@ -308,6 +489,18 @@ pub(crate) fn best_elevation_at_playback(
             playback_ts,
             available_elevations.first().copied().unwrap_or(1),
         )),
+        // The composite is built on the newest started sweep of the lowest tilt.
+        crate::state::ElevationSelection::LowestAvailable => scan
+            .sweeps
+            .iter()
+            .filter(|s| s.start_time <= playback_ts)
+            .min_by(|a, b| {
+                a.elevation
+                    .total_cmp(&b.elevation)
+                    .then(b.start_time.total_cmp(&a.start_time))
+            })
+            .map(|s| s.elevation_number)
+            .or_else(|| available_elevations.first().copied()),
     }
 }
 
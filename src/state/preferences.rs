@@ -1,11 +1,12 @@
 //! User preferences persisted to localStorage.
 //!
-//! Covers playback speed, visualization settings, and layer visibility.
+//! Covers playback speed, visualization settings, layer visibility, and color
+//! tables.
 //! Loaded on startup, saved automatically when changes are detected.
 
 use serde::{Deserialize, Serialize};
 
-use super::{AppState, ElevationSelection, InterpolationMode, PlaybackSpeed};
+use super::{AppState, ElevationSelection, InterpolationMode, PaletteSettings, PlaybackSpeed};
 
 /// User preferences that persist across page reloads.
 #[derive(Clone, PartialEq, Serialize, Deserialize)]
@@ -14,6 +15,11 @@ pub struct UserPreferences {
     pub speed: PlaybackSpeed,
     #[serde(default)]
     pub elevation_auto: bool,
+    /// Lowest-available composite mode; takes precedence over `elevation_auto`.
+    #[serde(default)]
+    pub elevation_lowest_available: bool,
+    #[serde(default = "default_lowest_available_mins")]
+    pub lowest_available_mins: u32,
     #[serde(default = "default_elevation_angle")]
     pub preferred_elevation_angle: f32,
     #[serde(default = "default_true")]
@@ -46,6 +52,13 @@ pub struct UserPreferences {
     pub sweep_animation: bool,
     #[serde(default = "default_true")]
     pub data_age_desaturation: bool,
+    #[serde(default)]
+    pub frame_interpolation: bool,
+    #[serde(default)]
+    pub dealias_velocity: bool,
+    /// Selected and imported `.pal` color tables.
+    #[serde(default)]
+    pub palettes: PaletteSettings,
 
     /// Mobile UI override: `None` = auto, `Some(true)` = force mobile,
     /// `Some(false)` = force desktop.
@@ -61,6 +74,10 @@ fn default_elevation_angle() -> f32 {
     0.5
 }
 
+fn default_lowest_available_mins() -> u32 {
+    10
+}
+
 fn default_opacity() -> f32 {
     1.0
 }
@@ -70,6 +87,8 @@ impl Default for UserPreferences {
         Self {
             speed: PlaybackSpeed::default(),
             elevation_auto: false,
+            elevation_lowest_available: false,
+            lowest_available_mins: default_lowest_available_mins(),
             preferred_elevation_angle: 0.5,
             layer_states: true,
             layer_counties: true,
@@ -84,6 +103,9 @@ impl Default for UserPreferences {
             opacity: 1.0,
             sweep_animation: false,
             data_age_desaturation: true,
+            frame_interpolation: false,
+            dealias_velocity: false,
+            palettes: PaletteSettings::default(),
             mobile_override: None,
         }
     }
@@ -97,6 +119,8 @@ impl UserPreferences {
         Self {
             speed: state.playback_state.speed,
             elevation_auto: state.viz_state.elevation_selection.is_auto(),
+            elevation_lowest_available: state.viz_state.elevation_selection.is_lowest_available(),
+            lowest_available_mins: state.viz_state.lowest_available_mins,
             preferred_elevation_angle: state.viz_state.elevation_selection.angle(),
             layer_states: state.layer_state.geo.states,
             layer_counties: state.layer_state.geo.counties,
@@ -111,6 +135,9 @@ impl UserPreferences {
             opacity: state.render_processing.opacity,
             sweep_animation: state.render_processing.sweep_animation,
             data_age_desaturation: state.render_processing.data_age_desaturation,
+            frame_interpolation: state.render_processing.frame_interpolation,
+            dealias_velocity: state.render_processing.dealias_velocity,
+            palettes: state.palettes.clone(),
             mobile_override: state.mobile_override,
         }
     }
@@ -118,7 +145,10 @@ impl UserPreferences {
     /// Apply loaded preferences to application state.
     pub fn apply_to(&self, state: &mut AppState) {
         state.playback_state.speed = self.speed;
-        if self.elevation_auto {
+        state.viz_state.lowest_available_mins = self.lowest_available_mins.max(1);
+        if self.elevation_lowest_available {
+            state.viz_state.elevation_selection = ElevationSelection::LowestAvailable;
+        } else if self.elevation_auto {
             state.viz_state.elevation_selection = ElevationSelection::Latest;
         } else {
             state.viz_state.elevation_selection = ElevationSelection::Fixed {
@@ -140,26 +170,19 @@ impl UserPreferences {
         state.render_processing.opacity = self.opacity;
         state.render_processing.sweep_animation = self.sweep_animation;
         state.render_processing.data_age_desaturation = self.data_age_desaturation;
+        state.render_processing.frame_interpolation = self.frame_interpolation;
+        state.render_processing.dealias_velocity = self.dealias_velocity;
+        state.palettes = self.palettes.clone();
         state.mobile_override = self.mobile_override;
     }
 
     /// Load preferences from localStorage.
     pub fn load() -> Self {
-        let window = match web_sys::window() {
-            Some(w) => w,
+        let json = match crate::platform::storage_get(Self::STORAGE_KEY) {
+            Some(s) => s,
             None => return Self::default(),
         };
 
-        let storage = match window.local_storage() {
-            Ok(Some(s)) => s,
-            _ => return Self::default(),
-        };
-
-        let json = match storage.get_item(Self::STORAGE_KEY) {
-            Ok(Some(s)) => s,
-            _ => return Self::default(),
-        };
-
         match serde_json::from_str(&json) {
             Ok(prefs) => {
                 log::debug!("Loaded user preferences from localStorage");
@@ -174,16 +197,6 @@ impl UserPreferences {
 
     /// Save preferences to localStorage.
     pub fn save(&self) {
-        let window = match web_sys::window() {
-            Some(w) => w,
-            None => return,
-        };
-
-        let storage = match window.local_storage() {
-            Ok(Some(s)) => s,
-            _ => return,
-        };
-
         let json = match serde_json::to_string(self) {
             Ok(s) => s,
             Err(e) => {
@@ -192,8 +205,8 @@ impl UserPreferences {
             }
         };
 
-        if let Err(e) = storage.set_item(Self::STORAGE_KEY, &json) {
-            log::warn!("Failed to save user preferences: {:?}", e);
+        if let Err(e) = crate::platform::storage_set(Self::STORAGE_KEY, &json) {
+            log::warn!("Failed to save user preferences: {}", e);
         }
     }
 }
@@ -110,6 +110,27 @@ impl Scan {
             .find(|(_, sweep)| ts >= sweep.start_time && ts <= sweep.end_time)
     }
 
+    /// Whether this scan is known to have no stored `product` at
+    /// `elevation_number`. Unknown sweep or product lists (metadata-only
+    /// scans, legacy index entries) count as available so callers fall
+    /// through to the worker rather than blanking.
+    pub fn lacks_sweep(&self, elevation_number: u8, product: &str) -> bool {
+        if self.sweeps.is_empty() {
+            return false;
+        }
+        match self
+            .sweeps
+            .iter()
+            .find(|s| s.elevation_number == elevation_number)
+        {
+            None => true,
+            Some(s) => {
+                !s.available_products.is_empty()
+                    && !s.available_products.iter().any(|p| p == product)
+            }
+        }
+    }
+
     /// Calculate scan progress as a percentage (0.0 to 1.0)
     pub fn progress_at_timestamp(&self, ts: f64) -> Option<f32> {
         if ts < self.start_time || ts > self.end_time {
@@ -357,6 +378,21 @@ impl RadarTimeline {
         times
     }
 
+    /// Find the scan whose sweep of `elevation_number` ends at `end_time`
+    /// (a macro playback frame).
+    pub fn find_scan_with_sweep_ending(
+        &self,
+        end_time: f64,
+        elevation_number: u8,
+    ) -> Option<&Scan> {
+        let idx = self.scans.partition_point(|s| s.start_time <= end_time);
+        self.scans[..idx].iter().rev().find(|scan| {
+            scan.sweeps.iter().any(|s| {
+                s.elevation_number == elevation_number && (s.end_time - end_time).abs() < 0.01
+            })
+        })
+    }
+
     /// Find the end time of the next sweep matching `elevation_number` after `ts`.
     pub fn next_matching_sweep_end_by_number(&self, ts: f64, elevation_number: u8) -> Option<f64> {
         for scan in &self.scans {
@@ -547,6 +583,20 @@ mod tests {
         }
     }
 
+    #[test]
+    fn lacks_sweep_only_when_known_missing() {
+        let mut split_cut = sweep(0.0, 10.0, 0.5, 1);
+        split_cut.available_products = vec!["reflectivity".to_string()];
+        let legacy = sweep(10.0, 20.0, 0.9, 2);
+        let s = scan_with_sweeps(0.0, 20.0, vec![split_cut, legacy]);
+
+        assert!(!s.lacks_sweep(1, "reflectivity"));
+        assert!(s.lacks_sweep(1, "velocity"));
+        assert!(!s.lacks_sweep(2, "velocity"));
+        assert!(s.lacks_sweep(3, "reflectivity"));
+        assert!(!scan(0.0, 20.0).lacks_sweep(3, "velocity"));
+    }
+
     // --- TimeRange tests ---
 
     #[test]
@@ -834,6 +884,29 @@ mod tests {
         assert_eq!(times, vec![1010.0, 1020.0, 1030.0, 1040.0, 1310.0, 1320.0]);
     }
 
+    #[test]
+    fn find_scan_with_sweep_ending_matches_frame() {
+        let tl = RadarTimeline {
+            scans: vec![
+                scan_with_sweeps(
+                    1000.0,
+                    1040.0,
+                    vec![sweep(1000.0, 1010.0, 0.5, 1), sweep(1010.0, 1020.0, 0.9, 2)],
+                ),
+                scan_with_sweeps(
+                    1300.0,
+                    1340.0,
+                    vec![sweep(1300.0, 1310.0, 0.5, 1), sweep(1310.0, 1320.0, 0.9, 2)],
+                ),
+            ],
+        };
+        let scan = tl.find_scan_with_sweep_ending(1310.0, 1).unwrap();
+        assert_eq!(scan.key_timestamp, 1300.0);
+        let scan = tl.find_scan_with_sweep_ending(1020.0, 2).unwrap();
+        assert_eq!(scan.key_timestamp, 1000.0);
+        assert!(tl.find_scan_with_sweep_ending(1020.0, 1).is_none());
+    }
+
     #[test]
     fn matching_sweep_end_times_empty() {
         let tl = RadarTimeline { scans: vec![] };
@@ -19,6 +19,10 @@ pub struct SavedEvent {
     pub start_time: f64,
     /// Event end time (Unix seconds).
     pub end_time: f64,
+    /// Z-R relationship and KDP blending used for this event's rainfall
+    /// accumulations. `None` until changed while the event is selected.
+    #[serde(default)]
+    pub qpe_settings: Option<crate::nexrad::qpe::QpeSettings>,
 }
 
 /// Collection of saved events, persisted to localStorage.
@@ -33,21 +37,11 @@ impl SavedEvents {
 
     /// Load saved events from localStorage.
     pub fn load() -> Self {
-        let window = match web_sys::window() {
-            Some(w) => w,
+        let json = match crate::platform::storage_get(Self::STORAGE_KEY) {
+            Some(s) => s,
             None => return Self::default(),
         };
 
-        let storage = match window.local_storage() {
-            Ok(Some(s)) => s,
-            _ => return Self::default(),
-        };
-
-        let json = match storage.get_item(Self::STORAGE_KEY) {
-            Ok(Some(s)) => s,
-            _ => return Self::default(),
-        };
-
         match serde_json::from_str(&json) {
             Ok(events) => {
                 log::debug!("Loaded saved events from localStorage");
@@ -62,16 +56,6 @@ impl SavedEvents {
 
     /// Save events to localStorage.
     pub fn save(&self) {
-        let window = match web_sys::window() {
-            Some(w) => w,
-            None => return,
-        };
-
-        let storage = match window.local_storage() {
-            Ok(Some(s)) => s,
-            _ => return,
-        };
-
         let json = match serde_json::to_string(self) {
             Ok(s) => s,
             Err(e) => {
@@ -80,20 +64,21 @@ impl SavedEvents {
             }
         };
 
-        if let Err(e) = storage.set_item(Self::STORAGE_KEY, &json) {
-            log::warn!("Failed to save events: {:?}", e);
+        if let Err(e) = crate::platform::storage_set(Self::STORAGE_KEY, &json) {
+            log::warn!("Failed to save events: {}", e);
         }
     }
 
     /// Add a new event and persist immediately.
     pub fn add(&mut self, name: String, site_id: String, start_time: f64, end_time: f64) {
-        let id = js_sys::Date::now() as u64;
+        let id = crate::platform::now_ms() as u64;
         self.events.push(SavedEvent {
             id,
             name,
             site_id,
             start_time,
             end_time,
+            qpe_settings: None,
         });
         self.save();
     }
@@ -113,4 +98,21 @@ impl SavedEvents {
             self.save();
         }
     }
+
+    /// Store an event's rainfall accumulation settings and persist immediately.
+    pub fn set_qpe_settings(&mut self, id: u64, settings: crate::nexrad::qpe::QpeSettings) {
+        if let Some(event) = self.events.iter_mut().find(|e| e.id == id) {
+            event.qpe_settings = Some(settings);
+            self.save();
+        }
+    }
+
+    /// The event for `site_id` whose bounds match `range` (within a second).
+    pub fn matching(&self, site_id: &str, range: (f64, f64)) -> Option<&SavedEvent> {
+        self.events.iter().find(|e| {
+            e.site_id == site_id
+                && (e.start_time - range.0).abs() < 1.0
+                && (e.end_time - range.1).abs() < 1.0
+        })
+    }
 }
@@ -1,7 +1,10 @@
-//! Storage settings for cache management.
+//! Storage settings for cache management, where data is fetched from, and
+//! color table choices.
 //!
 //! Settings are persisted to localStorage so they survive page reloads.
 
+use std::collections::BTreeMap;
+
 use serde::{Deserialize, Serialize};
 
 /// Storage quota and eviction settings.
@@ -34,21 +37,11 @@ impl StorageSettings {
 
     /// Load settings from localStorage.
     pub fn load() -> Self {
-        let window = match web_sys::window() {
-            Some(w) => w,
+        let json = match crate::platform::storage_get(Self::STORAGE_KEY) {
+            Some(s) => s,
             None => return Self::default(),
         };
 
-        let storage = match window.local_storage() {
-            Ok(Some(s)) => s,
-            _ => return Self::default(),
-        };
-
-        let json = match storage.get_item(Self::STORAGE_KEY) {
-            Ok(Some(s)) => s,
-            _ => return Self::default(),
-        };
-
         match serde_json::from_str(&json) {
             Ok(settings) => {
                 log::debug!("Loaded storage settings from localStorage");
@@ -63,16 +56,6 @@ impl StorageSettings {
 
     /// Save settings to localStorage.
     pub fn save(&self) {
-        let window = match web_sys::window() {
-            Some(w) => w,
-            None => return,
-        };
-
-        let storage = match window.local_storage() {
-            Ok(Some(s)) => s,
-            _ => return,
-        };
-
         let json = match serde_json::to_string(self) {
             Ok(s) => s,
             Err(e) => {
@@ -81,8 +64,8 @@ impl StorageSettings {
             }
         };
 
-        if let Err(e) = storage.set_item(Self::STORAGE_KEY, &json) {
-            log::warn!("Failed to save storage settings: {:?}", e);
+        if let Err(e) = crate::platform::storage_set(Self::STORAGE_KEY, &json) {
+            log::warn!("Failed to save storage settings: {}", e);
         } else {
             log::debug!("Saved storage settings to localStorage");
         }
@@ -111,3 +94,172 @@ pub fn format_bytes(bytes: u64) -> String {
         format!("{} B", bytes)
     }
 }
+
+/// Where Level II data is fetched from. See [`crate::nexrad::data_source`].
+///
+/// Persisted to localStorage like [`StorageSettings`].
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DataSourceSettings {
+    /// Base URL of an S3-compatible copy of the archive bucket. Empty means
+    /// the public AWS bucket.
+    #[serde(default)]
+    pub archive_url: String,
+    /// Base URL of an S3-compatible copy of the real-time chunk bucket.
+    /// Empty means the public AWS bucket.
+    #[serde(default)]
+    pub realtime_url: String,
+    /// Base URL of an S3-compatible copy of the Level III product bucket.
+    /// Empty means the public AWS bucket.
+    #[serde(default)]
+    pub level3_url: String,
+    /// When set, live mode streams a replay of archive volumes instead of
+    /// the real-time bucket.
+    #[serde(default)]
+    pub replay: Option<ReplaySettings>,
+}
+
+/// Clock of a simulated real-time feed.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ReplaySettings {
+    /// Archive time the replay starts from, Unix seconds.
+    pub start_secs: i64,
+    /// Archive seconds replayed per wall-clock second.
+    pub speed: f64,
+}
+
+impl DataSourceSettings {
+    const STORAGE_KEY: &'static str = "nexrad_data_source";
+
+    /// Whether anything differs from the public AWS buckets.
+    pub fn is_custom(&self) -> bool {
+        *self != Self::default()
+    }
+
+    /// Load settings from localStorage.
+    pub fn load() -> Self {
+        let Some(json) = crate::platform::storage_get(Self::STORAGE_KEY) else {
+            return Self::default();
+        };
+        serde_json::from_str(&json).unwrap_or_else(|e| {
+            log::warn!("Failed to parse data source settings: {}", e);
+            Self::default()
+        })
+    }
+
+    /// Save settings to localStorage.
+    pub fn save(&self) {
+        match serde_json::to_string(self) {
+            Ok(json) => {
+                if let Err(e) = crate::platform::storage_set(Self::STORAGE_KEY, &json) {
+                    log::warn!("Failed to save data source settings: {}", e);
+                }
+            }
+            Err(e) => log::warn!("Failed to serialize data source settings: {}", e),
+        }
+    }
+}
+
+/// Color table choices. See [`crate::nexrad::palette`].
+///
+/// Persisted with [`super::UserPreferences`].
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PaletteSettings {
+    /// Palette name per moment (worker product string). Moments without an
+    /// entry use the built-in table.
+    #[serde(default)]
+    pub selected: BTreeMap<String, String>,
+    /// Palettes imported from `.pal` files.
+    #[serde(default)]
+    pub imported: Vec<ImportedPalette>,
+}
+
+/// A user `.pal` file, kept as source so it survives a reload.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ImportedPalette {
+    /// File stem, unique per moment.
+    pub name: String,
+    /// Worker product string of the moment it colors.
+    pub moment: String,
+    pub source: String,
+}
+
+impl PaletteSettings {
+    /// Validate and store a `.pal` file, replacing an import of the same
+    /// name, and select it. The moment comes from the file's `Product:`
+    /// line, or `fallback_moment` when that is missing or unknown. Returns
+    /// the palette name and moment.
+    pub fn import(
+        &mut self,
+        file_name: &str,
+        source: &str,
+        fallback_moment: Option<&'static str>,
+    ) -> Result<(String, &'static str), String> {
+        use crate::nexrad::palette::{moment_for_code, Palette};
+
+        let name = file_name
+            .rsplit_once('.')
+            .map_or(file_name, |(stem, _)| stem)
+            .to_string();
+        let palette = Palette::parse(&name, source)?;
+        let moment = palette
+            .product
+            .as_deref()
+            .and_then(moment_for_code)
+            .or(fallback_moment)
+            .ok_or("no recognised Product line")?;
+
+        self.imported
+            .retain(|p| !(p.moment == moment && p.name == name));
+        self.imported.push(ImportedPalette {
+            name: name.clone(),
+            moment: moment.to_string(),
+            source: source.to_string(),
+        });
+        self.selected.insert(moment.to_string(), name.clone());
+        Ok((name, moment))
+    }
+
+    /// Forget an imported palette, deselecting it if it was in use.
+    pub fn remove(&mut self, moment: &str, name: &str) {
+        self.imported
+            .retain(|p| !(p.moment == moment && p.name == name));
+        if self.selected.get(moment).map(String::as_str) == Some(name) {
+            self.selected.remove(moment);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PAL: &str = "Product: BV\nColor: 0 0 0 0\n";
+
+    #[test]
+    fn import_selects_the_palette_for_its_product() {
+        let mut settings = PaletteSettings::default();
+        let (name, moment) = settings.import("mine.pal", PAL, None).unwrap();
+        assert_eq!((name.as_str(), moment), ("mine", "velocity"));
+        assert_eq!(settings.selected["velocity"], "mine");
+
+        // Re-importing the same file replaces it.
+        settings.import("mine.pal", PAL, None).unwrap();
+        assert_eq!(settings.imported.len(), 1);
+
+        settings.remove("velocity", "mine");
+        assert!(settings.imported.is_empty());
+        assert!(settings.selected.is_empty());
+    }
+
+    #[test]
+    fn import_falls_back_to_the_given_moment() {
+        let mut settings = PaletteSettings::default();
+        let source = "Color: 0 0 0 0";
+        assert!(settings.import("a.pal", source, None).is_err());
+        let (_, moment) = settings
+            .import("a.pal", source, Some("reflectivity"))
+            .unwrap();
+        assert_eq!(moment, "reflectivity");
+        assert!(settings.import("b.pal", "Product: BR", None).is_err());
+    }
+}
@@ -39,7 +39,7 @@ impl PipelineStatus {
         if last_done_ms <= 0.0 {
             return false;
         }
-        let now = js_sys::Date::now();
+        let now = crate::platform::now_ms();
         (now - last_done_ms) < Self::LINGER_MS
     }
 
@@ -53,7 +53,7 @@ impl PipelineStatus {
             return true;
         }
         // Show if any group completed recently
-        let now = js_sys::Date::now();
+        let now = crate::platform::now_ms();
         (now - self.last_download_done_ms) < Self::LINGER_MS
             || (now - self.last_processing_done_ms) < Self::LINGER_MS
             || (now - self.last_render_done_ms) < Self::LINGER_MS
@@ -62,14 +62,14 @@ impl PipelineStatus {
     /// Mark processing phase as completed (ingest + decode finished).
     pub fn mark_processing_done(&mut self) {
         self.processing = false;
-        self.last_processing_done_ms = js_sys::Date::now();
+        self.last_processing_done_ms = crate::platform::now_ms();
         self.ever_active = true;
     }
 
     /// Mark rendering phase as completed (GPU upload finished).
     pub fn mark_render_done(&mut self) {
         self.rendering = false;
-        self.last_render_done_ms = js_sys::Date::now();
+        self.last_render_done_ms = crate::platform::now_ms();
         self.ever_active = true;
     }
 }
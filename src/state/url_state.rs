@@ -92,9 +92,13 @@ pub fn parse_from_url() -> UrlParams {
         view: ViewState::default(),
     };
 
+    #[cfg(target_arch = "wasm32")]
     let Ok(search) = web_sys::window().expect("no window").location().search() else {
         return params;
     };
+    // The desktop build has no URL to restore from.
+    #[cfg(not(target_arch = "wasm32"))]
+    let search = String::new();
 
     let query = search.trim_start_matches('?');
     if query.is_empty() {
@@ -126,6 +130,7 @@ pub fn parse_from_url() -> UrlParams {
 }
 
 /// Push current state to the URL query string using `replaceState`.
+#[cfg(target_arch = "wasm32")]
 pub fn push_to_url(site: &str, time: f64, product: &str, lat: f64, lon: f64, view: &ViewState) {
     let v_json = serde_json::to_vec(view).unwrap_or_default();
     let v_b64 = base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(&v_json);
@@ -139,3 +144,15 @@ pub fn push_to_url(site: &str, time: f64, product: &str, lat: f64, lon: f64, vie
     let history = window.history().expect("no history");
     let _ = history.replace_state_with_url(&wasm_bindgen::JsValue::NULL, "", Some(&query));
 }
+
+/// The desktop build has no URL to keep in sync.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn push_to_url(
+    _site: &str,
+    _time: f64,
+    _product: &str,
+    _lat: f64,
+    _lon: f64,
+    _view: &ViewState,
+) {
+}
@@ -1,19 +1,40 @@
 //! Visualization state (canvas, zoom/pan, product selection).
 
-use crate::geo::GlobeCamera;
-use eframe::egui::Vec2;
+use crate::geo::{GlobeCamera, KM_PER_DEG};
+use crate::nexrad::level3::Level3Product;
+use eframe::egui::{Rect, Vec2};
 
 /// Available radar products for display.
-#[derive(Default, Clone, Copy, PartialEq, Eq)]
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq)]
 pub enum RadarProduct {
     #[default]
     Reflectivity,
     Velocity,
+    /// Velocity unfolded during ingest (only stored when dealiasing is on).
+    DealiasedVelocity,
+    /// Velocity with the storm motion's radial component removed (SRM).
+    StormRelativeVelocity,
     SpectrumWidth,
     DifferentialReflectivity,
     CorrelationCoefficient,
     DifferentialPhase,
     ClutterFilterPower,
+    /// Fuzzy-logic hydrometeor classification from the dual-pol moments.
+    HydrometeorClass,
+    /// Maximum reflectivity over all tilts of the volume.
+    CompositeReflectivity,
+    /// Highest beam height reaching 18 / 30 / 50 dBZ.
+    EchoTops18,
+    EchoTops30,
+    EchoTops50,
+    /// Vertically integrated liquid.
+    Vil,
+    /// Rainfall accumulations from cached lowest-tilt reflectivity.
+    Qpe1Hour,
+    Qpe3Hour,
+    QpeStormTotal,
+    /// An RPG product fetched from the Level III archive.
+    Level3(Level3Product),
 }
 
 impl RadarProduct {
@@ -21,11 +42,23 @@ impl RadarProduct {
         match self {
             RadarProduct::Reflectivity => "Reflectivity",
             RadarProduct::Velocity => "Velocity",
+            RadarProduct::DealiasedVelocity => "Velocity (Dealiased)",
+            RadarProduct::StormRelativeVelocity => "Storm-Relative Velocity",
             RadarProduct::SpectrumWidth => "Spectrum Width",
             RadarProduct::DifferentialReflectivity => "Differential Reflectivity",
             RadarProduct::CorrelationCoefficient => "Correlation Coefficient",
             RadarProduct::DifferentialPhase => "Differential Phase",
             RadarProduct::ClutterFilterPower => "Clutter Filter Power",
+            RadarProduct::HydrometeorClass => "Hydrometeor Classification",
+            RadarProduct::CompositeReflectivity => "Composite Reflectivity",
+            RadarProduct::EchoTops18 => "Echo Tops (18 dBZ)",
+            RadarProduct::EchoTops30 => "Echo Tops (30 dBZ)",
+            RadarProduct::EchoTops50 => "Echo Tops (50 dBZ)",
+            RadarProduct::Vil => "Vertically Integrated Liquid",
+            RadarProduct::Qpe1Hour => "1-Hour Precipitation",
+            RadarProduct::Qpe3Hour => "3-Hour Precipitation",
+            RadarProduct::QpeStormTotal => "Storm Total Precipitation",
+            RadarProduct::Level3(product) => product.label(),
         }
     }
 
@@ -34,11 +67,21 @@ impl RadarProduct {
         match self {
             RadarProduct::Reflectivity => "dBZ",
             RadarProduct::Velocity => "m/s",
+            RadarProduct::DealiasedVelocity => "m/s",
+            RadarProduct::StormRelativeVelocity => "m/s",
             RadarProduct::SpectrumWidth => "m/s",
             RadarProduct::DifferentialReflectivity => "dB",
             RadarProduct::CorrelationCoefficient => "",
             RadarProduct::DifferentialPhase => "\u{00B0}/km",
             RadarProduct::ClutterFilterPower => "dB",
+            RadarProduct::HydrometeorClass => "",
+            RadarProduct::CompositeReflectivity => "dBZ",
+            RadarProduct::EchoTops18 | RadarProduct::EchoTops30 | RadarProduct::EchoTops50 => {
+                "km MSL"
+            }
+            RadarProduct::Vil => "kg/m\u{00B2}",
+            RadarProduct::Qpe1Hour | RadarProduct::Qpe3Hour | RadarProduct::QpeStormTotal => "mm",
+            RadarProduct::Level3(product) => product.unit(),
         }
     }
 
@@ -47,11 +90,23 @@ impl RadarProduct {
         match self {
             RadarProduct::Reflectivity => "REF",
             RadarProduct::Velocity => "VEL",
+            RadarProduct::DealiasedVelocity => "DVEL",
+            RadarProduct::StormRelativeVelocity => "SRM",
             RadarProduct::SpectrumWidth => "SW",
             RadarProduct::DifferentialReflectivity => "ZDR",
             RadarProduct::CorrelationCoefficient => "CC",
             RadarProduct::DifferentialPhase => "KDP",
             RadarProduct::ClutterFilterPower => "CFP",
+            RadarProduct::HydrometeorClass => "HCA",
+            RadarProduct::CompositeReflectivity => "CREF",
+            RadarProduct::EchoTops18 => "ET18",
+            RadarProduct::EchoTops30 => "ET30",
+            RadarProduct::EchoTops50 => "ET50",
+            RadarProduct::Vil => "VIL",
+            RadarProduct::Qpe1Hour => "OHP",
+            RadarProduct::Qpe3Hour => "THP",
+            RadarProduct::QpeStormTotal => "STP",
+            RadarProduct::Level3(product) => product.code(),
         }
     }
 
@@ -60,12 +115,23 @@ impl RadarProduct {
         match code {
             "REF" => Some(RadarProduct::Reflectivity),
             "VEL" => Some(RadarProduct::Velocity),
+            "DVEL" => Some(RadarProduct::DealiasedVelocity),
+            "SRM" => Some(RadarProduct::StormRelativeVelocity),
             "SW" => Some(RadarProduct::SpectrumWidth),
             "ZDR" => Some(RadarProduct::DifferentialReflectivity),
             "CC" => Some(RadarProduct::CorrelationCoefficient),
             "KDP" => Some(RadarProduct::DifferentialPhase),
             "CFP" => Some(RadarProduct::ClutterFilterPower),
-            _ => None,
+            "HCA" => Some(RadarProduct::HydrometeorClass),
+            "CREF" => Some(RadarProduct::CompositeReflectivity),
+            "ET18" => Some(RadarProduct::EchoTops18),
+            "ET30" => Some(RadarProduct::EchoTops30),
+            "ET50" => Some(RadarProduct::EchoTops50),
+            "VIL" => Some(RadarProduct::Vil),
+            "OHP" => Some(RadarProduct::Qpe1Hour),
+            "THP" => Some(RadarProduct::Qpe3Hour),
+            "STP" => Some(RadarProduct::QpeStormTotal),
+            _ => Level3Product::from_code(code).map(RadarProduct::Level3),
         }
     }
 
@@ -73,11 +139,33 @@ impl RadarProduct {
         &[
             RadarProduct::Reflectivity,
             RadarProduct::Velocity,
+            RadarProduct::DealiasedVelocity,
+            RadarProduct::StormRelativeVelocity,
             RadarProduct::SpectrumWidth,
             RadarProduct::DifferentialReflectivity,
             RadarProduct::CorrelationCoefficient,
             RadarProduct::DifferentialPhase,
             RadarProduct::ClutterFilterPower,
+            RadarProduct::HydrometeorClass,
+            RadarProduct::CompositeReflectivity,
+            RadarProduct::EchoTops18,
+            RadarProduct::EchoTops30,
+            RadarProduct::EchoTops50,
+            RadarProduct::Vil,
+            RadarProduct::Qpe1Hour,
+            RadarProduct::Qpe3Hour,
+            RadarProduct::QpeStormTotal,
+            RadarProduct::Level3(Level3Product::N0q),
+            RadarProduct::Level3(Level3Product::N0u),
+            RadarProduct::Level3(Level3Product::N0c),
+            RadarProduct::Level3(Level3Product::N0x),
+            RadarProduct::Level3(Level3Product::Dvl),
+            RadarProduct::Level3(Level3Product::Eet),
+            RadarProduct::Level3(Level3Product::Ntp),
+            RadarProduct::Level3(Level3Product::Ncr),
+            RadarProduct::Level3(Level3Product::Tz0),
+            RadarProduct::Level3(Level3Product::Tv0),
+            RadarProduct::Level3(Level3Product::Tzl),
         ]
     }
 
@@ -86,16 +174,61 @@ impl RadarProduct {
         match self {
             RadarProduct::Reflectivity => "reflectivity",
             RadarProduct::Velocity => "velocity",
+            RadarProduct::DealiasedVelocity => "dealiased_velocity",
+            // Motion is removed at render time from the base velocity sweep.
+            RadarProduct::StormRelativeVelocity => "velocity",
             RadarProduct::SpectrumWidth => "spectrum_width",
             RadarProduct::DifferentialReflectivity => "differential_reflectivity",
             RadarProduct::CorrelationCoefficient => "correlation_coefficient",
             RadarProduct::DifferentialPhase => "differential_phase",
-            RadarProduct::ClutterFilterPower => "reflectivity", // fallback
+            RadarProduct::ClutterFilterPower => "clutter_filter_power",
+            RadarProduct::HydrometeorClass => "hydrometeor_class",
+            RadarProduct::CompositeReflectivity => "composite_reflectivity",
+            RadarProduct::EchoTops18 => "echo_tops_18",
+            RadarProduct::EchoTops30 => "echo_tops_30",
+            RadarProduct::EchoTops50 => "echo_tops_50",
+            RadarProduct::Vil => "vil",
+            RadarProduct::Qpe1Hour => "qpe_1h",
+            RadarProduct::Qpe3Hour => "qpe_3h",
+            RadarProduct::QpeStormTotal => "qpe_storm_total",
+            RadarProduct::Level3(product) => product.worker_string(),
         }
     }
+
+    /// Whether this product is derived from the whole volume rather than a
+    /// single tilt. Volume products are stored once per scan at its lowest
+    /// elevation, so elevation selection does not apply to them. Level III
+    /// products are filed the same way.
+    pub fn is_volume_product(self) -> bool {
+        crate::nexrad::derived::is_volume_product(self.to_worker_string()) || self.is_level3()
+    }
+
+    /// Whether this product comes from the Level III archive.
+    pub fn is_level3(self) -> bool {
+        matches!(self, RadarProduct::Level3(_))
+    }
+
+    /// Whether this is a rainfall accumulation. These are computed on
+    /// demand from the lowest tilt of several cached scans rather than read
+    /// from a single sweep.
+    pub fn is_qpe_product(self) -> bool {
+        crate::nexrad::qpe::is_qpe_product(self.to_worker_string())
+    }
+
+    /// Whether this is a radial velocity product (raw, dealiased, or
+    /// storm-relative).
+    pub fn is_velocity(self) -> bool {
+        matches!(
+            self,
+            RadarProduct::Velocity
+                | RadarProduct::DealiasedVelocity
+                | RadarProduct::StormRelativeVelocity
+        )
+    }
 }
 
-/// User's elevation selection — by specific VCP cut or auto (latest) mode.
+/// User's elevation selection — by specific VCP cut, auto (latest) mode or
+/// the lowest-available composite.
 #[derive(Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 pub enum ElevationSelection {
     /// A specific VCP elevation number. The f32 is the angle at time of
@@ -103,6 +236,9 @@ pub enum ElevationSelection {
     Fixed { elevation_number: u8, angle: f32 },
     /// Auto: show the most recently completed sweep (any elevation).
     Latest,
+    /// Composite: each gate shows the newest valid data from the lowest
+    /// tilt collected in the last `VizState::lowest_available_mins`.
+    LowestAvailable,
 }
 
 impl Default for ElevationSelection {
@@ -119,19 +255,23 @@ impl ElevationSelection {
         matches!(self, ElevationSelection::Latest)
     }
 
+    pub fn is_lowest_available(&self) -> bool {
+        matches!(self, ElevationSelection::LowestAvailable)
+    }
+
     pub fn elevation_number(&self) -> Option<u8> {
         match self {
             ElevationSelection::Fixed {
                 elevation_number, ..
             } => Some(*elevation_number),
-            ElevationSelection::Latest => None,
+            ElevationSelection::Latest | ElevationSelection::LowestAvailable => None,
         }
     }
 
     pub fn angle(&self) -> f32 {
         match self {
             ElevationSelection::Fixed { angle, .. } => *angle,
-            ElevationSelection::Latest => 0.5,
+            ElevationSelection::Latest | ElevationSelection::LowestAvailable => 0.5,
         }
     }
 
@@ -202,6 +342,10 @@ pub struct RenderProcessing {
     pub sweep_animation: bool,
     /// Whether data age desaturation is shown (desaturates oldest data behind sweep line).
     pub data_age_desaturation: bool,
+    /// Whether macro playback shows motion-compensated frames between scans.
+    pub frame_interpolation: bool,
+    /// Whether ingest also stores a dealiased copy of each velocity sweep.
+    pub dealias_velocity: bool,
 }
 
 impl Default for RenderProcessing {
@@ -211,6 +355,8 @@ impl Default for RenderProcessing {
             opacity: 1.0,
             sweep_animation: false,
             data_age_desaturation: true,
+            frame_interpolation: false,
+            dealias_velocity: false,
         }
     }
 }
@@ -225,6 +371,84 @@ pub enum ViewMode {
     Globe3D,
 }
 
+/// Split-view arrangement of the flat map. Every panel shares the camera
+/// and playback position; the first panel shows the main product selection.
+#[derive(Default, Clone, Copy, PartialEq, Eq)]
+pub enum PanelLayout {
+    #[default]
+    Single,
+    /// Two panels side by side.
+    Split1x2,
+    /// Four panels in a 2×2 grid.
+    Grid2x2,
+}
+
+impl PanelLayout {
+    pub fn label(&self) -> &'static str {
+        match self {
+            PanelLayout::Single => "Single",
+            PanelLayout::Split1x2 => "1\u{00D7}2",
+            PanelLayout::Grid2x2 => "2\u{00D7}2",
+        }
+    }
+
+    pub fn all() -> &'static [PanelLayout] {
+        &[
+            PanelLayout::Single,
+            PanelLayout::Split1x2,
+            PanelLayout::Grid2x2,
+        ]
+    }
+
+    /// Number of panels, including the main one.
+    pub fn panel_count(self) -> usize {
+        match self {
+            PanelLayout::Single => 1,
+            PanelLayout::Split1x2 => 2,
+            PanelLayout::Grid2x2 => 4,
+        }
+    }
+
+    /// Panel rectangles within `rect`, main panel first, in reading order.
+    pub fn panel_rects(self, rect: Rect) -> Vec<Rect> {
+        let (cols, rows) = match self {
+            PanelLayout::Single => (1, 1),
+            PanelLayout::Split1x2 => (2, 1),
+            PanelLayout::Grid2x2 => (2, 2),
+        };
+        let size = Vec2::new(rect.width() / cols as f32, rect.height() / rows as f32);
+        (0..rows)
+            .flat_map(|row| (0..cols).map(move |col| (row, col)))
+            .map(|(row, col)| {
+                Rect::from_min_size(
+                    rect.min + Vec2::new(col as f32 * size.x, row as f32 * size.y),
+                    size,
+                )
+            })
+            .collect()
+    }
+}
+
+/// Number of split-view panels besides the main one (a 2×2 grid uses all).
+pub const EXTRA_PANEL_COUNT: usize = 3;
+
+/// Product and tilt shown in one of the extra split-view panels.
+#[derive(Clone, Copy, PartialEq)]
+pub struct PanelView {
+    pub product: RadarProduct,
+    /// Fixed elevation number, or `None` to follow the main panel's tilt.
+    pub elevation_number: Option<u8>,
+}
+
+impl PanelView {
+    fn following(product: RadarProduct) -> Self {
+        Self {
+            product,
+            elevation_number: None,
+        }
+    }
+}
+
 /// Lightweight storm cell info for rendering on the canvas.
 #[derive(Clone, Debug)]
 #[allow(dead_code)]
@@ -253,6 +477,144 @@ pub struct StormCellInfo {
     /// Number of gates comprising the cell. Useful for debugging / further
     /// filtering.
     pub gate_count: u32,
+    /// Cross-scan identity and motion, filled in by the storm tracker.
+    /// `None` when the sweep time or elevation was unknown at detection.
+    pub track: Option<CellTrack>,
+}
+
+/// Rotation (mesocyclone) feature detected on a velocity sweep.
+#[derive(Clone, Debug)]
+pub struct RotationInfo {
+    /// ΔV-weighted centroid latitude.
+    pub lat: f64,
+    /// ΔV-weighted centroid longitude.
+    pub lon: f64,
+    /// Compass bearing (0° = N, clockwise) from radar to centroid.
+    pub bearing_from_radar_deg: f32,
+    /// Distance from radar to centroid, km.
+    pub range_from_radar_km: f32,
+    /// Rotational velocity (half the strongest gate-to-gate ΔV), m/s.
+    pub rotational_velocity_ms: f32,
+    /// Strongest inbound/outbound velocity difference, m/s.
+    pub delta_v_ms: f32,
+    /// Core diameter (distance between the velocity extrema), km.
+    pub diameter_km: f32,
+    /// Beam-centre height above radar level at the feature, km.
+    pub height_km: f32,
+    /// Peak azimuthal shear across any pattern vector, s⁻¹.
+    pub max_shear_per_s: f32,
+    /// Number of gate-level pattern vectors grouped into the feature.
+    pub pattern_vector_count: u32,
+}
+
+/// Coarse rotation strength, from rotational velocity.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RotationStrength {
+    Weak,
+    Moderate,
+    Strong,
+}
+
+impl RotationInfo {
+    /// Rotational velocity in knots, for display.
+    pub fn rotational_velocity_kt(&self) -> f32 {
+        self.rotational_velocity_ms * 1.943_844
+    }
+
+    /// Strength category. Thresholds follow the common warning-decision
+    /// rule of thumb (Vrot ≥ 30 kt moderate, ≥ 40 kt strong).
+    pub fn strength(&self) -> RotationStrength {
+        let kt = self.rotational_velocity_kt();
+        if kt >= 40.0 {
+            RotationStrength::Strong
+        } else if kt >= 30.0 {
+            RotationStrength::Moderate
+        } else {
+            RotationStrength::Weak
+        }
+    }
+}
+
+/// Forecast lead times (minutes) drawn for tracked storm cells.
+pub const STORM_FORECAST_LEAD_MINUTES: [f64; 4] = [15.0, 30.0, 45.0, 60.0];
+
+/// Track identity and history of a storm cell across consecutive scans.
+#[derive(Clone, Debug)]
+pub struct CellTrack {
+    /// Persistent track ID, stable across scans of the same site/elevation.
+    pub id: u32,
+    /// Fitted motion vector. `None` until the track spans enough scans.
+    pub motion: Option<CellMotion>,
+    /// Centroid positions `(lat, lon)` in earlier scans, oldest first.
+    pub past_positions: Vec<(f64, f64)>,
+}
+
+/// Storm motion vector.
+#[derive(Clone, Copy, Debug)]
+pub struct CellMotion {
+    /// Eastward component, km/h.
+    pub east_kmh: f32,
+    /// Northward component, km/h.
+    pub north_kmh: f32,
+    /// Speed, km/h.
+    pub speed_kmh: f32,
+    /// Direction of travel (toward), compass degrees (0° = N, clockwise).
+    pub heading_deg: f32,
+}
+
+impl CellMotion {
+    pub fn from_components(east_kmh: f32, north_kmh: f32) -> Self {
+        Self {
+            east_kmh,
+            north_kmh,
+            speed_kmh: (east_kmh * east_kmh + north_kmh * north_kmh).sqrt(),
+            heading_deg: (east_kmh.atan2(north_kmh).to_degrees() + 360.0) % 360.0,
+        }
+    }
+
+    /// Speed in knots, for display.
+    pub fn speed_kt(&self) -> f32 {
+        self.speed_kmh * 0.539957
+    }
+
+    /// Advance `(lat, lon)` along this vector for `minutes`
+    /// (equirectangular, matching the detection module).
+    pub fn extrapolate(&self, lat: f64, lon: f64, minutes: f64) -> (f64, f64) {
+        let hours = minutes / 60.0;
+        let cos_lat = lat.to_radians().cos().max(1e-6);
+        (
+            lat + self.north_kmh as f64 * hours / KM_PER_DEG,
+            lon + self.east_kmh as f64 * hours / (KM_PER_DEG * cos_lat),
+        )
+    }
+}
+
+/// A motion-compensated frame on screen in place of an observed sweep.
+#[derive(Clone, Copy, Debug)]
+pub struct InterpolatedFrame {
+    /// End time of the observed sweep the frame starts from (Unix seconds).
+    pub from_secs: f64,
+    /// End time of the observed sweep it moves towards (Unix seconds).
+    pub to_secs: f64,
+    /// How far along, 0..1.
+    pub fraction: f32,
+}
+
+impl InterpolatedFrame {
+    /// Time the frame stands for (Unix seconds).
+    pub fn valid_secs(&self) -> f64 {
+        self.from_secs + (self.to_secs - self.from_secs) * self.fraction as f64
+    }
+}
+
+/// Where the storm motion for storm-relative velocity comes from.
+#[derive(Default, Clone, Copy, PartialEq, Eq)]
+pub enum StormMotionSource {
+    /// Mean motion of tracked storm cells from the latest reflectivity scan.
+    #[default]
+    TrackedCells,
+    /// Heading and speed entered by hand.
+    Manual,
 }
 
 /// Visualization state including view controls.
@@ -281,6 +643,9 @@ pub struct VizState {
     /// Stored Fixed selection to restore when toggling off auto mode.
     pub last_fixed_selection: Option<(u8, f32)>,
 
+    /// Trailing window of the lowest-available composite, minutes.
+    pub lowest_available_mins: u32,
+
     /// Overlay info: radar site ID
     pub site_id: String,
 
@@ -310,6 +675,9 @@ pub struct VizState {
     /// Used to recompute `data_staleness_secs` every frame so the age counter ticks.
     pub rendered_sweep_end_secs: Option<f64>,
 
+    /// Mean elevation angle (degrees) of the rendered sweep.
+    pub rendered_sweep_elevation_deg: Option<f32>,
+
     /// Previous sweep info for overlay display during sweep animation.
     /// Contains (elevation_deg, start_time_secs, end_time_secs).
     pub prev_sweep_overlay: Option<(f32, f64, f64)>,
@@ -323,6 +691,9 @@ pub struct VizState {
     /// Cached last sweep line position (azimuth, start_azimuth) for between-sweep display.
     pub last_sweep_line_cache: Option<(f32, f32)>,
 
+    /// Interpolated frame on screen during macro playback, if any.
+    pub interpolated_frame: Option<InterpolatedFrame>,
+
     /// Whether 3D volumetric rendering is enabled (ray-marched volume).
     pub volume_3d_enabled: bool,
 
@@ -350,12 +721,55 @@ pub struct VizState {
     /// Cached storm cell detection results (centroid lat, lon, max dBZ, area km2).
     pub detected_storm_cells: Vec<StormCellInfo>,
 
+    /// Whether past tracks and forecast cones are drawn for storm cells.
+    pub storm_tracks_visible: bool,
+
+    /// Cross-scan tracker that assigns persistent IDs and motion to
+    /// `detected_storm_cells`.
+    pub storm_tracker: crate::nexrad::detection::StormTracker,
+
+    /// Whether rotation (mesocyclone) detection overlay is visible. Only
+    /// runs while a velocity product is displayed.
+    pub rotation_visible: bool,
+
+    /// Cached rotation detection results. `None` until detection has run on
+    /// the displayed sweep (an empty list means "ran, found nothing").
+    pub detected_rotations: Option<Vec<RotationInfo>>,
+
+    /// Storm motion source for storm-relative velocity.
+    pub storm_motion_source: StormMotionSource,
+
+    /// Manual storm motion heading (toward), compass degrees.
+    pub storm_motion_heading_deg: f32,
+
+    /// Manual storm motion speed, knots.
+    pub storm_motion_speed_kt: f32,
+
+    /// Mean motion of the tracked cells in the most recent reflectivity
+    /// detection. `None` until at least one track has a motion fit.
+    pub tracked_mean_motion: Option<CellMotion>,
+
+    /// Z-R relationship and KDP blending for rainfall accumulations.
+    pub qpe_settings: crate::nexrad::qpe::QpeSettings,
+
+    /// Split-view arrangement of the flat map.
+    pub panel_layout: PanelLayout,
+
+    /// Product and tilt of each extra panel, in layout order. Only the
+    /// first `panel_layout.panel_count() - 1` are shown.
+    pub extra_panels: [PanelView; EXTRA_PANEL_COUNT],
+
     /// Timestamp of the currently displayed scan (seconds since epoch).
     pub displayed_scan_timestamp: Option<i64>,
 
     /// Elevation number of the currently displayed sweep.
     pub displayed_sweep_elevation_number: Option<u8>,
 
+    /// The selected volume product has no stored grid for the displayed
+    /// scan, which happens for scans cached before volume products were
+    /// computed at ingest. Cleared by the next successful sweep render.
+    pub volume_product_missing: bool,
+
     /// Last observed visible map bounds in 2D mode, as
     /// `(min_lon, min_lat, max_lon, max_lat)`. Updated each frame by the
     /// canvas renderer and consumed by top-bar / modal logic that needs
@@ -375,6 +789,7 @@ impl Default for VizState {
             elevation_selection: ElevationSelection::default(),
             cached_vcp_elevations: Vec::new(),
             last_fixed_selection: None,
+            lowest_available_mins: 10,
             site_id: "KDMX".to_string(),
             timestamp: "--:--:-- UTC".to_string(),
             elevation: "-- deg".to_string(),
@@ -384,10 +799,12 @@ impl Default for VizState {
             data_staleness_start_secs: None,
             rendered_sweep_start_secs: None,
             rendered_sweep_end_secs: None,
+            rendered_sweep_elevation_deg: None,
             prev_sweep_overlay: None,
             prev_sweep_scan_timestamp: None,
             prev_sweep_elevation_number: None,
             last_sweep_line_cache: None,
+            interpolated_frame: None,
             volume_3d_enabled: false,
             volume_density_cutoff: 5.0,
             inspector_enabled: false,
@@ -397,14 +814,115 @@ impl Default for VizState {
             storm_cells_visible: false,
             storm_cell_threshold_dbz: 35.0,
             detected_storm_cells: Vec::new(),
+            storm_tracks_visible: true,
+            storm_tracker: Default::default(),
+            rotation_visible: false,
+            detected_rotations: None,
+            storm_motion_source: StormMotionSource::default(),
+            storm_motion_heading_deg: 60.0,
+            storm_motion_speed_kt: 25.0,
+            tracked_mean_motion: None,
+            qpe_settings: Default::default(),
+            panel_layout: PanelLayout::default(),
+            extra_panels: [
+                PanelView::following(RadarProduct::Velocity),
+                PanelView::following(RadarProduct::DifferentialReflectivity),
+                PanelView::following(RadarProduct::CorrelationCoefficient),
+            ],
             displayed_scan_timestamp: None,
             displayed_sweep_elevation_number: None,
+            volume_product_missing: false,
             last_visible_bounds: None,
         }
     }
 }
 
 impl VizState {
+    /// Replace the detected storm cells, running them through the cross-scan
+    /// tracker when the sweep they came from is known.
+    pub fn apply_storm_cell_detection(
+        &mut self,
+        cells: Vec<StormCellInfo>,
+        elevation_number: Option<u8>,
+        sweep_start_secs: Option<f64>,
+    ) {
+        self.detected_storm_cells = match (elevation_number, sweep_start_secs) {
+            (Some(elev), Some(start)) if start > 0.0 => self.storm_tracker.update(
+                (self.site_id.clone(), elev, self.product.short_code()),
+                start,
+                cells,
+            ),
+            _ => cells,
+        };
+
+        // Only reflectivity cells are real storm cores; thresholding other
+        // moments in dBZ would produce a meaningless mean motion.
+        if self.product == RadarProduct::Reflectivity {
+            let motions: Vec<CellMotion> = self
+                .detected_storm_cells
+                .iter()
+                .filter_map(|c| c.track.as_ref()?.motion)
+                .collect();
+            if !motions.is_empty() {
+                let n = motions.len() as f32;
+                self.tracked_mean_motion = Some(CellMotion::from_components(
+                    motions.iter().map(|m| m.east_kmh).sum::<f32>() / n,
+                    motions.iter().map(|m| m.north_kmh).sum::<f32>() / n,
+                ));
+            }
+        }
+    }
+
+    /// Storm motion to remove for the current product, or `None` when the
+    /// product isn't storm-relative or no motion is available yet.
+    pub fn storm_relative_motion(&self) -> Option<CellMotion> {
+        self.storm_relative_motion_for(self.product)
+    }
+
+    /// [`storm_relative_motion`](Self::storm_relative_motion) for any
+    /// product, e.g. one shown in a split-view panel.
+    pub fn storm_relative_motion_for(&self, product: RadarProduct) -> Option<CellMotion> {
+        if product != RadarProduct::StormRelativeVelocity {
+            return None;
+        }
+        match self.storm_motion_source {
+            StormMotionSource::TrackedCells => self.tracked_mean_motion,
+            StormMotionSource::Manual => {
+                let speed_kmh = self.storm_motion_speed_kt / 0.539957;
+                let (sin, cos) = self.storm_motion_heading_deg.to_radians().sin_cos();
+                Some(CellMotion::from_components(
+                    speed_kmh * sin,
+                    speed_kmh * cos,
+                ))
+            }
+        }
+    }
+
+    /// Whether any elevation of the current scan carries `product`. True
+    /// while the elevation list is unknown (no scan yet, or index entries
+    /// predating product tracking) so the selector isn't emptied.
+    pub fn product_available(&self, product: RadarProduct) -> bool {
+        // Accumulations only need the lowest-tilt reflectivity.
+        let name = if product.is_qpe_product() {
+            "reflectivity"
+        } else {
+            product.to_worker_string()
+        };
+        self.cached_vcp_elevations.is_empty()
+            || self.cached_vcp_elevations.iter().any(|e| {
+                e.available_products.is_empty() || e.available_products.iter().any(|p| p == name)
+            })
+    }
+
+    /// Extra panels currently on screen. Split view is a flat-map feature,
+    /// so the globe shows none.
+    pub fn visible_extra_panels(&self) -> &[PanelView] {
+        if self.view_mode != ViewMode::Flat2D {
+            return &[];
+        }
+        &self.extra_panels[..self.panel_layout.panel_count() - 1]
+    }
+
     /// Update the canvas overlay text with sweep timing and elevation info.
     pub fn update_overlay(
         &mut self,
@@ -416,37 +934,21 @@ impl VizState {
         self.elevation = format!("{:.1}\u{00B0}", elevation_deg);
 
         // Format midpoint timestamp with full date and time
-        let mid_ms = ((start + end) / 2.0) * 1000.0;
-        let date = js_sys::Date::new(&wasm_bindgen::JsValue::from_f64(mid_ms));
-        if use_local_time {
-            self.timestamp = format!(
-                "{:04}-{:02}-{:02} {:02}:{:02}:{:02}.{:03}",
-                date.get_full_year(),
-                date.get_month() + 1,
-                date.get_date(),
-                date.get_hours(),
-                date.get_minutes(),
-                date.get_seconds(),
-                date.get_milliseconds()
-            );
+        let date = crate::platform::local_datetime((start + end) / 2.0);
+        self.timestamp = if use_local_time {
+            date.format("%Y-%m-%d %H:%M:%S%.3f").to_string()
         } else {
-            self.timestamp = format!(
-                "{:04}-{:02}-{:02} {:02}:{:02}:{:02}.{:03} UTC",
-                date.get_utc_full_year(),
-                date.get_utc_month() + 1,
-                date.get_utc_date(),
-                date.get_utc_hours(),
-                date.get_utc_minutes(),
-                date.get_utc_seconds(),
-                date.get_utc_milliseconds()
-            );
-        }
+            date.naive_utc()
+                .format("%Y-%m-%d %H:%M:%S%.3f UTC")
+                .to_string()
+        };
 
         // Store sweep start/end times so staleness can be recomputed each frame
         self.rendered_sweep_start_secs = Some(start);
         self.rendered_sweep_end_secs = Some(end);
+        self.rendered_sweep_elevation_deg = Some(elevation_deg);
         // Staleness is recomputed per-frame in update(); seed it here for immediate display
-        let now = js_sys::Date::now() / 1000.0;
+        let now = crate::platform::now_secs();
         let staleness_end = now - end;
         let staleness_start = now - start;
         self.data_staleness_secs = if staleness_end >= 0.0 {
@@ -369,19 +369,13 @@ fn truncate(s: &str, max: usize) -> String {
 
 fn format_absolute(ts_secs: f64) -> String {
     // Show as local date-time; the user can mentally convert if needed.
-    let d = js_sys::Date::new(&wasm_bindgen::JsValue::from_f64(ts_secs * 1000.0));
-    format!(
-        "{:04}-{:02}-{:02} {:02}:{:02} local",
-        d.get_full_year(),
-        d.get_month() + 1,
-        d.get_date(),
-        d.get_hours(),
-        d.get_minutes(),
-    )
+    crate::platform::local_datetime(ts_secs)
+        .format("%Y-%m-%d %H:%M local")
+        .to_string()
 }
 
 fn format_relative(ts_secs: f64) -> String {
-    let now = js_sys::Date::now() / 1000.0;
+    let now = crate::platform::now_secs();
     let delta = ts_secs - now;
     if delta < 0.0 {
         return "in the past".to_string();
@@ -1,16 +1,20 @@
 //! Central canvas UI: radar visualization area.
 
-use super::canvas_inspector::{render_distance_measurement, render_inspector, render_storm_cells};
+use super::canvas_inspector::{
+    render_cross_section_line, render_distance_measurement, render_inspector, render_rotations,
+    render_storm_cells,
+};
 use super::canvas_interaction::{handle_canvas_interaction, handle_globe_interaction};
 use super::canvas_overlays::{
     draw_color_scale, draw_compass, draw_globe, draw_national_mosaic, draw_overlay_info,
-    draw_scale_bar, render_alerts, render_nexrad_sites, render_radar_sweep, RadarCutout,
+    draw_regional_mosaic, draw_scale_bar, render_alerts, render_nexrad_sites, render_radar_sweep,
+    RadarCutout,
 };
 use super::colors::canvas as canvas_colors;
-use crate::geo::{GeoLayerSet, MapProjection};
+use crate::geo::{GeoLayerSet, MapProjection, KM_PER_DEG};
 use crate::nexrad::RadarGpuRenderer;
-use crate::state::{AppState, RenderProcessing, ViewMode};
-use eframe::egui::{self, Color32, Rect, Sense, Stroke};
+use crate::state::{AppState, PanelView, RadarProduct, RenderProcessing, ViewMode};
+use eframe::egui::{self, Color32, Painter, Rect, Sense, Stroke};
 use geo_types::Coord;
 use std::sync::{Arc, Mutex};
 
@@ -19,7 +23,7 @@ pub fn render_canvas_with_geo(
     ctx: &egui::Context,
     state: &mut AppState,
     geo_layers: Option<&GeoLayerSet>,
-    gpu: &crate::GpuResources,
+    gpu: &crate::app::GpuResources,
 ) {
     let gpu_renderer = gpu.gpu.as_ref();
     let globe_renderer = gpu.globe.as_ref();
@@ -32,6 +36,7 @@ pub fn render_canvas_with_geo(
         let (response, painter) = ui.allocate_painter(available_size, Sense::click_and_drag());
 
         let rect = response.rect;
+        state.export.canvas_rect = Some(rect);
 
         let dark = state.is_dark;
 
@@ -64,104 +69,97 @@ pub fn render_canvas_with_geo(
                 draw_overlay_info(ui, &rect, state);
                 draw_compass(ui, &rect, &state.viz_state.camera);
 
+                if state.export.is_capturing() && state.export.burn_timestamp {
+                    draw_timestamp_burn_in(&painter, &rect, state);
+                }
+
                 // Handle orbit/zoom interactions
                 handle_globe_interaction(&response, &rect, state);
             }
             ViewMode::Flat2D => {
-                // --- Existing flat 2D path ---
-                let mut projection =
-                    MapProjection::new(state.viz_state.center_lat, state.viz_state.center_lon);
-                projection.update(state.viz_state.zoom, state.viz_state.pan_offset, rect);
+                // Every split-view panel shares the camera, so each gets the
+                // same projection over its own rect. Panel 0 is the main view.
+                let panel_rects = state.viz_state.panel_layout.panel_rects(rect);
+                let projections: Vec<MapProjection> = panel_rects
+                    .iter()
+                    .map(|panel_rect| {
+                        let mut projection = MapProjection::new(
+                            state.viz_state.center_lat,
+                            state.viz_state.center_lon,
+                        );
+                        projection.update(
+                            state.viz_state.zoom,
+                            state.viz_state.pan_offset,
+                            *panel_rect,
+                        );
+                        projection
+                    })
+                    .collect();
+                let projection = &projections[0];
 
                 // Cache current visible bounds so non-canvas UI (top bar chip,
                 // modals) can filter geographic data without reconstructing
                 // the projection.
                 state.viz_state.last_visible_bounds = Some(projection.visible_bounds());
 
-                // Screen-space cutout circle for the active radar's coverage.
-                // Fixed at the WSR-88D reflectivity range so the hole stays
-                // stable as the user scrubs elevations/products instead of
-                // tracking the current sweep's per-gate extent.
-                const NEXRAD_MAX_RANGE_KM: f64 = 460.0;
-                let radar_cutout = gpu_renderer.and_then(|renderer| {
-                    let has_data = renderer.lock().expect("renderer mutex poisoned").has_data();
-                    if !has_data {
-                        return None;
-                    }
-                    let km_to_deg = 1.0 / 111.0;
-                    let lat_correction = state.viz_state.center_lat.to_radians().cos();
-                    let lon_range = NEXRAD_MAX_RANGE_KM * km_to_deg / lat_correction;
-                    let center = projection.geo_to_screen(Coord {
-                        x: state.viz_state.center_lon,
-                        y: state.viz_state.center_lat,
-                    });
-                    let edge = projection.geo_to_screen(Coord {
-                        x: state.viz_state.center_lon + lon_range,
-                        y: state.viz_state.center_lat,
-                    });
-                    let radius = (edge.x - center.x).abs();
-                    Some(RadarCutout { center, radius })
-                });
-
-                if state.layer_state.geo.national_mosaic {
-                    draw_national_mosaic(
-                        &painter,
-                        &projection,
-                        &state.national_mosaic,
-                        state.viz_state.zoom,
-                        radar_cutout,
-                    );
-                }
-
-                if let Some(layers) = geo_layers {
-                    crate::geo::render_geo_layers(
-                        &painter,
-                        layers,
-                        &state.layer_state.geo,
-                        &projection,
-                        state.viz_state.zoom,
-                        state.layer_state.geo.labels,
-                    );
-                }
-
-                render_nexrad_sites(
-                    &painter,
-                    &projection,
-                    &state.viz_state.site_id,
-                    &state.layer_state.geo,
-                );
-
-                if state.layer_state.geo.alerts && !state.alerts.alerts.is_empty() {
-                    render_alerts(&painter, &projection, &state.alerts.alerts);
-                }
-
                 let sweep_info = compute_sweep_line_azimuth(state);
                 let (gpu_sweep, between_sweeps) = compute_gpu_sweep_state(state, sweep_info);
 
                 let chunk_boundary = state.live_radar_model.estimated_azimuth;
 
-                if let Some(renderer) = gpu_renderer {
-                    draw_radar_gpu(
-                        ui,
-                        &projection,
-                        renderer,
-                        &rect,
-                        state.viz_state.center_lat,
-                        state.viz_state.center_lon,
-                        &state.render_processing,
-                        gpu_sweep,
-                        chunk_boundary,
+                // (product, renderer) per panel. Extra panels show completed
+                // sweeps only, so they take no sweep-animation parameters.
+                let panels: Vec<(RadarProduct, Option<&Arc<Mutex<RadarGpuRenderer>>>)> =
+                    std::iter::once((state.viz_state.product, gpu_renderer))
+                        .chain(
+                            state
+                                .viz_state
+                                .visible_extra_panels()
+                                .iter()
+                                .enumerate()
+                                .map(|(i, view)| (view.product, gpu.panels.get(i))),
+                        )
+                        .collect();
+
+                // Pinned sites are drawn in the main view only.
+                let pinned_layers: Vec<PinnedLayer> = state
+                    .pinned_sites
+                    .sites
+                    .iter()
+                    .zip(&gpu.pinned)
+                    .filter(|(site, _)| site.visible)
+                    .filter_map(|(site, renderer)| {
+                        let info = crate::data::get_site(&site.site_id)?;
+                        Some(PinnedLayer {
+                            lat: info.lat,
+                            lon: info.lon,
+                            renderer,
+                            opacity: site.opacity,
+                        })
+                    })
+                    .collect();
+
+                for (i, ((panel_rect, panel_projection), (_, renderer))) in panel_rects
+                    .iter()
+                    .zip(&projections)
+                    .zip(&panels)
+                    .enumerate()
+                {
+                    let is_main = i == 0;
+                    draw_map_panel(
+                        &painter.with_clip_rect(*panel_rect),
+                        panel_rect,
+                        panel_projection,
+                        state,
+                        geo_layers,
+                        *renderer,
+                        if is_main { &pinned_layers } else { &[] },
+                        if is_main { gpu_sweep } else { None },
+                        if is_main { chunk_boundary } else { None },
                     );
+                }
 
-                    // Boundary ring at the edge of the active site's data range,
-                    // matching the mosaic cutout so the seam reads as a single circle.
-                    if let Some(c) = radar_cutout {
-                        painter.circle_stroke(
-                            c.center,
-                            c.radius,
-                            Stroke::new(1.5, canvas_colors::ring_major(dark)),
-                        );
-                    }
+                if gpu_renderer.is_some() {
                     // Request only as fast as the visible animation requires. A
                     // bare `request_repaint()` pins the UI at full display rate
                     // (often 60 fps) and compounds every per-frame loop below.
@@ -188,17 +186,6 @@ pub fn render_canvas_with_geo(
                     }
                 }
 
-                if state.viz_state.storm_cells_visible
-                    && !state.viz_state.detected_storm_cells.is_empty()
-                {
-                    render_storm_cells(
-                        &painter,
-                        &projection,
-                        &state.viz_state.detected_storm_cells,
-                        dark,
-                    );
-                }
-
                 // Show sweep line when actively revealing, between sweeps, or during live streaming.
                 // In live mode, the data boundaries and the "now" line are separate:
                 //   data_sweep = (data_edge, data_start) — from actual received chunks
@@ -215,47 +202,250 @@ pub fn render_canvas_with_geo(
                         _ => (None, false),
                     }
                 };
-                render_radar_sweep(&painter, &projection, state, sweep_line_info, sweep_stale);
-
-                if state.viz_state.distance_tool_active || state.viz_state.distance_start.is_some()
-                {
-                    render_distance_measurement(
-                        &painter,
-                        &projection,
-                        state.viz_state.distance_start,
-                        state.viz_state.distance_end,
-                    );
-                }
+                render_radar_sweep(
+                    &painter.with_clip_rect(panel_rects[0]),
+                    projection,
+                    state,
+                    sweep_line_info,
+                    sweep_stale,
+                );
 
-                if state.viz_state.inspector_enabled {
-                    if let Some(hover_pos) = response.hover_pos() {
+                // The crosshair follows the cursor's geographic position into
+                // every panel. It is left out of exported frames.
+                let hover_geo = response.hover_pos().and_then(|pos| {
+                    let i = panel_rects.iter().position(|r| r.contains(pos))?;
+                    Some(projections[i].screen_to_geo(pos))
+                });
+                let show_inspector =
+                    state.viz_state.inspector_enabled && !state.export.is_capturing();
+                if let Some(geo) = hover_geo.filter(|_| show_inspector) {
+                    for (i, ((panel_rect, panel_projection), (product, renderer))) in panel_rects
+                        .iter()
+                        .zip(&projections)
+                        .zip(&panels)
+                        .enumerate()
+                    {
                         render_inspector(
-                            &painter,
-                            &projection,
-                            hover_pos,
+                            &painter.with_clip_rect(*panel_rect),
+                            panel_projection,
+                            panel_projection.geo_to_screen(geo),
                             state.viz_state.center_lat,
                             state.viz_state.center_lon,
-                            gpu_renderer,
-                            &state.viz_state.product,
+                            *renderer,
+                            product,
                             state.use_local_time,
-                            gpu_sweep,
+                            state.playback_state.playback_position(),
+                            if i == 0 { gpu_sweep } else { None },
+                            if i == 0 {
+                                state.viz_state.interpolated_frame
+                            } else {
+                                None
+                            },
                         );
                     }
                 }
 
-                draw_color_scale(ui, &rect, &state.viz_state.product);
-                draw_overlay_info(ui, &rect, state);
-                draw_scale_bar(ui, &rect, &projection);
+                for (panel_rect, (product, _)) in panel_rects.iter().zip(&panels) {
+                    draw_color_scale(ui, panel_rect, product);
+                }
+                draw_overlay_info(ui, &panel_rects[0], state);
+                if state.viz_state.interpolated_frame.is_some() {
+                    painter.rect_stroke(
+                        panel_rects[0].shrink(1.0),
+                        0.0,
+                        Stroke::new(2.0, canvas_colors::INTERPOLATED),
+                        egui::StrokeKind::Inside,
+                    );
+                }
+                for (panel_rect, view) in panel_rects[1..]
+                    .iter()
+                    .zip(state.viz_state.visible_extra_panels())
+                {
+                    draw_panel_label(&painter, panel_rect, &panel_label(state, view));
+                }
+                draw_scale_bar(ui, &panel_rects[0], projection);
+                draw_panel_separators(&painter, &panel_rects, dark);
+                if state.export.is_capturing() && state.export.burn_timestamp {
+                    draw_timestamp_burn_in(&painter, &rect, state);
+                }
 
-                handle_canvas_interaction(&response, &rect, state, &projection);
+                // Pan/zoom/clicks act on the panel under the pointer; the
+                // shared camera moves every panel with it.
+                let active = response
+                    .interact_pointer_pos()
+                    .or(response.hover_pos())
+                    .and_then(|pos| panel_rects.iter().position(|r| r.contains(pos)))
+                    .unwrap_or(0);
+                handle_canvas_interaction(
+                    &response,
+                    &panel_rects[active],
+                    state,
+                    &projections[active],
+                );
             }
         }
     });
 }
 
+/// A pinned site's renderer, where it sits and how opaque to draw it.
+struct PinnedLayer<'a> {
+    lat: f64,
+    lon: f64,
+    renderer: &'a Arc<Mutex<RadarGpuRenderer>>,
+    opacity: f32,
+}
+
+/// Draw one flat-map panel: basemap, mosaic, radar data and geographic
+/// overlays, clipped to `rect` by `painter`.
+#[allow(clippy::too_many_arguments)]
+fn draw_map_panel(
+    painter: &Painter,
+    rect: &Rect,
+    projection: &MapProjection,
+    state: &AppState,
+    geo_layers: Option<&GeoLayerSet>,
+    renderer: Option<&Arc<Mutex<RadarGpuRenderer>>>,
+    pinned: &[PinnedLayer],
+    gpu_sweep: Option<(f32, f32)>,
+    chunk_boundary: Option<f32>,
+) {
+    let dark = state.is_dark;
+
+    // Screen-space cutout circle for the active radar's coverage.
+    // Fixed at the WSR-88D reflectivity range so the hole stays
+    // stable as the user scrubs elevations/products instead of
+    // tracking the current sweep's per-gate extent.
+    const NEXRAD_MAX_RANGE_KM: f64 = 460.0;
+    let radar_cutout = renderer.and_then(|renderer| {
+        let has_data = renderer.lock().expect("renderer mutex poisoned").has_data();
+        if !has_data {
+            return None;
+        }
+        let km_to_deg = 1.0 / KM_PER_DEG;
+        let lat_correction = state.viz_state.center_lat.to_radians().cos();
+        let lon_range = NEXRAD_MAX_RANGE_KM * km_to_deg / lat_correction;
+        let center = projection.geo_to_screen(Coord {
+            x: state.viz_state.center_lon,
+            y: state.viz_state.center_lat,
+        });
+        let edge = projection.geo_to_screen(Coord {
+            x: state.viz_state.center_lon + lon_range,
+            y: state.viz_state.center_lat,
+        });
+        let radius = (edge.x - center.x).abs();
+        Some(RadarCutout { center, radius })
+    });
+
+    if state.layer_state.geo.national_mosaic {
+        draw_national_mosaic(
+            painter,
+            projection,
+            &state.national_mosaic,
+            state.viz_state.zoom,
+            radar_cutout,
+        );
+    }
+    draw_regional_mosaic(painter, projection, &state.mosaic, radar_cutout);
+
+    if let Some(layers) = geo_layers {
+        crate::geo::render_geo_layers(
+            painter,
+            layers,
+            &state.layer_state.geo,
+            projection,
+            state.viz_state.zoom,
+            state.layer_state.geo.labels,
+        );
+    }
+
+    render_nexrad_sites(
+        painter,
+        projection,
+        &state.viz_state.site_id,
+        &state.layer_state.geo,
+    );
+
+    if state.layer_state.geo.alerts && !state.alerts.alerts.is_empty() {
+        render_alerts(painter, projection, &state.alerts.alerts);
+    }
+
+    // Pinned sites go under the active radar, each at its own opacity.
+    for layer in pinned {
+        let mut processing = state.render_processing.clone();
+        processing.opacity *= layer.opacity;
+        draw_radar_gpu(
+            painter,
+            projection,
+            layer.renderer,
+            rect,
+            layer.lat,
+            layer.lon,
+            &processing,
+            None,
+            None,
+        );
+    }
+
+    if let Some(renderer) = renderer {
+        draw_radar_gpu(
+            painter,
+            projection,
+            renderer,
+            rect,
+            state.viz_state.center_lat,
+            state.viz_state.center_lon,
+            &state.render_processing,
+            gpu_sweep,
+            chunk_boundary,
+        );
+
+        // Boundary ring at the edge of the active site's data range,
+        // matching the mosaic cutout so the seam reads as a single circle.
+        if let Some(c) = radar_cutout {
+            painter.circle_stroke(
+                c.center,
+                c.radius,
+                Stroke::new(1.5, canvas_colors::ring_major(dark)),
+            );
+        }
+    }
+
+    if state.viz_state.storm_cells_visible && !state.viz_state.detected_storm_cells.is_empty() {
+        render_storm_cells(
+            painter,
+            projection,
+            &state.viz_state.detected_storm_cells,
+            state.viz_state.storm_tracks_visible,
+            dark,
+        );
+    }
+
+    if state.viz_state.rotation_visible {
+        if let Some(rotations) = &state.viz_state.detected_rotations {
+            render_rotations(painter, projection, rotations);
+        }
+    }
+
+    if state.viz_state.distance_tool_active || state.viz_state.distance_start.is_some() {
+        render_distance_measurement(
+            painter,
+            projection,
+            state.viz_state.distance_start,
+            state.viz_state.distance_end,
+        );
+    }
+
+    render_cross_section_line(
+        painter,
+        projection,
+        state.cross_section.start,
+        state.cross_section.end,
+    );
+}
+
 #[allow(clippy::too_many_arguments)]
 fn draw_radar_gpu(
-    ui: &mut egui::Ui,
+    painter: &Painter,
     projection: &MapProjection,
     renderer: &Arc<Mutex<RadarGpuRenderer>>,
     rect: &Rect,
@@ -278,7 +468,7 @@ fn draw_radar_gpu(
     // pixel-to-km mapping matches the geographic projection exactly.
     let range_km = max_range_km;
 
-    let km_to_deg = 1.0 / 111.0;
+    let km_to_deg = 1.0 / KM_PER_DEG;
     let lat_correction = radar_lat.to_radians().cos();
     let lon_range = range_km * km_to_deg / lat_correction;
 
@@ -326,7 +516,86 @@ fn draw_radar_gpu(
         })),
     };
 
-    ui.painter().add(callback);
+    painter.add(callback);
+}
+
+/// Short product/tilt caption for an extra split-view panel, e.g. "VEL 0.5°".
+fn panel_label(state: &AppState, view: &PanelView) -> String {
+    if view.product.is_volume_product() {
+        return view.product.short_code().to_string();
+    }
+    let angle = view
+        .elevation_number
+        .or(state.viz_state.displayed_sweep_elevation_number)
+        .and_then(|n| {
+            state
+                .viz_state
+                .cached_vcp_elevations
+                .iter()
+                .find(|e| e.elevation_number == n)
+        })
+        .map(|e| e.angle);
+    match angle {
+        Some(angle) => format!("{} {:.1}\u{00B0}", view.product.short_code(), angle),
+        None => view.product.short_code().to_string(),
+    }
+}
+
+fn draw_panel_label(painter: &Painter, rect: &Rect, text: &str) {
+    let font_id = egui::FontId::monospace(12.0);
+    let galley =
+        painter.layout_no_wrap(text.to_string(), font_id, Color32::from_rgb(220, 220, 240));
+    let pos = rect.left_top() + egui::Vec2::new(10.0, 10.0);
+    let padding = egui::Vec2::new(6.0, 3.0);
+    painter.rect_filled(
+        Rect::from_min_size(pos - padding, galley.size() + padding * 2.0),
+        4.0,
+        Color32::from_rgba_unmultiplied(20, 20, 30, 200),
+    );
+    painter.galley(pos, galley, Color32::WHITE);
+}
+
+/// Site, product and sweep time in the bottom-right corner of exported
+/// frames. Interpolated frames carry their nominal time and say so.
+fn draw_timestamp_burn_in(painter: &Painter, rect: &Rect, state: &AppState) {
+    let interpolated = state.viz_state.interpolated_frame;
+    let time = interpolated
+        .map(|frame| frame.valid_secs())
+        .or(state.viz_state.rendered_sweep_end_secs)
+        .unwrap_or_else(|| state.playback_state.playback_position());
+    let text = format!(
+        "{} \u{00B7} {} \u{00B7} {}{}",
+        state.viz_state.site_id,
+        state.viz_state.product.short_code(),
+        format_unix_timestamp_with_date(time, state.use_local_time),
+        if interpolated.is_some() {
+            " (interpolated)"
+        } else {
+            ""
+        },
+    );
+    let galley = painter.layout_no_wrap(text, egui::FontId::monospace(16.0), Color32::WHITE);
+    let padding = egui::Vec2::new(8.0, 4.0);
+    let pos = rect.right_bottom() - galley.size() - padding - egui::Vec2::new(10.0, 10.0);
+    painter.rect_filled(
+        Rect::from_min_size(pos - padding, galley.size() + padding * 2.0),
+        4.0,
+        Color32::from_rgba_unmultiplied(20, 20, 30, 220),
+    );
+    painter.galley(pos, galley, Color32::WHITE);
+}
+
+/// Divider lines between split-view panels.
+fn draw_panel_separators(painter: &Painter, panel_rects: &[Rect], dark: bool) {
+    let stroke = Stroke::new(1.0, canvas_colors::ring_major(dark));
+    for r in &panel_rects[1..] {
+        if r.left() > panel_rects[0].left() {
+            painter.vline(r.left(), r.y_range(), stroke);
+        }
+        if r.top() > panel_rects[0].top() {
+            painter.hline(r.x_range(), r.top(), stroke);
+        }
+    }
 }
 
 pub(super) fn format_age(secs: f64) -> String {
@@ -511,15 +780,9 @@ fn compute_sweep_line_azimuth(state: &AppState) -> Option<(f32, f32)> {
 
 pub(super) fn format_time_short(ts: f64, use_local: bool) -> String {
     if use_local {
-        let d = js_sys::Date::new_0();
-        d.set_time(ts * 1000.0);
-        format!(
-            "{:02}:{:02}:{:02}.{:03}",
-            d.get_hours(),
-            d.get_minutes(),
-            d.get_seconds(),
-            d.get_milliseconds()
-        )
+        crate::platform::local_datetime(ts)
+            .format("%H:%M:%S%.3f")
+            .to_string()
     } else {
         use chrono::{TimeZone, Timelike, Utc};
         let secs = ts.floor() as i64;
@@ -541,13 +804,9 @@ pub(super) fn format_time_short(ts: f64, use_local: bool) -> String {
 
 pub(super) fn format_unix_timestamp(ts: f64, use_local: bool) -> String {
     if use_local {
-        let d = js_sys::Date::new_0();
-        d.set_time(ts * 1000.0);
-        let h = d.get_hours();
-        let m = d.get_minutes();
-        let s = d.get_seconds();
-        let ms = d.get_milliseconds();
-        format!("{h:02}:{m:02}:{s:02}.{ms:03} Local")
+        crate::platform::local_datetime(ts)
+            .format("%H:%M:%S%.3f Local")
+            .to_string()
     } else {
         use chrono::{TimeZone, Utc};
         let secs = ts.floor() as i64;
@@ -561,18 +820,9 @@ pub(super) fn format_unix_timestamp(ts: f64, use_local: bool) -> String {
 
 pub(super) fn format_unix_timestamp_with_date(ts: f64, use_local: bool) -> String {
     if use_local {
-        let d = js_sys::Date::new_0();
-        d.set_time(ts * 1000.0);
-        format!(
-            "{:04}-{:02}-{:02} {:02}:{:02}:{:02}.{:03}",
-            d.get_full_year(),
-            d.get_month() + 1,
-            d.get_date(),
-            d.get_hours(),
-            d.get_minutes(),
-            d.get_seconds(),
-            d.get_milliseconds()
-        )
+        crate::platform::local_datetime(ts)
+            .format("%Y-%m-%d %H:%M:%S%.3f")
+            .to_string()
     } else {
         use chrono::{Datelike, TimeZone, Timelike, Utc};
         let secs = ts.floor() as i64;
@@ -594,7 +844,7 @@ pub(super) fn format_unix_timestamp_with_date(ts: f64, use_local: bool) -> Strin
 }
 
 pub(super) fn format_age_compact(ts_secs: f64) -> Option<String> {
-    let now = js_sys::Date::now() / 1000.0;
+    let now = crate::platform::now_secs();
     let age = now - ts_secs;
     if (0.0..1.5).contains(&age) {
         Some("(now)".to_string())
@@ -1,17 +1,18 @@
 //! Canvas inspector tool and measurement overlays.
 //!
 //! Renders the hover tooltip (lat/lon, azimuth, range, product value),
-//! the crosshair cursor, distance measurement lines, and detected storm
-//! cell bounding boxes on top of the radar canvas.
+//! the crosshair cursor, distance measurement and cross-section lines, and
+//! detected storm cell bounding boxes (with past tracks and forecast cones)
+//! and rotation markers on top of the radar canvas.
 
-use crate::geo::MapProjection;
+use crate::geo::{MapProjection, KM_PER_DEG};
 use crate::nexrad::RadarGpuRenderer;
-use crate::state::StormCellInfo;
+use crate::state::{RotationInfo, RotationStrength, StormCellInfo, STORM_FORECAST_LEAD_MINUTES};
 use eframe::egui::{self, Color32, Painter, Pos2, Rect, Shape, Stroke, StrokeKind, Vec2};
 use geo_types::Coord;
 use std::sync::{Arc, Mutex};
 
-use super::canvas::format_unix_timestamp;
+use super::canvas::{format_age, format_unix_timestamp};
 
 #[allow(clippy::too_many_arguments)]
 pub(crate) fn render_inspector(
@@ -23,7 +24,9 @@ pub(crate) fn render_inspector(
     gpu_renderer: Option<&Arc<Mutex<RadarGpuRenderer>>>,
     product: &crate::state::RadarProduct,
     use_local_time: bool,
+    playback_ts: f64,
     sweep_params: Option<(f32, f32)>,
+    interpolated: Option<crate::state::InterpolatedFrame>,
 ) {
     let geo = projection.screen_to_geo(hover_pos);
     let lat = geo.y;
@@ -32,18 +35,20 @@ pub(crate) fn render_inspector(
     // Compute polar coordinates relative to radar site
     let dlat = lat - radar_lat;
     let dlon = (lon - radar_lon) * radar_lat.to_radians().cos();
-    let range_km = (dlat * dlat + dlon * dlon).sqrt() * 111.0;
+    let range_km = (dlat * dlat + dlon * dlon).sqrt() * KM_PER_DEG;
     let azimuth_deg = (dlon.atan2(dlat).to_degrees() + 360.0) % 360.0;
 
-    // Look up data value and collection time (sweep-aware when animating)
-    let (value, collection_time) = gpu_renderer
+    // Look up data value and collection time (sweep-aware when animating).
+    // Composites carry a time per gate, which also gives the data's age.
+    let (value, collection_time, gate_time) = gpu_renderer
         .map(|r| {
             let renderer = r.lock().expect("renderer mutex poisoned");
             let v = renderer.value_at_polar(azimuth_deg as f32, range_km, sweep_params);
             let t = renderer.collection_time_at_polar(azimuth_deg as f32, sweep_params);
-            (v, t)
+            let g = renderer.gate_time_at_polar(azimuth_deg as f32, range_km);
+            (v, t, g)
         })
-        .unwrap_or((None, None));
+        .unwrap_or((None, None, None));
 
     // Build tooltip text
     let mut lines = vec![
@@ -52,13 +57,41 @@ pub(crate) fn render_inspector(
     ];
     if let Some(v) = value {
         let unit = product.unit();
-        if unit.is_empty() {
+        if *product == crate::state::RadarProduct::HydrometeorClass {
+            if let Some((class, confidence)) = crate::nexrad::hydro_class::decode(v) {
+                lines.push(format!(
+                    "{}: {} ({:.0}%)",
+                    product.short_code(),
+                    class.label(),
+                    confidence * 100.0
+                ));
+            }
+        } else if product.is_qpe_product() {
+            lines.push(format!(
+                "{}: {:.2} in ({:.1} mm)",
+                product.short_code(),
+                v / 25.4,
+                v
+            ));
+        } else if unit.is_empty() {
             lines.push(format!("{}: {:.3}", product.short_code(), v));
         } else {
             lines.push(format!("{}: {:.1} {}", product.short_code(), v, unit));
         }
     }
-    if let Some(ts) = collection_time {
+    // Values of an interpolated frame were never observed at any time.
+    if let Some(frame) = interpolated {
+        lines.push(format!(
+            "Interpolated ~{}",
+            format_unix_timestamp(frame.valid_secs(), use_local_time)
+        ));
+    } else if let Some(ts) = gate_time {
+        lines.push(format!(
+            "{} ({} old)",
+            format_unix_timestamp(ts, use_local_time),
+            format_age((playback_ts - ts).max(0.0))
+        ));
+    } else if let Some(ts) = collection_time {
         lines.push(format_unix_timestamp(ts, use_local_time));
     }
     let text = lines.join("\n");
@@ -175,6 +208,33 @@ pub(crate) fn render_distance_measurement(
     }
 }
 
+/// Draw the cross-section line with its "A" and "B" endpoint labels.
+pub(crate) fn render_cross_section_line(
+    painter: &Painter,
+    projection: &MapProjection,
+    start: Option<(f64, f64)>,
+    end: Option<(f64, f64)>,
+) {
+    let color = Color32::from_rgb(255, 210, 80);
+    let to_screen = |(lat, lon): (f64, f64)| projection.geo_to_screen(Coord { x: lon, y: lat });
+    let points: Vec<Pos2> = [start, end].into_iter().flatten().map(to_screen).collect();
+
+    if let [a, b] = points[..] {
+        painter.line_segment([a, b], Stroke::new(2.0, color));
+    }
+    for (pos, label) in points.iter().zip(["A", "B"]) {
+        painter.circle_filled(*pos, 5.0, color);
+        painter.circle_stroke(*pos, 5.0, Stroke::new(1.5, Color32::BLACK));
+        painter.text(
+            *pos + Vec2::new(8.0, -8.0),
+            egui::Align2::LEFT_BOTTOM,
+            label,
+            egui::FontId::proportional(13.0),
+            color,
+        );
+    }
+}
+
 fn haversine_km(lat1: f64, lon1: f64, lat2: f64, lon2: f64) -> f64 {
     let r = 6371.0; // Earth radius in km
     let dlat = (lat2 - lat1).to_radians();
@@ -190,10 +250,22 @@ fn haversine_km(lat1: f64, lon1: f64, lat2: f64, lon2: f64) -> f64 {
 /// staying cheap to tessellate.
 const ELLIPSE_SEGMENTS: usize = 32;
 
+/// Forecast cone half-width at zero lead time.
+const CONE_BASE_HALF_WIDTH_KM: f64 = 2.0;
+
+/// Forecast cone widening per minute of lead time. Roughly the growth of
+/// SCIT centroid forecast error with lead time.
+const CONE_HALF_WIDTH_KM_PER_MIN: f64 = 0.25;
+
+/// Below this speed a motion vector is indistinguishable from centroid
+/// jitter, so no forecast is drawn.
+const MIN_FORECAST_SPEED_KMH: f32 = 5.0;
+
 pub(crate) fn render_storm_cells(
     painter: &Painter,
     projection: &MapProjection,
     cells: &[StormCellInfo],
+    show_tracks: bool,
     _dark: bool,
 ) {
     for cell in cells {
@@ -210,6 +282,10 @@ pub(crate) fn render_storm_cells(
             Color32::from_rgb(255, 220, 80) // Moderate
         };
 
+        if show_tracks {
+            render_cell_track(painter, projection, cell, center, color);
+        }
+
         // Derive ellipse semi-axes from area + elongation: an ellipse of
         // area A and ratio e has semi-minor b = √(A / (π·e)) and semi-major
         // a = b·e. Clamp to avoid imaginary/zero axes for tiny cells.
@@ -233,20 +309,172 @@ pub(crate) fn render_storm_cells(
             let dx_km = ex * cos_t - ey * sin_t;
             let dy_km = ex * sin_t + ey * cos_t;
             // km → lat/lon offset (equirectangular, matches projection).
-            let lat = cell.lat + dy_km / 111.0;
-            let lon = cell.lon + dx_km / (111.0 * cos_lat);
+            let lat = cell.lat + dy_km / KM_PER_DEG;
+            let lon = cell.lon + dx_km / (KM_PER_DEG * cos_lat);
             points.push(projection.geo_to_screen(Coord { x: lon, y: lat }));
         }
         painter.add(Shape::closed_line(points, Stroke::new(1.5, color)));
 
-        // Small centroid marker + max-dBZ label. Bearing/range stay
-        // available in the state struct for future tooltip / side-panel
-        // consumers; surfacing them inline on the map was too noisy.
+        // Small centroid marker + max-dBZ label (and track ID once the
+        // tracker has assigned one). Bearing/range stay available in the
+        // state struct for future tooltip / side-panel consumers; surfacing
+        // them inline on the map was too noisy.
         painter.circle_stroke(center, 3.0, Stroke::new(1.5, color));
+        let label = match &cell.track {
+            Some(track) => format!("{:.0} #{}", cell.max_dbz, track.id),
+            None => format!("{:.0}", cell.max_dbz),
+        };
         painter.text(
             center + Vec2::new(6.0, -6.0),
             egui::Align2::LEFT_BOTTOM,
-            format!("{:.0}", cell.max_dbz),
+            label,
+            egui::FontId::proportional(10.0),
+            color,
+        );
+    }
+}
+
+/// Draw a tracked cell's past centroid path and its forecast cone with
+/// ticks at each lead time in [`STORM_FORECAST_LEAD_MINUTES`].
+fn render_cell_track(
+    painter: &Painter,
+    projection: &MapProjection,
+    cell: &StormCellInfo,
+    center: Pos2,
+    color: Color32,
+) {
+    let Some(track) = &cell.track else {
+        return;
+    };
+
+    // Past path: oldest → current centroid.
+    if !track.past_positions.is_empty() {
+        let mut path: Vec<Pos2> = track
+            .past_positions
+            .iter()
+            .map(|&(lat, lon)| projection.geo_to_screen(Coord { x: lon, y: lat }))
+            .collect();
+        for p in &path {
+            painter.circle_filled(*p, 2.0, color.gamma_multiply(0.7));
+        }
+        path.push(center);
+        painter.add(Shape::line(
+            path,
+            Stroke::new(1.5, color.gamma_multiply(0.7)),
+        ));
+    }
+
+    let Some(motion) = track.motion else {
+        return;
+    };
+    if motion.speed_kmh < MIN_FORECAST_SPEED_KMH {
+        return;
+    }
+
+    // Unit vectors along and across the motion, in km (x = east, y = north).
+    let heading_rad = (motion.heading_deg as f64).to_radians();
+    let (along_x, along_y) = (heading_rad.sin(), heading_rad.cos());
+    let (across_x, across_y) = (along_y, -along_x);
+    let cos_lat = cell.lat.to_radians().cos().max(1e-6);
+    let offset = |dx_km: f64, dy_km: f64| {
+        projection.geo_to_screen(Coord {
+            x: cell.lon + dx_km / (KM_PER_DEG * cos_lat),
+            y: cell.lat + dy_km / KM_PER_DEG,
+        })
+    };
+
+    let speed_km_per_min = motion.speed_kmh as f64 / 60.0;
+    let mut left: Vec<Pos2> = vec![center];
+    let mut right: Vec<Pos2> = vec![center];
+    let mut ticks: Vec<Pos2> = Vec::with_capacity(STORM_FORECAST_LEAD_MINUTES.len());
+    for &lead in &STORM_FORECAST_LEAD_MINUTES {
+        let dist = speed_km_per_min * lead;
+        let half = CONE_BASE_HALF_WIDTH_KM + CONE_HALF_WIDTH_KM_PER_MIN * lead;
+        let (cx, cy) = (along_x * dist, along_y * dist);
+        left.push(offset(cx + across_x * half, cy + across_y * half));
+        right.push(offset(cx - across_x * half, cy - across_y * half));
+        ticks.push(offset(cx, cy));
+    }
+
+    let mut cone = left;
+    cone.extend(right.into_iter().rev());
+    painter.add(Shape::convex_polygon(
+        cone,
+        color.gamma_multiply(0.12),
+        Stroke::new(1.0, color.gamma_multiply(0.5)),
+    ));
+
+    if let Some(&end) = ticks.last() {
+        painter.line_segment([center, end], Stroke::new(1.5, color));
+    }
+    for tick in &ticks {
+        painter.circle_stroke(*tick, 2.5, Stroke::new(1.0, color));
+    }
+    if let Some(&end) = ticks.last() {
+        painter.text(
+            end + Vec2::new(4.0, 0.0),
+            egui::Align2::LEFT_CENTER,
+            format!(
+                "{:.0}\u{00B0}/{:.0} kt",
+                motion.heading_deg,
+                motion.speed_kt()
+            ),
+            egui::FontId::proportional(9.0),
+            color.gamma_multiply(0.8),
+        );
+    }
+}
+
+/// Draw detected rotation features: a circle sized to the core diameter
+/// (with a floor so small couplets stay visible), spurs marking the sense of
+/// rotation, and a Vrot / height label.
+pub(crate) fn render_rotations(
+    painter: &Painter,
+    projection: &MapProjection,
+    rotations: &[RotationInfo],
+) {
+    const MIN_RADIUS_PX: f32 = 7.0;
+
+    for rot in rotations {
+        let center = projection.geo_to_screen(Coord {
+            x: rot.lon,
+            y: rot.lat,
+        });
+
+        let (color, width) = match rot.strength() {
+            RotationStrength::Strong => (Color32::from_rgb(255, 40, 140), 3.0),
+            RotationStrength::Moderate => (Color32::from_rgb(230, 70, 230), 2.0),
+            RotationStrength::Weak => (Color32::from_rgb(255, 150, 255), 1.5),
+        };
+
+        // Core radius in screen space, measured east-west at the feature.
+        let cos_lat = rot.lat.to_radians().cos().max(1e-6);
+        let edge = projection.geo_to_screen(Coord {
+            x: rot.lon + (rot.diameter_km as f64 * 0.5) / (KM_PER_DEG * cos_lat),
+            y: rot.lat,
+        });
+        let radius = (edge.x - center.x).abs().max(MIN_RADIUS_PX);
+
+        painter.circle_stroke(center, radius, Stroke::new(width, color));
+
+        // Four counter-clockwise spurs tangent to the circle.
+        for i in 0..4 {
+            let angle = i as f32 * std::f32::consts::FRAC_PI_2;
+            let (sin_a, cos_a) = angle.sin_cos();
+            let on_circle = center + Vec2::new(cos_a, sin_a) * radius;
+            // Screen y points down, so (sin, -cos) is counter-clockwise.
+            let tangent = Vec2::new(sin_a, -cos_a) * (radius * 0.6).min(8.0);
+            painter.line_segment([on_circle, on_circle + tangent], Stroke::new(width, color));
+        }
+
+        painter.text(
+            center + Vec2::new(radius + 3.0, radius + 2.0),
+            egui::Align2::LEFT_TOP,
+            format!(
+                "{:.0} kt \u{00B7} {:.1} km ARL",
+                rot.rotational_velocity_kt(),
+                rot.height_km
+            ),
             egui::FontId::proportional(10.0),
             color,
         );
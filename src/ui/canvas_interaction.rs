@@ -1,9 +1,10 @@
 //! Canvas mouse/keyboard interaction handlers.
 //!
 //! Separates input handling from rendering: pan (drag), zoom (scroll),
-//! distance tool clicks, globe orbit/translate, and double-click reset.
+//! distance and cross-section tool clicks, globe orbit/translate, and
+//! double-click reset.
 
-use crate::data::NEXRAD_SITES;
+use crate::data::all_sites;
 use crate::geo::MapProjection;
 use crate::state::AppState;
 use eframe::egui::{self, Rect, Vec2};
@@ -146,6 +147,20 @@ pub(crate) fn handle_canvas_interaction(
                 state.viz_state.distance_end = Some((geo.y, geo.x));
             }
         }
+    } else if state.cross_section.tool_active && response.clicked() {
+        // Cross-section tool: same two-click placement; the second click
+        // opens the section window.
+        if let Some(click_pos) = response.interact_pointer_pos() {
+            let geo = projection.screen_to_geo(click_pos);
+            let section = &mut state.cross_section;
+            if section.start.is_none() || section.end.is_some() {
+                section.clear();
+                section.start = Some((geo.y, geo.x));
+            } else {
+                section.end = Some((geo.y, geo.x));
+                section.window_open = true;
+            }
+        }
     } else if response.clicked() {
         if let Some(click_pos) = response.interact_pointer_pos() {
             // Alert hit-testing first: when the alerts overlay is on, a click
@@ -242,7 +257,7 @@ fn pick_site_at(
     let hit_radius_sq = SITE_HIT_RADIUS_PX * SITE_HIT_RADIUS_PX;
 
     let mut best: Option<(&'static str, f64, f64, f32)> = None;
-    for site in NEXRAD_SITES.iter() {
+    for site in all_sites() {
         if site.id == current_upper {
             continue;
         }
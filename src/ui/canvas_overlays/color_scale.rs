@@ -1,7 +1,10 @@
 //! Vertical color scale legend displayed on the right edge of the canvas.
 //!
 //! Builds a 1024-entry LUT matching the GPU shader's color mapping, then
-//! renders it as a gradient bar with tick marks and unit labels.
+//! renders it as a gradient bar with tick marks and unit labels. Categorical
+//! products get a swatch-per-class key instead. With a user palette the ticks
+//! and unit follow the palette's units, and its name and range-folded color
+//! are shown under the bar.
 
 use eframe::egui::{self, Color32, Pos2, Rect, Stroke, StrokeKind, Vec2};
 
@@ -10,37 +13,18 @@ pub(crate) fn draw_color_scale(
     rect: &Rect,
     product: &crate::state::RadarProduct,
 ) {
-    use crate::nexrad::color_table::{build_reflectivity_lut, product_value_range};
-    use nexrad_render::Product;
-
-    let product_nr = match product {
-        crate::state::RadarProduct::Reflectivity => Product::Reflectivity,
-        crate::state::RadarProduct::Velocity => Product::Velocity,
-        crate::state::RadarProduct::SpectrumWidth => Product::SpectrumWidth,
-        crate::state::RadarProduct::DifferentialReflectivity => Product::DifferentialReflectivity,
-        crate::state::RadarProduct::CorrelationCoefficient => Product::CorrelationCoefficient,
-        crate::state::RadarProduct::DifferentialPhase => Product::DifferentialPhase,
-        crate::state::RadarProduct::ClutterFilterPower => Product::ClutterFilterPower,
-    };
+    use crate::nexrad::color_table::{build_product_lut, value_range_for};
+    use crate::nexrad::palette;
 
-    let (min_val, max_val) = product_value_range(product_nr);
+    if *product == crate::state::RadarProduct::HydrometeorClass {
+        draw_hydro_class_legend(ui, rect);
+        return;
+    }
 
-    // Build the LUT (1024 entries) — for reflectivity uses OKLab, others use crate scale
-    let lut = if matches!(product, crate::state::RadarProduct::Reflectivity) {
-        build_reflectivity_lut(min_val, max_val)
-    } else {
-        let color_scale = crate::nexrad::color_table::continuous_color_scale(product_nr);
-        let lut_size = 1024usize;
-        let mut data = Vec::with_capacity(lut_size * 4);
-        for i in 0..lut_size {
-            let t = i as f32 / (lut_size - 1) as f32;
-            let value = min_val + t * (max_val - min_val);
-            let color = color_scale.color(value);
-            let rgba = color.to_rgba8();
-            data.extend_from_slice(&rgba);
-        }
-        data
-    };
+    // Same product string the GPU color table is built from.
+    let (min_val, max_val) = value_range_for(product.to_worker_string());
+    let lut = build_product_lut(product.to_worker_string());
+    let palette = palette::active_for(product.to_worker_string());
 
     let bar_width = 16.0f32;
     let margin = 14.0f32;
@@ -82,24 +66,25 @@ pub(crate) fn draw_color_scale(
         StrokeKind::Outside,
     );
 
-    // Tick labels
+    // Tick labels, placed in the palette's units when it has its own.
     let range = max_val - min_val;
-    let tick_step = if range > 200.0 {
-        60.0
-    } else if range > 60.0 {
-        10.0
-    } else if range > 10.0 {
-        5.0
-    } else if range > 2.0 {
-        1.0
-    } else {
-        0.2
+    let to_display = |v: f32| palette.as_ref().map_or(v, |p| p.to_display(v));
+    let to_physical = |v: f32| palette.as_ref().map_or(v, |p| p.to_physical(v));
+    let (lo, hi) = {
+        let (a, b) = (to_display(min_val), to_display(max_val));
+        (a.min(b), a.max(b))
     };
+    // The palette's own step, unless its labels would overlap.
+    let tick_step = palette
+        .as_ref()
+        .and_then(|p| p.step)
+        .filter(|step| *step > 0.0 && (hi - lo) / step * 12.0 <= bar_height)
+        .unwrap_or_else(|| tick_step(hi - lo));
 
     let label_x = bar_left - 4.0;
-    let mut val = (min_val / tick_step).ceil() * tick_step;
-    while val <= max_val {
-        let frac = (val - min_val) / range;
+    let mut val = (lo / tick_step).ceil() * tick_step;
+    while val <= hi {
+        let frac = (to_physical(val) - min_val) / range;
         let y = bar_top + bar_height * (1.0 - frac);
 
         // Tick line
@@ -125,11 +110,88 @@ pub(crate) fn draw_color_scale(
     }
 
     // Unit label at top
+    let unit = palette
+        .as_ref()
+        .and_then(|p| p.units.as_deref())
+        .unwrap_or(product.unit());
     painter.text(
         Pos2::new(bar_left + bar_width * 0.5, bar_top - 6.0),
         egui::Align2::CENTER_BOTTOM,
-        product.unit(),
+        unit,
         egui::FontId::monospace(10.0),
         Color32::from_rgba_unmultiplied(160, 160, 170, 200),
     );
+
+    let Some(palette) = palette else {
+        return;
+    };
+    // Range-folded swatch and palette name below the bar
+    let mut y = bar_top + bar_height + 6.0;
+    if let Some([r, g, b, a]) = palette.range_folded {
+        let swatch = Rect::from_min_size(Pos2::new(bar_left, y), Vec2::new(bar_width, 10.0));
+        painter.rect_filled(swatch, 0.0, Color32::from_rgba_unmultiplied(r, g, b, a));
+        painter.text(
+            Pos2::new(label_x, y + 5.0),
+            egui::Align2::RIGHT_CENTER,
+            "RF",
+            egui::FontId::monospace(10.0),
+            Color32::from_rgba_unmultiplied(180, 180, 190, 220),
+        );
+        y += 14.0;
+    }
+    painter.text(
+        Pos2::new(bar_left + bar_width, y),
+        egui::Align2::RIGHT_TOP,
+        &palette.name,
+        egui::FontId::proportional(10.0),
+        Color32::from_rgba_unmultiplied(160, 160, 170, 200),
+    );
+}
+
+/// Spacing of legend ticks over a value span.
+fn tick_step(range: f32) -> f32 {
+    if range > 200.0 {
+        60.0
+    } else if range > 60.0 {
+        10.0
+    } else if range > 10.0 {
+        5.0
+    } else if range > 2.0 {
+        1.0
+    } else {
+        0.2
+    }
+}
+
+/// Discrete key for the hydrometeor classification: one swatch and label
+/// per class, top to bottom in encoding order.
+fn draw_hydro_class_legend(ui: &mut egui::Ui, rect: &Rect) {
+    use crate::nexrad::hydro_class::HydroClass;
+
+    let swatch = 12.0f32;
+    let row_height = 16.0f32;
+    let margin = 14.0f32;
+    let top = rect.top() + 20.0;
+    let swatch_left = rect.right() - margin - swatch;
+
+    let painter = ui.painter();
+    for (i, class) in HydroClass::ALL.iter().enumerate() {
+        let y = top + i as f32 * row_height;
+        let [r, g, b] = class.color();
+        let swatch_rect = Rect::from_min_size(Pos2::new(swatch_left, y), Vec2::splat(swatch));
+        painter.rect_filled(swatch_rect, 0.0, Color32::from_rgb(r, g, b));
+        painter.rect_stroke(
+            swatch_rect,
+            0.0,
+            Stroke::new(1.0, Color32::from_rgba_unmultiplied(120, 120, 130, 180)),
+            StrokeKind::Outside,
+        );
+        painter.text(
+            Pos2::new(swatch_left - 4.0, y + swatch * 0.5),
+            egui::Align2::RIGHT_CENTER,
+            class.label(),
+            egui::FontId::monospace(10.0),
+            Color32::from_rgba_unmultiplied(180, 180, 190, 220),
+        );
+    }
 }
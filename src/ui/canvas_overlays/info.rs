@@ -7,15 +7,18 @@ use crate::state::AppState;
 use eframe::egui::{self, Color32, Rect, RichText, Vec2};
 
 use super::super::canvas::{
-    age_color, format_age, format_unix_timestamp_with_date, AGE_RANGE_COLLAPSE_SECS,
-    ARCHIVE_AGE_THRESHOLD_SECS,
+    age_color, format_age, format_unix_timestamp, format_unix_timestamp_with_date,
+    AGE_RANGE_COLLAPSE_SECS, ARCHIVE_AGE_THRESHOLD_SECS,
 };
+use super::super::colors::canvas::INTERPOLATED;
 
 pub(crate) fn draw_overlay_info(ui: &mut egui::Ui, rect: &Rect, state: &AppState) {
     let has_prev = state.viz_state.prev_sweep_overlay.is_some();
     let is_live = state.live_radar_model.active;
     let overlay_pos = rect.left_top() + Vec2::new(10.0, 10.0);
-    let overlay_height = if has_prev { 170.0 } else { 105.0 };
+    let interpolated = state.viz_state.interpolated_frame;
+    let overlay_height =
+        if has_prev { 170.0 } else { 105.0 } + if interpolated.is_some() { 40.0 } else { 0.0 };
     let overlay_rect = Rect::from_min_size(overlay_pos, Vec2::new(310.0, overlay_height));
 
     ui.scope_builder(egui::UiBuilder::new().max_rect(overlay_rect), |ui| {
@@ -33,6 +36,27 @@ pub(crate) fn draw_overlay_info(ui: &mut egui::Ui, rect: &Rect, state: &AppState
                         .color(Color32::from_rgb(255, 160, 40)),
                 );
             }
+            // The sections below describe the observed sweeps; the picture
+            // itself is synthesised between them.
+            if let Some(frame) = interpolated {
+                ui.label(
+                    RichText::new("INTERPOLATED FRAME")
+                        .monospace()
+                        .size(14.0)
+                        .strong()
+                        .color(INTERPOLATED),
+                );
+                ui.label(
+                    RichText::new(format!(
+                        "  ~{} ({:.0}% towards next scan)",
+                        format_unix_timestamp(frame.valid_secs(), state.use_local_time),
+                        frame.fraction * 100.0,
+                    ))
+                    .monospace()
+                    .size(11.0)
+                    .color(INTERPOLATED),
+                );
+            }
 
             let info_color = Color32::from_rgb(200, 200, 220);
             let header_color = Color32::from_rgb(220, 220, 240);
@@ -83,7 +107,7 @@ pub(crate) fn draw_overlay_info(ui: &mut egui::Ui, rect: &Rect, state: &AppState
             if let Some((prev_elev_deg, prev_start, prev_end)) = state.viz_state.prev_sweep_overlay
             {
                 ui.add_space(2.0);
-                let now = js_sys::Date::now() / 1000.0;
+                let now = crate::platform::now_secs();
                 let prev_age_end = now - prev_end;
                 let prev_age_start = now - prev_start;
 
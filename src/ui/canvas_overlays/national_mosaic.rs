@@ -64,7 +64,29 @@ pub(crate) fn draw_national_mosaic(
     let fade_t = ((zoom - FADE_START_ZOOM) / (FADE_END_ZOOM - FADE_START_ZOOM)).clamp(0.0, 1.0);
     let alpha = (BASE_ALPHA * (1.0 - 0.5 * fade_t)) as u8;
     let tint = Color32::from_rgba_unmultiplied(255, 255, 255, alpha);
-    let mut mesh = egui::Mesh::with_texture(texture.id());
+    draw_warped_texture(
+        painter,
+        projection,
+        texture.id(),
+        (min_lon, min_lat, max_lon, max_lat),
+        tint,
+        cutout,
+    );
+}
+
+/// Paint a north-up lat/lon image covering `bounds` (min_lon, min_lat,
+/// max_lon, max_lat) as a warped mesh, leaving out `cutout`. Shared with
+/// the regional mosaic.
+pub(crate) fn draw_warped_texture(
+    painter: &Painter,
+    projection: &MapProjection,
+    texture: egui::TextureId,
+    bounds: (f64, f64, f64, f64),
+    tint: Color32,
+    cutout: Option<RadarCutout>,
+) {
+    let (min_lon, min_lat, max_lon, max_lat) = bounds;
+    let mut mesh = egui::Mesh::with_texture(texture);
 
     // Precompute the base grid's lon/lat and screen positions so that the
     // per-cell decision path can reuse them and, when subdividing, bilinear-
@@ -0,0 +1,33 @@
+//! Regional mosaic overlay rendering.
+//!
+//! Paints the composite of the picked neighbouring sites the same way as
+//! the national mosaic, above it and under the active radar's coverage.
+
+use super::national_mosaic::{draw_warped_texture, RadarCutout};
+use crate::geo::MapProjection;
+use crate::state::MosaicState;
+use eframe::egui::{Color32, Painter};
+
+/// Mostly opaque: the regional mosaic is the user's pick and sits at radar
+/// resolution, unlike the national one.
+const ALPHA: u8 = 220;
+
+pub(crate) fn draw_regional_mosaic(
+    painter: &Painter,
+    projection: &MapProjection,
+    mosaic: &MosaicState,
+    cutout: Option<RadarCutout>,
+) {
+    if !mosaic.enabled {
+        return;
+    }
+    let Some((texture, bounds)) = &mosaic.texture else {
+        return;
+    };
+    let (min_lon, min_lat, max_lon, max_lat) = *bounds;
+    if !projection.bbox_visible(min_lon, min_lat, max_lon, max_lat) {
+        return;
+    }
+    let tint = Color32::from_rgba_unmultiplied(255, 255, 255, ALPHA);
+    draw_warped_texture(painter, projection, texture.id(), *bounds, tint, cutout);
+}
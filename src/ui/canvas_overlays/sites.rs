@@ -1,10 +1,11 @@
 //! NEXRAD site marker overlay on the 2D canvas.
 //!
 //! Renders colored dots and optional labels for all 156+ NEXRAD sites
-//! within the visible map bounds. The active site is drawn larger and
-//! highlighted; off-screen sites are culled for performance.
+//! within the visible map bounds, and violet diamonds for the TDWRs. The
+//! active site is drawn larger and highlighted; off-screen sites are
+//! culled for performance.
 
-use crate::data::{get_site, NEXRAD_SITES};
+use crate::data::{all_sites, get_site, RadarType};
 use crate::geo::MapProjection;
 use crate::state::GeoLayerVisibility;
 use eframe::egui::{self, Painter, Stroke, Vec2};
@@ -22,7 +23,7 @@ pub(crate) fn render_nexrad_sites(
     let (min_lon, min_lat, max_lon, max_lat) = projection.visible_bounds();
 
     if visibility.nexrad_sites {
-        for site in NEXRAD_SITES.iter() {
+        for site in all_sites() {
             if site.id == current_site_id_upper {
                 continue;
             }
@@ -41,8 +42,30 @@ pub(crate) fn render_nexrad_sites(
                 y: site.lat,
             });
 
-            painter.circle_filled(screen_pos, 4.0, site_colors::OTHER);
-            painter.circle_stroke(screen_pos, 4.0, Stroke::new(1.0, site_colors::OTHER_STROKE));
+            match site.radar_type() {
+                RadarType::Wsr88d => {
+                    painter.circle_filled(screen_pos, 4.0, site_colors::OTHER);
+                    painter.circle_stroke(
+                        screen_pos,
+                        4.0,
+                        Stroke::new(1.0, site_colors::OTHER_STROKE),
+                    );
+                }
+                RadarType::Tdwr => {
+                    let r = 4.5;
+                    let diamond = vec![
+                        screen_pos + Vec2::new(0.0, -r),
+                        screen_pos + Vec2::new(r, 0.0),
+                        screen_pos + Vec2::new(0.0, r),
+                        screen_pos + Vec2::new(-r, 0.0),
+                    ];
+                    painter.add(egui::Shape::convex_polygon(
+                        diamond,
+                        site_colors::TDWR,
+                        Stroke::new(1.0, site_colors::TDWR_STROKE),
+                    ));
+                }
+            }
 
             if visibility.labels {
                 painter.text(
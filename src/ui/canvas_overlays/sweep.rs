@@ -7,8 +7,7 @@
 
 use super::super::canvas::{format_age_compact, format_time_short};
 use super::super::colors::{canvas as canvas_colors, radar};
-use crate::geo::MapProjection;
-use crate::nexrad::RADAR_COVERAGE_RANGE_KM;
+use crate::geo::{MapProjection, KM_PER_DEG};
 use crate::state::AppState;
 use eframe::egui::{self, Color32, Painter, Pos2, Rect, Stroke, Vec2};
 use geo_types::Coord;
@@ -31,9 +30,9 @@ pub(crate) fn render_radar_sweep(
         y: radar_lat,
     });
 
-    // Compute radius in screen pixels for the coverage range
-    let range_km = RADAR_COVERAGE_RANGE_KM;
-    let km_to_deg = 1.0 / 111.0;
+    // Compute radius in screen pixels for the site's coverage range
+    let range_km = state.radar_type().coverage_range_km();
+    let km_to_deg = 1.0 / KM_PER_DEG;
     let lat_correction = radar_lat.to_radians().cos();
     let lon_range = range_km * km_to_deg / lat_correction;
 
@@ -172,7 +171,7 @@ pub(crate) fn render_radar_sweep(
                 // elevation the antenna is on even when the worker is still
                 // downloading a previous elevation's data.
                 let now_label_radius = radius + 4.0 + 6.0 + 14.0; // donut_outer + offset
-                let now_secs = js_sys::Date::now() / 1000.0;
+                let now_secs = crate::platform::now_secs();
                 let collecting_label = state
                     .live_radar_model
                     .position
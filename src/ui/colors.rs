@@ -312,6 +312,9 @@ pub mod timeline {
 pub mod canvas {
     use super::Color32;
 
+    /// Badge and border marking an interpolated frame (theme-independent).
+    pub const INTERPOLATED: Color32 = Color32::from_rgb(230, 120, 230);
+
     pub fn background(dark: bool) -> Color32 {
         if dark {
             Color32::BLACK
@@ -396,6 +399,10 @@ pub mod sites {
     pub const OTHER: Color32 = Color32::from_rgb(255, 180, 80);
     /// Orange stroke for other sites.
     pub const OTHER_STROKE: Color32 = Color32::from_rgb(180, 120, 40);
+    /// Violet for other TDWR sites.
+    pub const TDWR: Color32 = Color32::from_rgb(190, 140, 255);
+    /// Violet stroke for other TDWR sites.
+    pub const TDWR_STROKE: Color32 = Color32::from_rgb(120, 80, 190);
     /// Cyan for current site.
     pub const CURRENT: Color32 = Color32::from_rgb(50, 200, 255);
     /// Cyan stroke for current site.
@@ -0,0 +1,324 @@
+//! Command console window and the runner for queued console commands.
+//!
+//! The command language itself (parsing, the JS bridge) lives in
+//! [`crate::state::console`]; this applies parsed commands to app state the
+//! same way the matching UI controls do.
+
+use super::site_modal::apply_site_selection;
+use crate::state::console::{
+    self, ConsoleCommand, ConsoleLine, ConsoleWait, DownloadTarget, ElevationTarget,
+    IDLE_SETTLE_MS, IDLE_WAIT_TIMEOUT_MS,
+};
+use crate::state::{AppCommand, AppState, ElevationSelection, LiveExitReason};
+use eframe::egui::{self, Color32, RichText, Vec2};
+
+const ERROR_COLOR: Color32 = Color32::from_rgb(255, 120, 120);
+
+/// Pick up scripts sent from JS and run at most one queued command. Call once
+/// per frame, before commands are dispatched.
+pub fn run_console_commands(ctx: &egui::Context, state: &mut AppState) {
+    let arrived = console::take_bridge_scripts(ctx);
+    state.console.queue.extend(arrived);
+
+    let now_ms = crate::platform::now_ms();
+    let mut errors = Vec::new();
+
+    if let Some(result) = state.export.last_result.clone() {
+        if state.console.watching_export {
+            state.console.watching_export = false;
+            match result {
+                Ok(message) => state.console.push_log(ConsoleLine::Output(message)),
+                Err(message) => {
+                    state.console.push_log(ConsoleLine::Error(message.clone()));
+                    errors.push(format!("export: {message}"));
+                }
+            }
+        }
+    }
+
+    match wait_finished(state, now_ms) {
+        Ok(false) => {}
+        Ok(true) => {
+            state.console.wait = None;
+            if let Some(line) = state.console.queue.pop_front() {
+                state
+                    .console
+                    .push_log(ConsoleLine::Input(line.text.clone()));
+                match execute(state, line.command, now_ms) {
+                    Ok(Some(message)) => state.console.push_log(ConsoleLine::Output(message)),
+                    Ok(None) => {}
+                    Err(e) => {
+                        errors.push(format!("{}: {e}", line.text));
+                        stop_script(state, e);
+                    }
+                }
+            }
+        }
+        Err(e) => {
+            errors.push(e.clone());
+            stop_script(state, e);
+        }
+    }
+
+    // The app only repaints on input, so keep frames coming while a script
+    // has work left.
+    if state.console.pending() > 0 {
+        ctx.request_repaint_after(std::time::Duration::from_millis(100));
+    }
+    console::publish_bridge_status(state.console.pending(), errors);
+}
+
+/// Log a failure and drop the rest of the script, so later commands don't run
+/// against the wrong state.
+fn stop_script(state: &mut AppState, error: String) {
+    state.console.push_log(ConsoleLine::Error(error));
+    state.console.cancel();
+}
+
+/// Whether the running wait (if any) is over. Errors when an idle wait
+/// times out.
+fn wait_finished(state: &mut AppState, now_ms: f64) -> Result<bool, String> {
+    match state.console.wait {
+        None => Ok(true),
+        Some(ConsoleWait::Until(until_ms)) => Ok(now_ms >= until_ms),
+        Some(ConsoleWait::Idle {
+            started_ms,
+            idle_since_ms,
+        }) => {
+            if now_ms - started_ms > IDLE_WAIT_TIMEOUT_MS {
+                return Err("timed out waiting for the pipeline to go idle".to_string());
+            }
+            if !pipeline_idle(state) {
+                state.console.wait = Some(ConsoleWait::Idle {
+                    started_ms,
+                    idle_since_ms: None,
+                });
+                return Ok(false);
+            }
+            match idle_since_ms {
+                Some(since) => Ok(now_ms - since >= IDLE_SETTLE_MS),
+                None => {
+                    state.console.wait = Some(ConsoleWait::Idle {
+                        started_ms,
+                        idle_since_ms: Some(now_ms),
+                    });
+                    Ok(false)
+                }
+            }
+        }
+    }
+}
+
+/// Nothing queued, downloading, decoding, rendering or exporting.
+fn pipeline_idle(state: &AppState) -> bool {
+    let pipeline = &state.session_stats.pipeline;
+    state.commands.is_empty()
+        && !state.auto_position_on_timeline_load
+        && !state.acquisition.has_active_operations()
+        && pipeline.downloading == 0
+        && !pipeline.processing
+        && !pipeline.rendering
+        && !state.export.start_requested
+        && !state.export.is_capturing()
+        && !state.export.data_pending
+}
+
+/// Apply one command. Returns a message to log, if any.
+fn execute(
+    state: &mut AppState,
+    command: ConsoleCommand,
+    now_ms: f64,
+) -> Result<Option<String>, String> {
+    match command {
+        ConsoleCommand::Site(id) => {
+            let site = crate::data::get_site(&id).ok_or_else(|| format!("unknown site '{id}'"))?;
+            apply_site_selection(state, site.id, site.lat, site.lon);
+            // Hold the script until the timeline loads, so its auto-position
+            // can't override a following `goto`.
+            state.console.wait = Some(ConsoleWait::Idle {
+                started_ms: now_ms,
+                idle_since_ms: None,
+            });
+            Ok(Some(format!("{} {}", site.id, site.name)))
+        }
+        ConsoleCommand::Goto(ts) => {
+            if state.live_mode_state.is_active() {
+                state.live_mode_state.stop(LiveExitReason::UserSeeked);
+                state.playback_state.time_model.disable_realtime_lock();
+            }
+            state.playback_state.set_playback_position(ts);
+            let view_width_secs = state.playback_state.view_width_secs();
+            state.playback_state.timeline_view_start = ts - view_width_secs * 0.05;
+            Ok(Some(format_utc(ts)))
+        }
+        ConsoleCommand::Product(product) => {
+            if !product.is_level3() && !state.viz_state.product_available(product) {
+                return Err(format!("{} isn't in the loaded scan", product.label()));
+            }
+            state.viz_state.product = product;
+            Ok(Some(product.label().to_string()))
+        }
+        ConsoleCommand::Elevation(ElevationTarget::Latest) => {
+            state.viz_state.elevation_selection = ElevationSelection::Latest;
+            Ok(None)
+        }
+        ConsoleCommand::Elevation(ElevationTarget::Lowest) => {
+            state.viz_state.elevation_selection = ElevationSelection::LowestAvailable;
+            Ok(None)
+        }
+        ConsoleCommand::Elevation(ElevationTarget::Angle(angle)) => {
+            let entry = state
+                .viz_state
+                .cached_vcp_elevations
+                .iter()
+                .min_by(|a, b| (a.angle - angle).abs().total_cmp(&(b.angle - angle).abs()))
+                .ok_or("no elevations loaded yet; add a 'wait' after loading a scan")?;
+            let message = format!(
+                "tilt {} ({:.1}\u{00B0})",
+                entry.elevation_number, entry.angle
+            );
+            state.viz_state.elevation_selection = ElevationSelection::Fixed {
+                elevation_number: entry.elevation_number,
+                angle: entry.angle,
+            };
+            Ok(Some(message))
+        }
+        ConsoleCommand::Range(Some((start, end))) => {
+            state.playback_state.selection_start = Some(start);
+            state.playback_state.selection_end = Some(end);
+            state.playback_state.selection_in_progress = false;
+            Ok(Some(format!(
+                "{} to {}",
+                format_utc(start),
+                format_utc(end)
+            )))
+        }
+        ConsoleCommand::Range(None) => {
+            state.playback_state.clear_selection();
+            Ok(None)
+        }
+        ConsoleCommand::Download(DownloadTarget::Range) => {
+            if state.playback_state.selection_range().is_none() {
+                return Err("no timeline selection; set one with 'range'".to_string());
+            }
+            state.push_command(AppCommand::DownloadSelection);
+            Ok(None)
+        }
+        ConsoleCommand::Download(DownloadTarget::Here) => {
+            state.push_command(AppCommand::DownloadAtPosition);
+            Ok(None)
+        }
+        ConsoleCommand::Export(format) => {
+            if state.export.is_capturing() || state.export.data_pending {
+                return Err("an export is already running".to_string());
+            }
+            state.export.format = format;
+            state.export.last_result = None;
+            state.export.start_requested = true;
+            state.console.watching_export = true;
+            Ok(None)
+        }
+        ConsoleCommand::Play => {
+            state.playback_state.playing = true;
+            Ok(None)
+        }
+        ConsoleCommand::Pause => {
+            if state.live_mode_state.is_active() {
+                state.live_mode_state.stop(LiveExitReason::UserStopped);
+                state.playback_state.time_model.disable_realtime_lock();
+            }
+            state.playback_state.playing = false;
+            Ok(None)
+        }
+        ConsoleCommand::Wait(Some(secs)) => {
+            state.console.wait = Some(ConsoleWait::Until(now_ms + secs * 1000.0));
+            Ok(None)
+        }
+        ConsoleCommand::Wait(None) => {
+            state.console.wait = Some(ConsoleWait::Idle {
+                started_ms: now_ms,
+                idle_since_ms: None,
+            });
+            Ok(None)
+        }
+        ConsoleCommand::Help => {
+            for (usage, description) in console::HELP {
+                state
+                    .console
+                    .push_log(ConsoleLine::Output(format!("{usage:<26}{description}")));
+            }
+            Ok(None)
+        }
+    }
+}
+
+fn format_utc(ts: f64) -> String {
+    chrono::DateTime::from_timestamp(ts as i64, 0)
+        .map(|dt| dt.format("%Y-%m-%d %H:%M:%S UTC").to_string())
+        .unwrap_or_default()
+}
+
+pub fn render_console_window(ctx: &egui::Context, state: &mut AppState) {
+    if !state.console.open {
+        return;
+    }
+
+    let mut open = true;
+    egui::Window::new("Console")
+        .open(&mut open)
+        .resizable(true)
+        .default_size(Vec2::new(520.0, 300.0))
+        .min_size(Vec2::new(320.0, 160.0))
+        .show(ctx, |ui| {
+            let pending = state.console.pending();
+            egui::TopBottomPanel::bottom("console_input")
+                .frame(egui::Frame::NONE)
+                .show_inside(ui, |ui| {
+                    ui.add_space(4.0);
+                    ui.horizontal(|ui| {
+                        if pending > 0 {
+                            ui.spinner();
+                            ui.label(RichText::new(format!("{pending} left")).small().weak());
+                            if ui.small_button("Cancel").clicked() {
+                                state.console.cancel();
+                            }
+                        }
+                        let response = ui.add(
+                            egui::TextEdit::singleline(&mut state.console.input)
+                                .font(egui::TextStyle::Monospace)
+                                .hint_text("Type 'help' for commands")
+                                .desired_width(f32::INFINITY),
+                        );
+                        if response.has_focus() {
+                            if ui.input(|i| i.key_pressed(egui::Key::ArrowUp)) {
+                                state.console.recall_history(true);
+                            }
+                            if ui.input(|i| i.key_pressed(egui::Key::ArrowDown)) {
+                                state.console.recall_history(false);
+                            }
+                        }
+                        if response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+                            state.console.submit_input();
+                            response.request_focus();
+                        }
+                    });
+                });
+
+            egui::ScrollArea::vertical()
+                .auto_shrink([false, false])
+                .stick_to_bottom(true)
+                .show(ui, |ui| {
+                    for line in &state.console.log {
+                        let text = match line {
+                            ConsoleLine::Input(text) => RichText::new(format!("> {text}")).strong(),
+                            ConsoleLine::Output(text) => RichText::new(text).weak(),
+                            ConsoleLine::Error(text) => RichText::new(text).color(ERROR_COLOR),
+                        };
+                        ui.label(text.monospace());
+                    }
+                });
+        });
+    if !open {
+        state.console.open = false;
+    }
+}
@@ -0,0 +1,328 @@
+//! Vertical cross-section window.
+//!
+//! Shows the range-height slice computed along the line drawn with the
+//! cross-section tool, colored with the product's own LUT. Distance from "A"
+//! runs along the x axis and height above the radar up the y axis, with
+//! optional beam-centre lines for every tilt that contributed.
+
+use super::colors::canvas as canvas_colors;
+use crate::nexrad::beam::beam_height_km;
+use crate::nexrad::color_table::{build_product_lut, is_categorical, value_range_for};
+use crate::nexrad::CrossSectionData;
+use crate::state::{AppState, RadarProduct};
+use eframe::egui::{self, Color32, Pos2, Rect, RichText, Sense, Stroke, Vec2};
+
+const KFT_PER_KM: f64 = 3.28084;
+
+/// Cached section image. Stored on WorkbenchApp.
+#[derive(Default)]
+pub struct CrossSectionWindowState {
+    /// Texture and the [`CrossSectionState::revision`](crate::state::CrossSectionState)
+    /// it was built from.
+    texture: Option<(u64, egui::TextureHandle)>,
+}
+
+pub fn render_cross_section_window(
+    ctx: &egui::Context,
+    state: &mut AppState,
+    window_state: &mut CrossSectionWindowState,
+) {
+    if !state.cross_section.window_open {
+        return;
+    }
+
+    let mut open = true;
+    egui::Window::new("Cross Section")
+        .open(&mut open)
+        .resizable(true)
+        .default_size(Vec2::new(640.0, 340.0))
+        .min_size(Vec2::new(360.0, 220.0))
+        .show(ctx, |ui| {
+            render_header(ui, state);
+            ui.separator();
+
+            let product = state.viz_state.product;
+            if product.is_volume_product() || product.is_qpe_product() {
+                ui.label(
+                    RichText::new("Cross-sections need a single-tilt product")
+                        .weak()
+                        .italics(),
+                );
+                return;
+            }
+            if let Some(error) = &state.cross_section.error {
+                ui.label(RichText::new(error).color(Color32::from_rgb(255, 120, 120)));
+            }
+
+            let revision = state.cross_section.revision;
+            let Some(data) = &state.cross_section.result else {
+                ui.label(RichText::new("Computing\u{2026}").weak().italics());
+                return;
+            };
+            if data.section.is_empty() {
+                ui.label(
+                    RichText::new("No cached data along this line")
+                        .weak()
+                        .italics(),
+                );
+                return;
+            }
+
+            let texture = match &window_state.texture {
+                Some((built, texture)) if *built == revision => texture.clone(),
+                _ => {
+                    let texture = build_texture(ctx, data);
+                    window_state.texture = Some((revision, texture.clone()));
+                    texture
+                }
+            };
+            draw_section(
+                ui,
+                state.is_dark,
+                data,
+                &texture,
+                product,
+                state.cross_section.show_beam_lines,
+                state.cross_section.height_in_kft,
+            );
+        });
+
+    if !open {
+        state.cross_section.window_open = false;
+    }
+}
+
+fn render_header(ui: &mut egui::Ui, state: &mut AppState) {
+    ui.horizontal(|ui| {
+        ui.label(RichText::new(state.viz_state.product.label()).strong());
+        if let Some(data) = &state.cross_section.result {
+            ui.label(
+                RichText::new(format!(
+                    "A\u{2192}B {:.1} km, {} tilts",
+                    data.section.length_km,
+                    data.section.tilts_deg.len()
+                ))
+                .weak(),
+            );
+        }
+        if state.cross_section.pending {
+            ui.spinner();
+        }
+        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+            ui.selectable_value(&mut state.cross_section.height_in_kft, true, "kft");
+            ui.selectable_value(&mut state.cross_section.height_in_kft, false, "km");
+            ui.checkbox(&mut state.cross_section.show_beam_lines, "Beam centres")
+                .on_hover_text("Height of each tilt's beam centre along the line");
+        });
+    });
+}
+
+/// Color every cell through the product LUT. Empty cells are transparent.
+fn build_texture(ctx: &egui::Context, data: &CrossSectionData) -> egui::TextureHandle {
+    let product = data.request.product.as_str();
+    let lut = build_product_lut(product);
+    let (min_val, max_val) = value_range_for(product);
+    let lut_len = lut.len() / 4;
+
+    let section = &data.section;
+    let mut pixels = Vec::with_capacity(section.values.len() * 4);
+    for &v in &section.values {
+        if v.is_nan() {
+            pixels.extend_from_slice(&[0, 0, 0, 0]);
+            continue;
+        }
+        let t = ((v - min_val) / (max_val - min_val)).clamp(0.0, 1.0);
+        let i = ((t * (lut_len - 1) as f32) as usize).min(lut_len - 1);
+        pixels.extend_from_slice(&lut[i * 4..i * 4 + 4]);
+    }
+    let image = egui::ColorImage::from_rgba_unmultiplied([section.columns, section.rows], &pixels);
+    let options = if is_categorical(product) {
+        egui::TextureOptions::NEAREST
+    } else {
+        egui::TextureOptions::LINEAR
+    };
+    ctx.load_texture("cross_section", image, options)
+}
+
+/// Round `span / target` up to a 1-2-5 step.
+fn nice_step(span: f64, target: f64) -> f64 {
+    let raw = (span / target).max(1e-6);
+    let magnitude = 10f64.powf(raw.log10().floor());
+    [1.0, 2.0, 5.0, 10.0]
+        .into_iter()
+        .map(|m| m * magnitude)
+        .find(|step| *step >= raw)
+        .unwrap_or(10.0 * magnitude)
+}
+
+fn draw_section(
+    ui: &mut egui::Ui,
+    dark: bool,
+    data: &CrossSectionData,
+    texture: &egui::TextureHandle,
+    product: RadarProduct,
+    show_beam_lines: bool,
+    height_in_kft: bool,
+) {
+    let section = &data.section;
+    let size = Vec2::new(
+        ui.available_width(),
+        (ui.available_height() - 20.0).max(160.0),
+    );
+    let (response, painter) = ui.allocate_painter(size, Sense::hover());
+    let outer = response.rect;
+    let plot = Rect::from_min_max(
+        outer.min + Vec2::new(52.0, 8.0),
+        outer.max - Vec2::new(14.0, 26.0),
+    );
+
+    let length_km = section.length_km.max(1e-3);
+    let x_at = |d_km: f64| plot.left() + (d_km / length_km) as f32 * plot.width();
+    let y_at = |h_km: f64| plot.bottom() - (h_km / section.top_km) as f32 * plot.height();
+
+    painter.rect_filled(plot, 0.0, canvas_colors::background(dark));
+    painter.image(
+        texture.id(),
+        plot,
+        Rect::from_min_max(Pos2::ZERO, Pos2::new(1.0, 1.0)),
+        Color32::WHITE,
+    );
+
+    let grid = Stroke::new(1.0, canvas_colors::ring(dark));
+    let text_color = ui.visuals().text_color();
+    let font = egui::FontId::monospace(10.0);
+
+    // Height axis.
+    let (unit, per_km) = if height_in_kft {
+        ("kft", KFT_PER_KM)
+    } else {
+        ("km", 1.0)
+    };
+    let top = section.top_km * per_km;
+    let step = nice_step(top, 6.0);
+    let mut h = 0.0;
+    while h <= top + 1e-9 {
+        let y = y_at(h / per_km);
+        painter.hline(plot.x_range(), y, grid);
+        painter.text(
+            Pos2::new(plot.left() - 6.0, y),
+            egui::Align2::RIGHT_CENTER,
+            format!("{:.0}", h),
+            font.clone(),
+            text_color,
+        );
+        h += step;
+    }
+    painter.text(
+        Pos2::new(outer.left() + 2.0, plot.top()),
+        egui::Align2::LEFT_TOP,
+        unit,
+        font.clone(),
+        text_color,
+    );
+
+    // Distance axis.
+    let step = nice_step(length_km, 8.0);
+    let mut d = 0.0;
+    while d <= length_km + 1e-9 {
+        let x = x_at(d);
+        painter.vline(x, plot.y_range(), grid);
+        painter.text(
+            Pos2::new(x, plot.bottom() + 4.0),
+            egui::Align2::CENTER_TOP,
+            format!("{:.0}", d),
+            font.clone(),
+            text_color,
+        );
+        d += step;
+    }
+    painter.text(
+        Pos2::new(plot.left(), plot.bottom() + 14.0),
+        egui::Align2::LEFT_TOP,
+        "A",
+        font.clone(),
+        text_color,
+    );
+    painter.text(
+        Pos2::new(plot.right(), plot.bottom() + 14.0),
+        egui::Align2::RIGHT_TOP,
+        "B  km",
+        font.clone(),
+        text_color,
+    );
+
+    if show_beam_lines {
+        let (start, end) = (data.request.start_km, data.request.end_km);
+        let beam = Stroke::new(1.0, Color32::from_white_alpha(150));
+        for &tilt in &section.tilts_deg {
+            let points: Vec<Pos2> = (0..=100)
+                .map(|i| {
+                    let t = i as f64 / 100.0;
+                    let x = start.0 + t * (end.0 - start.0);
+                    let y = start.1 + t * (end.1 - start.1);
+                    let h = beam_height_km(x.hypot(y), tilt).min(section.top_km);
+                    Pos2::new(x_at(t * length_km), y_at(h))
+                })
+                .collect();
+            let label_pos = points[points.len() - 1];
+            painter
+                .with_clip_rect(plot)
+                .add(egui::Shape::line(points, beam));
+            if label_pos.y > plot.top() + 4.0 {
+                painter.text(
+                    label_pos - Vec2::new(2.0, 2.0),
+                    egui::Align2::RIGHT_BOTTOM,
+                    format!("{:.1}\u{00B0}", tilt),
+                    egui::FontId::monospace(9.0),
+                    Color32::from_white_alpha(200),
+                );
+            }
+        }
+    }
+
+    painter.rect_stroke(plot, 0.0, grid, egui::StrokeKind::Inside);
+
+    // Hover readout.
+    let readout = response
+        .hover_pos()
+        .filter(|pos| plot.contains(*pos))
+        .map(|pos| {
+            painter.hline(plot.x_range(), pos.y, Stroke::new(1.0, Color32::WHITE));
+            painter.vline(pos.x, plot.y_range(), Stroke::new(1.0, Color32::WHITE));
+
+            let fx = ((pos.x - plot.left()) / plot.width()).clamp(0.0, 0.9999);
+            let fy = ((pos.y - plot.top()) / plot.height()).clamp(0.0, 0.9999);
+            let d = fx as f64 * length_km;
+            let h = (1.0 - fy as f64) * section.top_km;
+            let column = (fx * section.columns as f32) as usize;
+            let row = (fy * section.rows as f32) as usize;
+            let value = section
+                .value(column, row)
+                .map(|v| format_value(product, v))
+                .unwrap_or_else(|| "no data".to_string());
+            format!(
+                "{:.1} km from A  {:.2} km ({:.1} kft)  {}",
+                d,
+                h,
+                h * KFT_PER_KM,
+                value
+            )
+        });
+    ui.label(
+        RichText::new(readout.unwrap_or_default())
+            .monospace()
+            .size(11.0),
+    );
+}
+
+fn format_value(product: RadarProduct, v: f32) -> String {
+    if product == RadarProduct::HydrometeorClass {
+        return crate::nexrad::hydro_class::decode(v)
+            .map(|(class, confidence)| format!("{} ({:.0}%)", class.label(), confidence * 100.0))
+            .unwrap_or_default();
+    }
+    match product.unit() {
+        "" => format!("{}: {:.3}", product.short_code(), v),
+        unit => format!("{}: {:.1} {}", product.short_code(), v, unit),
+    }
+}
@@ -87,15 +87,14 @@ impl EventModalState {
         use_local: bool,
     ) -> (String, String, String, String, String, String) {
         if use_local {
-            let d = js_sys::Date::new_0();
-            d.set_time(ts * 1000.0);
+            let dt = crate::platform::local_datetime(ts);
             (
-                format!("{:04}", d.get_full_year()),
-                format!("{:02}", d.get_month() + 1),
-                format!("{:02}", d.get_date()),
-                format!("{:02}", d.get_hours()),
-                format!("{:02}", d.get_minutes()),
-                format!("{:02}", d.get_seconds()),
+                dt.format("%Y").to_string(),
+                dt.format("%m").to_string(),
+                dt.format("%d").to_string(),
+                dt.format("%H").to_string(),
+                dt.format("%M").to_string(),
+                dt.format("%S").to_string(),
             )
         } else {
             let dt = Utc.timestamp_opt(ts as i64, 0).unwrap();
@@ -153,19 +152,7 @@ impl EventModalState {
         let s: u32 = second.parse().ok()?;
 
         if use_local {
-            let date = js_sys::Date::new_0();
-            date.set_full_year(y as u32);
-            date.set_month(mo.checked_sub(1)?);
-            date.set_date(d);
-            date.set_hours(h);
-            date.set_minutes(mi);
-            date.set_seconds(s);
-            date.set_milliseconds(0);
-            let ts = date.get_time();
-            if ts.is_nan() {
-                return None;
-            }
-            Some(ts / 1000.0)
+            crate::platform::local_to_unix(y, mo, d, h, mi, s)
         } else {
             let dt = Utc.with_ymd_and_hms(y, mo, d, h, mi, s);
             match dt {
@@ -131,7 +131,7 @@ fn query_radar_state_at_timestamp<'a>(state: &'a AppState) -> RadarStateAtTimest
             // In live mode, use the unified VcpPositionModel for azimuth,
             // elevation, and progress instead of reaching into LiveModeState.
             if let Some(ref position) = state.live_radar_model.position {
-                let now = js_sys::Date::now() / 1000.0;
+                let now = crate::platform::now_secs();
                 let vcp = Some(position.vcp_number).filter(|&v| v > 0);
                 let azimuth = position.estimated_azimuth_at(now);
                 let sweep_index = position.elevation_index_at(now).or_else(|| {
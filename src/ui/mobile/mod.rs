@@ -25,6 +25,7 @@ pub(crate) use top_bar::render_mobile_top_bar;
 /// Reads CSS custom properties set in `index.html` via `getComputedStyle`,
 /// dispatched through a pre-declared `window.__nexradSafeAreaInsets()`
 /// helper to avoid enabling the `CssStyleDeclaration` feature in web-sys.
+#[cfg(target_arch = "wasm32")]
 pub(crate) fn safe_area_insets() -> (f32, f32, f32, f32) {
     use wasm_bindgen::{JsCast, JsValue};
 
@@ -49,3 +50,9 @@ pub(crate) fn safe_area_insets() -> (f32, f32, f32, f32) {
     };
     (read("top"), read("right"), read("bottom"), read("left"))
 }
+
+/// Desktop windows have no safe-area insets.
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) fn safe_area_insets() -> (f32, f32, f32, f32) {
+    (0.0, 0.0, 0.0, 0.0)
+}
@@ -49,7 +49,7 @@ pub(super) fn render_scrubber(ui: &mut egui::Ui, state: &mut AppState) {
     let (t_start, t_end) = match state.radar_timeline.overall_time_range() {
         Some((s, e)) if e > s => (s, e),
         _ => {
-            let now = js_sys::Date::now() / 1000.0;
+            let now = crate::platform::now_secs();
             (now - 1800.0, now + 1800.0)
         }
     };
@@ -89,7 +89,7 @@ pub(super) fn render_scrubber(ui: &mut egui::Ui, state: &mut AppState) {
 
     // "Now" marker in live mode.
     if state.live_mode_state.is_active() {
-        let now = js_sys::Date::now() / 1000.0;
+        let now = crate::platform::now_secs();
         if now >= t_start && now <= t_end {
             let x = ts_to_x(now);
             let color = Color32::from_rgb(220, 60, 60);
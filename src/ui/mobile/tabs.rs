@@ -225,7 +225,8 @@ pub(super) fn step_frame(state: &mut AppState, direction: isize) {
                             .unwrap_or(fallback)
                     }
                 }
-                crate::state::ElevationSelection::Latest => {
+                crate::state::ElevationSelection::Latest
+                | crate::state::ElevationSelection::LowestAvailable => {
                     if direction < 0 {
                         state
                             .radar_timeline
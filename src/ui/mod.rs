@@ -15,6 +15,8 @@ mod canvas_inspector;
 mod canvas_interaction;
 mod canvas_overlays;
 pub(crate) mod colors;
+mod console;
+mod cross_section_window;
 mod event_modal;
 mod left_panel;
 mod mobile;
@@ -33,6 +35,8 @@ mod wipe_modal;
 pub use alerts_modal::render_alerts_modals;
 pub use bottom_panel::render_bottom_panel;
 pub use canvas::render_canvas_with_geo;
+pub use console::{render_console_window, run_console_commands};
+pub use cross_section_window::{render_cross_section_window, CrossSectionWindowState};
 pub use event_modal::{render_event_modal, EventModalState};
 pub use left_panel::render_left_panel;
 pub(crate) use mobile::{
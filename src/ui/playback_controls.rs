@@ -259,7 +259,8 @@ pub(super) fn render_playback_controls(ui: &mut egui::Ui, state: &mut AppState)
                         .radar_timeline
                         .prev_matching_sweep_end_by_number(current_pos, *elevation_number)
                         .unwrap_or(fallback),
-                    crate::state::ElevationSelection::Latest => state
+                    crate::state::ElevationSelection::Latest
+                    | crate::state::ElevationSelection::LowestAvailable => state
                         .radar_timeline
                         .prev_any_sweep_end(current_pos)
                         .unwrap_or(fallback),
@@ -301,7 +302,8 @@ pub(super) fn render_playback_controls(ui: &mut egui::Ui, state: &mut AppState)
                         .radar_timeline
                         .next_matching_sweep_end_by_number(current_pos, *elevation_number)
                         .unwrap_or(fallback),
-                    crate::state::ElevationSelection::Latest => state
+                    crate::state::ElevationSelection::Latest
+                    | crate::state::ElevationSelection::LowestAvailable => state
                         .radar_timeline
                         .next_any_sweep_end(current_pos)
                         .unwrap_or(fallback),
@@ -1,7 +1,11 @@
 //! Right panel UI: product selection, layers, and rendering controls.
 
+use crate::nexrad::level3::Level3Product;
+use crate::nexrad::mosaic::MosaicMode;
+use crate::nexrad::qpe::ZrRelation;
 use crate::state::{
-    format_bytes, AppState, ElevationSelection, InterpolationMode, RadarProduct, StorageSettings,
+    format_bytes, AppState, ElevationSelection, ExportFormat, ExportResolution, InterpolationMode,
+    PanelLayout, RadarProduct, ReplaySettings, StorageSettings, StormMotionSource, ViewMode,
 };
 use eframe::egui::{self, RichText, ScrollArea};
 
@@ -23,22 +27,40 @@ pub fn render_right_panel(ctx: &egui::Context, state: &mut AppState) {
                 render_product_section(ui, state);
                 ui.add_space(5.0);
 
+                render_split_view_section(ui, state);
+                ui.add_space(5.0);
+
                 render_layers_section(ui, state);
                 ui.add_space(5.0);
 
+                render_mosaic_section(ui, state);
+                ui.add_space(5.0);
+
+                render_pinned_sites_section(ui, state);
+                ui.add_space(5.0);
+
                 render_rendering_section(ui, state);
                 ui.add_space(5.0);
 
+                render_color_tables_section(ui, state);
+                ui.add_space(5.0);
+
                 render_volume_section(ui, state);
                 ui.add_space(5.0);
 
                 render_tools_section(ui, state);
                 ui.add_space(5.0);
 
+                render_export_section(ui, state);
+                ui.add_space(5.0);
+
                 render_events_section(ui, state);
                 ui.add_space(5.0);
 
                 render_storage_section(ui, state);
+                ui.add_space(5.0);
+
+                render_data_source_section(ui, state);
             });
         });
 }
@@ -52,41 +74,104 @@ pub(super) fn render_product_section(ui: &mut egui::Ui, state: &mut AppState) {
                 .selected_text(state.viz_state.product.label())
                 .width(150.0)
                 .show_ui(ui, |ui| {
+                    let radar_type = state.radar_type();
+                    let mut level3_header = false;
                     for product in RadarProduct::all() {
-                        ui.selectable_value(
-                            &mut state.viz_state.product,
-                            *product,
-                            product.label(),
-                        );
+                        if let RadarProduct::Level3(level3) = product {
+                            if level3.radar_type() != radar_type {
+                                continue;
+                            }
+                            if !level3_header {
+                                ui.separator();
+                                ui.label(RichText::new("Level III").small().weak());
+                                level3_header = true;
+                            }
+                        }
+                        let available = state.viz_state.product_available(*product);
+                        ui.add_enabled_ui(available, |ui| {
+                            ui.selectable_value(
+                                &mut state.viz_state.product,
+                                *product,
+                                product.label(),
+                            )
+                            .on_disabled_hover_text("Not recorded in the current scan");
+                        });
                     }
                 });
 
+            render_level3_fetch(ui, state);
+
+            ui.checkbox(
+                &mut state.render_processing.dealias_velocity,
+                "Dealias velocity on ingest",
+            )
+            .on_hover_text(
+                "Store an unfolded copy of each velocity sweep next to the raw data. \
+                 Applies to scans downloaded after enabling.",
+            );
+
+            if state.viz_state.product == RadarProduct::StormRelativeVelocity {
+                render_storm_motion_controls(ui, state);
+            }
+            if state.viz_state.product.is_qpe_product() {
+                render_qpe_controls(ui, state);
+            }
+
             ui.add_space(8.0);
 
-            // Auto (latest sweep) checkbox
+            // Auto (latest sweep) and lowest-available checkboxes
             let mut is_auto = state.viz_state.elevation_selection.is_auto();
-            if ui
+            let mut is_lowest = state.viz_state.elevation_selection.is_lowest_available();
+            let auto_changed = ui
                 .checkbox(&mut is_auto, "Auto (latest sweep)")
                 .on_hover_text("Show the most recently completed sweep regardless of elevation")
-                .changed()
-            {
-                if is_auto {
-                    // Save current Fixed selection before switching to Latest
-                    if let ElevationSelection::Fixed {
-                        elevation_number,
-                        angle,
-                    } = &state.viz_state.elevation_selection
-                    {
-                        state.viz_state.last_fixed_selection = Some((*elevation_number, *angle));
-                    }
-                    state.viz_state.elevation_selection = ElevationSelection::Latest;
+                .changed();
+            let mut lowest_changed = false;
+            ui.horizontal(|ui| {
+                lowest_changed = ui
+                    .checkbox(&mut is_lowest, "Lowest available")
+                    .on_hover_text(
+                        "Show, at each gate, the newest valid data from the lowest tilt \
+                         collected in the window",
+                    )
+                    .changed();
+                ui.add_enabled(
+                    is_lowest,
+                    egui::DragValue::new(&mut state.viz_state.lowest_available_mins)
+                        .range(1..=30)
+                        .suffix(" min"),
+                )
+                .on_hover_text("How far back the composite looks");
+            });
+            if auto_changed || lowest_changed {
+                let mode = if auto_changed && is_auto {
+                    Some(ElevationSelection::Latest)
+                } else if lowest_changed && is_lowest {
+                    Some(ElevationSelection::LowestAvailable)
                 } else {
-                    // Restore previous Fixed selection
-                    let (num, angle) = state.viz_state.last_fixed_selection.unwrap_or((1, 0.5));
-                    state.viz_state.elevation_selection = ElevationSelection::Fixed {
-                        elevation_number: num,
-                        angle,
-                    };
+                    None
+                };
+                match mode {
+                    Some(mode) => {
+                        // Save current Fixed selection before switching modes
+                        if let ElevationSelection::Fixed {
+                            elevation_number,
+                            angle,
+                        } = &state.viz_state.elevation_selection
+                        {
+                            state.viz_state.last_fixed_selection =
+                                Some((*elevation_number, *angle));
+                        }
+                        state.viz_state.elevation_selection = mode;
+                    }
+                    None => {
+                        // Restore previous Fixed selection
+                        let (num, angle) = state.viz_state.last_fixed_selection.unwrap_or((1, 0.5));
+                        state.viz_state.elevation_selection = ElevationSelection::Fixed {
+                            elevation_number: num,
+                            angle,
+                        };
+                    }
                 }
             }
 
@@ -94,7 +179,52 @@ pub(super) fn render_product_section(ui: &mut egui::Ui, state: &mut AppState) {
 
             // Elevation list
             let entries = state.viz_state.cached_vcp_elevations.clone();
-            let list_enabled = !is_auto;
+            let is_volume_product = state.viz_state.product.is_volume_product();
+            let is_qpe_product = state.viz_state.product.is_qpe_product();
+            let is_fixed = state
+                .viz_state
+                .elevation_selection
+                .elevation_number()
+                .is_some();
+            let list_enabled = is_fixed && !is_volume_product && !is_qpe_product;
+            if state.viz_state.product.is_level3() {
+                ui.label(
+                    RichText::new("Level III product \u{2014} from the RPG archive")
+                        .small()
+                        .weak(),
+                );
+            } else if is_volume_product {
+                ui.label(
+                    RichText::new("Volume product \u{2014} built from all elevations")
+                        .small()
+                        .weak(),
+                );
+                if state.viz_state.volume_product_missing {
+                    ui.label(
+                        RichText::new(
+                            "Not available for this scan \u{2014} it was cached before volume \
+                             products were built. Use Clear Cache and download it again.",
+                        )
+                        .small()
+                        .color(egui::Color32::from_rgb(230, 180, 80)),
+                    );
+                }
+            } else if is_qpe_product {
+                ui.label(
+                    RichText::new("Accumulation \u{2014} built from the lowest tilt")
+                        .small()
+                        .weak(),
+                );
+            } else if state.viz_state.elevation_selection.is_lowest_available() {
+                ui.label(
+                    RichText::new(format!(
+                        "Composite \u{2014} lowest tilt over the last {} min",
+                        state.viz_state.lowest_available_mins
+                    ))
+                    .small()
+                    .weak(),
+                );
+            }
             let selected_product = state.viz_state.product.to_worker_string();
 
             ui.add_enabled_ui(list_enabled, |ui| {
@@ -200,6 +330,120 @@ pub(super) fn render_product_section(ui: &mut egui::Ui, state: &mut AppState) {
         });
 }
 
+/// Split-view layout and the product/tilt of each extra panel.
+fn render_split_view_section(ui: &mut egui::Ui, state: &mut AppState) {
+    egui::CollapsingHeader::new(RichText::new("Split View").strong())
+        .default_open(false)
+        .show(ui, |ui| {
+            ui.horizontal(|ui| {
+                for layout in PanelLayout::all() {
+                    ui.selectable_value(&mut state.viz_state.panel_layout, *layout, layout.label());
+                }
+            });
+            if state.viz_state.view_mode != ViewMode::Flat2D {
+                ui.label(
+                    RichText::new("Split view is shown on the 2D map")
+                        .small()
+                        .weak(),
+                );
+            }
+
+            let extra = state.viz_state.panel_layout.panel_count() - 1;
+            let entries = state.viz_state.cached_vcp_elevations.clone();
+            let products: Vec<(RadarProduct, bool)> = RadarProduct::all()
+                .iter()
+                .filter(|p| !p.is_qpe_product())
+                .map(|p| (*p, state.viz_state.product_available(*p)))
+                .collect();
+            for i in 0..extra {
+                ui.add_space(4.0);
+                ui.label(format!("Panel {}:", i + 2));
+                let view = &mut state.viz_state.extra_panels[i];
+                egui::ComboBox::from_id_salt(("panel_product", i))
+                    .selected_text(view.product.label())
+                    .width(150.0)
+                    .show_ui(ui, |ui| {
+                        // Accumulations span many scans and only render in
+                        // the main panel.
+                        for (product, available) in &products {
+                            ui.add_enabled_ui(*available, |ui| {
+                                ui.selectable_value(&mut view.product, *product, product.label())
+                                    .on_disabled_hover_text("Not recorded in the current scan");
+                            });
+                        }
+                    });
+
+                if view.product.is_volume_product() {
+                    continue;
+                }
+                let elevation_text = match view.elevation_number {
+                    None => "Follow main tilt".to_string(),
+                    Some(n) => entries
+                        .iter()
+                        .find(|e| e.elevation_number == n)
+                        .map_or(format!("Elevation {}", n), |e| {
+                            format!("{:<3} {:.1}\u{00B0}", n, e.angle)
+                        }),
+                };
+                egui::ComboBox::from_id_salt(("panel_elevation", i))
+                    .selected_text(elevation_text)
+                    .width(150.0)
+                    .show_ui(ui, |ui| {
+                        ui.selectable_value(&mut view.elevation_number, None, "Follow main tilt");
+                        for entry in &entries {
+                            ui.selectable_value(
+                                &mut view.elevation_number,
+                                Some(entry.elevation_number),
+                                format!("{:<3} {:.1}\u{00B0}", entry.elevation_number, entry.angle),
+                            );
+                        }
+                    });
+            }
+        });
+}
+
+/// Product picker and button for fetching Level III products over the
+/// selection (or the loaded data), with the progress of the last fetch.
+fn render_level3_fetch(ui: &mut egui::Ui, state: &mut AppState) {
+    // Only offer the products the current site's network issues.
+    let radar_type = state.radar_type();
+    if state.level3.product.radar_type() != radar_type {
+        if let Some(product) = Level3Product::ALL
+            .into_iter()
+            .find(|p| p.radar_type() == radar_type)
+        {
+            state.level3.product = product;
+        }
+    }
+
+    ui.add_space(4.0);
+    ui.horizontal(|ui| {
+        egui::ComboBox::from_id_salt("level3_product")
+            .selected_text(state.level3.product.code())
+            .width(60.0)
+            .show_ui(ui, |ui| {
+                for product in Level3Product::ALL {
+                    if product.radar_type() == radar_type {
+                        ui.selectable_value(&mut state.level3.product, product, product.label());
+                    }
+                }
+            });
+        let busy = state.level3.is_busy();
+        let fetch = ui
+            .add_enabled(!busy, egui::Button::new("Fetch Level III"))
+            .on_hover_text("Download this product over the selection, or the loaded scans");
+        if fetch.clicked() {
+            state.push_command(crate::state::AppCommand::FetchLevel3);
+        }
+        if busy {
+            ui.spinner();
+        }
+    });
+    if state.level3.is_busy() || state.level3.stored > 0 || !state.level3.failures.is_empty() {
+        ui.label(RichText::new(state.level3.summary()).small().weak());
+    }
+}
+
 pub(super) fn render_layers_section(ui: &mut egui::Ui, state: &mut AppState) {
     egui::CollapsingHeader::new(RichText::new("Layers").strong())
         .default_open(true)
@@ -223,6 +467,197 @@ pub(super) fn render_layers_section(ui: &mut egui::Ui, state: &mut AppState) {
         });
 }
 
+/// Regional mosaic of neighbouring sites: site picks, compositing mode, and
+/// the button fetching their scans.
+fn render_mosaic_section(ui: &mut egui::Ui, state: &mut AppState) {
+    egui::CollapsingHeader::new(RichText::new("Regional Mosaic").strong())
+        .default_open(false)
+        .show(ui, |ui| {
+            let toggled = ui
+                .checkbox(&mut state.mosaic.enabled, "Show mosaic")
+                .on_hover_text(
+                    "Composite the lowest tilt of the picked sites under the active radar",
+                )
+                .changed();
+            if toggled && state.mosaic.enabled {
+                let site_id = state.viz_state.site_id.clone();
+                state.mosaic.seed(&site_id);
+            }
+
+            ui.horizontal(|ui| {
+                ui.label("Overlap:");
+                egui::ComboBox::from_id_salt("mosaic_mode")
+                    .selected_text(state.mosaic.mode.label())
+                    .show_ui(ui, |ui| {
+                        for mode in MosaicMode::ALL {
+                            ui.selectable_value(&mut state.mosaic.mode, mode, mode.label());
+                        }
+                    });
+            });
+            ui.add(
+                egui::Slider::new(&mut state.mosaic.max_age_mins, 2..=30)
+                    .text("Max age")
+                    .suffix(" min"),
+            )
+            .on_hover_text("Leave out sites whose latest scan is older than this");
+
+            ui.add_space(4.0);
+            ui.label(RichText::new("Sites").small().weak());
+            if let Some(site) = crate::data::get_site(&state.viz_state.site_id) {
+                let nearby = crate::data::sites_within(
+                    site.lat,
+                    site.lon,
+                    crate::state::MOSAIC_NEIGHBOUR_RANGE_KM,
+                );
+                for (nearby_site, distance_km) in nearby {
+                    let mut picked = state.mosaic.site_ids.iter().any(|s| s == nearby_site.id);
+                    let label = format!("{}  {:.0} km", nearby_site.id, distance_km);
+                    if ui.checkbox(&mut picked, label).changed() {
+                        state.mosaic.toggle_site(nearby_site.id);
+                    }
+                }
+            }
+
+            ui.add_space(4.0);
+            ui.horizontal(|ui| {
+                let busy = state.mosaic.is_fetching();
+                let fetch = ui
+                    .add_enabled(
+                        !busy && !state.mosaic.site_ids.is_empty(),
+                        egui::Button::new("Fetch neighbour scans"),
+                    )
+                    .on_hover_text(
+                        "Download the picked sites' scans over the selection, or at the playhead",
+                    );
+                if fetch.clicked() {
+                    state.push_command(crate::state::AppCommand::FetchMosaicScans);
+                }
+                if busy || state.mosaic.compositing {
+                    ui.spinner();
+                }
+            });
+            let mosaic = &state.mosaic;
+            if mosaic.is_fetching() || mosaic.stored > 0 || !mosaic.failures.is_empty() {
+                ui.label(RichText::new(mosaic.summary()).small().weak());
+            }
+            if mosaic.enabled && mosaic.texture.is_some() {
+                ui.label(
+                    RichText::new(format!(
+                        "{} of {} sites in the mosaic",
+                        mosaic.scan_keys.len(),
+                        mosaic.site_ids.len()
+                    ))
+                    .small()
+                    .weak(),
+                );
+            }
+            if let Some(error) = &mosaic.error {
+                ui.label(
+                    RichText::new(error)
+                        .small()
+                        .color(egui::Color32::from_rgb(255, 120, 120)),
+                );
+            }
+        });
+}
+
+/// Secondary sites drawn under the active one: visibility, opacity,
+/// unpinning, and a picker for nearby sites.
+fn render_pinned_sites_section(ui: &mut egui::Ui, state: &mut AppState) {
+    egui::CollapsingHeader::new(RichText::new("Pinned Sites").strong())
+        .default_open(false)
+        .show(ui, |ui| {
+            let use_local = state.use_local_time;
+            let mut unpin = None;
+            for site in &mut state.pinned_sites.sites {
+                ui.horizontal(|ui| {
+                    ui.checkbox(&mut site.visible, site.site_id.as_str());
+                    ui.add(
+                        egui::Slider::new(&mut site.opacity, 0.0..=1.0)
+                            .show_value(false)
+                            .text("Opacity"),
+                    );
+                    if ui
+                        .small_button("\u{2715}")
+                        .on_hover_text("Unpin this site")
+                        .clicked()
+                    {
+                        unpin = Some(site.site_id.clone());
+                    }
+                });
+                let status = match &site.shown_scan {
+                    Some(key) => crate::data::ScanKey::from_storage_key(key)
+                        .map(|k| {
+                            let ts = k.scan_start.as_secs() as f64;
+                            format!("Scan {}", super::canvas::format_time_short(ts, use_local))
+                        })
+                        .unwrap_or_default(),
+                    None if site.is_fetching() || site.loading => "Fetching\u{2026}".to_string(),
+                    None => "No scan at this time".to_string(),
+                };
+                ui.horizontal(|ui| {
+                    ui.label(RichText::new(status).small().weak());
+                    if site.is_fetching() {
+                        ui.spinner();
+                    }
+                });
+                if let Some(error) = &site.error {
+                    ui.label(
+                        RichText::new(error)
+                            .small()
+                            .color(egui::Color32::from_rgb(255, 120, 120)),
+                    );
+                }
+            }
+            if let Some(site_id) = unpin {
+                state.push_command(crate::state::AppCommand::UnpinSite(site_id));
+            }
+
+            let full = state.pinned_sites.sites.len() >= crate::state::MAX_PINNED_SITES;
+            let mut pick = None;
+            ui.add_enabled_ui(!full, |ui| {
+                egui::ComboBox::from_id_salt("pin_site")
+                    .selected_text("Pin a nearby site\u{2026}")
+                    .show_ui(ui, |ui| {
+                        let Some(active) = crate::data::get_site(&state.viz_state.site_id) else {
+                            return;
+                        };
+                        let nearby = crate::data::sites_within(
+                            active.lat,
+                            active.lon,
+                            crate::state::MOSAIC_NEIGHBOUR_RANGE_KM,
+                        );
+                        for (site, distance_km) in nearby {
+                            if site.id == active.id
+                                || state.pinned_sites.index_of(site.id).is_some()
+                            {
+                                continue;
+                            }
+                            let label = format!("{}  {:.0} km", site.id, distance_km);
+                            if ui.selectable_label(false, label).clicked() {
+                                pick = Some(site.id.to_string());
+                            }
+                        }
+                    });
+            });
+            if let Some(site_id) = pick {
+                state.push_command(crate::state::AppCommand::PinSite(site_id));
+            }
+
+            if !state.pinned_sites.sites.is_empty() {
+                let busy = state.pinned_sites.is_fetching();
+                let fetch = ui
+                    .add_enabled(!busy, egui::Button::new("Fetch pinned scans"))
+                    .on_hover_text(
+                        "Download the pinned sites' scans over the selection, or at the playhead",
+                    );
+                if fetch.clicked() {
+                    state.push_command(crate::state::AppCommand::FetchPinnedScans);
+                }
+            }
+        });
+}
+
 pub(super) fn render_rendering_section(ui: &mut egui::Ui, state: &mut AppState) {
     let in_macro = state.playback_state.playback_mode() == crate::state::PlaybackMode::Macro;
     egui::CollapsingHeader::new(RichText::new("Rendering").strong())
@@ -264,6 +699,18 @@ pub(super) fn render_rendering_section(ui: &mut egui::Ui, state: &mut AppState)
                 });
             });
 
+            // Frame interpolation (macro mode only — micro playback already
+            // moves continuously through each scan)
+            ui.add_enabled_ui(in_macro, |ui| {
+                ui.checkbox(&mut proc.frame_interpolation, "Frame Interpolation")
+                    .on_hover_text(if in_macro {
+                        "Fill the gap between scans of a fixed tilt with motion-compensated \
+                         frames during playback; interpolated frames are marked on the map"
+                    } else {
+                        "Frame interpolation only applies when zoomed out (macro playback mode)"
+                    });
+            });
+
             ui.add_space(4.0);
 
             // Opacity
@@ -282,9 +729,220 @@ pub(super) fn render_rendering_section(ui: &mut egui::Ui, state: &mut AppState)
         });
 }
 
-fn render_volume_section(ui: &mut egui::Ui, state: &mut AppState) {
-    use crate::state::ViewMode;
+/// Palette per moment, imported `.pal` files, and the import button.
+fn render_color_tables_section(ui: &mut egui::Ui, state: &mut AppState) {
+    use crate::nexrad::palette;
+
+    egui::CollapsingHeader::new(RichText::new("Color Tables").strong())
+        .default_open(false)
+        .show(ui, |ui| {
+            let mut changed = false;
+            egui::Grid::new("color_table_grid")
+                .num_columns(2)
+                .show(ui, |ui| {
+                    for &(moment, label) in palette::MOMENTS {
+                        ui.label(label);
+                        let selected = state.palettes.selected.get(moment).cloned();
+                        let mut choice = selected.clone();
+                        egui::ComboBox::from_id_salt(("color_table", moment))
+                            .selected_text(choice.as_deref().unwrap_or("Default"))
+                            .width(120.0)
+                            .show_ui(ui, |ui| {
+                                ui.selectable_value(&mut choice, None, "Default");
+                                for name in palette::available(&state.palettes, moment) {
+                                    ui.selectable_value(&mut choice, Some(name.clone()), name);
+                                }
+                            });
+                        if choice != selected {
+                            match choice {
+                                Some(name) => {
+                                    state.palettes.selected.insert(moment.to_string(), name);
+                                }
+                                None => {
+                                    state.palettes.selected.remove(moment);
+                                }
+                            }
+                            changed = true;
+                        }
+                        ui.end_row();
+                    }
+                });
+
+            let mut remove = None;
+            for imported in &state.palettes.imported {
+                ui.horizontal(|ui| {
+                    if ui
+                        .small_button("\u{2715}")
+                        .on_hover_text("Forget this palette")
+                        .clicked()
+                    {
+                        remove = Some((imported.moment.clone(), imported.name.clone()));
+                    }
+                    let moment = palette::MOMENTS
+                        .iter()
+                        .find(|(m, _)| *m == imported.moment)
+                        .map_or("", |(_, label)| label);
+                    ui.label(
+                        RichText::new(format!("{} ({})", imported.name, moment))
+                            .small()
+                            .weak(),
+                    );
+                });
+            }
+            if let Some((moment, name)) = remove {
+                state.palettes.remove(&moment, &name);
+                changed = true;
+            }
+
+            ui.add_space(4.0);
+            if ui
+                .button("Import .pal\u{2026}")
+                .on_hover_text(
+                    "GRLevelX / GR2Analyst color tables. The file's Product line picks \
+                     the moment, otherwise the displayed product's. .pal files can also \
+                     be dropped on the window.",
+                )
+                .clicked()
+            {
+                state.push_command(crate::state::AppCommand::ImportPalettes);
+            }
+
+            if changed {
+                state.push_command(crate::state::AppCommand::ApplyPalettes);
+            }
+        });
+}
+
+/// Storm motion source and manual vector for storm-relative velocity.
+fn render_storm_motion_controls(ui: &mut egui::Ui, state: &mut AppState) {
+    let viz = &mut state.viz_state;
+    ui.indent("storm_motion_indent", |ui| {
+        ui.label("Storm motion:");
+        ui.radio_value(
+            &mut viz.storm_motion_source,
+            StormMotionSource::TrackedCells,
+            "Tracked cells",
+        )
+        .on_hover_text("Mean motion of tracked storm cells from the latest reflectivity scan");
+        ui.radio_value(
+            &mut viz.storm_motion_source,
+            StormMotionSource::Manual,
+            "Manual",
+        );
+
+        match viz.storm_motion_source {
+            StormMotionSource::TrackedCells => match viz.tracked_mean_motion {
+                Some(m) => {
+                    ui.label(format!(
+                        "{:.0}\u{00B0} / {:.0} kt",
+                        m.heading_deg,
+                        m.speed_kt()
+                    ));
+                }
+                None => {
+                    ui.label(
+                        RichText::new(
+                            "No tracked motion yet \u{2014} enable Storm Cells on Reflectivity",
+                        )
+                        .weak(),
+                    );
+                }
+            },
+            StormMotionSource::Manual => {
+                ui.add(
+                    egui::Slider::new(&mut viz.storm_motion_heading_deg, 0.0..=359.0)
+                        .text("Heading")
+                        .suffix("\u{00B0}")
+                        .step_by(1.0),
+                );
+                ui.add(
+                    egui::Slider::new(&mut viz.storm_motion_speed_kt, 0.0..=80.0)
+                        .text("Speed")
+                        .suffix(" kt")
+                        .step_by(1.0),
+                );
+            }
+        }
+    });
+}
+
+/// Z-R relationship, R(KDP) blending and window for rainfall accumulations.
+///
+/// Settings are stored on the saved event matching the timeline selection,
+/// so each event keeps its own relationship.
+fn render_qpe_controls(ui: &mut egui::Ui, state: &mut AppState) {
+    let before = state.viz_state.qpe_settings;
+    let window = state.qpe_window();
+    let use_local_time = state.use_local_time;
+    let settings = &mut state.viz_state.qpe_settings;
+    ui.indent("qpe_indent", |ui| {
+        ui.label("Z-R relationship:");
+        ui.radio_value(
+            &mut settings.zr,
+            ZrRelation::MarshallPalmer,
+            ZrRelation::MarshallPalmer.label(),
+        )
+        .on_hover_text("Z = 200R\u{00B9}\u{00B7}\u{2076} \u{2014} stratiform and general rain");
+        ui.radio_value(
+            &mut settings.zr,
+            ZrRelation::Tropical,
+            ZrRelation::Tropical.label(),
+        )
+        .on_hover_text("Z = 250R\u{00B9}\u{00B7}\u{00B2} \u{2014} warm-rain, tropical systems");
+        let is_custom = matches!(settings.zr, ZrRelation::Custom { .. });
+        if ui.radio(is_custom, "Custom").clicked() && !is_custom {
+            let (a, b) = settings.zr.coefficients();
+            settings.zr = ZrRelation::Custom { a, b };
+        }
+        if let ZrRelation::Custom { a, b } = &mut settings.zr {
+            ui.horizontal(|ui| {
+                ui.label("a");
+                ui.add(egui::DragValue::new(a).range(10.0..=1000.0).speed(1.0));
+                ui.label("b");
+                ui.add(egui::DragValue::new(b).range(0.8..=2.5).speed(0.01));
+            });
+        }
+        ui.checkbox(&mut settings.use_kdp, "Blend R(KDP)")
+            .on_hover_text("Use specific differential phase where it is significant (heavy rain)");
 
+        match window {
+            Some((start, end)) => {
+                ui.label(
+                    RichText::new(format!(
+                        "{} \u{2013} {}",
+                        format_event_time(start, use_local_time),
+                        format_event_time(end, use_local_time),
+                    ))
+                    .small()
+                    .weak(),
+                );
+            }
+            None => {
+                ui.label(
+                    RichText::new("Select a time range on the timeline")
+                        .small()
+                        .weak(),
+                );
+            }
+        }
+    });
+
+    if state.viz_state.qpe_settings != before {
+        if let Some(range) = state.playback_state.selection_range() {
+            let event_id = state
+                .saved_events
+                .matching(&state.viz_state.site_id, range)
+                .map(|e| e.id);
+            if let Some(id) = event_id {
+                state
+                    .saved_events
+                    .set_qpe_settings(id, state.viz_state.qpe_settings);
+            }
+        }
+    }
+}
+
+fn render_volume_section(ui: &mut egui::Ui, state: &mut AppState) {
     // Only show volume controls in 3D mode
     if !matches!(state.viz_state.view_mode, ViewMode::Globe3D) {
         return;
@@ -329,6 +987,30 @@ pub(super) fn render_tools_section(ui: &mut egui::Ui, state: &mut AppState) {
                 state.viz_state.distance_start = None;
                 state.viz_state.distance_end = None;
             }
+            if !was_active && state.viz_state.distance_tool_active {
+                state.cross_section.tool_active = false;
+            }
+
+            let was_active = state.cross_section.tool_active;
+            ui.checkbox(&mut state.cross_section.tool_active, "Cross Section")
+                .on_hover_text("Click two points on the map to slice the volume between them");
+            if !was_active && state.cross_section.tool_active {
+                state.viz_state.distance_tool_active = false;
+                state.viz_state.distance_start = None;
+                state.viz_state.distance_end = None;
+            }
+            // Like the distance tool, switching off removes the line.
+            if was_active && !state.cross_section.tool_active {
+                state.cross_section.clear();
+                state.cross_section.window_open = false;
+            }
+            if state.cross_section.end.is_some() && !state.cross_section.window_open {
+                ui.indent("cross_section_indent", |ui| {
+                    if ui.small_button("Show section").clicked() {
+                        state.cross_section.window_open = true;
+                    }
+                });
+            }
 
             ui.checkbox(&mut state.viz_state.storm_cells_visible, "Storm Cells")
                 .on_hover_text("Detect and display storm cells on the radar");
@@ -347,11 +1029,151 @@ pub(super) fn render_tools_section(ui: &mut egui::Ui, state: &mut AppState) {
                         // Clear cached results so detection re-runs with new threshold
                         state.viz_state.detected_storm_cells.clear();
                     }
+                    ui.checkbox(
+                        &mut state.viz_state.storm_tracks_visible,
+                        "Tracks & Forecast",
+                    )
+                    .on_hover_text(
+                        "Draw each cell's past path and its 15/30/45/60-minute forecast cone",
+                    );
+                });
+            }
+
+            ui.checkbox(&mut state.viz_state.rotation_visible, "Rotation")
+                .on_hover_text("Detect mesocyclone-scale velocity couplets on velocity sweeps");
+            if state.viz_state.rotation_visible {
+                ui.indent("rotation_indent", |ui| {
+                    if !state.viz_state.product.is_velocity() {
+                        ui.label(RichText::new("Select Velocity to detect rotation").weak());
+                        return;
+                    }
+                    match &state.viz_state.detected_rotations {
+                        Some(rotations) if !rotations.is_empty() => {
+                            for rot in rotations {
+                                ui.label(
+                                    RichText::new(format!(
+                                        "{:.0} kt  \u{2300}{:.1} km  {:.1} km ARL  {:.0}\u{00B0}/{:.0} km",
+                                        rot.rotational_velocity_kt(),
+                                        rot.diameter_km,
+                                        rot.height_km,
+                                        rot.bearing_from_radar_deg,
+                                        rot.range_from_radar_km,
+                                    ))
+                                    .small()
+                                    .monospace(),
+                                )
+                                .on_hover_text(format!(
+                                    "\u{0394}V {:.0} m/s \u{00B7} peak shear {:.1}\u{00D7}10\u{207B}\u{00B3} s\u{207B}\u{00B9} \u{00B7} {} pattern vectors",
+                                    rot.delta_v_ms,
+                                    rot.max_shear_per_s * 1000.0,
+                                    rot.pattern_vector_count,
+                                ));
+                            }
+                        }
+                        Some(_) => {
+                            ui.label(RichText::new("No rotation detected").weak());
+                        }
+                        None => {}
+                    }
                 });
             }
         });
 }
 
+fn render_export_section(ui: &mut egui::Ui, state: &mut AppState) {
+    egui::CollapsingHeader::new(RichText::new("Export").strong())
+        .default_open(false)
+        .show(ui, |ui| {
+            let export = &mut state.export;
+            ui.add_enabled_ui(!export.is_capturing(), |ui| {
+                egui::ComboBox::from_id_salt("export_format")
+                    .selected_text(export.format.label())
+                    .width(150.0)
+                    .show_ui(ui, |ui| {
+                        for format in ExportFormat::all() {
+                            ui.selectable_value(&mut export.format, *format, format.label());
+                        }
+                    });
+                if export.format.is_image() {
+                    egui::ComboBox::from_id_salt("export_resolution")
+                        .selected_text(export.resolution.label())
+                        .width(150.0)
+                        .show_ui(ui, |ui| {
+                            for resolution in ExportResolution::all() {
+                                ui.selectable_value(
+                                    &mut export.resolution,
+                                    *resolution,
+                                    resolution.label(),
+                                );
+                            }
+                        });
+                    ui.checkbox(&mut export.burn_timestamp, "Burn in timestamp");
+                }
+                let hint = match export.format {
+                    ExportFormat::Png => None,
+                    ExportFormat::Gif => {
+                        ui.add(
+                            egui::Slider::new(&mut export.frame_delay_ms, 50..=2000)
+                                .text("ms/frame")
+                                .logarithmic(true),
+                        );
+                        Some("One frame per sweep in the timeline selection")
+                    }
+                    ExportFormat::CfRadial => {
+                        Some("Every cached elevation and moment of this scan")
+                    }
+                    ExportFormat::GeoTiff | ExportFormat::Csv => {
+                        Some("Displayed product on a 0.01\u{00B0} lat/lon grid")
+                    }
+                };
+                if let Some(hint) = hint {
+                    ui.label(RichText::new(hint).small().weak());
+                }
+            });
+
+            ui.add_space(4.0);
+            match export.progress {
+                Some(progress) => {
+                    ui.horizontal(|ui| {
+                        ui.spinner();
+                        ui.label(format!(
+                            "Frame {}/{}",
+                            progress.captured + 1,
+                            progress.total
+                        ));
+                        if ui.button("Cancel").clicked() {
+                            export.cancel_requested = true;
+                        }
+                    });
+                }
+                None if export.data_pending => {
+                    ui.horizontal(|ui| {
+                        ui.spinner();
+                        ui.label("Writing CF-Radial\u{2026}");
+                    });
+                }
+                None => {
+                    if ui.button("Export").clicked() {
+                        export.start_requested = true;
+                    }
+                }
+            }
+            match &export.last_result {
+                Some(Ok(message)) => {
+                    ui.label(RichText::new(message).small().weak());
+                }
+                Some(Err(message)) => {
+                    ui.label(
+                        RichText::new(message)
+                            .small()
+                            .color(egui::Color32::from_rgb(255, 120, 120)),
+                    );
+                }
+                None => {}
+            }
+        });
+}
+
 pub(super) fn render_events_section(ui: &mut egui::Ui, state: &mut AppState) {
     egui::CollapsingHeader::new(RichText::new("Events").strong())
         .default_open(true)
@@ -494,6 +1316,10 @@ fn navigate_to_event(state: &mut AppState, event: &crate::state::SavedEvent) {
         }
     }
 
+    if let Some(settings) = event.qpe_settings {
+        state.viz_state.qpe_settings = settings;
+    }
+
     // Set selection to event bounds
     state.playback_state.selection_start = Some(event.start_time);
     state.playback_state.selection_end = Some(event.end_time);
@@ -506,16 +1332,9 @@ fn navigate_to_event(state: &mut AppState, event: &crate::state::SavedEvent) {
 /// Format a timestamp for display in the events list.
 fn format_event_time(ts: f64, use_local: bool) -> String {
     if use_local {
-        let d = js_sys::Date::new_0();
-        d.set_time(ts * 1000.0);
-        format!(
-            "{:04}-{:02}-{:02} {:02}:{:02}",
-            d.get_full_year(),
-            d.get_month() + 1,
-            d.get_date(),
-            d.get_hours(),
-            d.get_minutes(),
-        )
+        crate::platform::local_datetime(ts)
+            .format("%Y-%m-%d %H:%M")
+            .to_string()
     } else {
         use chrono::{TimeZone, Utc};
         let dt = Utc.timestamp_opt(ts as i64, 0).unwrap();
@@ -601,6 +1420,35 @@ pub(super) fn render_storage_section(ui: &mut egui::Ui, state: &mut AppState) {
 
             ui.add_space(8.0);
 
+            ui.label("Import from disk:");
+            ui.horizontal(|ui| {
+                if ui
+                    .button("Files\u{2026}")
+                    .on_hover_text(
+                        "Archive II volumes, plain or gzip/bzip2 wrapped. \
+                         Files can also be dropped onto the window.",
+                    )
+                    .clicked()
+                {
+                    state.push_command(crate::state::AppCommand::ImportFiles { directory: false });
+                }
+                if ui
+                    .button("Chunk folder\u{2026}")
+                    .on_hover_text("A folder of real-time chunk files (YYYYMMDD-HHMMSS-NNN-S/I/E)")
+                    .clicked()
+                {
+                    state.push_command(crate::state::AppCommand::ImportFiles { directory: true });
+                }
+            });
+            if state.import.is_busy() {
+                ui.horizontal(|ui| {
+                    ui.spinner();
+                    ui.label(RichText::new(state.import.summary()).small());
+                });
+            }
+
+            ui.add_space(8.0);
+
             if ui
                 .button("Clear Cache")
                 .on_hover_text("Delete all cached radar data")
@@ -620,3 +1468,109 @@ pub(super) fn render_storage_section(ui: &mut egui::Ui, state: &mut AppState) {
             }
         });
 }
+
+fn render_data_source_section(ui: &mut egui::Ui, state: &mut AppState) {
+    egui::CollapsingHeader::new(RichText::new("Data Source").strong())
+        .default_open(false)
+        .show(ui, |ui| {
+            let source = &mut state.data_source;
+            ui.label(
+                RichText::new(
+                    "S3-compatible base URLs: a mirror, MinIO, or scripts/fixture_server.py",
+                )
+                .small()
+                .weak(),
+            );
+
+            ui.label("Archive:");
+            ui.add(
+                egui::TextEdit::singleline(&mut source.archive_url)
+                    .hint_text("AWS (default)")
+                    .desired_width(f32::INFINITY),
+            );
+            ui.label("Real-time:");
+            ui.add_enabled(
+                source.replay.is_none(),
+                egui::TextEdit::singleline(&mut source.realtime_url)
+                    .hint_text("AWS (default)")
+                    .desired_width(f32::INFINITY),
+            );
+            ui.label("Level III:");
+            ui.add(
+                egui::TextEdit::singleline(&mut source.level3_url)
+                    .hint_text("AWS (default)")
+                    .desired_width(f32::INFINITY),
+            );
+
+            let mut replay = source.replay.is_some();
+            if ui
+                .checkbox(&mut replay, "Replay archive as live")
+                .on_hover_text(
+                    "Live mode streams archive volumes cut into chunks, \
+                     released on a clock from the start time",
+                )
+                .changed()
+            {
+                source.replay = replay.then(|| ReplaySettings {
+                    start_secs: state.playback_state.playback_position() as i64,
+                    speed: 1.0,
+                });
+            }
+
+            if let Some(replay) = &mut source.replay {
+                // The start time is typed as text; keep the edit buffer across
+                // frames and only take it once it parses.
+                let id = ui.id().with("replay_start");
+                let mut text = ui.data_mut(|d| {
+                    d.get_temp::<String>(id).unwrap_or_else(|| {
+                        chrono::DateTime::from_timestamp(replay.start_secs, 0)
+                            .map(|t| t.format("%Y-%m-%d %H:%M").to_string())
+                            .unwrap_or_default()
+                    })
+                });
+                ui.horizontal(|ui| {
+                    ui.label("Start (UTC):");
+                    ui.add(egui::TextEdit::singleline(&mut text).desired_width(120.0));
+                });
+                match crate::state::console::parse_time(&text) {
+                    Ok(secs) => replay.start_secs = secs as i64,
+                    Err(e) => {
+                        ui.label(
+                            RichText::new(e)
+                                .small()
+                                .color(egui::Color32::from_rgb(255, 120, 120)),
+                        );
+                    }
+                }
+                ui.data_mut(|d| d.insert_temp(id, text));
+
+                ui.horizontal(|ui| {
+                    ui.label("Speed:");
+                    ui.add(
+                        egui::DragValue::new(&mut replay.speed)
+                            .range(0.25..=60.0)
+                            .speed(0.1)
+                            .suffix("\u{00D7}"),
+                    );
+                });
+            }
+
+            ui.add_space(4.0);
+            ui.horizontal(|ui| {
+                if ui
+                    .button("Apply")
+                    .on_hover_text("Use this source for new requests (stops live mode)")
+                    .clicked()
+                {
+                    state.push_command(crate::state::AppCommand::ApplyDataSource);
+                }
+                if ui
+                    .add_enabled(state.data_source.is_custom(), egui::Button::new("Use AWS"))
+                    .clicked()
+                {
+                    state.data_source = Default::default();
+                    state.push_command(crate::state::AppCommand::ApplyDataSource);
+                }
+            });
+        });
+}
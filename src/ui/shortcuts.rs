@@ -118,6 +118,10 @@ const GENERAL_SHORTCUTS: &[Shortcut] = &[
         key: "?",
         description: "Toggle this help overlay",
     },
+    Shortcut {
+        key: "`",
+        description: "Toggle command console",
+    },
     Shortcut {
         key: "Esc",
         description: "Close open modal / overlay",
@@ -165,6 +169,7 @@ pub fn handle_shortcuts(ctx: &egui::Context, state: &mut AppState) {
     let cycle_product = ctx.input(|i| i.key_pressed(egui::Key::P) && !i.modifiers.any());
     let cycle_elevation = ctx.input(|i| i.key_pressed(egui::Key::E) && !i.modifiers.any());
     let open_site = ctx.input(|i| i.key_pressed(egui::Key::S) && !i.modifiers.any());
+    let toggle_console = ctx.input(|i| i.key_pressed(egui::Key::Backtick) && !i.modifiers.any());
     let toggle_help = ctx.input(|i| {
         // ? requires Shift on most layouts
         i.key_pressed(egui::Key::Questionmark)
@@ -219,7 +224,8 @@ pub fn handle_shortcuts(ctx: &egui::Context, state: &mut AppState) {
                 .radar_timeline
                 .prev_matching_sweep_end_by_number(current_pos, *elevation_number)
                 .unwrap_or(current_pos - jog_fallback),
-            crate::state::ElevationSelection::Latest => state
+            crate::state::ElevationSelection::Latest
+            | crate::state::ElevationSelection::LowestAvailable => state
                 .radar_timeline
                 .prev_any_sweep_end(current_pos)
                 .unwrap_or(current_pos - jog_fallback),
@@ -236,7 +242,8 @@ pub fn handle_shortcuts(ctx: &egui::Context, state: &mut AppState) {
                 .radar_timeline
                 .next_matching_sweep_end_by_number(current_pos, *elevation_number)
                 .unwrap_or(current_pos + jog_fallback),
-            crate::state::ElevationSelection::Latest => state
+            crate::state::ElevationSelection::Latest
+            | crate::state::ElevationSelection::LowestAvailable => state
                 .radar_timeline
                 .next_any_sweep_end(current_pos)
                 .unwrap_or(current_pos + jog_fallback),
@@ -281,7 +288,13 @@ pub fn handle_shortcuts(ctx: &egui::Context, state: &mut AppState) {
     if cycle_product {
         let products = RadarProduct::all();
         if let Some(idx) = products.iter().position(|p| *p == state.viz_state.product) {
-            state.viz_state.product = products[(idx + 1) % products.len()];
+            // Skip products the current scan doesn't carry.
+            if let Some(next) = (1..products.len())
+                .map(|step| products[(idx + step) % products.len()])
+                .find(|p| state.viz_state.product_available(*p))
+            {
+                state.viz_state.product = next;
+            }
         }
     }
 
@@ -298,7 +311,8 @@ pub fn handle_shortcuts(ctx: &egui::Context, state: &mut AppState) {
                     .iter()
                     .position(|e| e.elevation_number == *elevation_number)
                     .unwrap_or(0),
-                crate::state::ElevationSelection::Latest => 0,
+                crate::state::ElevationSelection::Latest
+                | crate::state::ElevationSelection::LowestAvailable => 0,
             };
             let next_idx = (current_idx + 1) % entries.len();
             let entry = &entries[next_idx];
@@ -408,6 +422,10 @@ pub fn handle_shortcuts(ctx: &egui::Context, state: &mut AppState) {
     if toggle_help {
         state.shortcuts_help_visible = !state.shortcuts_help_visible;
     }
+
+    if toggle_console {
+        state.console.open = !state.console.open;
+    }
 }
 
 /// Render the keyboard shortcut help overlay.
@@ -8,11 +8,9 @@
 use std::cell::RefCell;
 use std::rc::Rc;
 
-use crate::data::{all_sites_sorted, get_site, nearest_site};
+use crate::data::{all_sites_sorted, get_site, nearest_site, RadarType};
 use crate::state::AppState;
 use eframe::egui::{self, Color32, RichText, Vec2};
-use wasm_bindgen::prelude::*;
-use wasm_bindgen::JsCast;
 
 /// Which view the modal is currently showing.
 #[derive(Default, Clone, PartialEq)]
@@ -40,6 +38,8 @@ pub enum LocationResult {
 pub struct SiteModalState {
     /// Search filter for the site list view.
     pub filter: String,
+    /// Radar type shown in the site list view (`None` = all).
+    pub radar_filter: Option<RadarType>,
     /// Current modal view.
     pub mode: SiteModalMode,
     /// Zip code input string.
@@ -57,6 +57,7 @@ impl Default for SiteModalState {
         let location_results = Rc::new(RefCell::new(Vec::new()));
         Self {
             filter: String::new(),
+            radar_filter: None,
             mode: SiteModalMode::Welcome,
             zip_input: String::new(),
             error_message: None,
@@ -108,7 +109,11 @@ pub(super) fn apply_site_selection(state: &mut AppState, site_id: &str, lat: f64
 }
 
 /// Start browser geolocation lookup.
+#[cfg(target_arch = "wasm32")]
 fn start_geolocation(results: Rc<RefCell<Vec<LocationResult>>>, ctx: egui::Context) {
+    use wasm_bindgen::prelude::*;
+    use wasm_bindgen::JsCast;
+
     let window = match web_sys::window() {
         Some(w) => w,
         None => {
@@ -169,45 +174,47 @@ fn start_geolocation(results: Rc<RefCell<Vec<LocationResult>>>, ctx: egui::Conte
     error_cb.forget();
 }
 
+/// The desktop build has no location service to ask.
+#[cfg(not(target_arch = "wasm32"))]
+fn start_geolocation(results: Rc<RefCell<Vec<LocationResult>>>, _ctx: egui::Context) {
+    results.borrow_mut().push(LocationResult::Error(
+        "Location lookup is only available in the browser; enter a zip code instead".into(),
+    ));
+}
+
 /// Start zip code geocoding via the Zippopotam.us API.
 fn start_zip_lookup(zip: &str, results: Rc<RefCell<Vec<LocationResult>>>, ctx: egui::Context) {
     let url = format!("https://api.zippopotam.us/us/{}", zip);
     let results = results.clone();
 
-    wasm_bindgen_futures::spawn_local(async move {
+    crate::platform::spawn_local(async move {
         let result = async {
-            let window = web_sys::window().ok_or("No browser window")?;
-            let resp_value = wasm_bindgen_futures::JsFuture::from(window.fetch_with_str(&url))
+            let resp = crate::platform::http_get(&url, &[])
                 .await
                 .map_err(|_| "Network error looking up zip code".to_string())?;
-            let resp: web_sys::Response = resp_value
-                .dyn_into()
-                .map_err(|_| "Invalid response".to_string())?;
 
             if !resp.ok() {
                 return Err("Zip code not found".to_string());
             }
 
-            let json = wasm_bindgen_futures::JsFuture::from(
-                resp.json()
-                    .map_err(|_| "Failed to parse response".to_string())?,
-            )
-            .await
-            .map_err(|_| "Failed to read response body".to_string())?;
+            let json: serde_json::Value = serde_json::from_slice(&resp.body)
+                .map_err(|_| "Failed to parse response".to_string())?;
 
             // Zippopotam response: { "places": [{ "latitude": "...", "longitude": "..." }] }
-            let places = js_sys::Reflect::get(&json, &"places".into())
-                .map_err(|_| "Invalid response format".to_string())?;
-            let first = js_sys::Reflect::get_u32(&places, 0)
-                .map_err(|_| "No location data for zip code".to_string())?;
-
-            let lat_str = js_sys::Reflect::get(&first, &"latitude".into())
-                .map_err(|_| "Missing latitude".to_string())?
-                .as_string()
+            let first = json
+                .get("places")
+                .and_then(|places| places.get(0))
+                .ok_or("No location data for zip code")?;
+
+            let lat_str = first
+                .get("latitude")
+                .ok_or("Missing latitude")?
+                .as_str()
                 .ok_or("Invalid latitude")?;
-            let lon_str = js_sys::Reflect::get(&first, &"longitude".into())
-                .map_err(|_| "Missing longitude".to_string())?
-                .as_string()
+            let lon_str = first
+                .get("longitude")
+                .ok_or("Missing longitude")?
+                .as_str()
                 .ok_or("Invalid longitude")?;
 
             let lat: f64 = lat_str
@@ -507,27 +514,37 @@ fn render_site_list(
                 }
             });
 
+            ui.horizontal(|ui| {
+                ui.label("Type:");
+                ui.selectable_value(&mut modal_state.radar_filter, None, "All");
+                for radar_type in [RadarType::Wsr88d, RadarType::Tdwr] {
+                    ui.selectable_value(
+                        &mut modal_state.radar_filter,
+                        Some(radar_type),
+                        radar_type.label(),
+                    );
+                }
+            });
+
             ui.add_space(8.0);
             ui.separator();
             ui.add_space(4.0);
 
             // Filter sites
             let filter_upper = modal_state.filter.to_uppercase();
-            let sites = all_sites_sorted();
-            let filtered: Vec<_> = if modal_state.filter.is_empty() {
-                sites.clone()
-            } else {
-                sites
-                    .into_iter()
-                    .filter(|s| {
-                        s.id.contains(&filter_upper)
-                            || s.name.contains(&filter_upper)
-                            || s.state
-                                .map(|st| st.to_uppercase().contains(&filter_upper))
-                                .unwrap_or(false)
-                    })
-                    .collect()
-            };
+            let radar_filter = modal_state.radar_filter;
+            let filtered: Vec<_> = all_sites_sorted()
+                .into_iter()
+                .filter(|s| radar_filter.is_none_or(|t| s.radar_type() == t))
+                .filter(|s| {
+                    modal_state.filter.is_empty()
+                        || s.id.contains(&filter_upper)
+                        || s.name.contains(&filter_upper)
+                        || s.state
+                            .map(|st| st.to_uppercase().contains(&filter_upper))
+                            .unwrap_or(false)
+                })
+                .collect();
 
             // Enter key selects the site when filter narrows to exactly one result
             let enter_pressed = ui.input(|i| i.key_pressed(egui::Key::Enter));
@@ -558,7 +575,10 @@ fn render_site_list(
                 .show(ui, |ui| {
                     for site in &filtered {
                         let is_current = site.id == state.viz_state.site_id;
-                        let label = site.display_label();
+                        let label = match site.radar_type() {
+                            RadarType::Wsr88d => site.display_label(),
+                            RadarType::Tdwr => format!("{} (TDWR)", site.display_label()),
+                        };
 
                         let text = if is_current {
                             RichText::new(format!("{} {}", label, egui_phosphor::regular::CHECK))
@@ -22,7 +22,7 @@ use tooltips::render_timeline_tooltip;
 
 /// Get current Unix timestamp in seconds.
 pub(super) fn current_timestamp_secs() -> f64 {
-    js_sys::Date::now() / 1000.0
+    crate::platform::now_secs()
 }
 
 /// Level of detail for radar data rendering
@@ -155,15 +155,14 @@ pub(super) struct DateTimeComponents {
 impl DateTimeComponents {
     pub(super) fn from_timestamp(timestamp: i64, use_local: bool) -> Self {
         if use_local {
-            let d = js_sys::Date::new_0();
-            d.set_time((timestamp as f64) * 1000.0);
+            let dt = crate::platform::local_datetime(timestamp as f64);
             Self {
-                year: d.get_full_year() as i32,
-                month: d.get_month() + 1, // JS months are 0-based
-                day: d.get_date(),
-                hour: d.get_hours(),
-                minute: d.get_minutes(),
-                second: d.get_seconds(),
+                year: dt.year(),
+                month: dt.month(),
+                day: dt.day(),
+                hour: dt.hour(),
+                minute: dt.minute(),
+                second: dt.second(),
             }
         } else {
             let dt = Utc.timestamp_opt(timestamp, 0).unwrap();
@@ -443,7 +442,7 @@ pub(super) fn render_timeline(ui: &mut egui::Ui, state: &mut AppState) {
 
     // -- Render real-time partial scan progress --
     // Compute `now` once per frame so render + tooltip use a consistent boundary.
-    let frame_now_secs = js_sys::Date::now() / 1000.0;
+    let frame_now_secs = crate::platform::now_secs();
     if let Some(ref position) = state.live_radar_model.position {
         let anim_time = ui.ctx().input(|i| i.time);
         let overlay_ctx = overlays::LiveOverlayContext {
@@ -495,11 +494,10 @@ pub(super) fn render_timeline(ui: &mut egui::Ui, state: &mut AppState) {
     // not UTC midnight.  We obtain the browser's timezone offset and shift
     // into local seconds for alignment, then shift back to UTC for plotting.
     let tz_offset_secs: i64 = if use_local {
-        let d = js_sys::Date::new_0();
-        d.set_time(view_start * 1000.0);
-        // getTimezoneOffset() returns minutes positive-west; convert to
-        // seconds-east so that local = utc + tz_offset_secs.
-        -(d.get_timezone_offset() as i64) * 60
+        // Seconds east of UTC, so that local = utc + tz_offset_secs.
+        crate::platform::local_datetime(view_start)
+            .offset()
+            .local_minus_utc() as i64
     } else {
         0
     };
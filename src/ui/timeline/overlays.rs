@@ -58,7 +58,7 @@ pub(super) fn render_download_ghosts(
         return;
     }
 
-    let now_wall = js_sys::Date::now() / 1000.0;
+    let now_wall = crate::platform::now_secs();
 
     // Recently completed scans — brief green flash
     for &(scan_start, completion_time) in &progress.recently_completed {
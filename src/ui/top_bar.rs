@@ -11,7 +11,7 @@ pub fn render_top_bar(ctx: &egui::Context, state: &mut AppState) {
     let status_id = egui::Id::new("__last_status_msg");
     let prev_msg: Option<String> = ctx.data(|d| d.get_temp(status_id));
     if prev_msg.as_deref() != Some(&state.status_message) {
-        state.status_message_set_ms = js_sys::Date::now();
+        state.status_message_set_ms = crate::platform::now_ms();
         ctx.data_mut(|d| d.insert_temp(status_id, state.status_message.clone()));
     }
 
@@ -91,7 +91,7 @@ pub fn render_top_bar(ctx: &egui::Context, state: &mut AppState) {
                 // text with chunk counts/countdown).
                 if state.app_mode != AppMode::Live && !state.status_message.is_empty() {
                     // Auto-dismiss: fade out after 8 seconds, clear after 10
-                    let now = js_sys::Date::now();
+                    let now = crate::platform::now_ms();
                     let age_ms = now - state.status_message_set_ms;
                     const FADE_START_MS: f64 = 8000.0;
                     const DISMISS_MS: f64 = 10000.0;
@@ -177,12 +177,9 @@ pub fn render_top_bar(ctx: &egui::Context, state: &mut AppState) {
                             .on_hover_text(format!("{} — click to view changelog", full_version));
 
                         if clicked {
-                            if let Some(window) = web_sys::window() {
-                                let _ = window.open_with_url_and_target(
-                                    "https://github.com/danielway/nexrad-workbench/releases",
-                                    "_blank",
-                                );
-                            }
+                            ui.ctx().open_url(egui::OpenUrl::new_tab(
+                                "https://github.com/danielway/nexrad-workbench/releases",
+                            ));
                         }
                     }
 
@@ -780,15 +780,9 @@ fn stats_on(values: &[f64]) -> Option<(f64, f64, f64)> {
 
 /// Format a Unix-seconds timestamp as `YYYY-MM-DD HH:MM:SSZ`.
 fn format_time(secs: f64) -> String {
-    let ms = (secs * 1000.0) as i64;
-    let date = js_sys::Date::new(&wasm_bindgen::JsValue::from_f64(ms as f64));
-    let iso = date.to_iso_string().as_string().unwrap_or_default();
-    // "2026-04-21T18:14:03.000Z" → "2026-04-21 18:14:03Z"
-    if iso.len() >= 20 {
-        format!("{} {}Z", &iso[0..10], &iso[11..19])
-    } else {
-        iso
-    }
+    chrono::DateTime::from_timestamp_millis((secs * 1000.0) as i64)
+        .map(|dt| dt.format("%Y-%m-%d %H:%M:%SZ").to_string())
+        .unwrap_or_default()
 }
 
 // ── Plain-text serialization (clipboard payload) ────────────────────────
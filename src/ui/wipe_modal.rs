@@ -1,6 +1,7 @@
 //! Confirmation modal for wiping all application data.
 //!
-//! Clears IndexedDB stores, localStorage, and reloads the page.
+//! Clears the record cache and saved settings, then reloads the page (or,
+//! on the desktop, closes the app).
 
 use crate::state::AppState;
 use eframe::egui::{self, Color32, RichText, Vec2};
@@ -30,16 +31,28 @@ pub fn render_wipe_modal(ctx: &egui::Context, state: &mut AppState) {
 
             ui.add_space(8.0);
 
-            ui.label("  \u{2022} All cached radar data (IndexedDB)");
-            ui.label("  \u{2022} Settings and preferences (localStorage)");
+            let (cache, settings, after) = if cfg!(target_arch = "wasm32") {
+                (
+                    "IndexedDB",
+                    "localStorage",
+                    "The page will reload after reset.",
+                )
+            } else {
+                (
+                    "disk cache",
+                    "config files",
+                    "The app will close after reset.",
+                )
+            };
+            ui.label(format!("  \u{2022} All cached radar data ({})", cache));
+            ui.label(format!(
+                "  \u{2022} Settings and preferences ({})",
+                settings
+            ));
 
             ui.add_space(8.0);
 
-            ui.label(
-                RichText::new("The page will reload after reset.")
-                    .weak()
-                    .italics(),
-            );
+            ui.label(RichText::new(after).weak().italics());
 
             ui.add_space(12.0);
             ui.separator();
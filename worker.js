@@ -11,13 +11,18 @@
 //     Worker → Main:  { type: 'ready' }
 //
 //   Archive ingest (full file → split, decode, store in IDB):
-//     Main → Worker:  { type: 'ingest', id, data: ArrayBuffer, siteId, timestampSecs, fileName }
+//     Main → Worker:  { type: 'ingest', id, data: ArrayBuffer, siteId?, timestampSecs?, fileName, dealiasVelocity }
+//     (siteId and timestampSecs are omitted for local imports and read from the volume header)
 //     Worker → Main:  { type: 'ingested', id, result: { scanKey, recordsStored, elevationNumbers, sweeps, vcp, timing... } }
 //
 //   Chunk ingest (real-time streaming, one chunk at a time):
-//     Main → Worker:  { type: 'ingest_chunk', id, data: ArrayBuffer, siteId, timestampSecs, chunkIndex, isStart, isEnd, fileName }
+//     Main → Worker:  { type: 'ingest_chunk', id, data: ArrayBuffer, siteId, timestampSecs, chunkIndex, isStart, isEnd, fileName, dealiasVelocity }
 //     Worker → Main:  { type: 'chunk_ingested', id, result: { scanKey, sweepsStored, elevationsCompleted, sweeps, vcp, ... } }
 //
+//   Level III ingest (one RPG product → decoded sweep stored in IDB):
+//     Main → Worker:  { type: 'ingest_level3', id, data: ArrayBuffer, siteId, fileName }
+//     Worker → Main:  { type: 'level3_ingested', id, scanKey, product, totalMs }
+//
 //   Single-elevation render (read pre-computed sweep from IDB):
 //     Main → Worker:  { type: 'render', id, scanKey, elevationNumber, product }
 //     Worker → Main:  { type: 'decoded', id, azimuths: ArrayBuffer, gateValues: ArrayBuffer, azimuthCount, gateCount, scale, offset, ... }
@@ -30,6 +35,26 @@
 //     Main → Worker:  { type: 'render_live', id, elevationNumber, product }
 //     Worker → Main:  { type: 'live_decoded', id, azimuths: ArrayBuffer, gateValues: ArrayBuffer, ... }
 //
+//   Rainfall accumulation (lowest-tilt reflectivity summed over cached scans):
+//     Main → Worker:  { type: 'render_qpe', id, product, scans: [{ scanKey, elevationNumber, hours }], settings }
+//     Worker → Main:  { type: 'qpe_decoded', id, azimuths: ArrayBuffer, gateValues: ArrayBuffer, ... }
+//
+//   Lowest-available composite (newest valid gate of the lowest tilt over recent sweeps):
+//     Main → Worker:  { type: 'render_lowest_available', id, product, sweeps: [{ scanKey, elevationNumber }] }
+//     Worker → Main:  { type: 'lowest_available_decoded', id, azimuths: ArrayBuffer, gateValues: ArrayBuffer, gateTimes: ArrayBuffer, ... }
+//
+//   Vertical cross-section (every cached tilt sliced along a line):
+//     Main → Worker:  { type: 'cross_section', id, scanKey, product, elevationNumbers, startKm, endKm, stormMotionMs }
+//     Worker → Main:  { type: 'cross_section_decoded', id, values: ArrayBuffer, columns, rows, ... }
+//
+//   Regional mosaic (lowest tilt of several sites' latest cached scans on one grid):
+//     Main → Worker:  { type: 'mosaic', id, siteIds, timeSecs, maxAgeSecs, product, mode }
+//     Worker → Main:  { type: 'mosaic_decoded', id, values: ArrayBuffer, west, north, cellDeg, columns, rows, scanKeys, totalMs }
+//
+//   CF-Radial export (every cached tilt and moment written as NetCDF):
+//     Main → Worker:  { type: 'cf_radial', id, scanKey, elevationNumbers, siteId, siteName, latitude, longitude, altitudeM }
+//     Worker → Main:  { type: 'cf_radial_encoded', id, data: ArrayBuffer, sweepCount, totalMs }
+//
 //   Errors:
 //     Worker → Main:  { type: 'error', id, message }
 
@@ -67,6 +92,7 @@ self.onmessage = async function (e) {
                 siteId: msg.siteId,
                 timestampSecs: msg.timestampSecs,
                 fileName: msg.fileName,
+                dealiasVelocity: msg.dealiasVelocity || false,
             });
 
             self.postMessage({ type: 'ingested', id: msg.id, result: result });
@@ -88,6 +114,7 @@ self.onmessage = async function (e) {
                 fileName: msg.fileName,
                 skipOverlapDelete: msg.skipOverlapDelete || false,
                 isLastInSweep: msg.isLastInSweep || false,
+                dealiasVelocity: msg.dealiasVelocity || false,
             });
             self.postMessage({ type: 'chunk_ingested', id: msg.id, result: result });
         } catch (err) {
@@ -142,6 +169,127 @@ self.onmessage = async function (e) {
         return;
     }
 
+    if (msg.type === 'render_qpe') {
+        try {
+            const result = await wasm.worker_render_qpe({
+                product: msg.product,
+                scans: msg.scans,
+                settings: msg.settings,
+            });
+
+            const { azimuths, gateValues } = result;
+            const transferList = [azimuths, gateValues];
+            const payload = Object.assign({}, result, {
+                type: 'qpe_decoded',
+                id: msg.id,
+            });
+            self.postMessage(payload, transferList);
+        } catch (err) {
+            self.postMessage({ type: 'error', id: msg.id, message: String(err) });
+        }
+        return;
+    }
+
+    if (msg.type === 'render_lowest_available') {
+        try {
+            const result = await wasm.worker_render_lowest_available({
+                product: msg.product,
+                sweeps: msg.sweeps,
+            });
+
+            const { azimuths, gateValues, gateTimes } = result;
+            const transferList = [azimuths, gateValues, gateTimes];
+            const payload = Object.assign({}, result, {
+                type: 'lowest_available_decoded',
+                id: msg.id,
+            });
+            self.postMessage(payload, transferList);
+        } catch (err) {
+            self.postMessage({ type: 'error', id: msg.id, message: String(err) });
+        }
+        return;
+    }
+
+    if (msg.type === 'cross_section') {
+        try {
+            const result = await wasm.worker_cross_section({
+                scanKey: msg.scanKey,
+                product: msg.product,
+                elevationNumbers: msg.elevationNumbers,
+                startKm: msg.startKm,
+                endKm: msg.endKm,
+                stormMotionMs: msg.stormMotionMs,
+            });
+
+            const payload = Object.assign({}, result, {
+                type: 'cross_section_decoded',
+                id: msg.id,
+            });
+            self.postMessage(payload, [result.values]);
+        } catch (err) {
+            self.postMessage({ type: 'error', id: msg.id, message: String(err) });
+        }
+        return;
+    }
+
+    if (msg.type === 'mosaic') {
+        try {
+            const result = await wasm.worker_mosaic({
+                siteIds: msg.siteIds,
+                timeSecs: msg.timeSecs,
+                maxAgeSecs: msg.maxAgeSecs,
+                product: msg.product,
+                mode: msg.mode,
+            });
+
+            const payload = Object.assign({}, result, {
+                type: 'mosaic_decoded',
+                id: msg.id,
+            });
+            self.postMessage(payload, [result.values]);
+        } catch (err) {
+            self.postMessage({ type: 'error', id: msg.id, message: String(err) });
+        }
+        return;
+    }
+
+    if (msg.type === 'ingest_level3') {
+        try {
+            const result = await wasm.worker_ingest_level3({
+                data: msg.data,
+                siteId: msg.siteId,
+                fileName: msg.fileName,
+            });
+            self.postMessage(Object.assign({}, result, { type: 'level3_ingested', id: msg.id }));
+        } catch (err) {
+            self.postMessage({ type: 'error', id: msg.id, message: String(err) });
+        }
+        return;
+    }
+
+    if (msg.type === 'cf_radial') {
+        try {
+            const result = await wasm.worker_cf_radial({
+                scanKey: msg.scanKey,
+                elevationNumbers: msg.elevationNumbers,
+                siteId: msg.siteId,
+                siteName: msg.siteName,
+                latitude: msg.latitude,
+                longitude: msg.longitude,
+                altitudeM: msg.altitudeM,
+            });
+
+            const payload = Object.assign({}, result, {
+                type: 'cf_radial_encoded',
+                id: msg.id,
+            });
+            self.postMessage(payload, [result.data]);
+        } catch (err) {
+            self.postMessage({ type: 'error', id: msg.id, message: String(err) });
+        }
+        return;
+    }
+
     if (msg.type === 'render_live') {
         try {
             // worker_render_live: JsValue -> JsValue (synchronous, reads from memory)